@@ -0,0 +1,33 @@
+//! Uses the `no_std` surface of qubic-types, building this crate for a target without std
+//! (e.g. `thumbv7em-none-eabihf`) fails if qubic-types pulls in std without the `std` feature
+
+#![cfg_attr(not(test), no_std)]
+
+extern crate alloc;
+
+use alloc::string::String;
+use qubic_types::{errors::QubicError, QubicId, QubicWallet, Signature};
+
+/// identity of the wallet of `seed`
+pub fn identity(seed: &str) -> Result<String, QubicError> {
+    Ok(QubicWallet::from_seed(seed)?.get_identity())
+}
+
+pub fn sign(seed: &str, digest: [u8; 32]) -> Result<Signature, QubicError> {
+    Ok(QubicWallet::from_seed(seed)?.sign_raw(digest))
+}
+
+pub fn verify(identity: &str, digest: [u8; 32], signature: Signature) -> bool {
+    identity.parse::<QubicId>().is_ok_and(|id| id.verify_raw(digest, signature))
+}
+
+#[test]
+fn test_sign_and_verify() {
+    let seed = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let identity = identity(seed).unwrap();
+    let signature = sign(seed, [7; 32]).unwrap();
+
+    assert_eq!(identity, "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK");
+    assert!(verify(&identity, [7; 32], signature));
+    assert!(!verify(&identity, [8; 32], signature));
+}
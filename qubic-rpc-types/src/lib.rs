@@ -1,91 +1,64 @@
-use qubic_tcp_types::prelude::*;
-use qubic_types::{QubicId, QubicTxHash};
 use serde::{Serialize, Deserialize};
 
 mod serializeable_types;
+pub mod v1;
+pub mod v2;
+
+#[cfg(test)]
+mod tests;
 
 pub use serializeable_types::*;
+pub use v1::*;
+/// network parameters of the packet layouts, shared with `qubic_tcp_types`
+pub use qubic_tcp_types::consts;
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(tag = "method", content = "params", rename_all = "camelCase")]
-pub enum RequestMethods {
-    RequestCurrentTickInfo,
-    RequestEntity(QubicId),
-    RequestComputors,
-    SendTransaction(Transaction),
-    RequestTickTransactions(u32)
+/// Schema version of a request, selected by its optional `version` field (default 1)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+#[serde(try_from = "u8", into = "u8")]
+pub enum Version {
+    #[default]
+    V1,
+    V2
 }
 
-impl RequestMethods {
-    pub fn get_method(&self) -> Methods {
-        match self {
-            Self::RequestComputors => Methods::RequestComputors,
-            Self::RequestCurrentTickInfo => Methods::RequestCurrentTickInfo,
-            Self::RequestEntity(_) => Methods::RequestEntity,
-            Self::SendTransaction(_) => Methods::SendTransaction,
-            Self::RequestTickTransactions(_) => Methods::RequestTickTransaction
+impl TryFrom<u8> for Version {
+    type Error = String;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            1 => Ok(Self::V1),
+            2 => Ok(Self::V2),
+            version => Err(format!("Unsupported JSON-RPC schema version {version}"))
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-pub struct QubicJsonRpcRequest {
-    pub jsonrpc: String,
-    pub id: u32,
-    #[serde(flatten)]
-    pub request: RequestMethods
-}
-
-impl QubicJsonRpcRequest {
-    pub fn new(id: u32, request: RequestMethods) -> Self {
-        Self {
-            jsonrpc: "2.0".to_owned(),
-            id,
-            request
+impl From<Version> for u8 {
+    fn from(value: Version) -> Self {
+        match value {
+            Version::V1 => 1,
+            Version::V2 => 2
         }
     }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(tag = "method", content = "result", rename_all = "camelCase")]
-pub enum RequestResults {
-    RequestCurrentTickInfo(CurrentTickInfo),
-    RequestEntity(Entity),
-    RequestComputors(ComputorInfos),
-    SendTransaction(QubicTxHash),
-    RequestTickTransactions(Vec<TransactionWithData>)
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub enum Methods {
-    RequestCurrentTickInfo,
-    RequestEntity,
-    RequestComputors,
-    SendTransaction,
-    RequestTickTransaction
+#[cfg(feature = "utoipa")]
+impl utoipa::PartialSchema for Version {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::Type::Integer)
+            .enum_values(Some([1, 2]))
+            .default(Some(1.into()))
+            .into()
+    }
 }
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct RequestError {
-    pub method: Methods,
-    pub error: String
-}
+#[cfg(feature = "utoipa")]
+impl utoipa::ToSchema for Version {}
 
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase", untagged)]
-pub enum ResponseType {
-    Error(RequestError),
-    Result(RequestResults)
+/// Reads only the `version` field of a request to pick the schema the rest of it is parsed with
+#[derive(Debug, Deserialize)]
+pub struct VersionedRequest {
+    #[serde(default)]
+    pub version: Version
 }
-
-#[derive(Debug, Serialize, Deserialize)]
-#[serde(rename_all = "camelCase")]
-pub struct QubicJsonRpcResponse {
-    pub jsonrpc: String,
-    pub id: u32,
-
-    #[serde(flatten)]
-    pub response: ResponseType
-}
\ No newline at end of file
@@ -1,12 +1,18 @@
-use qubic_tcp_types::types::Computors;
-use qubic_types::{QubicId, Signature};
+use std::{fmt::Display, net::Ipv4Addr, str::FromStr};
+
+use qubic_tcp_types::{consts::MAX_INPUT_SIZE, types::{activity::TransferCategory, special_commands::MiningScoreEntry, ticks::{CurrentTickInfo, QuorumSummary, TickData}, transactions::{RawTransaction, TickTransactionsReport, Transaction, TransactionData, TransactionStatus, TransactionWithData}, Computors, Entity, ExchangePublicPeers, SystemInfo, WorkSolution}};
+use qubic_types::{traits::{FromBytes, ToBytes, VerifySignature}, MiningSeed, Nonce, QubicId, QubicTxHash, Signature, H256};
 use serde::{Serialize, Deserialize};
 
-#[derive(Debug, Serialize, Deserialize)]
+/// Computor list of `epoch`, `verified` if the list is signed by the arbitrator
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct ComputorInfos {
     pub epoch: u16,
     pub ids: Vec<QubicId>,
-    pub signature: Signature
+    pub signature: Signature,
+    #[serde(default)]
+    pub verified: bool
 }
 
 impl From<Computors> for ComputorInfos {
@@ -14,7 +20,854 @@ impl From<Computors> for ComputorInfos {
         ComputorInfos {
             epoch: value.epoch,
             ids: value.public_key.to_vec(),
-            signature: value.signature
+            signature: value.signature,
+            verified: value.verify()
+        }
+    }
+}
+
+/// `SendTransaction` result of schema v2, schema v1 answers only the hash, see `tx_hash_only`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BroadcastedTransaction {
+    pub tx_hash: QubicTxHash,
+    pub peers_broadcasted: usize
+}
+
+/// `BroadcastedTransaction` as the bare transaction hash schema v1 has always answered `SendTransaction` with. The
+/// number of peers is not part of it, a parsed result counts the one peer which accepted the transaction at least
+pub mod tx_hash_only {
+    use qubic_types::QubicTxHash;
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::BroadcastedTransaction;
+
+    pub fn serialize<S: Serializer>(value: &BroadcastedTransaction, serializer: S) -> Result<S::Ok, S::Error> {
+        value.tx_hash.serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<BroadcastedTransaction, D::Error> {
+        QubicTxHash::deserialize(deserializer).map(|tx_hash| BroadcastedTransaction { tx_hash, peers_broadcasted: 1 })
+    }
+}
+
+/// Params of `SendTransaction`, either a signed transaction or its fields with a signature made by the client
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(untagged)]
+pub enum TransactionParams {
+    Signed(Transaction),
+    External(ExternallySignedTransaction)
+}
+
+impl From<Transaction> for TransactionParams {
+    fn from(value: Transaction) -> Self {
+        Self::Signed(value)
+    }
+}
+
+impl TransactionParams {
+    /// transaction to broadcast, externally signed transactions are only returned if their signature verifies
+    pub fn transaction(&self) -> Result<TransactionWithData, TransactionParamsError> {
+        match self {
+            Self::Signed(transaction) => Ok((*transaction).into()),
+            Self::External(transaction) => transaction.transaction()
+        }
+    }
+}
+
+/// Fields of a transaction as signed by the client, `input_hex` is the encoded input of the transaction
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ExternalRawTransaction {
+    pub source_id: QubicId,
+    pub dest_id: QubicId,
+    #[serde(with = "qubic_types::amount")]
+    pub amount: u64,
+    pub tick: u32,
+    pub input_type: u16,
+    #[serde(default)]
+    pub input_hex: String
+}
+
+/// Transaction signed without handing the seed to the server. `signature_hex` signs the K12 digest of the encoded
+/// transaction without signature, as the node verifies it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ExternallySignedTransaction {
+    pub raw_transaction: ExternalRawTransaction,
+    pub signature_hex: String
+}
+
+impl ExternalRawTransaction {
+    /// encodes the fields with an empty signature, e.g. to simulate the transaction before it is signed
+    pub fn unsigned(&self) -> Result<TransactionWithData, TransactionParamsError> {
+        let input = hex::decode(self.input_hex.strip_prefix("0x").unwrap_or(&self.input_hex))
+            .map_err(|e| TransactionParamsError::MalformedFields(format!("Invalid inputHex: {e}")))?;
+
+        if input.len() > MAX_INPUT_SIZE {
+            return Err(TransactionParamsError::MalformedFields(format!("inputHex of {} bytes exceeds {MAX_INPUT_SIZE} bytes", input.len())))
+        }
+
+        let raw_transaction = RawTransaction { from: self.source_id, to: self.dest_id, amount: self.amount, tick: self.tick, input_type: self.input_type, input_size: input.len() as u16 };
+        let encoded = [raw_transaction.to_bytes(), input, vec![0; 64]].concat();
+
+        TransactionWithData::from_bytes(&encoded)
+            .map_err(|e| TransactionParamsError::MalformedFields(format!("Input does not match inputType {}: {e:?}", self.input_type)))
+    }
+}
+
+impl ExternallySignedTransaction {
+    /// encodes the fields and verifies the signature against `source_id`
+    pub fn transaction(&self) -> Result<TransactionWithData, TransactionParamsError> {
+        let mut transaction = self.raw_transaction.unsigned()?;
+        let signature = hex::decode(self.signature_hex.strip_prefix("0x").unwrap_or(&self.signature_hex)).ok()
+            .and_then(|signature| <[u8; 64]>::try_from(signature).ok())
+            .ok_or_else(|| TransactionParamsError::MalformedFields(format!("Invalid signatureHex {:?}, expected 64 bytes of hex", self.signature_hex)))?;
+
+        transaction.signature = Signature(signature);
+
+        if !transaction.verify() {
+            return Err(TransactionParamsError::SignatureInvalid)
+        }
+
+        Ok(transaction)
+    }
+}
+
+/// Rejection of the `SendTransaction` params, the transaction is not broadcast
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransactionParamsError {
+    /// fields which cannot be encoded, nothing was verified
+    MalformedFields(String),
+    /// the fields are well formed but the signature does not verify against the source
+    SignatureInvalid
+}
+
+impl Display for TransactionParamsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::MalformedFields(e) => write!(f, "Malformed transaction fields: {e}"),
+            Self::SignatureInvalid => write!(f, "Signature invalid: signatureHex does not verify against sourceId")
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct QuorumInfos {
+    pub total_votes: usize,
+    pub agreeing_votes: usize,
+    pub quorum_reached: bool,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    pub prev_spectrum_digest: Option<H256>,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    pub prev_universe_digest: Option<H256>,
+    #[cfg_attr(feature = "utoipa", schema(value_type = Option<String>))]
+    pub transaction_digest: Option<H256>
+}
+
+impl From<QuorumSummary> for QuorumInfos {
+    fn from(value: QuorumSummary) -> Self {
+        QuorumInfos {
+            total_votes: value.total_votes,
+            agreeing_votes: value.agreeing_votes,
+            quorum_reached: value.quorum_reached,
+            prev_spectrum_digest: value.digests.map(|d| d.prev_spectrum_digest),
+            prev_universe_digest: value.digests.map(|d| d.prev_universe_digest),
+            transaction_digest: value.digests.map(|d| d.transaction_digest)
+        }
+    }
+}
+
+/// Result of a long-poll for the next tick, `changed` is false if the timeout elapsed first
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NextTick {
+    pub changed: bool,
+    pub tick_info: CurrentTickInfo
+}
+
+/// Network-wide counters of a `SystemInfo` sample
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkStats {
+    pub tick: u32,
+    pub epoch: u16,
+    pub number_of_entities: u32,
+    pub number_of_transactions: u32,
+    pub solution_threshold: u32
+}
+
+impl From<SystemInfo> for NetworkStats {
+    fn from(value: SystemInfo) -> Self {
+        NetworkStats {
+            tick: value.tick,
+            epoch: value.epoch,
+            number_of_entities: value.number_of_entities,
+            number_of_transactions: value.number_of_transactions,
+            solution_threshold: value.solution_threshold
+        }
+    }
+}
+
+/// Transactions of a tick, incomplete ticks should be requested from another computor. Transactions of a tick which is
+/// not `finalized` yet may still change
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TickTransactions {
+    pub transactions: Vec<TransactionWithData>,
+    pub requested: usize,
+    pub received: usize,
+    pub tick_data_digest_count: Option<usize>,
+    pub complete: bool,
+    /// a quorum of computors voted for the same digests of the tick
+    #[serde(default)]
+    pub finalized: bool
+}
+
+impl From<TickTransactionsReport> for TickTransactions {
+    fn from(value: TickTransactionsReport) -> Self {
+        TickTransactions {
+            transactions: value.transactions,
+            requested: value.requested,
+            received: value.received,
+            tick_data_digest_count: value.tick_data_digest_count,
+            complete: value.complete,
+            finalized: false
+        }
+    }
+}
+
+/// Kind of a transaction in filters, classified like `TransactionWithData::kind` with bids of IPOs apart
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum TransactionKind {
+    /// plain transfer without input
+    Transfer,
+    SubmitWork,
+    /// asset issuance and transfers of the QX contract
+    Qx,
+    SendToMany,
+    IpoBid,
+    /// calls of the Quottery contract
+    Quottery,
+    /// calls of every other contract
+    Contract
+}
+
+impl TransactionKind {
+    /// `None` for inputs that aren't classified, see `TransactionWithData::kind`
+    pub fn of(tx: &TransactionWithData) -> Option<Self> {
+        use qubic_tcp_types::types::transactions::TransactionKind as Kind;
+
+        if let TransactionData::IpoBid(_) = tx.data {
+            return Some(Self::IpoBid)
+        }
+
+        match tx.kind() {
+            Kind::Transfer => Some(Self::Transfer),
+            Kind::SubmitWork => Some(Self::SubmitWork),
+            Kind::QxCall => Some(Self::Qx),
+            Kind::SendToMany => Some(Self::SendToMany),
+            Kind::QuotteryCall => Some(Self::Quottery),
+            Kind::OtherContract(_) => Some(Self::Contract),
+            Kind::Unknown => None
+        }
+    }
+}
+
+/// Memo of a transfer as text (invalid UTF-8 replaced), `None` without a memo or if it holds control characters.
+/// Trailing zero bytes are padding. Memos are a convention of exchanges, see `is_memo`
+pub fn printable_memo(data: &TransactionData) -> Option<String> {
+    let memo = data.memo()?;
+    let text = String::from_utf8_lossy(memo);
+    let text = text.trim_end_matches('\0');
+
+    (!text.is_empty() && !text.chars().any(char::is_control)).then(|| text.to_owned())
+}
+
+/// Selects transactions by input type and kind, an empty list matches everything.
+/// A transaction has to match both lists
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionFilter {
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_types: Vec<u16>,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub kinds: Vec<TransactionKind>
+}
+
+impl TransactionFilter {
+    pub fn is_empty(&self) -> bool {
+        self.input_types.is_empty() && self.kinds.is_empty()
+    }
+
+    pub fn matches(&self, tx: &TransactionWithData) -> bool {
+        (self.input_types.is_empty() || self.input_types.contains(&tx.raw_transaction.input_type))
+            && (self.kinds.is_empty() || TransactionKind::of(tx).is_some_and(|kind| self.kinds.contains(&kind)))
+    }
+}
+
+impl TickTransactions {
+    /// keeps the transactions matching `filter`, the counts still describe the whole tick
+    pub fn filtered(mut self, filter: &TransactionFilter) -> Self {
+        self.transactions.retain(|tx| filter.matches(tx));
+        self
+    }
+}
+
+/// Public peers known to the computor, unset slots are omitted
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct PublicPeers {
+    #[cfg_attr(feature = "utoipa", schema(value_type = Vec<String>, format = Ipv4))]
+    pub peers: Vec<Ipv4Addr>
+}
+
+impl From<ExchangePublicPeers> for PublicPeers {
+    fn from(value: ExchangePublicPeers) -> Self {
+        PublicPeers {
+            peers: value.peers.into_iter().filter(|peer| !peer.is_unspecified()).collect()
+        }
+    }
+}
+
+/// Part of a `NetworkOverview`, a failed request only fails its own section
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum OverviewSection<T> {
+    Result(T),
+    Error(String)
+}
+
+impl<T, E: ToString> From<Result<T, E>> for OverviewSection<T> {
+    fn from(value: Result<T, E>) -> Self {
+        match value {
+            Ok(res) => Self::Result(res),
+            Err(e) => Self::Error(e.to_string())
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct NetworkOverview {
+    pub tick_info: OverviewSection<CurrentTickInfo>,
+    pub system_info: OverviewSection<SystemInfo>,
+    pub peers: OverviewSection<PublicPeers>
+}
+
+/// Result of verifying a `SignedChallenge`, `error` tells why it was rejected
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AuthVerification {
+    pub identity: QubicId,
+    pub valid: bool,
+    pub error: Option<String>
+}
+
+/// Check of `/v1/healthcheck`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum HealthCheckKind {
+    /// the computor did not answer the current tick recently
+    Upstream,
+    /// the archive is too many ticks behind the current tick
+    ArchiveLag,
+    /// the archive database cannot be read
+    Database
+}
+
+/// Health of the server, `status` is only true if no check `failed`. Checks of features the server was started
+/// without are absent
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct HealthCheck {
+    pub status: bool,
+    pub failed: Vec<HealthCheckKind>,
+    /// seconds since the computor last answered the current tick, absent if it never did
+    pub upstream_age: Option<u64>,
+    pub current_tick: Option<u32>,
+    /// current tick minus the last archived tick
+    pub archive_lag: Option<u32>,
+    pub database: Option<bool>
+}
+
+/// Votes of a computor over the monitored window, `divergent` votes differ from the majority digests
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ComputorHealth {
+    pub computor_index: u16,
+    pub identity: Option<QubicId>,
+    pub signed: u32,
+    pub missed: u32,
+    pub divergent: u32
+}
+
+/// Monitored window of computor votes, `ticks` is the number of evaluated ticks in it
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ComputorsHealth {
+    pub epoch: Option<u16>,
+    pub ticks: usize,
+    pub computors: Vec<ComputorHealth>
+}
+
+/// Entity of an identity at `tick`, reconstructed from the archived snapshot at `based_on_tick` and the
+/// archived transactions in between
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct EntitySnapshot {
+    pub tick: u32,
+    pub based_on_tick: u32,
+    pub entity: Entity
+}
+
+/// Archived transaction of the identity, `delta` is its effect on the balance of the identity
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct DiffTransaction {
+    pub tick: u32,
+    pub hash: QubicTxHash,
+    pub from: QubicId,
+    pub to: QubicId,
+    #[serde(with = "qubic_types::amount")]
+    pub amount: u64,
+    pub delta: i64,
+    /// unknown if the transaction was archived without the node logs, it is assumed to have moved funds
+    pub money_flew: Option<bool>,
+    /// memo of the transfer if it is printable, see `printable_memo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(flatten)]
+    pub category: TransferCategory
+}
+
+/// Changes of an identity between `from_tick` and `to_tick`. `residual` is the part of `balance_delta` which is not
+/// explained by the archived transactions, e.g. mining rewards, contract payouts or transactions of `missing_ticks`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct BalanceDiff {
+    pub identity: QubicId,
+    pub from_tick: u32,
+    pub to_tick: u32,
+    pub from: EntitySnapshot,
+    pub to: EntitySnapshot,
+    pub balance_delta: i64,
+    pub incoming_transfers_delta: i64,
+    pub outgoing_transfers_delta: i64,
+    pub transactions: Vec<DiffTransaction>,
+    pub explained_delta: i64,
+    pub residual: i64,
+    /// inclusive ranges of ticks in the interval which are not archived
+    pub missing_ticks: Vec<[u32; 2]>,
+    pub warnings: Vec<String>
+}
+
+/// Archived transaction of an identity, `kind` names its `TransactionData`, e.g. `SubmitWork` or `None` for plain
+/// transfers
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityTransaction {
+    pub tick: u32,
+    pub hash: QubicTxHash,
+    pub from: QubicId,
+    pub to: QubicId,
+    #[serde(with = "qubic_types::amount")]
+    pub amount: u64,
+    pub input_type: u16,
+    pub kind: String,
+    /// unknown if the transaction was archived without the node logs
+    pub money_flew: Option<bool>,
+    /// memo of the transfer if it is printable, see `printable_memo`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>
+}
+
+/// Page of the archived transactions an identity sent or received in ascending tick order, `total` counts the ones of
+/// the whole tick range
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionsResponse {
+    pub identity: QubicId,
+    pub page: u32,
+    pub page_size: u32,
+    pub total: u64,
+    pub transactions: Vec<IdentityTransaction>
+}
+
+/// Tick data with whether a quorum of computors voted for the same digests of the tick, tick data of a tick which is
+/// not `finalized` yet may still change
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TickDataReport {
+    #[serde(flatten)]
+    pub tick_data: TickData,
+    #[serde(default)]
+    pub finalized: bool
+}
+
+/// Status of a transaction with whether its target tick is final
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct TransactionStatusReport {
+    #[serde(flatten)]
+    pub status: TransactionStatus,
+    #[serde(default)]
+    pub finalized: bool
+}
+
+/// Latest archived tick a quorum of computors voted for
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LatestFinalizedTick {
+    pub tick: u32,
+    pub epoch: u16
+}
+
+/// What the input of `/v1/identities/resolve` was recognized as
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(tag = "kind", rename_all = "camelCase")]
+pub enum ResolvedInput {
+    /// identity in uppercase or mixed case, surrounding whitespace is ignored
+    Identity { identity: QubicId },
+    /// 64 hex characters of a public key, optionally prefixed with `0x`
+    PublicKey { identity: QubicId },
+    /// 60 lowercase characters with a matching checksum, a transaction hash unless an identity was pasted in lowercase
+    HashOrIdentity { hash: QubicTxHash, identity: QubicId },
+    /// identity with a mismatching checksum, the identities differing from it in a single character whose checksum
+    /// matches. Several suggestions are ambiguous
+    Suggestions { suggestions: Vec<String> }
+}
+
+/// Ticks of `from_tick..=to_tick` which are not archived, classified by the tick data of the computor. Ranges are
+/// inclusive
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct ArchiveGaps {
+    pub from_tick: u32,
+    pub to_tick: u32,
+    pub archived_ticks: u32,
+    /// ticks the computor has tick data of, archiving missed them
+    pub unarchived: Vec<[u32; 2]>,
+    /// ticks without tick data, there is nothing to archive
+    pub empty: Vec<[u32; 2]>,
+    /// ticks the computor has no record of
+    pub unavailable: Vec<[u32; 2]>
+}
+
+/// Identity of the rich list with the balance of its latest archived entity and the tick of that entity
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichListEntry {
+    pub identity: QubicId,
+    #[serde(with = "qubic_types::amount")]
+    pub balance: u64,
+    pub tick: u32
+}
+
+/// Page of the rich list ordered by descending balance and then identity, `next` is passed as `after` to fetch the
+/// following page and is absent on the last one
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichList {
+    pub total: u64,
+    pub entries: Vec<RichListEntry>,
+    pub next: Option<QubicId>
+}
+
+/// Distribution of the QU over the identities of the rich list, computed on a schedule from a snapshot of their latest
+/// balances. Identities without QU are left out. `top1` to `top1000` are the percentages of the QU held by the 1 to
+/// 1000 richest identities, `gini` is the Gini coefficient of the balances from 0 (equal balances) to 1 (one identity
+/// holds everything). All of them are 0 while no identity holds QU
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RichListStats {
+    /// latest archived tick when the stats were computed
+    pub tick: Option<u32>,
+    /// seconds since the UNIX epoch
+    pub computed_at: u64,
+    pub wallets: u64,
+    #[serde(with = "qubic_types::amount")]
+    pub total_balance: u64,
+    pub top1: f64,
+    pub top10: f64,
+    pub top100: f64,
+    pub top1000: f64,
+    pub gini: f64
+}
+
+/// Statistics of the archived transactions of an epoch. `transferred` sums the amounts of the transactions which were not
+/// logged to move no funds, `qx_volume` sums the amounts of the QX calls. `active_addresses` counts the distinct sources and
+/// destinations with a HyperLogLog estimate, its standard error is 1.6%
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct EpochStats {
+    pub epoch: u16,
+    pub transactions: u64,
+    #[serde(with = "qubic_types::amount")]
+    pub transferred: u64,
+    pub active_addresses: u64,
+    #[serde(with = "qubic_types::amount")]
+    pub qx_volume: u64
+}
+
+/// Statistics of the network at the latest archived tick, computed from the archive. The epoch counts its ticks from its
+/// first archived tick, ticks without archived tick data are empty. `circulating_supply` and `active_addresses` cover
+/// the identities of the rich list, those which moved QU in an archived tick. `burned` sums the amounts archived
+/// transactions sent to the zero identity. `price` (in USD per QU) and `market_cap` are null unless the server is
+/// started with `--price-url`
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct LatestStats {
+    pub tick: u32,
+    pub epoch: u16,
+    pub ticks_in_epoch: u32,
+    pub empty_ticks_in_epoch: u32,
+    /// percentage of the ticks of the epoch which are not empty
+    pub epoch_tick_quality: f64,
+    #[serde(with = "qubic_types::amount")]
+    pub circulating_supply: u64,
+    /// tracked identities holding QU
+    pub active_addresses: u64,
+    #[serde(with = "qubic_types::amount")]
+    pub burned: u64,
+    pub price: Option<f64>,
+    pub market_cap: Option<f64>
+}
+
+/// Counters of the read requests which were coalesced, every read was either requested upstream, coalesced
+/// with an identical request in flight or answered from the cache
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct CoalescingMetrics {
+    pub upstream_calls: u64,
+    pub coalesced: u64,
+    pub cache_hits: u64,
+    /// upstream requests a busy computor turned away which were sent again to a peer it suggested
+    #[serde(default)]
+    pub peer_redirects: u64,
+    #[serde(default)]
+    pub upstream_scheduler: UpstreamSchedulerMetrics
+}
+
+/// Turns for upstream requests of a priority class, handed out by the rate limit of the computor
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamClassMetrics {
+    /// requests currently waiting for their turn
+    pub queued: u64,
+    pub granted: u64,
+    /// milliseconds the granted requests waited in total, divided by `granted` for the mean wait
+    pub total_wait_ms: u64,
+    pub max_wait_ms: u64
+}
+
+/// Upstream turns of API requests (interactive) and of the archiver (background), which only uses the rate API
+/// requests leave over
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct UpstreamSchedulerMetrics {
+    pub interactive: UpstreamClassMetrics,
+    pub background: UpstreamClassMetrics
+}
+
+/// How a JSON-RPC request was served, answered if the request sets `debug` or the `x-qubic-debug` header
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Diagnostics {
+    /// milliseconds spent waiting for upstreams over all attempts, unset if no upstream was requested
+    pub upstream_latency_ms: Option<u64>,
+    /// computor or fallback RPC which served the request
+    pub upstream_peer: Option<String>,
+    /// number of upstreams requested, the computor and the fallback RPC if the computor was not reachable
+    pub attempts: u32,
+    /// served from state of the server, e.g. the watched tick or recorded network stats
+    pub served_from_cache: bool
+}
+
+/// Mining solution relayed by the server, `random_seed` is given as hex or lowercase seed identity and `nonce` as 0x prefixed hex
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SubmitWork {
+    pub identity: QubicId,
+    pub random_seed: String,
+    pub nonce: String
+}
+
+impl SubmitWork {
+    /// parses the seed and nonce, the error tells which one is malformed
+    pub fn solution(&self) -> Result<WorkSolution, String> {
+        let hex32 = |value: &str| -> Option<[u8; 32]> { hex::decode(value).ok()?.try_into().ok() };
+
+        let random_seed = match self.random_seed.strip_prefix("0x") {
+            Some(seed) => hex32(seed).map(MiningSeed),
+            None if self.random_seed.len() == 64 => hex32(&self.random_seed).map(MiningSeed),
+            None => MiningSeed::from_str(&self.random_seed).ok()
+        }.ok_or_else(|| format!("Invalid random seed {:?}, expected 32 bytes of hex or a lowercase seed identity", self.random_seed))?;
+
+        let nonce = self.nonce.strip_prefix("0x").and_then(hex32).map(Nonce)
+            .ok_or_else(|| format!("Invalid nonce {:?}, expected 32 bytes of 0x prefixed hex", self.nonce))?;
+
+        Ok(WorkSolution { public_key: self.identity, random_seed, nonce })
+    }
+}
+
+/// Relayed mining solution with the tick it was submitted at
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct SubmittedWork {
+    pub identity: QubicId,
+    pub tick: u32
+}
+
+/// Scores of the miners as reported by the operated computor, `fetched_at` is the unix time in seconds of the request
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct MiningRanking {
+    pub fetched_at: u64,
+    pub rankings: Vec<MiningScoreEntry>
+}
+
+/// Archived events a webhook can subscribe to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookEvent {
+    /// transaction moving a positive amount from or to one of the identities
+    Transfer,
+    /// every archived tick, regardless of the identities
+    TickFinalized
+}
+
+/// Registration of a webhook, deliveries carry an HMAC-SHA256 of the body keyed with `secret`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct RegisterWebhook {
+    pub url: String,
+    #[serde(default)]
+    pub identities: Vec<QubicId>,
+    pub events: Vec<WebhookEvent>,
+    pub secret: String
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum WebhookStatus {
+    Active,
+    /// delivery failed too often in a row, events are no longer delivered
+    DeadLetter
+}
+
+/// Registered webhook without its secret
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct Webhook {
+    pub id: u64,
+    pub url: String,
+    pub identities: Vec<QubicId>,
+    pub events: Vec<WebhookEvent>,
+    pub status: WebhookStatus,
+    pub delivered: u64,
+    /// failed attempts since the last delivery
+    pub consecutive_failures: u32,
+    pub last_error: Option<String>
+}
+
+/// Body POSTed to a webhook
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(tag = "event", rename_all = "camelCase")]
+pub enum WebhookPayload {
+    #[serde(rename_all = "camelCase")]
+    Transfer {
+        webhook_id: u64,
+        tick: u32,
+        hash: QubicTxHash,
+        from: QubicId,
+        to: QubicId,
+        #[serde(with = "qubic_types::amount")]
+        amount: u64,
+        money_flew: Option<bool>
+    },
+    #[serde(rename_all = "camelCase")]
+    TickFinalized {
+        webhook_id: u64,
+        tick: u32,
+        epoch: u16
+    }
+}
+
+/// Operation of the server recorded to the audit log
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub enum AuditOperation {
+    SendTransaction,
+    SubmitWork
+}
+
+/// Record of the audit log, `timestamp` is the unix time in milliseconds the operation succeeded at. Mining solutions
+/// have no transaction, destination or amount, their `source` is the miner. `hash` is the SHA-256 (hex) of the record
+/// serialized with an empty `hash`, `prev_hash` the hash of the previous record (zeros for the first)
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "camelCase")]
+pub struct AuditRecord {
+    pub sequence: u64,
+    pub timestamp: u64,
+    pub operation: AuditOperation,
+    pub client_ip: Option<String>,
+    pub tx_id: Option<QubicTxHash>,
+    pub source: QubicId,
+    pub destination: Option<QubicId>,
+    #[serde(default, with = "qubic_types::amount::option")]
+    pub amount: Option<u64>,
+    pub tick: u32,
+    pub upstream_peer: String,
+    pub prev_hash: String,
+    pub hash: String
+}
@@ -0,0 +1,387 @@
+use std::str::FromStr;
+
+use qubic_tcp_types::{prelude::*, types::ExchangePublicPeers};
+use qubic_types::{QubicId, QubicTxHash};
+use serde::{de::DeserializeOwned, Serialize};
+use serde_json::{json, Value};
+
+use crate::{v1, v2, BroadcastedTransaction, Diagnostics, NetworkOverview, NetworkStats, NextTick, OverviewSection, PublicPeers, SubmitWork, SubmittedWork, TickTransactions, TransactionFilter, TransactionKind, TransactionStatusReport, Version, VersionedRequest};
+
+const ID: &str = "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK";
+
+/// serializes `value`, compares it to the locked `expected` schema and parses it back
+fn assert_schema<T: Serialize + DeserializeOwned>(value: T, expected: Value) {
+    let serialized = serde_json::to_value(&value).unwrap();
+    assert_eq!(serialized, expected);
+
+    let parsed: T = serde_json::from_value(serialized).unwrap();
+    assert_eq!(serde_json::to_value(parsed).unwrap(), expected);
+}
+
+fn stats() -> NetworkStats {
+    NetworkStats { tick: 12000000, epoch: 100, number_of_entities: 500000, number_of_transactions: 90000, solution_threshold: 29 }
+}
+
+#[test]
+fn test_v1_schema() {
+    let id = QubicId::from_str(ID).unwrap();
+
+    assert_schema(v1::QubicJsonRpcRequest::new(0, v1::RequestMethods::RequestCurrentTickInfo), json!({ "jsonrpc": "2.0", "id": 0, "method": "requestCurrentTickInfo" }));
+    assert_schema(v1::QubicJsonRpcRequest::new(1, v1::RequestMethods::RequestEntity(id)), json!({ "jsonrpc": "2.0", "id": 1, "method": "requestEntity", "params": ID }));
+    assert_schema(v1::QubicJsonRpcRequest::new(2, v1::RequestMethods::RequestComputors), json!({ "jsonrpc": "2.0", "id": 2, "method": "requestComputors" }));
+    assert_schema(v1::QubicJsonRpcRequest::new(3, v1::RequestMethods::RequestTickTransactions(12000000)), json!({ "jsonrpc": "2.0", "id": 3, "method": "requestTickTransactions", "params": 12000000 }));
+    assert_schema(v1::QubicJsonRpcRequest::new(4, v1::RequestMethods::FindAsset("QX".to_owned())), json!({ "jsonrpc": "2.0", "id": 4, "method": "findAsset", "params": "QX" }));
+    assert_schema(v1::QubicJsonRpcRequest::new(5, v1::RequestMethods::RequestQuorumVotes(12000000)), json!({ "jsonrpc": "2.0", "id": 5, "method": "requestQuorumVotes", "params": 12000000 }));
+    assert_schema(v1::QubicJsonRpcRequest::new(6, v1::RequestMethods::WaitForNextTick { after: 12000000, timeout: Some(10) }), json!({ "jsonrpc": "2.0", "id": 6, "method": "waitForNextTick", "params": { "after": 12000000, "timeout": 10 } }));
+    assert_schema(v1::QubicJsonRpcRequest::new(7, v1::RequestMethods::GetNetworkStatsHistory { from_tick: 100, to_tick: 200, step: None }), json!({ "jsonrpc": "2.0", "id": 7, "method": "getNetworkStatsHistory", "params": { "fromTick": 100, "toTick": 200, "step": null } }));
+    assert_schema(v1::QubicJsonRpcRequest::new(8, v1::RequestMethods::GetNetworkStatsLatest), json!({ "jsonrpc": "2.0", "id": 8, "method": "getNetworkStatsLatest" }));
+
+    assert_schema(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 7, response: v1::ResponseType::Result(v1::RequestResults::GetNetworkStatsLatest(Some(stats()))), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "id": 7,
+        "method": "getNetworkStatsLatest",
+        "result": { "tick": 12000000, "epoch": 100, "numberOfEntities": 500000, "numberOfTransactions": 90000, "solutionThreshold": 29 }
+    }));
+    // v1 answers the bare hash as it did before the number of peers was reported, v2 the whole result
+    let broadcasted = BroadcastedTransaction { tx_hash: QubicTxHash([3; 32]), peers_broadcasted: 1 };
+    let hash = broadcasted.tx_hash.get_identity();
+    assert_schema(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 9, response: v1::ResponseType::Result(v1::RequestResults::SendTransaction(broadcasted.clone())), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "id": 9,
+        "method": "sendTransaction",
+        "result": hash
+    }));
+    assert_schema(v2::QubicJsonRpcResponse::from(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 9, response: v1::ResponseType::Result(v1::RequestResults::SendTransaction(BroadcastedTransaction { peers_broadcasted: 3, ..broadcasted })), diagnostics: None }), json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 9,
+        "method": "sendTransaction",
+        "result": { "txHash": hash, "peersBroadcasted": 3 }
+    }));
+    assert_schema(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 3, response: v1::ResponseType::Error(v1::RequestError { method: v1::Methods::RequestTickTransaction, error: "Timeout".to_owned() }), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "id": 3,
+        "method": "requestTickTransaction",
+        "error": "Timeout"
+    }));
+}
+
+#[test]
+fn test_v2_schema() {
+    let id = QubicId::from_str(ID).unwrap();
+
+    assert_schema(v2::QubicJsonRpcRequest::new(0, v2::RequestMethods::RequestCurrentTickInfo), json!({ "jsonrpc": "2.0", "version": 2, "id": 0, "method": "requestCurrentTickInfo" }));
+    assert_schema(v2::QubicJsonRpcRequest::new(1, v2::RequestMethods::RequestEntity { id }), json!({ "jsonrpc": "2.0", "version": 2, "id": 1, "method": "requestEntity", "params": { "id": ID } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(2, v2::RequestMethods::RequestComputors), json!({ "jsonrpc": "2.0", "version": 2, "id": 2, "method": "requestComputors" }));
+    assert_schema(v2::QubicJsonRpcRequest::new(3, v2::RequestMethods::RequestTickTransactions { tick: 12000000, filter: TransactionFilter::default() }), json!({ "jsonrpc": "2.0", "version": 2, "id": 3, "method": "requestTickTransactions", "params": { "tick": 12000000 } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(4, v2::RequestMethods::RequestTickData { tick: 12000000 }), json!({ "jsonrpc": "2.0", "version": 2, "id": 4, "method": "requestTickData", "params": { "tick": 12000000 } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(5, v2::RequestMethods::RequestSystemInfo), json!({ "jsonrpc": "2.0", "version": 2, "id": 5, "method": "requestSystemInfo" }));
+    assert_schema(v2::QubicJsonRpcRequest::new(6, v2::RequestMethods::FindAsset { name: "QX".to_owned() }), json!({ "jsonrpc": "2.0", "version": 2, "id": 6, "method": "findAsset", "params": { "name": "QX" } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(7, v2::RequestMethods::RequestQuorumVotes { tick: 12000000 }), json!({ "jsonrpc": "2.0", "version": 2, "id": 7, "method": "requestQuorumVotes", "params": { "tick": 12000000 } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(8, v2::RequestMethods::WaitForNextTick { after: 12000000, timeout: None }), json!({ "jsonrpc": "2.0", "version": 2, "id": 8, "method": "waitForNextTick", "params": { "after": 12000000, "timeout": null } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(9, v2::RequestMethods::GetNetworkStatsHistory { from_tick: 100, to_tick: 200, step: Some(10) }), json!({ "jsonrpc": "2.0", "version": 2, "id": 9, "method": "getNetworkStatsHistory", "params": { "fromTick": 100, "toTick": 200, "step": 10 } }));
+    assert_schema(v2::QubicJsonRpcRequest::new(10, v2::RequestMethods::GetNetworkStatsLatest), json!({ "jsonrpc": "2.0", "version": 2, "id": 10, "method": "getNetworkStatsLatest" }));
+    assert_schema(v2::QubicJsonRpcRequest::new(11, v2::RequestMethods::RequestPublicPeers), json!({ "jsonrpc": "2.0", "version": 2, "id": 11, "method": "requestPublicPeers" }));
+    assert_schema(v2::QubicJsonRpcRequest::new(12, v2::RequestMethods::RequestNetworkOverview), json!({ "jsonrpc": "2.0", "version": 2, "id": 12, "method": "requestNetworkOverview" }));
+
+    assert_schema(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id: 9, response: v2::ResponseType::Result(v2::RequestResults::GetNetworkStatsHistory(vec![stats()])), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 9,
+        "method": "getNetworkStatsHistory",
+        "result": [{ "tick": 12000000, "epoch": 100, "numberOfEntities": 500000, "numberOfTransactions": 90000, "solutionThreshold": 29 }]
+    }));
+    assert_schema(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id: 3, response: v2::ResponseType::Result(v2::RequestResults::RequestTickTransactions(TickTransactions { transactions: vec![], requested: 1024, received: 0, tick_data_digest_count: Some(0), complete: true, finalized: true })), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 3,
+        "method": "requestTickTransactions",
+        "result": { "transactions": [], "requested": 1024, "received": 0, "tickDataDigestCount": 0, "complete": true, "finalized": true }
+    }));
+    assert_schema(TransactionStatusReport { status: TransactionStatus::Pending { target_tick: 12000010, current_tick: 12000000 }, finalized: false }, json!({
+        "status": "pending", "targetTick": 12000010, "currentTick": 12000000, "finalized": false
+    }));
+    let peers = PublicPeers::from(ExchangePublicPeers { peers: [[1, 2, 3, 4].into(), [0, 0, 0, 0].into(), [5, 6, 7, 8].into(), [0, 0, 0, 0].into()] });
+    assert_schema(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id: 11, response: v2::ResponseType::Result(v2::RequestResults::RequestPublicPeers(peers.clone())), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 11,
+        "method": "requestPublicPeers",
+        "result": { "peers": ["1.2.3.4", "5.6.7.8"] }
+    }));
+
+    let overview = NetworkOverview {
+        tick_info: OverviewSection::Result(CurrentTickInfo { tick_duration: 2, epoch: 100, tick: 12000000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 11900000 }),
+        system_info: OverviewSection::Error("Timeout".to_owned()),
+        peers: OverviewSection::Result(peers)
+    };
+    assert_schema(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id: 12, response: v2::ResponseType::Result(v2::RequestResults::RequestNetworkOverview(Box::new(overview))), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 12,
+        "method": "requestNetworkOverview",
+        "result": {
+            "tickInfo": { "result": { "tick_duration": 2, "epoch": 100, "tick": 12000000, "number_of_aligned_votes": 451, "number_of_misaligned_votes": 0, "initial_tick": 11900000 } },
+            "systemInfo": { "error": "Timeout" },
+            "peers": { "result": { "peers": ["1.2.3.4", "5.6.7.8"] } }
+        }
+    }));
+    assert_schema(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id: 3, response: v2::ResponseType::Error(v2::RequestError { method: v2::Methods::RequestTickTransactions, error: "Timeout".to_owned() }), diagnostics: None }, json!({
+        "jsonrpc": "2.0",
+        "version": 2,
+        "id": 3,
+        "method": "requestTickTransactions",
+        "error": "Timeout"
+    }));
+}
+
+#[test]
+fn test_version_conversion() {
+    let id = QubicId::from_str(ID).unwrap();
+    let requests = [
+        v1::RequestMethods::RequestCurrentTickInfo,
+        v1::RequestMethods::RequestEntity(id),
+        v1::RequestMethods::RequestComputors,
+        v1::RequestMethods::SendTransaction(Transaction::default().into()),
+        v1::RequestMethods::RequestTickTransactions(12000000),
+        v1::RequestMethods::FindAsset("QX".to_owned()),
+        v1::RequestMethods::RequestQuorumVotes(12000000),
+        v1::RequestMethods::WaitForNextTick { after: 12000000, timeout: Some(10) },
+        v1::RequestMethods::GetNetworkStatsHistory { from_tick: 100, to_tick: 200, step: Some(10) },
+        v1::RequestMethods::GetNetworkStatsLatest,
+        v1::RequestMethods::RequestSubmitWork(SubmitWork { identity: id, random_seed: "0x00".to_owned(), nonce: "0x00".to_owned() })
+    ];
+
+    for request in requests {
+        let v2_request = v2::RequestMethods::from(request.clone());
+        assert_eq!(v2::Methods::from(request.get_method()), v2_request.get_method());
+
+        let v1_request = v1::RequestMethods::try_from(v2_request).unwrap();
+        assert_eq!(serde_json::to_value(v1_request).unwrap(), serde_json::to_value(request).unwrap());
+    }
+
+    assert_eq!(v1::RequestMethods::try_from(v2::RequestMethods::RequestTickData { tick: 1 }).unwrap_err(), v2::Methods::RequestTickData);
+    assert_eq!(v1::RequestMethods::try_from(v2::RequestMethods::RequestSystemInfo).unwrap_err(), v2::Methods::RequestSystemInfo);
+    assert_eq!(v1::RequestMethods::try_from(v2::RequestMethods::RequestPublicPeers).unwrap_err(), v2::Methods::RequestPublicPeers);
+    assert_eq!(v1::RequestMethods::try_from(v2::RequestMethods::RequestNetworkOverview).unwrap_err(), v2::Methods::RequestNetworkOverview);
+
+    let next_tick = NextTick { changed: true, tick_info: CurrentTickInfo { tick_duration: 2, epoch: 100, tick: 12000000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 11900000 } };
+    let v2_result = v2::RequestResults::from(v1::RequestResults::WaitForNextTick(next_tick));
+    assert!(matches!(v1::RequestResults::try_from(v2_result), Ok(v1::RequestResults::WaitForNextTick(res)) if res.changed));
+
+    // v1 results do not know whether the tick is complete
+    let v2_result = v2::RequestResults::from(v1::RequestResults::RequestTickTransactions(vec![Transaction::default().into()]));
+    assert!(matches!(&v2_result, v2::RequestResults::RequestTickTransactions(res) if res.received == 1 && !res.complete));
+    assert!(matches!(v1::RequestResults::try_from(v2_result), Ok(v1::RequestResults::RequestTickTransactions(txs)) if txs.len() == 1));
+
+    let v2_response = v2::QubicJsonRpcResponse::from(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 3, response: v1::ResponseType::Error(v1::RequestError { method: v1::Methods::RequestTickTransaction, error: "Timeout".to_owned() }), diagnostics: None });
+    assert_eq!(v2_response.version, Version::V2);
+    assert!(matches!(v2_response.response, v2::ResponseType::Error(e) if e.method == v2::Methods::RequestTickTransactions));
+}
+
+#[test]
+fn test_version_field() {
+    let version = |request: Value| serde_json::from_value::<VersionedRequest>(request).map(|r| r.version);
+
+    assert_eq!(version(json!({ "jsonrpc": "2.0", "id": 0, "method": "requestComputors" })).unwrap(), Version::V1);
+    assert_eq!(version(json!({ "jsonrpc": "2.0", "version": 1, "id": 0, "method": "requestComputors" })).unwrap(), Version::V1);
+    assert_eq!(version(serde_json::to_value(v2::QubicJsonRpcRequest::new(0, v2::RequestMethods::RequestComputors)).unwrap()).unwrap(), Version::V2);
+    assert!(version(json!({ "jsonrpc": "2.0", "version": 3, "id": 0, "method": "requestComputors" })).is_err());
+
+    // a v2 request defaults to its own version and rejects any other
+    let v2_request = |request: Value| serde_json::from_value::<v2::QubicJsonRpcRequest>(request).map(|r| r.version);
+
+    assert_eq!(v2_request(json!({ "jsonrpc": "2.0", "id": 0, "method": "requestComputors" })).unwrap(), Version::V2);
+    assert_eq!(v2_request(json!({ "jsonrpc": "2.0", "version": 2, "id": 0, "method": "requestComputors" })).unwrap(), Version::V2);
+    assert!(v2_request(json!({ "jsonrpc": "2.0", "version": 1, "id": 0, "method": "requestComputors" })).unwrap_err().to_string().contains("Expected schema version 2, found 1"));
+    assert!(v2_request(json!({ "jsonrpc": "2.0", "version": 3, "id": 0, "method": "requestComputors" })).is_err());
+}
+
+#[test]
+fn test_diagnostics() {
+    let diagnostics = Diagnostics { upstream_latency_ms: Some(42), upstream_peer: Some("127.0.0.1:21841".to_owned()), attempts: 1, served_from_cache: false };
+
+    // absent unless requested
+    assert_schema(v1::QubicJsonRpcRequest { debug: true, ..v1::QubicJsonRpcRequest::new(0, v1::RequestMethods::RequestComputors) }, json!({ "jsonrpc": "2.0", "id": 0, "method": "requestComputors", "debug": true }));
+    assert_schema(v2::QubicJsonRpcRequest { debug: true, ..v2::QubicJsonRpcRequest::new(1, v2::RequestMethods::RequestSystemInfo) }, json!({ "jsonrpc": "2.0", "version": 2, "id": 1, "method": "requestSystemInfo", "debug": true }));
+    assert!(!serde_json::from_value::<v1::QubicJsonRpcRequest>(json!({ "jsonrpc": "2.0", "id": 0, "method": "requestComputors" })).unwrap().debug);
+
+    let response = |diagnostics| v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 2, response: v1::ResponseType::Result(v1::RequestResults::GetNetworkStatsLatest(None)), diagnostics };
+
+    assert_schema(response(None), json!({ "jsonrpc": "2.0", "id": 2, "method": "getNetworkStatsLatest", "result": null }));
+    assert_schema(response(Some(diagnostics.clone())), json!({
+        "jsonrpc": "2.0",
+        "id": 2,
+        "method": "getNetworkStatsLatest",
+        "result": null,
+        "diagnostics": { "upstreamLatencyMs": 42, "upstreamPeer": "127.0.0.1:21841", "attempts": 1, "servedFromCache": false }
+    }));
+
+    // carried over to the v2 schema
+    assert_eq!(v2::QubicJsonRpcResponse::from(response(Some(diagnostics.clone()))).diagnostics, Some(diagnostics));
+}
+
+/// a transaction of every kind and an unclassified one
+fn synthetic_tick() -> Vec<TransactionWithData> {
+    use qubic_tcp_types::types::ContractIpoBid;
+    use qubic_types::traits::FromBytes;
+
+    fn zeroed<T: FromBytes>() -> T {
+        T::from_bytes(&vec![0; std::mem::size_of::<T>()]).unwrap()
+    }
+
+    let tx = |input_type, data| TransactionWithData { raw_transaction: RawTransaction { input_type, ..Default::default() }, data, signature: Default::default() };
+
+    vec![
+        tx(0, TransactionData::None),
+        tx(2, TransactionData::SubmitWork { seed: zeroed(), nonce: zeroed() }),
+        tx(2, TransactionData::TransferAsset(zeroed())),
+        tx(1, TransactionData::SendToMany(zeroed())),
+        tx(0, TransactionData::IpoBid(ContractIpoBid { price: 4, quantity: 2 })),
+        tx(9, TransactionData::Unknown(vec![1, 2]))
+    ]
+}
+
+#[test]
+fn test_transaction_filter() {
+    use TransactionKind::*;
+
+    let kinds = |filter: TransactionFilter| synthetic_tick().iter().filter(|tx| filter.matches(tx)).map(TransactionKind::of).collect::<Vec<_>>();
+
+    assert_eq!(kinds(TransactionFilter::default()).len(), 6);
+    assert_eq!(kinds(TransactionFilter { kinds: vec![SubmitWork], ..Default::default() }), [Some(SubmitWork)]);
+    assert_eq!(kinds(TransactionFilter { kinds: vec![IpoBid, Qx], ..Default::default() }), [Some(Qx), Some(IpoBid)]);
+    assert_eq!(kinds(TransactionFilter { input_types: vec![2], ..Default::default() }), [Some(SubmitWork), Some(Qx)]);
+    assert_eq!(kinds(TransactionFilter { input_types: vec![0], kinds: vec![Transfer, SendToMany] }), [Some(Transfer)]);
+    assert_eq!(kinds(TransactionFilter { input_types: vec![9], ..Default::default() }), [None]);
+
+    let tick = TickTransactions { transactions: synthetic_tick(), requested: 6, received: 6, tick_data_digest_count: Some(6), complete: true, finalized: false };
+    let filtered = tick.filtered(&TransactionFilter { kinds: vec![SendToMany], ..Default::default() });
+    assert_eq!((filtered.transactions.len(), filtered.received, filtered.complete), (1, 6, true));
+
+    let filter = TransactionFilter { input_types: vec![2], kinds: vec![SubmitWork, Qx] };
+    assert_schema(v2::QubicJsonRpcRequest::new(3, v2::RequestMethods::RequestTickTransactions { tick: 12000000, filter: filter.clone() }), json!({
+        "jsonrpc": "2.0", "version": 2, "id": 3, "method": "requestTickTransactions", "params": { "tick": 12000000, "inputTypes": [2], "kinds": ["submitWork", "qx"] }
+    }));
+
+    // v1 has no params to carry the filter
+    assert!(matches!(v1::RequestMethods::try_from(v2::RequestMethods::RequestTickTransactions { tick: 12000000, filter }), Err(v2::Methods::RequestTickTransactions)));
+    assert!(matches!(v1::RequestMethods::try_from(v2::RequestMethods::RequestTickTransactions { tick: 12000000, filter: TransactionFilter::default() }), Ok(v1::RequestMethods::RequestTickTransactions(12000000))));
+}
+
+#[test]
+fn test_health_check_schema() {
+    use crate::{HealthCheck, HealthCheckKind};
+
+    let degraded = HealthCheck { status: false, failed: vec![HealthCheckKind::ArchiveLag], upstream_age: Some(2), current_tick: Some(12000000), archive_lag: Some(500), database: Some(true) };
+    assert_schema(degraded, json!({ "status": false, "failed": ["archiveLag"], "upstreamAge": 2, "currentTick": 12000000, "archiveLag": 500, "database": true }));
+
+    let unreachable = HealthCheck { status: false, failed: vec![HealthCheckKind::Upstream], upstream_age: None, current_tick: None, archive_lag: None, database: None };
+    assert_schema(unreachable, json!({ "status": false, "failed": ["upstream"], "upstreamAge": null, "currentTick": null, "archiveLag": null, "database": null }));
+}
+
+#[test]
+fn test_submit_work() {
+    use qubic_types::{MiningSeed, Nonce};
+
+    let id = QubicId::from_str(ID).unwrap();
+    let seed = MiningSeed([7; 32]);
+    let work = |random_seed: String, nonce: &str| SubmitWork { identity: id, random_seed, nonce: nonce.to_owned() };
+    let nonce = format!("0x{}", "01".repeat(32));
+
+    // the seed is accepted as lowercase identity and as hex with or without prefix
+    for random_seed in [seed.get_identity(), "07".repeat(32), format!("0x{}", "07".repeat(32))] {
+        let solution = work(random_seed, &nonce).solution().unwrap();
+        assert_eq!((solution.public_key, solution.random_seed, solution.nonce), (id, seed, Nonce([1; 32])));
+    }
+
+    assert!(work(seed.get_identity().to_uppercase(), &nonce).solution().unwrap_err().contains("random seed"));
+    assert!(work("07".repeat(31), &nonce).solution().unwrap_err().contains("random seed"));
+    assert!(work(seed.get_identity(), &"01".repeat(32)).solution().unwrap_err().contains("nonce"));
+    assert!(work(seed.get_identity(), "0x01").solution().unwrap_err().contains("nonce"));
+    assert!(work(seed.get_identity(), &format!("0x{}", "zz".repeat(32))).solution().unwrap_err().contains("nonce"));
+
+    let submitted = work(seed.get_identity(), &nonce);
+    assert_schema(v1::QubicJsonRpcRequest::new(9, v1::RequestMethods::RequestSubmitWork(submitted.clone())), json!({
+        "jsonrpc": "2.0", "id": 9, "method": "requestSubmitWork", "params": { "identity": ID, "randomSeed": seed.get_identity(), "nonce": nonce }
+    }));
+    assert_schema(v2::QubicJsonRpcRequest::new(13, v2::RequestMethods::RequestSubmitWork(submitted)), json!({
+        "jsonrpc": "2.0", "version": 2, "id": 13, "method": "requestSubmitWork", "params": { "identity": ID, "randomSeed": seed.get_identity(), "nonce": nonce }
+    }));
+    assert_schema(v1::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 9, response: v1::ResponseType::Result(v1::RequestResults::RequestSubmitWork(SubmittedWork { identity: id, tick: 12000000 })), diagnostics: None }, json!({
+        "jsonrpc": "2.0", "id": 9, "method": "requestSubmitWork", "result": { "identity": ID, "tick": 12000000 }
+    }));
+}
+
+#[test]
+fn test_externally_signed_transaction() {
+    use qubic_types::{traits::Sign, QubicWallet};
+    use crate::{ExternalRawTransaction, ExternallySignedTransaction, TransactionParams, TransactionParamsError};
+    use qubic_tcp_types::consts::MAX_INPUT_SIZE;
+
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let dest = QubicId::from_str(ID).unwrap();
+
+    // signed by the client as if it encoded the transaction itself
+    let mut signed = TransactionWithData {
+        raw_transaction: RawTransaction { from: wallet.public_key, to: dest, amount: 1000, tick: 12000000, input_type: 9, input_size: 3 },
+        data: TransactionData::Unknown(vec![1, 2, 3]),
+        signature: Default::default()
+    };
+    signed.sign(&wallet).unwrap();
+
+    let params = json!({
+        "rawTransaction": { "sourceId": wallet.public_key, "destId": ID, "amount": 1000, "tick": 12000000, "inputType": 9, "inputHex": "010203" },
+        "signatureHex": hex::encode(signed.signature.0)
+    });
+    let request = json!({ "jsonrpc": "2.0", "id": 1, "method": "sendTransaction", "params": params });
+
+    let parsed: v1::QubicJsonRpcRequest = serde_json::from_value(request.clone()).unwrap();
+    assert_eq!(serde_json::to_value(&parsed).unwrap(), request);
+    let v1::RequestMethods::SendTransaction(TransactionParams::External(external)) = parsed.request else { panic!("expected the external params") };
+    assert_eq!(external.transaction(), Ok(signed.clone()));
+    assert_eq!(external.raw_transaction.unsigned(), Ok(TransactionWithData { signature: Default::default(), ..signed.clone() }));
+
+    let parsed: v2::RequestMethods = serde_json::from_value(json!({ "method": "sendTransaction", "params": { "transaction": params } })).unwrap();
+    assert!(matches!(parsed, v2::RequestMethods::SendTransaction { transaction: TransactionParams::External(_) }));
+
+    // the signed transaction struct is still accepted as is
+    let transaction = Transaction { raw_transaction: RawTransaction { from: wallet.public_key, ..Default::default() }, signature: Default::default() };
+    let parsed: v1::RequestMethods = serde_json::from_value(json!({ "method": "sendTransaction", "params": transaction })).unwrap();
+    assert!(matches!(parsed, v1::RequestMethods::SendTransaction(TransactionParams::Signed(parsed)) if parsed == transaction));
+
+    let tampered = ExternallySignedTransaction { raw_transaction: ExternalRawTransaction { amount: 1001, ..external.raw_transaction.clone() }, ..external.clone() };
+    assert_eq!(tampered.transaction(), Err(TransactionParamsError::SignatureInvalid));
+    assert!(tampered.transaction().unwrap_err().to_string().starts_with("Signature invalid"));
+
+    let malformed = [
+        ExternallySignedTransaction { raw_transaction: ExternalRawTransaction { input_hex: "0g".to_owned(), ..external.raw_transaction.clone() }, ..external.clone() },
+        ExternallySignedTransaction { signature_hex: "00".repeat(63), ..external.clone() }
+    ];
+
+    for transaction in malformed {
+        assert!(matches!(transaction.transaction(), Err(TransactionParamsError::MalformedFields(_))));
+    }
+
+    // inputs up to the limit of the node are encoded, longer ones rejected instead of truncating their size
+    let sized = |len: usize| ExternalRawTransaction { input_hex: "01".repeat(len), ..external.raw_transaction.clone() }.unsigned();
+    assert_eq!(sized(MAX_INPUT_SIZE).unwrap().raw_transaction.input_size as usize, MAX_INPUT_SIZE);
+
+    for len in [MAX_INPUT_SIZE + 1, u16::MAX as usize + 1] {
+        assert_eq!(sized(len), Err(TransactionParamsError::MalformedFields(format!("inputHex of {len} bytes exceeds {MAX_INPUT_SIZE} bytes"))));
+    }
+}
+
+#[test]
+fn test_printable_memo() {
+    use crate::printable_memo;
+
+    assert_eq!(printable_memo(&TransactionData::Memo(b"user-42".to_vec())), Some("user-42".to_owned()));
+    // zero padding is dropped, invalid UTF-8 replaced
+    assert_eq!(printable_memo(&TransactionData::Memo([b"42".as_slice(), &[0; 30]].concat())), Some("42".to_owned()));
+    assert_eq!(printable_memo(&TransactionData::Memo(vec![b'4', 0xff, b'2'])), Some("4\u{fffd}2".to_owned()));
+    // binary memos are not shown
+    assert_eq!(printable_memo(&TransactionData::Memo(vec![1, 2, 3])), None);
+    assert_eq!(printable_memo(&TransactionData::Memo(vec![0; 4])), None);
+    assert_eq!(printable_memo(&TransactionData::Unknown(b"user-42".to_vec())), None);
+}
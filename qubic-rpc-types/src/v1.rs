@@ -0,0 +1,135 @@
+use qubic_tcp_types::{prelude::*, types::assets::AssetSummary};
+use qubic_types::QubicId;
+use serde::{Serialize, Deserialize};
+
+use crate::serializeable_types::*;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::RequestMethods))]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum RequestMethods {
+    RequestCurrentTickInfo,
+    RequestEntity(QubicId),
+    RequestComputors,
+    SendTransaction(TransactionParams),
+    RequestTickTransactions(u32),
+    FindAsset(String),
+    RequestQuorumVotes(u32),
+    /// holds the request until the current tick exceeds `after` or `timeout` seconds elapsed
+    WaitForNextTick { after: u32, timeout: Option<u64> },
+    /// sampled network stats between `from_tick` and `to_tick`, one sample per `step` ticks
+    #[serde(rename_all = "camelCase")]
+    GetNetworkStatsHistory { from_tick: u32, to_tick: u32, step: Option<u32> },
+    GetNetworkStatsLatest,
+    /// relays a mining solution for the current random seed
+    RequestSubmitWork(SubmitWork)
+}
+
+impl RequestMethods {
+    pub fn get_method(&self) -> Methods {
+        match self {
+            Self::RequestComputors => Methods::RequestComputors,
+            Self::RequestCurrentTickInfo => Methods::RequestCurrentTickInfo,
+            Self::RequestEntity(_) => Methods::RequestEntity,
+            Self::SendTransaction(_) => Methods::SendTransaction,
+            Self::RequestTickTransactions(_) => Methods::RequestTickTransaction,
+            Self::FindAsset(_) => Methods::FindAsset,
+            Self::RequestQuorumVotes(_) => Methods::RequestQuorumVotes,
+            Self::WaitForNextTick { .. } => Methods::WaitForNextTick,
+            Self::GetNetworkStatsHistory { .. } => Methods::GetNetworkStatsHistory,
+            Self::GetNetworkStatsLatest => Methods::GetNetworkStatsLatest,
+            Self::RequestSubmitWork(_) => Methods::RequestSubmitWork
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::QubicJsonRpcRequest))]
+pub struct QubicJsonRpcRequest {
+    pub jsonrpc: String,
+    pub id: u32,
+    #[serde(flatten)]
+    pub request: RequestMethods,
+    /// answers with `Diagnostics` of how the request was served
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub debug: bool
+}
+
+impl QubicJsonRpcRequest {
+    pub fn new(id: u32, request: RequestMethods) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            id,
+            request,
+            debug: false
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::RequestResults))]
+#[serde(tag = "method", content = "result", rename_all = "camelCase")]
+pub enum RequestResults {
+    RequestCurrentTickInfo(CurrentTickInfo),
+    RequestEntity(Entity),
+    RequestComputors(ComputorInfos),
+    /// the hash of the broadcast transaction, schema v2 also answers the number of peers which accepted it
+    #[serde(with = "crate::tx_hash_only")]
+    #[cfg_attr(feature = "utoipa", schema(value_type = qubic_types::QubicTxHash))]
+    SendTransaction(BroadcastedTransaction),
+    RequestTickTransactions(Vec<TransactionWithData>),
+    FindAsset(Option<AssetSummary>),
+    RequestQuorumVotes(QuorumInfos),
+    WaitForNextTick(NextTick),
+    GetNetworkStatsHistory(Vec<NetworkStats>),
+    GetNetworkStatsLatest(Option<NetworkStats>),
+    RequestSubmitWork(SubmittedWork)
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::Methods))]
+#[serde(rename_all = "camelCase")]
+pub enum Methods {
+    RequestCurrentTickInfo,
+    RequestEntity,
+    RequestComputors,
+    SendTransaction,
+    RequestTickTransaction,
+    FindAsset,
+    RequestQuorumVotes,
+    WaitForNextTick,
+    GetNetworkStatsHistory,
+    GetNetworkStatsLatest,
+    RequestSubmitWork
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::RequestError))]
+#[serde(rename_all = "camelCase")]
+pub struct RequestError {
+    pub method: Methods,
+    pub error: String
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::ResponseType))]
+#[serde(rename_all = "camelCase", untagged)]
+pub enum ResponseType {
+    Error(RequestError),
+    Result(RequestResults)
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v1::QubicJsonRpcResponse))]
+#[serde(rename_all = "camelCase")]
+pub struct QubicJsonRpcResponse {
+    pub jsonrpc: String,
+    pub id: u32,
+
+    #[serde(flatten)]
+    pub response: ResponseType,
+
+    /// only present if the request asked for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Diagnostics>
+}
\ No newline at end of file
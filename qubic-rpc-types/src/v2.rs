@@ -0,0 +1,306 @@
+use qubic_tcp_types::{prelude::*, types::{assets::AssetSummary, SystemInfo}};
+use qubic_types::QubicId;
+use serde::{Serialize, Deserialize};
+
+use crate::{serializeable_types::*, v1, Version};
+
+/// Methods of the v2 schema, params are passed as objects instead of positional values
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::RequestMethods))]
+#[serde(tag = "method", content = "params", rename_all = "camelCase")]
+pub enum RequestMethods {
+    RequestCurrentTickInfo,
+    RequestEntity { id: QubicId },
+    RequestComputors,
+    SendTransaction { transaction: TransactionParams },
+    /// transactions of `tick` matching the filter
+    RequestTickTransactions {
+        tick: u32,
+        #[serde(flatten)]
+        filter: TransactionFilter
+    },
+    RequestTickData { tick: u32 },
+    RequestSystemInfo,
+    /// public peers the computor knows
+    RequestPublicPeers,
+    /// current tick, system info and public peers requested at once, failed parts are reported per section
+    RequestNetworkOverview,
+    FindAsset { name: String },
+    RequestQuorumVotes { tick: u32 },
+    /// holds the request until the current tick exceeds `after` or `timeout` seconds elapsed
+    WaitForNextTick { after: u32, timeout: Option<u64> },
+    /// sampled network stats between `from_tick` and `to_tick`, one sample per `step` ticks
+    #[serde(rename_all = "camelCase")]
+    GetNetworkStatsHistory { from_tick: u32, to_tick: u32, step: Option<u32> },
+    GetNetworkStatsLatest,
+    /// relays a mining solution for the current random seed
+    RequestSubmitWork(SubmitWork)
+}
+
+impl RequestMethods {
+    pub fn get_method(&self) -> Methods {
+        match self {
+            Self::RequestComputors => Methods::RequestComputors,
+            Self::RequestCurrentTickInfo => Methods::RequestCurrentTickInfo,
+            Self::RequestEntity { .. } => Methods::RequestEntity,
+            Self::SendTransaction { .. } => Methods::SendTransaction,
+            Self::RequestTickTransactions { .. } => Methods::RequestTickTransactions,
+            Self::RequestTickData { .. } => Methods::RequestTickData,
+            Self::RequestSystemInfo => Methods::RequestSystemInfo,
+            Self::RequestPublicPeers => Methods::RequestPublicPeers,
+            Self::RequestNetworkOverview => Methods::RequestNetworkOverview,
+            Self::FindAsset { .. } => Methods::FindAsset,
+            Self::RequestQuorumVotes { .. } => Methods::RequestQuorumVotes,
+            Self::WaitForNextTick { .. } => Methods::WaitForNextTick,
+            Self::GetNetworkStatsHistory { .. } => Methods::GetNetworkStatsHistory,
+            Self::GetNetworkStatsLatest => Methods::GetNetworkStatsLatest,
+            Self::RequestSubmitWork(_) => Methods::RequestSubmitWork
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::QubicJsonRpcRequest))]
+pub struct QubicJsonRpcRequest {
+    pub jsonrpc: String,
+    /// always `Version::V2`, a missing version defaults to it
+    #[serde(default = "schema_version::default", deserialize_with = "schema_version::deserialize")]
+    pub version: Version,
+    pub id: u32,
+    #[serde(flatten)]
+    pub request: RequestMethods,
+    /// answers with `Diagnostics` of how the request was served
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub debug: bool
+}
+
+/// `version` of v2 requests, which may be left out but must not name another schema
+mod schema_version {
+    use serde::{de::Error, Deserialize, Deserializer};
+
+    use crate::Version;
+
+    pub fn default() -> Version {
+        Version::V2
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Version, D::Error> {
+        match Version::deserialize(deserializer)? {
+            Version::V2 => Ok(Version::V2),
+            version => Err(D::Error::custom(format!("Expected schema version 2, found {}", u8::from(version))))
+        }
+    }
+}
+
+impl QubicJsonRpcRequest {
+    pub fn new(id: u32, request: RequestMethods) -> Self {
+        Self {
+            jsonrpc: "2.0".to_owned(),
+            version: Version::V2,
+            id,
+            request,
+            debug: false
+        }
+    }
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::RequestResults))]
+#[serde(tag = "method", content = "result", rename_all = "camelCase")]
+pub enum RequestResults {
+    RequestCurrentTickInfo(CurrentTickInfo),
+    RequestEntity(Entity),
+    RequestComputors(ComputorInfos),
+    SendTransaction(BroadcastedTransaction),
+    RequestTickTransactions(TickTransactions),
+    RequestTickData(Box<TickDataReport>),
+    RequestSystemInfo(SystemInfo),
+    RequestPublicPeers(PublicPeers),
+    RequestNetworkOverview(Box<NetworkOverview>),
+    FindAsset(Option<AssetSummary>),
+    RequestQuorumVotes(QuorumInfos),
+    WaitForNextTick(NextTick),
+    GetNetworkStatsHistory(Vec<NetworkStats>),
+    GetNetworkStatsLatest(Option<NetworkStats>),
+    RequestSubmitWork(SubmittedWork)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::Methods))]
+#[serde(rename_all = "camelCase")]
+pub enum Methods {
+    RequestCurrentTickInfo,
+    RequestEntity,
+    RequestComputors,
+    SendTransaction,
+    RequestTickTransactions,
+    RequestTickData,
+    RequestSystemInfo,
+    RequestPublicPeers,
+    RequestNetworkOverview,
+    FindAsset,
+    RequestQuorumVotes,
+    WaitForNextTick,
+    GetNetworkStatsHistory,
+    GetNetworkStatsLatest,
+    RequestSubmitWork
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::RequestError))]
+#[serde(rename_all = "camelCase")]
+pub struct RequestError {
+    pub method: Methods,
+    pub error: String
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::ResponseType))]
+#[serde(rename_all = "camelCase", untagged)]
+pub enum ResponseType {
+    Error(RequestError),
+    Result(RequestResults)
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema), schema(as = v2::QubicJsonRpcResponse))]
+#[serde(rename_all = "camelCase")]
+pub struct QubicJsonRpcResponse {
+    pub jsonrpc: String,
+    pub version: Version,
+    pub id: u32,
+
+    #[serde(flatten)]
+    pub response: ResponseType,
+
+    /// only present if the request asked for it
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub diagnostics: Option<Diagnostics>
+}
+
+impl From<v1::RequestMethods> for RequestMethods {
+    fn from(value: v1::RequestMethods) -> Self {
+        match value {
+            v1::RequestMethods::RequestCurrentTickInfo => Self::RequestCurrentTickInfo,
+            v1::RequestMethods::RequestEntity(id) => Self::RequestEntity { id },
+            v1::RequestMethods::RequestComputors => Self::RequestComputors,
+            v1::RequestMethods::SendTransaction(transaction) => Self::SendTransaction { transaction },
+            v1::RequestMethods::RequestTickTransactions(tick) => Self::RequestTickTransactions { tick, filter: TransactionFilter::default() },
+            v1::RequestMethods::FindAsset(name) => Self::FindAsset { name },
+            v1::RequestMethods::RequestQuorumVotes(tick) => Self::RequestQuorumVotes { tick },
+            v1::RequestMethods::WaitForNextTick { after, timeout } => Self::WaitForNextTick { after, timeout },
+            v1::RequestMethods::GetNetworkStatsHistory { from_tick, to_tick, step } => Self::GetNetworkStatsHistory { from_tick, to_tick, step },
+            v1::RequestMethods::GetNetworkStatsLatest => Self::GetNetworkStatsLatest,
+            v1::RequestMethods::RequestSubmitWork(work) => Self::RequestSubmitWork(work)
+        }
+    }
+}
+
+/// fails with the method if it only exists in v2, or if its params don't
+impl TryFrom<RequestMethods> for v1::RequestMethods {
+    type Error = Methods;
+
+    fn try_from(value: RequestMethods) -> Result<Self, Self::Error> {
+        Ok(match value {
+            RequestMethods::RequestCurrentTickInfo => Self::RequestCurrentTickInfo,
+            RequestMethods::RequestEntity { id } => Self::RequestEntity(id),
+            RequestMethods::RequestComputors => Self::RequestComputors,
+            RequestMethods::SendTransaction { transaction } => Self::SendTransaction(transaction),
+            RequestMethods::RequestTickTransactions { tick, filter } if filter.is_empty() => Self::RequestTickTransactions(tick),
+            RequestMethods::FindAsset { name } => Self::FindAsset(name),
+            RequestMethods::RequestQuorumVotes { tick } => Self::RequestQuorumVotes(tick),
+            RequestMethods::WaitForNextTick { after, timeout } => Self::WaitForNextTick { after, timeout },
+            RequestMethods::GetNetworkStatsHistory { from_tick, to_tick, step } => Self::GetNetworkStatsHistory { from_tick, to_tick, step },
+            RequestMethods::GetNetworkStatsLatest => Self::GetNetworkStatsLatest,
+            RequestMethods::RequestSubmitWork(work) => Self::RequestSubmitWork(work),
+            request @ (RequestMethods::RequestTickTransactions { .. } | RequestMethods::RequestTickData { .. } | RequestMethods::RequestSystemInfo | RequestMethods::RequestPublicPeers | RequestMethods::RequestNetworkOverview) => return Err(request.get_method())
+        })
+    }
+}
+
+impl From<v1::RequestResults> for RequestResults {
+    fn from(value: v1::RequestResults) -> Self {
+        match value {
+            v1::RequestResults::RequestCurrentTickInfo(res) => Self::RequestCurrentTickInfo(res),
+            v1::RequestResults::RequestEntity(res) => Self::RequestEntity(res),
+            v1::RequestResults::RequestComputors(res) => Self::RequestComputors(res),
+            v1::RequestResults::SendTransaction(res) => Self::SendTransaction(res),
+            // completeness is unknown to v1
+            v1::RequestResults::RequestTickTransactions(res) => Self::RequestTickTransactions(TickTransactionsReport::new(res, &TransactionFlags::all(), None).into()),
+            v1::RequestResults::FindAsset(res) => Self::FindAsset(res),
+            v1::RequestResults::RequestQuorumVotes(res) => Self::RequestQuorumVotes(res),
+            v1::RequestResults::WaitForNextTick(res) => Self::WaitForNextTick(res),
+            v1::RequestResults::GetNetworkStatsHistory(res) => Self::GetNetworkStatsHistory(res),
+            v1::RequestResults::GetNetworkStatsLatest(res) => Self::GetNetworkStatsLatest(res),
+            v1::RequestResults::RequestSubmitWork(res) => Self::RequestSubmitWork(res)
+        }
+    }
+}
+
+/// fails with the method if it only exists in v2
+impl TryFrom<RequestResults> for v1::RequestResults {
+    type Error = Methods;
+
+    fn try_from(value: RequestResults) -> Result<Self, Self::Error> {
+        Ok(match value {
+            RequestResults::RequestCurrentTickInfo(res) => Self::RequestCurrentTickInfo(res),
+            RequestResults::RequestEntity(res) => Self::RequestEntity(res),
+            RequestResults::RequestComputors(res) => Self::RequestComputors(res),
+            RequestResults::SendTransaction(res) => Self::SendTransaction(res),
+            RequestResults::RequestTickTransactions(res) => Self::RequestTickTransactions(res.transactions),
+            RequestResults::FindAsset(res) => Self::FindAsset(res),
+            RequestResults::RequestQuorumVotes(res) => Self::RequestQuorumVotes(res),
+            RequestResults::WaitForNextTick(res) => Self::WaitForNextTick(res),
+            RequestResults::GetNetworkStatsHistory(res) => Self::GetNetworkStatsHistory(res),
+            RequestResults::GetNetworkStatsLatest(res) => Self::GetNetworkStatsLatest(res),
+            RequestResults::RequestSubmitWork(res) => Self::RequestSubmitWork(res),
+            RequestResults::RequestTickData(_) => return Err(Methods::RequestTickData),
+            RequestResults::RequestSystemInfo(_) => return Err(Methods::RequestSystemInfo),
+            RequestResults::RequestPublicPeers(_) => return Err(Methods::RequestPublicPeers),
+            RequestResults::RequestNetworkOverview(_) => return Err(Methods::RequestNetworkOverview)
+        })
+    }
+}
+
+impl From<v1::Methods> for Methods {
+    fn from(value: v1::Methods) -> Self {
+        match value {
+            v1::Methods::RequestCurrentTickInfo => Self::RequestCurrentTickInfo,
+            v1::Methods::RequestEntity => Self::RequestEntity,
+            v1::Methods::RequestComputors => Self::RequestComputors,
+            v1::Methods::SendTransaction => Self::SendTransaction,
+            v1::Methods::RequestTickTransaction => Self::RequestTickTransactions,
+            v1::Methods::FindAsset => Self::FindAsset,
+            v1::Methods::RequestQuorumVotes => Self::RequestQuorumVotes,
+            v1::Methods::WaitForNextTick => Self::WaitForNextTick,
+            v1::Methods::GetNetworkStatsHistory => Self::GetNetworkStatsHistory,
+            v1::Methods::GetNetworkStatsLatest => Self::GetNetworkStatsLatest,
+            v1::Methods::RequestSubmitWork => Self::RequestSubmitWork
+        }
+    }
+}
+
+impl From<v1::QubicJsonRpcRequest> for QubicJsonRpcRequest {
+    fn from(value: v1::QubicJsonRpcRequest) -> Self {
+        Self { jsonrpc: value.jsonrpc, version: Version::V2, id: value.id, request: value.request.into(), debug: value.debug }
+    }
+}
+
+impl TryFrom<QubicJsonRpcRequest> for v1::QubicJsonRpcRequest {
+    type Error = Methods;
+
+    fn try_from(value: QubicJsonRpcRequest) -> Result<Self, Self::Error> {
+        Ok(Self { jsonrpc: value.jsonrpc, id: value.id, request: value.request.try_into()?, debug: value.debug })
+    }
+}
+
+impl From<v1::QubicJsonRpcResponse> for QubicJsonRpcResponse {
+    fn from(value: v1::QubicJsonRpcResponse) -> Self {
+        let response = match value.response {
+            v1::ResponseType::Error(e) => ResponseType::Error(RequestError { method: e.method.into(), error: e.error }),
+            v1::ResponseType::Result(res) => ResponseType::Result(res.into())
+        };
+
+        Self { jsonrpc: value.jsonrpc, version: Version::V2, id: value.id, response, diagnostics: value.diagnostics }
+    }
+}
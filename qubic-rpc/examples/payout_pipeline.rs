@@ -0,0 +1,354 @@
+//! Pays out a CSV of `identity,amount` rows from a keystore wallet in SendToMany transactions of up to 25 recipients,
+//! waits until the target tick of every transaction passed and writes the outcome of every row to a results CSV
+//!
+//! QUBIC_KEYSTORE_PASSWORD=... cargo run --example payout_pipeline -- --keystore wallets.qks --source <ID> payouts.csv results.csv
+//!
+//! Transactions are broadcast to the computor of `--computor` or through the qubic-rpc server of `--rpc`.
+//! `cargo test --example payout_pipeline` runs the pipeline against a fake computor.
+
+use std::{path::PathBuf, str::FromStr, time::Duration};
+
+use clap::Parser;
+use qubic_rpc_types::{BroadcastedTransaction, ExternalRawTransaction, ExternallySignedTransaction, QubicJsonRpcRequest, QubicJsonRpcResponse, RequestMethods, RequestResults, ResponseType, TransactionParams, TransactionStatusReport};
+use qubic_types::{keystore::Keystore, traits::ToBytes, QubicId, QubicTxHash};
+use qubic_web3_rs::{client::Client, qubic_tcp_types::types::{fees::FeeSchedule, send_to_many::SendToManyInput, simulation::MIN_TICK_MARGIN, transactions::{TransactionBuilder, TransactionData, TransactionStatus, TransactionWithData}}, transport::Tcp};
+
+type Result<T> = std::result::Result<T, Box<dyn std::error::Error>>;
+
+/// recipients of a SendToMany transaction
+const BATCH_SIZE: usize = 25;
+
+#[derive(Parser, Debug)]
+struct Args {
+    /// CSV of `identity,amount` rows, an `identity,amount` header is skipped
+    payouts: PathBuf,
+    /// CSV the outcome of every row is written to
+    results: PathBuf,
+    #[arg(long)]
+    keystore: PathBuf,
+    /// identity of the keystore paying out
+    #[arg(long)]
+    source: String,
+    #[arg(long, default_value = "146.0.74.233:21841")]
+    computor: String,
+    /// broadcasts through the qubic-rpc server at this URL instead of `--computor`
+    #[arg(long)]
+    rpc: Option<String>,
+    /// SendToMany fee with `--rpc`, the fee is requested from the contract otherwise
+    #[arg(long, default_value_t = 10)]
+    send_to_many_fee: u64,
+    /// seconds between status requests while a transaction is pending
+    #[arg(long, default_value_t = 5)]
+    poll_interval: u64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+struct Payout {
+    identity: QubicId,
+    amount: u64
+}
+
+/// outcome of a row of the payouts CSV, `tx_hash` is `None` if the transaction could not be broadcast
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct PayoutResult {
+    payout: Payout,
+    tx_hash: Option<QubicTxHash>,
+    tick: u32,
+    status: String
+}
+
+/// rows of the payouts CSV, nothing is paid out if any identity fails its checksum or any amount is not positive
+fn read_payouts(csv: &str) -> Result<Vec<Payout>> {
+    let (mut payouts, mut invalid) = (Vec::new(), Vec::new());
+
+    for (line, row) in csv.lines().enumerate().map(|(idx, row)| (idx + 1, row.trim())).filter(|(_, row)| !row.is_empty()) {
+        let Some((identity, amount)) = row.split_once(',').map(|(identity, amount)| (identity.trim(), amount.trim())) else {
+            invalid.push(format!("line {line}: expected identity,amount"));
+            continue
+        };
+
+        if line == 1 && identity.eq_ignore_ascii_case("identity") {
+            continue
+        }
+
+        // parsing ignores the checksum characters, they have to match the encoding of the parsed key
+        let parsed = QubicId::from_str(identity).map_err(|e| e.to_string())
+            .and_then(|id| if id.to_string() == identity { Ok(id) } else { Err("checksum does not match".to_owned()) });
+
+        match (parsed, amount.parse::<u64>()) {
+            (Ok(identity), Ok(amount)) if amount > 0 => payouts.push(Payout { identity, amount }),
+            (Err(e), _) => invalid.push(format!("line {line}: {identity} is not a valid identity ({e})")),
+            _ => invalid.push(format!("line {line}: {amount} is not a positive amount"))
+        }
+    }
+
+    if !invalid.is_empty() {
+        return Err(format!("Nothing was paid out, the payouts contain invalid rows:\n{}", invalid.join("\n")).into())
+    }
+
+    Ok(payouts)
+}
+
+/// SendToMany transaction paying `batch` at `tick`, signed by `source` in the keystore
+fn batch_transaction(batch: &[Payout], source: QubicId, tick: u32, fees: &FeeSchedule, keystore: &Keystore) -> Result<TransactionWithData> {
+    let mut input = SendToManyInput::default();
+
+    for (slot, payout) in batch.iter().enumerate() {
+        input.ids[slot] = payout.identity;
+        input.amounts[slot] = payout.amount;
+    }
+
+    let mut tx = TransactionBuilder::new()
+        .with_from_id(source)
+        .with_tx_data(TransactionData::SendToMany(input))?
+        .with_estimated_fees(fees)
+        .unwrap_or_else(|e| match e {})
+        .with_tick(tick)
+        .build();
+
+    keystore.sign(&mut tx)?;
+
+    Ok(tx)
+}
+
+/// where transactions are broadcast and their status is requested
+enum Backend {
+    Computor(Client<Tcp>),
+    Rpc { url: String, http: reqwest::Client, send_to_many_fee: u64 }
+}
+
+impl Backend {
+    async fn call(http: &reqwest::Client, url: &str, request: RequestMethods) -> Result<RequestResults> {
+        let response: QubicJsonRpcResponse = http.post(url).json(&QubicJsonRpcRequest::new(0, request)).send().await?.json().await?;
+
+        match response.response {
+            ResponseType::Result(result) => Ok(result),
+            ResponseType::Error(e) => Err(e.error.into())
+        }
+    }
+
+    async fn current_tick(&self) -> Result<u32> {
+        match self {
+            Self::Computor(client) => Ok(client.qu().get_current_tick_info().await?.tick),
+            Self::Rpc { url, http, .. } => match Self::call(http, url, RequestMethods::RequestCurrentTickInfo).await? {
+                RequestResults::RequestCurrentTickInfo(info) => Ok(info.tick),
+                other => Err(format!("Unexpected result {other:?}").into())
+            }
+        }
+    }
+
+    async fn fee_schedule(&self) -> Result<FeeSchedule> {
+        match self {
+            Self::Computor(client) => Ok(client.qu().fee_schedule().await?),
+            Self::Rpc { send_to_many_fee, .. } => Ok(FeeSchedule { send_to_many_fee: *send_to_many_fee })
+        }
+    }
+
+    async fn broadcast(&self, tx: &TransactionWithData) -> Result<QubicTxHash> {
+        match self {
+            Self::Computor(client) => {
+                client.qu().send_signed_transaction(tx.clone()).await?;
+                Ok(QubicTxHash::from(tx))
+            },
+            Self::Rpc { url, http, .. } => {
+                let raw = &tx.raw_transaction;
+                let params = TransactionParams::External(ExternallySignedTransaction {
+                    raw_transaction: ExternalRawTransaction { source_id: raw.from, dest_id: raw.to, amount: raw.amount, tick: raw.tick, input_type: raw.input_type, input_hex: hex::encode(tx.data.to_bytes()) },
+                    signature_hex: hex::encode(tx.signature.0)
+                });
+
+                match Self::call(http, url, RequestMethods::SendTransaction(params)).await? {
+                    RequestResults::SendTransaction(BroadcastedTransaction { tx_hash, .. }) => Ok(tx_hash),
+                    other => Err(format!("Unexpected result {other:?}").into())
+                }
+            }
+        }
+    }
+
+    async fn status(&self, tx_hash: QubicTxHash, tick: u32) -> Result<TransactionStatus> {
+        match self {
+            Self::Computor(client) => Ok(client.qu().check_transaction_status(tx_hash, tick).await?),
+            Self::Rpc { url, http, .. } => {
+                let report: TransactionStatusReport = http.get(format!("{}/v1/tx-status/{tx_hash}?tick={tick}", url.trim_end_matches('/')))
+                    .send().await?
+                    .error_for_status()?
+                    .json().await?;
+
+                Ok(report.status)
+            }
+        }
+    }
+}
+
+fn status_name(status: &TransactionStatus) -> String {
+    match status {
+        TransactionStatus::Pending { .. } => "Pending".to_owned(),
+        TransactionStatus::NotIncluded => "NotIncluded".to_owned(),
+        TransactionStatus::Included => "Included".to_owned(),
+        TransactionStatus::Executed => "Executed".to_owned(),
+        TransactionStatus::Unknown { reason } => format!("Unknown: {reason}")
+    }
+}
+
+/// broadcasts a transaction per batch and waits for the status of each. Once a transaction is broadcast failures
+/// are recorded in the results instead of returned, the results have to be written whatever happens
+async fn run(backend: &Backend, keystore: &Keystore, source: QubicId, payouts: &[Payout], poll_interval: Duration) -> Result<Vec<PayoutResult>> {
+    let fees = backend.fee_schedule().await?;
+    let mut broadcast = Vec::new();
+
+    for batch in payouts.chunks(BATCH_SIZE) {
+        let tick = match backend.current_tick().await {
+            Ok(tick) => tick + MIN_TICK_MARGIN,
+            Err(e) => {
+                broadcast.push((batch, 0, Err(format!("BroadcastFailed: {e}"))));
+                continue
+            }
+        };
+        let tx = batch_transaction(batch, source, tick, &fees, keystore)?;
+
+        broadcast.push((batch, tick, backend.broadcast(&tx).await.map_err(|e| format!("BroadcastFailed: {e}"))));
+    }
+
+    let mut results = Vec::new();
+
+    for (batch, tick, tx_hash) in broadcast {
+        let status = match &tx_hash {
+            Ok(tx_hash) => loop {
+                match backend.status(*tx_hash, tick).await {
+                    Ok(TransactionStatus::Pending { .. }) => tokio::time::sleep(poll_interval).await,
+                    Ok(status) => break status_name(&status),
+                    Err(e) => break format!("StatusFailed: {e}")
+                }
+            },
+            Err(e) => e.clone()
+        };
+
+        results.extend(batch.iter().map(|payout| PayoutResult { payout: *payout, tx_hash: tx_hash.as_ref().ok().copied(), tick, status: status.clone() }));
+    }
+
+    Ok(results)
+}
+
+fn results_csv(results: &[PayoutResult]) -> String {
+    let mut csv = "identity,amount,tx_id,tick,status\n".to_owned();
+
+    for result in results {
+        let tx_id = result.tx_hash.map(|tx_hash| tx_hash.to_string()).unwrap_or_default();
+        csv += &format!("{},{},{tx_id},{},\"{}\"\n", result.payout.identity, result.payout.amount, result.tick, result.status.replace('"', "\"\""));
+    }
+
+    csv
+}
+
+#[tokio::main]
+async fn main() -> Result<()> {
+    let args = Args::parse();
+    let password = std::env::var("QUBIC_KEYSTORE_PASSWORD").map_err(|_| "Set the password of the keystore in QUBIC_KEYSTORE_PASSWORD")?;
+    let keystore = Keystore::open(&args.keystore, &password)?;
+    let source = QubicId::from_str(&args.source)?;
+
+    if !keystore.contains(&source) {
+        return Err(format!("{source} is not in the keystore").into())
+    }
+
+    let payouts = read_payouts(&std::fs::read_to_string(&args.payouts)?)?;
+
+    let backend = match args.rpc {
+        Some(url) => Backend::Rpc { url, http: reqwest::Client::new(), send_to_many_fee: args.send_to_many_fee },
+        None => Backend::Computor(Client::<Tcp>::new(args.computor).await?)
+    };
+
+    let results = run(&backend, &keystore, source, &payouts, Duration::from_secs(args.poll_interval)).await?;
+    std::fs::write(&args.results, results_csv(&results))?;
+
+    let executed = results.iter().filter(|result| result.status == "Executed").count();
+    println!("{executed} of {} payouts executed, see {}", results.len(), args.results.display());
+
+    Ok(())
+}
+
+#[tokio::test]
+async fn test_payout_pipeline() {
+    use std::sync::{atomic::{AtomicU32, AtomicUsize, Ordering}, Arc, Mutex};
+    use qubic_types::{traits::{FromBytes, VerifySignature}, SeedString};
+    use qubic_web3_rs::{fake_computor::{end_response, packet, FakeComputor, Reply}, qubic_tcp_types::{types::{send_to_many::SendToManyFeeOutput, ticks::{CurrentTickInfo, TickData}}, MessageType}};
+
+    // the tick advances by 3 with every tick info request, the second broadcast is lost
+    let tick = Arc::new(AtomicU32::new(1_000));
+    let received: Arc<Mutex<Vec<TransactionWithData>>> = Arc::default();
+    let broadcasts = Arc::new(AtomicUsize::new(0));
+
+    let computor = {
+        let (received, tick_transactions, tick_data) = (received.clone(), received.clone(), received.clone());
+
+        FakeComputor::new()
+            .on(MessageType::RequestCurrentTickInfo, move |_| {
+                let info = CurrentTickInfo { tick_duration: 2, epoch: 100, tick: tick.fetch_add(3, Ordering::Relaxed), number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 900 };
+                Reply::Packets(vec![packet(MessageType::RespondCurrentTickInfo, &info.to_bytes())])
+            })
+            .respond(MessageType::RequestContractFunction, MessageType::RespondContractFunction, SendToManyFeeOutput { fee: 10 }.to_bytes())
+            .on(MessageType::BroadcastTransaction, move |payload| {
+                if broadcasts.fetch_add(1, Ordering::Relaxed) != 1 {
+                    received.lock().unwrap().push(TransactionWithData::from_bytes(payload).unwrap());
+                }
+
+                Reply::Packets(vec![])
+            })
+            .on(MessageType::RequestTickTransactions, move |payload| {
+                let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+                let mut packets: Vec<_> = tick_transactions.lock().unwrap().iter()
+                    .filter(|tx| tx.raw_transaction.tick == tick)
+                    .map(|tx| packet(MessageType::BroadcastTransaction, &tx.to_bytes()))
+                    .collect();
+                packets.push(end_response());
+
+                Reply::Packets(packets)
+            })
+            .on(MessageType::RequestTickData, move |payload| {
+                let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+                let digests: Vec<QubicTxHash> = tick_data.lock().unwrap().iter().filter(|tx| tx.raw_transaction.tick == tick).map(QubicTxHash::from).collect();
+
+                let mut data = TickData::from_bytes(&vec![0; std::mem::size_of::<TickData>()]).unwrap();
+                (data.epoch, data.tick) = (100, tick);
+                data.transaction_digest[..digests.len()].copy_from_slice(&digests);
+
+                Reply::Packets(vec![packet(MessageType::BroadcastFutureTickData, &data.to_bytes())])
+            })
+            .start()
+    };
+
+    let path = std::env::temp_dir().join(format!("payout-pipeline-{}.qks", std::process::id()));
+    let keystore = Keystore::open(&path, "password").unwrap();
+    let source = keystore.add(SeedString::from_str("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap()).unwrap();
+
+    let mut csv = "identity,amount\n".to_owned();
+    for idx in 1..=27u8 {
+        csv += &format!("{},{}\n", QubicId([idx; 32]), u64::from(idx) * 100);
+    }
+    let payouts = read_payouts(&csv).unwrap();
+    assert_eq!((payouts.len(), payouts[26]), (27, Payout { identity: QubicId([27; 32]), amount: 2_700 }));
+
+    let backend = Backend::Computor(Client::<Tcp>::new(computor.url()).await.unwrap());
+    let results = run(&backend, &keystore, source, &payouts, Duration::from_millis(10)).await.unwrap();
+
+    // the first batch pays 25 recipients plus the contract fee
+    let sent = received.lock().unwrap()[0].clone();
+    let TransactionData::SendToMany(input) = &sent.data else { panic!("expected a SendToMany transaction") };
+    assert_eq!((sent.raw_transaction.from, sent.raw_transaction.amount, input.ids[24], input.amounts[24]), (source, 32_500 + 10, QubicId([25; 32]), 2_500));
+    assert!(sent.verify());
+
+    assert_eq!(results.len(), 27);
+    assert!(results[..25].iter().all(|result| result.status == "Executed" && result.tx_hash == Some(QubicTxHash::from(&sent))));
+    assert!(results[25..].iter().all(|result| result.status == "NotIncluded" && result.tx_hash.is_some()));
+
+    let csv = results_csv(&results);
+    assert_eq!(csv.lines().count(), 28);
+    assert!(csv.lines().nth(1).unwrap().ends_with(&format!(",{},\"Executed\"", sent.raw_transaction.tick)));
+
+    // a single broken checksum stops the whole payout
+    let mut id = QubicId([1; 32]).to_string();
+    id.replace_range(59.., if id.ends_with('A') { "B" } else { "A" });
+    assert!(read_payouts(&format!("{id},100\n{},0\n", QubicId([2; 32]))).unwrap_err().to_string().contains("line 1"));
+
+    let _ = std::fs::remove_file(path);
+}
@@ -0,0 +1,1574 @@
+use std::{collections::{BTreeSet, HashSet}, convert::Infallible, error::Error, fs::File, future::Future, io::{BufWriter, Write}, ops::{Bound, Range}, sync::{Arc, Mutex}, time::Duration};
+
+use qubic_rpc_types::{ComputorInfos, EpochStats, RichListEntry};
+use qubic_types::{traits::VerifySignature, QubicId, QubicTxHash};
+use qubic_web3_rs::{client::Client, transport::Tcp, qubic_tcp_types::types::{fees::FeeSchedule, qlogging::{QuTransferLog, QubicLogs}, ticks::{QuorumSummary, TickData}, transactions::{order_transactions, verify_batch, RawTransaction, TransactionFlags, TransactionKind, TransactionStatus, TransactionWithData}, Computors, Entity, RespondedEntity}};
+use serde::{Deserialize, Serialize};
+use sled::{transaction::{TransactionError, TransactionResult}, Transactional};
+use tokio::{sync::mpsc, task::JoinHandle};
+
+use crate::{hll::HyperLogLog, scheduler::{Priority, UpstreamScheduler}};
+
+pub type SinkResult = Result<(), Box<dyn Error + Send + Sync>>;
+
+/// Attempts to fetch a tick before it is skipped, computors do not answer for empty ticks
+const MAX_FETCH_ATTEMPTS: usize = 3;
+
+/// Archived ticks whose votes are checked on every poll until they are final, older ones stay unfinalized
+const MAX_PENDING_FINALITY: usize = 16;
+
+/// Receives the archived ticks. Every sink sees the ticks in ascending order and per tick
+/// the epoch change (if any), the tick data, its transactions and then the completion of the tick. Ticks are finalized
+/// after they were archived, the computor list of an epoch is handed over once it was fetched and the entities of the
+/// identities moving QU in archived ticks once they were refreshed
+pub trait ArchiverSink: Send + Sync + 'static {
+    fn name(&self) -> &str;
+
+    fn on_tick(&self, tick_data: &TickData) -> impl Future<Output = SinkResult> + Send;
+
+    fn on_transaction(&self, tx: &ArchivedTransaction) -> impl Future<Output = SinkResult> + Send;
+
+    /// every event of `tick` was handled without an error, as were the events of all ticks before it. Once an event
+    /// failed no tick is completed anymore, so progress recorded here never skips over a tick
+    fn on_tick_complete(&self, _tick: u32) -> impl Future<Output = SinkResult> + Send {
+        async { Ok(()) }
+    }
+
+    fn on_epoch_change(&self, epoch: u16) -> impl Future<Output = SinkResult> + Send;
+
+    /// a quorum of computors voted for the same digests of the archived `tick`
+    fn on_finalized(&self, _tick: u32) -> impl Future<Output = SinkResult> + Send {
+        async { Ok(()) }
+    }
+
+    fn on_computors(&self, _computors: &Computors) -> impl Future<Output = SinkResult> + Send {
+        async { Ok(()) }
+    }
+
+    /// current `entity` of an identity which moved QU in an archived tick, valid at `tick`
+    fn on_entity(&self, _tick: u32, _entity: &Entity) -> impl Future<Output = SinkResult> + Send {
+        async { Ok(()) }
+    }
+}
+
+/// Transaction as handed to the sinks, `money_flew` is unknown (`None`) unless the node logs are archived as well.
+/// `malformed` transactions disagree with their own input size or type, they only reach the sinks if kept by the archiver.
+/// `signature_valid` is unknown (`None`) for transactions archived before the signatures were checked
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct ArchivedTransaction {
+    pub tick: u32,
+    #[serde(flatten)]
+    pub transaction: TransactionWithData,
+    pub money_flew: Option<bool>,
+    #[serde(default)]
+    pub malformed: bool,
+    #[serde(default)]
+    pub signature_valid: Option<bool>
+}
+
+/// Whether each transaction of a tick moved funds according to the logged transfers of the tick.
+///
+/// A transaction moved funds if a transfer with the same source, destination and amount was logged. The node
+/// executes and logs the transactions in the order of the tick, so identical transactions claim the logged transfers
+/// in that order: if only two of three identical transactions were logged, the first two moved funds
+pub fn match_transfers(transactions: &[TransactionWithData], transfers: &[QuTransferLog]) -> Vec<bool> {
+    let mut claimed = vec![false; transfers.len()];
+
+    transactions.iter().map(|tx| {
+        let raw = &tx.raw_transaction;
+        let transfer = transfers.iter().enumerate().position(|(i, transfer)| {
+            !claimed[i] && transfer.from == raw.from && transfer.to == raw.to && transfer.amount == raw.amount
+        });
+
+        match transfer {
+            Some(i) => {
+                claimed[i] = true;
+                true
+            },
+            None => false
+        }
+    }).collect()
+}
+
+/// Archived ticks which were not seen with a quorum of matching votes yet
+#[derive(Debug, Default)]
+pub struct FinalityTracker {
+    pending: BTreeSet<u32>
+}
+
+impl FinalityTracker {
+    /// the oldest pending tick is given up once `MAX_PENDING_FINALITY` ticks are pending
+    pub fn track(&mut self, tick: u32) {
+        self.pending.insert(tick);
+
+        if self.pending.len() > MAX_PENDING_FINALITY {
+            if let Some(tick) = self.pending.pop_first() {
+                warn!("Tick {tick} did not reach quorum while archived, it stays unfinalized");
+            }
+        }
+    }
+
+    pub fn pending(&self) -> Vec<u32> {
+        self.pending.iter().copied().collect()
+    }
+
+    /// returns whether the pending `tick` became final with `votes`
+    pub fn observe(&mut self, tick: u32, votes: &QuorumSummary) -> bool {
+        votes.quorum_reached && self.pending.remove(&tick)
+    }
+}
+
+enum ArchiveEvent {
+    EpochChange(u16),
+    Tick(Box<TickData>),
+    Transaction(Box<ArchivedTransaction>),
+    TickComplete(u32),
+    Finalized(u32),
+    Computors(Box<Computors>),
+    Entity(u32, Box<Entity>)
+}
+
+/// Feeds archived ticks to the registered sinks. Each sink runs in its own task behind a bounded queue,
+/// a failing sink only logs while a full queue holds back the ingestion
+pub struct Archiver {
+    capacity: usize,
+    sinks: Vec<mpsc::Sender<Arc<ArchiveEvent>>>,
+    workers: Vec<JoinHandle<()>>,
+    epoch: Option<u16>,
+    /// epoch of the last computor list handed to the sinks
+    computors_epoch: Option<u16>,
+    keep_malformed: bool,
+    finality: FinalityTracker,
+    /// identities which moved QU in the ingested ticks since their entities were last refreshed
+    moved: HashSet<QubicId>,
+    scheduler: UpstreamScheduler
+}
+
+impl Archiver {
+    /// `capacity` events are queued per sink
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            sinks: Vec::new(),
+            workers: Vec::new(),
+            epoch: None,
+            computors_epoch: None,
+            keep_malformed: false,
+            finality: FinalityTracker::default(),
+            moved: HashSet::new(),
+            scheduler: UpstreamScheduler::unlimited()
+        }
+    }
+
+    /// sends the requests of `run` as background work of `scheduler`, they are not limited otherwise
+    pub fn with_scheduler(mut self, scheduler: UpstreamScheduler) -> Self {
+        self.scheduler = scheduler;
+        self
+    }
+
+    /// hands transactions whose input size or type is inconsistent to the sinks tagged as `malformed` instead of dropping them
+    pub fn with_malformed(mut self, keep: bool) -> Self {
+        self.keep_malformed = keep;
+        self
+    }
+
+    pub fn with_sink<S: ArchiverSink>(mut self, sink: S) -> Self {
+        let (tx, mut rx) = mpsc::channel::<Arc<ArchiveEvent>>(self.capacity);
+
+        self.workers.push(tokio::spawn(async move {
+            // whether an event of an archived tick failed, no tick is completed afterwards
+            let mut failed = false;
+
+            while let Some(event) = rx.recv().await {
+                let res = match event.as_ref() {
+                    ArchiveEvent::EpochChange(epoch) => sink.on_epoch_change(*epoch).await,
+                    ArchiveEvent::Tick(tick_data) => sink.on_tick(tick_data).await,
+                    ArchiveEvent::Transaction(tx) => sink.on_transaction(tx).await,
+                    ArchiveEvent::TickComplete(tick) if failed => {
+                        warn!("Archiver sink {} does not complete tick {tick} after an earlier tick failed", sink.name());
+                        Ok(())
+                    },
+                    ArchiveEvent::TickComplete(tick) => sink.on_tick_complete(*tick).await,
+                    ArchiveEvent::Finalized(tick) => sink.on_finalized(*tick).await,
+                    ArchiveEvent::Computors(computors) => sink.on_computors(computors).await,
+                    ArchiveEvent::Entity(tick, entity) => sink.on_entity(*tick, entity).await
+                };
+
+                if let Err(e) = res {
+                    error!("Archiver sink {} failed: {e}", sink.name());
+
+                    if matches!(event.as_ref(), ArchiveEvent::EpochChange(_) | ArchiveEvent::Tick(_) | ArchiveEvent::Transaction(_) | ArchiveEvent::TickComplete(_)) {
+                        failed = true;
+                    }
+                }
+            }
+        }));
+        self.sinks.push(tx);
+
+        self
+    }
+
+    pub fn has_sinks(&self) -> bool {
+        !self.sinks.is_empty()
+    }
+
+    /// queues the tick and its transactions for every sink, waits while the queue of a sink is full.
+    /// `transfers` are the logged transfers of the tick, without them `money_flew` is unknown.
+    /// Malformed transactions still claim their logged transfers since the node executed them anyway.
+    /// The signatures are verified with `verify_batch` off the runtime, busy ticks keep every core busy for a while.
+    /// Identities sending or receiving an amount which may have moved are remembered for `refresh_entities`
+    pub async fn ingest(&mut self, tick_data: TickData, transactions: Vec<TransactionWithData>, transfers: Option<&[QuTransferLog]>) {
+        let tick = tick_data.tick;
+        let (transactions, signatures) = tokio::task::spawn_blocking(move || {
+            let signatures = verify_batch(&transactions);
+            (transactions, signatures)
+        }).await.expect("signature verification panicked");
+        let money_flew = match transfers {
+            Some(transfers) => match_transfers(&transactions, transfers).into_iter().map(Some).collect(),
+            None => vec![None; transactions.len()]
+        };
+        let mut events = Vec::with_capacity(transactions.len() + 3);
+
+        if self.epoch != Some(tick_data.epoch) {
+            self.epoch = Some(tick_data.epoch);
+            events.push(ArchiveEvent::EpochChange(tick_data.epoch));
+        }
+
+        events.push(ArchiveEvent::Tick(Box::new(tick_data)));
+        events.extend(transactions.into_iter().zip(money_flew).zip(signatures).filter_map(|((transaction, money_flew), signature_valid)| {
+            // the amounts are not checked, so the schedule does not matter
+            let malformed = !transaction.validate_with(signature_valid, &FeeSchedule::default()).is_well_formed();
+
+            if malformed && !self.keep_malformed {
+                warn!("Dropping malformed transaction {} of tick {tick}", QubicTxHash::from(&transaction));
+                return None;
+            }
+
+            if !signature_valid {
+                warn!("Transaction {} of tick {tick} is not signed by its source", QubicTxHash::from(&transaction));
+            }
+
+            let raw = &transaction.raw_transaction;
+
+            if raw.amount > 0 && money_flew != Some(false) {
+                self.moved.extend([raw.from, raw.to].into_iter().filter(|id| *id != QubicId::default()));
+            }
+
+            Some(ArchiveEvent::Transaction(Box::new(ArchivedTransaction { tick, transaction, money_flew, malformed, signature_valid: Some(signature_valid) })))
+        }));
+        events.push(ArchiveEvent::TickComplete(tick));
+
+        self.finality.track(tick);
+        self.send(events).await
+    }
+
+    /// checks the votes of `tick`, the sinks are told once the tick is final
+    pub async fn observe_votes(&mut self, tick: u32, votes: &QuorumSummary) {
+        if self.finality.observe(tick, votes) {
+            self.send(vec![ArchiveEvent::Finalized(tick)]).await
+        }
+    }
+
+    /// hands the computor list to the sinks unless the list of its epoch was handed over already
+    pub async fn observe_computors(&mut self, computors: Computors) {
+        if self.computors_epoch != Some(computors.epoch) {
+            self.computors_epoch = Some(computors.epoch);
+            self.send(vec![ArchiveEvent::Computors(Box::new(computors))]).await
+        }
+    }
+
+    /// hands the current entity of an identity to the sinks
+    pub async fn observe_entity(&mut self, responded: RespondedEntity) {
+        self.send(vec![ArchiveEvent::Entity(responded.tick, Box::new(responded.entity))]).await
+    }
+
+    /// requests the current entity of every identity which moved QU since the previous refresh, so the balances of
+    /// the sinks follow the archived transfers. Identities whose entity could not be fetched are refreshed next time
+    async fn refresh_entities(&mut self, client: &Client<Tcp>) {
+        for id in std::mem::take(&mut self.moved) {
+            self.scheduler.acquire(Priority::Background).await;
+
+            match client.qu().request_entity(id).await {
+                Ok(responded) => self.observe_entity(responded).await,
+                Err(e) => {
+                    warn!("Failed to refresh the entity of {id}: {e}");
+                    self.moved.insert(id);
+                }
+            }
+        }
+    }
+
+    async fn send(&self, events: Vec<ArchiveEvent>) {
+        for event in events.into_iter().map(Arc::new) {
+            for sink in self.sinks.iter() {
+                // a closed queue belongs to a panicked sink, the others are still fed
+                let _ = sink.send(event.clone()).await;
+            }
+        }
+    }
+
+    /// waits until every sink processed its queued events
+    #[cfg(test)]
+    pub async fn shutdown(self) {
+        drop(self.sinks);
+
+        for worker in self.workers {
+            let _ = worker.await;
+        }
+    }
+
+    /// archives every tick from `from_tick` (default: the current tick) on, the computor is polled every `interval`.
+    /// With the logging `passcode` of the computor the transactions are matched to the logged transfers. The votes of
+    /// the archived ticks are requested on every poll until the ticks are final, the computor list until the list of the
+    /// current epoch is archived. The entities of the identities which moved QU are refreshed once the poll archived
+    /// its ticks
+    pub async fn run(mut self, computor: String, from_tick: Option<u32>, interval: Duration, passcode: Option<[u64; 4]>) {
+        let client = crate::computor_client(&computor).await.unwrap();
+        let mut next_tick = from_tick;
+        let mut attempts = 0;
+        let mut logs = TransferLogs::default();
+
+        loop {
+            self.scheduler.acquire(Priority::Background).await;
+
+            match client.qu().get_current_tick_info().await {
+                Ok(info) => {
+                    // requested once per poll after the current tick, so the logs of every tick before it are complete
+                    if let Some(passcode) = passcode {
+                        self.scheduler.acquire(Priority::Background).await;
+
+                        match client.qu().request_logs(passcode).await {
+                            Ok(received) => logs.received(info.tick, received),
+                            Err(e) => {
+                                warn!("Failed to fetch logs up to tick {}: {e}", info.tick);
+                                logs.failed();
+                            }
+                        }
+                    }
+
+                    if self.computors_epoch != Some(info.epoch) {
+                        self.scheduler.acquire(Priority::Background).await;
+
+                        match client.qu().request_computors().await {
+                            Ok(computors) => self.observe_computors(*computors).await,
+                            Err(e) => warn!("Failed to fetch the computors of epoch {}: {e}", info.epoch)
+                        }
+                    }
+
+                    let next = next_tick.get_or_insert(info.tick);
+
+                    while *next < info.tick {
+                        self.scheduler.acquire(Priority::Background).await;
+
+                        let res = match client.qu().request_tick_data(*next).await {
+                            // transfers are matched with the logs in execution order
+                            Ok(tick_data) => {
+                                self.scheduler.acquire(Priority::Background).await;
+
+                                client.qu().request_tick_transactions(*next, TransactionFlags::all()).await.map(|mut txs| {
+                                    order_transactions(&mut txs, &tick_data);
+                                    (tick_data, txs)
+                                })
+                            },
+                            Err(e) => Err(e)
+                        };
+
+                        match res {
+                            Ok((tick_data, txs)) => {
+                                let transfers = logs.take(*next);
+                                self.ingest(*tick_data, txs, transfers.as_deref()).await
+                            },
+                            Err(e) if attempts + 1 < MAX_FETCH_ATTEMPTS => {
+                                attempts += 1;
+                                warn!("Failed to fetch tick {next} for archiving: {e}");
+                                break;
+                            },
+                            Err(e) => warn!("Skipping tick {next} after {MAX_FETCH_ATTEMPTS} attempts: {e}")
+                        }
+
+                        attempts = 0;
+                        *next += 1;
+                    }
+
+                    self.refresh_entities(&client).await;
+
+                    for tick in self.finality.pending() {
+                        self.scheduler.acquire(Priority::Background).await;
+
+                        match client.qu().request_quorum_votes(tick).await {
+                            Ok(votes) => self.observe_votes(tick, &votes).await,
+                            Err(e) => warn!("Failed to fetch quorum votes of tick {tick}: {e}")
+                        }
+                    }
+                },
+                Err(e) => warn!("Failed to poll current tick for archiving: {e}")
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    }
+}
+
+/// Transfer logs received from the node but not yet matched. The node only sends the logs emitted since the previous
+/// request, so the logs of a tick are only known to be complete if it was executed after the first successful request,
+/// before the latest one and no request failed since
+#[derive(Default)]
+struct TransferLogs {
+    logs: QubicLogs,
+    /// ticks whose logs are complete
+    complete: Option<Range<u32>>
+}
+
+impl TransferLogs {
+    /// logs received while `current_tick` was the current tick of the node
+    fn received(&mut self, current_tick: u32, logs: QubicLogs) {
+        let start = self.complete.as_ref().map_or(current_tick + 1, |complete| complete.start);
+
+        self.logs.0.extend(logs.0);
+        self.complete = Some(start..current_tick);
+    }
+
+    /// the logs emitted since the previous request are lost
+    fn failed(&mut self) {
+        self.logs.0.clear();
+        self.complete = None;
+    }
+
+    /// transfers of `tick` in execution order, `None` unless its logs are complete
+    fn take(&mut self, tick: u32) -> Option<Vec<QuTransferLog>> {
+        let transfers = self.complete.as_ref().is_some_and(|complete| complete.contains(&tick)).then(|| self.logs.transfers(tick).cloned().collect());
+        self.logs.0.retain(|log| log.header.tick > tick);
+
+        transfers
+    }
+}
+
+/// Persists ticks and transactions in sled, transactions are keyed by tick and hash.
+/// The last completely archived tick is kept as `cursor` next to the current `epoch` in the meta tree, it only advances
+/// once the tick and all its transactions are stored,
+/// entities refreshed by the archiver or fetched by the diff are kept keyed by identity and tick. The first archived tick of every epoch and the
+/// computor lists fetched by the archiver or the server are kept keyed by epoch, each with whether it is signed by the
+/// arbitrator.
+///
+/// The rich list indexes the latest stored entity of every identity by descending balance and then identity, the
+/// `balances` tree maps identities to their indexed balance and `rich_list_size` in the meta tree counts them. It only
+/// holds identities which sent or received QU in an archived tick or were diffed, identities untouched since the
+/// archiving started are missing and balances changed without a transaction (mining rewards, contract payouts) are only
+/// caught up with on their next transfer.
+///
+/// Every newly archived transaction is counted in the statistics of the epoch of its tick, keyed by epoch in
+/// `epoch_stats` with the active addresses of the epoch in `epoch_addresses`, the amounts sent to the zero identity are
+/// counted as burned. Transactions archived again are not counted.
+///
+/// Archived ticks which reached quorum are kept in `finalized`
+///
+/// `identity_transactions` indexes the archived transactions by the identities sending and receiving them, keyed by
+/// identity, tick and hash. The zero identity is not indexed
+#[derive(Clone)]
+pub struct SledSink {
+    ticks: sled::Tree,
+    transactions: sled::Tree,
+    meta: sled::Tree,
+    entities: sled::Tree,
+    epochs: sled::Tree,
+    computors: sled::Tree,
+    balances: sled::Tree,
+    rich_list: sled::Tree,
+    epoch_stats: sled::Tree,
+    epoch_addresses: sled::Tree,
+    finalized: sled::Tree,
+    identity_transactions: sled::Tree
+}
+
+/// Counters of an epoch as persisted in `epoch_stats`
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize)]
+struct EpochCounters {
+    transactions: u64,
+    transferred: u64,
+    qx_volume: u64,
+    #[serde(default)]
+    burned: u64
+}
+
+/// Computor list as persisted in `computors`, lists stored before the signature check was recorded lack `verified`
+#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+struct StoredComputors {
+    #[serde(flatten)]
+    computors: Computors,
+    #[serde(default)]
+    verified: Option<bool>
+}
+
+impl SledSink {
+    /// trees of the archive, a snapshot of the archive consists of them
+    pub const TREES: [&'static str; 12] = ["ticks", "transactions", "meta", "entities", "epochs", "computors", "balances", "rich_list", "epoch_stats", "epoch_addresses", "finalized", "identity_transactions"];
+
+    pub fn open(path: &str) -> sled::Result<Self> {
+        Self::from_db(&sled::open(path)?)
+    }
+
+    pub fn from_db(db: &sled::Db) -> sled::Result<Self> {
+        let sink = Self {
+            ticks: db.open_tree("ticks")?,
+            transactions: db.open_tree("transactions")?,
+            meta: db.open_tree("meta")?,
+            entities: db.open_tree("entities")?,
+            epochs: db.open_tree("epochs")?,
+            computors: db.open_tree("computors")?,
+            balances: db.open_tree("balances")?,
+            rich_list: db.open_tree("rich_list")?,
+            epoch_stats: db.open_tree("epoch_stats")?,
+            epoch_addresses: db.open_tree("epoch_addresses")?,
+            finalized: db.open_tree("finalized")?,
+            identity_transactions: db.open_tree("identity_transactions")?
+        };
+
+        // archives written before the rich list was indexed
+        if !sink.meta.contains_key("rich_list_size")? {
+            sink.reindex_rich_list()?;
+        }
+
+        // archives written before the transactions were indexed by identity
+        if !sink.meta.contains_key("identity_transactions_indexed")? {
+            sink.reindex_identity_transactions()?;
+        }
+
+        Ok(sink)
+    }
+
+    /// last archived tick, archiving resumes after it
+    pub fn cursor(&self) -> sled::Result<Option<u32>> {
+        Ok(self.meta.get("cursor")?.and_then(|cursor| Some(u32::from_be_bytes(cursor.as_ref().try_into().ok()?))))
+    }
+
+    #[cfg(test)]
+    pub fn transaction(&self, tick: u32, hash: &QubicTxHash) -> sled::Result<Option<ArchivedTransaction>> {
+        Ok(self.transactions.get([tick.to_be_bytes().as_slice(), &hash.0].concat())?.and_then(|record| serde_json::from_slice(&record).ok()))
+    }
+
+    /// status of the transaction in `tick` if the tick is archived, archived ticks passed and have tick data
+    pub fn transaction_status(&self, tx_hash: &QubicTxHash, tick: u32) -> sled::Result<Option<TransactionStatus>> {
+        let Some(tick_data) = self.ticks.get(tick.to_be_bytes())?.and_then(|record| serde_json::from_slice::<TickData>(&record).ok()) else {
+            return Ok(None)
+        };
+        let received = self.transactions.contains_key([tick.to_be_bytes().as_slice(), &tx_hash.0].concat())?;
+
+        Ok(Some(TransactionStatus::in_tick(tx_hash, received.then_some(*tx_hash), Some(&tick_data))))
+    }
+
+    /// whether the archive saw a quorum of matching votes for `tick`
+    pub fn is_finalized(&self, tick: u32) -> sled::Result<bool> {
+        self.finalized.contains_key(tick.to_be_bytes())
+    }
+
+    /// tick data of the latest finalized tick
+    pub fn latest_finalized(&self) -> sled::Result<Option<TickData>> {
+        let Some((tick, _)) = self.finalized.last()? else { return Ok(None) };
+
+        Ok(self.ticks.get(tick)?.and_then(|record| serde_json::from_slice(&record).ok()))
+    }
+
+    /// archived transactions of the ticks in `from_tick..=to_tick` in tick order
+    pub fn transactions_between(&self, from_tick: u32, to_tick: u32) -> sled::Result<Vec<ArchivedTransaction>> {
+        if from_tick > to_tick {
+            return Ok(Vec::new())
+        }
+
+        let range = from_tick.to_be_bytes().to_vec()..[to_tick.to_be_bytes().as_slice(), &[u8::MAX; 32]].concat();
+
+        self.transactions.range(range).values()
+            .map(|record| record.map(|record| serde_json::from_slice(&record).ok()))
+            .filter_map(Result::transpose)
+            .collect()
+    }
+
+    /// archived transactions `id` sent or received in `from_tick..=to_tick` in tick order, `skip` and `limit` select the
+    /// page. The number of transactions in the range is counted from the index keys
+    pub fn identity_transactions(&self, id: &QubicId, from_tick: u32, to_tick: u32, skip: usize, limit: usize) -> sled::Result<(u64, Vec<ArchivedTransaction>)> {
+        if from_tick > to_tick {
+            return Ok((0, Vec::new()))
+        }
+
+        let range = entity_key(id, from_tick)..[id.0.as_slice(), &to_tick.to_be_bytes(), &[u8::MAX; 32]].concat();
+        let mut total = 0;
+        let mut transactions = Vec::new();
+
+        for key in self.identity_transactions.range(range).keys() {
+            let key = key?;
+            total += 1;
+
+            if total <= skip as u64 || transactions.len() >= limit {
+                continue
+            }
+
+            if let Some(tx) = self.transactions.get(&key[32..])?.and_then(|record| serde_json::from_slice(&record).ok()) {
+                transactions.push(tx);
+            }
+        }
+
+        Ok((total, transactions))
+    }
+
+    /// number of archived ticks in `from_tick..=to_tick`, counted without reading their tick data
+    pub fn count_ticks_between(&self, from_tick: u32, to_tick: u32) -> sled::Result<u32> {
+        if from_tick > to_tick {
+            return Ok(0)
+        }
+
+        self.ticks.range(from_tick.to_be_bytes()..=to_tick.to_be_bytes()).keys().try_fold(0, |count, key| key.map(|_| count + 1))
+    }
+
+    /// archived ticks in `from_tick..=to_tick`
+    pub fn ticks_between(&self, from_tick: u32, to_tick: u32) -> sled::Result<Vec<u32>> {
+        if from_tick > to_tick {
+            return Ok(Vec::new())
+        }
+
+        self.ticks.range(from_tick.to_be_bytes()..=to_tick.to_be_bytes()).keys()
+            .map(|key| key.map(|key| u32::from_be_bytes(key.as_ref().try_into().unwrap_or_default())))
+            .collect()
+    }
+
+    /// stores the entity and moves its identity in the rich list unless a later entity of it is stored already
+    pub fn insert_entity(&self, tick: u32, entity: &Entity) -> sled::Result<()> {
+        let id = entity.public_key;
+        let value = serde_json::to_vec(entity).expect("Entity serializes");
+
+        let result: TransactionResult<(), Infallible> = (&self.entities, &self.balances, &self.rich_list, &self.meta).transaction(|(entities, balances, rich_list, meta)| {
+            entities.insert(entity_key(&id, tick), value.as_slice())?;
+
+            let size = meta.get("rich_list_size")?.as_deref().map(stored_u64).unwrap_or_default();
+
+            match balances.get(id.0)?.as_deref().map(stored_balance) {
+                Some((_, latest)) if latest > tick => return Ok(()),
+                Some((balance, _)) => { rich_list.remove(rich_list_key(balance, &id))?; },
+                None => { meta.insert("rich_list_size", &(size + 1).to_be_bytes())?; }
+            }
+
+            rich_list.insert(rich_list_key(entity.balance(), &id), &tick.to_be_bytes())?;
+            balances.insert(&id.0, [entity.balance().to_be_bytes().as_slice(), &tick.to_be_bytes()].concat())?;
+
+            Ok(())
+        });
+
+        result.map_err(|e| match e {
+            TransactionError::Storage(e) => e,
+            TransactionError::Abort(never) => match never {}
+        })
+    }
+
+    /// number of identities in the rich list, read from the counter without scanning
+    pub fn rich_list_size(&self) -> sled::Result<u64> {
+        Ok(self.meta.get("rich_list_size")?.as_deref().map(stored_u64).unwrap_or_default())
+    }
+
+    /// circulating supply and number of identities holding QU, over the latest balance of every identity of the rich list
+    pub fn supply(&self) -> sled::Result<(u64, u64)> {
+        self.balances.iter().values().try_fold((0u64, 0u64), |(supply, holders), balance| {
+            let (balance, _) = stored_balance(&balance?);
+
+            Ok((supply.saturating_add(balance), holders + u64::from(balance > 0)))
+        })
+    }
+
+    /// latest balance of every identity of the rich list holding QU, in no particular order. Read from the balances
+    /// rather than the rich list: an identity moved by a concurrent update is counted once, before or after the update
+    pub fn held_balances(&self) -> sled::Result<Vec<u64>> {
+        self.balances.iter().values()
+            .map(|balance| balance.map(|balance| stored_balance(&balance).0))
+            .filter(|balance| !matches!(balance, Ok(0)))
+            .collect()
+    }
+
+    /// identities of the rich list following `after`, or from the top without it, read as the iterator advances.
+    /// Returns `None` if `after` is not in the rich list
+    pub fn rich_list(&self, after: Option<&QubicId>) -> sled::Result<Option<impl Iterator<Item = sled::Result<RichListEntry>> + Send + 'static>> {
+        let start = match after {
+            Some(id) => match self.balances.get(id.0)? {
+                Some(stored) => Bound::Excluded(rich_list_key(stored_balance(&stored).0, id)),
+                None => return Ok(None)
+            },
+            None => Bound::Unbounded
+        };
+
+        Ok(Some(self.rich_list.range::<Vec<u8>, _>((start, Bound::Unbounded)).map(|entry| entry.map(|(key, tick)| {
+            let (rank, id) = key.split_at(8);
+
+            RichListEntry {
+                identity: QubicId(id.try_into().unwrap_or_default()),
+                balance: u64::MAX - stored_u64(rank),
+                tick: u32::from_be_bytes(tick.as_ref().try_into().unwrap_or_default())
+            }
+        }))))
+    }
+
+    /// rebuilds the rich list from the latest stored entity of every identity
+    fn reindex_rich_list(&self) -> sled::Result<()> {
+        self.balances.clear()?;
+        self.rich_list.clear()?;
+
+        let mut latest: Option<(u32, Entity)> = None;
+        let mut size = 0u64;
+
+        for entry in self.entities.iter().map(|entry| entry.map(|(key, value)| stored_entity(&key, &value))) {
+            let Some((tick, entity)) = entry? else { continue };
+
+            if let Some((latest_tick, latest_entity)) = latest.replace((tick, entity)) {
+                if latest_entity.public_key != entity.public_key {
+                    self.index_entity(latest_tick, &latest_entity)?;
+                    size += 1;
+                }
+            }
+        }
+
+        if let Some((tick, entity)) = latest {
+            self.index_entity(tick, &entity)?;
+            size += 1;
+        }
+
+        self.meta.insert("rich_list_size", &size.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    fn reindex_identity_transactions(&self) -> sled::Result<()> {
+        for entry in self.transactions.iter() {
+            let (key, record) = entry?;
+            let Ok(tx) = serde_json::from_slice::<ArchivedTransaction>(&record) else { continue };
+
+            for id in identities_of(&tx.transaction.raw_transaction) {
+                self.identity_transactions.insert([id.0.as_slice(), &key].concat(), &[])?;
+            }
+        }
+
+        self.meta.insert("identity_transactions_indexed", &[])?;
+
+        Ok(())
+    }
+
+    fn index_entity(&self, tick: u32, entity: &Entity) -> sled::Result<()> {
+        self.rich_list.insert(rich_list_key(entity.balance(), &entity.public_key), &tick.to_be_bytes())?;
+        self.balances.insert(entity.public_key.0, [entity.balance().to_be_bytes().as_slice(), &tick.to_be_bytes()].concat())?;
+
+        Ok(())
+    }
+
+    /// latest stored entity of `id` at or before `tick` with its tick
+    pub fn entity_at_or_before(&self, id: &QubicId, tick: u32) -> sled::Result<Option<(u32, Entity)>> {
+        Ok(self.entities.range(entity_key(id, 0)..=entity_key(id, tick)).next_back().transpose()?.and_then(|(key, value)| stored_entity(&key, &value)))
+    }
+
+    /// earliest stored entity of `id` after `tick` with its tick
+    pub fn entity_after(&self, id: &QubicId, tick: u32) -> sled::Result<Option<(u32, Entity)>> {
+        if tick == u32::MAX {
+            return Ok(None)
+        }
+
+        Ok(self.entities.range(entity_key(id, tick + 1)..=entity_key(id, u32::MAX)).next().transpose()?.and_then(|(key, value)| stored_entity(&key, &value)))
+    }
+
+    /// stores the computor list of its epoch with the result of the arbitrator signature check
+    pub fn insert_computors(&self, computors: &Computors) -> sled::Result<()> {
+        let stored = StoredComputors { computors: *computors, verified: Some(computors.verify()) };
+        self.computors.insert(computors.epoch.to_be_bytes(), serde_json::to_vec(&stored).expect("Computors serialize"))?;
+
+        Ok(())
+    }
+
+    pub fn computors(&self, epoch: u16) -> sled::Result<Option<Computors>> {
+        Ok(self.stored_computors(epoch)?.map(|stored| stored.computors))
+    }
+
+    /// computor list of `epoch` as served, lists stored before the check was recorded are checked again
+    pub fn archived_computors(&self, epoch: u16) -> sled::Result<Option<ComputorInfos>> {
+        Ok(self.stored_computors(epoch)?.map(|StoredComputors { computors, verified }| ComputorInfos {
+            epoch: computors.epoch,
+            ids: computors.public_key.to_vec(),
+            signature: computors.signature,
+            verified: verified.unwrap_or_else(|| computors.verify())
+        }))
+    }
+
+    fn stored_computors(&self, epoch: u16) -> sled::Result<Option<StoredComputors>> {
+        Ok(self.computors.get(epoch.to_be_bytes())?.and_then(|computors| serde_json::from_slice(&computors).ok()))
+    }
+
+    /// statistics of the archived transactions of the epochs in `from..=to` in ascending order
+    pub fn epoch_stats(&self, from: u16, to: u16) -> sled::Result<Vec<EpochStats>> {
+        self.epoch_stats.range(from.to_be_bytes()..=to.to_be_bytes())
+            .map(|entry| {
+                let (epoch, counters) = entry?;
+                let counters: EpochCounters = serde_json::from_slice(&counters).unwrap_or_default();
+                let addresses = self.epoch_addresses.get(&epoch)?.map_or_else(HyperLogLog::new, |registers| HyperLogLog::from_bytes(&registers));
+
+                Ok(EpochStats {
+                    epoch: u16::from_be_bytes(epoch.as_ref().try_into().unwrap_or_default()),
+                    transactions: counters.transactions,
+                    transferred: counters.transferred,
+                    active_addresses: addresses.estimate(),
+                    qx_volume: counters.qx_volume
+                })
+            })
+            .collect()
+    }
+
+    /// amounts the archived transactions of all epochs sent to the zero identity
+    pub fn burned(&self) -> sled::Result<u64> {
+        self.epoch_stats.iter().values().try_fold(0u64, |burned, counters| {
+            let counters: EpochCounters = serde_json::from_slice(&counters?).unwrap_or_default();
+
+            Ok(burned.saturating_add(counters.burned))
+        })
+    }
+
+    /// epoch of `tick`, the last archived epoch starting at or before it
+    fn epoch_of(&self, tick: u32) -> sled::Result<Option<u16>> {
+        Ok(self.epochs()?.into_iter().take_while(|(_, first_tick)| *first_tick <= tick).last().map(|(epoch, _)| epoch))
+    }
+
+    /// archived epochs in ascending order with the first archived tick of each
+    pub fn epochs(&self) -> sled::Result<Vec<(u16, u32)>> {
+        self.epochs.iter()
+            .map(|entry| entry.map(|(epoch, tick)| (u16::from_be_bytes(epoch.as_ref().try_into().unwrap_or_default()), u32::from_be_bytes(tick.as_ref().try_into().unwrap_or_default()))))
+            .collect()
+    }
+}
+
+fn entity_key(id: &QubicId, tick: u32) -> Vec<u8> {
+    [id.0.as_slice(), &tick.to_be_bytes()].concat()
+}
+
+/// identities the transaction is indexed by in `identity_transactions`
+fn identities_of(raw: &RawTransaction) -> impl Iterator<Item = QubicId> {
+    let to = Some(raw.to).filter(|to| *to != raw.from);
+
+    [Some(raw.from), to].into_iter().flatten().filter(|id| *id != QubicId::default())
+}
+
+fn stored_entity(key: &[u8], value: &[u8]) -> Option<(u32, Entity)> {
+    Some((u32::from_be_bytes(key.get(32..)?.try_into().ok()?), serde_json::from_slice(value).ok()?))
+}
+
+/// inverted balance first so the rich list iterates by descending balance, equal balances by ascending identity
+fn rich_list_key(balance: u64, id: &QubicId) -> Vec<u8> {
+    [(u64::MAX - balance).to_be_bytes().as_slice(), &id.0].concat()
+}
+
+fn stored_u64(value: &[u8]) -> u64 {
+    u64::from_be_bytes(value.get(..8).and_then(|value| value.try_into().ok()).unwrap_or_default())
+}
+
+/// balance and tick of the `balances` tree
+fn stored_balance(value: &[u8]) -> (u64, u32) {
+    (stored_u64(value), value.get(8..12).and_then(|tick| tick.try_into().ok()).map(u32::from_be_bytes).unwrap_or_default())
+}
+
+impl ArchiverSink for SledSink {
+    fn name(&self) -> &str {
+        "sled"
+    }
+
+    async fn on_tick(&self, tick_data: &TickData) -> SinkResult {
+        self.ticks.insert(tick_data.tick.to_be_bytes(), serde_json::to_vec(tick_data)?)?;
+        // ticks arrive in ascending order, the first one of an epoch is kept
+        let _ = self.epochs.compare_and_swap(tick_data.epoch.to_be_bytes(), None as Option<&[u8]>, Some(&tick_data.tick.to_be_bytes()))?;
+
+        Ok(())
+    }
+
+    async fn on_transaction(&self, tx: &ArchivedTransaction) -> SinkResult {
+        let hash = QubicTxHash::from(&tx.transaction);
+        let (key, value) = ([tx.tick.to_be_bytes().as_slice(), &hash.0].concat(), serde_json::to_vec(tx)?);
+        let epoch = self.epoch_of(tx.tick)?;
+        let raw = &tx.transaction.raw_transaction;
+        // transactions which provably moved no funds are counted without their amount
+        let amount = if tx.money_flew == Some(false) { 0 } else { raw.amount };
+
+        let result: TransactionResult<(), Infallible> = (&self.transactions, &self.epoch_stats, &self.epoch_addresses, &self.identity_transactions).transaction(|(transactions, epoch_stats, epoch_addresses, identity_transactions)| {
+            if transactions.insert(key.as_slice(), value.as_slice())?.is_some() {
+                return Ok(())
+            }
+
+            for id in identities_of(raw) {
+                identity_transactions.insert([id.0.as_slice(), &key].concat(), &[])?;
+            }
+
+            let Some(epoch) = epoch.map(u16::to_be_bytes) else { return Ok(()) };
+
+            let mut counters: EpochCounters = epoch_stats.get(epoch)?.and_then(|counters| serde_json::from_slice(&counters).ok()).unwrap_or_default();
+            counters.transactions += 1;
+            counters.transferred += amount;
+            counters.qx_volume += if tx.transaction.kind() == TransactionKind::QxCall { amount } else { 0 };
+            counters.burned += if raw.to == QubicId::default() { amount } else { 0 };
+            epoch_stats.insert(&epoch, serde_json::to_vec(&counters).expect("EpochCounters serialize"))?;
+
+            let mut addresses = epoch_addresses.get(epoch)?.map_or_else(HyperLogLog::new, |registers| HyperLogLog::from_bytes(&registers));
+            let changed = [raw.from, raw.to].iter().filter(|id| **id != QubicId::default()).fold(false, |changed, id| addresses.insert(id) | changed);
+
+            if changed {
+                epoch_addresses.insert(&epoch, addresses.as_bytes())?;
+            }
+
+            Ok(())
+        });
+
+        result.map_err(|e| match e {
+            TransactionError::Storage(e) => e,
+            TransactionError::Abort(never) => match never {}
+        })?;
+
+        Ok(())
+    }
+
+    async fn on_tick_complete(&self, tick: u32) -> SinkResult {
+        self.meta.insert("cursor", &tick.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    async fn on_epoch_change(&self, epoch: u16) -> SinkResult {
+        self.meta.insert("epoch", &epoch.to_be_bytes())?;
+
+        Ok(())
+    }
+
+    async fn on_finalized(&self, tick: u32) -> SinkResult {
+        self.finalized.insert(tick.to_be_bytes(), &[])?;
+
+        Ok(())
+    }
+
+    async fn on_computors(&self, computors: &Computors) -> SinkResult {
+        Ok(self.insert_computors(computors)?)
+    }
+
+    async fn on_entity(&self, tick: u32, entity: &Entity) -> SinkResult {
+        Ok(self.insert_entity(tick, entity)?)
+    }
+}
+
+/// Sample sink writing one `tick,hash,from,to,amount,input_type,money_flew` line per transaction, `money_flew` is empty if unknown
+pub struct CsvSink {
+    writer: Mutex<BufWriter<File>>
+}
+
+impl CsvSink {
+    pub fn create(path: &str) -> std::io::Result<Self> {
+        let mut writer = BufWriter::new(File::create(path)?);
+        writeln!(writer, "tick,hash,from,to,amount,input_type,money_flew")?;
+
+        Ok(Self { writer: Mutex::new(writer) })
+    }
+}
+
+impl ArchiverSink for CsvSink {
+    fn name(&self) -> &str {
+        "csv"
+    }
+
+    /// the transactions of the previous tick are complete once the next one arrives
+    async fn on_tick(&self, _tick_data: &TickData) -> SinkResult {
+        self.writer.lock().unwrap().flush()?;
+
+        Ok(())
+    }
+
+    async fn on_transaction(&self, tx: &ArchivedTransaction) -> SinkResult {
+        let raw = tx.transaction.raw_transaction;
+        let money_flew = tx.money_flew.map(|flew| flew.to_string()).unwrap_or_default();
+        writeln!(self.writer.lock().unwrap(), "{},{},{},{},{},{},{money_flew}", tx.tick, QubicTxHash::from(&tx.transaction), raw.from, raw.to, raw.amount, raw.input_type)?;
+
+        Ok(())
+    }
+
+    async fn on_epoch_change(&self, _epoch: u16) -> SinkResult {
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn tick_data(epoch: u16, tick: u32) -> TickData {
+    use qubic_web3_rs::qubic_tcp_types::types::time::QubicTime;
+
+    TickData {
+        computor_index: 0,
+        epoch,
+        tick,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 25 },
+        time_lock: [0; 32],
+        transaction_digest: [QubicTxHash::default(); qubic_web3_rs::qubic_tcp_types::consts::NUMBER_OF_TRANSACTION_PER_TICK],
+        contract_fees: [0; qubic_web3_rs::qubic_tcp_types::consts::MAX_NUMBER_OF_CONTRACTS],
+        signature: Default::default()
+    }
+}
+
+/// summary of `agreeing` synthetic votes for the same digests of `tick` and one divergent vote
+#[cfg(test)]
+pub(crate) fn quorum_votes(tick: u32, agreeing: usize) -> QuorumSummary {
+    use qubic_types::H256;
+    use qubic_web3_rs::qubic_tcp_types::{consts::NUMBER_OF_COMPUTORS, types::{ticks::Tick, time::QubicTime}};
+
+    let vote = |computor_index: usize, transaction_digest| Tick {
+        computor_index: computor_index as u16,
+        epoch: 100,
+        tick,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 25 },
+        prev_resource_testing_digest: 0,
+        salted_resource_testing_digest: 0,
+        prev_spectrum_digest: H256::repeat_byte(1),
+        prev_universe_digest: H256::repeat_byte(2),
+        prev_computor_digest: H256::zero(),
+        salted_spectrum_digest: H256::zero(),
+        salted_universe_digest: H256::zero(),
+        salted_computor_digest: H256::zero(),
+        transaction_digest,
+        expected_next_tick_transaction_digest: H256::zero(),
+        signature: Default::default()
+    };
+    let votes = (0..agreeing).map(|index| vote(index, H256::repeat_byte(3)))
+        .chain([vote(NUMBER_OF_COMPUTORS - 1, H256::repeat_byte(4))])
+        .collect::<Vec<_>>();
+
+    QuorumSummary::from_votes(&votes)
+}
+
+#[cfg(test)]
+struct RecordingSink {
+    events: Arc<Mutex<Vec<String>>>,
+    delay: Duration
+}
+
+#[cfg(test)]
+impl ArchiverSink for RecordingSink {
+    fn name(&self) -> &str {
+        "recording"
+    }
+
+    async fn on_tick(&self, tick_data: &TickData) -> SinkResult {
+        tokio::time::sleep(self.delay).await;
+        self.events.lock().unwrap().push(format!("tick {}", tick_data.tick));
+
+        Ok(())
+    }
+
+    async fn on_transaction(&self, tx: &ArchivedTransaction) -> SinkResult {
+        let malformed = if tx.malformed { " malformed" } else { "" };
+        self.events.lock().unwrap().push(format!("tx {} {} {:?}{malformed}", tx.tick, tx.transaction.raw_transaction.amount, tx.money_flew));
+
+        Ok(())
+    }
+
+    async fn on_epoch_change(&self, epoch: u16) -> SinkResult {
+        self.events.lock().unwrap().push(format!("epoch {epoch}"));
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+struct FailingSink;
+
+#[cfg(test)]
+impl ArchiverSink for FailingSink {
+    fn name(&self) -> &str {
+        "failing"
+    }
+
+    async fn on_tick(&self, _tick_data: &TickData) -> SinkResult {
+        Err("tick rejected".into())
+    }
+
+    async fn on_transaction(&self, _tx: &ArchivedTransaction) -> SinkResult {
+        Err("transaction rejected".into())
+    }
+
+    async fn on_epoch_change(&self, _epoch: u16) -> SinkResult {
+        Err("epoch rejected".into())
+    }
+}
+
+#[tokio::test]
+async fn test_archiver_sinks() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::RawTransaction;
+
+    let tx = |amount| TransactionWithData::from(RawTransaction { amount, ..Default::default() });
+    let (fast, slow) = (Arc::new(Mutex::new(Vec::new())), Arc::new(Mutex::new(Vec::new())));
+
+    let mut archiver = Archiver::new(2)
+        .with_sink(FailingSink)
+        .with_sink(RecordingSink { events: fast.clone(), delay: Duration::ZERO })
+        .with_sink(RecordingSink { events: slow.clone(), delay: Duration::from_millis(20) });
+
+    let transfers = [QuTransferLog { from: QubicId::default(), to: QubicId::default(), amount: 3, transfer_id: None }];
+
+    archiver.ingest(tick_data(100, 1), vec![tx(1), tx(2)], None).await;
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+    archiver.ingest(tick_data(101, 3), vec![tx(3), tx(4)], Some(&transfers)).await;
+    archiver.shutdown().await;
+
+    let expected = ["epoch 100", "tick 1", "tx 1 1 None", "tx 1 2 None", "tick 2", "epoch 101", "tick 3", "tx 3 3 Some(true)", "tx 3 4 Some(false)"];
+
+    // the failing sink neither stalls nor reorders the others
+    assert_eq!(*fast.lock().unwrap(), expected);
+    assert_eq!(*slow.lock().unwrap(), expected);
+}
+
+#[cfg(test)]
+struct CompletingSink {
+    completed: Arc<Mutex<Vec<u32>>>,
+    failing_tick: u32
+}
+
+#[cfg(test)]
+impl ArchiverSink for CompletingSink {
+    fn name(&self) -> &str {
+        "completing"
+    }
+
+    async fn on_tick(&self, _tick_data: &TickData) -> SinkResult {
+        Ok(())
+    }
+
+    async fn on_transaction(&self, tx: &ArchivedTransaction) -> SinkResult {
+        if tx.tick == self.failing_tick {
+            return Err("transaction rejected".into())
+        }
+
+        Ok(())
+    }
+
+    async fn on_epoch_change(&self, _epoch: u16) -> SinkResult {
+        Ok(())
+    }
+
+    async fn on_tick_complete(&self, tick: u32) -> SinkResult {
+        self.completed.lock().unwrap().push(tick);
+
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn test_archiver_completes_ticks() {
+    let completed = Arc::new(Mutex::new(Vec::new()));
+    let mut archiver = Archiver::new(4).with_malformed(true).with_sink(CompletingSink { completed: completed.clone(), failing_tick: 3 });
+
+    for tick in 1..=4 {
+        archiver.ingest(tick_data(100, tick), vec![TransactionWithData::default()], None).await;
+    }
+    archiver.shutdown().await;
+
+    // ticks are completed after their transactions, a failed transaction stops the completion for good
+    assert_eq!(*completed.lock().unwrap(), [1, 2]);
+}
+
+#[tokio::test]
+async fn test_archiver_refreshes_moved_entities() {
+    use qubic_web3_rs::qubic_tcp_types::consts::SPECTRUM_DEPTH;
+
+    let transfer = |from: u8, to: u8, amount| TransactionWithData::from(RawTransaction { from: QubicId([from; 32]), to: QubicId([to; 32]), amount, ..Default::default() });
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+    let mut archiver = Archiver::new(4).with_sink(archive.clone());
+
+    let logged = [QuTransferLog { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 5, transfer_id: None }];
+    archiver.ingest(tick_data(100, 1), vec![transfer(1, 2, 5), transfer(3, 4, 0), transfer(5, 6, 7)], Some(&logged)).await;
+    archiver.ingest(tick_data(100, 2), vec![transfer(7, 0, 9)], None).await;
+
+    // transfers without an amount or provably without moving funds are left out, as is the zero identity
+    let mut moved = archiver.moved.iter().map(|id| id.0[0]).collect::<Vec<_>>();
+    moved.sort();
+    assert_eq!(moved, [1, 2, 7]);
+
+    archiver.observe_entity(RespondedEntity { entity: rich_entity(2, 5), tick: 3, spectrum_index: 0, siblings: [QubicId::default(); SPECTRUM_DEPTH] }).await;
+    archiver.shutdown().await;
+
+    assert_eq!(archive.rich_list_size().unwrap(), 1);
+    assert_eq!(archive.entity_at_or_before(&QubicId([2; 32]), 3).unwrap().map(|(tick, entity)| (tick, entity.balance())), Some((3, 5)));
+}
+
+#[tokio::test]
+async fn test_archiver_backpressure() {
+    let events = Arc::new(Mutex::new(Vec::new()));
+    let mut archiver = Archiver::new(1).with_sink(RecordingSink { events: events.clone(), delay: Duration::from_secs(60) });
+
+    archiver.ingest(tick_data(100, 1), vec![], None).await;
+
+    // the sink is stuck in the first tick, its queue fills up with the second one
+    let tx = TransactionWithData::default();
+    assert!(tokio::time::timeout(Duration::from_millis(200), archiver.ingest(tick_data(100, 2), vec![tx], None)).await.is_err());
+    assert_eq!(*events.lock().unwrap(), ["epoch 100"]);
+}
+
+#[tokio::test]
+async fn test_csv_sink() {
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::RawTransaction;
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-archive-{}.csv", std::process::id()));
+    let tx = TransactionWithData::from(RawTransaction { amount: 42, input_type: 1, ..Default::default() });
+
+    let mut archiver = Archiver::new(4).with_sink(CsvSink::create(path.to_str().unwrap()).unwrap());
+    archiver.ingest(tick_data(100, 1), vec![tx.clone()], None).await;
+    archiver.ingest(tick_data(100, 2), vec![tx.clone()], Some(&[])).await;
+    archiver.ingest(tick_data(100, 3), vec![], None).await;
+    archiver.shutdown().await;
+
+    let csv = std::fs::read_to_string(&path).unwrap();
+    std::fs::remove_file(path).unwrap();
+
+    let (hash, raw) = (QubicTxHash::from(tx.clone()), tx.raw_transaction);
+    assert_eq!(csv, format!("tick,hash,from,to,amount,input_type,money_flew\n1,{hash},{},{},42,1,\n2,{hash},{},{},42,1,false\n", raw.from, raw.to, raw.from, raw.to));
+}
+
+#[test]
+fn test_match_transfers() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::RawTransaction;
+
+    let tx = |from: u8, to: u8, amount| TransactionWithData::from(RawTransaction { from: QubicId([from; 32]), to: QubicId([to; 32]), amount, ..Default::default() });
+    let transfer = |from: u8, to: u8, amount| QuTransferLog { from: QubicId([from; 32]), to: QubicId([to; 32]), amount, transfer_id: None };
+
+    // source, destination and amount have to match
+    let transactions = [tx(1, 2, 100), tx(1, 3, 100), tx(2, 1, 100), tx(1, 2, 50)];
+    assert_eq!(match_transfers(&transactions, &[transfer(1, 2, 100), transfer(1, 2, 50)]), [true, false, false, true]);
+    assert_eq!(match_transfers(&transactions, &[]), [false; 4]);
+
+    // identical transactions claim the logged transfers in tick order, every transfer at most once
+    let transactions = [tx(1, 2, 100), tx(1, 2, 100), tx(3, 4, 5), tx(1, 2, 100)];
+    assert_eq!(match_transfers(&transactions, &[transfer(1, 2, 100), transfer(3, 4, 5), transfer(1, 2, 100)]), [true, true, true, false]);
+    assert_eq!(match_transfers(&transactions, &vec![transfer(1, 2, 100); 4]), [true, true, false, true]);
+    assert_eq!(match_transfers(&[], &[transfer(1, 2, 100)]), Vec::<bool>::new());
+}
+
+#[test]
+fn test_transfer_logs() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::types::qlogging::{LogHeader, LogMessages, QubicLog};
+
+    let transfer = |amount| QuTransferLog { from: QubicId([1; 32]), to: QubicId([2; 32]), amount, transfer_id: None };
+    let logs = |entries: &[(u32, u64)]| QubicLogs(entries.iter().map(|&(tick, amount)| QubicLog {
+        header: LogHeader { tick, ..Default::default() },
+        message: LogMessages::QuTransferLog(transfer(amount))
+    }).collect());
+
+    let mut transfer_logs = TransferLogs::default();
+
+    // the first response may lack logs requested by someone else before, ticks up to the current one stay unknown
+    transfer_logs.received(100, logs(&[(99, 1), (100, 2)]));
+    assert_eq!(transfer_logs.take(99), None);
+    assert_eq!(transfer_logs.take(100), None);
+
+    transfer_logs.received(103, logs(&[(101, 3), (102, 4), (102, 5)]));
+    assert_eq!(transfer_logs.take(101), Some(vec![transfer(3)]));
+    assert_eq!(transfer_logs.take(102), Some(vec![transfer(4), transfer(5)]));
+    // the current tick may still be executed
+    assert_eq!(transfer_logs.take(103), None);
+
+    // a tick without transfers is known to have none
+    transfer_logs.received(105, logs(&[]));
+    assert_eq!(transfer_logs.take(104), Some(vec![]));
+
+    // the logs emitted between the last successful request and the next one are lost
+    transfer_logs.failed();
+    transfer_logs.received(110, logs(&[(109, 6)]));
+    assert_eq!(transfer_logs.take(109), None);
+
+    transfer_logs.received(112, logs(&[(111, 7)]));
+    assert_eq!(transfer_logs.take(111), Some(vec![transfer(7)]));
+}
+
+#[tokio::test]
+async fn test_sled_sink_money_flew() {
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::RawTransaction;
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-archive-{}.sled", std::process::id()));
+    let tx = TransactionWithData::from(RawTransaction { amount: 42, ..Default::default() });
+
+    let mut archiver = Archiver::new(4).with_sink(SledSink::open(path.to_str().unwrap()).unwrap());
+    archiver.ingest(tick_data(100, 1), vec![tx.clone()], None).await;
+    archiver.ingest(tick_data(100, 2), vec![tx.clone()], Some(&[QuTransferLog { from: tx.raw_transaction.from, to: tx.raw_transaction.to, amount: 42, transfer_id: None }])).await;
+    archiver.shutdown().await;
+
+    // the flusher thread of the dropped sink releases the database lock asynchronously
+    let db = (0..50).find_map(|_| sled::open(&path).ok().or_else(|| { std::thread::sleep(std::time::Duration::from_millis(20)); None })).unwrap();
+
+    let records = db.open_tree("transactions").unwrap().iter().values()
+        .map(|record| serde_json::from_slice::<serde_json::Value>(&record.unwrap()).unwrap())
+        .collect::<Vec<_>>();
+    drop(db);
+    std::fs::remove_dir_all(path).unwrap();
+
+    assert_eq!(records.iter().map(|record| (record["tick"].clone(), record["moneyFlew"].clone())).collect::<Vec<_>>(), [
+        (serde_json::json!(1), serde_json::Value::Null),
+        (serde_json::json!(2), serde_json::json!(true))
+    ]);
+}
+
+#[tokio::test]
+async fn test_finality() {
+    use qubic_web3_rs::qubic_tcp_types::consts::QUORUM;
+
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+    let mut archiver = Archiver::new(4).with_sink(archive.clone());
+
+    archiver.ingest(tick_data(100, 1), vec![], None).await;
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+    assert_eq!(archiver.finality.pending(), [1, 2]);
+
+    // votes arrive until the quorum is reached, a divergent vote never counts
+    for agreeing in [0, 200, QUORUM - 1] {
+        archiver.observe_votes(2, &quorum_votes(2, agreeing)).await;
+    }
+    archiver.observe_votes(2, &quorum_votes(2, QUORUM)).await;
+    // finalized ticks are not checked again
+    archiver.observe_votes(2, &quorum_votes(2, QUORUM + 10)).await;
+    assert_eq!(archiver.finality.pending(), [1]);
+
+    // ticks which never reach quorum are given up
+    for tick in 3..=(MAX_PENDING_FINALITY as u32 + 2) {
+        archiver.ingest(tick_data(100, tick), vec![], None).await;
+    }
+    assert!(!archiver.finality.pending().contains(&1));
+    archiver.observe_votes(1, &quorum_votes(1, QUORUM)).await;
+    archiver.shutdown().await;
+
+    assert_eq!((archive.is_finalized(1).unwrap(), archive.is_finalized(2).unwrap(), archive.is_finalized(3).unwrap()), (false, true, false));
+    assert_eq!(archive.latest_finalized().unwrap().map(|tick_data| tick_data.tick), Some(2));
+}
+
+#[tokio::test]
+async fn test_epoch_stats() {
+    use qubic_web3_rs::qubic_tcp_types::types::{assets::QXID, transactions::RawTransaction};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-epoch-stats-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+    let tx = |from: u8, to: QubicId, amount| TransactionWithData::from(RawTransaction { from: QubicId([from; 32]), to, amount, ..Default::default() });
+    let ticks = [
+        (100, 1, vec![tx(1, QubicId([2; 32]), 10), tx(2, QXID, 5)]),
+        (100, 2, vec![tx(1, QubicId([3; 32]), 7)]),
+        (101, 3, vec![tx(4, QubicId([1; 32]), 20), tx(4, QubicId([1; 32]), 1)])
+    ];
+
+    // the second pass archives the same ticks again, as after a restart from an earlier cursor
+    for _ in 0..2 {
+        let mut archiver = Archiver::new(16).with_sink(archive.clone());
+        for (epoch, tick, transactions) in ticks.iter().cloned() {
+            let transfers = (tick == 3).then_some([QuTransferLog { from: QubicId([4; 32]), to: QubicId([1; 32]), amount: 20, transfer_id: None }]);
+            archiver.ingest(tick_data(epoch, tick), transactions, transfers.as_ref().map(|transfers| transfers.as_slice())).await;
+        }
+        archiver.shutdown().await;
+
+        assert_eq!(archive.epoch_stats(99, 102).unwrap(), [
+            EpochStats { epoch: 100, transactions: 3, transferred: 22, active_addresses: 4, qx_volume: 5 },
+            EpochStats { epoch: 101, transactions: 2, transferred: 20, active_addresses: 2, qx_volume: 0 }
+        ]);
+    }
+
+    let single = archive.epoch_stats(101, 101).unwrap();
+    let none = archive.epoch_stats(102, 200).unwrap();
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+
+    assert_eq!(single.len(), 1);
+    assert!(none.is_empty());
+}
+
+#[cfg(test)]
+pub(crate) fn rich_entity(id: u8, balance: u64) -> Entity {
+    Entity { public_key: QubicId([id; 32]), incoming_amount: balance, outgoing_amount: 0, number_of_incoming_transfers: 0, number_of_outgoing_transfers: 0, latest_incoming_transfer_tick: 0, latest_outgoing_transfer_tick: 0 }
+}
+
+#[test]
+fn test_rich_list() {
+    let path = std::env::temp_dir().join(format!("qubic-rpc-rich-list-{}.sled", std::process::id()));
+    let db = sled::open(&path).unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+
+    archive.insert_entity(5, &rich_entity(3, 50)).unwrap();
+    archive.insert_entity(5, &rich_entity(1, 50)).unwrap();
+    archive.insert_entity(5, &rich_entity(2, 70)).unwrap();
+    archive.insert_entity(9, &rich_entity(4, 10)).unwrap();
+    // newer entity moves the identity, an older one is only archived
+    archive.insert_entity(8, &rich_entity(4, 90)).unwrap();
+    archive.insert_entity(7, &rich_entity(2, 20)).unwrap();
+    archive.insert_entity(6, &rich_entity(1, 0)).unwrap();
+
+    let page = |archive: &SledSink, after: Option<&QubicId>, limit| archive.rich_list(after).unwrap().map(|entries| entries.take(limit).collect::<sled::Result<Vec<_>>>().unwrap());
+    let ranked = |entries: Option<Vec<RichListEntry>>| entries.unwrap().iter().map(|entry| (entry.identity.0[0], entry.balance, entry.tick)).collect::<Vec<_>>();
+
+    assert_eq!(archive.rich_list_size().unwrap(), 4);
+    assert_eq!(ranked(page(&archive, None, 10)), [(3, 50, 5), (2, 20, 7), (4, 10, 9), (1, 0, 6)]);
+    assert_eq!(ranked(page(&archive, Some(&QubicId([3; 32])), 2)), [(2, 20, 7), (4, 10, 9)]);
+    assert_eq!(ranked(page(&archive, Some(&QubicId([1; 32])), 2)), []);
+    assert_eq!(page(&archive, Some(&QubicId([7; 32])), 2), None);
+    assert_eq!(archive.entity_at_or_before(&QubicId([4; 32]), 8).unwrap().map(|(tick, entity)| (tick, entity.balance())), Some((8, 90)));
+
+    // equal balances are ordered by identity
+    archive.insert_entity(10, &rich_entity(4, 50)).unwrap();
+    assert_eq!(ranked(page(&archive, None, 3)), [(3, 50, 5), (4, 50, 10), (2, 20, 7)]);
+
+    // archives without the index are reindexed when opened
+    db.open_tree("balances").unwrap().clear().unwrap();
+    db.open_tree("rich_list").unwrap().clear().unwrap();
+    db.open_tree("meta").unwrap().remove("rich_list_size").unwrap();
+
+    let reopened = SledSink::from_db(&db).unwrap();
+    assert_eq!(reopened.rich_list_size().unwrap(), 4);
+    assert_eq!(ranked(page(&reopened, None, 10)), [(3, 50, 5), (4, 50, 10), (2, 20, 7), (1, 0, 6)]);
+
+    drop((archive, reopened, db));
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_identity_transactions_reindex() {
+    let path = std::env::temp_dir().join(format!("qubic-rpc-identity-transactions-{}.sled", std::process::id()));
+    let db = sled::open(&path).unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+
+    let tx = |from: u8, to: u8, amount| TransactionWithData::from(RawTransaction { from: QubicId([from; 32]), to: QubicId([to; 32]), amount, ..Default::default() });
+    let mut archiver = Archiver::new(4).with_sink(archive.clone());
+    archiver.ingest(tick_data(100, 1), vec![tx(1, 2, 5), tx(2, 0, 7)], None).await;
+    archiver.ingest(tick_data(100, 2), vec![tx(1, 1, 0)], None).await;
+    archiver.shutdown().await;
+
+    let listed = |archive: &SledSink, id: u8| {
+        let (total, transactions) = archive.identity_transactions(&QubicId([id; 32]), 0, u32::MAX, 0, 10).unwrap();
+        (total, transactions.iter().map(|tx| tx.tick).collect::<Vec<_>>())
+    };
+
+    // transactions to itself are indexed once, the zero identity is not indexed
+    assert_eq!(listed(&archive, 1), (2, vec![1, 2]));
+    assert_eq!(listed(&archive, 2), (2, vec![1, 1]));
+    assert_eq!(listed(&archive, 0), (0, vec![]));
+
+    // archives without the index are reindexed when opened
+    db.open_tree("identity_transactions").unwrap().clear().unwrap();
+    db.open_tree("meta").unwrap().remove("identity_transactions_indexed").unwrap();
+
+    let reopened = SledSink::from_db(&db).unwrap();
+    assert_eq!(listed(&reopened, 1), (2, vec![1, 2]));
+    assert_eq!(listed(&reopened, 2), (2, vec![1, 1]));
+
+    drop((archive, reopened, db));
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+/// run with `cargo test --release -- --ignored bench_rich_list`, pages deep in a million identities are as fast as
+/// the first one
+#[test]
+#[ignore]
+fn bench_rich_list() {
+    use std::time::Instant;
+
+    const IDENTITIES: u32 = 1_000_000;
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-rich-list-bench-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+
+    let started = Instant::now();
+    for i in 0..IDENTITIES {
+        let mut entity = rich_entity(0, (i as u64 * 7919) % 100_000);
+        entity.public_key.0[..4].copy_from_slice(&i.to_be_bytes());
+        archive.insert_entity(1, &entity).unwrap();
+    }
+    println!("inserted {IDENTITIES} identities in {:?}", started.elapsed());
+
+    let mut after = None;
+    let mut pages = Vec::new();
+    let started = Instant::now();
+
+    loop {
+        let page_started = Instant::now();
+        let total = archive.rich_list_size().unwrap();
+        let entries = archive.rich_list(after.as_ref()).unwrap().unwrap().take(1000).collect::<sled::Result<Vec<_>>>().unwrap();
+        pages.push(page_started.elapsed());
+
+        assert_eq!(total, IDENTITIES as u64);
+        match entries.last() {
+            Some(last) => after = Some(last.identity),
+            None => break
+        }
+    }
+
+    let first = pages[..10].iter().sum::<Duration>() / 10;
+    let last = pages[pages.len() - 11..pages.len() - 1].iter().sum::<Duration>() / 10;
+    println!("paged through in {:?}, first pages {first:?}, last pages {last:?}, slowest {:?}", started.elapsed(), pages.iter().max().unwrap());
+
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+
+    assert_eq!(pages.len(), IDENTITIES as usize / 1000 + 1);
+    assert!(last < first * 4 + Duration::from_millis(5), "deep pages are slower than the first ones");
+}
+
+#[tokio::test]
+async fn test_archiver_malformed() {
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::RawTransaction;
+
+    let tx = |amount, input_size| TransactionWithData::from(RawTransaction { amount, input_size, ..Default::default() });
+    let transfers = [QuTransferLog { from: Default::default(), to: Default::default(), amount: 1, transfer_id: None }];
+
+    for (keep, expected) in [
+        (false, vec!["epoch 100", "tick 1", "tx 1 2 Some(false)"]),
+        (true, vec!["epoch 100", "tick 1", "tx 1 1 Some(true) malformed", "tx 1 2 Some(false)"])
+    ] {
+        let events = Arc::new(Mutex::new(Vec::new()));
+        let mut archiver = Archiver::new(4).with_malformed(keep).with_sink(RecordingSink { events: events.clone(), delay: Duration::ZERO });
+
+        // declares 8 bytes of input without carrying any
+        archiver.ingest(tick_data(100, 1), vec![tx(1, 8), tx(2, 0)], Some(&transfers)).await;
+        archiver.shutdown().await;
+
+        assert_eq!(*events.lock().unwrap(), expected);
+    }
+}
+
+#[tokio::test]
+async fn test_archiver_signatures() {
+    use qubic_types::{traits::Sign, QubicWallet};
+
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+
+    let mut signed = TransactionWithData::from(RawTransaction { from: wallet.public_key, to: QubicId([2; 32]), amount: 5, tick: 1, ..Default::default() });
+    signed.sign(&wallet).unwrap();
+    let mut forged = signed.clone();
+    forged.raw_transaction.amount = 6;
+
+    // invalid signatures are archived and flagged, the node would not have included them
+    let mut archiver = Archiver::new(4).with_sink(archive.clone());
+    archiver.ingest(tick_data(100, 1), vec![signed.clone(), forged.clone()], None).await;
+    archiver.shutdown().await;
+
+    let signature_valid = |tx: &TransactionWithData| archive.transaction(1, &QubicTxHash::from(tx)).unwrap().unwrap().signature_valid;
+    assert_eq!(signature_valid(&signed), Some(true));
+    assert_eq!(signature_valid(&forged), Some(false));
+}
+
+#[test]
+fn test_archived_computors() {
+    use qubic_types::Signature;
+    use qubic_web3_rs::qubic_tcp_types::consts::NUMBER_OF_COMPUTORS;
+
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let archive = SledSink::from_db(&db).unwrap();
+    let computors = |epoch| Computors { epoch, public_key: [QubicId([1; 32]); NUMBER_OF_COMPUTORS], signature: Signature([2; 64]) };
+
+    archive.insert_computors(&computors(100)).unwrap();
+    // lists stored before the signature check was recorded are still read
+    db.open_tree("computors").unwrap().insert(99u16.to_be_bytes(), serde_json::to_vec(&computors(99)).unwrap()).unwrap();
+
+    for epoch in [99, 100] {
+        assert_eq!(archive.computors(epoch).unwrap(), Some(computors(epoch)));
+
+        let archived = archive.archived_computors(epoch).unwrap().unwrap();
+        assert_eq!((archived.epoch, archived.ids.len(), archived.signature, archived.verified), (epoch, NUMBER_OF_COMPUTORS, Signature([2; 64]), false));
+    }
+
+    assert!(archive.archived_computors(101).unwrap().is_none());
+}
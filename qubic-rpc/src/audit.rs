@@ -0,0 +1,284 @@
+//! Append-only audit log of the transactions and mining solutions the server relayed
+//!
+//! Records are keyed by their sequence number and chained by hash, every record holds the hash of the previous one.
+//! The sequence number and hash of the last record are kept apart from the records, so altering a record or removing
+//! records anywhere (including the end) is detected by `AuditLog::verify`. An operation is only answered as successful
+//! after its record was flushed to disk.
+
+use std::{convert::Infallible, fmt::Display, net::IpAddr, sync::Mutex, time::{SystemTime, UNIX_EPOCH}};
+
+use qubic_rpc_types::{AuditOperation, AuditRecord, SubmittedWork};
+use qubic_types::{amount::NumberFormat, QubicId, QubicTxHash};
+use qubic_web3_rs::qubic_tcp_types::types::transactions::TransactionWithData;
+use sha2::{Digest, Sha256};
+use sled::{transaction::{TransactionError, TransactionResult}, Transactional};
+
+/// Records answered per request of the audit log
+pub const MAX_AUDIT_RECORDS: usize = 10_000;
+
+const HEAD_KEY: &[u8] = b"head";
+
+/// Operation to record, the log adds its sequence number, timestamp and hashes
+#[derive(Debug, Clone)]
+pub struct AuditEntry {
+    pub operation: AuditOperation,
+    pub client_ip: Option<IpAddr>,
+    pub tx_id: Option<QubicTxHash>,
+    pub source: QubicId,
+    pub destination: Option<QubicId>,
+    pub amount: Option<u64>,
+    pub tick: u32,
+    pub upstream_peer: String
+}
+
+impl AuditEntry {
+    /// transaction broadcast through `upstream_peer` for the client at `client_ip`
+    pub fn transaction(tx: &TransactionWithData, client_ip: Option<IpAddr>, upstream_peer: String) -> Self {
+        Self {
+            operation: AuditOperation::SendTransaction,
+            client_ip,
+            tx_id: Some(QubicTxHash::from(tx)),
+            source: tx.raw_transaction.from,
+            destination: Some(tx.raw_transaction.to),
+            amount: Some(tx.raw_transaction.amount),
+            tick: tx.raw_transaction.tick,
+            upstream_peer
+        }
+    }
+
+    /// mining solution relayed to `upstream_peer` for the client at `client_ip`
+    pub fn work(work: &SubmittedWork, client_ip: Option<IpAddr>, upstream_peer: String) -> Self {
+        Self { operation: AuditOperation::SubmitWork, client_ip, tx_id: None, source: work.identity, destination: None, amount: None, tick: work.tick, upstream_peer }
+    }
+}
+
+#[derive(Debug)]
+pub enum AuditError {
+    Db(sled::Error),
+    Malformed { sequence: u64 },
+    Missing { sequence: u64 },
+    Altered { sequence: u64 },
+    ChainBroken { sequence: u64 },
+    Truncated { head: Option<u64>, last: Option<u64> }
+}
+
+impl Display for AuditError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Db(e) => write!(f, "Audit database failed: {e}"),
+            Self::Malformed { sequence } => write!(f, "Audit record {sequence} is malformed"),
+            Self::Missing { sequence } => write!(f, "Audit record {sequence} is missing"),
+            Self::Altered { sequence } => write!(f, "Audit record {sequence} does not match its hash"),
+            Self::ChainBroken { sequence } => write!(f, "Audit record {sequence} does not chain to the previous record"),
+            Self::Truncated { head, last } => write!(f, "Audit log ends at record {last:?} but its head is record {head:?}")
+        }
+    }
+}
+
+impl From<sled::Error> for AuditError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+/// Audit records persisted in sled
+pub struct AuditLog {
+    records: sled::Tree,
+    head: sled::Tree,
+    /// sequence number and hash of the last record, appends are serialized by the lock
+    last: Mutex<Option<(u64, [u8; 32])>>
+}
+
+impl AuditLog {
+    pub fn open(path: &str) -> sled::Result<Self> {
+        Self::from_db(&sled::open(path)?)
+    }
+
+    fn from_db(db: &sled::Db) -> sled::Result<Self> {
+        let head = db.open_tree("audit_head")?;
+        let last = head.get(HEAD_KEY)?.as_deref().and_then(decode_head);
+
+        Ok(Self { records: db.open_tree("audit")?, head, last: Mutex::new(last) })
+    }
+
+    /// chains the entry to the last record and waits until it is flushed to disk
+    pub async fn append(&self, entry: AuditEntry) -> sled::Result<AuditRecord> {
+        let record = {
+            let mut last = self.last.lock().unwrap();
+            let (sequence, prev_hash) = match *last {
+                Some((sequence, hash)) => (sequence + 1, hash),
+                None => (0, [0; 32])
+            };
+
+            let mut record = AuditRecord {
+                sequence,
+                timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+                operation: entry.operation,
+                client_ip: entry.client_ip.map(|ip| ip.to_string()),
+                tx_id: entry.tx_id,
+                source: entry.source,
+                destination: entry.destination,
+                amount: entry.amount,
+                tick: entry.tick,
+                upstream_peer: entry.upstream_peer,
+                prev_hash: hex::encode(prev_hash),
+                hash: String::new()
+            };
+
+            let hash = record_hash(&record);
+            record.hash = hex::encode(hash);
+
+            let value = serde_json::to_vec(&record).expect("AuditRecord serializes");
+            let head = [sequence.to_be_bytes().as_slice(), &hash].concat();
+
+            let result: TransactionResult<(), Infallible> = (&self.records, &self.head).transaction(|(records, head_tree)| {
+                records.insert(&sequence.to_be_bytes(), value.as_slice())?;
+                head_tree.insert(HEAD_KEY, head.as_slice())?;
+
+                Ok(())
+            });
+
+            result.map_err(|e| match e {
+                TransactionError::Storage(e) => e,
+                TransactionError::Abort(never) => match never {}
+            })?;
+
+            *last = Some((sequence, hash));
+            record
+        };
+
+        self.records.flush_async().await?;
+
+        Ok(record)
+    }
+
+    /// records with a timestamp in `from..=to` (unix milliseconds), at most `MAX_AUDIT_RECORDS` starting with the oldest
+    pub fn records(&self, from: u64, to: u64) -> sled::Result<Vec<AuditRecord>> {
+        let mut records = Vec::new();
+
+        for entry in self.records.iter() {
+            let (_, value) = entry?;
+            let Ok(record) = serde_json::from_slice::<AuditRecord>(&value) else { continue };
+
+            if (from..=to).contains(&record.timestamp) {
+                records.push(record);
+            }
+
+            if records.len() == MAX_AUDIT_RECORDS {
+                break
+            }
+        }
+
+        Ok(records)
+    }
+
+    /// validates the hash chain from the first record to the head, returns the number of records
+    pub fn verify(&self) -> Result<u64, AuditError> {
+        let mut last: Option<(u64, [u8; 32])> = None;
+
+        for entry in self.records.iter() {
+            let (key, value) = entry?;
+            let expected = last.map_or(0, |(sequence, _)| sequence + 1);
+            let sequence = key.as_ref().try_into().map(u64::from_be_bytes).map_err(|_| AuditError::Malformed { sequence: expected })?;
+
+            if sequence != expected {
+                return Err(AuditError::Missing { sequence: expected })
+            }
+
+            let record = serde_json::from_slice::<AuditRecord>(&value).map_err(|_| AuditError::Malformed { sequence })?;
+            let hash = record_hash(&record);
+
+            if record.sequence != sequence || record.hash != hex::encode(hash) {
+                return Err(AuditError::Altered { sequence })
+            }
+
+            if record.prev_hash != hex::encode(last.map_or([0; 32], |(_, hash)| hash)) {
+                return Err(AuditError::ChainBroken { sequence })
+            }
+
+            last = Some((sequence, hash));
+        }
+
+        let head = self.head.get(HEAD_KEY)?.as_deref().and_then(decode_head);
+
+        if head != last {
+            return Err(AuditError::Truncated { head: head.map(|(sequence, _)| sequence), last: last.map(|(sequence, _)| sequence) })
+        }
+
+        Ok(last.map_or(0, |(sequence, _)| sequence + 1))
+    }
+}
+
+/// SHA-256 of the record serialized with an empty `hash`, amounts are hashed as numbers whatever format the request
+/// appending the record asked for
+fn record_hash(record: &AuditRecord) -> [u8; 32] {
+    let record = AuditRecord { hash: String::new(), ..record.clone() };
+
+    Sha256::digest(NumberFormat::Number.scope(|| serde_json::to_vec(&record)).expect("AuditRecord serializes")).into()
+}
+
+fn decode_head(value: &[u8]) -> Option<(u64, [u8; 32])> {
+    let (sequence, hash) = value.split_first_chunk::<8>()?;
+
+    Some((u64::from_be_bytes(*sequence), hash.try_into().ok()?))
+}
+
+#[tokio::test]
+async fn test_verify_audit_log() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let log = AuditLog::from_db(&db).unwrap();
+
+    let entry = |amount| AuditEntry {
+        operation: AuditOperation::SendTransaction,
+        client_ip: Some(IpAddr::from([127, 0, 0, 1])),
+        tx_id: Some(QubicTxHash([amount as u8; 32])),
+        source: QubicId([1; 32]),
+        destination: Some(QubicId([2; 32])),
+        amount: Some(amount),
+        tick: 100,
+        upstream_peer: "127.0.0.1:21841".to_owned()
+    };
+
+    assert_eq!(log.verify().unwrap(), 0);
+
+    for amount in 1..=4 {
+        let record = log.append(entry(amount)).await.unwrap();
+        assert_eq!(record.sequence, amount - 1);
+    }
+
+    assert_eq!(log.verify().unwrap(), 4);
+    assert_eq!(log.records(0, u64::MAX).unwrap().len(), 4);
+    assert!(log.records(0, 1).unwrap().is_empty());
+
+    // the head survives reopening, appends continue the chain
+    let log = AuditLog::from_db(&db).unwrap();
+    assert_eq!(log.append(entry(5)).await.unwrap().sequence, 4);
+    assert_eq!(log.verify().unwrap(), 5);
+
+    let key = |sequence: u64| sequence.to_be_bytes();
+    let original = log.records.get(key(1)).unwrap().unwrap();
+    let mut record: AuditRecord = serde_json::from_slice(&original).unwrap();
+
+    // an altered amount no longer matches the hash
+    record.amount = Some(1_000_000);
+    log.records.insert(key(1), serde_json::to_vec(&record).unwrap()).unwrap();
+    assert!(matches!(log.verify(), Err(AuditError::Altered { sequence: 1 })));
+
+    // rehashing the altered record breaks the link of the next one
+    record.hash = hex::encode(record_hash(&record));
+    log.records.insert(key(1), serde_json::to_vec(&record).unwrap()).unwrap();
+    assert!(matches!(log.verify(), Err(AuditError::ChainBroken { sequence: 2 })));
+
+    log.records.insert(key(1), original.clone()).unwrap();
+    assert_eq!(log.verify().unwrap(), 5);
+
+    // removed records are detected at the start, in the middle and at the end
+    for sequence in [0, 2, 4] {
+        let removed = log.records.remove(key(sequence)).unwrap().unwrap();
+        assert!(log.verify().is_err(), "removal of record {sequence} was not detected");
+        log.records.insert(key(sequence), removed).unwrap();
+    }
+
+    log.records.remove(key(4)).unwrap();
+    assert!(matches!(log.verify(), Err(AuditError::Truncated { head: Some(4), last: Some(3) })));
+}
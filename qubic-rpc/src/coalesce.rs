@@ -0,0 +1,157 @@
+use std::{collections::HashMap, future::Future, sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
+
+use qubic_rpc_types::{CoalescingMetrics, RequestMethods};
+use tokio::sync::OnceCell;
+
+/// How a coalesced request was answered
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Served {
+    Upstream,
+    /// by the upstream call of an identical request in flight
+    Coalesced,
+    Cached
+}
+
+/// result of the upstream call once answered, with the time it was answered at
+type Entry<V> = Arc<OnceCell<(V, Instant)>>;
+
+/// Single-flight coalescing of identical read requests: concurrent requests with the same key await one
+/// upstream call and share its result, which is cached for `ttl` afterwards
+pub struct Coalescer<V> {
+    ttl: Duration,
+    entries: Mutex<HashMap<String, Entry<V>>>,
+    upstream_calls: AtomicU64,
+    coalesced: AtomicU64,
+    cache_hits: AtomicU64
+}
+
+impl<V: Clone> Coalescer<V> {
+    /// a `ttl` of zero only coalesces requests in flight
+    pub fn new(ttl: Duration) -> Self {
+        Self {
+            ttl,
+            entries: Mutex::new(HashMap::new()),
+            upstream_calls: AtomicU64::new(0),
+            coalesced: AtomicU64::new(0),
+            cache_hits: AtomicU64::new(0)
+        }
+    }
+
+    /// awaits `fetch` unless a request with the same key is in flight or was answered less than `ttl` ago.
+    /// Results rejected by `cache` are shared with the requests in flight but not cached
+    pub async fn get<F: Future<Output = V>>(&self, key: String, fetch: F, cache: impl Fn(&V) -> bool) -> (V, Served) {
+        let cell = {
+            let mut entries = self.entries.lock().unwrap();
+            entries.retain(|_, cell| cell.get().is_none_or(|(_, answered)| answered.elapsed() < self.ttl));
+
+            entries.entry(key.clone()).or_default().clone()
+        };
+
+        if let Some((value, _)) = cell.get() {
+            self.cache_hits.fetch_add(1, Ordering::Relaxed);
+            return (value.clone(), Served::Cached)
+        }
+
+        let mut fetched = false;
+        let (value, _) = cell.get_or_init(|| async {
+            fetched = true;
+            (fetch.await, Instant::now())
+        }).await;
+
+        if !fetched {
+            self.coalesced.fetch_add(1, Ordering::Relaxed);
+            return (value.clone(), Served::Coalesced)
+        }
+
+        self.upstream_calls.fetch_add(1, Ordering::Relaxed);
+
+        if !cache(value) {
+            let mut entries = self.entries.lock().unwrap();
+
+            if entries.get(&key).is_some_and(|entry| Arc::ptr_eq(entry, &cell)) {
+                entries.remove(&key);
+            }
+        }
+
+        (value.clone(), Served::Upstream)
+    }
+
+    pub fn metrics(&self) -> CoalescingMetrics {
+        CoalescingMetrics {
+            upstream_calls: self.upstream_calls.load(Ordering::Relaxed),
+            coalesced: self.coalesced.load(Ordering::Relaxed),
+            cache_hits: self.cache_hits.load(Ordering::Relaxed),
+            peer_redirects: 0,
+            upstream_scheduler: Default::default()
+        }
+    }
+}
+
+/// key of a coalescable request, `None` for mutations and methods served by the server itself
+pub fn request_key(request: &RequestMethods) -> Option<String> {
+    match request {
+        RequestMethods::RequestCurrentTickInfo
+        | RequestMethods::RequestEntity(_)
+        | RequestMethods::RequestComputors
+        | RequestMethods::RequestTickTransactions(_)
+        | RequestMethods::FindAsset(_)
+        | RequestMethods::RequestQuorumVotes(_) => serde_json::to_string(request).ok(),
+        RequestMethods::SendTransaction(_)
+        | RequestMethods::RequestSubmitWork(_)
+        | RequestMethods::WaitForNextTick { .. }
+        | RequestMethods::GetNetworkStatsHistory { .. }
+        | RequestMethods::GetNetworkStatsLatest => None
+    }
+}
+
+#[tokio::test]
+async fn test_coalescing() {
+    let calls = Arc::new(AtomicU64::new(0));
+    let coalescer = Coalescer::new(Duration::from_millis(300));
+
+    let fetch = |value: u32| {
+        let calls = calls.clone();
+
+        async move {
+            calls.fetch_add(1, Ordering::SeqCst);
+            tokio::time::sleep(Duration::from_millis(50)).await;
+            value
+        }
+    };
+
+    let requests = (0..50).map(|i| coalescer.get("tick".into(), fetch(i), |_| true));
+    let results = futures::future::join_all(requests).await;
+
+    // every request got the result of the single upstream call
+    assert_eq!(calls.load(Ordering::SeqCst), 1);
+    assert!(results.iter().all(|(value, _)| *value == 0));
+    assert_eq!(results.iter().filter(|(_, served)| *served == Served::Upstream).count(), 1);
+    assert_eq!(coalescer.metrics(), CoalescingMetrics { upstream_calls: 1, coalesced: 49, cache_hits: 0, peer_redirects: 0, ..Default::default() });
+
+    // repeated within the ttl, other keys are fetched on their own
+    assert_eq!(coalescer.get("tick".into(), fetch(1), |_| true).await, (0, Served::Cached));
+    assert_eq!(coalescer.get("entity".into(), fetch(2), |_| true).await, (2, Served::Upstream));
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    assert_eq!(coalescer.get("tick".into(), fetch(3), |_| true).await, (3, Served::Upstream));
+
+    // rejected results are not cached
+    assert_eq!(coalescer.get("failing".into(), fetch(4), |_| false).await, (4, Served::Upstream));
+    assert_eq!(coalescer.get("failing".into(), fetch(5), |_| false).await, (5, Served::Upstream));
+
+    assert_eq!(calls.load(Ordering::SeqCst), 5);
+    assert_eq!(coalescer.metrics(), CoalescingMetrics { upstream_calls: 5, coalesced: 49, cache_hits: 1, peer_redirects: 0, ..Default::default() });
+}
+
+#[test]
+fn test_request_key() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::Transaction;
+
+    assert_eq!(request_key(&RequestMethods::RequestEntity(QubicId([1; 32]))), request_key(&RequestMethods::RequestEntity(QubicId([1; 32]))));
+    assert_ne!(request_key(&RequestMethods::RequestEntity(QubicId([1; 32]))), request_key(&RequestMethods::RequestEntity(QubicId([2; 32]))));
+    assert_ne!(request_key(&RequestMethods::RequestTickTransactions(1)), request_key(&RequestMethods::RequestQuorumVotes(1)));
+
+    // mutations are never coalesced
+    assert_eq!(request_key(&RequestMethods::SendTransaction(Transaction::default().into())), None);
+}
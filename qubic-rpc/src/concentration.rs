@@ -0,0 +1,155 @@
+//! Concentration of the QU over the rich list served at `/v1/rich-list/stats`
+//!
+//! Ranking millions of balances is too slow for a request, the stats are computed in the background every
+//! `--rich-list-stats-interval` seconds once a later tick is archived and requests are answered from the cache. Every
+//! computation reads a snapshot of the balances and replaces the cached stats as a whole, requests never see the stats
+//! of an update in progress.
+
+use std::{sync::{Arc, Mutex}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use qubic_rpc_types::RichListStats;
+
+use crate::archiver::SledSink;
+
+/// Latest stats of the rich list, `None` until they were computed once
+#[derive(Clone, Default)]
+pub struct RichListStatsCache {
+    stats: Arc<Mutex<Option<RichListStats>>>
+}
+
+impl RichListStatsCache {
+    pub fn get(&self) -> Option<RichListStats> {
+        self.stats.lock().unwrap().clone()
+    }
+
+    /// computes the stats again unless they were computed at the latest archived tick already, the cached stats are
+    /// kept if the archive fails
+    pub async fn refresh(&self, archive: &SledSink) -> sled::Result<()> {
+        let tick = archive.cursor()?;
+
+        if self.stats.lock().unwrap().as_ref().is_some_and(|stats| stats.tick.is_some() && stats.tick == tick) {
+            return Ok(())
+        }
+
+        let archive = archive.clone();
+        let balances = tokio::task::spawn_blocking(move || archive.held_balances()).await.expect("Reading the balances panicked")?;
+        let computed_at = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+        *self.stats.lock().unwrap() = Some(compute(balances, tick, computed_at));
+
+        Ok(())
+    }
+
+    pub fn spawn_refresher(&self, archive: SledSink, interval: Duration) {
+        let cache = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                if let Err(e) = cache.refresh(&archive).await {
+                    warn!("Failed to compute the rich list stats: {e}");
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
+
+/// stats of the positive `balances` in any order
+pub fn compute(mut balances: Vec<u64>, tick: Option<u32>, computed_at: u64) -> RichListStats {
+    balances.retain(|balance| *balance > 0);
+    balances.sort_unstable_by(|a, b| b.cmp(a));
+
+    let total: u128 = balances.iter().map(|balance| u128::from(*balance)).sum();
+    let share = |top: usize| match total {
+        0 => 0.0,
+        total => balances.iter().take(top).map(|balance| u128::from(*balance)).sum::<u128>() as f64 * 100.0 / total as f64
+    };
+
+    // with the balances ranked from the richest r = 1 to n, G = (n + 1) / n - 2 * sum(r * balance_r) / (n * total)
+    let n = balances.len() as f64;
+    let ranked: u128 = balances.iter().enumerate().map(|(rank, balance)| (rank as u128 + 1) * u128::from(*balance)).sum();
+    let gini = match total {
+        0 => 0.0,
+        total => ((n + 1.0) / n - 2.0 * ranked as f64 / (n * total as f64)).max(0.0)
+    };
+
+    RichListStats {
+        tick,
+        computed_at,
+        wallets: balances.len() as u64,
+        total_balance: u64::try_from(total).unwrap_or(u64::MAX),
+        top1: share(1),
+        top10: share(10),
+        top100: share(100),
+        top1000: share(1000),
+        gini
+    }
+}
+
+#[cfg(test)]
+fn assert_close(actual: f64, expected: f64) {
+    assert!((actual - expected).abs() < 1e-9, "{actual} != {expected}");
+}
+
+#[test]
+fn test_compute() {
+    let stats = compute(vec![20, 0, 40, 10, 30], Some(7), 1_700_000_000);
+    assert_eq!((stats.tick, stats.computed_at, stats.wallets, stats.total_balance), (Some(7), 1_700_000_000, 4, 100));
+    assert_eq!((stats.top1, stats.top10, stats.top100, stats.top1000), (40.0, 100.0, 100.0, 100.0));
+    // sum of |a - b| over all ordered pairs / (2 * n^2 * mean) = 200 / (2 * 16 * 25)
+    assert_close(stats.gini, 0.25);
+
+    // one whale holding 1000 and 1499 identities holding 1 each
+    let stats = compute([vec![1; 1499], vec![1000]].concat(), None, 0);
+    assert_eq!((stats.wallets, stats.total_balance), (1500, 2499));
+    assert_close(stats.top1, 1000.0 * 100.0 / 2499.0);
+    assert_close(stats.top10, 1009.0 * 100.0 / 2499.0);
+    assert_close(stats.top100, 1099.0 * 100.0 / 2499.0);
+    assert_close(stats.top1000, 1999.0 * 100.0 / 2499.0);
+    // 2 * 1499 * 999 / (2 * 1500^2 * 2499 / 1500)
+    assert_close(stats.gini, 1499.0 * 999.0 / (1500.0 * 2499.0));
+
+    // equal balances
+    assert_close(compute(vec![5; 8], None, 0).gini, 0.0);
+
+    let empty = compute(vec![0, 0], None, 0);
+    assert_eq!((empty.wallets, empty.total_balance, empty.top1, empty.top1000, empty.gini), (0, 0, 0.0, 0.0, 0.0));
+}
+
+#[tokio::test]
+async fn test_rich_list_stats_cache() {
+    use crate::archiver::{rich_entity, tick_data, Archiver};
+
+    let archive = SledSink::from_db(&sled::Config::new().temporary(true).open().unwrap()).unwrap();
+    let cache = RichListStatsCache::default();
+    assert_eq!(cache.get(), None);
+
+    cache.refresh(&archive).await.unwrap();
+    assert_eq!(cache.get().map(|stats| (stats.tick, stats.wallets)), Some((None, 0)));
+
+    archive.insert_entity(1, &rich_entity(1, 300)).unwrap();
+    archive.insert_entity(1, &rich_entity(2, 100)).unwrap();
+    archive.insert_entity(1, &rich_entity(3, 0)).unwrap();
+
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    archiver.ingest(tick_data(100, 1), vec![], None).await;
+    archiver.shutdown().await;
+
+    cache.refresh(&archive).await.unwrap();
+    let stats = cache.get().unwrap();
+    assert_eq!((stats.tick, stats.wallets, stats.total_balance, stats.top1), (Some(1), 2, 400, 75.0));
+
+    // the stats of a tick are not computed again
+    archive.insert_entity(2, &rich_entity(2, 500)).unwrap();
+    cache.refresh(&archive).await.unwrap();
+    assert_eq!(cache.get(), Some(stats));
+
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+    archiver.shutdown().await;
+
+    cache.refresh(&archive).await.unwrap();
+    let stats = cache.get().unwrap();
+    assert_eq!((stats.tick, stats.wallets, stats.total_balance, stats.top1), (Some(2), 2, 800, 62.5));
+}
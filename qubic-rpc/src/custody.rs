@@ -0,0 +1,306 @@
+//! Chain-of-custody exports of archived transactions, signed by the server operator for regulatory reporting
+//!
+//! An export is a directory of two files. `transactions.bin` is the canonical serialization of the archived
+//! transactions of a tick range, `manifest.json` holds a digest per archived tick of the range, the digest over all of
+//! them and the signature of the operator over that digest. `verify` re-derives the digests from `transactions.bin`
+//! and checks the signature, it needs the export and the identity of the operator only.
+//!
+//! Format version 1, the layout below never changes within a version:
+//!
+//! - `transactions.bin` is a sequence of records ordered by tick, then by the bytes of the transaction hash. A record
+//!   is the tick (u32 LE), the length of the transaction (u32 LE) and the transaction as broadcast to the network:
+//!   `RawTransaction`, input and signature
+//! - the digest of a tick is `message_digest(TICK_DOMAIN, records)` over the records of the tick, every archived tick
+//!   of the range has one, ticks without transactions hash no records
+//! - the digest of the export is `message_digest(EXPORT_DOMAIN, ...)` over the format version (u32 LE), the first and
+//!   the last tick of the range (u32 LE) and per archived tick the tick (u32 LE), its number of transactions (u32 LE)
+//!   and its digest
+//! - the signature is `QubicWallet::sign_raw` of the digest of the export, the domains keep it from verifying as a
+//!   transaction or as another signed message
+//!
+//! `message_digest` is the domain separated K12 hash of `qubic_types::message`.
+
+use std::{fmt::Display, fs::File, io::{BufWriter, Write}, path::Path};
+
+use qubic_types::{message::message_digest, traits::ToBytes, QubicId, QubicWallet, Signature};
+use serde::{Deserialize, Serialize};
+
+use crate::archiver::SledSink;
+
+pub const FORMAT_VERSION: u32 = 1;
+
+pub const TICK_DOMAIN: &str = "qubic-rpc-custody-tick";
+pub const EXPORT_DOMAIN: &str = "qubic-rpc-custody-export";
+
+const TRANSACTIONS: &str = "transactions.bin";
+const MANIFEST: &str = "manifest.json";
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct TickDigest {
+    pub tick: u32,
+    pub transactions: u32,
+    /// hex encoded digest of the records of the tick
+    pub digest: String
+}
+
+/// Describes an export, stored next to its transactions
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct CustodyManifest {
+    pub format_version: u32,
+    pub from_tick: u32,
+    pub to_tick: u32,
+    pub ticks: Vec<TickDigest>,
+    /// hex encoded digest of the export
+    pub digest: String,
+    pub operator: QubicId,
+    pub signature: Signature
+}
+
+#[derive(Debug)]
+pub enum CustodyError {
+    Io(std::io::Error),
+    Db(sled::Error),
+    Manifest(serde_json::Error),
+    FormatMismatch { found: u32, expected: u32 },
+    Corrupted(String),
+    TickMismatch { tick: u32 },
+    DigestMismatch,
+    OperatorMismatch { found: QubicId, expected: QubicId },
+    InvalidSignature
+}
+
+impl Display for CustodyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Custody export I/O failed: {e}"),
+            Self::Db(e) => write!(f, "Archive database failed: {e}"),
+            Self::Manifest(e) => write!(f, "Invalid custody manifest: {e}"),
+            Self::FormatMismatch { found, expected } => write!(f, "Custody export has format version {found} but this qubic-rpc expects version {expected}"),
+            Self::Corrupted(reason) => write!(f, "Corrupted custody export: {reason}"),
+            Self::TickMismatch { tick } => write!(f, "Transactions of tick {tick} do not match the manifest"),
+            Self::DigestMismatch => write!(f, "Ticks of the manifest do not match the digest of the export"),
+            Self::OperatorMismatch { found, expected } => write!(f, "Custody export is signed by {found} instead of {expected}"),
+            Self::InvalidSignature => write!(f, "Signature of the custody export is invalid")
+        }
+    }
+}
+
+impl std::error::Error for CustodyError {}
+
+impl From<std::io::Error> for CustodyError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<sled::Error> for CustodyError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+impl From<serde_json::Error> for CustodyError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Manifest(value)
+    }
+}
+
+/// Writes the archived transactions of `from_tick..=to_tick` and their manifest signed by `operator` to the `output`
+/// directory. The records are written tick by tick while they are hashed, only the transactions of one tick are held
+/// in memory
+pub fn export(sink: &SledSink, from_tick: u32, to_tick: u32, operator: &QubicWallet, output: &Path) -> Result<CustodyManifest, CustodyError> {
+    std::fs::create_dir_all(output)?;
+
+    let mut file = BufWriter::new(File::create(output.join(TRANSACTIONS))?);
+    let mut records = Vec::new();
+    let mut ticks = Vec::new();
+
+    for tick in sink.ticks_between(from_tick, to_tick)? {
+        let transactions = sink.transactions_between(tick, tick)?;
+        records.clear();
+
+        for tx in transactions.iter() {
+            let bytes = tx.transaction.to_bytes();
+            records.extend_from_slice(&tick.to_le_bytes());
+            records.extend_from_slice(&(bytes.len() as u32).to_le_bytes());
+            records.extend_from_slice(&bytes);
+        }
+
+        file.write_all(&records)?;
+        ticks.push(TickDigest { tick, transactions: transactions.len() as u32, digest: hex::encode(message_digest(TICK_DOMAIN, &records)) });
+    }
+
+    file.flush()?;
+
+    let digest = export_digest(from_tick, to_tick, &ticks)?;
+    let manifest = CustodyManifest {
+        format_version: FORMAT_VERSION,
+        from_tick,
+        to_tick,
+        ticks,
+        digest: hex::encode(digest),
+        operator: operator.public_key,
+        signature: operator.sign_raw(digest)
+    };
+
+    let mut file = BufWriter::new(File::create(output.join(MANIFEST))?);
+    serde_json::to_writer_pretty(&mut file, &manifest)?;
+    file.flush()?;
+
+    Ok(manifest)
+}
+
+/// Re-derives the digests of the export in the `input` directory and checks that `operator` signed them
+pub fn verify(input: &Path, operator: &QubicId) -> Result<CustodyManifest, CustodyError> {
+    let manifest: CustodyManifest = serde_json::from_slice(&std::fs::read(input.join(MANIFEST))?)?;
+
+    if manifest.format_version != FORMAT_VERSION {
+        return Err(CustodyError::FormatMismatch { found: manifest.format_version, expected: FORMAT_VERSION });
+    }
+
+    let records = std::fs::read(input.join(TRANSACTIONS))?;
+    let mut rest = records.as_slice();
+
+    for expected in &manifest.ticks {
+        let start = records.len() - rest.len();
+        let mut count = 0;
+
+        while let Some(tick) = rest.get(..4).map(|tick| u32::from_le_bytes(tick.try_into().unwrap())) {
+            if tick != expected.tick {
+                break
+            }
+
+            let len = rest.get(4..8).map(|len| u32::from_le_bytes(len.try_into().unwrap()) as usize)
+                .filter(|len| rest.len() >= 8 + len)
+                .ok_or_else(|| CustodyError::Corrupted(format!("truncated record of tick {tick}")))?;
+
+            rest = &rest[8 + len..];
+            count += 1;
+        }
+
+        let end = records.len() - rest.len();
+
+        if count != expected.transactions || hex::encode(message_digest(TICK_DOMAIN, &records[start..end])) != expected.digest {
+            return Err(CustodyError::TickMismatch { tick: expected.tick });
+        }
+    }
+
+    if !rest.is_empty() {
+        return Err(CustodyError::Corrupted(format!("{} bytes of {TRANSACTIONS} belong to no tick of the manifest", rest.len())));
+    }
+
+    let digest = export_digest(manifest.from_tick, manifest.to_tick, &manifest.ticks)?;
+
+    if hex::encode(digest) != manifest.digest {
+        return Err(CustodyError::DigestMismatch);
+    }
+
+    if manifest.operator != *operator {
+        return Err(CustodyError::OperatorMismatch { found: manifest.operator, expected: *operator });
+    }
+
+    if !operator.verify_raw(digest, manifest.signature) {
+        return Err(CustodyError::InvalidSignature);
+    }
+
+    Ok(manifest)
+}
+
+/// digest of the export over the range and the digests of its ticks, which have to be ordered and within the range
+fn export_digest(from_tick: u32, to_tick: u32, ticks: &[TickDigest]) -> Result<[u8; 32], CustodyError> {
+    let mut payload = [FORMAT_VERSION, from_tick, to_tick].map(u32::to_le_bytes).concat();
+    let mut last = None;
+
+    for tick in ticks {
+        let digest = hex::decode(&tick.digest).ok().filter(|digest| digest.len() == 32)
+            .ok_or_else(|| CustodyError::Corrupted(format!("malformed digest of tick {}", tick.tick)))?;
+
+        if !(from_tick..=to_tick).contains(&tick.tick) || last.is_some_and(|last| last >= tick.tick) {
+            return Err(CustodyError::Corrupted(format!("tick {} is out of order or outside the range", tick.tick)));
+        }
+
+        payload.extend_from_slice(&tick.tick.to_le_bytes());
+        payload.extend_from_slice(&tick.transactions.to_le_bytes());
+        payload.extend_from_slice(&digest);
+        last = Some(tick.tick);
+    }
+
+    Ok(message_digest(EXPORT_DOMAIN, &payload))
+}
+
+/// export of a fixed archive, checked against the golden files in `fixtures/custody` so any change of the format fails
+#[cfg(test)]
+async fn golden_export(dir: &Path) -> CustodyManifest {
+    use qubic_types::traits::Sign;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionWithData};
+    use crate::archiver::{tick_data, Archiver};
+
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let tx = |tick, amount| {
+        let mut tx = TransactionWithData::from(RawTransaction { from: wallet.public_key, to: QubicId([2; 32]), amount, tick, ..Default::default() });
+        tx.sign(&wallet).unwrap();
+        tx
+    };
+
+    let sink = SledSink::from_db(&sled::Config::new().temporary(true).open().unwrap()).unwrap();
+    let mut archiver = Archiver::new(4).with_sink(sink.clone());
+    archiver.ingest(tick_data(100, 7), vec![], None).await;
+    archiver.ingest(tick_data(100, 8), vec![tx(8, 1), tx(8, 2)], None).await;
+    archiver.ingest(tick_data(100, 9), vec![tx(9, 3)], None).await;
+    archiver.ingest(tick_data(100, 10), vec![tx(10, 4)], None).await;
+    archiver.shutdown().await;
+
+    let operator = QubicWallet::from_seed("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap();
+    export(&sink, 7, 9, &operator, dir).unwrap()
+}
+
+#[tokio::test]
+async fn test_custody_golden() {
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-custody-golden-{}", std::process::id()));
+    let manifest = golden_export(&dir).await;
+
+    assert_eq!(manifest.ticks.iter().map(|tick| (tick.tick, tick.transactions)).collect::<Vec<_>>(), vec![(7, 0), (8, 2), (9, 1)]);
+    assert_eq!(std::fs::read(dir.join(TRANSACTIONS)).unwrap(), include_bytes!("../fixtures/custody/transactions.bin"));
+    assert_eq!(std::fs::read_to_string(dir.join(MANIFEST)).unwrap(), include_str!("../fixtures/custody/manifest.json"));
+
+    // exports of earlier versions keep verifying
+    let fixtures = Path::new(env!("CARGO_MANIFEST_DIR")).join("fixtures/custody");
+    assert_eq!(verify(&fixtures, &manifest.operator).unwrap(), manifest);
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[tokio::test]
+async fn test_custody_verify() {
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-custody-verify-{}", std::process::id()));
+    let manifest = golden_export(&dir).await;
+    let operator = manifest.operator;
+
+    let stranger = QubicWallet::from_seed("ccccccccccccccccccccccccccccccccccccccccccccccccccccccc").unwrap().public_key;
+    assert!(matches!(verify(&dir, &stranger), Err(CustodyError::OperatorMismatch { .. })));
+
+    // a changed transaction no longer matches the digest of its tick
+    let original = std::fs::read(dir.join(TRANSACTIONS)).unwrap();
+    let mut altered = original.clone();
+    *altered.last_mut().unwrap() ^= 1;
+    std::fs::write(dir.join(TRANSACTIONS), &altered).unwrap();
+    assert!(matches!(verify(&dir, &operator), Err(CustodyError::TickMismatch { tick: 9 })));
+
+    std::fs::write(dir.join(TRANSACTIONS), &original[..original.len() - 1]).unwrap();
+    assert!(matches!(verify(&dir, &operator), Err(CustodyError::Corrupted(_))));
+    std::fs::write(dir.join(TRANSACTIONS), &original).unwrap();
+
+    // dropping a tick from the manifest changes the digest of the export, rehashing it breaks the signature
+    let mut dropped = manifest.clone();
+    dropped.ticks.remove(0);
+    std::fs::write(dir.join(MANIFEST), serde_json::to_vec(&dropped).unwrap()).unwrap();
+    assert!(matches!(verify(&dir, &operator), Err(CustodyError::DigestMismatch)));
+
+    dropped.digest = hex::encode(export_digest(dropped.from_tick, dropped.to_tick, &dropped.ticks).unwrap());
+    std::fs::write(dir.join(MANIFEST), serde_json::to_vec(&dropped).unwrap()).unwrap();
+    assert!(matches!(verify(&dir, &operator), Err(CustodyError::InvalidSignature)));
+
+    std::fs::remove_dir_all(dir).unwrap();
+}
@@ -0,0 +1,386 @@
+//! Changes of an identity between two ticks reconstructed from the archive
+//!
+//! The archive holds no entity for every tick. Every entity the diff fetches from the computor is stored, an entity
+//! at a tick is the latest stored one at or before it plus the archived transactions since. Accuracy caveats:
+//!
+//! - without a stored entity at or before a tick the earliest later one (usually the current entity) is used as is
+//! - only transactions are archived, mining rewards, contract payouts and burns are part of the residual
+//! - payouts of computors are only recognized for epochs whose computors were stored while they were current and whose
+//!   end is archived, other payouts are categorized as plain transfers
+//! - transactions archived without the node logs are assumed to have moved funds
+//! - computors do not answer for empty ticks, so `missing_ticks` also lists ticks without any transaction
+//! - the residual only reveals unexplained changes between two different stored entities
+
+use std::{fmt::Display, future::Future};
+
+use axum::http::StatusCode;
+use qubic_rpc_types::{printable_memo, BalanceDiff, DiffTransaction, EntitySnapshot};
+use qubic_types::{QubicId, QubicTxHash};
+use qubic_web3_rs::{client::Client, errors::ClientError, qubic_tcp_types::types::activity::{classify, EpochPayouts}, transport::Tcp};
+
+use crate::archiver::{ArchivedTransaction, SledSink};
+
+/// ticks around the last tick of an epoch its computors are expected to pay out in
+const PAYOUT_WINDOW: u32 = 10;
+
+#[derive(Debug)]
+pub enum DiffError {
+    InvalidRange { from_tick: u32, to_tick: u32 },
+    NoEntity(ClientError),
+    Db(sled::Error)
+}
+
+impl Display for DiffError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRange { from_tick, to_tick } => write!(f, "from_tick {from_tick} exceeds to_tick {to_tick}"),
+            Self::NoEntity(e) => write!(f, "No entity of the identity is archived and the computor failed: {e}"),
+            Self::Db(e) => write!(f, "Archive database failed: {e}")
+        }
+    }
+}
+
+impl From<sled::Error> for DiffError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+impl DiffError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidRange { .. } => StatusCode::BAD_REQUEST,
+            Self::NoEntity(ClientError::Timeout) => StatusCode::GATEWAY_TIMEOUT,
+            Self::NoEntity(_) => StatusCode::BAD_GATEWAY,
+            Self::Db(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// stores the current entity of `id`, requested from the computor `connect` resolves to, and diffs the archive between
+/// `from_tick` and `to_tick`
+pub async fn balance_diff(archive: &SledSink, connect: impl Future<Output = Result<Client<Tcp>, ClientError>>, id: QubicId, from_tick: u32, to_tick: u32) -> Result<BalanceDiff, DiffError> {
+    if from_tick > to_tick {
+        return Err(DiffError::InvalidRange { from_tick, to_tick })
+    }
+
+    let mut warnings = Vec::new();
+
+    // a computor which can't be connected to is treated like one failing the request
+    let client = match connect.await {
+        Ok(client) => match client.qu().request_entity(id).await {
+            Ok(current) => {
+                archive.insert_entity(current.tick, &current.entity)?;
+                Some(client)
+            },
+            Err(e) => {
+                no_current_entity(archive, id, e, &mut warnings)?;
+                Some(client)
+            }
+        },
+        Err(e) => {
+            no_current_entity(archive, id, e, &mut warnings)?;
+            None
+        }
+    };
+
+    // computors are only served for the current epoch, they are stored to recognize its payouts once it ended
+    if let Some(client) = client {
+        match client.qu().request_computors().await {
+            Ok(computors) => archive.insert_computors(&computors)?,
+            Err(e) => warnings.push(format!("Failed to fetch the computors, payouts of the current epoch may not be recognized: {e}"))
+        }
+    }
+
+    let mut diff = archived_diff(archive, id, from_tick, to_tick)?;
+    warnings.append(&mut diff.warnings);
+    diff.warnings = warnings;
+
+    Ok(diff)
+}
+
+/// falls back to the archived entities of `id` if the current one could not be fetched, fails without any
+fn no_current_entity(archive: &SledSink, id: QubicId, e: ClientError, warnings: &mut Vec<String>) -> Result<(), DiffError> {
+    if archive.entity_at_or_before(&id, u32::MAX)?.is_none() {
+        return Err(DiffError::NoEntity(e))
+    }
+
+    warnings.push(format!("Failed to fetch the current entity, only archived entities are used: {e}"));
+
+    Ok(())
+}
+
+/// diffs the stored entities and archived transactions, at least one entity of `id` has to be stored
+pub fn archived_diff(archive: &SledSink, id: QubicId, from_tick: u32, to_tick: u32) -> Result<BalanceDiff, DiffError> {
+    let mut warnings = Vec::new();
+    let from = snapshot(archive, id, from_tick, &mut warnings)?;
+    let to = snapshot(archive, id, to_tick, &mut warnings)?;
+    let payouts = payouts(archive)?;
+
+    let transactions = archive.transactions_between(from_tick.saturating_add(1), to_tick)?.iter()
+        .filter(|tx| tx.transaction.raw_transaction.from == id || tx.transaction.raw_transaction.to == id)
+        .map(|tx| DiffTransaction {
+            tick: tx.tick,
+            hash: QubicTxHash::from(&tx.transaction),
+            from: tx.transaction.raw_transaction.from,
+            to: tx.transaction.raw_transaction.to,
+            amount: tx.transaction.raw_transaction.amount,
+            delta: delta(tx, &id),
+            money_flew: tx.money_flew,
+            memo: printable_memo(&tx.transaction.data),
+            category: classify(&tx.transaction.raw_transaction, &payouts)
+        })
+        .collect::<Vec<_>>();
+
+    // the archive is relied on from the tick of the stored entity `from` is based on
+    let covered_from = from.based_on_tick.min(from_tick);
+    let missing_ticks = gaps(&archive.ticks_between(covered_from.saturating_add(1), to_tick)?, covered_from.saturating_add(1), to_tick);
+
+    if !missing_ticks.is_empty() {
+        warnings.push(format!("{} ticks are not archived, their transactions are part of the residual", missing_ticks.iter().map(|[first, last]| last - first + 1).sum::<u32>()));
+    }
+
+    let balance_delta = to.entity.balance() as i64 - from.entity.balance() as i64;
+    let explained_delta = transactions.iter().map(|tx| tx.delta).sum::<i64>();
+
+    Ok(BalanceDiff {
+        identity: id,
+        from_tick,
+        to_tick,
+        balance_delta,
+        incoming_transfers_delta: to.entity.number_of_incoming_transfers as i64 - from.entity.number_of_incoming_transfers as i64,
+        outgoing_transfers_delta: to.entity.number_of_outgoing_transfers as i64 - from.entity.number_of_outgoing_transfers as i64,
+        from,
+        to,
+        transactions,
+        explained_delta,
+        residual: balance_delta - explained_delta,
+        missing_ticks,
+        warnings
+    })
+}
+
+/// keeps the incoming transfers tagged with `memo`, the deltas still cover every transaction
+pub fn retain_deposits(diff: &mut BalanceDiff, memo: &str) {
+    diff.transactions.retain(|tx| tx.to == diff.identity && tx.memo.as_deref() == Some(memo));
+}
+
+/// entity of `id` at `tick` based on the latest stored entity at or before it
+fn snapshot(archive: &SledSink, id: QubicId, tick: u32, warnings: &mut Vec<String>) -> Result<EntitySnapshot, DiffError> {
+    let Some((based_on_tick, mut entity)) = archive.entity_at_or_before(&id, tick)? else {
+        let (based_on_tick, entity) = archive.entity_after(&id, tick)?.expect("an entity of the identity is stored");
+        warnings.push(format!("No entity at or before tick {tick} is archived, the entity of tick {based_on_tick} is used"));
+
+        return Ok(EntitySnapshot { tick, based_on_tick, entity })
+    };
+
+    for tx in archive.transactions_between(based_on_tick.saturating_add(1), tick)? {
+        let raw = tx.transaction.raw_transaction;
+
+        if raw.amount == 0 || raw.from == raw.to || tx.money_flew == Some(false) {
+            continue;
+        }
+
+        if raw.to == id {
+            entity.incoming_amount += raw.amount;
+            entity.number_of_incoming_transfers += 1;
+            entity.latest_incoming_transfer_tick = tx.tick;
+        } else if raw.from == id {
+            entity.outgoing_amount += raw.amount;
+            entity.number_of_outgoing_transfers += 1;
+            entity.latest_outgoing_transfer_tick = tx.tick;
+        }
+    }
+
+    Ok(EntitySnapshot { tick, based_on_tick, entity })
+}
+
+/// payout windows of the archived epochs with stored computors, an epoch ends before the first tick of the next one
+fn payouts(archive: &SledSink) -> Result<Vec<EpochPayouts>, DiffError> {
+    let mut payouts = Vec::new();
+
+    for pair in archive.epochs()?.windows(2) {
+        let [(epoch, _), (next_epoch, next_first_tick)] = *pair else { unreachable!() };
+
+        if next_epoch != epoch + 1 {
+            continue;
+        }
+
+        if let Some(computors) = archive.computors(epoch)? {
+            payouts.push(EpochPayouts::at_epoch_end(&computors, next_first_tick.saturating_sub(1), PAYOUT_WINDOW));
+        }
+    }
+
+    Ok(payouts)
+}
+
+/// effect of the transaction on the balance of `id`
+fn delta(tx: &ArchivedTransaction, id: &QubicId) -> i64 {
+    let raw = tx.transaction.raw_transaction;
+
+    if tx.money_flew == Some(false) || raw.from == raw.to {
+        return 0
+    }
+
+    match (raw.from == *id, raw.to == *id) {
+        (true, _) => -(raw.amount as i64),
+        (_, true) => raw.amount as i64,
+        _ => 0
+    }
+}
+
+/// inclusive ranges of `from..=to` missing in the ascending `ticks`
+fn gaps(ticks: &[u32], from: u32, to: u32) -> Vec<[u32; 2]> {
+    let mut gaps = Vec::new();
+    let mut next = from;
+
+    for tick in ticks.iter().copied().chain((to < u32::MAX).then_some(to + 1)) {
+        if tick > next {
+            gaps.push([next, tick - 1]);
+        }
+
+        next = tick.saturating_add(1);
+    }
+
+    gaps
+}
+
+#[tokio::test]
+async fn test_balance_diff() {
+    use qubic_web3_rs::qubic_tcp_types::types::{transactions::{RawTransaction, TransactionWithData}, Entity};
+    use crate::archiver::{tick_data, Archiver};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-diff-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+    let (id, other) = (QubicId([1; 32]), QubicId([2; 32]));
+    let tx = |from, to, amount| TransactionWithData::from(RawTransaction { from, to, amount, ..Default::default() });
+    let entity = |incoming_amount, outgoing_amount, number_of_incoming_transfers, number_of_outgoing_transfers| Entity {
+        public_key: id, incoming_amount, outgoing_amount, number_of_incoming_transfers, number_of_outgoing_transfers, latest_incoming_transfer_tick: 0, latest_outgoing_transfer_tick: 0
+    };
+
+    // ticks 5 and 6 are missing, 500 qus were rewarded meanwhile
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    for tick in [1, 2, 3, 4, 7, 8, 9, 10] {
+        let (transactions, transfers) = match tick {
+            3 => (vec![tx(other, id, 100)], None),
+            4 => (vec![tx(id, other, 30)], None),
+            7 => (vec![tx(other, QubicId([3; 32]), 1)], None),
+            8 => (vec![tx(other, id, 50)], Some(vec![])),
+            _ => (vec![], None)
+        };
+
+        archiver.ingest(tick_data(100, tick), transactions, transfers.as_deref()).await;
+    }
+    archiver.shutdown().await;
+
+    archive.insert_entity(2, &entity(1000, 0, 1, 0)).unwrap();
+    archive.insert_entity(9, &entity(1600, 30, 3, 1)).unwrap();
+
+    let diff = archived_diff(&archive, id, 2, 10).unwrap();
+    assert_eq!((diff.from.based_on_tick, diff.to.based_on_tick), (2, 9));
+    assert_eq!((diff.balance_delta, diff.explained_delta, diff.residual), (570, 70, 500));
+    assert_eq!((diff.incoming_transfers_delta, diff.outgoing_transfers_delta), (2, 1));
+    assert_eq!(diff.missing_ticks, [[5, 6]]);
+
+    // the transfer which did not move funds is listed without effect
+    assert_eq!(diff.transactions.iter().map(|tx| (tx.tick, tx.delta, tx.money_flew)).collect::<Vec<_>>(), [(3, 100, None), (4, -30, None), (8, 0, Some(false))]);
+
+    // reconstructed from the entity of tick 2 and the transactions of ticks 3 and 4
+    let diff = archived_diff(&archive, id, 2, 4).unwrap();
+    assert_eq!(diff.to.entity, Entity { latest_incoming_transfer_tick: 3, latest_outgoing_transfer_tick: 4, ..entity(1100, 30, 2, 1) });
+    assert_eq!((diff.balance_delta, diff.residual, diff.missing_ticks.len()), (70, 0, 0));
+    assert!(diff.warnings.is_empty());
+
+    // nothing is archived before tick 2
+    let diff = archived_diff(&archive, id, 1, 2).unwrap();
+    assert_eq!((diff.from.based_on_tick, diff.warnings.len()), (2, 1));
+
+    assert!(matches!(archived_diff(&archive, id, 0, 12).unwrap().missing_ticks.as_slice(), [[5, 6], [11, 12]]));
+
+    // the computor is not reachable, the archived entities are used
+    let diff = balance_diff(&archive, crate::computor_client("127.0.0.1:1"), id, 2, 10).await.unwrap();
+    assert_eq!(diff.residual, 500);
+    assert!(diff.warnings[0].starts_with("Failed to fetch the current entity"));
+
+    assert!(matches!(balance_diff(&archive, crate::computor_client("127.0.0.1:1"), other, 2, 10).await, Err(DiffError::NoEntity(_))));
+    assert_eq!(balance_diff(&archive, crate::computor_client("127.0.0.1:1"), id, 10, 2).await.unwrap_err().status(), StatusCode::BAD_REQUEST);
+
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_diff_categories() {
+    use qubic_types::Signature;
+    use qubic_web3_rs::qubic_tcp_types::types::{activity::TransferCategory, assets::QXID, transactions::{RawTransaction, TransactionWithData}, Computors, Entity};
+    use crate::archiver::{tick_data, Archiver};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-diff-categories-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+    let (id, other, computor) = (QubicId([1; 32]), QubicId([2; 32]), QubicId([3; 32]));
+    let tx = |from, to| TransactionWithData::from(RawTransaction { from, to, amount: 10, ..Default::default() });
+
+    // epoch 100 ends with tick 3, its computor pays out in tick 4 of epoch 101
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    for (epoch, tick, transactions) in [(100, 2, vec![tx(other, id)]), (100, 3, vec![]), (101, 4, vec![tx(computor, id)]), (101, 5, vec![tx(id, QubicId::default())]), (101, 6, vec![tx(id, QXID)])] {
+        archiver.ingest(tick_data(epoch, tick), transactions, None).await;
+    }
+    archiver.shutdown().await;
+
+    let mut public_key = [QubicId::default(); qubic_web3_rs::qubic_tcp_types::consts::NUMBER_OF_COMPUTORS];
+    public_key[7] = computor;
+    archive.insert_computors(&Computors { epoch: 100, public_key, signature: Signature::default() }).unwrap();
+    archive.insert_entity(1, &Entity { public_key: id, incoming_amount: 100, outgoing_amount: 0, number_of_incoming_transfers: 1, number_of_outgoing_transfers: 0, latest_incoming_transfer_tick: 0, latest_outgoing_transfer_tick: 0 }).unwrap();
+    assert_eq!(archive.epochs().unwrap(), [(100, 2), (101, 4)]);
+
+    let categories = archived_diff(&archive, id, 1, 6).unwrap().transactions.into_iter().map(|tx| tx.category).collect::<Vec<_>>();
+    assert_eq!(categories, [TransferCategory::Transfer, TransferCategory::Reward { epoch: 100 }, TransferCategory::Burn, TransferCategory::Contract]);
+
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_deposits_by_memo() {
+    use qubic_web3_rs::qubic_tcp_types::types::{transactions::{RawTransaction, TransactionBuilder, TransactionWithData}, Entity};
+    use crate::archiver::{tick_data, Archiver};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-diff-memos-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+    let (exchange, user) = (QubicId([1; 32]), QubicId([2; 32]));
+    let deposit = |from, to, memo: &[u8]| TransactionBuilder::new().with_from_id(from).with_to_id(to).with_amount(100).with_memo(memo).unwrap().build();
+
+    // binary memos are not shown, withdrawals are not deposits
+    let transactions = [
+        deposit(user, exchange, b"user-42"),
+        deposit(user, exchange, b"user-7"),
+        TransactionWithData::from(RawTransaction { from: user, to: exchange, amount: 5, ..Default::default() }),
+        deposit(user, exchange, &[1, 2]),
+        deposit(exchange, user, b"user-42")
+    ];
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    for (tick, tx) in (2..).zip(transactions) {
+        archiver.ingest(tick_data(100, tick), vec![tx], None).await;
+    }
+    archiver.shutdown().await;
+    archive.insert_entity(1, &Entity { public_key: exchange, incoming_amount: 0, outgoing_amount: 0, number_of_incoming_transfers: 0, number_of_outgoing_transfers: 0, latest_incoming_transfer_tick: 0, latest_outgoing_transfer_tick: 0 }).unwrap();
+
+    let mut diff = archived_diff(&archive, exchange, 1, 6).unwrap();
+    let memos = diff.transactions.iter().map(|tx| tx.memo.as_deref()).collect::<Vec<_>>();
+    assert_eq!(memos, [Some("user-42"), Some("user-7"), None, None, Some("user-42")]);
+
+    retain_deposits(&mut diff, "user-42");
+    assert_eq!(diff.transactions.iter().map(|tx| (tx.tick, tx.delta)).collect::<Vec<_>>(), [(2, 100)]);
+    assert_eq!(diff.explained_delta, 205);
+
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+}
+
+#[test]
+fn test_gaps() {
+    assert_eq!(gaps(&[1, 2, 3], 1, 3), Vec::<[u32; 2]>::new());
+    assert_eq!(gaps(&[2, 5], 1, 7), [[1, 1], [3, 4], [6, 7]]);
+    assert_eq!(gaps(&[], 4, 4), [[4, 4]]);
+    assert_eq!(gaps(&[u32::MAX], u32::MAX - 1, u32::MAX), [[u32::MAX - 1, u32::MAX - 1]]);
+}
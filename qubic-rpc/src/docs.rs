@@ -0,0 +1,41 @@
+use qubic_rpc_types::{v1, v2, Version};
+use serde::Serialize;
+use utoipa::{OpenApi, ToSchema};
+
+/// OpenAPI document of the JSON-RPC routes, served at `/api-docs/openapi.json` with `--docs`
+#[derive(OpenApi)]
+#[openapi(
+    info(title = "qubic-rpc", description = "JSON-RPC interface of a Qubic computor. Amounts are JSON numbers, every route answers them as strings with the query parameter `numberFormat=string`"),
+    paths(crate::versioned_request_handler, crate::v2_json_handler, crate::auth_verify_handler, crate::healthcheck_handler, crate::computors_health_handler, crate::submit_work_handler, crate::metrics_handler, crate::mining_ranking_handler, crate::balance_diff_handler, crate::resolve_identity_handler, crate::identity_transactions_handler, crate::rich_list_handler, crate::rich_list_stats_handler, crate::archive_gaps_handler, crate::tx_status_handler, crate::latest_finalized_handler, crate::next_tick_handler, crate::latest_stats_handler, crate::network_stats_history_handler, crate::network_stats_latest_handler, crate::epoch_stats_handler, crate::epoch_computors_handler, crate::epochs_stats_handler, crate::simulate_transfer_handler, crate::asset_by_name_handler, crate::register_webhook_handler, crate::webhook_handler, crate::delete_webhook_handler, crate::reactivate_webhook_handler, crate::audit_handler),
+    components(schemas(RpcRequest, RpcResponse, UnknownMethod))
+)]
+pub struct ApiDoc;
+
+/// request body of `/`, the `version` field selects the schema (default 1)
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub enum RpcRequest {
+    V1(v1::QubicJsonRpcRequest),
+    V2(v2::QubicJsonRpcRequest)
+}
+
+/// response body of `/`, answered with the schema version of the request
+#[derive(Serialize, ToSchema)]
+#[serde(untagged)]
+#[allow(dead_code)]
+pub enum RpcResponse {
+    V1(v1::QubicJsonRpcResponse),
+    V2(v2::QubicJsonRpcResponse)
+}
+
+/// error of a method unknown to every schema, `version` is echoed if the request had one
+#[derive(ToSchema)]
+#[allow(dead_code)]
+pub struct UnknownMethod {
+    jsonrpc: String,
+    id: Option<u32>,
+    method: String,
+    error: String,
+    version: Option<Version>
+}
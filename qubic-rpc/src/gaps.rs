@@ -0,0 +1,178 @@
+//! Ticks missing from the archive, classified by asking the computor for their tick data
+//!
+//! The archive only holds ticks with tick data. For every tick of the range which is not archived the computor tells
+//! whether it has tick data (archiving missed the tick), answers without tick data (nothing to archive) or has no
+//! record of the tick (it cannot be backfilled from this computor).
+
+use std::fmt::Display;
+
+use axum::http::StatusCode;
+use qubic_rpc_types::ArchiveGaps;
+use qubic_web3_rs::{client::Client, errors::ClientError, qubic_tcp_types::types::ticks::TickDataStatus, transport::Tcp};
+
+use crate::archiver::SledSink;
+
+/// Ticks of a range checked per request
+pub const MAX_GAP_RANGE: u32 = 100_000;
+
+/// Ticks requested per connection, tick data of a chunk is held in memory until it is classified
+const CHUNK: u32 = 1000;
+
+#[derive(Debug)]
+pub enum GapsError {
+    InvalidRange { from_tick: u32, to_tick: u32 },
+    Computor(ClientError),
+    Db(sled::Error)
+}
+
+impl Display for GapsError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::InvalidRange { from_tick, to_tick } => write!(f, "from_tick {from_tick} exceeds to_tick {to_tick} or the range exceeds {MAX_GAP_RANGE} ticks"),
+            Self::Computor(e) => write!(f, "Computor failed to answer for the tick data: {e}"),
+            Self::Db(e) => write!(f, "Archive database failed: {e}")
+        }
+    }
+}
+
+impl From<sled::Error> for GapsError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+impl From<ClientError> for GapsError {
+    fn from(value: ClientError) -> Self {
+        Self::Computor(value)
+    }
+}
+
+impl GapsError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::InvalidRange { .. } => StatusCode::BAD_REQUEST,
+            Self::Computor(ClientError::Timeout) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Computor(_) => StatusCode::BAD_GATEWAY,
+            Self::Db(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// classifies the ticks of `from_tick..=to_tick` which are not archived with the tick data of `client`
+pub async fn archive_gaps(archive: &SledSink, client: &Client<Tcp>, from_tick: u32, to_tick: u32) -> Result<ArchiveGaps, GapsError> {
+    if from_tick > to_tick || to_tick - from_tick >= MAX_GAP_RANGE {
+        return Err(GapsError::InvalidRange { from_tick, to_tick })
+    }
+
+    let archived = archive.ticks_between(from_tick, to_tick)?;
+    let mut gaps = ArchiveGaps { from_tick, to_tick, archived_ticks: archived.len() as u32, unarchived: Vec::new(), empty: Vec::new(), unavailable: Vec::new() };
+    let mut archived = archived.into_iter().peekable();
+
+    for start in (from_tick..=to_tick).step_by(CHUNK as usize) {
+        let end = start.saturating_add(CHUNK - 1).min(to_tick);
+        let mut unarchived = Vec::new();
+
+        for tick in start..=end {
+            match archived.peek() {
+                Some(&next) if next == tick => { archived.next(); },
+                _ => unarchived.push(tick)
+            }
+        }
+
+        let (Some(&first), Some(&last)) = (unarchived.first(), unarchived.last()) else { continue };
+        let range = client.qu().request_tick_data_range(first, last).await?;
+
+        for tick in unarchived {
+            let ranges = match range.get(tick) {
+                Some(TickDataStatus::Present(_)) => &mut gaps.unarchived,
+                Some(TickDataStatus::EmptyTick) => &mut gaps.empty,
+                Some(TickDataStatus::Missing) | None => &mut gaps.unavailable
+            };
+
+            push_tick(ranges, tick);
+        }
+    }
+
+    Ok(gaps)
+}
+
+/// adds `tick` to the inclusive ranges, ticks are pushed in ascending order
+fn push_tick(ranges: &mut Vec<[u32; 2]>, tick: u32) {
+    match ranges.last_mut() {
+        Some(range) if range[1] + 1 == tick => range[1] = tick,
+        _ => ranges.push([tick, tick])
+    }
+}
+
+/// computor answering tick data of ticks ending in 5 and no tick data for ticks ending in 6, other ticks are unknown
+#[cfg(test)]
+fn fake_computor() -> String {
+    use std::io::{Read, Write};
+    use qubic_types::traits::{FromBytes, ToBytes};
+    use qubic_web3_rs::qubic_tcp_types::{types::{ticks::TickData, ExchangePublicPeers, Packet}, Header, MessageType};
+
+    fn serve(mut stream: std::net::TcpStream) -> std::io::Result<()> {
+        stream.write_all(&Packet::new(ExchangePublicPeers::default(), false).unwrap().to_bytes())?;
+
+        loop {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+            let mut tick = [0u8; 4];
+            stream.read_exact(&mut header)?;
+            stream.read_exact(&mut tick)?;
+
+            let dejavu = Header::from_bytes(&header).unwrap().dejavu;
+            let tick = u32::from_le_bytes(tick);
+
+            let mut packet = match tick % 10 {
+                5 => {
+                    let mut tick_data = TickData::from_bytes(&vec![0; std::mem::size_of::<TickData>()]).unwrap();
+                    (tick_data.epoch, tick_data.tick) = (100, tick);
+
+                    Packet::new(tick_data, false).unwrap().to_bytes()
+                },
+                6 => Header::new(std::mem::size_of::<Header>().try_into().unwrap(), MessageType::EndResponse, false).to_bytes(),
+                _ => continue
+            };
+
+            packet[4..8].copy_from_slice(&dejavu.to_le_bytes());
+            stream.write_all(&packet)?;
+        }
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = listener.local_addr().unwrap().to_string();
+
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || serve(stream));
+        }
+    });
+
+    url
+}
+
+#[tokio::test]
+async fn test_archive_gaps() {
+    use std::time::Duration;
+    use qubic_web3_rs::client::ClientBuilder;
+    use crate::archiver::{tick_data, Archiver};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-gaps-{}.sled", std::process::id()));
+    let archive = SledSink::open(path.to_str().unwrap()).unwrap();
+
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    for tick in [10, 11, 12, 13, 17, 18, 19, 20] {
+        archiver.ingest(tick_data(100, tick), vec![], None).await;
+    }
+    archiver.shutdown().await;
+
+    let client = ClientBuilder::<Tcp>::new(fake_computor()).with_read_timeout(Duration::from_millis(100)).build().await.unwrap();
+    let gaps = archive_gaps(&archive, &client, 8, 26).await.unwrap();
+    let invalid = archive_gaps(&archive, &client, 2, 1).await;
+
+    drop(archive);
+    std::fs::remove_dir_all(path).unwrap();
+
+    assert_eq!(gaps, ArchiveGaps { from_tick: 8, to_tick: 26, archived_ticks: 8, unarchived: vec![[15, 15], [25, 25]], empty: vec![[16, 16], [26, 26]], unavailable: vec![[8, 9], [14, 14], [21, 24]] });
+    assert!(matches!(invalid, Err(GapsError::InvalidRange { .. })));
+}
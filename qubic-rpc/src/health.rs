@@ -0,0 +1,171 @@
+use std::{future::Future, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use qubic_rpc_types::{ComputorHealth, ComputorsHealth, HealthCheck, HealthCheckKind};
+use qubic_web3_rs::{client::Client, computor_monitor::ComputorMonitor, errors::ClientError, qubic_tcp_types::types::ExchangePublicPeers, transport::Tcp};
+
+use crate::scheduler::{Priority, UpstreamScheduler};
+
+/// Interval the computor set is requested with, the set of a new epoch re-keys the monitored window
+const COMPUTORS_REFRESH: Duration = Duration::from_secs(300);
+
+/// Interval the current tick is requested with, it bounds the ticks of the votes the monitor accepts
+const TICK_REFRESH: Duration = Duration::from_secs(30);
+
+/// Feeds the votes broadcasted by `computor` to the monitor and keeps its computor sets and current tick up to date,
+/// the monitor drops votes until it knows the computors of their epoch. Its requests are background work of the `scheduler`
+pub fn spawn_monitor(monitor: Arc<Mutex<ComputorMonitor>>, computor: String, scheduler: UpstreamScheduler) {
+    tokio::spawn(async move {
+        let client = match crate::computor_client(&computor).await {
+            Ok(client) => client,
+            Err(e) => return error!("Failed to monitor computors of {computor}: {e}")
+        };
+
+        let votes = monitor.clone();
+        scheduler.acquire(Priority::Background).await;
+
+        if let Err(e) = client.qu().subscribe(ExchangePublicPeers::default(), move |envelope| {
+            votes.lock().unwrap().handle_event(&envelope.event);
+            Ok(())
+        }).await {
+            return error!("Failed to subscribe to {computor}: {e}")
+        }
+
+        let mut computors_refreshed: Option<Instant> = None;
+
+        loop {
+            scheduler.acquire(Priority::Background).await;
+
+            match client.qu().get_current_tick_info().await {
+                Ok(info) => monitor.lock().unwrap().set_tick(info.tick),
+                Err(e) => warn!("Failed to request the current tick: {e}")
+            }
+
+            if computors_refreshed.is_none_or(|refreshed| refreshed.elapsed() >= COMPUTORS_REFRESH) {
+                scheduler.acquire(Priority::Background).await;
+
+                match client.qu().request_computors().await {
+                    Ok(computors) => {
+                        monitor.lock().unwrap().set_computors(computors.epoch, computors.public_key.to_vec());
+                        computors_refreshed = Some(Instant::now());
+                    },
+                    Err(e) => warn!("Failed to request computors: {e}")
+                }
+            }
+
+            tokio::time::sleep(TICK_REFRESH).await;
+        }
+    });
+}
+
+pub fn report(monitor: &ComputorMonitor) -> ComputorsHealth {
+    ComputorsHealth {
+        epoch: monitor.epoch(),
+        ticks: monitor.ticks(),
+        computors: monitor.report().into_iter().map(|stats| ComputorHealth {
+            computor_index: stats.computor_index,
+            identity: stats.identity,
+            signed: stats.signed,
+            missed: stats.missed,
+            divergent: stats.divergent
+        }).collect()
+    }
+}
+
+/// Limits of `/v1/healthcheck`, the server is unhealthy beyond any of them
+#[derive(Debug, Clone, Copy)]
+pub struct HealthThresholds {
+    pub max_upstream_age: Duration,
+    pub max_archive_lag: u32
+}
+
+/// Last current tick the computor answered, load balancers polling the health check do not reach the computor more
+/// than once per allowed age
+#[derive(Debug, Default)]
+pub struct UpstreamProbe {
+    last: Mutex<Option<(Instant, u32)>>
+}
+
+impl UpstreamProbe {
+    /// age of the last answer and its tick, the computor `connect` resolves to is requested if it did not answer within
+    /// `max_age`
+    pub async fn latest(&self, connect: impl Future<Output = Result<Client<Tcp>, ClientError>>, max_age: Duration) -> Option<(Duration, u32)> {
+        let last = *self.last.lock().unwrap();
+
+        if let Some((at, tick)) = last.filter(|(at, _)| at.elapsed() <= max_age) {
+            return Some((at.elapsed(), tick))
+        }
+
+        let res = match connect.await {
+            Ok(client) => client.qu().get_current_tick_info().await,
+            Err(e) => Err(e)
+        };
+
+        match res {
+            Ok(info) => {
+                *self.last.lock().unwrap() = Some((Instant::now(), info.tick));
+                Some((Duration::ZERO, info.tick))
+            },
+            Err(e) => {
+                warn!("Health check of the computor failed: {e}");
+                last.map(|(at, tick)| (at.elapsed(), tick))
+            }
+        }
+    }
+}
+
+/// evaluates the last answer of the computor and the `cursor` of the archive (if archived) against the thresholds
+pub fn check(upstream: Option<(Duration, u32)>, cursor: Option<sled::Result<Option<u32>>>, thresholds: &HealthThresholds) -> HealthCheck {
+    let mut failed = Vec::new();
+    let current_tick = upstream.map(|(_, tick)| tick);
+
+    if upstream.is_none_or(|(age, _)| age > thresholds.max_upstream_age) {
+        failed.push(HealthCheckKind::Upstream);
+    }
+
+    let database = cursor.as_ref().map(Result::is_ok);
+
+    if database == Some(false) {
+        failed.push(HealthCheckKind::Database);
+    }
+
+    // nothing is archived yet right after the archive was created
+    let archive_lag = match (current_tick, cursor) {
+        (Some(tick), Some(Ok(Some(cursor)))) => Some(tick.saturating_sub(cursor)),
+        _ => None
+    };
+
+    if archive_lag.is_some_and(|lag| lag > thresholds.max_archive_lag) {
+        failed.push(HealthCheckKind::ArchiveLag);
+    }
+
+    HealthCheck {
+        status: failed.is_empty(),
+        failed,
+        upstream_age: upstream.map(|(age, _)| age.as_secs()),
+        current_tick,
+        archive_lag,
+        database
+    }
+}
+
+#[test]
+fn test_health_check() {
+    use HealthCheckKind::*;
+
+    let thresholds = HealthThresholds { max_upstream_age: Duration::from_secs(30), max_archive_lag: 10 };
+    let failed = |upstream, cursor| check(upstream, cursor, &thresholds).failed;
+
+    let healthy = check(Some((Duration::from_secs(2), 1000)), Some(Ok(Some(995))), &thresholds);
+    assert_eq!(healthy, HealthCheck { status: true, failed: vec![], upstream_age: Some(2), current_tick: Some(1000), archive_lag: Some(5), database: Some(true) });
+    assert!(check(Some((Duration::ZERO, 1000)), None, &thresholds).status);
+
+    // every degraded dimension is reported on its own
+    assert_eq!(failed(None, None), [Upstream]);
+    assert_eq!(failed(Some((Duration::from_secs(31), 1000)), None), [Upstream]);
+    assert_eq!(failed(Some((Duration::ZERO, 1000)), Some(Ok(Some(989)))), [ArchiveLag]);
+    assert_eq!(failed(Some((Duration::ZERO, 1000)), Some(Err(sled::Error::Unsupported("corrupted".to_owned())))), [Database]);
+
+    // the lag of an empty archive or without current tick is unknown
+    assert_eq!(failed(Some((Duration::ZERO, 1000)), Some(Ok(None))), []);
+    assert_eq!(failed(None, Some(Ok(Some(10)))), [Upstream]);
+}
@@ -0,0 +1,88 @@
+//! HyperLogLog estimating the number of distinct identities, e.g. the active addresses of an epoch
+//!
+//! 4096 one-byte registers (precision 12) give a standard error of 1.04 / sqrt(4096) ≈ 1.6%, about 95% of the
+//! estimates are within ±3.3% of the exact count. Counts below ~10k are estimated by linear counting and are more
+//! accurate. Adding an identity again never changes the registers, so replaying transactions does not inflate the count.
+
+use qubic_types::QubicId;
+use sha2::{Digest, Sha256};
+
+const PRECISION: u32 = 12;
+pub const REGISTERS: usize = 1 << PRECISION;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HyperLogLog {
+    registers: Vec<u8>
+}
+
+impl Default for HyperLogLog {
+    fn default() -> Self {
+        Self { registers: vec![0; REGISTERS] }
+    }
+}
+
+impl HyperLogLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// registers as persisted by `as_bytes`, anything of another length starts empty
+    pub fn from_bytes(bytes: &[u8]) -> Self {
+        match bytes.len() {
+            REGISTERS => Self { registers: bytes.to_vec() },
+            _ => Self::new()
+        }
+    }
+
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.registers
+    }
+
+    /// returns whether a register changed and has to be persisted
+    pub fn insert(&mut self, id: &QubicId) -> bool {
+        // public keys are not uniformly distributed in every bit, the hash is stable across releases
+        let hash = u64::from_le_bytes(Sha256::digest(id.0)[..8].try_into().expect("digest has 32 bytes"));
+        let index = (hash >> (64 - PRECISION)) as usize;
+        let rank = ((hash << PRECISION) | (1 << (PRECISION - 1))).leading_zeros() as u8 + 1;
+
+        if self.registers[index] >= rank {
+            return false
+        }
+
+        self.registers[index] = rank;
+        true
+    }
+
+    pub fn estimate(&self) -> u64 {
+        let m = REGISTERS as f64;
+        let alpha = 0.7213 / (1.0 + 1.079 / m);
+        let sum: f64 = self.registers.iter().map(|register| (-(*register as f64)).exp2()).sum();
+        let raw = alpha * m * m / sum;
+        let zeros = self.registers.iter().filter(|register| **register == 0).count();
+
+        if raw <= 2.5 * m && zeros > 0 {
+            (m * (m / zeros as f64).ln()).round() as u64
+        } else {
+            raw.round() as u64
+        }
+    }
+}
+
+#[test]
+fn test_estimate() {
+    let id = |i: u32| QubicId([i.to_le_bytes().as_slice(), &[7; 28]].concat().try_into().unwrap());
+
+    for count in [0, 1, 100, 5_000, 50_000, 300_000] {
+        let mut hll = HyperLogLog::new();
+        for i in 0..count {
+            hll.insert(&id(i));
+        }
+
+        let error = (hll.estimate() as f64 - count as f64).abs() / (count.max(1) as f64);
+        assert!(error < 0.05, "estimated {} of {count}", hll.estimate());
+
+        // adding the identities again changes nothing
+        assert!((0..count.min(1000)).all(|i| !hll.insert(&id(i))));
+        assert_eq!(HyperLogLog::from_bytes(hll.as_bytes()), hll);
+    }
+}
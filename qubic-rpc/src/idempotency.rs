@@ -0,0 +1,237 @@
+//! Replay guard of broadcast transactions, clients on flaky networks retry broadcasts and must not transfer twice
+//!
+//! A broadcast is answered with the result of the first broadcast instead of being sent again if it carries the
+//! `Idempotency-Key` of an earlier broadcast of the same source identity or if the same signed transaction was broadcast
+//! before. The transaction stands for the body of a request: a retry under another JSON-RPC id is a replay, while a key
+//! reused for another transaction of the source (e.g. signed again for a later tick) is rejected. Keys of different
+//! sources never collide. Entries expire after the TTL and only successful broadcasts are remembered, a failed broadcast
+//! can be retried right away. A broadcast in flight is released as soon as its request is dropped and its reservation
+//! expires after `IN_FLIGHT_TTL` at the latest, so a crashed server does not block retries for the whole TTL.
+
+use std::{convert::Infallible, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use qubic_rpc_types::BroadcastedTransaction;
+use qubic_types::{QubicId, QubicTxHash};
+use serde::{Deserialize, Serialize};
+use sled::transaction::{TransactionError, TransactionResult};
+
+pub const IDEMPOTENCY_HEADER: &str = "idempotency-key";
+
+/// Longest time a broadcast stays reserved while in flight, a broadcast is not relayed for longer
+const IN_FLIGHT_TTL: Duration = Duration::from_secs(60);
+
+/// Broadcast recorded under an idempotency key or under its transaction
+#[derive(Debug, Serialize, Deserialize)]
+struct Entry {
+    tx_id: QubicTxHash,
+    /// unix milliseconds
+    expires_at: u64,
+    /// `None` while the broadcast is in flight
+    result: Option<BroadcastedTransaction>
+}
+
+#[derive(Debug)]
+pub enum Replay {
+    /// nothing is recorded, the broadcast is reserved until the reservation is finished or dropped
+    New(Reservation),
+    /// result of the first broadcast
+    Done(BroadcastedTransaction),
+    /// the key was used for another transaction
+    Conflict(QubicTxHash),
+    /// the first broadcast did not finish yet
+    InFlight
+}
+
+/// Broadcasts of the last TTL persisted in sled, keyed by source and idempotency key and by transaction
+#[derive(Debug, Clone)]
+pub struct IdempotencyStore {
+    entries: sled::Tree,
+    ttl: Duration
+}
+
+impl IdempotencyStore {
+    pub fn from_db(db: &sled::Db, ttl: Duration) -> sled::Result<Self> {
+        Ok(Self { entries: db.open_tree("idempotency")?, ttl })
+    }
+
+    /// looks up the broadcast of `tx_id` under the `key` of its `source` and by the transaction, a new broadcast is
+    /// reserved under both
+    pub fn begin(&self, source: &QubicId, key: Option<&str>, tx_id: &QubicTxHash) -> sled::Result<Replay> {
+        let now = now();
+        let keys = entry_keys(source, key, tx_id);
+        let reserved = self.entry(tx_id, None);
+
+        let result: TransactionResult<Replay, Infallible> = self.entries.transaction(|entries| {
+            for key in keys.iter() {
+                let Some(entry) = entries.get(key)?.and_then(|entry| serde_json::from_slice::<Entry>(&entry).ok()) else { continue };
+
+                if entry.expires_at > now {
+                    return Ok(match entry.result {
+                        _ if entry.tx_id != *tx_id => Replay::Conflict(entry.tx_id),
+                        Some(result) => Replay::Done(result),
+                        None => Replay::InFlight
+                    })
+                }
+            }
+
+            for key in keys.iter() {
+                entries.insert(key.as_slice(), reserved.as_slice())?;
+            }
+
+            Ok(Replay::New(Reservation { store: self.clone(), keys: keys.clone(), tx_id: *tx_id, reserved: Some(reserved.clone()) }))
+        });
+
+        result.map_err(|e| match e {
+            TransactionError::Storage(e) => e,
+            TransactionError::Abort(never) => match never {}
+        })
+    }
+
+    /// removes the expired entries, returns their number
+    pub fn prune(&self) -> sled::Result<usize> {
+        let now = now();
+        let mut pruned = 0;
+
+        for entry in self.entries.iter() {
+            let (key, value) = entry?;
+            let expired = serde_json::from_slice::<Entry>(&value).map_or(true, |entry| entry.expires_at <= now);
+
+            // an entry renewed meanwhile is kept
+            if expired && self.entries.compare_and_swap(key, Some(value), None as Option<&[u8]>)?.is_ok() {
+                pruned += 1;
+            }
+        }
+
+        Ok(pruned)
+    }
+
+    /// prunes the expired entries once per TTL
+    pub fn spawn_pruner(&self) {
+        let store = self.clone();
+
+        tokio::spawn(async move {
+            loop {
+                tokio::time::sleep(store.ttl.max(Duration::from_secs(1))).await;
+
+                if let Err(e) = store.prune() {
+                    warn!("Pruning idempotency keys failed: {e}");
+                }
+            }
+        });
+    }
+
+    /// entry expiring after the TTL, or after `IN_FLIGHT_TTL` if earlier while the broadcast is in flight
+    fn entry(&self, tx_id: &QubicTxHash, result: Option<BroadcastedTransaction>) -> Vec<u8> {
+        let ttl = if result.is_some() { self.ttl } else { self.ttl.min(IN_FLIGHT_TTL) };
+        let expires_at = now() + ttl.as_millis() as u64;
+
+        serde_json::to_vec(&Entry { tx_id: *tx_id, expires_at, result }).expect("Entry serializes")
+    }
+}
+
+/// Broadcast reserved by `begin`, released when dropped unless it was finished
+#[derive(Debug)]
+pub struct Reservation {
+    store: IdempotencyStore,
+    keys: Vec<Vec<u8>>,
+    tx_id: QubicTxHash,
+    /// entry of the reservation, `None` once finished
+    reserved: Option<Vec<u8>>
+}
+
+impl Reservation {
+    /// records the `result` of the broadcast, the reservation of a failed broadcast is released
+    pub fn finish(mut self, result: Option<&BroadcastedTransaction>) -> sled::Result<()> {
+        let Some(result) = result else { return self.release() };
+        let mut batch = sled::Batch::default();
+
+        for key in self.keys.iter() {
+            batch.insert(key.as_slice(), self.store.entry(&self.tx_id, Some(result.clone())));
+        }
+
+        self.reserved = None;
+        self.store.entries.apply_batch(batch)
+    }
+
+    /// removes the entries still holding this reservation, a reservation which expired and was taken over is kept
+    fn release(&mut self) -> sled::Result<()> {
+        let Some(reserved) = self.reserved.take() else { return Ok(()) };
+
+        for key in self.keys.iter() {
+            let _ = self.store.entries.compare_and_swap(key.as_slice(), Some(reserved.as_slice()), None as Option<&[u8]>)?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Drop for Reservation {
+    fn drop(&mut self) {
+        if let Err(e) = self.release() {
+            warn!("Releasing the reservation of {} failed: {e}", self.tx_id);
+        }
+    }
+}
+
+fn now() -> u64 {
+    SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64
+}
+
+/// the idempotency key of the source (if any) and the transaction
+fn entry_keys(source: &QubicId, key: Option<&str>, tx_id: &QubicTxHash) -> Vec<Vec<u8>> {
+    key.map(|key| [b"key:".as_slice(), &source.0, key.as_bytes()].concat()).into_iter()
+        .chain([[b"tx:".as_slice(), &tx_id.0].concat()])
+        .collect()
+}
+
+#[test]
+fn test_replay_guard() {
+    let db = sled::Config::new().temporary(true).open().unwrap();
+    let store = IdempotencyStore::from_db(&db, Duration::from_secs(600)).unwrap();
+    let (first, second) = (QubicTxHash([1; 32]), QubicTxHash([2; 32]));
+    let (alice, bob) = (QubicId([1; 32]), QubicId([2; 32]));
+    let result = BroadcastedTransaction { tx_hash: first, peers_broadcasted: 3 };
+
+    let Replay::New(reservation) = store.begin(&alice, Some("retry"), &first).unwrap() else { panic!("not reserved") };
+    assert!(matches!(store.begin(&alice, Some("retry"), &first).unwrap(), Replay::InFlight));
+
+    // a failed broadcast is released
+    reservation.finish(None).unwrap();
+    let Replay::New(reservation) = store.begin(&alice, Some("retry"), &first).unwrap() else { panic!("not released") };
+    reservation.finish(Some(&result)).unwrap();
+
+    assert!(matches!(store.begin(&alice, Some("retry"), &first).unwrap(), Replay::Done(done) if done.peers_broadcasted == 3));
+    assert!(matches!(store.begin(&alice, Some("retry"), &second).unwrap(), Replay::Conflict(tx_id) if tx_id == first));
+    // the same transaction without or under another key is a duplicate
+    assert!(matches!(store.begin(&alice, None, &first).unwrap(), Replay::Done(_)));
+    assert!(matches!(store.begin(&alice, Some("other"), &first).unwrap(), Replay::Done(_)));
+    assert_eq!(store.prune().unwrap(), 0);
+
+    // keys are scoped to the source, another source reusing it is not answered with the result of alice
+    let Replay::New(reservation) = store.begin(&bob, Some("retry"), &second).unwrap() else { panic!("key of alice was shared") };
+
+    // a dropped reservation is released, e.g. when the client disconnects during the broadcast
+    drop(reservation);
+    assert!(matches!(store.begin(&bob, Some("retry"), &second).unwrap(), Replay::New(_)));
+
+    // expired entries are forgotten
+    let store = IdempotencyStore::from_db(&sled::Config::new().temporary(true).open().unwrap(), Duration::ZERO).unwrap();
+    let Replay::New(reservation) = store.begin(&alice, Some("retry"), &first).unwrap() else { panic!("not reserved") };
+    reservation.finish(Some(&result)).unwrap();
+    assert!(matches!(store.begin(&alice, Some("retry"), &second).unwrap(), Replay::New(_)));
+    // the dropped reservation of the second transaction is gone already
+    assert_eq!(store.prune().unwrap(), 1);
+}
+
+#[test]
+fn test_in_flight_expiry() {
+    let store = IdempotencyStore::from_db(&sled::Config::new().temporary(true).open().unwrap(), Duration::from_secs(3600)).unwrap();
+    let tx_id = QubicTxHash([1; 32]);
+
+    // a reservation never finished nor dropped (the server crashed) expires long before the TTL
+    let Replay::New(reservation) = store.begin(&QubicId([1; 32]), None, &tx_id).unwrap() else { panic!("not reserved") };
+    std::mem::forget(reservation);
+
+    let entry = serde_json::from_slice::<Entry>(&store.entries.get(entry_keys(&QubicId([1; 32]), None, &tx_id)[0].as_slice()).unwrap().unwrap()).unwrap();
+    assert!(entry.result.is_none() && entry.expires_at <= now() + IN_FLIGHT_TTL.as_millis() as u64);
+}
@@ -0,0 +1,165 @@
+//! Statistics of the latest archived tick served at `/v1/latest-stats`
+//!
+//! Everything but the price is computed from the archive, once per archived tick: requests until the next tick is
+//! archived are answered from the cache. The price is requested from `--price-url` with every computation, the URL
+//! answers JSON like `{"price": 0.0000021}` in USD per QU. Without it, or if it fails, price and market cap are null.
+
+use std::{sync::{Arc, Mutex}, time::Duration};
+
+use qubic_rpc_types::LatestStats;
+use serde::Deserialize;
+
+use crate::archiver::SledSink;
+
+/// The stats are answered without a price if `--price-url` does not answer in time
+const PRICE_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug, Deserialize)]
+struct Price {
+    price: f64
+}
+
+/// Latest stats of the archive, computed again once a later tick is archived
+#[derive(Clone)]
+pub struct LatestStatsCache {
+    price_url: Option<String>,
+    client: reqwest::Client,
+    latest: Arc<Mutex<Option<LatestStats>>>
+}
+
+impl LatestStatsCache {
+    pub fn new(price_url: Option<String>) -> Self {
+        Self { price_url, client: reqwest::Client::new(), latest: Arc::default() }
+    }
+
+    /// stats of the last archived tick, `None` until a tick is archived
+    pub async fn get(&self, archive: &SledSink) -> sled::Result<Option<LatestStats>> {
+        let Some(tick) = archive.cursor()? else { return Ok(None) };
+
+        if let Some(stats) = self.latest.lock().unwrap().as_ref().filter(|stats| stats.tick == tick) {
+            return Ok(Some(stats.clone()))
+        }
+
+        let Some(mut stats) = compute(archive, tick)? else { return Ok(None) };
+        stats.price = self.price().await;
+        stats.market_cap = stats.price.map(|price| price * stats.circulating_supply as f64);
+
+        *self.latest.lock().unwrap() = Some(stats.clone());
+
+        Ok(Some(stats))
+    }
+
+    async fn price(&self) -> Option<f64> {
+        let url = self.price_url.as_ref()?;
+        let price = async { self.client.get(url).timeout(PRICE_TIMEOUT).send().await?.error_for_status()?.json::<Price>().await };
+
+        match price.await {
+            Ok(price) => Some(price.price),
+            Err(e) => {
+                warn!("Failed to request the price from {url}: {e}");
+                None
+            }
+        }
+    }
+}
+
+/// stats of the archive at `tick` without the price, `None` if no epoch is archived up to `tick`
+fn compute(archive: &SledSink, tick: u32) -> sled::Result<Option<LatestStats>> {
+    let Some((epoch, first_tick)) = archive.epochs()?.into_iter().take_while(|(_, first_tick)| *first_tick <= tick).last() else {
+        return Ok(None)
+    };
+
+    let ticks_in_epoch = tick - first_tick + 1;
+    let empty_ticks_in_epoch = ticks_in_epoch - archive.count_ticks_between(first_tick, tick)?;
+    let (circulating_supply, active_addresses) = archive.supply()?;
+
+    Ok(Some(LatestStats {
+        tick,
+        epoch,
+        ticks_in_epoch,
+        empty_ticks_in_epoch,
+        epoch_tick_quality: tick_quality(ticks_in_epoch, empty_ticks_in_epoch),
+        circulating_supply,
+        active_addresses,
+        burned: archive.burned()?,
+        price: None,
+        market_cap: None
+    }))
+}
+
+/// percentage of `ticks` which are not empty, 100 without ticks
+pub fn tick_quality(ticks: u32, empty: u32) -> f64 {
+    match ticks {
+        0 => 100.0,
+        ticks => f64::from(ticks - empty.min(ticks)) * 100.0 / f64::from(ticks)
+    }
+}
+
+#[test]
+fn test_tick_quality() {
+    assert_eq!(tick_quality(10, 2), 80.0);
+    assert_eq!(tick_quality(4, 4), 0.0);
+    assert_eq!(tick_quality(0, 0), 100.0);
+    assert!((tick_quality(3, 1) - 200.0 / 3.0).abs() < 1e-9);
+}
+
+#[tokio::test]
+async fn test_latest_stats() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionWithData};
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    use crate::archiver::{rich_entity, tick_data, Archiver};
+
+    let server = MockServer::start().await;
+
+    // the price is requested once per archived tick
+    Mock::given(method("GET")).and(path("/price"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "price": 0.5 })))
+        .expect(2)
+        .mount(&server).await;
+
+    let archive = SledSink::from_db(&sled::Config::new().temporary(true).open().unwrap()).unwrap();
+    let stats = LatestStatsCache::new(Some(format!("{}/price", server.uri())));
+    assert_eq!(stats.get(&archive).await.unwrap(), None);
+
+    archive.insert_entity(1, &rich_entity(1, 1_000)).unwrap();
+    archive.insert_entity(1, &rich_entity(2, 500)).unwrap();
+    archive.insert_entity(1, &rich_entity(3, 0)).unwrap();
+
+    let burn = TransactionWithData::from(RawTransaction { from: QubicId([1; 32]), to: QubicId::default(), amount: 7, ..Default::default() });
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    archiver.ingest(tick_data(100, 1), vec![burn.clone()], None).await;
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+    // ticks 12 and 15 are empty
+    for tick in [10, 11, 13, 14] {
+        archiver.ingest(tick_data(101, tick), if tick == 13 { vec![burn.clone()] } else { vec![] }, None).await;
+    }
+    archiver.shutdown().await;
+
+    let expected = LatestStats {
+        tick: 14,
+        epoch: 101,
+        ticks_in_epoch: 5,
+        empty_ticks_in_epoch: 1,
+        epoch_tick_quality: 80.0,
+        circulating_supply: 1_500,
+        active_addresses: 2,
+        burned: 14,
+        price: Some(0.5),
+        market_cap: Some(750.0)
+    };
+    assert_eq!(stats.get(&archive).await.unwrap(), Some(expected.clone()));
+    assert_eq!(stats.get(&archive).await.unwrap(), Some(expected.clone()));
+
+    let mut archiver = Archiver::new(16).with_sink(archive.clone());
+    archiver.ingest(tick_data(101, 16), vec![], None).await;
+    archiver.shutdown().await;
+
+    let latest = stats.get(&archive).await.unwrap().unwrap();
+    assert_eq!((latest.tick, latest.ticks_in_epoch, latest.empty_ticks_in_epoch, latest.epoch_tick_quality), (16, 7, 2, 500.0 / 7.0));
+
+    // without a price URL the price is unknown
+    let latest = LatestStatsCache::new(None).get(&archive).await.unwrap().unwrap();
+    assert_eq!((latest.price, latest.market_cap), (None, None));
+}
@@ -1,19 +1,86 @@
-use std::sync::Arc;
+use std::{net::{IpAddr, SocketAddr}, sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex, OnceLock}, time::{Duration, Instant, SystemTime, UNIX_EPOCH}};
 use axum::{
-    routing::post,
-    extract::State,
-    Router, Json,
+    routing::{get, post},
+    extract::{ConnectInfo, Path, Query, State},
+    response::{IntoResponse, Response},
+    Router,
 };
-use qubic_web3_rs::{client::Client, transport::Tcp, qubic_tcp_types::types::transactions::TransactionFlags};
-use qubic_rpc_types::{QubicJsonRpcRequest, QubicJsonRpcResponse, ResponseType, RequestError, RequestMethods, RequestResults};
-use axum::http::Method;
+use qubic_web3_rs::{client::{Client, ClientBuilder}, computor_monitor::ComputorMonitor, errors::ClientError, interceptor::{Interceptor, RequestInfo, ResponseInfo}, proxy::ProxyConfig, transport::Tcp, wire_dump::WireDump, qubic_tcp_types::types::{assets::AssetSummary, simulation::TransferSimulation, transactions::{TransactionFlags, TransactionStatus}, ExchangePublicPeers}};
+use qubic_types::{message::SignedChallenge, QubicId, QubicTxHash, QubicWallet};
+use qubic_rpc_types::{printable_memo, v2, ArchiveGaps, AuditRecord, AuthVerification, BalanceDiff, BroadcastedTransaction, CoalescingMetrics, ComputorInfos, ComputorsHealth, Diagnostics, EpochStats, ExternalRawTransaction, HealthCheck, IdentityTransaction, LatestFinalizedTick, LatestStats, MiningRanking, NetworkOverview, NetworkStats, NextTick, PublicPeers, QubicJsonRpcRequest, QubicJsonRpcResponse, RegisterWebhook, ResponseType, RequestError, RequestMethods, RequestResults, ResolvedInput, RichList, RichListStats, SubmitWork, SubmittedWork, TickDataReport, TickTransactions, TransactionStatusReport, TransactionsResponse, Version, VersionedRequest, Webhook};
+use serde::Deserialize;
+use axum::http::{HeaderMap, Method, StatusCode};
 use tokio::net::TcpListener;
 use tower_http::cors::{CorsLayer, Any};
-use clap::Parser;
+use clap::{Parser, Subcommand};
+use archiver::{Archiver, CsvSink, SledSink};
+use audit::{AuditEntry, AuditError, AuditLog};
+use coalesce::{Coalescer, Served};
+use concentration::RichListStatsCache;
+use health::{HealthThresholds, UpstreamProbe};
+use idempotency::{IdempotencyStore, Replay, IDEMPOTENCY_HEADER};
+use latest::LatestStatsCache;
+use nonces::UsedNonces;
+use numbers::Json;
+use params::{ParsedIdentity, ParsedTxHash};
+use proxy::FallbackRpc;
+use ranking::RankingCache;
+use scheduler::{Priority, UpstreamScheduler};
+use stats::StatsStore;
+use ticks::TickWatcher;
+use webhooks::{WebhookStore, Webhooks};
+use work::WorkRelay;
+use utoipa::OpenApi;
+use utoipa_swagger_ui::SwaggerUi;
+
+mod archiver;
+mod audit;
+mod coalesce;
+mod concentration;
+mod custody;
+mod diff;
+mod docs;
+mod gaps;
+mod health;
+mod hll;
+mod idempotency;
+mod latest;
+mod nonces;
+mod numbers;
+mod panics;
+mod params;
+mod proxy;
+mod ranking;
+mod resolve;
+mod scheduler;
+mod snapshot;
+mod stats;
+mod stream;
+mod ticks;
+mod webhooks;
+mod work;
 
 #[macro_use]
 extern crate log;
 
+/// response header naming the backend which served the request (`computor` or `proxy`)
+const SOURCE_HEADER: &str = "x-qubic-source";
+
+/// request header asking for `Diagnostics` like the `debug` member of a request, `0` and `false` are ignored
+const DEBUG_HEADER: &str = "x-qubic-debug";
+
+/// request header carrying a `SignedChallenge` as JSON on routes restricted to authenticated identities
+const CHALLENGE_HEADER: &str = "x-qubic-challenge";
+
+/// proxy of `--proxy`, set once at startup
+static COMPUTOR_PROXY: OnceLock<ProxyConfig> = OnceLock::new();
+
+/// dump of the frames of the connections to computors of `--wire-dump-dir`, set once at startup
+static COMPUTOR_WIRE_DUMP: OnceLock<WireDump> = OnceLock::new();
+
+/// requests of busy computors which were sent again to a peer they suggested, see `RedirectCounter`
+static PEER_REDIRECTS: AtomicU64 = AtomicU64::new(0);
+
 #[derive(Debug, Parser)]
 struct Args {
     /// Binds server to provided port
@@ -22,7 +89,323 @@ struct Args {
 
     /// Computor to send requests
     #[arg(short, long, default_value = "95.156.230.174:21841")]
-    computor: String
+    computor: String,
+
+    /// Additional computors transactions are broadcasted to (can be passed multiple times)
+    #[arg(short, long)]
+    broadcast_peer: Vec<String>,
+
+    /// Minimum number of computors which have to accept a broadcasted transaction
+    #[arg(long, default_value = "1")]
+    min_broadcast_peers: usize,
+
+    /// Proxy all connections to computors are tunneled through, socks5://[user:password@]host:port or http://[user:password@]host:port
+    #[arg(long)]
+    proxy: Option<ProxyConfig>,
+
+    /// Directory every frame sent to and received from computors is dumped to (timestamp, direction, peer, header
+    /// and hex payload), for troubleshooting the protocol
+    #[arg(long)]
+    wire_dump_dir: Option<String>,
+
+    /// Payload bytes dumped per frame with --wire-dump-dir, longer payloads are cut
+    #[arg(long, default_value = "1024")]
+    wire_dump_max_payload: usize,
+
+    /// Official HTTP RPC serving requests if the computor is not reachable (e.g. https://rpc.qubic.org)
+    #[arg(long)]
+    fallback_rpc: Option<String>,
+
+    /// Serves all requests through the fallback RPC
+    #[arg(long, requires = "fallback_rpc")]
+    proxy_only: bool,
+
+    /// Interval in milliseconds the current tick is polled with while clients wait for the next tick
+    #[arg(long, default_value = "1000")]
+    tick_poll_interval: u64,
+
+    /// Path of the database network stats are recorded to, stats history is not served if unset
+    #[arg(long)]
+    stats_db: Option<String>,
+
+    /// Interval in seconds network stats are sampled with
+    #[arg(long, default_value = "60")]
+    stats_interval: u64,
+
+    /// Path of the database ticks and transactions are archived to
+    #[arg(long)]
+    archive_db: Option<String>,
+
+    /// CSV file archived transactions are written to
+    #[arg(long)]
+    archive_csv: Option<String>,
+
+    /// First tick to archive, defaults to the tick after the last archived one or else the current tick
+    #[arg(long)]
+    archive_from_tick: Option<u32>,
+
+    /// Number of events queued per archive sink before archiving waits for it
+    #[arg(long, default_value = "1024")]
+    archive_queue: usize,
+
+    /// Archives transactions whose input size or type is inconsistent tagged as malformed instead of dropping them
+    #[arg(long)]
+    archive_malformed: bool,
+
+    /// Logging passcode of the computor as four comma separated numbers, archived transactions are matched
+    /// to the logged transfers to tell whether they moved funds
+    #[arg(long, value_delimiter = ',', num_args = 4)]
+    log_passcode: Option<Vec<u64>>,
+
+    /// Serves the OpenAPI document at /api-docs/openapi.json and a Swagger UI at /docs
+    #[arg(long)]
+    docs: bool,
+
+    /// Audience challenges verified at /v1/auth/verify have to be issued for, e.g. the domain of the dApp
+    #[arg(long)]
+    auth_audience: Option<String>,
+
+    /// Seconds a signed challenge stays valid after it was issued
+    #[arg(long, default_value = "300")]
+    auth_max_age: u64,
+
+    /// Seconds a signed challenge may be issued ahead of the clock of the server
+    #[arg(long, default_value = "30")]
+    auth_max_skew: u64,
+
+    /// Number of ticks the votes of every computor are monitored over, /v1/computors/health is served if set
+    #[arg(long)]
+    monitor_window: Option<usize>,
+
+    /// Seed of the wallet signing the mining solutions relayed for miners at /v1/submit-work
+    #[arg(long)]
+    work_relay_seed: Option<String>,
+
+    /// Seconds a client (by its address) has to wait between two relayed mining solutions
+    #[arg(long, default_value = "1")]
+    work_interval: u64,
+
+    /// Milliseconds the result of a read request (e.g. requestCurrentTickInfo) answers identical requests, identical
+    /// concurrent reads share one upstream request regardless
+    #[arg(long, default_value = "500")]
+    read_cache_ttl: u64,
+
+    /// Seed of the operator of the computor, its mining score ranking is served at /v1/mining/ranking to
+    /// identities authenticated for --auth-audience. Custody exports are signed with it
+    #[arg(long)]
+    operator_seed: Option<String>,
+
+    /// Interval in seconds the mining score ranking is requested with
+    #[arg(long, default_value = "60")]
+    ranking_interval: u64,
+
+    /// Identity allowed to read the mining score ranking (can be passed multiple times), every authenticated
+    /// identity if unset
+    #[arg(long)]
+    ranking_viewer: Vec<QubicId>,
+
+    /// Interval in seconds the stats of the rich list are computed with, served at /v1/rich-list/stats with --archive-db
+    #[arg(long, default_value = "60")]
+    rich_list_stats_interval: u64,
+
+    /// Failed deliveries in a row after which a webhook is dead-lettered, webhooks are served with --archive-db
+    #[arg(long, default_value = "5")]
+    webhook_max_failures: u32,
+
+    /// Milliseconds before the first retry of a failed webhook delivery, doubled with every further retry
+    #[arg(long, default_value = "1000")]
+    webhook_backoff: u64,
+
+    /// Webhooks registered at most, dead-lettered ones count until their owner deletes them
+    #[arg(long, default_value = "1000")]
+    webhook_max: usize,
+
+    /// Webhooks an identity registers at most
+    #[arg(long, default_value = "10")]
+    webhook_max_per_identity: usize,
+
+    /// Allows webhooks to deliver to loopback, private and link-local addresses, e.g. to receivers next to the server
+    #[arg(long)]
+    webhook_allow_private: bool,
+
+    /// Path of the database broadcast transactions and relayed mining solutions are recorded to, hash chained so
+    /// altered or removed records are detected
+    #[arg(long)]
+    audit_log: Option<String>,
+
+    /// Identity allowed to read the audit log at /v1/admin/audit (can be passed multiple times)
+    #[arg(long)]
+    audit_viewer: Vec<QubicId>,
+
+    /// Seconds a broadcast transaction is answered with its first result instead of being sent again, for retries
+    /// with the same Idempotency-Key header and source identity or of the same signed transaction. Kept in
+    /// --archive-db across restarts
+    #[arg(long, default_value = "600")]
+    idempotency_ttl: u64,
+
+    /// URL answering the price of a QU in USD as JSON (`{"price": 0.0000021}`), /v1/latest-stats answers price and
+    /// market cap as null without it
+    #[arg(long)]
+    price_url: Option<String>,
+
+    /// Seconds since the computor last answered the current tick after which /v1/healthcheck fails
+    #[arg(long, default_value = "30")]
+    health_max_upstream_age: u64,
+
+    /// Ticks the archive may be behind the current tick before /v1/healthcheck fails
+    #[arg(long, default_value = "30")]
+    health_max_archive_lag: u32,
+
+    /// Requests per second sent to the computor by API requests and the archiver together, API requests go first.
+    /// Unlimited if unset
+    #[arg(long)]
+    upstream_rps: Option<u32>,
+
+    /// Share (0 to 1) of the one second burst of --upstream-rps the archiver may use up, the rest is kept for API
+    /// requests arriving during a backfill. The archiver still gets the whole rate API requests leave over
+    #[arg(long, default_value = "0.8")]
+    background_share: f64,
+
+    #[command(subcommand)]
+    command: Option<Command>
+}
+
+/// Maintenance of the archive database or the audit log instead of serving, the server must not run on the database
+/// meanwhile
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Writes a snapshot of the archive database to the output file (e.g. snapshot.tar.zst)
+    Export {
+        #[arg(short, long)]
+        output: String
+    },
+    /// Restores a snapshot into an empty archive database, archiving resumes after the last tick of the snapshot
+    Import {
+        #[arg(short, long)]
+        input: String
+    },
+    /// Validates the hash chain of the audit log of `--audit-log`
+    VerifyAudit,
+    /// Writes the archived transactions of a tick range to the output directory, signed with `--operator-seed`
+    CustodyExport {
+        #[arg(long)]
+        from_tick: u32,
+        #[arg(long)]
+        to_tick: u32,
+        #[arg(short, long)]
+        output: String
+    },
+    /// Validates the digests and the signature of a custody export against the identity of the operator
+    CustodyVerify {
+        #[arg(short, long)]
+        input: String,
+        #[arg(long)]
+        operator: String
+    }
+}
+
+/// runs a maintenance command, returns a summary of its outcome or the error it failed with
+fn run_command(args: &Args, command: &Command) -> Result<String, String> {
+    let snapshot_db = || sled::open(args.archive_db.as_ref().expect("--archive-db is required to export or import a snapshot")).expect("Failed to open archive database");
+    let snapshot_summary = |manifest: snapshot::Manifest| format!("Snapshot of ticks {:?} to {:?} (schema version {})", manifest.first_tick, manifest.last_tick, manifest.schema_version);
+
+    match command {
+        Command::Export { output } => snapshot::export(&snapshot_db(), output.as_ref()).map(snapshot_summary).map_err(|e| e.to_string()),
+        Command::Import { input } => snapshot::import(&snapshot_db(), input.as_ref()).map(snapshot_summary).map_err(|e| e.to_string()),
+        Command::VerifyAudit => {
+            let path = args.audit_log.as_ref().expect("--audit-log is required to verify the audit log");
+            let records = AuditLog::open(path).map_err(AuditError::from).and_then(|audit| audit.verify()).map_err(|e| e.to_string())?;
+
+            Ok(format!("Hash chain of {records} audit records is intact"))
+        },
+        Command::CustodyExport { from_tick, to_tick, output } => {
+            let path = args.archive_db.as_ref().expect("--archive-db is required to export archived transactions");
+            let operator = QubicWallet::from_seed(args.operator_seed.as_ref().expect("--operator-seed is required to sign a custody export")).expect("Invalid operator seed");
+            let sink = SledSink::open(path).expect("Failed to open archive database");
+            let manifest = custody::export(&sink, *from_tick, *to_tick, &operator, output.as_ref()).map_err(|e| e.to_string())?;
+
+            Ok(format!("Custody export of {} ticks with digest {}", manifest.ticks.len(), manifest.digest))
+        },
+        Command::CustodyVerify { input, operator } => {
+            let operator: QubicId = operator.parse().expect("Invalid operator identity");
+            let manifest = custody::verify(input.as_ref(), &operator).map_err(|e| e.to_string())?;
+
+            Ok(format!("Custody export of ticks {} to {} ({} ticks) is signed by {operator}", manifest.from_tick, manifest.to_tick, manifest.ticks.len()))
+        }
+    }
+}
+
+/// response to a v1 request with the backend which served it and how
+type ServedRequest = (StatusCode, &'static str, QubicJsonRpcResponse, Diagnostics);
+
+struct ServerState {
+    args: Args,
+    ticks: TickWatcher,
+    stats: Option<StatsStore>,
+    monitor: Option<Arc<Mutex<ComputorMonitor>>>,
+    work: Option<WorkRelay>,
+    reads: Coalescer<ServedRequest>,
+    archive: Option<SledSink>,
+    rich_list_stats: Option<RichListStatsCache>,
+    webhooks: Option<Webhooks>,
+    ranking: Option<RankingCache>,
+    audit: Option<AuditLog>,
+    broadcasts: IdempotencyStore,
+    nonces: UsedNonces,
+    upstream: UpstreamProbe,
+    latest: LatestStatsCache,
+    scheduler: UpstreamScheduler,
+    fallback: Option<FallbackRpc>
+}
+
+impl ServerState {
+    fn new(args: Args) -> Self {
+        let scheduler = UpstreamScheduler::new(args.upstream_rps, args.background_share);
+        let ticks = TickWatcher::new(args.computor.clone(), Duration::from_millis(args.tick_poll_interval), scheduler.clone());
+        let stats = args.stats_db.as_ref().map(|path| StatsStore::open(path).expect("Failed to open stats database"));
+        let monitor = args.monitor_window.map(|window| Arc::new(Mutex::new(
+            ComputorMonitor::new(window).on_alert(|alert| warn!("Computor alert: {alert:?}"))
+        )));
+
+        let work = args.work_relay_seed.as_ref().map(|seed| WorkRelay::new(
+            QubicWallet::from_seed(seed).expect("Invalid work relay seed"),
+            Duration::from_secs(args.work_interval)
+        ));
+
+        let reads = Coalescer::new(Duration::from_millis(args.read_cache_ttl));
+        let db = args.archive_db.as_ref().map(|path| sled::open(path).expect("Failed to open archive database"));
+        let archive = db.as_ref().map(|db| SledSink::from_db(db).expect("Failed to open archive database"));
+        let rich_list_stats = archive.as_ref().map(|_| RichListStatsCache::default());
+        let webhooks = db.as_ref().map(|db| Webhooks::new(
+            WebhookStore::from_db(db).expect("Failed to open webhooks")
+                .with_limits(args.webhook_max, args.webhook_max_per_identity)
+                .with_private_addresses(args.webhook_allow_private),
+            args.webhook_max_failures,
+            Duration::from_millis(args.webhook_backoff)
+        ));
+
+        let ranking = args.operator_seed.as_ref().map(|seed| RankingCache::new(
+            QubicWallet::from_seed(seed).expect("Invalid operator seed"),
+            args.ranking_viewer.clone()
+        ));
+
+        let audit = args.audit_log.as_ref().map(|path| AuditLog::open(path).expect("Failed to open audit log"));
+
+        // without an archive database replays are only detected until the server restarts
+        let broadcasts_db = db.unwrap_or_else(|| sled::Config::new().temporary(true).open().expect("Failed to open temporary database"));
+        let broadcasts = IdempotencyStore::from_db(&broadcasts_db, Duration::from_secs(args.idempotency_ttl)).expect("Failed to open idempotency keys");
+
+        let latest = LatestStatsCache::new(args.price_url.clone());
+        let fallback = args.fallback_rpc.as_deref().map(FallbackRpc::new);
+
+        Self { args, ticks, stats, monitor, work, reads, archive, rich_list_stats, webhooks, ranking, audit, broadcasts, nonces: UsedNonces::default(), upstream: UpstreamProbe::default(), latest, scheduler, fallback }
+    }
+
+    /// client of the computor for an API request, once the upstream scheduler granted its turn
+    async fn interactive_client(&self) -> Result<Client<Tcp>, ClientError> {
+        self.scheduler.acquire(Priority::Interactive).await;
+        computor_client(&self.args.computor).await
+    }
 }
 
 #[tokio::main]
@@ -31,30 +414,143 @@ async fn main() {
 
     let args = Args::parse();
 
+    if let Some(proxy) = &args.proxy {
+        COMPUTOR_PROXY.set(proxy.clone()).expect("Proxy is set once");
+    }
+
+    if let Some(dir) = &args.wire_dump_dir {
+        let dump = wire_dump(dir, args.wire_dump_max_payload).expect("Failed to open wire dump");
+        COMPUTOR_WIRE_DUMP.set(dump).expect("Wire dump is set once");
+    }
+
+    if let Some(command) = &args.command {
+        match run_command(&args, command) {
+            Ok(summary) => info!("{summary}"),
+            Err(e) => {
+                error!("{e}");
+                std::process::exit(1);
+            }
+        }
+
+        return;
+    }
+
+    let state = Arc::new(ServerState::new(args));
+
+    if let Some(stats) = &state.stats {
+        let computors = std::iter::once(state.args.computor.clone()).chain(state.args.broadcast_peer.iter().cloned()).collect();
+        stats::spawn_sampler(stats.clone(), computors, Duration::from_secs(state.args.stats_interval), state.scheduler.clone());
+    }
+
+    if let Some(monitor) = &state.monitor {
+        health::spawn_monitor(monitor.clone(), state.args.computor.clone(), state.scheduler.clone());
+    }
+
+    if let Some(ranking) = &state.ranking {
+        ranking.spawn_refresher(state.args.computor.clone(), Duration::from_secs(state.args.ranking_interval), state.scheduler.clone());
+    }
+
+    if let (Some(rich_list_stats), Some(archive)) = (&state.rich_list_stats, &state.archive) {
+        rich_list_stats.spawn_refresher(archive.clone(), Duration::from_secs(state.args.rich_list_stats_interval));
+    }
+
+    state.broadcasts.spawn_pruner();
+
+    let mut archiver = Archiver::new(state.args.archive_queue).with_malformed(state.args.archive_malformed).with_scheduler(state.scheduler.clone());
+
+    let mut archive_from_tick = state.args.archive_from_tick;
+
+    if let Some(sink) = &state.archive {
+        if archive_from_tick.is_none() {
+            archive_from_tick = sink.cursor().expect("Failed to read archive cursor").map(|cursor| cursor + 1);
+        }
+
+        archiver = archiver.with_sink(sink.clone());
+    }
+
+    if let Some(webhooks) = &state.webhooks {
+        archiver = archiver.with_sink(webhooks.clone());
+    }
+
+    if let Some(path) = &state.args.archive_csv {
+        archiver = archiver.with_sink(CsvSink::create(path).expect("Failed to create archive CSV file"));
+    }
+
+    if archiver.has_sinks() {
+        let passcode = state.args.log_passcode.as_ref().map(|passcode| passcode.as_slice().try_into().expect("Logging passcode has to consist of four numbers"));
+        tokio::spawn(archiver.run(state.args.computor.clone(), archive_from_tick, Duration::from_millis(state.args.tick_poll_interval), passcode));
+    }
+
+    info!("Binding server to port {}", state.args.port);
+    let tcp_listener = TcpListener::bind(&format!("0.0.0.0:{}", state.args.port)).await.unwrap();
+    axum::serve(tcp_listener, router(state).into_make_service_with_connect_info::<SocketAddr>()).await.unwrap();
+}
+
+fn router(state: Arc<ServerState>) -> Router {
     let cors = CorsLayer::new()
-                        .allow_methods([Method::POST])
+                        .allow_methods([Method::GET, Method::POST])
                         .allow_origin(Any)
                         .allow_headers(Any);
 
-    let state = Arc::new(args);
+    let mut app = Router::new()
+                    .route("/", post(versioned_request_handler))
+                    .route("/v2", post(v2_json_handler))
+                    .route("/v1/auth/verify", post(auth_verify_handler))
+                    .route("/v1/healthcheck", get(healthcheck_handler))
+                    .route("/v1/computors/health", get(computors_health_handler))
+                    .route("/v1/submit-work", post(submit_work_handler))
+                    .route("/v1/metrics", get(metrics_handler))
+                    .route("/v1/mining/ranking", get(mining_ranking_handler))
+                    .route("/v1/identities/:id/diff", get(balance_diff_handler))
+                    .route("/v1/identities/resolve", get(resolve_identity_handler))
+                    .route("/v2/identities/:id/transactions", get(identity_transactions_handler))
+                    .route("/v1/rich-list", get(rich_list_handler))
+                    .route("/v1/rich-list/stats", get(rich_list_stats_handler))
+                    .route("/v1/archive/gaps", get(archive_gaps_handler))
+                    .route("/v1/tx-status/:tx_id", get(tx_status_handler))
+                    .route("/v1/ticks/latest-finalized", get(latest_finalized_handler))
+                    .route("/v1/ticks/next", get(next_tick_handler))
+                    .route("/v1/latest-stats", get(latest_stats_handler))
+                    .route("/v1/network/stats/history", get(network_stats_history_handler))
+                    .route("/v1/network/stats/latest", get(network_stats_latest_handler))
+                    .route("/v1/epochs/stats", get(epochs_stats_handler))
+                    .route("/v1/epochs/:epoch/stats", get(epoch_stats_handler))
+                    .route("/v1/epochs/:epoch/computors", get(epoch_computors_handler))
+                    .route("/v1/simulate-transfer", post(simulate_transfer_handler))
+                    .route("/v1/assets/by-name/:name", get(asset_by_name_handler))
+                    .route("/v1/webhooks", post(register_webhook_handler))
+                    .route("/v1/webhooks/:id", get(webhook_handler).delete(delete_webhook_handler))
+                    .route("/v1/webhooks/:id/reactivate", post(reactivate_webhook_handler))
+                    .route("/v1/admin/audit", get(audit_handler));
+
+    if state.args.docs {
+        app = app.merge(SwaggerUi::new("/docs").url("/api-docs/openapi.json", docs::ApiDoc::openapi()));
+    }
 
-    let app = Router::new().route("/", post(request_handler)).with_state(state.clone()).layer(cors);
+    app.with_state(state).layer(axum::middleware::from_fn(numbers::number_format)).layer(axum::middleware::from_fn(panics::catch_panic)).layer(cors)
+}
 
-    info!("Binding server to port {}", state.port);
-    let tcp_listener = TcpListener::bind(&format!("0.0.0.0:{}", state.port)).await.unwrap();
-    axum::serve(tcp_listener, app.into_make_service()).await.unwrap();
+fn error_status(e: &ClientError) -> StatusCode {
+    match e {
+        ClientError::Timeout => StatusCode::GATEWAY_TIMEOUT,
+        ClientError::InvalidInput(_) | ClientError::StaleTick { .. } => StatusCode::BAD_REQUEST,
+        ClientError::Io(_) | ClientError::PeerClosed | ClientError::PeerBusy { .. } | ClientError::Decode(_) | ClientError::UnexpectedMessageType { .. } | ClientError::BroadcastFailed { .. } | ClientError::CommandRejected(_) | ClientError::ResponseTooLarge { .. } => StatusCode::BAD_GATEWAY
+    }
 }
 
-macro_rules! result_or_501 {
+macro_rules! result_or_error {
     ($handle: expr, $rpc_method: expr) => {
         match $handle {
             Ok(res) => res,
-            Err(_) => {
-                return Json(QubicJsonRpcResponse {
+            Err(e) => {
+                warn!("Request failed: {e}");
+
+                return (error_status(&e), Json(QubicJsonRpcResponse {
                     jsonrpc: "2.0".to_owned(),
                     id: $rpc_method.id,
-                    response: ResponseType::Error(RequestError { method: $rpc_method.request.get_method(), error: "InternalServerError".to_owned() })
-                })
+                    response: ResponseType::Error(RequestError { method: $rpc_method.request.get_method(), error: e.to_string() }),
+                    diagnostics: None
+                }))
             }
         }
     };
@@ -62,76 +558,2572 @@ macro_rules! result_or_501 {
 
 macro_rules! early_return_result {
     ($res_type: expr, $rpc_method: expr) => {
-        return Json(QubicJsonRpcResponse {
+        return (StatusCode::OK, Json(QubicJsonRpcResponse {
             jsonrpc: "2.0".to_owned(),
             id: $rpc_method.id,
-            response: ResponseType::Result($res_type)
-        })
+            response: ResponseType::Result($res_type),
+            diagnostics: None
+        }))
     };
 }
 
-async fn request_handler(State(state): State<Arc<Args>>, Json(rpc_method): Json<QubicJsonRpcRequest>) -> Json<QubicJsonRpcResponse> {
-    info!("Incoming request: {rpc_method:?}");
+/// whether `DEBUG_HEADER` is set
+fn debug_requested(headers: &HeaderMap) -> bool {
+    headers.get(DEBUG_HEADER).is_some_and(|value| !matches!(value.as_bytes(), b"0" | b"false"))
+}
 
-    if rpc_method.jsonrpc.as_str() != "2.0" {
-        return Json(QubicJsonRpcResponse {
-            jsonrpc: "2.0".to_owned(),
-            id: rpc_method.id,
-            response: ResponseType::Error(RequestError { method: rpc_method.request.get_method(), error: "Invalid JSON-RPC version found".to_owned() })
-        })
+fn idempotency_key(headers: &HeaderMap) -> Option<String> {
+    headers.get(IDEMPOTENCY_HEADER).and_then(|value| value.to_str().ok()).map(str::to_owned)
+}
+
+/// diagnostics of a request served by `peer` after `attempts` upstreams were requested since `started`
+fn upstream_diagnostics(started: Instant, peer: &str, attempts: u32) -> Diagnostics {
+    Diagnostics { upstream_latency_ms: Some(started.elapsed().as_millis() as u64), upstream_peer: Some(peer.to_owned()), attempts, served_from_cache: false }
+}
+
+/// JSON-RPC error for a method unknown to every schema, serde would only reject it as an unknown variant
+fn unknown_method(body: &serde_json::Value) -> Option<Response> {
+    let method = body.get("method")?;
+
+    if serde_json::from_value::<v2::Methods>(method.clone()).is_ok() {
+        return None
     }
 
-    let client = Client::<Tcp>::new(&state.computor).await.unwrap();
+    let mut response = serde_json::json!({ "jsonrpc": "2.0", "id": body.get("id"), "method": method, "error": format!("Unknown method {method}") });
 
-    match rpc_method.request {
-        RequestMethods::RequestComputors => {
-            let res = result_or_501!(client.qu().request_computors().await, rpc_method);
+    if let Some(version) = body.get("version") {
+        response["version"] = version.clone();
+    }
 
-            early_return_result!(RequestResults::RequestComputors(res.into()), rpc_method);
-        },
-        RequestMethods::RequestCurrentTickInfo => {
-            let res = result_or_501!(client.qu().get_current_tick_info().await, rpc_method);
+    Some((StatusCode::BAD_REQUEST, [(SOURCE_HEADER, "computor")], Json(response)).into_response())
+}
 
-            early_return_result!(RequestResults::RequestCurrentTickInfo(res), rpc_method);
+/// parses the request with the schema selected by its `version` field (default v1)
+#[utoipa::path(
+    post,
+    path = "/",
+    params(
+        ("x-qubic-debug" = Option<bool>, Header, description = "Answers with diagnostics of how the request was served"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries of a broadcast with the same key and source identity are answered with the first result")
+    ),
+    request_body = docs::RpcRequest,
+    responses(
+        (status = 200, description = "Result of the method", body = docs::RpcResponse, headers(("x-qubic-source" = String, description = "Backend which served the request"))),
+        (status = 400, description = "Unknown method", body = docs::UnknownMethod),
+        (status = 409, description = "Idempotency-Key was used for another transaction of the source or the first broadcast is in flight", body = docs::RpcResponse),
+        (status = 422, description = "Malformed request", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor or fallback RPC failed, the error is reported in the body", body = docs::RpcResponse)
+    )
+)]
+async fn versioned_request_handler(state: State<Arc<ServerState>>, connect_info: Option<ConnectInfo<SocketAddr>>, headers: HeaderMap, Json(body): Json<serde_json::Value>) -> Response {
+    let invalid_request = |e: serde_json::Error| (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response();
+
+    let client = connect_info.map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(res) = unknown_method(&body) {
+        return res
+    }
+
+    match VersionedRequest::deserialize(&body).map(|versioned| versioned.version) {
+        Ok(Version::V1) => match serde_json::from_value::<QubicJsonRpcRequest>(body) {
+            Ok(mut request) => {
+                request.debug |= debug_requested(&headers);
+
+                if let Some(res) = streamed_request(&state, &request).await {
+                    return res
+                }
+
+                // the handler futures are boxed, in debug builds they span most of the stack of the worker otherwise
+                let (status, response_headers, Json(res)) = Box::pin(request_handler(state, client, idempotency_key(&headers), Json(request))).await;
+                stream::v1_response(status, response_headers, res)
+            },
+            Err(e) => invalid_request(e)
         },
-        RequestMethods::RequestEntity(id) => {
-            let res = result_or_501!(client.qu().request_entity(id).await, rpc_method);
+        Ok(Version::V2) => match serde_json::from_value::<v2::QubicJsonRpcRequest>(body) {
+            Ok(mut request) => {
+                request.debug |= debug_requested(&headers);
+                let (status, response_headers, Json(res)) = Box::pin(v2_request_handler(state, client, idempotency_key(&headers), Json(request))).await;
+                stream::v2_response(status, response_headers, res)
+            },
+            Err(e) => invalid_request(e)
+        },
+        Err(e) => invalid_request(e)
+    }
+}
+
+/// streams `requestTickTransactions` from the computor to the client as the transactions are received, `None` for
+/// requests served through `request_handler`: other methods, debug requests and servers with a fallback RPC, which
+/// needs the whole result of the computor to decide whether to fall back. Streamed requests are not coalesced
+async fn streamed_request(state: &ServerState, request: &QubicJsonRpcRequest) -> Option<Response> {
+    let RequestMethods::RequestTickTransactions(tick) = request.request else { return None };
 
-            early_return_result!(RequestResults::RequestEntity(res.entity), rpc_method);
+    if request.jsonrpc != "2.0" || request.debug || state.fallback.is_some() {
+        return None
+    }
+
+    info!("Incoming request: {request:?}");
+
+    let error = |e: ClientError| {
+        warn!("Request failed: {e}");
+
+        (error_status(&e), [(SOURCE_HEADER, "computor")], Json(QubicJsonRpcResponse {
+            jsonrpc: "2.0".to_owned(),
+            id: request.id,
+            response: ResponseType::Error(RequestError { method: request.request.get_method(), error: e.to_string() }),
+            diagnostics: None
+        })).into_response()
+    };
+
+    Some(match state.interactive_client().await {
+        Ok(client) => stream::v1_tick_transactions(client, request.id, tick, [(SOURCE_HEADER, "computor")], error).await,
+        Err(e) => error(e)
+    })
+}
+
+/// parses the request with the v2 schema regardless of its `version` field
+#[utoipa::path(
+    post,
+    path = "/v2",
+    params(
+        ("x-qubic-debug" = Option<bool>, Header, description = "Answers with diagnostics of how the request was served"),
+        ("Idempotency-Key" = Option<String>, Header, description = "Retries of a broadcast with the same key and source identity are answered with the first result")
+    ),
+    request_body = v2::QubicJsonRpcRequest,
+    responses(
+        (status = 200, description = "Result of the method", body = v2::QubicJsonRpcResponse, headers(("x-qubic-source" = String, description = "Backend which served the request"))),
+        (status = 400, description = "Unknown method", body = docs::UnknownMethod),
+        (status = 409, description = "Idempotency-Key was used for another transaction of the source or the first broadcast is in flight", body = v2::QubicJsonRpcResponse),
+        (status = 422, description = "Malformed request", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor or fallback RPC failed, the error is reported in the body", body = v2::QubicJsonRpcResponse)
+    )
+)]
+async fn v2_json_handler(state: State<Arc<ServerState>>, connect_info: Option<ConnectInfo<SocketAddr>>, headers: HeaderMap, Json(body): Json<serde_json::Value>) -> Response {
+    let client = connect_info.map(|ConnectInfo(addr)| addr.ip());
+
+    if let Some(res) = unknown_method(&body) {
+        return res
+    }
+
+    match serde_json::from_value::<v2::QubicJsonRpcRequest>(body) {
+        Ok(mut request) => {
+            request.debug |= debug_requested(&headers);
+            let (status, response_headers, Json(res)) = Box::pin(v2_request_handler(state, client, idempotency_key(&headers), Json(request))).await;
+            stream::v2_response(status, response_headers, res)
         },
-        RequestMethods::SendTransaction(tx) => {
-            result_or_501!(client.qu().send_signed_transaction(tx).await, rpc_method);
+        Err(e) => (StatusCode::UNPROCESSABLE_ENTITY, e.to_string()).into_response()
+    }
+}
+
+/// verifies a signed challenge against the configured audience
+#[utoipa::path(
+    post,
+    path = "/v1/auth/verify",
+    request_body = SignedChallenge,
+    responses(
+        (status = 200, description = "Challenge is valid", body = AuthVerification),
+        (status = 401, description = "Challenge is expired, issued for another audience or not signed by its identity", body = AuthVerification),
+        (status = 501, description = "Server was started without --auth-audience", body = AuthVerification)
+    )
+)]
+async fn auth_verify_handler(State(state): State<Arc<ServerState>>, Json(challenge): Json<SignedChallenge>) -> (StatusCode, Json<AuthVerification>) {
+    let verification = |status: StatusCode, error: Option<String>| (status, Json(AuthVerification { identity: challenge.identity, valid: error.is_none(), error }));
+
+    let Some(audience) = &state.args.auth_audience else {
+        return verification(StatusCode::NOT_IMPLEMENTED, Some("Authentication is not configured".to_owned()))
+    };
+
+    match verify_challenge(&state, audience, &challenge) {
+        Ok(()) => verification(StatusCode::OK, None),
+        Err(e) => {
+            info!("Rejected challenge of {}: {e}", challenge.identity);
+            verification(StatusCode::UNAUTHORIZED, Some(e))
+        }
+    }
+}
+
+/// verifies the challenge and records its nonce, every challenge is accepted once
+fn verify_challenge(state: &ServerState, audience: &str, challenge: &SignedChallenge) -> Result<(), String> {
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs();
+
+    challenge.verify(audience, now, state.args.auth_max_age, state.args.auth_max_skew).map_err(|e| e.to_string())?;
+
+    if !state.nonces.claim(challenge.identity, challenge.nonce, challenge.timestamp.saturating_add(state.args.auth_max_age), now) {
+        return Err("Challenge was used before".to_owned())
+    }
+
+    Ok(())
+}
+
+/// identity of the challenge in `CHALLENGE_HEADER`, verified against the configured audience
+fn authenticate(state: &ServerState, headers: &HeaderMap) -> Result<QubicId, (StatusCode, String)> {
+    let Some(audience) = &state.args.auth_audience else {
+        return Err((StatusCode::NOT_IMPLEMENTED, "Authentication is not configured, start the server with --auth-audience".to_owned()))
+    };
+
+    let challenge: SignedChallenge = headers.get(CHALLENGE_HEADER)
+        .and_then(|value| serde_json::from_slice(value.as_bytes()).ok())
+        .ok_or_else(|| (StatusCode::UNAUTHORIZED, format!("Expected a signed challenge as JSON in the {CHALLENGE_HEADER} header")))?;
+
+    verify_challenge(state, audience, &challenge).map_err(|e| {
+        info!("Rejected challenge of {}: {e}", challenge.identity);
+        (StatusCode::UNAUTHORIZED, e)
+    })?;
+
+    Ok(challenge.identity)
+}
+
+/// mining score ranking of the computor, requested every --ranking-interval seconds with the operator seed
+#[utoipa::path(
+    get,
+    path = "/v1/mining/ranking",
+    params(("x-qubic-challenge" = String, Header, description = "Signed challenge as JSON, see /v1/auth/verify")),
+    responses(
+        (status = 200, description = "Last requested ranking", body = MiningRanking),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 403, description = "Identity is not a --ranking-viewer", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --operator-seed or --auth-audience", body = String, content_type = "text/plain"),
+        (status = 503, description = "Ranking was not requested successfully yet", body = String, content_type = "text/plain")
+    )
+)]
+async fn mining_ranking_handler(State(state): State<Arc<ServerState>>, headers: HeaderMap) -> Response {
+    let Some(ranking) = &state.ranking else {
+        return (StatusCode::NOT_IMPLEMENTED, "Mining score ranking is not served, start the server with --operator-seed").into_response()
+    };
+
+    let identity = match authenticate(&state, &headers) {
+        Ok(identity) => identity,
+        Err(e) => return e.into_response()
+    };
+
+    if !ranking.is_viewer(&identity) {
+        return (StatusCode::FORBIDDEN, format!("{identity} may not read the mining score ranking")).into_response()
+    }
+
+    match ranking.get() {
+        Some(ranking) => Json(ranking).into_response(),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "Mining score ranking was not received from the computor yet").into_response()
+    }
+}
+
+/// health of the server for load balancers, fails if the computor stopped answering, the archive falls behind or its
+/// database cannot be read
+#[utoipa::path(
+    get,
+    path = "/v1/healthcheck",
+    responses(
+        (status = 200, description = "Every check passed", body = HealthCheck),
+        (status = 503, description = "A check failed", body = HealthCheck)
+    )
+)]
+async fn healthcheck_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let thresholds = HealthThresholds {
+        max_upstream_age: Duration::from_secs(state.args.health_max_upstream_age),
+        max_archive_lag: state.args.health_max_archive_lag
+    };
+
+    let upstream = state.upstream.latest(state.interactive_client(), thresholds.max_upstream_age).await;
+    let check = health::check(upstream, state.archive.as_ref().map(SledSink::cursor), &thresholds);
+    let status = if check.status { StatusCode::OK } else { StatusCode::SERVICE_UNAVAILABLE };
+
+    (status, Json(check)).into_response()
+}
+
+/// votes of every computor over the monitored window
+#[utoipa::path(
+    get,
+    path = "/v1/computors/health",
+    responses(
+        (status = 200, description = "Monitored window", body = ComputorsHealth),
+        (status = 501, description = "Server was started without --monitor-window", body = String, content_type = "text/plain")
+    )
+)]
+async fn computors_health_handler(State(state): State<Arc<ServerState>>) -> Response {
+    match &state.monitor {
+        Some(monitor) => Json(health::report(&monitor.lock().unwrap())).into_response(),
+        None => (StatusCode::NOT_IMPLEMENTED, "Computors are not monitored, start the server with --monitor-window").into_response()
+    }
+}
+
+/// relays a mining solution for the current random seed to the computor
+#[utoipa::path(
+    post,
+    path = "/v1/submit-work",
+    request_body = SubmitWork,
+    responses(
+        (status = 200, description = "Solution was relayed at the tick", body = SubmittedWork),
+        (status = 400, description = "Seed or nonce is malformed", body = String, content_type = "text/plain"),
+        (status = 403, description = "Client address is unknown", body = String, content_type = "text/plain"),
+        (status = 409, description = "Solution is for a stale random seed", body = String, content_type = "text/plain"),
+        (status = 429, description = "Client submitted a valid solution less than --work-interval seconds ago", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --work-relay-seed", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn submit_work_handler(State(state): State<Arc<ServerState>>, connect_info: Option<ConnectInfo<SocketAddr>>, Json(work): Json<SubmitWork>) -> Response {
+    let Some(relay) = &state.work else {
+        return (StatusCode::NOT_IMPLEMENTED, "Mining solutions are not relayed, start the server with --work-relay-seed").into_response()
+    };
 
-            early_return_result!(RequestResults::SendTransaction(tx.into()), rpc_method);
+    let client = connect_info.map(|ConnectInfo(addr)| addr.ip());
+
+    match relay.submit(state.interactive_client(), &work, client).await {
+        Ok(submitted) => match audit(&state, AuditEntry::work(&submitted, client, state.args.computor.clone())).await {
+            Ok(()) => Json(submitted).into_response(),
+            Err(e) => e.into_response()
         },
-        RequestMethods::RequestTickTransactions(tick) => {
-            let res = result_or_501!(client.qu().request_tick_transactions(tick, TransactionFlags::all()).await, rpc_method);
+        Err(e) => {
+            info!("Rejected solution of {}: {e}", work.identity);
+            (e.status(), e.to_string()).into_response()
+        }
+    }
+}
 
-            early_return_result!(RequestResults::RequestTickTransactions(res), rpc_method);
+/// counters of the coalesced read requests, of the requests busy computors redirected and of the turns the upstream
+/// scheduler handed out
+#[utoipa::path(
+    get,
+    path = "/v1/metrics",
+    responses((status = 200, description = "Counters since the server started", body = CoalescingMetrics))
+)]
+async fn metrics_handler(State(state): State<Arc<ServerState>>) -> Json<CoalescingMetrics> {
+    Json(CoalescingMetrics { peer_redirects: PEER_REDIRECTS.load(Ordering::Relaxed), upstream_scheduler: state.scheduler.metrics(), ..state.reads.metrics() })
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct DiffRange {
+    from_tick: u32,
+    to_tick: u32,
+    /// only lists the incoming transfers tagged with this memo, e.g. the deposits of a user of an exchange. The deltas
+    /// still cover every transaction. Memos are a convention, not enforced by the network
+    memo: Option<String>
+}
+
+/// changes of the identity between two ticks, reconstructed from the archive
+#[utoipa::path(
+    get,
+    path = "/v1/identities/{id}/diff",
+    params(("id" = String, Path, description = "Identity in any case"), DiffRange),
+    responses(
+        (status = 200, description = "Balance and transfer changes with the archived transactions in between", body = BalanceDiff),
+        (status = 400, description = "Malformed identity or from_tick exceeds to_tick", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "No entity of the identity is archived and the computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn balance_diff_handler(State(state): State<Arc<ServerState>>, ParsedIdentity(id): ParsedIdentity, Query(range): Query<DiffRange>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    match diff::balance_diff(archive, state.interactive_client(), id, range.from_tick, range.to_tick).await {
+        Ok(mut diff) => {
+            if let Some(memo) = &range.memo {
+                diff::retain_deposits(&mut diff, memo);
+            }
+
+            Json(diff).into_response()
+        },
+        Err(e) => {
+            warn!("Balance diff of {id} failed: {e}");
+            (e.status(), e.to_string()).into_response()
         }
     }
 }
 
-#[tokio::test]
-async fn test() {
-    use std::str::FromStr;
-    use qubic_types::QubicId;
-    const RPC: &str = "http://127.0.0.1:2003/";
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct ResolveQuery {
+    /// identity, transaction hash or public key in hex, as pasted by a user
+    q: String
+}
 
-    let client = reqwest::Client::new();
-    let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestEntity(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()));
-    let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
-    dbg!(res);
+/// recognizes an identity, transaction hash or public key, a mistyped identity is answered with the corrections
+/// matching its checksum. Neither is picked if several match
+#[utoipa::path(
+    get,
+    path = "/v1/identities/resolve",
+    params(ResolveQuery),
+    responses(
+        (status = 200, description = "What the input was recognized as", body = ResolvedInput),
+        (status = 400, description = "Neither an identity, a transaction hash nor a public key", body = String, content_type = "text/plain")
+    )
+)]
+async fn resolve_identity_handler(Query(query): Query<ResolveQuery>) -> Response {
+    match resolve::resolve(&query.q) {
+        Ok(resolved) => Json(resolved).into_response(),
+        Err(e) => (StatusCode::BAD_REQUEST, e).into_response()
+    }
+}
 
-    let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestCurrentTickInfo);
-    let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
-    dbg!(&res);
+/// Transactions of an identity page
+const MAX_TRANSACTIONS_PAGE_SIZE: u32 = 1000;
 
-    if let ResponseType::Result(r) = res.response {
-        if let RequestResults::RequestCurrentTickInfo(current_tick) = r {
-            let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestTickTransactions(current_tick.tick - 10));
-            let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
-            dbg!(&res);
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct IdentityTransactionsQuery {
+    /// first tick of the range, defaults to the first tick
+    from_tick: Option<u32>,
+    /// last tick of the range, defaults to the last tick
+    to_tick: Option<u32>,
+    /// page starting at 1, defaults to 1
+    page: Option<u32>,
+    /// transactions of a page, defaults to 100 and is capped at 1000
+    page_size: Option<u32>
+}
+
+/// archived transactions the identity sent or received in the tick range, of every kind and also without amount.
+/// Served from the identity index of the archive
+#[utoipa::path(
+    get,
+    path = "/v2/identities/{id}/transactions",
+    params(("id" = String, Path, description = "Identity in any case"), IdentityTransactionsQuery),
+    responses(
+        (status = 200, description = "Page of the transactions in ascending tick order with the number of transactions in the range", body = TransactionsResponse),
+        (status = 400, description = "Malformed identity or from_tick exceeds to_tick", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn identity_transactions_handler(State(state): State<Arc<ServerState>>, ParsedIdentity(id): ParsedIdentity, Query(query): Query<IdentityTransactionsQuery>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    let (from_tick, to_tick) = (query.from_tick.unwrap_or(0), query.to_tick.unwrap_or(u32::MAX));
+
+    if from_tick > to_tick {
+        return (StatusCode::BAD_REQUEST, format!("from_tick {from_tick} exceeds to_tick {to_tick}")).into_response()
+    }
+
+    let page = query.page.unwrap_or(1).max(1);
+    let page_size = query.page_size.unwrap_or(100).clamp(1, MAX_TRANSACTIONS_PAGE_SIZE);
+    let skip = (page as usize - 1) * page_size as usize;
+
+    match archive.identity_transactions(&id, from_tick, to_tick, skip, page_size as usize) {
+        Ok((total, transactions)) => {
+            let transactions = transactions.into_iter()
+                .map(|tx| {
+                    let raw = &tx.transaction.raw_transaction;
+
+                    IdentityTransaction {
+                        tick: tx.tick,
+                        hash: QubicTxHash::from(&tx.transaction),
+                        from: raw.from,
+                        to: raw.to,
+                        amount: raw.amount,
+                        input_type: raw.input_type,
+                        kind: tx.transaction.data.name().to_owned(),
+                        money_flew: tx.money_flew,
+                        memo: printable_memo(&tx.transaction.data)
+                    }
+                })
+                .collect();
+
+            Json(TransactionsResponse { identity: id, page, page_size, total, transactions }).into_response()
+        },
+        Err(e) => {
+            warn!("Transactions of {id} failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// ticks of the range which are not archived, classified by whether the computor has tick data of them
+#[utoipa::path(
+    get,
+    path = "/v1/archive/gaps",
+    params(DiffRange),
+    responses(
+        (status = 200, description = "Unarchived ticks with and without tick data and the ones unknown to the computor", body = ArchiveGaps),
+        (status = 400, description = "from_tick exceeds to_tick or the range exceeds 100000 ticks", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor or archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn archive_gaps_handler(State(state): State<Arc<ServerState>>, Query(range): Query<DiffRange>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    let client = match state.interactive_client().await {
+        Ok(client) => client,
+        Err(e) => return (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+    };
+
+    match gaps::archive_gaps(archive, &client, range.from_tick, range.to_tick).await {
+        Ok(gaps) => Json(gaps).into_response(),
+        Err(e) => {
+            warn!("Archive gaps of {}..={} failed: {e}", range.from_tick, range.to_tick);
+            (e.status(), e.to_string()).into_response()
         }
     }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct TxStatusQuery {
+    /// tick the transaction targets
+    tick: u32
+}
+
+/// status of the transaction in its target tick, answered from the archive if the tick is archived
+#[utoipa::path(
+    get,
+    path = "/v1/tx-status/{tx_id}",
+    params(("tx_id" = String, Path, description = "Transaction hash in any case"), TxStatusQuery),
+    responses(
+        (status = 200, description = "Pending until the tick passed, then whether the tick includes the transaction and whether the tick is final", body = TransactionStatusReport),
+        (status = 400, description = "Malformed transaction hash", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Tick is not archived and the computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn tx_status_handler(State(state): State<Arc<ServerState>>, ParsedTxHash(tx_id): ParsedTxHash, Query(query): Query<TxStatusQuery>) -> Response {
+    let client = match state.interactive_client().await {
+        Ok(client) => client,
+        Err(e) => return (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+    };
+
+    if let Some(archive) = &state.archive {
+        match archive.transaction_status(&tx_id, query.tick) {
+            Ok(Some(status)) => {
+                let finalized = tick_finalized(&state, &client, query.tick).await;
+
+                return ([(SOURCE_HEADER, "archive")], Json(TransactionStatusReport { status, finalized })).into_response()
+            },
+            Ok(None) => (),
+            Err(e) => warn!("Archived status of {tx_id} failed: {e}")
+        }
+    }
+
+    match client.qu().check_transaction_status(tx_id, query.tick).await {
+        Ok(status) => {
+            // a tick which has not passed yet cannot be final
+            let finalized = !matches!(status, TransactionStatus::Pending { .. }) && tick_finalized(&state, &client, query.tick).await;
+
+            ([(SOURCE_HEADER, "computor")], Json(TransactionStatusReport { status, finalized })).into_response()
+        },
+        Err(e) => {
+            warn!("Status of {tx_id} failed: {e}");
+            (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+        }
+    }
+}
+
+/// latest archived tick a quorum of computors voted for
+#[utoipa::path(
+    get,
+    path = "/v1/ticks/latest-finalized",
+    responses(
+        (status = 200, description = "Latest finalized tick and its epoch", body = LatestFinalizedTick),
+        (status = 404, description = "No archived tick is finalized yet", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn latest_finalized_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    match archive.latest_finalized() {
+        Ok(Some(tick_data)) => Json(LatestFinalizedTick { tick: tick_data.tick, epoch: tick_data.epoch }).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No archived tick is finalized yet").into_response(),
+        Err(e) => {
+            warn!("Latest finalized tick failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct NextTickQuery {
+    /// tick the current tick has to exceed
+    after: u32,
+    /// seconds the request is held open at most, 30 by default and capped at 60
+    timeout: Option<u64>
+}
+
+/// holds the request until the current tick exceeds `after`, all waiters share one poll of the computor
+#[utoipa::path(
+    get,
+    path = "/v1/ticks/next",
+    params(NextTickQuery),
+    responses(
+        (status = 200, description = "Tick info once the tick advanced, or the unchanged tick with `changed: false` if the timeout elapsed first", body = NextTick),
+        (status = 504, description = "No tick info received from the computor", body = String, content_type = "text/plain")
+    )
+)]
+async fn next_tick_handler(State(state): State<Arc<ServerState>>, Query(query): Query<NextTickQuery>) -> Response {
+    let timeout = query.timeout.map(Duration::from_secs).unwrap_or(ticks::DEFAULT_WAIT);
+
+    match state.ticks.wait_for_next_tick(query.after, timeout).await {
+        Some(next) => ([(SOURCE_HEADER, "computor")], Json(next)).into_response(),
+        None => (StatusCode::GATEWAY_TIMEOUT, [(SOURCE_HEADER, "computor")], "No tick info received from computor").into_response()
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct StatsRange {
+    from_tick: u32,
+    to_tick: u32,
+    /// width of the buckets in ticks, the latest sample of every bucket is returned. 1 by default
+    step: Option<u32>
+}
+
+/// `SystemInfo` samples of the range, downsampled to the latest sample of every `step` ticks
+#[utoipa::path(
+    get,
+    path = "/v1/network/stats/history",
+    params(StatsRange),
+    responses(
+        (status = 200, description = "Samples in ascending order of their tick", body = Vec<NetworkStats>),
+        (status = 400, description = "from_tick exceeds to_tick", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --stats-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Stats database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn network_stats_history_handler(State(state): State<Arc<ServerState>>, Query(range): Query<StatsRange>) -> Response {
+    let Some(stats) = &state.stats else {
+        return (StatusCode::NOT_IMPLEMENTED, "Network stats are not recorded, start the server with --stats-db").into_response()
+    };
+
+    if range.from_tick > range.to_tick {
+        return (StatusCode::BAD_REQUEST, "from_tick exceeds to_tick").into_response()
+    }
+
+    match stats.history(range.from_tick, range.to_tick, range.step.unwrap_or(1)) {
+        Ok(history) => Json(history).into_response(),
+        Err(e) => {
+            error!("Failed to read network stats: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// latest `SystemInfo` sample
+#[utoipa::path(
+    get,
+    path = "/v1/network/stats/latest",
+    responses(
+        (status = 200, description = "Latest sample", body = NetworkStats),
+        (status = 404, description = "No sample is recorded yet", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --stats-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Stats database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn network_stats_latest_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let Some(stats) = &state.stats else {
+        return (StatusCode::NOT_IMPLEMENTED, "Network stats are not recorded, start the server with --stats-db").into_response()
+    };
+
+    match stats.latest() {
+        Ok(Some(latest)) => Json(latest).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No network stats are recorded yet").into_response(),
+        Err(e) => {
+            error!("Failed to read network stats: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// statistics of the network at the latest archived tick, computed once per archived tick
+#[utoipa::path(
+    get,
+    path = "/v1/latest-stats",
+    responses(
+        (status = 200, description = "Tick quality of the epoch, circulating supply, active addresses, burned QU and the price if --price-url is set", body = LatestStats),
+        (status = 404, description = "No tick is archived yet", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn latest_stats_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    match state.latest.get(archive).await {
+        Ok(Some(stats)) => Json(stats).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, "No tick is archived yet").into_response(),
+        Err(e) => {
+            warn!("Latest stats failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// statistics of the archived transactions of the epoch
+#[utoipa::path(
+    get,
+    path = "/v1/epochs/{epoch}/stats",
+    params(("epoch" = u16, Path, description = "Epoch")),
+    responses(
+        (status = 200, description = "Transactions, transferred amount, QX volume and estimated active addresses of the epoch", body = EpochStats),
+        (status = 404, description = "No transaction of the epoch is archived", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn epoch_stats_handler(State(state): State<Arc<ServerState>>, Path(epoch): Path<u16>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    match archive.epoch_stats(epoch, epoch) {
+        Ok(stats) => match stats.first() {
+            Some(stats) => Json(*stats).into_response(),
+            None => (StatusCode::NOT_FOUND, format!("No transaction of epoch {epoch} is archived")).into_response()
+        },
+        Err(e) => {
+            warn!("Stats of epoch {epoch} failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// computor list of the epoch, archived lists are served from the archive and the current one from the computor
+#[utoipa::path(
+    get,
+    path = "/v1/epochs/{epoch}/computors",
+    params(("epoch" = u16, Path, description = "Epoch")),
+    responses(
+        (status = 200, description = "Computors of the epoch and whether the list is signed by the arbitrator", body = ComputorInfos),
+        (status = 404, description = "List of a past or future epoch which is not archived", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "List is not archived and the computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn epoch_computors_handler(State(state): State<Arc<ServerState>>, Path(epoch): Path<u16>) -> Response {
+    if let Some(archive) = &state.archive {
+        match archive.archived_computors(epoch) {
+            Ok(Some(computors)) => return ([(SOURCE_HEADER, "archive")], Json(computors)).into_response(),
+            Ok(None) => (),
+            Err(e) => {
+                warn!("Computors of epoch {epoch} failed: {e}");
+                return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+            }
+        }
+    }
+
+    let client = match state.interactive_client().await {
+        Ok(client) => client,
+        Err(e) => return (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+    };
+
+    match client.qu().request_computors().await {
+        Ok(computors) if computors.epoch == epoch => {
+            // the list of the current epoch is kept to serve it once the epoch ended
+            if let Some(Err(e)) = state.archive.as_ref().map(|archive| archive.insert_computors(&computors)) {
+                warn!("Storing the computors of epoch {epoch} failed: {e}");
+            }
+
+            ([(SOURCE_HEADER, "computor")], Json(ComputorInfos::from(*computors))).into_response()
+        },
+        Ok(computors) => (StatusCode::NOT_FOUND, [(SOURCE_HEADER, "computor")], format!("Computors of epoch {epoch} are not archived, the current epoch is {}", computors.epoch)).into_response(),
+        Err(e) => {
+            warn!("Computors of epoch {epoch} failed: {e}");
+            (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+        }
+    }
+}
+
+/// checks whether the unsigned transaction would likely execute against the balance of its source and the current tick
+#[utoipa::path(
+    post,
+    path = "/v1/simulate-transfer",
+    request_body = ExternalRawTransaction,
+    responses(
+        (status = 200, description = "Verdict with the result of every check, nothing is broadcast", body = TransferSimulation),
+        (status = 400, description = "Fields cannot be encoded", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn simulate_transfer_handler(State(state): State<Arc<ServerState>>, Json(raw_transaction): Json<ExternalRawTransaction>) -> Response {
+    let tx = match raw_transaction.unsigned() {
+        Ok(tx) => tx,
+        Err(e) => return (StatusCode::BAD_REQUEST, e.to_string()).into_response()
+    };
+
+    let client = match state.interactive_client().await {
+        Ok(client) => client,
+        Err(e) => return (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+    };
+
+    match client.qu().simulate_transfer(&tx).await {
+        Ok(simulation) => ([(SOURCE_HEADER, "computor")], Json(simulation)).into_response(),
+        Err(e) => {
+            warn!("Simulating a transfer of {} failed: {e}", tx.raw_transaction.from);
+            (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+        }
+    }
+}
+
+/// issuance of the asset named `name` looked up in the universe of the computor, with its units summed over its owners
+#[utoipa::path(
+    get,
+    path = "/v1/assets/by-name/{name}",
+    params(("name" = String, Path, description = "Asset name of 1 to 7 characters")),
+    responses(
+        (status = 200, description = "Issuer, units, decimals and unit of measurement of the asset", body = AssetSummary),
+        (status = 400, description = "Name is not a valid asset name", body = String, content_type = "text/plain"),
+        (status = 404, description = "No asset of that name is issued", body = String, content_type = "text/plain"),
+        (status = "5XX", description = "Computor failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn asset_by_name_handler(State(state): State<Arc<ServerState>>, Path(name): Path<String>) -> Response {
+    let client = match state.interactive_client().await {
+        Ok(client) => client,
+        Err(e) => return (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+    };
+
+    match client.qx().find_asset(&name).await {
+        Ok(Some(asset)) => ([(SOURCE_HEADER, "computor")], Json(asset)).into_response(),
+        Ok(None) => (StatusCode::NOT_FOUND, [(SOURCE_HEADER, "computor")], format!("No asset named {name} is issued")).into_response(),
+        Err(e) => {
+            warn!("Looking up the asset {name} failed: {e}");
+            (error_status(&e), [(SOURCE_HEADER, "computor")], e.to_string()).into_response()
+        }
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct EpochRange {
+    from: u16,
+    to: u16
+}
+
+/// statistics of the epochs of the range with archived transactions in ascending order
+#[utoipa::path(
+    get,
+    path = "/v1/epochs/stats",
+    params(EpochRange),
+    responses(
+        (status = 200, description = "Statistics of every epoch of the range with archived transactions", body = Vec<EpochStats>),
+        (status = 400, description = "from exceeds to", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn epochs_stats_handler(State(state): State<Arc<ServerState>>, Query(range): Query<EpochRange>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    if range.from > range.to {
+        return (StatusCode::BAD_REQUEST, format!("from {} exceeds to {}", range.from, range.to)).into_response()
+    }
+
+    match archive.epoch_stats(range.from, range.to) {
+        Ok(stats) => Json(stats).into_response(),
+        Err(e) => {
+            warn!("Stats of epochs {}..={} failed: {e}", range.from, range.to);
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// Identities of a rich list page
+const MAX_RICH_LIST_LIMIT: usize = 1000;
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct RichListPage {
+    /// `next` of the previous page, the first page is served without it
+    #[param(value_type = Option<String>)]
+    after: Option<QubicId>,
+    /// identities of the page, defaults to 100 and is capped at 1000
+    limit: Option<usize>
+}
+
+/// identities which sent or received QU in an archived tick by descending balance and then identity, identities
+/// untouched since the archiving started are not listed. Pages are fetched by cursor, so every page is a single bounded
+/// scan of the balance index no matter how deep it is
+#[utoipa::path(
+    get,
+    path = "/v1/rich-list",
+    params(RichListPage),
+    responses(
+        (status = 200, description = "Page of the rich list with the number of archived identities", body = RichList),
+        (status = 400, description = "after is not in the rich list", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain"),
+        (status = 500, description = "Archive database failed", body = String, content_type = "text/plain")
+    )
+)]
+async fn rich_list_handler(State(state): State<Arc<ServerState>>, Query(page): Query<RichListPage>) -> Response {
+    let Some(archive) = &state.archive else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    let limit = page.limit.unwrap_or(100).clamp(1, MAX_RICH_LIST_LIMIT);
+
+    match archive.rich_list(page.after.as_ref()).and_then(|entries| Ok((archive.rich_list_size()?, entries))) {
+        Ok((total, Some(entries))) => stream::rich_list_response(total, entries, limit),
+        Ok((_, None)) => (StatusCode::BAD_REQUEST, format!("{} is not in the rich list", page.after.unwrap_or_default())).into_response(),
+        Err(e) => {
+            warn!("Rich list failed: {e}");
+            (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    }
+}
+
+/// concentration of the QU over the rich list, computed on a schedule rather than per request
+#[utoipa::path(
+    get,
+    path = "/v1/rich-list/stats",
+    responses(
+        (status = 200, description = "Identities holding QU, their QU, the shares of the richest identities and the Gini coefficient", body = RichListStats),
+        (status = 503, description = "Stats are not computed yet", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db", body = String, content_type = "text/plain")
+    )
+)]
+async fn rich_list_stats_handler(State(state): State<Arc<ServerState>>) -> Response {
+    let Some(rich_list_stats) = &state.rich_list_stats else {
+        return (StatusCode::NOT_IMPLEMENTED, "Ticks are not archived, start the server with --archive-db").into_response()
+    };
+
+    match rich_list_stats.get() {
+        Some(stats) => Json(stats).into_response(),
+        None => (StatusCode::SERVICE_UNAVAILABLE, "Rich list stats are not computed yet").into_response()
+    }
+}
+
+/// registers a webhook the archived transfers of its identities and the finalized ticks are POSTed to, signed with
+/// its secret in the `x-qubic-signature` header. The webhook is managed by the identity of the challenge, its URL has
+/// to resolve to public addresses
+#[utoipa::path(
+    post,
+    path = "/v1/webhooks",
+    request_body = RegisterWebhook,
+    params(("x-qubic-challenge" = String, Header, description = "Signed challenge as JSON, see /v1/auth/verify")),
+    responses(
+        (status = 201, description = "Webhook was registered", body = Webhook),
+        (status = 400, description = "URL, events or secret are invalid or the URL resolves to a non-public address", body = String, content_type = "text/plain"),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 429, description = "--webhook-max or --webhook-max-per-identity webhooks are registered", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db or --auth-audience", body = String, content_type = "text/plain")
+    )
+)]
+async fn register_webhook_handler(State(state): State<Arc<ServerState>>, headers: HeaderMap, Json(registration): Json<RegisterWebhook>) -> Response {
+    let (webhooks, owner) = match webhook_owner(&state, &headers) {
+        Ok(owned) => owned,
+        Err(e) => return e.into_response()
+    };
+
+    match webhooks.store().register(owner, registration).await {
+        Ok(webhook) => (StatusCode::CREATED, Json(webhook)).into_response(),
+        Err(e) => (e.status(), e.to_string()).into_response()
+    }
+}
+
+/// webhooks and the authenticated identity managing them
+fn webhook_owner<'a>(state: &'a ServerState, headers: &HeaderMap) -> Result<(&'a Webhooks, QubicId), (StatusCode, String)> {
+    let Some(webhooks) = &state.webhooks else {
+        return Err((StatusCode::NOT_IMPLEMENTED, "Webhooks are not served, start the server with --archive-db".to_owned()))
+    };
+
+    authenticate(state, headers).map(|owner| (webhooks, owner))
+}
+
+/// registered webhook with its delivery state, dead-lettered webhooks no longer receive events
+#[utoipa::path(
+    get,
+    path = "/v1/webhooks/{id}",
+    params(("id" = u64, Path, description = "Id of the webhook"), ("x-qubic-challenge" = String, Header, description = "Signed challenge of the identity which registered the webhook")),
+    responses(
+        (status = 200, description = "Registered webhook", body = Webhook),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 403, description = "Webhook was registered by another identity", body = String, content_type = "text/plain"),
+        (status = 404, description = "No webhook has the id", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db or --auth-audience", body = String, content_type = "text/plain")
+    )
+)]
+async fn webhook_handler(State(state): State<Arc<ServerState>>, Path(id): Path<u64>, headers: HeaderMap) -> Response {
+    let (webhooks, owner) = match webhook_owner(&state, &headers) {
+        Ok(owned) => owned,
+        Err(e) => return e.into_response()
+    };
+
+    match webhooks.store().owned(owner, id) {
+        Ok(webhook) => Json(webhook).into_response(),
+        Err(e) => (e.status(), e.to_string()).into_response()
+    }
+}
+
+/// removes a webhook, its queued deliveries are dropped
+#[utoipa::path(
+    delete,
+    path = "/v1/webhooks/{id}",
+    params(("id" = u64, Path, description = "Id of the webhook"), ("x-qubic-challenge" = String, Header, description = "Signed challenge of the identity which registered the webhook")),
+    responses(
+        (status = 200, description = "Removed webhook", body = Webhook),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 403, description = "Webhook was registered by another identity", body = String, content_type = "text/plain"),
+        (status = 404, description = "No webhook has the id", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db or --auth-audience", body = String, content_type = "text/plain")
+    )
+)]
+async fn delete_webhook_handler(State(state): State<Arc<ServerState>>, Path(id): Path<u64>, headers: HeaderMap) -> Response {
+    let (webhooks, owner) = match webhook_owner(&state, &headers) {
+        Ok(owned) => owned,
+        Err(e) => return e.into_response()
+    };
+
+    match webhooks.delete(owner, id) {
+        Ok(webhook) => Json(webhook).into_response(),
+        Err(e) => (e.status(), e.to_string()).into_response()
+    }
+}
+
+/// resumes the deliveries to a dead-lettered webhook with the next event, the events in between are not delivered
+#[utoipa::path(
+    post,
+    path = "/v1/webhooks/{id}/reactivate",
+    params(("id" = u64, Path, description = "Id of the webhook"), ("x-qubic-challenge" = String, Header, description = "Signed challenge of the identity which registered the webhook")),
+    responses(
+        (status = 200, description = "Webhook is active", body = Webhook),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 403, description = "Webhook was registered by another identity", body = String, content_type = "text/plain"),
+        (status = 404, description = "No webhook has the id", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --archive-db or --auth-audience", body = String, content_type = "text/plain")
+    )
+)]
+async fn reactivate_webhook_handler(State(state): State<Arc<ServerState>>, Path(id): Path<u64>, headers: HeaderMap) -> Response {
+    let (webhooks, owner) = match webhook_owner(&state, &headers) {
+        Ok(owned) => owned,
+        Err(e) => return e.into_response()
+    };
+
+    match webhooks.store().reactivate(owner, id) {
+        Ok(webhook) => Json(webhook).into_response(),
+        Err(e) => (e.status(), e.to_string()).into_response()
+    }
+}
+
+#[derive(Debug, Deserialize, utoipa::IntoParams)]
+struct AuditRange {
+    /// unix time in milliseconds, defaults to the first record
+    from: Option<u64>,
+    /// unix time in milliseconds, defaults to the last record
+    to: Option<u64>
+}
+
+/// records of the audit log with a timestamp in the range, at most 10000 starting with the oldest
+#[utoipa::path(
+    get,
+    path = "/v1/admin/audit",
+    params(AuditRange, ("x-qubic-challenge" = String, Header, description = "Signed challenge as JSON, see /v1/auth/verify")),
+    responses(
+        (status = 200, description = "Records in ascending order", body = Vec<AuditRecord>),
+        (status = 401, description = "Challenge is missing or invalid", body = String, content_type = "text/plain"),
+        (status = 403, description = "Identity is not an --audit-viewer", body = String, content_type = "text/plain"),
+        (status = 501, description = "Server was started without --audit-log or --auth-audience", body = String, content_type = "text/plain")
+    )
+)]
+async fn audit_handler(State(state): State<Arc<ServerState>>, Query(range): Query<AuditRange>, headers: HeaderMap) -> Response {
+    let Some(audit) = &state.audit else {
+        return (StatusCode::NOT_IMPLEMENTED, "Operations are not audited, start the server with --audit-log").into_response()
+    };
+
+    let identity = match authenticate(&state, &headers) {
+        Ok(identity) => identity,
+        Err(e) => return e.into_response()
+    };
+
+    if !state.args.audit_viewer.contains(&identity) {
+        return (StatusCode::FORBIDDEN, format!("{identity} may not read the audit log")).into_response()
+    }
+
+    match audit.records(range.from.unwrap_or(0), range.to.unwrap_or(u64::MAX)) {
+        Ok(records) => Json(records).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, format!("Audit database failed: {e}")).into_response()
+    }
+}
+
+/// records a relayed operation to `--audit-log` before it is answered as successful, an operation which could not be
+/// recorded is answered as failed
+async fn audit(state: &ServerState, entry: AuditEntry) -> Result<(), (StatusCode, String)> {
+    let Some(audit) = &state.audit else {
+        return Ok(())
+    };
+
+    audit.append(entry).await.map(|_| ()).map_err(|e| {
+        error!("Failed to write audit record: {e}");
+        (StatusCode::INTERNAL_SERVER_ERROR, "Operation was relayed but could not be recorded to the audit log".to_owned())
+    })
+}
+
+/// client of `computor`, connections are tunneled through `--proxy` and dumped to `--wire-dump-dir` if they are set
+async fn computor_client(computor: &str) -> Result<Client<Tcp>, ClientError> {
+    let mut builder = ClientBuilder::<Tcp>::new(computor);
+
+    if let Some(proxy) = COMPUTOR_PROXY.get() {
+        builder = builder.with_proxy(proxy.clone());
+    }
+
+    if let Some(dump) = COMPUTOR_WIRE_DUMP.get() {
+        builder = builder.with_wire_dump(dump.clone());
+    }
+
+    Ok(builder.with_interceptor(RedirectCounter).build().await?)
+}
+
+/// logs the redirects of busy computors and counts them for `/v1/metrics`
+struct RedirectCounter;
+
+impl Interceptor for RedirectCounter {
+    fn before(&self, _req: &RequestInfo) {}
+
+    fn after(&self, _req: &RequestInfo, _result: &Result<ResponseInfo, ClientError>) {}
+
+    fn redirected(&self, req: &RequestInfo, to: &str) {
+        PEER_REDIRECTS.fetch_add(1, Ordering::Relaxed);
+        info!("Computor {} is busy, {} is redirected to {to}", req.peer, req.message_type);
+    }
+}
+
+/// dump file of this run in `dir`, named after the time the server started
+fn wire_dump(dir: &str, max_payload: usize) -> std::io::Result<WireDump> {
+    std::fs::create_dir_all(dir)?;
+
+    let started = SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis();
+    let path = std::path::Path::new(dir).join(format!("upstream-{started}.wire"));
+    info!("Dumping the frames of computor connections to {}", path.display());
+
+    Ok(WireDump::to_file(path)?.with_max_payload(max_payload))
+}
+
+/// whether `tick` reached quorum, known from the archive or else aggregated from the votes the computor has
+async fn tick_finalized(state: &ServerState, client: &Client<Tcp>, tick: u32) -> bool {
+    if let Some(archive) = &state.archive {
+        match archive.is_finalized(tick) {
+            Ok(true) => return true,
+            Ok(false) => (),
+            Err(e) => warn!("Archived finality of tick {tick} failed: {e}")
+        }
+    }
+
+    client.qu().is_tick_finalized(tick).await.unwrap_or_else(|e| {
+        warn!("Finality of tick {tick} failed: {e}");
+        false
+    })
+}
+
+/// requests every section of the overview concurrently
+async fn network_overview(client: &Client<Tcp>) -> NetworkOverview {
+    let qu = client.qu();
+    let (tick_info, system_info, peers) = tokio::join!(
+        qu.get_current_tick_info(),
+        qu.request_system_info(),
+        qu.exchange_public_peers(ExchangePublicPeers::default())
+    );
+
+    NetworkOverview { tick_info: tick_info.into(), system_info: system_info.into(), peers: peers.map(PublicPeers::from).into() }
+}
+
+/// serves methods shared with v1 through the v1 handler, only the methods added or extended in v2 are handled here
+async fn v2_request_handler(State(state): State<Arc<ServerState>>, client: Option<IpAddr>, idempotency_key: Option<String>, Json(rpc_method): Json<v2::QubicJsonRpcRequest>) -> (StatusCode, [(&'static str, &'static str); 1], Json<v2::QubicJsonRpcResponse>) {
+    let id = rpc_method.id;
+
+    if rpc_method.jsonrpc.as_str() != "2.0" {
+        return (StatusCode::BAD_REQUEST, [(SOURCE_HEADER, "computor")], Json(v2::QubicJsonRpcResponse {
+            jsonrpc: "2.0".to_owned(),
+            version: Version::V2,
+            id,
+            response: v2::ResponseType::Error(v2::RequestError { method: rpc_method.request.get_method(), error: "Invalid JSON-RPC version found".to_owned() }),
+            diagnostics: None
+        }))
+    }
+
+    // v2 reports the completeness of tick transactions, which the v1 result has no field for
+    let request = match rpc_method.request {
+        v2::RequestMethods::RequestTickTransactions { .. } => rpc_method.request,
+        _ => match QubicJsonRpcRequest::try_from(rpc_method.clone()) {
+            Ok(request) => {
+                let (status, headers, Json(res)) = request_handler(State(state), client, idempotency_key, Json(request)).await;
+
+                return (status, headers, Json(res.into()))
+            },
+            Err(_) => rpc_method.request
+        }
+    };
+
+    info!("Incoming request: {request:?}");
+
+    let started = Instant::now();
+    let res = match state.interactive_client().await {
+        Ok(client) => match request {
+            v2::RequestMethods::RequestTickData { tick } => match client.qu().request_tick_data(tick).await {
+                Ok(tick_data) => {
+                    // boxed before awaiting the finality, the tick data is too large to be held in the future
+                    let mut report = Box::new(TickDataReport { tick_data: *tick_data, finalized: false });
+                    report.finalized = tick_finalized(&state, &client, tick).await;
+
+                    Ok(v2::RequestResults::RequestTickData(report))
+                },
+                Err(e) => Err(e)
+            },
+            v2::RequestMethods::RequestSystemInfo => client.qu().request_system_info().await.map(v2::RequestResults::RequestSystemInfo),
+            v2::RequestMethods::RequestPublicPeers => client.qu().exchange_public_peers(ExchangePublicPeers::default()).await.map(|peers| v2::RequestResults::RequestPublicPeers(peers.into())),
+            v2::RequestMethods::RequestNetworkOverview => Ok(v2::RequestResults::RequestNetworkOverview(Box::new(network_overview(&client).await))),
+            v2::RequestMethods::RequestTickTransactions { tick, ref filter } => match client.qu().request_tick_transactions_detailed(tick, TransactionFlags::all()).await {
+                Ok(report) => Ok(v2::RequestResults::RequestTickTransactions(TickTransactions { finalized: tick_finalized(&state, &client, tick).await, ..TickTransactions::from(report).filtered(filter) })),
+                Err(e) => Err(e)
+            },
+            _ => Err(ClientError::InvalidInput(format!("{:?} is served by the schema version 1", request.get_method())))
+        },
+        Err(e) => Err(e)
+    };
+
+    let (status, response) = match res {
+        Ok(res) => (StatusCode::OK, v2::ResponseType::Result(res)),
+        Err(e) => {
+            warn!("Request failed: {e}");
+
+            (error_status(&e), v2::ResponseType::Error(v2::RequestError { method: request.get_method(), error: e.to_string() }))
+        }
+    };
+
+    let diagnostics = rpc_method.debug.then(|| upstream_diagnostics(started, &state.args.computor, 1));
+
+    (status, [(SOURCE_HEADER, "computor")], Json(v2::QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), version: Version::V2, id, response, diagnostics }))
+}
+
+async fn request_handler(State(state): State<Arc<ServerState>>, client: Option<IpAddr>, idempotency_key: Option<String>, Json(rpc_method): Json<QubicJsonRpcRequest>) -> (StatusCode, [(&'static str, &'static str); 1], Json<QubicJsonRpcResponse>) {
+    info!("Incoming request: {rpc_method:?}");
+
+    if rpc_method.jsonrpc.as_str() != "2.0" {
+        return (StatusCode::BAD_REQUEST, [(SOURCE_HEADER, "computor")], Json(QubicJsonRpcResponse {
+            jsonrpc: "2.0".to_owned(),
+            id: rpc_method.id,
+            response: ResponseType::Error(RequestError { method: rpc_method.request.get_method(), error: "Invalid JSON-RPC version found".to_owned() }),
+            diagnostics: None
+        }))
+    }
+
+    let debug = rpc_method.debug;
+    let method = rpc_method.request.get_method();
+    let transaction = match &rpc_method.request {
+        RequestMethods::SendTransaction(params) => params.transaction().ok(),
+        _ => None
+    };
+    let tx_id = transaction.as_ref().map(QubicTxHash::from);
+    let mut reservation = None;
+
+    if let (Some(tx_id), Some(tx)) = (&tx_id, &transaction) {
+        let error = |status, error| (status, [(SOURCE_HEADER, "replay")], Json(QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: rpc_method.id, response: ResponseType::Error(RequestError { method, error }), diagnostics: None }));
+
+        match state.broadcasts.begin(&tx.raw_transaction.from, idempotency_key.as_deref(), tx_id) {
+            Ok(Replay::New(reserved)) => reservation = Some(reserved),
+            Ok(Replay::Done(broadcasted)) => {
+                info!("Answering replayed broadcast of {tx_id} with its first result");
+
+                return (StatusCode::OK, [(SOURCE_HEADER, "replay")], Json(QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: rpc_method.id, response: ResponseType::Result(RequestResults::SendTransaction(broadcasted)), diagnostics: None }))
+            },
+            Ok(Replay::Conflict(first)) => return error(StatusCode::CONFLICT, format!("Idempotency-Key was used for transaction {first}")),
+            Ok(Replay::InFlight) => return error(StatusCode::CONFLICT, format!("Transaction {tx_id} is being broadcast")),
+            Err(e) => {
+                error!("Replay guard of {tx_id} failed: {e}");
+                return error(StatusCode::INTERNAL_SERVER_ERROR, format!("Replay guard failed: {e}"))
+            }
+        }
+    }
+
+    let (mut status, source, mut res, diagnostics) = serve_request(&state, rpc_method, client).await;
+
+    if let (Some(reservation), Some(tx_id)) = (reservation, &tx_id) {
+        let broadcasted = match &res.response {
+            ResponseType::Result(RequestResults::SendTransaction(broadcasted)) if status == StatusCode::OK => Some(broadcasted),
+            _ => None
+        };
+
+        if let Err(e) = reservation.finish(broadcasted) {
+            error!("Recording broadcast of {tx_id} failed: {e}");
+        }
+    }
+
+    let upstream_peer = diagnostics.upstream_peer.clone().unwrap_or_else(|| state.args.computor.clone());
+    let entry = match (&res.response, transaction) {
+        (ResponseType::Result(RequestResults::SendTransaction(_)), Some(tx)) if state.audit.is_some() => Some(AuditEntry::transaction(&tx, client, upstream_peer)),
+        (ResponseType::Result(RequestResults::RequestSubmitWork(work)), _) => Some(AuditEntry::work(work, client, upstream_peer)),
+        _ => None
+    };
+
+    if let Some(entry) = entry {
+        if let Err((audit_status, error)) = audit(&state, entry).await {
+            status = audit_status;
+            res.response = ResponseType::Error(RequestError { method, error });
+        }
+    }
+
+    if debug {
+        res.diagnostics = Some(diagnostics);
+    }
+
+    (status, [(SOURCE_HEADER, source)], Json(res))
+}
+
+/// serves the request locally, from the computor or from the fallback RPC, the backend which served it is returned.
+/// Identical concurrent reads share one upstream request, successful reads are cached for `--read-cache-ttl`
+async fn serve_request(state: &ServerState, rpc_method: QubicJsonRpcRequest, client: Option<IpAddr>) -> ServedRequest {
+    let id = rpc_method.id;
+
+    if let Some((status, response)) = local_handler(state, &rpc_method.request, client).await {
+        let diagnostics = Diagnostics { upstream_latency_ms: None, upstream_peer: None, attempts: 0, served_from_cache: true };
+
+        return (status, "computor", QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id, response, diagnostics: None }, diagnostics)
+    }
+
+    let Some(key) = coalesce::request_key(&rpc_method.request) else {
+        return serve_upstream(state, rpc_method).await
+    };
+
+    let ((status, source, mut res, mut diagnostics), served) = state.reads.get(key, serve_upstream(state, rpc_method), |(status, ..)| *status == StatusCode::OK).await;
+    res.id = id;
+    diagnostics.served_from_cache = served != Served::Upstream;
+
+    (status, source, res, diagnostics)
+}
+
+/// serves the request from the computor, once the upstream scheduler granted its turn, or from the fallback RPC
+async fn serve_upstream(server: &ServerState, rpc_method: QubicJsonRpcRequest) -> ServedRequest {
+    let state = &server.args;
+    let id = rpc_method.id;
+    let rpc_response = |response| QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id, response, diagnostics: None };
+
+    let started = Instant::now();
+
+    let (fallback_rpc, attempts) = match &server.fallback {
+        Some(fallback_rpc) if state.proxy_only => (fallback_rpc, 1),
+        Some(fallback_rpc) => {
+            server.scheduler.acquire(Priority::Interactive).await;
+            let (status, Json(res)) = computor_handler(state, rpc_method.clone()).await;
+
+            if !matches!(status, StatusCode::BAD_GATEWAY | StatusCode::GATEWAY_TIMEOUT) {
+                return (status, "computor", res, upstream_diagnostics(started, &state.computor, 1))
+            }
+
+            warn!("Computor {} not reachable, falling back to {}", state.computor, fallback_rpc.url());
+            (fallback_rpc, 2)
+        },
+        None => {
+            server.scheduler.acquire(Priority::Interactive).await;
+            let (status, Json(res)) = computor_handler(state, rpc_method).await;
+
+            return (status, "computor", res, upstream_diagnostics(started, &state.computor, 1))
+        }
+    };
+
+    let (status, response) = match fallback_rpc.request(&rpc_method.request).await {
+        Ok(res) => (StatusCode::OK, ResponseType::Result(res)),
+        Err(e) => {
+            warn!("Request failed: {e}");
+
+            (e.status(), ResponseType::Error(RequestError { method: rpc_method.request.get_method(), error: e.to_string() }))
+        }
+    };
+
+    (status, "proxy", rpc_response(response), upstream_diagnostics(started, fallback_rpc.url(), attempts))
+}
+
+/// serves methods answered by the server itself instead of being forwarded to the computor
+async fn local_handler(state: &ServerState, request: &RequestMethods, client: Option<IpAddr>) -> Option<(StatusCode, ResponseType)> {
+    let error = |status, error: &str| Some((status, ResponseType::Error(RequestError { method: request.get_method(), error: error.to_owned() })));
+
+    let stats = match request {
+        RequestMethods::RequestSubmitWork(work) => return match &state.work {
+            Some(relay) => match relay.submit(state.interactive_client(), work, client).await {
+                Ok(submitted) => Some((StatusCode::OK, ResponseType::Result(RequestResults::RequestSubmitWork(submitted)))),
+                Err(e) => error(e.status(), &e.to_string())
+            },
+            None => error(StatusCode::NOT_IMPLEMENTED, "Mining solutions are not relayed, start the server with --work-relay-seed")
+        },
+        RequestMethods::WaitForNextTick { after, timeout } => {
+            let timeout = timeout.map(Duration::from_secs).unwrap_or(ticks::DEFAULT_WAIT);
+
+            return match state.ticks.wait_for_next_tick(*after, timeout).await {
+                Some(next) => Some((StatusCode::OK, ResponseType::Result(RequestResults::WaitForNextTick(next)))),
+                None => error(StatusCode::GATEWAY_TIMEOUT, "No tick info received from computor")
+            }
+        },
+        RequestMethods::GetNetworkStatsHistory { .. } | RequestMethods::GetNetworkStatsLatest => match &state.stats {
+            Some(stats) => stats,
+            None => return error(StatusCode::NOT_IMPLEMENTED, "Network stats are not recorded, start the server with --stats-db")
+        },
+        _ => return None
+    };
+
+    let res = match *request {
+        RequestMethods::GetNetworkStatsHistory { from_tick, to_tick, .. } if from_tick > to_tick => return error(StatusCode::BAD_REQUEST, "fromTick exceeds toTick"),
+        RequestMethods::GetNetworkStatsHistory { from_tick, to_tick, step } => stats.history(from_tick, to_tick, step.unwrap_or(1)).map(RequestResults::GetNetworkStatsHistory),
+        _ => stats.latest().map(RequestResults::GetNetworkStatsLatest)
+    };
+
+    match res {
+        Ok(res) => Some((StatusCode::OK, ResponseType::Result(res))),
+        Err(e) => {
+            error!("Failed to read network stats: {e}");
+
+            error(StatusCode::INTERNAL_SERVER_ERROR, &e.to_string())
+        }
+    }
+}
+
+async fn computor_handler(state: &Args, rpc_method: QubicJsonRpcRequest) -> (StatusCode, Json<QubicJsonRpcResponse>) {
+    let client = result_or_error!(computor_client(&state.computor).await, rpc_method);
+
+    match rpc_method.request {
+        RequestMethods::RequestComputors => {
+            let res = result_or_error!(client.qu().request_computors().await, rpc_method);
+
+            early_return_result!(RequestResults::RequestComputors((*res).into()), rpc_method);
+        },
+        RequestMethods::RequestCurrentTickInfo => {
+            let res = result_or_error!(client.qu().get_current_tick_info().await, rpc_method);
+
+            early_return_result!(RequestResults::RequestCurrentTickInfo(res), rpc_method);
+        },
+        RequestMethods::RequestEntity(id) => {
+            let res = result_or_error!(client.qu().request_entity(id).await, rpc_method);
+
+            early_return_result!(RequestResults::RequestEntity(res.entity_only()), rpc_method);
+        },
+        RequestMethods::SendTransaction(ref params) => {
+            let tx = match params.transaction() {
+                Ok(tx) => tx,
+                Err(e) => {
+                    warn!("Rejected transaction: {e}");
+
+                    return (StatusCode::BAD_REQUEST, Json(QubicJsonRpcResponse {
+                        jsonrpc: "2.0".to_owned(),
+                        id: rpc_method.id,
+                        response: ResponseType::Error(RequestError { method: rpc_method.request.get_method(), error: e.to_string() }),
+                        diagnostics: None
+                    }))
+                }
+            };
+
+            let peers_broadcasted = if state.broadcast_peer.is_empty() {
+                result_or_error!(client.qu().send_signed_transaction(tx.clone()).await, rpc_method);
+                1
+            } else {
+                let peers = std::iter::once(state.computor.clone()).chain(state.broadcast_peer.iter().cloned()).collect::<Vec<_>>();
+                let report = result_or_error!(client.qu().send_signed_transaction_multi(tx.clone(), &peers, state.min_broadcast_peers).await, rpc_method);
+
+                for (peer, e) in report.failed.iter() {
+                    warn!("Failed to broadcast transaction to {peer}: {e}");
+                }
+
+                report.succeeded.len()
+            };
+
+            early_return_result!(RequestResults::SendTransaction(BroadcastedTransaction { tx_hash: QubicTxHash::from(&tx), peers_broadcasted }), rpc_method);
+        },
+        RequestMethods::RequestTickTransactions(tick) => {
+            let res = result_or_error!(client.qu().request_tick_transactions(tick, TransactionFlags::all()).await, rpc_method);
+
+            early_return_result!(RequestResults::RequestTickTransactions(res), rpc_method);
+        },
+        RequestMethods::FindAsset(ref name) => {
+            let res = result_or_error!(client.qx().find_asset(name).await, rpc_method);
+
+            early_return_result!(RequestResults::FindAsset(res), rpc_method);
+        },
+        RequestMethods::RequestQuorumVotes(tick) => {
+            let res = result_or_error!(client.qu().request_quorum_votes(tick).await, rpc_method);
+
+            early_return_result!(RequestResults::RequestQuorumVotes(res.into()), rpc_method);
+        },
+        // answered by `local_handler` before the computor is requested
+        RequestMethods::WaitForNextTick { .. } | RequestMethods::GetNetworkStatsHistory { .. } | RequestMethods::GetNetworkStatsLatest | RequestMethods::RequestSubmitWork(_) => {
+            (StatusCode::NOT_IMPLEMENTED, Json(QubicJsonRpcResponse {
+                jsonrpc: "2.0".to_owned(),
+                id: rpc_method.id,
+                response: ResponseType::Error(RequestError { method: rpc_method.request.get_method(), error: "Method is not served by the computor".to_owned() }),
+                diagnostics: None
+            }))
+        }
+    }
+}
+
+#[tokio::test]
+async fn test() {
+    use std::str::FromStr;
+    use qubic_types::QubicId;
+    const RPC: &str = "http://127.0.0.1:2003/";
+
+    let client = reqwest::Client::new();
+    let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestEntity(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()));
+    let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
+    dbg!(res);
+
+    let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestCurrentTickInfo);
+    let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
+    dbg!(&res);
+
+    if let ResponseType::Result(r) = res.response {
+        if let RequestResults::RequestCurrentTickInfo(current_tick) = r {
+            let request = QubicJsonRpcRequest::new(0, RequestMethods::RequestTickTransactions(current_tick.tick - 10));
+            let res: QubicJsonRpcResponse = client.post(RPC).json(&request).send().await.unwrap().json().await.unwrap();
+            dbg!(&res);
+        }
+    }
+}
+#[tokio::test]
+async fn test_fallback_rpc() {
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/v1/tick-info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "tickInfo": { "tick": 12000000, "duration": 2, "epoch": 100, "initialTick": 11900000 } })))
+        .mount(&server).await;
+
+    Mock::given(method("POST")).and(path("/v1/broadcast-transaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "peersBroadcasted": 3, "encodedTransaction": "", "transactionId": "" })))
+        .mount(&server).await;
+
+    // nothing listens on port 1, computor requests fail immediately
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--fallback-rpc", &server.uri()])));
+
+    let (status, headers, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(0, RequestMethods::RequestCurrentTickInfo))).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers, [(SOURCE_HEADER, "proxy")]);
+    assert!(matches!(res.response, ResponseType::Result(RequestResults::RequestCurrentTickInfo(info)) if info.tick == 12000000 && info.epoch == 100));
+
+    let tx = qubic_web3_rs::qubic_tcp_types::types::transactions::Transaction::default();
+    let (status, headers, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(1, RequestMethods::SendTransaction(tx.into())))).await;
+
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers, [(SOURCE_HEADER, "proxy")]);
+    assert!(matches!(res.response, ResponseType::Result(RequestResults::SendTransaction(broadcasted)) if broadcasted.peers_broadcasted == 3));
+
+    let (status, headers, _) = request_handler(State(state), None, None, Json(QubicJsonRpcRequest::new(2, RequestMethods::RequestComputors))).await;
+
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(headers, [(SOURCE_HEADER, "proxy")]);
+}
+
+#[tokio::test]
+async fn test_without_fallback_rpc() {
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+
+    let (status, headers, Json(res)) = request_handler(State(state), None, None, Json(QubicJsonRpcRequest::new(0, RequestMethods::RequestCurrentTickInfo))).await;
+
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert_eq!(headers, [(SOURCE_HEADER, "computor")]);
+    assert!(matches!(res.response, ResponseType::Error(_)));
+}
+
+#[tokio::test]
+async fn test_diagnostics() {
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/v1/tick-info"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "tickInfo": { "tick": 12000000, "duration": 2, "epoch": 100, "initialTick": 11900000 } })))
+        .mount(&server).await;
+
+    // without cache every request reaches the upstreams
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--fallback-rpc", &server.uri(), "--read-cache-ttl", "0"])));
+
+    // absent by default
+    let (_, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(0, RequestMethods::RequestCurrentTickInfo))).await;
+    assert_eq!(res.diagnostics, None);
+
+    // the computor was requested before the fallback RPC served the request
+    let request = QubicJsonRpcRequest { debug: true, ..QubicJsonRpcRequest::new(1, RequestMethods::RequestCurrentTickInfo) };
+    let (status, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(request)).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let diagnostics = res.diagnostics.unwrap();
+    assert_eq!((diagnostics.upstream_peer, diagnostics.attempts, diagnostics.served_from_cache), (Some(server.uri()), 2, false));
+    assert!(diagnostics.upstream_latency_ms.is_some());
+
+    // served by the server itself
+    let request = QubicJsonRpcRequest { debug: true, ..QubicJsonRpcRequest::new(2, RequestMethods::GetNetworkStatsLatest) };
+    let (_, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(request)).await;
+    assert_eq!(res.diagnostics, Some(Diagnostics { upstream_latency_ms: None, upstream_peer: None, attempts: 0, served_from_cache: true }));
+
+    // requested with the header, shared methods keep the diagnostics of the v1 handler
+    let mut headers = HeaderMap::new();
+    headers.insert(DEBUG_HEADER, "1".parse().unwrap());
+
+    for (headers, expected) in [(headers, true), (HeaderMap::new(), false)] {
+        let res = versioned_request_handler(State(state.clone()), None, headers, Json(serde_json::json!({ "jsonrpc": "2.0", "version": 2, "id": 3, "method": "requestCurrentTickInfo" }))).await;
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+        let res: serde_json::Value = serde_json::from_slice(&body).unwrap();
+
+        assert_eq!(res.get("diagnostics").is_some(), expected);
+        assert_eq!(res["version"], 2);
+    }
+}
+
+#[tokio::test]
+async fn test_coalesced_reads() {
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, Transaction};
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("GET")).and(path("/v1/tick-info"))
+        .respond_with(ResponseTemplate::new(200).set_delay(Duration::from_millis(100)).set_body_json(serde_json::json!({ "tickInfo": { "tick": 12000000, "duration": 2, "epoch": 100, "initialTick": 11900000 } })))
+        .expect(1)
+        .mount(&server).await;
+
+    Mock::given(method("POST")).and(path("/v1/broadcast-transaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "peersBroadcasted": 3, "encodedTransaction": "", "transactionId": "" })))
+        .expect(2)
+        .mount(&server).await;
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--fallback-rpc", &server.uri(), "--proxy-only"])));
+
+    let requests = (0..50).map(|id| request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(id, RequestMethods::RequestCurrentTickInfo))));
+    let responses = futures::future::join_all(requests).await;
+
+    // every caller gets the shared result under its own id
+    for (id, (status, _, Json(res))) in responses.into_iter().enumerate() {
+        assert_eq!((status, res.id), (StatusCode::OK, id as u32));
+        assert!(matches!(res.response, ResponseType::Result(RequestResults::RequestCurrentTickInfo(info)) if info.tick == 12000000));
+    }
+
+    let (_, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest { debug: true, ..QubicJsonRpcRequest::new(50, RequestMethods::RequestCurrentTickInfo) })).await;
+    assert!(res.diagnostics.unwrap().served_from_cache);
+
+    // mutations are never coalesced
+    let tx = |amount| Transaction { raw_transaction: RawTransaction { amount, ..Default::default() }, ..Default::default() };
+    let broadcasts = (0..2).map(|id| request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(id, RequestMethods::SendTransaction(tx(id as u64).into())))));
+    futures::future::join_all(broadcasts).await;
+
+    // rejected before it reaches the fallback RPC
+    let unsigned = serde_json::from_value(serde_json::json!({
+        "rawTransaction": { "sourceId": QubicId::default(), "destId": QubicId::default(), "amount": 1, "tick": 12000000, "inputType": 0 },
+        "signatureHex": "00".repeat(64)
+    })).unwrap();
+    let (status, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(2, RequestMethods::SendTransaction(unsigned)))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(matches!(res.response, ResponseType::Error(e) if e.error.starts_with("Signature invalid")));
+
+    // input the node would drop is rejected before the signature is checked
+    let oversized = serde_json::from_value(serde_json::json!({
+        "rawTransaction": { "sourceId": QubicId::default(), "destId": QubicId::default(), "amount": 1, "tick": 12000000, "inputType": 0, "inputHex": "00".repeat(1025) },
+        "signatureHex": "00".repeat(64)
+    })).unwrap();
+    let (status, _, Json(res)) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(3, RequestMethods::SendTransaction(oversized)))).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(matches!(res.response, ResponseType::Error(e) if e.error.contains("inputHex of 1025 bytes exceeds 1024 bytes")));
+
+    let Json(metrics) = metrics_handler(State(state)).await;
+    assert_eq!(metrics, CoalescingMetrics { upstream_calls: 1, coalesced: 49, cache_hits: 1, peer_redirects: 0, ..Default::default() });
+}
+
+#[tokio::test]
+async fn test_idempotent_broadcasts() {
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, Transaction};
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST")).and(path("/v1/broadcast-transaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "peersBroadcasted": 3, "encodedTransaction": "", "transactionId": "" })))
+        .expect(2)
+        .mount(&server).await;
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--fallback-rpc", &server.uri(), "--proxy-only"])));
+
+    let tx = |tick| Transaction { raw_transaction: RawTransaction { amount: 10, tick, ..Default::default() }, ..Default::default() };
+    let broadcast = |id, tick, key: Option<&str>| request_handler(State(state.clone()), None, key.map(str::to_owned), Json(QubicJsonRpcRequest::new(id, RequestMethods::SendTransaction(tx(tick).into()))));
+
+    let (status, headers, Json(first)) = broadcast(0, 100, Some("retry")).await;
+    assert_eq!((status, headers), (StatusCode::OK, [(SOURCE_HEADER, "proxy")]));
+    let ResponseType::Result(RequestResults::SendTransaction(first)) = first.response else { panic!("broadcast failed") };
+
+    // same key and body: the first result is answered under the id of the retry
+    let mut headers = HeaderMap::new();
+    headers.insert(IDEMPOTENCY_HEADER, "retry".parse().unwrap());
+    let body = serde_json::to_value(QubicJsonRpcRequest::new(1, RequestMethods::SendTransaction(tx(100).into()))).unwrap();
+    let res = versioned_request_handler(State(state.clone()), None, headers, Json(body)).await;
+    assert_eq!((res.status(), res.headers()[SOURCE_HEADER].to_str().unwrap()), (StatusCode::OK, "replay"));
+    let res = serde_json::from_slice::<QubicJsonRpcResponse>(&axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap()).unwrap();
+    assert_eq!(res.id, 1);
+    assert!(matches!(res.response, ResponseType::Result(RequestResults::SendTransaction(replayed)) if replayed.tx_hash == first.tx_hash));
+
+    // same key, body signed again for a later tick
+    let (status, _, Json(res)) = broadcast(2, 101, Some("retry")).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert!(matches!(res.response, ResponseType::Error(e) if e.error.contains(&first.tx_hash.to_string())));
+
+    // the same signed transaction is not sent again without a key either
+    let (status, headers, Json(res)) = broadcast(3, 100, None).await;
+    assert_eq!((status, headers), (StatusCode::OK, [(SOURCE_HEADER, "replay")]));
+    assert!(matches!(res.response, ResponseType::Result(RequestResults::SendTransaction(replayed)) if replayed.tx_hash == first.tx_hash));
+
+    // the later transaction under a new key is broadcast
+    let (status, headers, _) = broadcast(4, 101, Some("later")).await;
+    assert_eq!((status, headers), (StatusCode::OK, [(SOURCE_HEADER, "proxy")]));
+}
+
+#[tokio::test]
+async fn test_versioned_requests() {
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+
+    let request = |body: serde_json::Value| {
+        let state = state.clone();
+
+        async move {
+            let res = versioned_request_handler(State(state), None, HeaderMap::new(), Json(body)).await;
+            let status = res.status();
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+            (status, serde_json::from_slice::<serde_json::Value>(&body).ok())
+        }
+    };
+
+    // v1 stays the default and answers without a version
+    let (status, res) = request(serde_json::json!({ "jsonrpc": "2.0", "id": 0, "method": "requestTickTransactions", "params": 1 })).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    let res = res.unwrap();
+    assert_eq!((res.get("version"), &res["id"], &res["method"]), (None, &serde_json::json!(0), &serde_json::json!("requestTickTransaction")));
+
+    // shared methods are answered with the v2 schema
+    let (status, res) = request(serde_json::json!({ "jsonrpc": "2.0", "version": 2, "id": 1, "method": "requestTickTransactions", "params": { "tick": 1 } })).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    let res = res.unwrap();
+    assert_eq!((res.get("version"), &res["id"], &res["method"]), (Some(&serde_json::json!(2)), &serde_json::json!(1), &serde_json::json!("requestTickTransactions")));
+
+    let (status, _, Json(res)) = v2_request_handler(State(state.clone()), None, None, Json(v2::QubicJsonRpcRequest::new(2, v2::RequestMethods::RequestSystemInfo))).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(matches!(res.response, v2::ResponseType::Error(e) if e.method == v2::Methods::RequestSystemInfo));
+
+    // positional params are rejected by v2, unknown versions by both
+    assert_eq!(request(serde_json::json!({ "jsonrpc": "2.0", "version": 2, "id": 3, "method": "requestTickData", "params": 1 })).await.0, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(request(serde_json::json!({ "jsonrpc": "2.0", "version": 3, "id": 4, "method": "requestComputors" })).await.0, StatusCode::UNPROCESSABLE_ENTITY);
+
+    // unknown methods are answered with a JSON-RPC error
+    for (version, id) in [(None, 5), (Some(2), 6)] {
+        let mut body = serde_json::json!({ "jsonrpc": "2.0", "id": id, "method": "requestEverything" });
+        if let Some(version) = version {
+            body["version"] = version.into();
+        }
+
+        let (status, res) = request(body).await;
+        assert_eq!(status, StatusCode::BAD_REQUEST);
+        let res = res.unwrap();
+        assert_eq!((res.get("version"), &res["id"], &res["method"], &res["error"]), (version.map(serde_json::Value::from).as_ref(), &serde_json::json!(id), &serde_json::json!("requestEverything"), &serde_json::json!("Unknown method \"requestEverything\"")));
+    }
+
+    let res = v2_json_handler(State(state.clone()), None, HeaderMap::new(), Json(serde_json::json!({ "jsonrpc": "2.0", "version": 2, "id": 7, "method": "requestEverything" }))).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_network_overview() {
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+
+    let (status, _, Json(res)) = v2_request_handler(State(state.clone()), None, None, Json(v2::QubicJsonRpcRequest::new(0, v2::RequestMethods::RequestPublicPeers))).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(matches!(res.response, v2::ResponseType::Error(e) if e.method == v2::Methods::RequestPublicPeers));
+
+    // an unreachable computor fails every section but not the overview
+    let (status, _, Json(res)) = v2_request_handler(State(state), None, None, Json(v2::QubicJsonRpcRequest::new(1, v2::RequestMethods::RequestNetworkOverview))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let v2::ResponseType::Result(v2::RequestResults::RequestNetworkOverview(overview)) = res.response else { panic!("expected an overview") };
+    assert!(matches!(overview.tick_info, qubic_rpc_types::OverviewSection::Error(_)));
+    assert!(matches!(overview.system_info, qubic_rpc_types::OverviewSection::Error(_)));
+    assert!(matches!(overview.peers, qubic_rpc_types::OverviewSection::Error(_)));
+}
+
+#[tokio::test]
+async fn test_openapi_document() {
+    let doc = serde_json::to_value(docs::ApiDoc::openapi()).unwrap();
+
+    for path in ["/", "/v2", "/v1/auth/verify"] {
+        assert!(doc["paths"][path]["post"]["responses"]["200"].is_object(), "missing path {path}");
+    }
+    assert!(doc["paths"]["/v1/computors/health"]["get"]["responses"]["200"].is_object());
+    assert!(doc["paths"]["/v1/mining/ranking"]["get"]["responses"]["200"].is_object());
+    assert!(doc["paths"]["/v1/webhooks"]["post"]["responses"]["201"].is_object());
+
+    let schemas = &doc["components"]["schemas"];
+    for schema in ["v1.QubicJsonRpcRequest", "v2.QubicJsonRpcRequest", "v2.RequestResults", "TransactionWithData", "TickData", "NetworkOverview", "QubicId", "SignedChallenge"] {
+        assert!(schemas[schema].is_object(), "missing schema {schema}");
+    }
+
+    // property names and unit variants follow serde
+    assert!(schemas["BroadcastedTransaction"]["properties"]["txHash"].is_object());
+    assert!(schemas["TickTransactions"]["properties"]["tickDataDigestCount"].is_object());
+    assert_eq!(schemas["v2.Methods"]["type"], "string");
+    assert!(schemas["v2.Methods"]["enum"].as_array().unwrap().contains(&serde_json::json!("requestNetworkOverview")));
+
+    // the document is only served with --docs
+    let serve = |args: &[&str]| {
+        let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"].iter().chain(args))));
+
+        async move {
+            let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+            let addr = listener.local_addr().unwrap();
+            tokio::spawn(async move { axum::serve(listener, router(state).into_make_service()).await });
+
+            addr
+        }
+    };
+
+    let addr = serve(&["--docs"]).await;
+    let served: serde_json::Value = reqwest::get(format!("http://{addr}/api-docs/openapi.json")).await.unwrap().json().await.unwrap();
+    assert_eq!(served, doc);
+    assert!(reqwest::get(format!("http://{addr}/docs/")).await.unwrap().status().is_success());
+
+    let addr = serve(&[]).await;
+    assert!(!reqwest::get(format!("http://{addr}/api-docs/openapi.json")).await.unwrap().status().is_success());
+}
+
+#[tokio::test]
+async fn test_auth_verify() {
+    use qubic_types::{Nonce, QubicWallet};
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--auth-audience", "example.org"])));
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    let challenge = SignedChallenge::sign(&wallet, "example.org", now, Nonce([7; 32]));
+    let (status, Json(res)) = auth_verify_handler(State(state.clone()), Json(challenge.clone())).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(res, AuthVerification { identity: wallet.public_key, valid: true, error: None });
+
+    // the clock of the signer may run ahead up to --auth-max-skew
+    let (status, _) = auth_verify_handler(State(state.clone()), Json(SignedChallenge::sign(&wallet, "example.org", now + 20, Nonce([9; 32])))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    let rejected = [
+        // replayed
+        challenge.clone(),
+        SignedChallenge { nonce: Nonce([8; 32]), ..challenge.clone() },
+        SignedChallenge::sign(&wallet, "example.com", now, Nonce([10; 32])),
+        SignedChallenge::sign(&wallet, "example.org", now - 301, Nonce([11; 32])),
+        SignedChallenge::sign(&wallet, "example.org", now + 60, Nonce([12; 32]))
+    ];
+
+    for challenge in rejected {
+        let (status, Json(res)) = auth_verify_handler(State(state.clone()), Json(challenge)).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert!(!res.valid && res.error.is_some());
+    }
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    let (status, Json(res)) = auth_verify_handler(State(state), Json(challenge)).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+    assert!(!res.valid);
+}
+
+#[tokio::test]
+async fn test_wire_dump_dir() {
+    use std::sync::atomic::{AtomicU32, AtomicUsize};
+    use qubic_web3_rs::wire_dump::{Direction, WireFrame};
+
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-wire-dump-{}", std::process::id()));
+    let computor = ticks::fake_computor(Arc::new(AtomicU32::new(1000)), Arc::new(AtomicUsize::new(0)));
+
+    let client = ClientBuilder::<Tcp>::new(&computor).with_wire_dump(wire_dump(dir.to_str().unwrap(), 16).unwrap()).build().await.unwrap();
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap().tick, 1000);
+
+    let file = std::fs::read_dir(&dir).unwrap().next().unwrap().unwrap().path();
+    let frames: Vec<WireFrame> = std::fs::read_to_string(file).unwrap().lines().map(|line| line.parse().unwrap()).collect();
+    assert_eq!(frames.iter().map(|frame| (frame.direction, frame.peer.as_str())).collect::<Vec<_>>(), [(Direction::Sent, computor.as_str()), (Direction::Received, computor.as_str())]);
+
+    let _ = std::fs::remove_dir_all(dir);
+}
+
+#[tokio::test]
+async fn test_next_tick_handler() {
+    use std::sync::atomic::{AtomicU32, AtomicUsize};
+
+    let next_tick = |state: Arc<ServerState>, after: u32, timeout: u64| async move {
+        let res = next_tick_handler(State(state), Query(NextTickQuery { after, timeout: Some(timeout) })).await;
+        let status = res.status();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+        (status, body)
+    };
+
+    // nothing listens on port 1, no tick is ever received
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    assert_eq!(next_tick(state, 0, 0).await.0, StatusCode::GATEWAY_TIMEOUT);
+
+    let computor = ticks::fake_computor(Arc::new(AtomicU32::new(1000)), Arc::new(AtomicUsize::new(0)));
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor, "--tick-poll-interval", "50"])));
+
+    let (status, body) = next_tick(state.clone(), 999, 5).await;
+    let next: NextTick = serde_json::from_slice(&body).unwrap();
+    assert_eq!((status, next.changed, next.tick_info.tick), (StatusCode::OK, true, 1000));
+
+    let (status, body) = next_tick(state, 1000, 0).await;
+    let next: NextTick = serde_json::from_slice(&body).unwrap();
+    assert_eq!((status, next.changed, next.tick_info.tick), (StatusCode::OK, false, 1000));
+}
+
+#[tokio::test]
+async fn test_network_stats_handlers() {
+    let body = |res: Response| async move {
+        let status = res.status();
+        (status, axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap())
+    };
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    assert_eq!(network_stats_latest_handler(State(state)).await.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-network-stats-{}.sled", std::process::id()));
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--stats-db", path.to_str().unwrap()])));
+    assert_eq!(network_stats_latest_handler(State(state.clone())).await.status(), StatusCode::NOT_FOUND);
+
+    let sample = |tick| NetworkStats { tick, epoch: 100, number_of_entities: tick, number_of_transactions: 0, solution_threshold: 29 };
+    for tick in [100, 105, 110, 120] {
+        state.stats.as_ref().unwrap().insert(sample(tick)).unwrap();
+    }
+
+    let (status, latest) = body(network_stats_latest_handler(State(state.clone())).await).await;
+    assert_eq!((status, serde_json::from_slice::<NetworkStats>(&latest).unwrap()), (StatusCode::OK, sample(120)));
+
+    let (status, history) = body(network_stats_history_handler(State(state.clone()), Query(StatsRange { from_tick: 100, to_tick: 119, step: Some(10) })).await).await;
+    assert_eq!((status, serde_json::from_slice::<Vec<NetworkStats>>(&history).unwrap()), (StatusCode::OK, vec![sample(105), sample(110)]));
+
+    let res = network_stats_history_handler(State(state.clone()), Query(StatsRange { from_tick: 120, to_tick: 100, step: None })).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_healthcheck() {
+    use std::sync::atomic::{AtomicU32, AtomicUsize};
+    use qubic_rpc_types::HealthCheckKind;
+    use crate::archiver::tick_data;
+
+    let healthcheck = |state: Arc<ServerState>| async move {
+        let res = healthcheck_handler(State(state)).await;
+        let status = res.status();
+        let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+        (status, serde_json::from_slice::<HealthCheck>(&body).unwrap())
+    };
+
+    // nothing listens on port 1
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    let (status, check) = healthcheck(state).await;
+    assert_eq!((status, check.failed, check.database), (StatusCode::SERVICE_UNAVAILABLE, vec![HealthCheckKind::Upstream], None));
+
+    let requests = Arc::new(AtomicUsize::new(0));
+    let computor = ticks::fake_computor(Arc::new(AtomicU32::new(1000)), requests.clone());
+    let path = std::env::temp_dir().join(format!("qubic-rpc-healthcheck-{}.sled", std::process::id()));
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor, "--archive-db", path.to_str().unwrap()])));
+
+    let mut archiver = Archiver::new(4).with_sink(state.archive.clone().unwrap());
+    archiver.ingest(tick_data(100, 10), vec![], None).await;
+    archiver.shutdown().await;
+
+    let (status, check) = healthcheck(state.clone()).await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(check, HealthCheck { status: false, failed: vec![HealthCheckKind::ArchiveLag], upstream_age: Some(0), current_tick: Some(1000), archive_lag: Some(990), database: Some(true) });
+
+    // the current tick is reused within the allowed age
+    healthcheck(state.clone()).await;
+    assert_eq!(requests.load(std::sync::atomic::Ordering::Relaxed), 1);
+    // the archive database is locked until the state is dropped
+    drop(state);
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor, "--archive-db", path.to_str().unwrap(), "--health-max-archive-lag", "1000"])));
+    let (status, check) = healthcheck(state).await;
+    assert_eq!((status, check.status, check.failed), (StatusCode::OK, true, vec![]));
+
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_computors_health() {
+    use qubic_types::QubicId;
+    use qubic_web3_rs::qubic_tcp_types::{consts::NUMBER_OF_COMPUTORS, types::ticks::Tick};
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--monitor-window", "5"])));
+    let computor = QubicWallet::from_seed(&"a".repeat(55)).unwrap();
+
+    {
+        let mut computors = vec![QubicId::default(); NUMBER_OF_COMPUTORS];
+        computors[7] = computor.public_key;
+
+        let mut monitor = state.monitor.as_ref().unwrap().lock().unwrap();
+        monitor.set_computors(100, computors);
+
+        for tick in 1..=10 {
+            let mut vote = Tick { tick, epoch: 100, computor_index: 7, ..Default::default() };
+            vote.sign(&computor);
+            monitor.add_vote(vote);
+        }
+    }
+
+    let res = computors_health_handler(State(state)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let health: ComputorsHealth = serde_json::from_slice(&body).unwrap();
+    assert_eq!((health.epoch, health.ticks, health.computors.len()), (Some(100), 5, NUMBER_OF_COMPUTORS));
+    assert_eq!(health.computors[7].identity, Some(computor.public_key));
+    assert_eq!((health.computors[7].signed, health.computors[7].missed), (5, 0));
+    assert_eq!((health.computors[0].signed, health.computors[0].missed), (0, 5));
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    assert_eq!(computors_health_handler(State(state)).await.status(), StatusCode::NOT_IMPLEMENTED);
+}
+
+#[tokio::test]
+async fn test_submit_work_handler() {
+    use qubic_types::QubicId;
+
+    let work = |nonce: &str| Json(SubmitWork { identity: QubicId([1; 32]), random_seed: "07".repeat(32), nonce: nonce.to_owned() });
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    assert_eq!(submit_work_handler(State(state), None, work("0x01")).await.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--work-relay-seed", "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa"])));
+    let res = submit_work_handler(State(state.clone()), None, work("0x01")).await;
+    assert_eq!(res.status(), StatusCode::BAD_REQUEST);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(body, "Invalid nonce \"0x01\", expected 32 bytes of 0x prefixed hex");
+
+    // the JSON-RPC method shares the relay, nothing listens on port 1
+    let (status, _, Json(res)) = request_handler(State(state), Some(IpAddr::from([10, 0, 0, 1])), None, Json(QubicJsonRpcRequest::new(0, RequestMethods::RequestSubmitWork(work(&format!("0x{}", "01".repeat(32))).0)))).await;
+    assert_eq!(status, StatusCode::BAD_GATEWAY);
+    assert!(matches!(res.response, ResponseType::Error(e) if e.error.starts_with("Failed to relay solution")));
+}
+
+#[tokio::test]
+async fn test_mining_ranking_handler() {
+    use qubic_types::{Nonce, QubicWallet};
+    use qubic_web3_rs::qubic_tcp_types::types::special_commands::MiningScoreEntry;
+
+    let seed = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+    let (viewer, other) = (QubicWallet::from_seed(seed).unwrap(), QubicWallet::from_seed(&"b".repeat(55)).unwrap());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    // every challenge is accepted once
+    let nonce = std::sync::atomic::AtomicU8::new(0);
+    let challenge = |wallet: &QubicWallet, audience: &str| {
+        let mut headers = HeaderMap::new();
+        headers.insert(CHALLENGE_HEADER, serde_json::to_string(&SignedChallenge::sign(wallet, audience, now, Nonce([nonce.fetch_add(1, Ordering::Relaxed); 32]))).unwrap().parse().unwrap());
+        headers
+    };
+
+    let serve = |args: &[&str]| Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"].iter().chain(args))));
+
+    assert_eq!(mining_ranking_handler(State(serve(&["--auth-audience", "example.org"])), challenge(&viewer, "example.org")).await.status(), StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(mining_ranking_handler(State(serve(&["--operator-seed", seed])), challenge(&viewer, "example.org")).await.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let viewer_id = viewer.public_key.to_string();
+    let state = serve(&["--operator-seed", seed, "--auth-audience", "example.org", "--ranking-viewer", &viewer_id]);
+
+    assert_eq!(mining_ranking_handler(State(state.clone()), HeaderMap::new()).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(mining_ranking_handler(State(state.clone()), challenge(&viewer, "example.com")).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(mining_ranking_handler(State(state.clone()), challenge(&other, "example.org")).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(mining_ranking_handler(State(state.clone()), challenge(&viewer, "example.org")).await.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let ranking = MiningRanking { fetched_at: now, rankings: vec![MiningScoreEntry { miner: QubicId([1; 32]), score: 120 }] };
+    state.ranking.as_ref().unwrap().set(ranking.clone());
+
+    let res = mining_ranking_handler(State(state), challenge(&viewer, "example.org")).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(serde_json::from_slice::<MiningRanking>(&body).unwrap(), ranking);
+}
+
+#[tokio::test]
+async fn test_audit_handler() {
+    use qubic_rpc_types::AuditOperation;
+    use qubic_types::{Nonce, QubicWallet};
+    use wiremock::{Mock, MockServer, ResponseTemplate, matchers::{method, path}};
+
+    let server = MockServer::start().await;
+
+    Mock::given(method("POST")).and(path("/v1/broadcast-transaction"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(serde_json::json!({ "peersBroadcasted": 3, "encodedTransaction": "", "transactionId": "" })))
+        .mount(&server).await;
+
+    let (viewer, other) = (QubicWallet::from_seed(&"a".repeat(55)).unwrap(), QubicWallet::from_seed(&"b".repeat(55)).unwrap());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    // every challenge is accepted once
+    let nonce = std::sync::atomic::AtomicU8::new(0);
+    let challenge = |wallet: &QubicWallet| {
+        let mut headers = HeaderMap::new();
+        headers.insert(CHALLENGE_HEADER, serde_json::to_string(&SignedChallenge::sign(wallet, "example.org", now, Nonce([nonce.fetch_add(1, Ordering::Relaxed); 32]))).unwrap().parse().unwrap());
+        headers
+    };
+
+    let all = || Query(AuditRange { from: None, to: None });
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--auth-audience", "example.org"])));
+    assert_eq!(audit_handler(State(state), all(), challenge(&viewer)).await.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-audit-{}.sled", std::process::id()));
+    let viewer_id = viewer.public_key.to_string();
+    let state = Arc::new(ServerState::new(Args::parse_from([
+        "qubic-rpc", "--computor", "127.0.0.1:1", "--fallback-rpc", &server.uri(), "--proxy-only", "--auth-audience", "example.org",
+        "--audit-log", path.to_str().unwrap(), "--audit-viewer", &viewer_id
+    ])));
+
+    let tx = qubic_web3_rs::qubic_tcp_types::types::transactions::Transaction::default();
+    let (status, _, _) = request_handler(State(state.clone()), Some(IpAddr::from([10, 0, 0, 1])), None, Json(QubicJsonRpcRequest::new(1, RequestMethods::SendTransaction(tx.into())))).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // failed operations are not recorded
+    let (status, _, _) = request_handler(State(state.clone()), None, None, Json(QubicJsonRpcRequest::new(2, RequestMethods::RequestSubmitWork(SubmitWork { identity: QubicId([1; 32]), random_seed: "0x01".to_owned(), nonce: "0x01".to_owned() })))).await;
+    assert_eq!(status, StatusCode::NOT_IMPLEMENTED);
+
+    assert_eq!(audit_handler(State(state.clone()), all(), HeaderMap::new()).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(audit_handler(State(state.clone()), all(), challenge(&other)).await.status(), StatusCode::FORBIDDEN);
+
+    let res = audit_handler(State(state.clone()), all(), challenge(&viewer)).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let records = serde_json::from_slice::<Vec<AuditRecord>>(&body).unwrap();
+    let earlier = audit_handler(State(state.clone()), Query(AuditRange { from: None, to: Some(records[0].timestamp - 1) }), challenge(&viewer)).await;
+    let verified = state.audit.as_ref().unwrap().verify();
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+
+    assert_eq!(records.len(), 1);
+    assert_eq!((records[0].operation, records[0].client_ip.as_deref(), records[0].upstream_peer.as_str()), (AuditOperation::SendTransaction, Some("10.0.0.1"), server.uri().as_str()));
+    assert_eq!((records[0].tx_id, records[0].amount), (Some(QubicTxHash::from(tx)), Some(0)));
+    assert_eq!(axum::body::to_bytes(earlier.into_body(), usize::MAX).await.unwrap(), "[]");
+    assert_eq!(verified.unwrap(), 1);
+}
+
+#[tokio::test]
+async fn test_webhook_handlers() {
+    use qubic_rpc_types::{WebhookEvent, WebhookStatus};
+    use qubic_types::{Nonce, QubicWallet};
+
+    let registration = RegisterWebhook { url: "http://127.0.0.1:1/hook".to_owned(), identities: vec![], events: vec![WebhookEvent::TickFinalized], secret: "s".to_owned() };
+    let (owner, other) = (QubicWallet::from_seed(&"a".repeat(55)).unwrap(), QubicWallet::from_seed(&"b".repeat(55)).unwrap());
+    let now = SystemTime::now().duration_since(UNIX_EPOCH).unwrap().as_secs();
+
+    // every challenge is accepted once
+    let nonce = std::sync::atomic::AtomicU8::new(0);
+    let challenge = |wallet: &QubicWallet| {
+        let mut headers = HeaderMap::new();
+        headers.insert(CHALLENGE_HEADER, serde_json::to_string(&SignedChallenge::sign(wallet, "example.org", now, Nonce([nonce.fetch_add(1, Ordering::Relaxed); 32]))).unwrap().parse().unwrap());
+        headers
+    };
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--auth-audience", "example.org"])));
+    assert_eq!(register_webhook_handler(State(state.clone()), challenge(&owner), Json(registration.clone())).await.status(), StatusCode::NOT_IMPLEMENTED);
+    assert_eq!(webhook_handler(State(state), Path(0), challenge(&owner)).await.status(), StatusCode::NOT_IMPLEMENTED);
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-webhook-handlers-{}.sled", std::process::id()));
+    let serve = |args: &[&str]| Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--archive-db", path.to_str().unwrap()].iter().chain(args))));
+
+    // registrations need an authenticated identity and a public receiver
+    let state = serve(&["--auth-audience", "example.org"]);
+    assert_eq!(register_webhook_handler(State(state.clone()), HeaderMap::new(), Json(registration.clone())).await.status(), StatusCode::UNAUTHORIZED);
+    assert_eq!(register_webhook_handler(State(state.clone()), challenge(&owner), Json(registration.clone())).await.status(), StatusCode::BAD_REQUEST);
+    drop(state);
+
+    let state = serve(&["--auth-audience", "example.org", "--webhook-allow-private", "--webhook-max-per-identity", "1"]);
+    let res = register_webhook_handler(State(state.clone()), challenge(&owner), Json(registration.clone())).await;
+    assert_eq!(res.status(), StatusCode::CREATED);
+
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    let webhook: Webhook = serde_json::from_slice(&body).unwrap();
+    assert_eq!((webhook.url.as_str(), webhook.status), ("http://127.0.0.1:1/hook", WebhookStatus::Active));
+    assert_eq!(register_webhook_handler(State(state.clone()), challenge(&owner), Json(registration.clone())).await.status(), StatusCode::TOO_MANY_REQUESTS);
+
+    // the secret is never served
+    let res = webhook_handler(State(state.clone()), Path(webhook.id), challenge(&owner)).await;
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(serde_json::from_slice::<Webhook>(&body).unwrap(), webhook);
+    assert!(serde_json::from_slice::<serde_json::Value>(&body).unwrap().get("secret").is_none());
+
+    assert_eq!(webhook_handler(State(state.clone()), Path(webhook.id), challenge(&other)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(webhook_handler(State(state.clone()), Path(webhook.id + 1), challenge(&owner)).await.status(), StatusCode::NOT_FOUND);
+    assert_eq!(register_webhook_handler(State(state.clone()), challenge(&other), Json(RegisterWebhook { secret: String::new(), ..registration })).await.status(), StatusCode::BAD_REQUEST);
+
+    assert_eq!(reactivate_webhook_handler(State(state.clone()), Path(webhook.id), challenge(&other)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(reactivate_webhook_handler(State(state.clone()), Path(webhook.id), challenge(&owner)).await.status(), StatusCode::OK);
+    assert_eq!(delete_webhook_handler(State(state.clone()), Path(webhook.id), challenge(&other)).await.status(), StatusCode::FORBIDDEN);
+    assert_eq!(delete_webhook_handler(State(state.clone()), Path(webhook.id), challenge(&owner)).await.status(), StatusCode::OK);
+    assert_eq!(webhook_handler(State(state.clone()), Path(webhook.id), challenge(&owner)).await.status(), StatusCode::NOT_FOUND);
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_tx_status_handler() {
+    use qubic_web3_rs::qubic_tcp_types::{consts::QUORUM, types::transactions::{RawTransaction, TransactionWithData}};
+    use crate::archiver::{quorum_votes, tick_data};
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-tx-status-{}.sled", std::process::id()));
+    // nothing listens on port 1, only archived ticks are answered
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1", "--archive-db", path.to_str().unwrap()])));
+
+    let tx = TransactionWithData::from(RawTransaction { amount: 42, tick: 10, ..Default::default() });
+    let (listed, received) = (QubicTxHash::from(&tx), QubicTxHash([7; 32]));
+    let mut tick = tick_data(100, 10);
+    tick.transaction_digest[0] = listed;
+
+    let mut archiver = Archiver::new(4).with_sink(state.archive.clone().unwrap());
+    archiver.ingest(tick, vec![tx], None).await;
+    archiver.shutdown().await;
+
+    let status = |tx_id, tick| {
+        let state = state.clone();
+
+        async move {
+            let res = tx_status_handler(State(state), ParsedTxHash(tx_id), Query(TxStatusQuery { tick })).await;
+            let (status, source) = (res.status(), res.headers()[SOURCE_HEADER].to_str().unwrap().to_owned());
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+            (status, source, serde_json::from_slice::<serde_json::Value>(&body).ok())
+        }
+    };
+
+    assert_eq!(status(listed, 10).await, (StatusCode::OK, "archive".to_owned(), Some(serde_json::json!({ "status": "executed", "finalized": false }))));
+    assert_eq!(status(received, 10).await, (StatusCode::OK, "archive".to_owned(), Some(serde_json::json!({ "status": "notIncluded", "finalized": false }))));
+    assert_eq!(latest_finalized_handler(State(state.clone())).await.status(), StatusCode::NOT_FOUND);
+
+    // the tick is final once the archiver saw a quorum of votes for it
+    let mut archiver = Archiver::new(4).with_sink(state.archive.clone().unwrap());
+    archiver.ingest(tick, vec![], None).await;
+    archiver.observe_votes(10, &quorum_votes(10, QUORUM - 1)).await;
+    archiver.observe_votes(10, &quorum_votes(10, QUORUM)).await;
+    archiver.shutdown().await;
+
+    assert_eq!(status(listed, 10).await, (StatusCode::OK, "archive".to_owned(), Some(serde_json::json!({ "status": "executed", "finalized": true }))));
+
+    let res = latest_finalized_handler(State(state.clone())).await;
+    let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+    assert_eq!(serde_json::from_slice::<serde_json::Value>(&body).unwrap(), serde_json::json!({ "tick": 10, "epoch": 100 }));
+
+    // unarchived ticks are asked upstream
+    let (code, source, _) = status(listed, 11).await;
+    assert_eq!((code, source.as_str()), (StatusCode::BAD_GATEWAY, "computor"));
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_epoch_computors_handler() {
+    use std::io::{Read, Write};
+    use qubic_types::{traits::ToBytes, Signature};
+    use qubic_web3_rs::qubic_tcp_types::{consts::NUMBER_OF_COMPUTORS, types::{Computors, Packet}, Header};
+
+    let computors = |epoch: u16| Computors { epoch, public_key: [QubicId([epoch as u8; 32]); NUMBER_OF_COMPUTORS], signature: Signature([1; 64]) };
+
+    // computor of epoch 101
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let computor = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+
+            if stream.read_exact(&mut header).is_ok() {
+                let _ = stream.write_all(&Packet::new(computors(101), false).unwrap().to_bytes());
+            }
+        }
+    });
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-epoch-computors-{}.sled", std::process::id()));
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor, "--archive-db", path.to_str().unwrap()])));
+
+    let mut archiver = Archiver::new(4).with_sink(state.archive.clone().unwrap());
+    archiver.observe_computors(computors(100)).await;
+    archiver.shutdown().await;
+
+    let get = |epoch| {
+        let state = state.clone();
+
+        async move {
+            let res = epoch_computors_handler(State(state), Path(epoch)).await;
+            let (status, source) = (res.status(), res.headers()[SOURCE_HEADER].to_str().unwrap().to_owned());
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+            (status, source, serde_json::from_slice::<ComputorInfos>(&body).ok())
+        }
+    };
+
+    // past epochs are served from the archive, the lists are not signed by the arbitrator
+    let (status, source, past) = get(100).await;
+    let past = past.unwrap();
+    assert_eq!((status, source.as_str(), past.epoch, past.ids[0], past.verified), (StatusCode::OK, "archive", 100, QubicId([100; 32]), false));
+
+    let (status, source, current) = get(101).await;
+    assert_eq!((status, source.as_str(), current.unwrap().epoch), (StatusCode::OK, "computor", 101));
+    // the current list was archived on the way
+    assert_eq!(get(101).await.1, "archive");
+
+    let (status, source, _) = get(99).await;
+    assert_eq!((status, source.as_str()), (StatusCode::NOT_FOUND, "computor"));
+    assert_eq!(get(102).await.0, StatusCode::NOT_FOUND);
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_identity_transactions_handler() {
+    use std::str::FromStr;
+    use qubic_types::{MiningSeed, Nonce};
+    use qubic_web3_rs::qubic_tcp_types::types::{assets::{AssetName, TransferAssetOwnershipAndPossessionInput, QXID}, transactions::{RawTransaction, TransactionData, TransactionWithData}};
+
+    use archiver::tick_data;
+
+    let (alice, bob) = (QubicId([1; 32]), QubicId([2; 32]));
+    let tx = |from, to, amount, data: TransactionData| {
+        let mut raw_transaction = RawTransaction { from, to, amount, ..Default::default() };
+        data.sanitize_transaction(&mut raw_transaction).unwrap();
+
+        TransactionWithData { raw_transaction, data, ..Default::default() }
+    };
+    let qx_call = TransactionData::TransferOwnershipAndPossession(TransferAssetOwnershipAndPossessionInput { issuer: alice, possessor: alice, new_owner: bob, asset_name: AssetName::from_str("QX").unwrap(), number_of_units: 5 });
+    let submit_work = TransactionData::SubmitWork { seed: MiningSeed([3; 32]), nonce: Nonce([4; 32]) };
+
+    let path = std::env::temp_dir().join(format!("qubic-rpc-identity-transactions-{}.sled", std::process::id()));
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--archive-db", path.to_str().unwrap()])));
+
+    let mut archiver = Archiver::new(4).with_sink(state.archive.clone().unwrap());
+    archiver.ingest(tick_data(100, 10), vec![tx(alice, bob, 500, TransactionData::None), tx(bob, QubicId([5; 32]), 7, TransactionData::None)], None).await;
+    archiver.ingest(tick_data(100, 11), vec![tx(alice, QXID, 0, qx_call)], None).await;
+    archiver.ingest(tick_data(100, 12), vec![tx(alice, QubicId::default(), 0, submit_work)], None).await;
+    // transactions without amount are listed as well
+    archiver.ingest(tick_data(100, 13), vec![tx(alice, bob, 0, TransactionData::None)], None).await;
+    archiver.ingest(tick_data(100, 14), vec![tx(bob, alice, 30, TransactionData::None)], None).await;
+    archiver.shutdown().await;
+
+    let get = |id, query: IdentityTransactionsQuery| {
+        let state = state.clone();
+
+        async move {
+            let res = identity_transactions_handler(State(state), ParsedIdentity(id), Query(query)).await;
+            let status = res.status();
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+            (status, serde_json::from_slice::<TransactionsResponse>(&body).ok())
+        }
+    };
+    let query = |from_tick, to_tick, page, page_size| IdentityTransactionsQuery { from_tick, to_tick, page, page_size };
+
+    let (status, all) = get(alice, query(None, None, None, None)).await;
+    let all = all.unwrap();
+    let kinds = all.transactions.iter().map(|tx| (tx.tick, tx.kind.as_str(), tx.amount)).collect::<Vec<_>>();
+    assert_eq!((status, all.total, all.page, all.page_size), (StatusCode::OK, 5, 1, 100));
+    assert_eq!(kinds, [(10, "None", 500), (11, "TransferOwnershipAndPossession", 1_000_000), (12, "SubmitWork", 1_000_000), (13, "None", 0), (14, "None", 30)]);
+
+    // pages of the tick range
+    let (_, page) = get(alice, query(Some(11), Some(13), Some(2), Some(2))).await;
+    let page = page.unwrap();
+    assert_eq!((page.total, page.transactions.len(), page.transactions[0].tick), (3, 1, 13));
+    assert_eq!(get(alice, query(None, None, Some(4), Some(2))).await.1.unwrap().transactions, []);
+
+    // the transfers between the identities are listed for both
+    let (_, bobs) = get(bob, query(None, None, None, None)).await;
+    assert_eq!(bobs.unwrap().transactions.iter().map(|tx| tx.tick).collect::<Vec<_>>(), [10, 10, 13, 14]);
+
+    assert_eq!(get(alice, query(Some(12), Some(11), None, None)).await.0, StatusCode::BAD_REQUEST);
+
+    let without_archive = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc"])));
+    let res = identity_transactions_handler(State(without_archive), ParsedIdentity(alice), Query(query(None, None, None, None))).await;
+    assert_eq!(res.status(), StatusCode::NOT_IMPLEMENTED);
+
+    drop(state);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_route_path_params() {
+    const ID: &str = "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK";
+    let hash = QubicTxHash(ID.parse::<QubicId>().unwrap().0).get_identity();
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", "127.0.0.1:1"])));
+    let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, router(state).into_make_service()).await });
+
+    let get = |path: String| async move {
+        let res = reqwest::get(format!("http://{addr}{path}")).await.unwrap();
+        (res.status(), res.text().await.unwrap())
+    };
+
+    // identities of any case reach the handlers, which are not served without an archive
+    for id in [ID.to_owned(), ID.to_lowercase(), format!("{}{}", &ID[..30], ID[30..].to_lowercase())] {
+        assert_eq!(get(format!("/v2/identities/{id}/transactions")).await.0, StatusCode::NOT_IMPLEMENTED, "{id}");
+        assert_eq!(get(format!("/v1/identities/{id}/diff?from_tick=1&to_tick=2")).await.0, StatusCode::NOT_IMPLEMENTED, "{id}");
+    }
+
+    for id in [&ID[..59], &format!("{ID}A"), &format!("{}7", &ID[..59])] {
+        let expected = (StatusCode::BAD_REQUEST, "identity must be 60 alphabetic characters".to_owned());
+        assert_eq!(get(format!("/v2/identities/{id}/transactions")).await, expected, "{id}");
+        assert_eq!(get(format!("/v1/identities/{id}/diff?from_tick=1&to_tick=2")).await, expected, "{id}");
+    }
+
+    // the status of a hash of any case is asked from the computor, which is unreachable
+    for tx_id in [hash.clone(), hash.to_uppercase()] {
+        assert!(get(format!("/v1/tx-status/{tx_id}?tick=1")).await.0.is_server_error(), "{tx_id}");
+    }
+
+    for tx_id in [&hash[..59], &format!("{hash}a"), &format!("{}_", &hash[..59])] {
+        assert_eq!(get(format!("/v1/tx-status/{tx_id}?tick=1")).await, (StatusCode::BAD_REQUEST, "transaction hash must be 60 alphabetic characters".to_owned()), "{tx_id}");
+    }
+}
+
+#[tokio::test]
+async fn test_simulate_transfer_handler() {
+    use std::io::{Read, Write};
+    use qubic_types::traits::{FromBytes, ToBytes};
+    use qubic_web3_rs::qubic_tcp_types::{types::{ticks::CurrentTickInfo, Entity, ExchangePublicPeers, Packet, RespondedEntity, SystemInfo}, Header, MessageType};
+
+    // computor at tick 1000 where every entity holds 10000 QU
+    fn serve(mut stream: std::net::TcpStream) -> std::io::Result<()> {
+        stream.write_all(&Packet::new(ExchangePublicPeers::default(), false).unwrap().to_bytes())?;
+
+        loop {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+            stream.read_exact(&mut header)?;
+            let header = Header::from_bytes(&header).unwrap();
+            let mut payload = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+            stream.read_exact(&mut payload)?;
+
+            let mut packet = match header.message_type {
+                MessageType::RequestEntity => {
+                    let entity = Entity { public_key: QubicId::from_bytes(&payload).unwrap(), incoming_amount: 10_000, outgoing_amount: 0, number_of_incoming_transfers: 1, number_of_outgoing_transfers: 0, latest_incoming_transfer_tick: 900, latest_outgoing_transfer_tick: 0 };
+                    Packet::new(RespondedEntity { entity, tick: 1_000, spectrum_index: 0, siblings: Default::default() }, false).unwrap().to_bytes()
+                },
+                MessageType::RequestCurrentTickInfo => {
+                    Packet::new(CurrentTickInfo { tick_duration: 2, epoch: 100, tick: 1_000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 900 }, false).unwrap().to_bytes()
+                },
+                MessageType::RequestSystemInfo => {
+                    let mut system_info = SystemInfo::from_bytes(&vec![0; std::mem::size_of::<SystemInfo>()]).unwrap();
+                    system_info.current_entity_balance_dust_threshold = 10;
+                    Packet::new(system_info, false).unwrap().to_bytes()
+                },
+                _ => continue
+            };
+
+            packet[4..8].copy_from_slice(&header.dejavu.to_le_bytes());
+            stream.write_all(&packet)?;
+        }
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let computor = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || serve(stream));
+        }
+    });
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor])));
+    let transfer = ExternalRawTransaction { source_id: QubicId([1; 32]), dest_id: QubicId([2; 32]), amount: 500, tick: 1_010, input_type: 0, input_hex: String::new() };
+
+    let simulate = |raw_transaction: ExternalRawTransaction| {
+        let state = state.clone();
+
+        async move {
+            let res = simulate_transfer_handler(State(state), Json(raw_transaction)).await;
+            let status = res.status();
+            let body = axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap();
+
+            (status, serde_json::from_slice::<TransferSimulation>(&body).ok())
+        }
+    };
+
+    let (status, simulation) = simulate(transfer.clone()).await;
+    let simulation = simulation.unwrap();
+    assert_eq!((status, simulation.likely_to_execute), (StatusCode::OK, true));
+    assert_eq!(simulation.balance.detail, "Balance 10000 of the source, 500 required");
+
+    let verdict = |simulation: TransferSimulation| (simulation.likely_to_execute, simulation.balance.passed, simulation.tick.passed, simulation.amount.passed, simulation.destination.passed);
+
+    let (_, overdrawn) = simulate(ExternalRawTransaction { amount: 10_001, ..transfer.clone() }).await;
+    assert_eq!(verdict(overdrawn.unwrap()), (false, false, true, true, true));
+
+    let (_, late) = simulate(ExternalRawTransaction { tick: 1_002, ..transfer.clone() }).await;
+    assert_eq!(verdict(late.unwrap()), (false, true, false, true, true));
+
+    let (_, dust) = simulate(ExternalRawTransaction { amount: 5, ..transfer.clone() }).await;
+    assert_eq!(verdict(dust.unwrap()), (false, true, true, false, true));
+
+    assert_eq!(simulate(ExternalRawTransaction { input_hex: "0g".to_owned(), ..transfer }).await.0, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_asset_by_name_handler() {
+    use std::io::{Read, Write};
+    use std::str::FromStr;
+    use qubic_types::traits::{FromBytes, ToBytes};
+    use qubic_web3_rs::qubic_tcp_types::{types::{assets::{Asset, AssetName, AssetType, Issuance, Ownership, RequestAssets, RespondAssets}, ExchangePublicPeers, Packet}, Header, MessageType};
+
+    // universe holding the issuance of CFB with two ownerships of 600 and 400 units
+    fn serve(mut stream: std::net::TcpStream) -> std::io::Result<()> {
+        stream.write_all(&Packet::new(ExchangePublicPeers::default(), false).unwrap().to_bytes())?;
+
+        loop {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+            stream.read_exact(&mut header)?;
+            let header = Header::from_bytes(&header).unwrap();
+            let mut payload = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+            stream.read_exact(&mut payload)?;
+
+            let request = RequestAssets::from_bytes(&payload).unwrap();
+            let cfb = AssetName::<8>::from_str("CFB").unwrap();
+            let records = match request.request_type {
+                _ if request.asset_name != cfb.as_u64() => vec![],
+                RequestAssets::ISSUANCE_RECORDS => vec![RespondAssets {
+                    asset: Asset { public_key: QubicId([7; 32]), asset_type: AssetType::Issuance(Issuance { name: cfb.into(), number_of_decimal_places: 0, unit_of_measurement: [0; 7] }) },
+                    tick: 1_000,
+                    universe_index: 3
+                }],
+                _ => [600, 400].into_iter().zip(10..).map(|(units, universe_index)| RespondAssets {
+                    asset: Asset { public_key: QubicId([1; 32]), asset_type: AssetType::Ownership(Ownership { padding: 0, managing_contract_index: 1.into(), issuance_index: 3.into(), number_of_units: i64::into(units) }) },
+                    tick: 1_000,
+                    universe_index
+                }).collect()
+            };
+
+            for mut packet in records.into_iter().map(|record| Packet::new(record, false).unwrap().to_bytes()).chain([Header::new(std::mem::size_of::<Header>().try_into().unwrap(), MessageType::EndResponse, false).to_bytes()]) {
+                packet[4..8].copy_from_slice(&header.dejavu.to_le_bytes());
+                stream.write_all(&packet)?;
+            }
+        }
+    }
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let computor = listener.local_addr().unwrap().to_string();
+    std::thread::spawn(move || {
+        for stream in listener.incoming().flatten() {
+            std::thread::spawn(move || serve(stream));
+        }
+    });
+
+    let state = Arc::new(ServerState::new(Args::parse_from(["qubic-rpc", "--computor", &computor])));
+    let lookup = |name: &str| {
+        let state = state.clone();
+        let name = name.to_owned();
+
+        async move {
+            let res = asset_by_name_handler(State(state), Path(name)).await;
+            let status = res.status();
+
+            (status, axum::body::to_bytes(res.into_body(), usize::MAX).await.unwrap())
+        }
+    };
+
+    let (status, body) = lookup("CFB").await;
+    let asset: AssetSummary = serde_json::from_slice(&body).unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!((asset.issuer, asset.name.to_string(), asset.total_units), (QubicId([7; 32]), "CFB".to_owned(), Some(1000)));
+
+    assert_eq!(lookup("QFT").await.0, StatusCode::NOT_FOUND);
+    assert_eq!(lookup("TOOLONG8").await.0, StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,42 @@
+use std::{collections::HashMap, sync::Mutex};
+
+use qubic_types::{Nonce, QubicId};
+
+/// Nonces of the accepted challenges, kept until the challenges expired so every challenge is accepted once
+#[derive(Default)]
+pub struct UsedNonces {
+    used: Mutex<HashMap<(QubicId, Nonce), u64>>
+}
+
+impl UsedNonces {
+    /// records the `nonce` of `identity` until `expires_at`, false if it was recorded before. Nonces expired at `now`
+    /// are dropped, their challenges are rejected as expired anyway
+    pub fn claim(&self, identity: QubicId, nonce: Nonce, expires_at: u64, now: u64) -> bool {
+        let mut used = self.used.lock().unwrap();
+        used.retain(|_, expiry| *expiry >= now);
+
+        if used.contains_key(&(identity, nonce)) {
+            return false
+        }
+
+        used.insert((identity, nonce), expires_at);
+        true
+    }
+}
+
+#[test]
+fn test_used_nonces() {
+    let nonces = UsedNonces::default();
+    let (alice, bob) = (QubicId([1; 32]), QubicId([2; 32]));
+
+    assert!(nonces.claim(alice, Nonce([7; 32]), 1_300, 1_000));
+    assert!(!nonces.claim(alice, Nonce([7; 32]), 1_300, 1_100));
+
+    // nonces are scoped to the identity
+    assert!(nonces.claim(bob, Nonce([7; 32]), 1_300, 1_100));
+    assert!(nonces.claim(alice, Nonce([8; 32]), 1_300, 1_100));
+
+    // forgotten once expired
+    assert!(nonces.claim(alice, Nonce([7; 32]), 1_600, 1_301));
+    assert_eq!(nonces.used.lock().unwrap().len(), 1);
+}
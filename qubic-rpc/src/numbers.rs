@@ -0,0 +1,117 @@
+//! `numberFormat` query parameter of every route, amounts are answered as JSON numbers (the default) or as strings
+//! with `numberFormat=string`. Unknown formats are ignored and answered with numbers
+//!
+//! The middleware only records the format of the request, it is applied while the `Json` of this module serializes a
+//! response body (see `qubic_types::amount`). Anything else the handler serializes, like the records it stores, keeps
+//! numbers. Streamed bodies are serialized after the handler returned and carry the format themselves.
+
+use axum::{async_trait, extract::{rejection::JsonRejection, FromRequest, Query, Request}, middleware::Next, response::{IntoResponse, Response}};
+use qubic_types::amount::NumberFormat;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+
+tokio::task_local! {
+    static FORMAT: NumberFormat;
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct NumberFormatQuery {
+    #[serde(default)]
+    number_format: NumberFormat
+}
+
+/// middleware answering the responses it wraps in the requested format
+pub async fn number_format(request: Request, next: Next) -> Response {
+    let format = Query::<NumberFormatQuery>::try_from_uri(request.uri()).map_or_else(|_| NumberFormat::default(), |Query(query)| query.number_format);
+
+    FORMAT.scope(format, next.run(request)).await
+}
+
+/// format requested for the response of the current request, numbers outside of the middleware
+pub fn current() -> NumberFormat {
+    FORMAT.try_with(|format| *format).unwrap_or_default()
+}
+
+/// `axum::Json` serializing the amounts of responses in the requested format, requests are extracted as by `axum::Json`
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Json<T>(pub T);
+
+impl<T: Serialize> IntoResponse for Json<T> {
+    fn into_response(self) -> Response {
+        current().scope(|| axum::Json(self.0).into_response())
+    }
+}
+
+#[async_trait]
+impl<T: DeserializeOwned, S: Send + Sync> FromRequest<S> for Json<T> {
+    type Rejection = JsonRejection;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        axum::Json::<T>::from_request(request, state).await.map(|axum::Json(value)| Self(value))
+    }
+}
+
+#[tokio::test]
+async fn test_number_format() {
+    use axum::{http::StatusCode, routing::get, Router};
+    use qubic_rpc_types::{QubicJsonRpcResponse, RequestResults, ResponseType, RichListEntry};
+    use qubic_types::{QubicId, Signature};
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionData, TransactionWithData};
+
+    let entry = RichListEntry { identity: QubicId([1; 32]), balance: 9_007_199_254_740_993, tick: 100 };
+    let tx = TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 1_000, tick: 100, input_type: 0, input_size: 0 },
+        data: TransactionData::None,
+        signature: Signature([3; 64])
+    };
+
+    let app = Router::new()
+        .route("/v1/entry", get(move || async move { Json(entry) }))
+        // e.g. a record stored by the handler
+        .route("/v1/stored", get(move || async move { Json(serde_json::to_value(entry).unwrap()) }))
+        .route("/v1/transactions", get(move || async move {
+            let res = QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 0, response: ResponseType::Result(RequestResults::RequestTickTransactions(vec![tx.clone()])), diagnostics: None };
+
+            crate::stream::v1_response(StatusCode::OK, [("x-qubic-source", "computor")], res)
+        }))
+        .layer(axum::middleware::from_fn(number_format));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let get = |path: &'static str| {
+        let url = url.clone();
+
+        async move {
+            let res = reqwest::get(format!("{url}{path}")).await.unwrap();
+            (res.status(), res.json::<serde_json::Value>().await.ok())
+        }
+    };
+
+    // numbers stay the default
+    for path in ["/v1/entry", "/v1/entry?numberFormat=number"] {
+        let (_, entry) = get(path).await;
+        assert_eq!(entry.unwrap()["balance"], serde_json::json!(9_007_199_254_740_993u64));
+    }
+
+    let (_, strings) = get("/v1/entry?numberFormat=string").await;
+    let strings = strings.unwrap();
+    assert_eq!((&strings["balance"], &strings["tick"]), (&serde_json::json!("9007199254740993"), &serde_json::json!(100)));
+    // both forms are read back
+    assert_eq!(serde_json::from_value::<RichListEntry>(strings).unwrap(), entry);
+
+    // streamed elements keep the format of their request
+    let (_, numbers) = get("/v1/transactions").await;
+    let (_, strings) = get("/v1/transactions?numberFormat=string").await;
+    assert_eq!(numbers.unwrap()["result"][0]["raw_transaction"]["amount"], serde_json::json!(1_000));
+    assert_eq!(strings.unwrap()["result"][0]["raw_transaction"]["amount"], serde_json::json!("1000"));
+
+    // only the response body is affected
+    let (_, stored) = get("/v1/stored?numberFormat=string").await;
+    assert_eq!(stored.unwrap()["balance"], serde_json::json!(9_007_199_254_740_993u64));
+
+    // unknown formats are ignored
+    let (status, entry) = get("/v1/entry?numberFormat=hex").await;
+    assert_eq!((status, &entry.unwrap()["balance"]), (StatusCode::OK, &serde_json::json!(9_007_199_254_740_993u64)));
+}
@@ -0,0 +1,47 @@
+//! Panics of request handlers are logged with their route and answered with a JSON error, without the layer the
+//! connection is dropped and nothing tells which handler failed
+
+use std::{any::Any, panic::AssertUnwindSafe};
+
+use axum::{extract::{MatchedPath, Request}, http::StatusCode, middleware::Next, response::{IntoResponse, Response}, Json};
+use futures::FutureExt;
+
+/// middleware catching the panics of the handlers it wraps
+pub async fn catch_panic(request: Request, next: Next) -> Response {
+    let method = request.method().clone();
+    let route = request.extensions().get::<MatchedPath>().map_or_else(|| request.uri().path().to_owned(), |path| path.as_str().to_owned());
+
+    match AssertUnwindSafe(next.run(request)).catch_unwind().await {
+        Ok(response) => response,
+        Err(panic) => {
+            error!("{method} {route} panicked: {}", panic_message(&*panic));
+
+            (StatusCode::INTERNAL_SERVER_ERROR, Json(serde_json::json!({ "error": "Internal server error", "route": route }))).into_response()
+        }
+    }
+}
+
+fn panic_message(panic: &(dyn Any + Send)) -> &str {
+    panic.downcast_ref::<&str>().copied().or_else(|| panic.downcast_ref::<String>().map(String::as_str)).unwrap_or("non-string payload")
+}
+
+#[tokio::test]
+async fn test_catch_panic() {
+    use axum::{routing::get, Router};
+
+    let app = Router::new()
+        .route("/v1/panic/:id", get(|| async { if true { panic!("handler failed") } }))
+        .route("/v1/ok", get(|| async { "ok" }))
+        .layer(axum::middleware::from_fn(catch_panic));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let res = reqwest::get(format!("{url}/v1/panic/1")).await.unwrap();
+    assert_eq!(res.status(), StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(res.json::<serde_json::Value>().await.unwrap(), serde_json::json!({ "error": "Internal server error", "route": "/v1/panic/:id" }));
+
+    // the server keeps serving
+    assert_eq!(reqwest::get(format!("{url}/v1/ok")).await.unwrap().text().await.unwrap(), "ok");
+}
@@ -0,0 +1,111 @@
+//! Identities and transaction hashes in route paths, parsed regardless of their case
+//!
+//! Explorers link identities in lowercase and users paste them into our routes, while `QubicId::from_str` only takes
+//! uppercase identities and `QubicTxHash::from_str` only lowercase hashes. The extractors normalize the case of the
+//! path parameter before parsing it and reject a malformed one with a 400 naming the expected format.
+
+use axum::{async_trait, extract::{FromRequestParts, Path}, http::{request::Parts, StatusCode}, response::{IntoResponse, Response}};
+use qubic_types::{QubicId, QubicTxHash};
+
+/// Characters of an identity or a transaction hash
+const ID_LEN: usize = 60;
+
+/// Identity of the only path parameter of a route, in any case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedIdentity(pub QubicId);
+
+/// Transaction hash of the only path parameter of a route, in any case
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ParsedTxHash(pub QubicTxHash);
+
+/// `param` with its letters in the case of `uppercase`, an error naming `kind` unless it has the length and the
+/// alphabet of an identity
+fn normalize(param: &str, kind: &str, uppercase: bool) -> Result<String, String> {
+    if param.len() != ID_LEN || !param.bytes().all(|c| c.is_ascii_alphabetic()) {
+        return Err(format!("{kind} must be {ID_LEN} alphabetic characters"))
+    }
+
+    Ok(if uppercase { param.to_ascii_uppercase() } else { param.to_ascii_lowercase() })
+}
+
+impl ParsedIdentity {
+    pub fn parse(param: &str) -> Result<Self, String> {
+        normalize(param, "identity", true)?.parse().map(Self).map_err(|e| format!("Invalid identity: {e}"))
+    }
+}
+
+impl ParsedTxHash {
+    pub fn parse(param: &str) -> Result<Self, String> {
+        normalize(param, "transaction hash", false)?.parse().map(Self).map_err(|e| format!("Invalid transaction hash: {e}"))
+    }
+}
+
+/// the only path parameter of the route, its rejection as it is
+async fn path_param<S: Send + Sync>(parts: &mut Parts, state: &S) -> Result<String, Response> {
+    let Path(param) = Path::<String>::from_request_parts(parts, state).await.map_err(IntoResponse::into_response)?;
+
+    Ok(param)
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ParsedIdentity {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Self::parse(&path_param(parts, state).await?).map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())
+    }
+}
+
+#[async_trait]
+impl<S: Send + Sync> FromRequestParts<S> for ParsedTxHash {
+    type Rejection = Response;
+
+    async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        Self::parse(&path_param(parts, state).await?).map_err(|e| (StatusCode::BAD_REQUEST, e).into_response())
+    }
+}
+
+#[tokio::test]
+async fn test_path_params() {
+    use axum::{routing::get, Router};
+
+    const ID: &str = "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK";
+    let id: QubicId = ID.parse().unwrap();
+    let hash = QubicTxHash(id.0);
+    let hash_str = hash.get_identity();
+
+    let app = Router::new()
+        .route("/identities/:id", get(|ParsedIdentity(id): ParsedIdentity| async move { id.to_string() }))
+        .route("/tx/:tx_id", get(|ParsedTxHash(hash): ParsedTxHash| async move { hash.get_identity() }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    let get = |path: String| {
+        let url = url.clone();
+
+        async move {
+            let res = reqwest::get(format!("{url}{path}")).await.unwrap();
+            (res.status(), res.text().await.unwrap())
+        }
+    };
+
+    let mixed = |s: &str| s.chars().enumerate().map(|(i, c)| if i % 2 == 0 { c.to_ascii_lowercase() } else { c.to_ascii_uppercase() }).collect::<String>();
+
+    for param in [ID.to_owned(), ID.to_lowercase(), mixed(ID)] {
+        assert_eq!(get(format!("/identities/{param}")).await, (StatusCode::OK, ID.to_owned()), "{param}");
+    }
+
+    for param in [hash_str.clone(), hash_str.to_uppercase(), mixed(&hash_str)] {
+        assert_eq!(get(format!("/tx/{param}")).await, (StatusCode::OK, hash_str.clone()), "{param}");
+    }
+
+    for (route, kind, valid) in [("identities", "identity", ID), ("tx", "transaction hash", hash_str.as_str())] {
+        let expected = (StatusCode::BAD_REQUEST, format!("{kind} must be 60 alphabetic characters"));
+
+        for param in [&valid[..59], &format!("{valid}A"), &format!("{}1", &valid[..59]), &format!("{}-", &valid[..59]), &format!("{}é", &valid[..58])] {
+            assert_eq!(get(format!("/{route}/{param}")).await, expected, "{param}");
+        }
+    }
+}
@@ -0,0 +1,179 @@
+use std::{fmt::Display, str::FromStr};
+
+use axum::http::StatusCode;
+use qubic_rpc_types::{BroadcastedTransaction, Methods, RequestMethods, RequestResults, TransactionParamsError};
+use qubic_types::{QubicId, QubicTxHash, traits::ToBytes};
+use qubic_web3_rs::qubic_tcp_types::types::{Entity, ticks::CurrentTickInfo};
+use serde::{Deserialize, Deserializer, Serialize};
+
+/// Serves requests through the official HTTP API (e.g. https://rpc.qubic.org) if no computor is reachable
+pub struct FallbackRpc {
+    client: reqwest::Client,
+    url: String
+}
+
+#[derive(Debug)]
+pub enum ProxyError {
+    Http(reqwest::Error),
+    Unsupported(Methods),
+    InvalidTransaction(TransactionParamsError)
+}
+
+impl Display for ProxyError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Http(e) => write!(f, "Fallback RPC request failed: {e}"),
+            Self::Unsupported(method) => write!(f, "{method:?} is not supported by the fallback RPC"),
+            Self::InvalidTransaction(e) => write!(f, "{e}")
+        }
+    }
+}
+
+impl From<reqwest::Error> for ProxyError {
+    fn from(value: reqwest::Error) -> Self {
+        Self::Http(value)
+    }
+}
+
+impl ProxyError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Http(e) if e.is_timeout() => StatusCode::GATEWAY_TIMEOUT,
+            Self::Http(_) => StatusCode::BAD_GATEWAY,
+            Self::Unsupported(_) => StatusCode::NOT_IMPLEMENTED,
+            Self::InvalidTransaction(_) => StatusCode::BAD_REQUEST
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TickInfoResponse {
+    tick_info: TickInfo
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct TickInfo {
+    tick: u32,
+    duration: u16,
+    epoch: u16,
+    initial_tick: u32
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BalanceResponse {
+    balance: Balance
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct Balance {
+    id: String,
+    #[serde(deserialize_with = "from_str")]
+    incoming_amount: u64,
+    #[serde(deserialize_with = "from_str")]
+    outgoing_amount: u64,
+    number_of_incoming_transfers: u32,
+    number_of_outgoing_transfers: u32,
+    latest_incoming_transfer_tick: u32,
+    latest_outgoing_transfer_tick: u32
+}
+
+#[derive(Debug, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastRequest {
+    encoded_transaction: String
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct BroadcastResponse {
+    peers_broadcasted: usize
+}
+
+/// amounts are encoded as strings by the official API
+fn from_str<'de, D: Deserializer<'de>, T: FromStr>(deserializer: D) -> Result<T, D::Error> where T::Err: Display {
+    String::deserialize(deserializer)?.parse().map_err(serde::de::Error::custom)
+}
+
+fn base64_encode(data: &[u8]) -> String {
+    const ALPHABET: &[u8; 64] = b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789+/";
+
+    let mut encoded = String::with_capacity(data.len().div_ceil(3) * 4);
+
+    for chunk in data.chunks(3) {
+        let n = (chunk[0] as u32) << 16 | (*chunk.get(1).unwrap_or(&0) as u32) << 8 | *chunk.get(2).unwrap_or(&0) as u32;
+
+        for i in 0..4 {
+            if i <= chunk.len() {
+                encoded.push(ALPHABET[(n >> (18 - 6 * i) & 0x3F) as usize] as char);
+            } else {
+                encoded.push('=');
+            }
+        }
+    }
+
+    encoded
+}
+
+impl FallbackRpc {
+    pub fn new(url: &str) -> Self {
+        Self {
+            client: reqwest::Client::new(),
+            url: url.trim_end_matches('/').to_owned()
+        }
+    }
+
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    pub async fn request(&self, request: &RequestMethods) -> Result<RequestResults, ProxyError> {
+        match request {
+            RequestMethods::RequestCurrentTickInfo => {
+                let res: TickInfoResponse = self.client.get(format!("{}/v1/tick-info", self.url)).send().await?.error_for_status()?.json().await?;
+
+                Ok(RequestResults::RequestCurrentTickInfo(CurrentTickInfo {
+                    tick_duration: res.tick_info.duration,
+                    epoch: res.tick_info.epoch,
+                    tick: res.tick_info.tick,
+                    number_of_aligned_votes: 0,
+                    number_of_misaligned_votes: 0,
+                    initial_tick: res.tick_info.initial_tick
+                }))
+            },
+            RequestMethods::RequestEntity(id) => {
+                let res: BalanceResponse = self.client.get(format!("{}/v1/balances/{}", self.url, id)).send().await?.error_for_status()?.json().await?;
+
+                Ok(RequestResults::RequestEntity(Entity {
+                    public_key: QubicId::from_str(&res.balance.id).unwrap_or(*id),
+                    incoming_amount: res.balance.incoming_amount,
+                    outgoing_amount: res.balance.outgoing_amount,
+                    number_of_incoming_transfers: res.balance.number_of_incoming_transfers,
+                    number_of_outgoing_transfers: res.balance.number_of_outgoing_transfers,
+                    latest_incoming_transfer_tick: res.balance.latest_incoming_transfer_tick,
+                    latest_outgoing_transfer_tick: res.balance.latest_outgoing_transfer_tick
+                }))
+            },
+            RequestMethods::SendTransaction(params) => {
+                let tx = params.transaction().map_err(ProxyError::InvalidTransaction)?;
+                let body = BroadcastRequest { encoded_transaction: base64_encode(&tx.to_bytes()) };
+                let res: BroadcastResponse = self.client.post(format!("{}/v1/broadcast-transaction", self.url)).json(&body).send().await?.error_for_status()?.json().await?;
+
+                Ok(RequestResults::SendTransaction(BroadcastedTransaction { tx_hash: QubicTxHash::from(&tx), peers_broadcasted: res.peers_broadcasted }))
+            },
+            request => Err(ProxyError::Unsupported(request.get_method()))
+        }
+    }
+}
+
+#[test]
+fn test_base64_encode() {
+    assert_eq!(base64_encode(b""), "");
+    assert_eq!(base64_encode(b"f"), "Zg==");
+    assert_eq!(base64_encode(b"fo"), "Zm8=");
+    assert_eq!(base64_encode(b"foo"), "Zm9v");
+    assert_eq!(base64_encode(b"foobar"), "Zm9vYmFy");
+}
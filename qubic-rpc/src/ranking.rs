@@ -0,0 +1,62 @@
+use std::{sync::{Arc, Mutex}, time::{Duration, SystemTime, UNIX_EPOCH}};
+
+use qubic_rpc_types::MiningRanking;
+use qubic_types::{QubicId, QubicWallet};
+
+use crate::scheduler::{Priority, UpstreamScheduler};
+
+/// Mining score ranking of the operated computor, only the operator may request it so it is requested once per
+/// interval and served from the cache
+#[derive(Clone)]
+pub struct RankingCache {
+    operator: QubicWallet,
+    /// identities allowed to read the ranking, every authenticated identity if empty
+    viewers: Vec<QubicId>,
+    ranking: Arc<Mutex<Option<MiningRanking>>>
+}
+
+impl RankingCache {
+    pub fn new(operator: QubicWallet, viewers: Vec<QubicId>) -> Self {
+        Self { operator, viewers, ranking: Arc::default() }
+    }
+
+    /// `None` until the first request succeeded
+    pub fn get(&self) -> Option<MiningRanking> {
+        self.ranking.lock().unwrap().clone()
+    }
+
+    pub fn set(&self, ranking: MiningRanking) {
+        *self.ranking.lock().unwrap() = Some(ranking);
+    }
+
+    pub fn is_viewer(&self, id: &QubicId) -> bool {
+        self.viewers.is_empty() || self.viewers.contains(id)
+    }
+
+    /// Requests the ranking from `computor` every `interval` as background work of the `scheduler`, the last ranking
+    /// stays cached if a request fails
+    pub fn spawn_refresher(&self, computor: String, interval: Duration, scheduler: UpstreamScheduler) {
+        let cache = self.clone();
+
+        tokio::spawn(async move {
+            let client = match crate::computor_client(&computor).await {
+                Ok(client) => client,
+                Err(e) => return error!("Failed to request mining score ranking from {computor}: {e}")
+            };
+
+            loop {
+                scheduler.acquire(Priority::Background).await;
+
+                match client.qu().get_mining_score_ranking(&cache.operator).await {
+                    Ok(ranking) => cache.set(MiningRanking {
+                        fetched_at: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_secs(),
+                        rankings: ranking.rankings
+                    }),
+                    Err(e) => warn!("Failed to request mining score ranking from {computor}: {e}")
+                }
+
+                tokio::time::sleep(interval).await;
+            }
+        });
+    }
+}
@@ -0,0 +1,50 @@
+//! Recognizes what users paste into an explorer search: identities, transaction hashes and public keys in hex
+
+use qubic_rpc_types::ResolvedInput;
+use qubic_types::{LenientId, QubicId, QubicTxHash};
+
+/// what `input` is, an error if it is neither an identity, a transaction hash nor a public key.
+/// Identities and transaction hashes share their encoding apart from the case, lowercase input may be either
+pub fn resolve(input: &str) -> Result<ResolvedInput, String> {
+    let input = input.trim();
+    let hex = input.strip_prefix("0x").unwrap_or(input);
+
+    if hex.len() == 64 {
+        if let Ok(key) = hex::decode(hex) {
+            return Ok(ResolvedInput::PublicKey { identity: QubicId(key.try_into().unwrap()) })
+        }
+    }
+
+    match QubicId::parse_lenient(input) {
+        Ok(LenientId::Valid(identity)) if input.bytes().all(|c| c.is_ascii_lowercase()) => Ok(ResolvedInput::HashOrIdentity { hash: QubicTxHash(identity.0), identity }),
+        Ok(LenientId::Valid(identity)) => Ok(ResolvedInput::Identity { identity }),
+        Ok(LenientId::Suggestion(suggestions)) => Ok(ResolvedInput::Suggestions { suggestions }),
+        Err(e) => Err(format!("Neither an identity, a transaction hash nor a public key: {e}"))
+    }
+}
+
+#[test]
+fn test_resolve() {
+    use std::str::FromStr;
+
+    const ID: &str = "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK";
+    let id = QubicId::from_str(ID).unwrap();
+
+    assert_eq!(resolve(ID), Ok(ResolvedInput::Identity { identity: id }));
+    assert_eq!(resolve(&format!(" {}{}\n", ID[..30].to_lowercase(), &ID[30..])), Ok(ResolvedInput::Identity { identity: id }));
+
+    let hash = QubicTxHash(id.0);
+    assert_eq!(resolve(&hash.get_identity()), Ok(ResolvedInput::HashOrIdentity { hash, identity: id }));
+
+    for key in [hex::encode(id.0), format!("0x{}", hex::encode(id.0)), hex::encode(id.0).to_uppercase()] {
+        assert_eq!(resolve(&key), Ok(ResolvedInput::PublicKey { identity: id }), "{key}");
+    }
+
+    // a mistyped character is suggested, ambiguous corrections are all listed
+    assert_eq!(resolve(&ID.replace("NWL", "NXL")), Ok(ResolvedInput::Suggestions { suggestions: vec![ID.to_string()] }));
+    assert_eq!(resolve(&ID.replace("LZBQ", "LZBD")), Ok(ResolvedInput::Suggestions { suggestions: vec![ID.replace("QUAS", "QURS").replace("LZBQ", "LZBD"), ID.to_string()] }));
+
+    for invalid in ["", "BZBQ", &ID.replace("EXK", "AAA"), &hex::encode([1; 31])] {
+        assert!(resolve(invalid).is_err(), "{invalid}");
+    }
+}
@@ -0,0 +1,231 @@
+//! Rate limit of the requests sent to the computor, shared by the API requests, the archiver and the pollers
+//!
+//! A token bucket holding up to a second of `--upstream-rps` hands out the turns. API requests are interactive and
+//! always go first. The archiver and the pollers of ticks, stats, rankings and computor health are background work and
+//! only take a turn while no API request waits. Background work also leaves a part of the bucket untouched (see
+//! `--background-share`), so API requests arriving during a backfill find turns ready instead of queueing behind it.
+//! Without interactive traffic background work still gets the whole rate, the reserve is refilled before it is used up.
+
+use std::{sync::{atomic::{AtomicU64, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
+
+use qubic_rpc_types::{UpstreamClassMetrics, UpstreamSchedulerMetrics};
+
+/// Interval waiting background work checks again while API requests are waiting
+const BACKGROUND_POLL: Duration = Duration::from_millis(10);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Priority {
+    /// requests of API clients
+    Interactive,
+    /// requests of the archiver and the pollers, served with the capacity API requests leave over
+    Background
+}
+
+struct Bucket {
+    tokens: f64,
+    refilled: Instant,
+    /// interactive requests waiting for their turn, background work waits for them
+    interactive_waiting: usize
+}
+
+#[derive(Default)]
+struct ClassCounters {
+    queued: AtomicU64,
+    granted: AtomicU64,
+    total_wait_ms: AtomicU64,
+    max_wait_ms: AtomicU64
+}
+
+impl ClassCounters {
+    fn metrics(&self) -> UpstreamClassMetrics {
+        UpstreamClassMetrics {
+            queued: self.queued.load(Ordering::Relaxed),
+            granted: self.granted.load(Ordering::Relaxed),
+            total_wait_ms: self.total_wait_ms.load(Ordering::Relaxed),
+            max_wait_ms: self.max_wait_ms.load(Ordering::Relaxed)
+        }
+    }
+}
+
+struct Inner {
+    /// tokens per second, unlimited if unset
+    rate: Option<f64>,
+    capacity: f64,
+    /// tokens background work leaves in the bucket
+    reserve: f64,
+    bucket: Mutex<Bucket>,
+    interactive: ClassCounters,
+    background: ClassCounters
+}
+
+/// Token bucket of the upstream requests with an interactive and a background priority class
+#[derive(Clone)]
+pub struct UpstreamScheduler {
+    inner: Arc<Inner>
+}
+
+/// undoes the bookkeeping of a waiting request, also if the waiting future is dropped
+struct Waiting<'a> {
+    scheduler: &'a UpstreamScheduler,
+    priority: Priority
+}
+
+impl Drop for Waiting<'_> {
+    fn drop(&mut self) {
+        self.scheduler.counters(self.priority).queued.fetch_sub(1, Ordering::Relaxed);
+
+        if self.priority == Priority::Interactive {
+            self.scheduler.inner.bucket.lock().unwrap().interactive_waiting -= 1;
+        }
+    }
+}
+
+impl UpstreamScheduler {
+    /// `rps` requests per second, unlimited if `None`. Background work may use up `background_share` (0 to 1) of
+    /// the bucket, a share of 0 only lets it take turns from a full bucket
+    pub fn new(rps: Option<u32>, background_share: f64) -> Self {
+        let capacity = rps.unwrap_or(0).max(1) as f64;
+
+        Self {
+            inner: Arc::new(Inner {
+                rate: rps.map(|rps| rps.max(1) as f64),
+                capacity,
+                reserve: (capacity - 1.0) * (1.0 - background_share.clamp(0.0, 1.0)),
+                bucket: Mutex::new(Bucket { tokens: capacity, refilled: Instant::now(), interactive_waiting: 0 }),
+                interactive: ClassCounters::default(),
+                background: ClassCounters::default()
+            })
+        }
+    }
+
+    /// scheduler without a rate limit, the turns are only counted
+    pub fn unlimited() -> Self {
+        Self::new(None, 1.0)
+    }
+
+    fn counters(&self, priority: Priority) -> &ClassCounters {
+        match priority {
+            Priority::Interactive => &self.inner.interactive,
+            Priority::Background => &self.inner.background
+        }
+    }
+
+    /// waits for the turn of one upstream request
+    pub async fn acquire(&self, priority: Priority) {
+        let started = Instant::now();
+        let counters = self.counters(priority);
+        counters.queued.fetch_add(1, Ordering::Relaxed);
+
+        if priority == Priority::Interactive {
+            self.inner.bucket.lock().unwrap().interactive_waiting += 1;
+        }
+
+        let waiting = Waiting { scheduler: self, priority };
+
+        while let Some(wait) = self.try_take(priority) {
+            tokio::time::sleep(wait).await;
+        }
+
+        drop(waiting);
+
+        let waited = started.elapsed().as_millis() as u64;
+        counters.granted.fetch_add(1, Ordering::Relaxed);
+        counters.total_wait_ms.fetch_add(waited, Ordering::Relaxed);
+        counters.max_wait_ms.fetch_max(waited, Ordering::Relaxed);
+    }
+
+    /// takes a token, or else the time until one is available for `priority`
+    fn try_take(&self, priority: Priority) -> Option<Duration> {
+        let rate = self.inner.rate?;
+        let mut bucket = self.inner.bucket.lock().unwrap();
+
+        let now = Instant::now();
+        bucket.tokens = (bucket.tokens + now.duration_since(bucket.refilled).as_secs_f64() * rate).min(self.inner.capacity);
+        bucket.refilled = now;
+
+        let required = match priority {
+            Priority::Interactive => 1.0,
+            Priority::Background if bucket.interactive_waiting > 0 => return Some(BACKGROUND_POLL),
+            Priority::Background => 1.0 + self.inner.reserve
+        };
+
+        if bucket.tokens >= required {
+            bucket.tokens -= 1.0;
+            return None
+        }
+
+        Some(Duration::from_secs_f64((required - bucket.tokens) / rate).max(Duration::from_millis(1)))
+    }
+
+    pub fn metrics(&self) -> UpstreamSchedulerMetrics {
+        UpstreamSchedulerMetrics { interactive: self.inner.interactive.metrics(), background: self.inner.background.metrics() }
+    }
+}
+
+#[tokio::test]
+async fn test_rate_limit() {
+    let scheduler = UpstreamScheduler::new(Some(20), 0.5);
+    let started = Instant::now();
+
+    // the burst of a second, then 20 per second
+    for _ in 0..30 {
+        scheduler.acquire(Priority::Interactive).await;
+    }
+
+    assert!(started.elapsed() >= Duration::from_millis(450), "{:?}", started.elapsed());
+    assert_eq!(scheduler.metrics().interactive.granted, 30);
+
+    let unlimited = UpstreamScheduler::unlimited();
+    for _ in 0..1_000 {
+        unlimited.acquire(Priority::Background).await;
+    }
+
+    assert_eq!(unlimited.metrics().background, UpstreamClassMetrics { queued: 0, granted: 1_000, total_wait_ms: 0, max_wait_ms: 0 });
+}
+
+#[tokio::test]
+async fn test_interactive_during_backfill() {
+    use qubic_web3_rs::{client::Client, fake_computor::FakeComputor, qubic_tcp_types::{types::ticks::CurrentTickInfo, MessageType}, transport::Tcp};
+    use qubic_types::traits::ToBytes;
+
+    let info = CurrentTickInfo { tick_duration: 2, epoch: 100, tick: 1_000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 900 };
+    let computor = FakeComputor::new().respond(MessageType::RequestCurrentTickInfo, MessageType::RespondCurrentTickInfo, info.to_bytes()).start();
+    let scheduler = UpstreamScheduler::new(Some(20), 0.8);
+
+    // a backfill with far more requests than the rate allows
+    let backfill: Vec<_> = (0..50).map(|_| {
+        let (scheduler, url) = (scheduler.clone(), computor.url().to_owned());
+
+        tokio::spawn(async move {
+            let client = Client::<Tcp>::new(&url).await.unwrap();
+
+            loop {
+                scheduler.acquire(Priority::Background).await;
+                client.qu().get_current_tick_info().await.unwrap();
+            }
+        })
+    }).collect();
+
+    tokio::time::sleep(Duration::from_millis(500)).await;
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    for _ in 0..5 {
+        let started = Instant::now();
+        scheduler.acquire(Priority::Interactive).await;
+        assert_eq!(client.qu().get_current_tick_info().await.unwrap().tick, 1_000);
+
+        assert!(started.elapsed() < Duration::from_millis(200), "interactive request took {:?}", started.elapsed());
+        tokio::time::sleep(Duration::from_millis(100)).await;
+    }
+
+    let metrics = scheduler.metrics();
+    assert_eq!(metrics.interactive.granted, 5);
+    assert!(metrics.interactive.max_wait_ms < 100, "{metrics:?}");
+    // the backfill goes on with the remaining rate and is queued meanwhile
+    assert!(metrics.background.granted >= 10, "{metrics:?}");
+    assert!(metrics.background.queued > 0, "{metrics:?}");
+
+    for task in backfill {
+        task.abort();
+    }
+}
@@ -0,0 +1,311 @@
+use std::{fmt::Display, fs::File, io::{BufReader, BufWriter, Read, Write}, path::Path};
+
+use ruzstd::{decoding::StreamingDecoder, encoding::{compress, CompressionLevel}};
+use serde::{Deserialize, Serialize};
+
+use crate::archiver::SledSink;
+
+/// Layout of the archive trees, snapshots of another version are rejected on import. Bumped whenever
+/// `SledSink::TREES` or the encoding of a tree changes
+pub const SCHEMA_VERSION: u32 = 2;
+
+const MANIFEST: &str = "manifest.json";
+
+/// Records written to the archive per batch on import
+const IMPORT_BATCH: usize = 10_000;
+
+/// Describes a snapshot, stored as its first entry
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Manifest {
+    pub schema_version: u32,
+    /// first and last archived tick, `None` for an empty archive
+    pub first_tick: Option<u32>,
+    pub last_tick: Option<u32>,
+    /// tick archiving resumes after
+    pub cursor: Option<u32>,
+    pub trees: Vec<String>
+}
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    Io(std::io::Error),
+    Db(sled::Error),
+    Manifest(serde_json::Error),
+    SchemaMismatch { found: u32, expected: u32 },
+    /// the trees of the manifest differ from the archive trees of this version
+    TreeMismatch { found: Vec<String>, expected: Vec<String> },
+    NotEmpty,
+    Corrupted(String)
+}
+
+impl Display for SnapshotError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "Snapshot I/O failed: {e}"),
+            Self::Db(e) => write!(f, "Archive database failed: {e}"),
+            Self::Manifest(e) => write!(f, "Invalid snapshot manifest: {e}"),
+            Self::SchemaMismatch { found, expected } => write!(f, "Snapshot has schema version {found} but this qubic-rpc expects version {expected}"),
+            Self::TreeMismatch { found, expected } => write!(f, "Snapshot has the trees {found:?} but this qubic-rpc expects {expected:?}"),
+            Self::NotEmpty => write!(f, "Snapshots are only imported into an empty archive database"),
+            Self::Corrupted(reason) => write!(f, "Corrupted snapshot: {reason}")
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<std::io::Error> for SnapshotError {
+    fn from(value: std::io::Error) -> Self {
+        Self::Io(value)
+    }
+}
+
+impl From<sled::Error> for SnapshotError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+impl From<serde_json::Error> for SnapshotError {
+    fn from(value: serde_json::Error) -> Self {
+        Self::Manifest(value)
+    }
+}
+
+/// Writes the archive trees of `db` to `output` as zstd compressed tar of the manifest and one `<tree>.kv` file
+/// of length prefixed key/value pairs per tree. The server has to be stopped, sled locks the database anyway
+pub fn export(db: &sled::Db, output: &Path) -> Result<Manifest, SnapshotError> {
+    let ticks = db.open_tree("ticks")?;
+    let tick = |key: sled::IVec| key.as_ref().try_into().map(u32::from_be_bytes).ok();
+
+    let manifest = Manifest {
+        schema_version: SCHEMA_VERSION,
+        first_tick: ticks.first()?.and_then(|(key, _)| tick(key)),
+        last_tick: ticks.last()?.and_then(|(key, _)| tick(key)),
+        cursor: SledSink::from_db(db)?.cursor()?,
+        trees: SledSink::TREES.map(String::from).to_vec()
+    };
+
+    // the tar is staged next to the output since the encoder reads it back
+    let staged = output.with_extension("partial");
+    let mut builder = tar::Builder::new(BufWriter::new(File::create(&staged)?));
+    let manifest_json = serde_json::to_vec_pretty(&manifest)?;
+    append(&mut builder, MANIFEST, manifest_json.len() as u64, manifest_json.as_slice())?;
+
+    // the size of a tar entry precedes its data, so every tree is iterated once to size it and once to stream it
+    for name in SledSink::TREES {
+        let tree = db.open_tree(name)?;
+        let size = tree.iter().try_fold(0u64, |size, entry| entry.map(|(key, value)| size + 8 + key.len() as u64 + value.len() as u64))?;
+
+        append(&mut builder, &format!("{name}.kv"), size, TreeRecords { entries: tree.iter(), record: Vec::new(), offset: 0 }.take(size))?;
+    }
+
+    builder.into_inner()?.flush()?;
+    compress(BufReader::new(File::open(&staged)?), BufWriter::new(File::create(output)?), CompressionLevel::Fastest);
+    std::fs::remove_file(staged)?;
+
+    Ok(manifest)
+}
+
+/// Restores a snapshot written by `export` into the empty `db`, the manifest is checked before anything is written
+pub fn import(db: &sled::Db, input: &Path) -> Result<Manifest, SnapshotError> {
+    if SledSink::TREES.iter().any(|name| db.open_tree(name).map(|tree| !tree.is_empty()).unwrap_or(true)) {
+        return Err(SnapshotError::NotEmpty);
+    }
+
+    let decoder = StreamingDecoder::new(BufReader::new(File::open(input)?)).map_err(|e| SnapshotError::Corrupted(e.to_string()))?;
+    let mut archive = tar::Archive::new(decoder);
+    let mut entries = archive.entries()?;
+
+    let manifest: Manifest = match entries.next() {
+        Some(entry) if entry.as_ref().map(|entry| entry.path().is_ok_and(|path| path == Path::new(MANIFEST))).unwrap_or(false) => serde_json::from_reader(entry?)?,
+        _ => return Err(SnapshotError::Corrupted(format!("{MANIFEST} is not the first entry")))
+    };
+
+    if manifest.schema_version != SCHEMA_VERSION {
+        return Err(SnapshotError::SchemaMismatch { found: manifest.schema_version, expected: SCHEMA_VERSION });
+    }
+
+    // a restored archive missing a tree would be served as if nothing was archived there
+    let mut found = manifest.trees.clone();
+    found.sort_unstable();
+    let mut expected = SledSink::TREES.map(String::from).to_vec();
+    expected.sort_unstable();
+
+    if found != expected {
+        return Err(SnapshotError::TreeMismatch { found: manifest.trees, expected: SledSink::TREES.map(String::from).to_vec() });
+    }
+
+    for entry in entries {
+        let mut entry = entry?;
+        let path = entry.path()?.to_string_lossy().into_owned();
+        let name = path.strip_suffix(".kv").filter(|name| manifest.trees.iter().any(|tree| tree == name))
+            .ok_or_else(|| SnapshotError::Corrupted(format!("unexpected entry {path}")))?;
+
+        let tree = db.open_tree(name)?;
+        let mut remaining = entry.header().size()?;
+        let mut records = BufReader::new(&mut entry);
+        let (mut batch, mut batched) = (sled::Batch::default(), 0);
+
+        while remaining > 0 {
+            let key = read_part(&mut records, &mut remaining).ok_or_else(|| SnapshotError::Corrupted(format!("truncated record in {path}")))??;
+            let value = read_part(&mut records, &mut remaining).ok_or_else(|| SnapshotError::Corrupted(format!("truncated record in {path}")))??;
+            batch.insert(key, value);
+            batched += 1;
+
+            if batched == IMPORT_BATCH {
+                tree.apply_batch(std::mem::take(&mut batch))?;
+                batched = 0;
+            }
+        }
+
+        tree.apply_batch(batch)?;
+    }
+
+    db.flush()?;
+
+    Ok(manifest)
+}
+
+fn append<W: Write>(builder: &mut tar::Builder<W>, path: &str, size: u64, data: impl Read) -> std::io::Result<()> {
+    let mut header = tar::Header::new_gnu();
+    header.set_size(size);
+    header.set_mode(0o644);
+    header.set_cksum();
+
+    builder.append_data(&mut header, path, data)
+}
+
+/// Reads the length prefixed key/value pairs of a tree, one pair is held at a time
+struct TreeRecords {
+    entries: sled::Iter,
+    record: Vec<u8>,
+    offset: usize
+}
+
+impl Read for TreeRecords {
+    fn read(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        while self.offset == self.record.len() {
+            let Some(entry) = self.entries.next() else { return Ok(0) };
+            let (key, value) = entry.map_err(std::io::Error::other)?;
+
+            self.record.clear();
+            self.offset = 0;
+
+            for part in [key, value] {
+                self.record.extend_from_slice(&(part.len() as u32).to_be_bytes());
+                self.record.extend_from_slice(&part);
+            }
+        }
+
+        let len = buf.len().min(self.record.len() - self.offset);
+        buf[..len].copy_from_slice(&self.record[self.offset..self.offset + len]);
+        self.offset += len;
+
+        Ok(len)
+    }
+}
+
+/// reads a length prefixed part of the `remaining` bytes of `records`, `None` if the part exceeds them
+fn read_part(records: &mut impl Read, remaining: &mut u64) -> Option<std::io::Result<Vec<u8>>> {
+    *remaining = remaining.checked_sub(4)?;
+    let mut len = [0; 4];
+    let len = match records.read_exact(&mut len) {
+        Ok(()) => u32::from_be_bytes(len) as u64,
+        Err(e) => return Some(Err(e))
+    };
+
+    *remaining = remaining.checked_sub(len)?;
+    let mut part = vec![0; len as usize];
+
+    Some(records.read_exact(&mut part).map(|()| part))
+}
+
+#[tokio::test]
+async fn test_snapshot_roundtrip() {
+    use qubic_types::QubicTxHash;
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionWithData};
+    use crate::archiver::{tick_data, Archiver};
+
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-snapshot-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let snapshot = dir.join("snapshot.tar.zst");
+    let tx = TransactionWithData::from(RawTransaction { amount: 42, input_type: 1, ..Default::default() });
+    let hash = QubicTxHash::from(tx.clone());
+
+    let source = sled::open(dir.join("source")).unwrap();
+    let mut archiver = Archiver::new(4).with_sink(SledSink::from_db(&source).unwrap());
+    archiver.ingest(tick_data(100, 7), vec![], None).await;
+    archiver.ingest(tick_data(100, 8), vec![tx.clone()], None).await;
+    archiver.shutdown().await;
+
+    let exported = export(&source, &snapshot).unwrap();
+    assert_eq!(exported, Manifest { schema_version: SCHEMA_VERSION, first_tick: Some(7), last_tick: Some(8), cursor: Some(8), trees: vec!["ticks".into(), "transactions".into(), "meta".into(), "entities".into(), "epochs".into(), "computors".into(), "balances".into(), "rich_list".into(), "epoch_stats".into(), "epoch_addresses".into(), "finalized".into(), "identity_transactions".into()] });
+
+    let target = sled::open(dir.join("target")).unwrap();
+    assert_eq!(import(&target, &snapshot).unwrap(), exported);
+
+    let sink = SledSink::from_db(&target).unwrap();
+    let restored = sink.transaction(8, &hash).unwrap().unwrap();
+    assert_eq!((restored.tick, restored.transaction), (8, tx));
+    assert_eq!(sink.cursor().unwrap(), Some(8));
+
+    // a second import would mix two archives
+    assert!(matches!(import(&target, &snapshot), Err(SnapshotError::NotEmpty)));
+
+    drop((source, target, sink));
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+/// writes a snapshot holding only `manifest`
+#[cfg(test)]
+fn manifest_only_snapshot(path: &Path, manifest: &Manifest) {
+    let mut builder = tar::Builder::new(Vec::new());
+    let manifest = serde_json::to_vec(manifest).unwrap();
+    append(&mut builder, MANIFEST, manifest.len() as u64, manifest.as_slice()).unwrap();
+    compress(builder.into_inner().unwrap().as_slice(), File::create(path).unwrap(), CompressionLevel::Fastest);
+}
+
+#[test]
+fn test_snapshot_schema_mismatch() {
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-snapshot-schema-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let snapshot = dir.join("snapshot.tar.zst");
+
+    manifest_only_snapshot(&snapshot, &Manifest { schema_version: SCHEMA_VERSION + 1, first_tick: None, last_tick: None, cursor: None, trees: Vec::new() });
+
+    let db = sled::open(dir.join("db")).unwrap();
+    let err = import(&db, &snapshot).unwrap_err();
+
+    assert!(matches!(err, SnapshotError::SchemaMismatch { found, expected: SCHEMA_VERSION } if found == SCHEMA_VERSION + 1));
+    assert_eq!(err.to_string(), format!("Snapshot has schema version {} but this qubic-rpc expects version {SCHEMA_VERSION}", SCHEMA_VERSION + 1));
+    assert!(db.open_tree("ticks").unwrap().is_empty());
+
+    drop(db);
+    std::fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn test_snapshot_tree_mismatch() {
+    let dir = std::env::temp_dir().join(format!("qubic-rpc-snapshot-trees-{}", std::process::id()));
+    std::fs::create_dir_all(&dir).unwrap();
+    let snapshot = dir.join("snapshot.tar.zst");
+
+    // a snapshot of an archive predating the identity transactions
+    let trees: Vec<String> = SledSink::TREES.iter().filter(|name| **name != "identity_transactions").map(|name| name.to_string()).collect();
+    manifest_only_snapshot(&snapshot, &Manifest { schema_version: SCHEMA_VERSION, first_tick: None, last_tick: None, cursor: None, trees: trees.clone() });
+
+    let db = sled::open(dir.join("db")).unwrap();
+    let err = import(&db, &snapshot).unwrap_err();
+
+    assert!(matches!(&err, SnapshotError::TreeMismatch { found, expected } if *found == trees && expected.len() == SledSink::TREES.len()));
+    assert!(err.to_string().starts_with("Snapshot has the trees"));
+
+    // the order of the trees does not matter
+    manifest_only_snapshot(&snapshot, &Manifest { schema_version: SCHEMA_VERSION, first_tick: None, last_tick: None, cursor: None, trees: SledSink::TREES.iter().rev().map(|name| name.to_string()).collect() });
+    assert!(import(&db, &snapshot).is_ok());
+
+    drop(db);
+    std::fs::remove_dir_all(dir).unwrap();
+}
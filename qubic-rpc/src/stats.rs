@@ -0,0 +1,111 @@
+use std::time::Duration;
+
+use qubic_rpc_types::NetworkStats;
+
+use crate::scheduler::{Priority, UpstreamScheduler};
+
+/// `SystemInfo` samples persisted in sled, keyed by tick
+#[derive(Clone)]
+pub struct StatsStore {
+    tree: sled::Tree
+}
+
+impl StatsStore {
+    pub fn open(path: &str) -> sled::Result<Self> {
+        Self::from_db(sled::open(path)?)
+    }
+
+    fn from_db(db: sled::Db) -> sled::Result<Self> {
+        Ok(Self { tree: db.open_tree("network_stats")? })
+    }
+
+    /// stores the sample unless one for the same tick exists, returns if it was stored
+    pub fn insert(&self, stats: NetworkStats) -> sled::Result<bool> {
+        let value = serde_json::to_vec(&stats).expect("NetworkStats serializes");
+
+        Ok(self.tree.compare_and_swap(stats.tick.to_be_bytes(), None as Option<&[u8]>, Some(value))?.is_ok())
+    }
+
+    pub fn latest(&self) -> sled::Result<Option<NetworkStats>> {
+        Ok(self.tree.last()?.and_then(|(_, value)| serde_json::from_slice(&value).ok()))
+    }
+
+    /// latest sample of every `step` ticks wide bucket in `from_tick..=to_tick`, buckets start at `from_tick`
+    pub fn history(&self, from_tick: u32, to_tick: u32, step: u32) -> sled::Result<Vec<NetworkStats>> {
+        let step = step.max(1);
+        let mut history: Vec<NetworkStats> = Vec::new();
+
+        for entry in self.tree.range(from_tick.to_be_bytes()..=to_tick.to_be_bytes()) {
+            let (_, value) = entry?;
+            let Ok(stats) = serde_json::from_slice::<NetworkStats>(&value) else { continue };
+
+            match history.last_mut() {
+                Some(last) if (last.tick - from_tick) / step == (stats.tick - from_tick) / step => *last = stats,
+                _ => history.push(stats)
+            }
+        }
+
+        Ok(history)
+    }
+}
+
+/// Samples `SystemInfo` every `interval` as background work of the `scheduler`, a failing computor hands over to the
+/// next one of `computors`
+pub fn spawn_sampler(store: StatsStore, computors: Vec<String>, interval: Duration, scheduler: UpstreamScheduler) {
+    tokio::spawn(async move {
+        let mut current = 0;
+
+        loop {
+            for attempt in 0..computors.len() {
+                let computor = &computors[(current + attempt) % computors.len()];
+                scheduler.acquire(Priority::Background).await;
+
+                let res = match crate::computor_client(computor).await {
+                    Ok(client) => client.qu().request_system_info().await.map_err(|e| e.to_string()),
+                    Err(e) => Err(e.to_string())
+                };
+
+                match res {
+                    Ok(info) => {
+                        current = (current + attempt) % computors.len();
+
+                        if let Err(e) = store.insert(info.into()) {
+                            error!("Failed to persist network stats: {e}");
+                        }
+
+                        break;
+                    },
+                    Err(e) => warn!("Failed to sample network stats from {computor}: {e}")
+                }
+            }
+
+            tokio::time::sleep(interval).await;
+        }
+    });
+}
+
+#[cfg(test)]
+fn sample(tick: u32) -> NetworkStats {
+    NetworkStats { tick, epoch: 100, number_of_entities: tick * 2, number_of_transactions: tick * 3, solution_threshold: 29 }
+}
+
+#[test]
+fn test_stats_history() {
+    let store = StatsStore::from_db(sled::Config::new().temporary(true).open().unwrap()).unwrap();
+
+    for tick in [100, 104, 109, 110, 119, 120, 135] {
+        assert!(store.insert(sample(tick)).unwrap());
+    }
+
+    // samples of the same tick are not duplicated
+    assert!(!store.insert(NetworkStats { number_of_entities: 0, ..sample(110) }).unwrap());
+
+    assert_eq!(store.latest().unwrap(), Some(sample(135)));
+
+    // buckets [100, 110), [110, 120), [120, 130), [130, 140)
+    assert_eq!(store.history(100, 140, 10).unwrap(), vec![sample(109), sample(119), sample(120), sample(135)]);
+    // bounds are inclusive, buckets start at from_tick
+    assert_eq!(store.history(104, 119, 6).unwrap(), vec![sample(109), sample(110), sample(119)]);
+    assert_eq!(store.history(100, 135, 1).unwrap().len(), 7);
+    assert_eq!(store.history(136, 200, 10).unwrap(), vec![]);
+}
@@ -0,0 +1,267 @@
+//! Streams the transactions of tick transaction results and the entries of rich list pages element by element instead
+//! of buffering the whole body
+
+use std::{future::{ready, Future}, sync::{Arc, Mutex}};
+
+use axum::{body::Body, http::{header, StatusCode}, response::{IntoResponse, Response}, BoxError};
+use futures::{channel::mpsc, future, stream, Stream, StreamExt};
+use qubic_rpc_types::{v2, QubicJsonRpcResponse, RequestResults, ResponseType, RichList, RichListEntry};
+use qubic_types::QubicId;
+use qubic_web3_rs::{client::Client, errors::ClientError, qubic_tcp_types::types::transactions::TransactionFlags, transport::Tcp};
+use serde::Serialize;
+
+use crate::numbers::{self, Json};
+
+type Headers = [(&'static str, &'static str); 1];
+
+/// transactions received from the computor ahead of the body sent to the client
+const TRANSACTION_BUFFER: usize = 64;
+
+/// v1 response, `requestTickTransactions` results are streamed
+pub fn v1_response(status: StatusCode, headers: Headers, mut res: QubicJsonRpcResponse) -> Response {
+    match &mut res.response {
+        ResponseType::Result(RequestResults::RequestTickTransactions(transactions)) => {
+            let transactions = std::mem::take(transactions);
+
+            json_array_response(status, headers, &res, b"\"result\":[]", stream::iter(transactions).map(Ok))
+        },
+        _ => (status, headers, Json(res)).into_response()
+    }
+}
+
+/// v2 response, `requestTickTransactions` results are streamed
+pub fn v2_response(status: StatusCode, headers: Headers, mut res: v2::QubicJsonRpcResponse) -> Response {
+    match &mut res.response {
+        v2::ResponseType::Result(v2::RequestResults::RequestTickTransactions(report)) => {
+            let transactions = std::mem::take(&mut report.transactions);
+
+            json_array_response(status, headers, &res, b"\"transactions\":[]", stream::iter(transactions).map(Ok))
+        },
+        _ => (status, headers, Json(res)).into_response()
+    }
+}
+
+/// v1 `requestTickTransactions` result of `tick`, every transaction is written to the body as soon as `client`
+/// received it. A request failing before the first transaction is answered with `error`, a later failure aborts the
+/// body, the status was sent already
+pub async fn v1_tick_transactions(client: Client<Tcp>, id: u32, tick: u32, headers: Headers, error: impl FnOnce(ClientError) -> Response) -> Response {
+    let (sender, mut transactions) = mpsc::channel(TRANSACTION_BUFFER);
+    let request = tokio::spawn(async move { client.qu().stream_tick_transactions(tick, TransactionFlags::all(), sender).await });
+
+    let (first, pending) = match transactions.next().await {
+        Some(tx) => (Some(tx), Some(request)),
+        None => match request.await {
+            Ok(Ok(_)) => (None, None),
+            Ok(Err(e)) => return error(e),
+            Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+        }
+    };
+
+    let failure = stream::iter(pending).then(|request| request).filter_map(|res| ready(match res {
+        Ok(Ok(_)) => None,
+        Ok(Err(e)) => Some(Err(BoxError::from(e))),
+        Err(e) => Some(Err(BoxError::from(e)))
+    }));
+    let elements = stream::iter(first).chain(transactions).map(Ok).chain(failure);
+
+    let template = QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id, response: ResponseType::Result(RequestResults::RequestTickTransactions(Vec::new())), diagnostics: None };
+
+    json_array_response(StatusCode::OK, headers, &template, b"\"result\":[]", elements)
+}
+
+/// `/v1/rich-list` page of up to `limit` of `entries`, every entry is written to the body as it is read. `next` follows
+/// the entries, it is the identity of the last entry of the page if `entries` yields one past the page. A failing read
+/// aborts the body
+pub fn rich_list_response(total: u64, entries: impl Iterator<Item = sled::Result<RichListEntry>> + Send + 'static, limit: usize) -> Response {
+    const MARKER: &[u8] = b"\"entries\":[]";
+
+    let template = move |next| RichList { total, entries: Vec::new(), next };
+    let prefix = match split_template(&template(None), MARKER) {
+        Ok((prefix, _)) => prefix,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    };
+
+    let next = Arc::new(Mutex::new(None::<QubicId>));
+    let (found, mut listed, mut last) = (next.clone(), 0, None);
+    let elements = stream::iter(entries.take(limit + 1)).filter_map(move |entry| ready(match entry {
+        Ok(_) if listed == limit => {
+            *found.lock().unwrap() = last;
+            None
+        },
+        Ok(entry) => {
+            listed += 1;
+            last = Some(entry.identity);
+            Some(Ok(entry))
+        },
+        Err(e) => Some(Err(BoxError::from(e)))
+    }));
+
+    // `next` is only known once the entries were read
+    let suffix = future::lazy(move |_| split_template(&template(*next.lock().unwrap()), MARKER).map(|(_, suffix)| suffix).map_err(BoxError::from));
+
+    (StatusCode::OK, [(header::CONTENT_TYPE, "application/json")], json_array_body(prefix, elements, suffix)).into_response()
+}
+
+/// Serializes `template` with `elements` spliced into its empty array following `marker`, the body is byte
+/// identical to serializing the response with the elements in place. An element failing aborts the body
+fn json_array_response<T: Serialize>(status: StatusCode, headers: Headers, template: &impl Serialize, marker: &[u8], elements: impl Stream<Item = Result<T, BoxError>> + Send + 'static) -> Response {
+    let (prefix, suffix) = match split_template(template, marker) {
+        Ok(parts) => parts,
+        Err(e) => return (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response()
+    };
+
+    (status, headers, [(header::CONTENT_TYPE, "application/json")], json_array_body(prefix, elements, ready(Ok(suffix)))).into_response()
+}
+
+/// serialized `template` split into the part up to and including the opening bracket of the empty array following
+/// `marker` and the rest
+fn split_template(template: &impl Serialize, marker: &[u8]) -> serde_json::Result<(Vec<u8>, Vec<u8>)> {
+    let mut prefix = serde_json::to_vec(template)?;
+
+    let split = prefix.windows(marker.len()).position(|window| window == marker).expect("template contains the emptied array") + marker.len() - 1;
+    let suffix = prefix.split_off(split);
+
+    Ok((prefix, suffix))
+}
+
+/// body of `prefix`, the comma separated `elements` and the suffix, which is awaited after the last element.
+/// Nothing follows a failure
+fn json_array_body<T: Serialize>(prefix: Vec<u8>, elements: impl Stream<Item = Result<T, BoxError>> + Send + 'static, suffix: impl Future<Output = Result<Vec<u8>, BoxError>> + Send + 'static) -> Body {
+    // the elements are serialized while the body is sent, after the format of the request was reset
+    let format = numbers::current();
+    let elements = elements.enumerate().map(move |(idx, element)| {
+        let mut chunk = if idx == 0 { Vec::new() } else { vec![b','] };
+        let element = element?;
+
+        format.scope(|| serde_json::to_writer(&mut chunk, &element))?;
+        Ok::<_, BoxError>(chunk)
+    });
+
+    // nothing follows a failure, the client must not mistake the body for a complete one
+    let mut failed = false;
+    let body = stream::once(ready(Ok(prefix))).chain(elements).chain(stream::once(suffix)).take_while(move |chunk| {
+        let sent = !failed;
+        failed |= chunk.is_err();
+
+        ready(sent)
+    });
+
+    Body::from_stream(body)
+}
+
+#[tokio::test]
+async fn test_streamed_tick_transactions() {
+    use futures::StreamExt;
+    use qubic_rpc_types::{TickTransactions, Version};
+    use qubic_types::{QubicId, Signature};
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionData, TransactionWithData};
+
+    // a full tick of transactions with the largest input
+    let transactions: Vec<_> = (0..1024u64).map(|idx| TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: idx, tick: 12_000_000, input_type: 1, input_size: 1024 },
+        data: TransactionData::Unknown(vec![3; 1024]),
+        signature: Signature([4; 64])
+    }).collect();
+
+    let v2_res = |transactions: Vec<TransactionWithData>| v2::QubicJsonRpcResponse {
+        jsonrpc: "2.0".to_owned(),
+        version: Version::V2,
+        id: 1,
+        response: v2::ResponseType::Result(v2::RequestResults::RequestTickTransactions(TickTransactions { transactions, requested: 1024, received: 1024, tick_data_digest_count: Some(1024), complete: true, finalized: false })), diagnostics: None
+    };
+    let v1_res = |transactions: Vec<TransactionWithData>| QubicJsonRpcResponse {
+        jsonrpc: "2.0".to_owned(),
+        id: 0,
+        response: ResponseType::Result(RequestResults::RequestTickTransactions(transactions)), diagnostics: None
+    };
+
+    let cases = [
+        (serde_json::to_vec(&v2_res(transactions.clone())).unwrap(), v2_response(StatusCode::OK, [("x-qubic-source", "computor")], v2_res(transactions.clone()))),
+        (serde_json::to_vec(&v1_res(transactions.clone())).unwrap(), v1_response(StatusCode::OK, [("x-qubic-source", "computor")], v1_res(transactions.clone()))),
+        (serde_json::to_vec(&v1_res(Vec::new())).unwrap(), v1_response(StatusCode::OK, [("x-qubic-source", "computor")], v1_res(Vec::new())))
+    ];
+
+    for (buffered, res) in cases {
+        assert_eq!(res.status(), StatusCode::OK);
+        assert_eq!(res.headers()[header::CONTENT_TYPE], "application/json");
+        assert!(res.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let chunks: Vec<_> = res.into_body().into_data_stream().map(Result::unwrap).collect().await;
+        let largest = chunks.iter().map(|chunk| chunk.len()).max().unwrap();
+
+        // no chunk holds more than a single transaction
+        assert!(largest < 4 * 1024, "chunk of {largest} bytes");
+        assert_eq!(chunks.concat(), buffered);
+    }
+}
+
+#[tokio::test]
+async fn test_streamed_rich_list() {
+    let entries: Vec<_> = (0..200u8).map(|idx| RichListEntry { identity: QubicId([idx; 32]), balance: 1_000 - idx as u64, tick: 12_000_000 }).collect();
+    let body = |res: Response| async move { res.into_body().into_data_stream().collect::<Vec<_>>().await };
+
+    let cases = [
+        // one entry past the page
+        (rich_list_response(500, entries.clone().into_iter().map(Ok), 199), RichList { total: 500, entries: entries[..199].to_vec(), next: Some(QubicId([198; 32])) }),
+        (rich_list_response(500, entries.clone().into_iter().map(Ok), 200), RichList { total: 500, entries: entries.clone(), next: None }),
+        (rich_list_response(0, std::iter::empty(), 100), RichList { total: 0, entries: Vec::new(), next: None })
+    ];
+
+    for (res, buffered) in cases {
+        assert_eq!(res.status(), StatusCode::OK);
+        assert!(res.headers().get(header::CONTENT_LENGTH).is_none());
+
+        let chunks: Vec<_> = body(res).await.into_iter().map(Result::unwrap).collect();
+
+        // no chunk holds more than a single entry
+        assert!(chunks.iter().all(|chunk| chunk.len() < 256));
+        assert_eq!(chunks.concat(), serde_json::to_vec(&buffered).unwrap());
+    }
+
+    // a failing read aborts the body after the entries read before it
+    let failing = entries.into_iter().take(2).map(Ok).chain([Err(sled::Error::Unsupported("failed".to_owned()))]);
+    let chunks = body(rich_list_response(500, failing, 100)).await;
+
+    assert_eq!(chunks.iter().filter(|chunk| chunk.is_ok()).count(), 3);
+    assert!(chunks.last().unwrap().is_err());
+}
+
+#[tokio::test]
+async fn test_tick_transactions_from_computor() {
+    use qubic_types::{traits::ToBytes, QubicId, Signature};
+    use qubic_web3_rs::{fake_computor::{packet, FakeComputor, Reply}, qubic_tcp_types::{types::transactions::{RawTransaction, TransactionData, TransactionWithData}, MessageType}};
+
+    let transactions: Vec<_> = (0..3u64).map(|idx| TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: idx, tick: 12_000_000, input_type: 0, input_size: 0 },
+        data: TransactionData::None,
+        signature: Signature([4; 64])
+    }).collect();
+    let packets: Vec<_> = transactions.iter().map(|tx| packet(MessageType::BroadcastTransaction, &tx.to_bytes())).collect();
+
+    let complete = FakeComputor::new().stream(MessageType::RequestTickTransactions, MessageType::BroadcastTransaction, transactions.iter().map(ToBytes::to_bytes).collect()).start();
+    let interrupted = FakeComputor::new().on(MessageType::RequestTickTransactions, move |_| Reply::Close(packets[..2].to_vec())).start();
+    let closed = FakeComputor::new().on(MessageType::RequestTickTransactions, |_| Reply::Close(Vec::new())).start();
+
+    let respond = |url: String| async move {
+        let client = Client::<Tcp>::new(url).await.unwrap();
+        v1_tick_transactions(client, 7, 12_000_000, [("x-qubic-source", "computor")], |e| (StatusCode::BAD_GATEWAY, e.to_string()).into_response()).await
+    };
+
+    let res = respond(complete.url().to_owned()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let body: Vec<_> = res.into_body().into_data_stream().map(Result::unwrap).collect().await;
+    let buffered = QubicJsonRpcResponse { jsonrpc: "2.0".to_owned(), id: 7, response: ResponseType::Result(RequestResults::RequestTickTransactions(transactions)), diagnostics: None };
+    assert_eq!(body.concat(), serde_json::to_vec(&buffered).unwrap());
+
+    // the transactions received before the computor dropped the connection were sent already, the body is aborted
+    let res = respond(interrupted.url().to_owned()).await;
+    assert_eq!(res.status(), StatusCode::OK);
+
+    let chunks: Vec<_> = res.into_body().into_data_stream().collect().await;
+    assert_eq!(chunks.iter().filter(|chunk| chunk.is_ok()).count(), 3);
+    assert!(chunks.last().unwrap().is_err());
+
+    // failing before the first transaction is answered as an error
+    assert_eq!(respond(closed.url().to_owned()).await.status(), StatusCode::BAD_GATEWAY);
+}
@@ -0,0 +1,134 @@
+use std::{sync::OnceLock, time::Duration};
+
+use qubic_rpc_types::NextTick;
+use qubic_web3_rs::qubic_tcp_types::types::ticks::CurrentTickInfo;
+use tokio::sync::watch;
+
+use crate::scheduler::{Priority, UpstreamScheduler};
+
+/// Longest a long-poll is held open
+pub const MAX_WAIT: Duration = Duration::from_secs(60);
+pub const DEFAULT_WAIT: Duration = Duration::from_secs(30);
+
+/// Polls the current tick of the computor for all long-polling requests.
+/// The poller is started with the first waiter, so the computor sees one request per interval regardless of the number of waiters.
+/// Its requests are background work of the upstream scheduler
+pub struct TickWatcher {
+    computor: String,
+    interval: Duration,
+    scheduler: UpstreamScheduler,
+    ticks: OnceLock<watch::Receiver<Option<CurrentTickInfo>>>
+}
+
+impl TickWatcher {
+    pub fn new(computor: String, interval: Duration, scheduler: UpstreamScheduler) -> Self {
+        Self {
+            computor,
+            interval,
+            scheduler,
+            ticks: OnceLock::new()
+        }
+    }
+
+    fn subscribe(&self) -> watch::Receiver<Option<CurrentTickInfo>> {
+        self.ticks.get_or_init(|| {
+            let (tx, rx) = watch::channel::<Option<CurrentTickInfo>>(None);
+            let (computor, interval, scheduler) = (self.computor.clone(), self.interval, self.scheduler.clone());
+
+            tokio::spawn(async move {
+                loop {
+                    scheduler.acquire(Priority::Background).await;
+
+                    match crate::computor_client(&computor).await {
+                        Ok(client) => match client.qu().get_current_tick_info().await {
+                            Ok(info) => {
+                                tx.send_if_modified(|current| {
+                                    let changed = !matches!(current, Some(current) if current.tick == info.tick);
+                                    *current = Some(info);
+
+                                    changed
+                                });
+                            },
+                            Err(e) => warn!("Failed to poll current tick: {e}")
+                        },
+                        Err(e) => warn!("Failed to poll current tick: {e}")
+                    }
+
+                    tokio::time::sleep(interval).await;
+                }
+            });
+
+            rx
+        }).clone()
+    }
+
+    /// waits until the current tick exceeds `after`, returns the latest known tick if `timeout` elapses first
+    pub async fn wait_for_next_tick(&self, after: u32, timeout: Duration) -> Option<NextTick> {
+        let mut ticks = self.subscribe();
+
+        if let Ok(Ok(info)) = tokio::time::timeout(timeout.min(MAX_WAIT), ticks.wait_for(|info| info.is_some_and(|info| info.tick > after))).await {
+            return info.map(|tick_info| NextTick { changed: true, tick_info })
+        }
+
+        let latest = *ticks.borrow();
+        latest.map(|tick_info| NextTick { changed: false, tick_info })
+    }
+}
+
+#[cfg(test)]
+pub(crate) fn fake_computor(tick: std::sync::Arc<std::sync::atomic::AtomicU32>, requests: std::sync::Arc<std::sync::atomic::AtomicUsize>) -> String {
+    use std::{io::{Read, Write}, sync::atomic::Ordering};
+    use qubic_types::traits::ToBytes;
+    use qubic_web3_rs::qubic_tcp_types::{types::Packet, Header};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = listener.local_addr().unwrap().to_string();
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+
+            if stream.read_exact(&mut header).is_ok() {
+                requests.fetch_add(1, Ordering::Relaxed);
+
+                let info = CurrentTickInfo { tick_duration: 1, epoch: 100, tick: tick.load(Ordering::Relaxed), number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 100 };
+                let _ = stream.write_all(&Packet::new(info, false).unwrap().to_bytes());
+            }
+        }
+    });
+
+    url
+}
+
+#[tokio::test]
+async fn test_wait_for_next_tick() {
+    use std::sync::{atomic::{AtomicU32, AtomicUsize, Ordering}, Arc};
+
+    let (tick, requests) = (Arc::new(AtomicU32::new(100)), Arc::new(AtomicUsize::new(0)));
+    let scheduler = UpstreamScheduler::unlimited();
+    let watcher = Arc::new(TickWatcher::new(fake_computor(tick.clone(), requests.clone()), Duration::from_millis(50), scheduler.clone()));
+
+    let waiters = (0..20).map(|_| {
+        let watcher = watcher.clone();
+        tokio::spawn(async move { watcher.wait_for_next_tick(100, Duration::from_secs(5)).await })
+    }).collect::<Vec<_>>();
+
+    tokio::time::sleep(Duration::from_millis(300)).await;
+    tick.store(101, Ordering::Relaxed);
+
+    for waiter in waiters {
+        let next = waiter.await.unwrap().unwrap();
+
+        assert!(next.changed);
+        assert_eq!(next.tick_info.tick, 101);
+    }
+
+    // one poll per interval, independent of the 20 waiters, each taking a background turn of the scheduler
+    assert!(requests.load(Ordering::Relaxed) <= 15);
+    assert!(scheduler.metrics().background.granted >= requests.load(Ordering::Relaxed) as u64);
+
+    let unchanged = watcher.wait_for_next_tick(101, Duration::from_millis(200)).await.unwrap();
+
+    assert!(!unchanged.changed);
+    assert_eq!(unchanged.tick_info.tick, 101);
+}
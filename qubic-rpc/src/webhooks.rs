@@ -0,0 +1,740 @@
+//! Pushes archived transfers and ticks to registered webhooks
+//!
+//! Webhooks are kept in the archive database. As an archiver sink they only queue deliveries, so a slow receiver
+//! never holds back the ingestion: every webhook is delivered to by a task of its own, which retries a failed
+//! delivery with exponential backoff. After `max_failures` failed attempts in a row the webhook is dead-lettered,
+//! its queued and later events are dropped until its owner reactivates it. Every webhook queues a bounded number of
+//! deliveries, events of a full queue are dropped.
+//!
+//! Webhooks are registered and managed by the identity of a signed challenge. Their URLs have to resolve to public
+//! addresses only, at registration and again on every delivery since the records may change. Deliveries follow no
+//! redirects and ignore proxies of the environment.
+
+use std::{collections::HashMap, fmt::Display, net::{IpAddr, SocketAddr}, sync::{Arc, Mutex}, time::Duration};
+
+use axum::http::{header, StatusCode};
+use hmac::{Hmac, KeyInit, Mac};
+use qubic_rpc_types::{RegisterWebhook, Webhook, WebhookEvent, WebhookPayload, WebhookStatus};
+use qubic_types::{QubicId, QubicTxHash};
+use reqwest::{dns::{Addrs, Name, Resolve, Resolving}, redirect, Url};
+use qubic_web3_rs::qubic_tcp_types::types::ticks::TickData;
+use serde::{Deserialize, Serialize};
+use sha2::Sha256;
+use tokio::sync::mpsc;
+
+use crate::archiver::{ArchivedTransaction, ArchiverSink, SinkResult};
+
+/// request header of deliveries with the hex HMAC-SHA256 of the body keyed with the secret, prefixed with `sha256=`
+pub const SIGNATURE_HEADER: &str = "x-qubic-signature";
+
+/// Time a receiver has to answer a delivery
+const DELIVERY_TIMEOUT: Duration = Duration::from_secs(10);
+
+/// Deliveries queued per webhook, later events are dropped while its receiver catches up
+const QUEUE_CAPACITY: usize = 1024;
+
+#[derive(Debug)]
+pub enum WebhookError {
+    Invalid(String),
+    LimitReached(String),
+    NotFound(u64),
+    Forbidden(u64),
+    Db(sled::Error)
+}
+
+impl Display for WebhookError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Invalid(reason) => write!(f, "Invalid webhook: {reason}"),
+            Self::LimitReached(reason) => write!(f, "Webhook limit reached: {reason}"),
+            Self::NotFound(id) => write!(f, "No webhook {id}"),
+            Self::Forbidden(id) => write!(f, "Webhook {id} is registered by another identity"),
+            Self::Db(e) => write!(f, "Archive database failed: {e}")
+        }
+    }
+}
+
+impl From<sled::Error> for WebhookError {
+    fn from(value: sled::Error) -> Self {
+        Self::Db(value)
+    }
+}
+
+impl WebhookError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Invalid(_) => StatusCode::BAD_REQUEST,
+            Self::LimitReached(_) => StatusCode::TOO_MANY_REQUESTS,
+            Self::NotFound(_) => StatusCode::NOT_FOUND,
+            Self::Forbidden(_) => StatusCode::FORBIDDEN,
+            Self::Db(_) => StatusCode::INTERNAL_SERVER_ERROR
+        }
+    }
+}
+
+/// webhooks registered before they were owned lack `owner`, they can't be managed
+#[derive(Serialize, Deserialize)]
+struct StoredWebhook {
+    webhook: Webhook,
+    secret: String,
+    #[serde(default)]
+    owner: Option<QubicId>
+}
+
+/// Registered webhooks keyed by id
+#[derive(Clone)]
+pub struct WebhookStore {
+    db: sled::Db,
+    webhooks: sled::Tree,
+    /// ids of the webhooks subscribing transfers of an identity, built on open and kept up to date on register and
+    /// delete, so archived transactions only decode the webhooks they are delivered to
+    transfer_subscribers: Arc<Mutex<HashMap<QubicId, Vec<u64>>>>,
+    max_webhooks: usize,
+    max_per_owner: usize,
+    allow_private: bool,
+    /// serializes counting and inserting registrations, so concurrent ones don't exceed the limits
+    registering: Arc<Mutex<()>>
+}
+
+impl WebhookStore {
+    pub fn from_db(db: &sled::Db) -> sled::Result<Self> {
+        let webhooks = db.open_tree("webhooks")?;
+        let mut transfer_subscribers = HashMap::new();
+
+        for stored in webhooks.iter().values() {
+            if let Ok(stored) = serde_json::from_slice::<StoredWebhook>(&stored?) {
+                subscribe_transfers(&mut transfer_subscribers, &stored.webhook);
+            }
+        }
+
+        Ok(Self {
+            db: db.clone(),
+            webhooks,
+            transfer_subscribers: Arc::new(Mutex::new(transfer_subscribers)),
+            max_webhooks: usize::MAX,
+            max_per_owner: usize::MAX,
+            allow_private: false,
+            registering: Arc::default()
+        })
+    }
+
+    /// registers at most `max_webhooks` webhooks, `max_per_owner` of them by the same identity. Dead-lettered webhooks
+    /// count until they are deleted
+    pub fn with_limits(mut self, max_webhooks: usize, max_per_owner: usize) -> Self {
+        self.max_webhooks = max_webhooks;
+        self.max_per_owner = max_per_owner;
+        self
+    }
+
+    /// allows URLs resolving to loopback, private and other non-public addresses, e.g. of receivers next to the server
+    pub fn with_private_addresses(mut self, allow: bool) -> Self {
+        self.allow_private = allow;
+        self
+    }
+
+    pub async fn register(&self, owner: QubicId, registration: RegisterWebhook) -> Result<Webhook, WebhookError> {
+        let url = Url::parse(&registration.url).ok().filter(|url| ["http", "https"].contains(&url.scheme()) && url.host().is_some())
+            .ok_or_else(|| WebhookError::Invalid(format!("{:?} is not an HTTP URL", registration.url)))?;
+
+        if registration.events.is_empty() {
+            return Err(WebhookError::Invalid("No events subscribed".to_owned()))
+        }
+
+        if registration.events.contains(&WebhookEvent::Transfer) && registration.identities.is_empty() {
+            return Err(WebhookError::Invalid("Transfers are subscribed without identities".to_owned()))
+        }
+
+        if registration.secret.is_empty() {
+            return Err(WebhookError::Invalid("Secret is empty".to_owned()))
+        }
+
+        check_addresses(&url, self.allow_private).await.map_err(WebhookError::Invalid)?;
+
+        let _registering = self.registering.lock().unwrap();
+        let owners = self.webhooks.iter().values()
+            .map(|stored| stored.map(|stored| serde_json::from_slice::<StoredWebhook>(&stored).ok().and_then(|stored| stored.owner)))
+            .collect::<sled::Result<Vec<_>>>()?;
+
+        if owners.len() >= self.max_webhooks {
+            return Err(WebhookError::LimitReached(format!("{} webhooks are registered", owners.len())))
+        }
+
+        if owners.iter().filter(|registered| **registered == Some(owner)).count() >= self.max_per_owner {
+            return Err(WebhookError::LimitReached(format!("{owner} registered {} webhooks", self.max_per_owner)))
+        }
+
+        let webhook = Webhook {
+            id: self.db.generate_id()?,
+            url: registration.url,
+            identities: registration.identities,
+            events: registration.events,
+            status: WebhookStatus::Active,
+            delivered: 0,
+            consecutive_failures: 0,
+            last_error: None
+        };
+
+        let stored = StoredWebhook { webhook: webhook.clone(), secret: registration.secret, owner: Some(owner) };
+        self.webhooks.insert(webhook.id.to_be_bytes(), serde_json::to_vec(&stored).expect("Webhook serializes"))?;
+        subscribe_transfers(&mut self.transfer_subscribers.lock().unwrap(), &webhook);
+
+        Ok(webhook)
+    }
+
+    pub fn get(&self, id: u64) -> sled::Result<Option<Webhook>> {
+        Ok(self.stored(id)?.map(|stored| stored.webhook))
+    }
+
+    /// webhook `id` if `owner` registered it
+    pub fn owned(&self, owner: QubicId, id: u64) -> Result<Webhook, WebhookError> {
+        match self.stored(id)? {
+            Some(stored) if stored.owner == Some(owner) => Ok(stored.webhook),
+            Some(_) => Err(WebhookError::Forbidden(id)),
+            None => Err(WebhookError::NotFound(id))
+        }
+    }
+
+    /// resumes the deliveries to a dead-lettered webhook of `owner` with the next event
+    pub fn reactivate(&self, owner: QubicId, id: u64) -> Result<Webhook, WebhookError> {
+        self.owned(owner, id)?;
+
+        self.update(id, |webhook| {
+            webhook.status = WebhookStatus::Active;
+            webhook.consecutive_failures = 0;
+        })?.ok_or(WebhookError::NotFound(id))
+    }
+
+    fn delete(&self, owner: QubicId, id: u64) -> Result<Webhook, WebhookError> {
+        let webhook = self.owned(owner, id)?;
+        self.webhooks.remove(id.to_be_bytes())?;
+
+        let mut transfer_subscribers = self.transfer_subscribers.lock().unwrap();
+        for identity in &webhook.identities {
+            if let Some(ids) = transfer_subscribers.get_mut(identity) {
+                ids.retain(|subscriber| *subscriber != id);
+
+                if ids.is_empty() {
+                    transfer_subscribers.remove(identity);
+                }
+            }
+        }
+
+        Ok(webhook)
+    }
+
+    /// active webhooks subscribing transfers of any of the `identities`, in the order of their ids
+    fn transfer_subscribers(&self, identities: &[QubicId]) -> sled::Result<Vec<StoredWebhook>> {
+        let mut ids = {
+            let transfer_subscribers = self.transfer_subscribers.lock().unwrap();
+            identities.iter().filter_map(|identity| transfer_subscribers.get(identity)).flatten().copied().collect::<Vec<_>>()
+        };
+        ids.sort_unstable();
+        ids.dedup();
+
+        Ok(ids.into_iter()
+            .map(|id| self.stored(id))
+            .collect::<sled::Result<Vec<_>>>()?
+            .into_iter()
+            .flatten()
+            .filter(|stored| stored.webhook.status == WebhookStatus::Active)
+            .collect())
+    }
+
+    fn stored(&self, id: u64) -> sled::Result<Option<StoredWebhook>> {
+        Ok(self.webhooks.get(id.to_be_bytes())?.and_then(|stored| serde_json::from_slice(&stored).ok()))
+    }
+
+    /// webhooks which are not dead-lettered
+    fn active(&self) -> sled::Result<Vec<StoredWebhook>> {
+        Ok(self.webhooks.iter().values()
+            .collect::<sled::Result<Vec<_>>>()?
+            .into_iter()
+            .filter_map(|stored| serde_json::from_slice::<StoredWebhook>(&stored).ok())
+            .filter(|stored| stored.webhook.status == WebhookStatus::Active)
+            .collect())
+    }
+
+    fn update(&self, id: u64, f: impl Fn(&mut Webhook)) -> sled::Result<Option<Webhook>> {
+        let updated = self.webhooks.update_and_fetch(id.to_be_bytes(), |stored| {
+            let mut stored: StoredWebhook = serde_json::from_slice(stored?).ok()?;
+            f(&mut stored.webhook);
+
+            Some(serde_json::to_vec(&stored).expect("Webhook serializes"))
+        })?;
+
+        Ok(updated.and_then(|stored| serde_json::from_slice::<StoredWebhook>(&stored).ok()).map(|stored| stored.webhook))
+    }
+}
+
+/// adds `webhook` to the subscribers of the identities it watches if it subscribes transfers
+fn subscribe_transfers(transfer_subscribers: &mut HashMap<QubicId, Vec<u64>>, webhook: &Webhook) {
+    if webhook.events.contains(&WebhookEvent::Transfer) {
+        for identity in &webhook.identities {
+            transfer_subscribers.entry(*identity).or_default().push(webhook.id);
+        }
+    }
+}
+
+/// whether `ip` is reachable on the internet, loopback, private, link-local, shared, reserved and documentation ranges
+/// are not
+fn is_public(ip: IpAddr) -> bool {
+    match ip {
+        IpAddr::V4(ip) => {
+            let [a, b, ..] = ip.octets();
+
+            !(ip.is_private() || ip.is_loopback() || ip.is_link_local() || ip.is_broadcast() || ip.is_documentation() || ip.is_unspecified() || ip.is_multicast()
+                || a == 0 || a >= 240 || (a == 100 && b & 0xc0 == 64) || (a == 198 && b & 0xfe == 18) || ip.octets()[..3] == [192, 0, 0])
+        },
+        IpAddr::V6(ip) => match ip.to_ipv4_mapped() {
+            Some(ip) => is_public(IpAddr::V4(ip)),
+            None => {
+                let [first, second, ..] = ip.segments();
+
+                !(ip.is_loopback() || ip.is_unspecified() || ip.is_multicast() || first & 0xfe00 == 0xfc00 || first & 0xffc0 == 0xfe80 || (first == 0x2001 && second == 0xdb8))
+            }
+        }
+    }
+}
+
+/// addresses of `host`, an error if any of them is not public
+async fn public_addresses(host: &str, port: u16, allow_private: bool) -> Result<Vec<SocketAddr>, String> {
+    let addresses = tokio::net::lookup_host((host, port)).await.map_err(|e| format!("Failed to resolve {host}: {e}"))?.collect::<Vec<_>>();
+
+    if addresses.is_empty() {
+        return Err(format!("{host} resolves to no address"))
+    }
+
+    match addresses.iter().find(|address| !allow_private && !is_public(address.ip())) {
+        Some(address) => Err(format!("{host} resolves to the non-public address {}", address.ip())),
+        None => Ok(addresses)
+    }
+}
+
+/// checks the addresses the host of `url` resolves to, literal addresses are not resolved by the HTTP client
+async fn check_addresses(url: &Url, allow_private: bool) -> Result<(), String> {
+    let host = url.host_str().ok_or_else(|| format!("{url} has no host"))?;
+    let host = host.strip_prefix('[').and_then(|host| host.strip_suffix(']')).unwrap_or(host);
+
+    public_addresses(host, url.port_or_known_default().unwrap_or(80), allow_private).await.map(|_| ())
+}
+
+/// Resolver of the delivering HTTP client, names resolving to a non-public address fail to connect
+struct PublicResolver;
+
+impl Resolve for PublicResolver {
+    fn resolve(&self, name: Name) -> Resolving {
+        Box::pin(async move {
+            let addresses = public_addresses(name.as_str(), 0, false).await?;
+
+            Ok(Box::new(addresses.into_iter()) as Addrs)
+        })
+    }
+}
+
+/// hex HMAC-SHA256 of `body` keyed with `secret`
+pub fn signature(secret: &str, body: &[u8]) -> String {
+    let mut mac = Hmac::<Sha256>::new_from_slice(secret.as_bytes()).expect("HMAC takes keys of any size");
+    mac.update(body);
+
+    hex::encode(mac.finalize().into_bytes())
+}
+
+/// Archiver sink queueing the events of the registered webhooks, see the module documentation
+#[derive(Clone)]
+pub struct Webhooks {
+    store: WebhookStore,
+    http: reqwest::Client,
+    queues: Arc<Mutex<HashMap<u64, mpsc::Sender<Vec<u8>>>>>,
+    /// archived tick whose transactions may still follow, it is finalized once the next tick arrives
+    pending_tick: Arc<Mutex<Option<(u32, u16)>>>,
+    max_failures: u32,
+    backoff: Duration
+}
+
+impl Webhooks {
+    /// dead-letters a webhook after `max_failures` failed attempts in a row, the first retry waits `backoff`
+    pub fn new(store: WebhookStore, max_failures: u32, backoff: Duration) -> Self {
+        let http = reqwest::Client::builder().redirect(redirect::Policy::none()).no_proxy();
+        let http = if store.allow_private { http } else { http.dns_resolver(PublicResolver) };
+
+        Self {
+            store,
+            http: http.build().expect("Webhook HTTP client builds"),
+            queues: Arc::default(),
+            pending_tick: Arc::default(),
+            max_failures: max_failures.max(1),
+            backoff
+        }
+    }
+
+    pub fn store(&self) -> &WebhookStore {
+        &self.store
+    }
+
+    /// removes the webhook of `owner` and drops its queued deliveries
+    pub fn delete(&self, owner: QubicId, id: u64) -> Result<Webhook, WebhookError> {
+        let webhook = self.store.delete(owner, id)?;
+        self.queues.lock().unwrap().remove(&id);
+
+        Ok(webhook)
+    }
+
+    /// queues the payload for the worker of the webhook, which is started with its first delivery
+    fn queue(&self, stored: &StoredWebhook, payload: &WebhookPayload) {
+        let body = serde_json::to_vec(payload).expect("Webhook payload serializes");
+        let mut queues = self.queues.lock().unwrap();
+
+        let queue = queues.entry(stored.webhook.id).or_insert_with(|| {
+            let (tx, rx) = mpsc::channel(QUEUE_CAPACITY);
+            tokio::spawn(self.clone().deliver(stored.webhook.id, stored.webhook.url.clone(), stored.secret.clone(), rx));
+
+            tx
+        });
+
+        if let Err(mpsc::error::TrySendError::Full(_)) = queue.try_send(body) {
+            warn!("Dropping an event of webhook {}, {QUEUE_CAPACITY} deliveries are queued", stored.webhook.id);
+        }
+    }
+
+    async fn deliver(self, id: u64, url: String, secret: String, mut queue: mpsc::Receiver<Vec<u8>>) {
+        while let Some(body) = queue.recv().await {
+            for attempt in 0.. {
+                if !matches!(self.store.get(id), Ok(Some(webhook)) if webhook.status == WebhookStatus::Active) {
+                    break;
+                }
+
+                let res = match self.post(&url, &secret, body.clone()).await {
+                    Ok(()) => self.store.update(id, |webhook| {
+                        webhook.delivered += 1;
+                        webhook.consecutive_failures = 0;
+                    }),
+                    Err(e) => {
+                        warn!("Delivery to webhook {id} failed: {e}");
+
+                        self.store.update(id, |webhook| {
+                            webhook.consecutive_failures += 1;
+                            webhook.last_error = Some(e.clone());
+
+                            if webhook.consecutive_failures >= self.max_failures {
+                                webhook.status = WebhookStatus::DeadLetter;
+                            }
+                        })
+                    }
+                };
+
+                match res {
+                    Ok(Some(webhook)) if webhook.consecutive_failures == 0 => break,
+                    Ok(Some(webhook)) if webhook.status == WebhookStatus::DeadLetter => {
+                        warn!("Webhook {id} is dead-lettered after {} failed deliveries", webhook.consecutive_failures);
+                        break;
+                    },
+                    Ok(_) => tokio::time::sleep(self.backoff * 2u32.saturating_pow(attempt)).await,
+                    Err(e) => {
+                        error!("Failed to update webhook {id}: {e}");
+                        break;
+                    }
+                }
+            }
+        }
+    }
+
+    async fn post(&self, url: &str, secret: &str, body: Vec<u8>) -> Result<(), String> {
+        let parsed = Url::parse(url).map_err(|e| e.to_string())?;
+
+        if parsed.host_str().is_some_and(|host| host.trim_matches(['[', ']']).parse::<IpAddr>().is_ok()) {
+            check_addresses(&parsed, self.store.allow_private).await?;
+        }
+
+        let res = self.http.post(parsed)
+            .header(header::CONTENT_TYPE, "application/json")
+            .header(SIGNATURE_HEADER, format!("sha256={}", signature(secret, &body)))
+            .body(body)
+            .timeout(DELIVERY_TIMEOUT)
+            .send().await
+            .map_err(|e| e.to_string())?;
+
+        match res.status().is_success() {
+            true => Ok(()),
+            false => Err(format!("Receiver answered {}", res.status()))
+        }
+    }
+}
+
+impl ArchiverSink for Webhooks {
+    fn name(&self) -> &str {
+        "webhooks"
+    }
+
+    /// the transactions of the previous tick are complete once the next one arrives
+    async fn on_tick(&self, tick_data: &TickData) -> SinkResult {
+        let Some((tick, epoch)) = self.pending_tick.lock().unwrap().replace((tick_data.tick, tick_data.epoch)) else { return Ok(()) };
+
+        for stored in self.store.active()?.iter().filter(|stored| stored.webhook.events.contains(&WebhookEvent::TickFinalized)) {
+            self.queue(stored, &WebhookPayload::TickFinalized { webhook_id: stored.webhook.id, tick, epoch });
+        }
+
+        Ok(())
+    }
+
+    async fn on_transaction(&self, tx: &ArchivedTransaction) -> SinkResult {
+        let raw = &tx.transaction.raw_transaction;
+
+        if raw.amount == 0 || tx.malformed {
+            return Ok(())
+        }
+
+        let subscribed = self.store.transfer_subscribers(&[raw.from, raw.to])?;
+
+        if subscribed.is_empty() {
+            return Ok(())
+        }
+
+        let hash = QubicTxHash::from(&tx.transaction);
+
+        for stored in subscribed {
+            self.queue(&stored, &WebhookPayload::Transfer {
+                webhook_id: stored.webhook.id,
+                tick: tx.tick,
+                hash,
+                from: raw.from,
+                to: raw.to,
+                amount: raw.amount,
+                money_flew: tx.money_flew
+            });
+        }
+
+        Ok(())
+    }
+
+    async fn on_epoch_change(&self, _epoch: u16) -> SinkResult {
+        Ok(())
+    }
+}
+
+/// local receiver answering with the statuses in turn (200 once they are used up) and recording the requests
+#[cfg(test)]
+async fn receiver(statuses: Vec<u16>) -> (String, Arc<Mutex<Vec<(Option<String>, Vec<u8>)>>>) {
+    use axum::{body::Bytes, http::HeaderMap, routing::post, Router};
+
+    let requests = Arc::new(Mutex::new(Vec::new()));
+    let recorded = requests.clone();
+
+    let app = Router::new().route("/hook", post(move |headers: HeaderMap, body: Bytes| {
+        let requests = recorded.clone();
+        let statuses = statuses.clone();
+
+        async move {
+            let mut requests = requests.lock().unwrap();
+            let signature = headers.get(SIGNATURE_HEADER).and_then(|value| value.to_str().ok()).map(str::to_owned);
+            let status = statuses.get(requests.len()).copied().unwrap_or(200);
+            requests.push((signature, body.to_vec()));
+
+            StatusCode::from_u16(status).unwrap()
+        }
+    }));
+
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    tokio::spawn(async move { axum::serve(listener, app).await });
+
+    (url, requests)
+}
+
+#[cfg(test)]
+fn temp_store(name: &str) -> (WebhookStore, std::path::PathBuf) {
+    let path = std::env::temp_dir().join(format!("qubic-rpc-webhooks-{name}-{}.sled", std::process::id()));
+    let _ = std::fs::remove_dir_all(&path);
+
+    (WebhookStore::from_db(&sled::open(&path).unwrap()).unwrap().with_private_addresses(true), path)
+}
+
+#[cfg(test)]
+async fn wait_for(mut done: impl FnMut() -> bool) {
+    for _ in 0..200 {
+        if done() {
+            return
+        }
+
+        tokio::time::sleep(Duration::from_millis(10)).await;
+    }
+
+    panic!("condition not met within 2s");
+}
+
+#[tokio::test]
+async fn test_webhook_delivery() {
+    use crate::archiver::{tick_data, Archiver};
+    use qubic_web3_rs::qubic_tcp_types::types::transactions::{RawTransaction, TransactionWithData};
+
+    let (url, requests) = receiver(vec![]).await;
+    let (store, path) = temp_store("delivery");
+    let watched = QubicId([1; 32]);
+
+    let webhook = store.register(QubicId([9; 32]), RegisterWebhook { url, identities: vec![watched], events: vec![WebhookEvent::Transfer, WebhookEvent::TickFinalized], secret: "s3cret".to_owned() }).await.unwrap();
+
+    let tx = |from: u8, to: u8, amount| TransactionWithData::from(RawTransaction { from: QubicId([from; 32]), to: QubicId([to; 32]), amount, ..Default::default() });
+    let incoming = tx(2, 1, 42);
+
+    let mut archiver = Archiver::new(4).with_sink(Webhooks::new(store.clone(), 3, Duration::from_millis(10)));
+    // unrelated and zero amount transactions are not delivered
+    archiver.ingest(tick_data(100, 1), vec![tx(2, 3, 5), incoming.clone(), tx(1, 2, 0)], Some(&[])).await;
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+    archiver.shutdown().await;
+
+    wait_for(|| requests.lock().unwrap().len() == 2).await;
+
+    let requests = requests.lock().unwrap().clone();
+    let payloads = requests.iter().map(|(_, body)| serde_json::from_slice::<serde_json::Value>(body).unwrap()).collect::<Vec<_>>();
+
+    assert_eq!(payloads, [
+        serde_json::json!({ "event": "transfer", "webhookId": webhook.id, "tick": 1, "hash": QubicTxHash::from(&incoming), "from": QubicId([2; 32]), "to": watched, "amount": 42, "moneyFlew": false }),
+        serde_json::json!({ "event": "tickFinalized", "webhookId": webhook.id, "tick": 1, "epoch": 100 })
+    ]);
+
+    for (header, body) in &requests {
+        assert_eq!(header.as_deref(), Some(format!("sha256={}", signature("s3cret", body)).as_str()));
+    }
+
+    // RFC 4231 test case 2
+    assert_eq!(signature("Jefe", b"what do ya want for nothing?"), "5bdcc146bf60754e6a042426089575c75a003f089d2739839dec58b964ec3843");
+
+    let webhook = store.get(webhook.id).unwrap().unwrap();
+    assert_eq!((webhook.delivered, webhook.consecutive_failures, webhook.status), (2, 0, WebhookStatus::Active));
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_webhook_retries() {
+    use crate::archiver::{tick_data, Archiver};
+
+    let (store, path) = temp_store("retries");
+    let owner = QubicId([9; 32]);
+    let webhooks = Webhooks::new(store.clone(), 3, Duration::from_millis(10));
+
+    // recovers after two failed attempts
+    let (flaky_url, flaky) = receiver(vec![500, 500]).await;
+    let recovering = store.register(owner, RegisterWebhook { url: flaky_url, identities: vec![], events: vec![WebhookEvent::TickFinalized], secret: "a".to_owned() }).await.unwrap();
+
+    // fails every attempt and is dead-lettered after the third
+    let (failing_url, failing) = receiver(vec![500; 10]).await;
+    let dead = store.register(owner, RegisterWebhook { url: failing_url, identities: vec![], events: vec![WebhookEvent::TickFinalized], secret: "b".to_owned() }).await.unwrap();
+
+    let mut archiver = Archiver::new(4).with_sink(webhooks);
+    archiver.ingest(tick_data(100, 1), vec![], None).await;
+    archiver.ingest(tick_data(100, 2), vec![], None).await;
+
+    wait_for(|| store.get(dead.id).unwrap().unwrap().status == WebhookStatus::DeadLetter && flaky.lock().unwrap().len() == 3).await;
+
+    let webhook = store.get(recovering.id).unwrap().unwrap();
+    assert_eq!((webhook.delivered, webhook.consecutive_failures, webhook.status), (1, 0, WebhookStatus::Active));
+    assert_eq!(webhook.last_error.as_deref(), Some("Receiver answered 500 Internal Server Error"));
+
+    let webhook = store.get(dead.id).unwrap().unwrap();
+    assert_eq!((webhook.delivered, webhook.consecutive_failures), (0, 3));
+
+    // later events are neither queued for the dead-lettered webhook nor block the others
+    archiver.ingest(tick_data(100, 3), vec![], None).await;
+
+    wait_for(|| flaky.lock().unwrap().len() == 4).await;
+    assert_eq!(failing.lock().unwrap().len(), 3);
+
+    // a reactivated webhook receives the next event and is dead-lettered again once it failed as often
+    let webhook = store.reactivate(owner, dead.id).unwrap();
+    assert_eq!((webhook.consecutive_failures, webhook.status), (0, WebhookStatus::Active));
+
+    archiver.ingest(tick_data(100, 4), vec![], None).await;
+    archiver.shutdown().await;
+
+    wait_for(|| store.get(dead.id).unwrap().unwrap().status == WebhookStatus::DeadLetter).await;
+    assert_eq!(failing.lock().unwrap().len(), 6);
+
+    drop(store);
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_register_webhook() {
+    let (store, path) = temp_store("register");
+    let (owner, other) = (QubicId([1; 32]), QubicId([2; 32]));
+    let registration = RegisterWebhook { url: "https://1.1.1.1/hook".to_owned(), identities: vec![], events: vec![WebhookEvent::TickFinalized], secret: "s".to_owned() };
+
+    let webhook = store.register(owner, registration.clone()).await.unwrap();
+    assert_eq!(store.get(webhook.id).unwrap(), Some(webhook.clone()));
+    assert_ne!(store.register(owner, registration.clone()).await.unwrap().id, webhook.id);
+
+    for invalid in [
+        RegisterWebhook { url: "ftp://example.org".to_owned(), ..registration.clone() },
+        RegisterWebhook { url: "http://".to_owned(), ..registration.clone() },
+        RegisterWebhook { events: vec![], ..registration.clone() },
+        RegisterWebhook { events: vec![WebhookEvent::Transfer], ..registration.clone() },
+        RegisterWebhook { secret: String::new(), ..registration.clone() }
+    ] {
+        assert!(matches!(store.register(owner, invalid).await, Err(WebhookError::Invalid(_))));
+    }
+
+    // only the owner manages a webhook
+    assert!(matches!(store.owned(other, webhook.id), Err(WebhookError::Forbidden(_))));
+    assert!(matches!(store.reactivate(other, webhook.id), Err(WebhookError::Forbidden(_))));
+    assert!(matches!(store.delete(other, webhook.id), Err(WebhookError::Forbidden(_))));
+    assert_eq!(store.delete(owner, webhook.id).unwrap(), webhook);
+    assert!(matches!(store.owned(owner, webhook.id), Err(WebhookError::NotFound(_))));
+
+    // non-public receivers are rejected unless allowed
+    let public_only = store.clone().with_private_addresses(false);
+    for url in ["http://127.0.0.1:8080/hook", "http://10.1.2.3/hook", "http://169.254.169.254/latest", "http://[::1]/hook", "http://[::ffff:192.168.0.1]/hook", "http://100.64.0.1/hook"] {
+        let err = public_only.register(owner, RegisterWebhook { url: url.to_owned(), ..registration.clone() }).await.unwrap_err();
+        assert!(err.to_string().contains("non-public address"), "{url}: {err}");
+    }
+    assert!(public_only.register(owner, RegisterWebhook { url: "http://[2606:4700:4700::1111]/hook".to_owned(), ..registration.clone() }).await.is_ok());
+
+    // deliveries check the addresses again, names through the resolver of the client
+    let (url, requests) = receiver(vec![]).await;
+    let webhooks = Webhooks::new(public_only.clone(), 1, Duration::ZERO);
+    assert_eq!(webhooks.post(&url, "s", vec![]).await.unwrap_err(), "127.0.0.1 resolves to the non-public address 127.0.0.1");
+    assert!(webhooks.post(&url.replace("127.0.0.1", "localhost"), "s", vec![]).await.is_err());
+    assert!(requests.lock().unwrap().is_empty());
+
+    // dead-lettered webhooks count until deleted
+    let limited = store.clone().with_limits(3, 2);
+    assert!(matches!(limited.register(owner, registration.clone()).await, Err(WebhookError::LimitReached(_))));
+    let _ = limited.register(other, registration.clone()).await.unwrap();
+    assert!(matches!(limited.register(QubicId([3; 32]), registration.clone()).await, Err(WebhookError::LimitReached(_))));
+
+    drop((store, public_only, limited));
+    let _ = std::fs::remove_dir_all(path);
+}
+
+#[tokio::test]
+async fn test_transfer_subscribers() {
+    let (store, path) = temp_store("subscribers");
+    let owner = QubicId([9; 32]);
+    let (alice, bob) = (QubicId([1; 32]), QubicId([2; 32]));
+    let registration = |identities: Vec<QubicId>, events: Vec<WebhookEvent>| RegisterWebhook { url: "http://127.0.0.1/hook".to_owned(), identities, events, secret: "s".to_owned() };
+    let ids = |store: &WebhookStore, identities: &[QubicId]| store.transfer_subscribers(identities).unwrap().into_iter().map(|stored| stored.webhook.id).collect::<Vec<_>>();
+
+    let both = store.register(owner, registration(vec![alice, bob], vec![WebhookEvent::Transfer])).await.unwrap();
+    let only_bob = store.register(owner, registration(vec![bob], vec![WebhookEvent::Transfer, WebhookEvent::TickFinalized])).await.unwrap();
+    // identities without the transfer event are not indexed
+    store.register(owner, registration(vec![alice], vec![WebhookEvent::TickFinalized])).await.unwrap();
+
+    // a transfer between two watched identities is delivered once per webhook
+    assert_eq!(ids(&store, &[alice, bob]), [both.id, only_bob.id]);
+    assert_eq!(ids(&store, &[alice]), [both.id]);
+
+    // dead-lettered webhooks stay indexed but are skipped
+    store.update(only_bob.id, |webhook| webhook.status = WebhookStatus::DeadLetter).unwrap();
+    assert_eq!(ids(&store, &[bob]), [both.id]);
+
+    store.delete(owner, both.id).unwrap();
+    assert!(ids(&store, &[alice, bob]).is_empty());
+
+    // the index is rebuilt from the database
+    store.reactivate(owner, only_bob.id).unwrap();
+    let reopened = WebhookStore::from_db(&store.db).unwrap();
+    assert_eq!(ids(&reopened, &[alice, bob]), [only_bob.id]);
+
+    drop((store, reopened));
+    let _ = std::fs::remove_dir_all(path);
+}
@@ -0,0 +1,164 @@
+use std::{collections::HashMap, fmt::Display, future::Future, net::IpAddr, sync::Mutex, time::{Duration, Instant}};
+
+use axum::http::StatusCode;
+use qubic_rpc_types::{SubmitWork, SubmittedWork};
+use qubic_types::{MiningSeed, QubicWallet};
+use qubic_web3_rs::{client::Client, errors::ClientError, transport::Tcp};
+
+/// Relays mining solutions of miners which can't reach a computor, the solutions are signed by the relay wallet.
+/// The identity of a solution is not signed by the miner, so submissions are limited per client address instead.
+/// Solutions of clients without a known address are not relayed
+pub struct WorkRelay {
+    wallet: QubicWallet,
+    interval: Duration,
+    last_submitted: Mutex<HashMap<IpAddr, Instant>>
+}
+
+#[derive(Debug)]
+pub enum WorkError {
+    Malformed(String),
+    StaleSeed { current: MiningSeed },
+    UnknownClient,
+    RateLimited { retry_after: Duration },
+    Computor(ClientError)
+}
+
+impl Display for WorkError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Self::Malformed(e) => f.write_str(e),
+            Self::StaleSeed { current } => write!(f, "Solution is for a stale random seed, the current seed is {current}"),
+            Self::UnknownClient => f.write_str("Solutions are only relayed for clients with a known address"),
+            Self::RateLimited { retry_after } => write!(f, "Too many solutions from this client, retry in {}ms", retry_after.as_millis()),
+            Self::Computor(e) => write!(f, "Failed to relay solution: {e}")
+        }
+    }
+}
+
+impl From<ClientError> for WorkError {
+    fn from(value: ClientError) -> Self {
+        Self::Computor(value)
+    }
+}
+
+impl WorkError {
+    pub fn status(&self) -> StatusCode {
+        match self {
+            Self::Malformed(_) => StatusCode::BAD_REQUEST,
+            Self::StaleSeed { .. } => StatusCode::CONFLICT,
+            Self::UnknownClient => StatusCode::FORBIDDEN,
+            Self::RateLimited { .. } => StatusCode::TOO_MANY_REQUESTS,
+            Self::Computor(ClientError::Timeout) => StatusCode::GATEWAY_TIMEOUT,
+            Self::Computor(_) => StatusCode::BAD_GATEWAY
+        }
+    }
+}
+
+impl WorkRelay {
+    /// every client relays at most one solution per `interval`
+    pub fn new(wallet: QubicWallet, interval: Duration) -> Self {
+        Self { wallet, interval, last_submitted: Mutex::new(HashMap::new()) }
+    }
+
+    /// relays the solution of the `client` to the computor `connect` resolves to if it is for the current random seed,
+    /// returns the tick it was submitted at. Malformed solutions and clients without an address are rejected before
+    /// connecting. Only valid solutions count towards the limit of the client
+    pub async fn submit(&self, connect: impl Future<Output = Result<Client<Tcp>, ClientError>>, work: &SubmitWork, client: Option<IpAddr>) -> Result<SubmittedWork, WorkError> {
+        let solution = work.solution().map_err(WorkError::Malformed)?;
+        let client = client.ok_or(WorkError::UnknownClient)?;
+
+        let computor = connect.await?;
+        let system_info = computor.qu().request_system_info().await?;
+
+        if solution.random_seed.0 != system_info.random_mining_seed {
+            return Err(WorkError::StaleSeed { current: MiningSeed(system_info.random_mining_seed) })
+        }
+
+        self.claim(client)?;
+        let tick = system_info.tick;
+        computor.qu().submit_work(&self.wallet, solution).await?;
+        info!("Relayed solution of {} at tick {tick}", work.identity);
+
+        Ok(SubmittedWork { identity: work.identity, tick })
+    }
+
+    /// counts a submission of `client` unless its last one is less than the interval ago
+    fn claim(&self, client: IpAddr) -> Result<(), WorkError> {
+        let now = Instant::now();
+        let mut last_submitted = self.last_submitted.lock().unwrap();
+
+        if let Some(elapsed) = last_submitted.get(&client).map(|last| now.duration_since(*last)).filter(|elapsed| *elapsed < self.interval) {
+            return Err(WorkError::RateLimited { retry_after: self.interval - elapsed })
+        }
+
+        last_submitted.retain(|_, last| now.duration_since(*last) < self.interval);
+        last_submitted.insert(client, now);
+
+        Ok(())
+    }
+}
+
+/// computor answering every request with system info of `random_seed`
+#[cfg(test)]
+fn fake_computor(random_seed: [u8; 32]) -> String {
+    use std::io::{Read, Write};
+    use qubic_types::traits::{FromBytes, ToBytes};
+    use qubic_web3_rs::qubic_tcp_types::{types::{Packet, SystemInfo}, Header};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = listener.local_addr().unwrap().to_string();
+
+    std::thread::spawn(move || {
+        for mut stream in listener.incoming().flatten() {
+            let mut header = [0u8; std::mem::size_of::<Header>()];
+
+            if stream.read_exact(&mut header).is_ok() {
+                let mut system_info = SystemInfo::from_bytes(&[0; std::mem::size_of::<SystemInfo>()]).unwrap();
+                system_info.tick = 12_000_000;
+                system_info.random_mining_seed = random_seed;
+
+                let _ = stream.write_all(&Packet::new(system_info, false).unwrap().to_bytes());
+            }
+        }
+    });
+
+    url
+}
+
+#[tokio::test]
+async fn test_work_relay() {
+    use qubic_types::QubicId;
+
+    let seed = MiningSeed([7; 32]);
+    let computor = fake_computor(seed.0);
+    let relay = WorkRelay::new(QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap(), Duration::from_secs(60));
+    let work = |identity: u8, random_seed: String, nonce: &str| SubmitWork { identity: QubicId([identity; 32]), random_seed, nonce: nonce.to_owned() };
+    let nonce = format!("0x{}", "01".repeat(32));
+    let (first, second, third, fourth) = (Some(IpAddr::from([10, 0, 0, 1])), Some(IpAddr::from([10, 0, 0, 2])), Some(IpAddr::from([10, 0, 0, 3])), Some(IpAddr::from([10, 0, 0, 4])));
+
+    let submitted = relay.submit(crate::computor_client(&computor), &work(1, seed.get_identity(), &nonce), first).await.unwrap();
+    assert_eq!(submitted, SubmittedWork { identity: QubicId([1; 32]), tick: 12_000_000 });
+
+    // a second solution of the client within the interval, whatever identity it claims
+    assert!(matches!(relay.submit(crate::computor_client(&computor), &work(2, seed.get_identity(), &nonce), first).await, Err(WorkError::RateLimited { .. })));
+
+    let err = relay.submit(crate::computor_client(&computor), &work(2, "08".repeat(32), &nonce), second).await.unwrap_err();
+    assert_eq!(err.status(), StatusCode::CONFLICT);
+    assert_eq!(err.to_string(), format!("Solution is for a stale random seed, the current seed is {seed}"));
+
+    let err = relay.submit(crate::computor_client(&computor), &work(3, seed.get_identity(), "0x0102"), third).await.unwrap_err();
+    assert_eq!(err.status(), StatusCode::BAD_REQUEST);
+    assert!(err.to_string().starts_with("Invalid nonce"));
+
+    // stale and malformed solutions are rejected before they count towards the limit
+    assert!(relay.submit(crate::computor_client(&computor), &work(2, seed.get_identity(), &nonce), second).await.is_ok());
+    assert!(relay.submit(crate::computor_client(&computor), &work(3, seed.get_identity(), &nonce), third).await.is_ok());
+
+    // clients without an address would share one limit
+    let err = relay.submit(crate::computor_client(&computor), &work(4, seed.get_identity(), &nonce), None).await.unwrap_err();
+    assert_eq!(err.status(), StatusCode::FORBIDDEN);
+
+    // an unreachable computor is a bad gateway
+    let err = relay.submit(crate::computor_client("127.0.0.1:1"), &work(4, seed.get_identity(), &nonce), fourth).await.unwrap_err();
+    assert_eq!(err.status(), StatusCode::BAD_GATEWAY);
+}
@@ -3,8 +3,7 @@ use qubic_tcp_types::types::Entity;
 use qubic_types::QubicId;
 use anyhow::Result;
 
-pub const SPECTRUM_DEPTH: usize = 24;
-pub const SPECTRUM_CAPACITY: usize = 0x1000000;
+pub use qubic_tcp_types::consts::{SPECTRUM_CAPACITY, SPECTRUM_DEPTH};
 
 
 pub struct SpectrumFile {
@@ -0,0 +1,130 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, hint::black_box, sync::atomic::{AtomicUsize, Ordering}};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use qubic_tcp_types::types::{send_to_many::SendToManyInput, transactions::{RawTransaction, TransactionData, TransactionWithData}, Packet};
+use qubic_types::{traits::ToBytes, QubicId, QubicTxHash, Signature};
+
+/// counts the allocations to compare them per packet
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const PACKETS: usize = 1_000;
+
+fn allocations_per_packet(mut f: impl FnMut()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    for _ in 0..PACKETS {
+        f();
+    }
+
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / PACKETS as f64
+}
+
+fn packet() -> Packet<TransactionWithData> {
+    Packet::new(TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, tick: 12_000_000, input_type: 0, input_size: 64 },
+        data: TransactionData::Unknown(vec![3; 64]),
+        signature: Signature([4; 64])
+    }, false).unwrap()
+}
+
+/// encoding before `write_to`, every part is encoded into a buffer of its own and appended
+fn concatenated(packet: &Packet<TransactionWithData>) -> Vec<u8> {
+    let mut tx = packet.data.raw_transaction.to_bytes();
+    tx.extend(packet.data.data.to_bytes());
+    tx.extend(packet.data.signature.to_bytes());
+
+    let mut buffer = packet.header.to_bytes();
+    buffer.extend(tx);
+
+    buffer
+}
+
+fn bench_encoding(c: &mut Criterion) {
+    let packet = packet();
+    assert_eq!(concatenated(&packet), packet.to_bytes());
+
+    // a broadcasting loop reuses its buffer
+    let mut buffer = Vec::with_capacity(packet.encoded_len());
+    let mut reused = |packet: &Packet<TransactionWithData>| {
+        buffer.clear();
+        packet.write_to(&mut buffer);
+        black_box(&buffer);
+    };
+
+    println!(
+        "allocations per packet: concatenated {}, to_bytes {}, write_to {}",
+        allocations_per_packet(|| { black_box(concatenated(black_box(&packet))); }),
+        allocations_per_packet(|| { black_box(black_box(&packet).to_bytes()); }),
+        allocations_per_packet(|| reused(black_box(&packet)))
+    );
+
+    c.bench_function("packet concatenated", |b| b.iter(|| concatenated(black_box(&packet))));
+    c.bench_function("packet to_bytes", |b| b.iter(|| black_box(&packet).to_bytes()));
+    c.bench_function("packet write_to", |b| b.iter(|| reused(black_box(&packet))));
+}
+
+/// SendToMany transactions and transactions with large payloads in turns
+fn transactions() -> Vec<TransactionWithData> {
+    (0..PACKETS).map(|i| {
+        let data = match i % 2 {
+            0 => TransactionData::SendToMany(SendToManyInput { ids: [QubicId([5; 32]); 25], amounts: [10; 25] }),
+            _ => TransactionData::Unknown(vec![6; 1_024])
+        };
+
+        TransactionWithData { raw_transaction: RawTransaction { tick: i as u32, ..Default::default() }, data, signature: Signature([4; 64]) }
+    }).collect()
+}
+
+/// send path before `Packet::from_ref`, the transaction is cloned for the hash and for the packet
+fn rebroadcast_owned(tx: &TransactionWithData) -> (QubicTxHash, Vec<u8>) {
+    let hash = QubicTxHash::from(tx.clone());
+
+    (hash, Packet::new(tx.clone(), false).unwrap().to_bytes())
+}
+
+fn rebroadcast_borrowed(tx: &TransactionWithData) -> (QubicTxHash, Vec<u8>) {
+    (QubicTxHash::from(tx), Packet::from_ref(tx, false).unwrap().to_bytes())
+}
+
+fn bench_rebroadcast(c: &mut Criterion) {
+    let transactions = transactions();
+    assert!(transactions.iter().all(|tx| rebroadcast_owned(tx).1[8..] == rebroadcast_borrowed(tx).1[8..]));
+
+    let allocations = |f: fn(&TransactionWithData) -> (QubicTxHash, Vec<u8>)| {
+        let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+        for tx in &transactions {
+            black_box(f(black_box(tx)));
+        }
+
+        ALLOCATIONS.load(Ordering::Relaxed) - before
+    };
+
+    println!(
+        "allocations rebroadcasting {PACKETS} transactions: owned {}, borrowed {}",
+        allocations(rebroadcast_owned),
+        allocations(rebroadcast_borrowed)
+    );
+
+    c.bench_function("rebroadcast owned", |b| b.iter(|| transactions.iter().map(rebroadcast_owned).for_each(|res| { black_box(res); })));
+    c.bench_function("rebroadcast borrowed", |b| b.iter(|| transactions.iter().map(rebroadcast_borrowed).for_each(|res| { black_box(res); })));
+}
+
+criterion_group!(benches, bench_encoding, bench_rebroadcast);
+criterion_main!(benches);
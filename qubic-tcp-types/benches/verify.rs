@@ -0,0 +1,33 @@
+use std::hint::black_box;
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use qubic_tcp_types::types::transactions::{verify_batch, RawTransaction, TransactionWithData};
+use qubic_types::{traits::{Sign, VerifySignature}, QubicId, QubicWallet};
+
+const TRANSACTIONS: usize = 1_024;
+
+/// signed transfers, as many as a busy tick carries
+fn transactions() -> Vec<TransactionWithData> {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+
+    (0..TRANSACTIONS).map(|i| {
+        let mut tx = TransactionWithData::from(RawTransaction { from: wallet.public_key, to: QubicId([2; 32]), amount: i as u64 + 1, tick: 12_000_000, ..Default::default() });
+        tx.sign(&wallet).unwrap();
+
+        tx
+    }).collect()
+}
+
+fn bench_verify(c: &mut Criterion) {
+    let transactions = transactions();
+    assert!(verify_batch(&transactions).into_iter().all(|valid| valid));
+
+    let mut group = c.benchmark_group("verify 1024 transactions");
+    group.sample_size(10);
+    group.bench_function("serial", |b| b.iter(|| black_box(&transactions).iter().map(|tx| tx.verify()).collect::<Vec<_>>()));
+    group.bench_function("verify_batch", |b| b.iter(|| verify_batch(black_box(&transactions))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_verify);
+criterion_main!(benches);
@@ -0,0 +1,85 @@
+use std::{alloc::{GlobalAlloc, Layout, System}, hint::black_box, sync::atomic::{AtomicUsize, Ordering}};
+
+use criterion::{criterion_group, criterion_main, Criterion};
+use qubic_tcp_types::{consts::{MAX_NUMBER_OF_CONTRACTS, NUMBER_OF_TRANSACTION_PER_TICK}, types::{ticks::TickData, time::QubicTime, transactions::{RawTransaction, TransactionData, TransactionWithData}}, views::NetworkEventView, MessageType};
+use qubic_types::{traits::{FromBytes, ToBytes}, QubicId, QubicTxHash, Signature};
+
+/// counts the allocations to compare them per event
+struct CountingAllocator;
+
+static ALLOCATIONS: AtomicUsize = AtomicUsize::new(0);
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.fetch_add(1, Ordering::Relaxed);
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static GLOBAL: CountingAllocator = CountingAllocator;
+
+const EVENTS: usize = 1_000;
+
+fn allocations_per_event(f: impl Fn()) -> f64 {
+    let before = ALLOCATIONS.load(Ordering::Relaxed);
+
+    for _ in 0..EVENTS {
+        f();
+    }
+
+    (ALLOCATIONS.load(Ordering::Relaxed) - before) as f64 / EVENTS as f64
+}
+
+fn transaction() -> Vec<u8> {
+    TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, tick: 12_000_000, input_type: 0, input_size: 64 },
+        data: TransactionData::Unknown(vec![3; 64]),
+        signature: Signature([4; 64])
+    }.to_bytes()
+}
+
+fn tick_data() -> Vec<u8> {
+    TickData {
+        computor_index: 1,
+        epoch: 100,
+        tick: 12_000_000,
+        time: QubicTime { milliseconds: 0, second: 1, minute: 2, hour: 3, day: 4, month: 5, year: 24 },
+        time_lock: [0; 32],
+        transaction_digest: [QubicTxHash([5; 32]); NUMBER_OF_TRANSACTION_PER_TICK],
+        contract_fees: [0; MAX_NUMBER_OF_CONTRACTS],
+        signature: Signature([6; 64])
+    }.to_bytes()
+}
+
+fn bench_views(c: &mut Criterion) {
+    let tx = transaction();
+    let tick_data = tick_data();
+
+    // what a handler of `subscribe` receives compared to reading the same fields from a view
+    let owned_tx = || { black_box(TransactionWithData::from_bytes(black_box(&tx)).unwrap().raw_transaction.amount); };
+    let view_tx = || {
+        let Some(NetworkEventView::BroadcastTransaction(view)) = NetworkEventView::parse(MessageType::BroadcastTransaction, black_box(&tx)).unwrap() else { unreachable!() };
+        black_box(view.amount());
+    };
+    let owned_tick_data = || { black_box(Box::new(TickData::from_bytes(black_box(&tick_data)).unwrap()).tick); };
+    let view_tick_data = || {
+        let Some(NetworkEventView::BroadcastFutureTick(view)) = NetworkEventView::parse(MessageType::BroadcastFutureTickData, black_box(&tick_data)).unwrap() else { unreachable!() };
+        black_box(view.tick());
+    };
+
+    println!("allocations per transaction: owned {}, view {}", allocations_per_event(owned_tx), allocations_per_event(view_tx));
+    println!("allocations per tick data: owned {}, view {}", allocations_per_event(owned_tick_data), allocations_per_event(view_tick_data));
+
+    c.bench_function("transaction owned", |b| b.iter(owned_tx));
+    c.bench_function("transaction view", |b| b.iter(view_tx));
+    c.bench_function("tick data owned", |b| b.iter(owned_tick_data));
+    c.bench_function("tick data view", |b| b.iter(view_tick_data));
+}
+
+criterion_group!(benches, bench_views);
+criterion_main!(benches);
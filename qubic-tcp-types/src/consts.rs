@@ -1,8 +1,32 @@
+//! Network parameters the packet layouts are derived from, array lengths of the packets refer to these instead of
+//! literals and every layout is checked against them at compile time
+
 use qubic_types::QubicId;
 
 pub const NUMBER_OF_TRANSACTION_PER_TICK: usize = 1024;
 pub const MAX_NUMBER_OF_CONTRACTS: usize = 1024;
 pub const NUMBER_OF_COMPUTORS: usize = 676;
+/// bytes of input a transaction may carry, the node drops transactions with more
+pub const MAX_INPUT_SIZE: usize = 1024;
+/// number of agreeing computors required for a tick to be final (451)
+pub const QUORUM: usize = NUMBER_OF_COMPUTORS * 2 / 3 + 1;
+/// epochs the node keeps solution thresholds for
+pub const MAX_NUMBER_EPOCH: u32 = 1000;
+/// scores do not exceed the length of the mining data, the node applies its default threshold beyond
+pub const MAX_SOLUTION_THRESHOLD: i32 = 256;
 pub const SPECTRUM_DEPTH: usize = 24;
 pub const SPECTRUM_CAPACITY: usize = 0x1000000;
-pub const ARBITRATOR: QubicId = QubicId([158, 26, 16, 12, 251, 85, 109, 239, 123, 204, 98, 82, 228, 125, 223, 9, 133, 66, 134, 55, 195, 209, 179, 202, 161, 111, 51, 253, 152, 67, 141, 148]);
\ No newline at end of file
+pub const ARBITRATOR: QubicId = QubicId([158, 26, 16, 12, 251, 85, 109, 239, 123, 204, 98, 82, 228, 125, 223, 9, 133, 66, 134, 55, 195, 209, 179, 202, 161, 111, 51, 253, 152, 67, 141, 148]);
+
+/// one bit per computor (85 bytes), bit `i % 8` of byte `i / 8` belongs to computor `i`
+pub type ComputorBitfield = [u8; NUMBER_OF_COMPUTORS.div_ceil(8)];
+/// computors whose vote a quorum tick is already known of, their votes are not sent again
+pub type VoteFlags = ComputorBitfield;
+/// three bits per computor (254 bytes), the vote of every computor in a ballot of the `Ballot` command
+pub type BallotVotes = [u8; (NUMBER_OF_COMPUTORS * 3).div_ceil(8)];
+/// one bit per transaction slot of a tick (128 bytes)
+pub type TransactionBitfield = [u8; NUMBER_OF_TRANSACTION_PER_TICK / 8];
+
+const _: () = assert!(NUMBER_OF_TRANSACTION_PER_TICK.is_multiple_of(8), "transaction flags have to fill whole bytes");
+const _: () = assert!(QUORUM * 3 > NUMBER_OF_COMPUTORS * 2 && (QUORUM - 1) * 3 <= NUMBER_OF_COMPUTORS * 2);
+const _: () = assert!(SPECTRUM_CAPACITY == 1 << SPECTRUM_DEPTH, "the spectrum is a complete binary tree of SPECTRUM_DEPTH levels");
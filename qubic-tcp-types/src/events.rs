@@ -1,10 +1,189 @@
-use crate::{types::{BroadcastMessage, ExchangePublicPeers}, prelude::{Tick, TickData, TransactionWithData}};
+use alloc::{boxed::Box, string::String};
 
-#[derive(Debug)]
+use crate::{types::{BroadcastMessage, Computors, ExchangePublicPeers}, prelude::{Tick, TickData, TransactionWithData}};
+
+/// Event broadcasted by the peers, the payloads are boxed so events stay small while they are queued and moved between
+/// threads
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum NetworkEvent {
     ExchangePublicPeers(ExchangePublicPeers),
-    BroadcastMessage(BroadcastMessage),
-    BroadcastTransaction(TransactionWithData),
-    BroadcastTick(Tick),
-    BroadcastFutureTick(Box<TickData>)
-}
\ No newline at end of file
+    BroadcastMessage(Box<BroadcastMessage>),
+    BroadcastTransaction(Box<TransactionWithData>),
+    BroadcastTick(Box<Tick>),
+    BroadcastFutureTick(Box<TickData>),
+    BroadcastComputors(Box<Computors>),
+    /// derived by `EpochTracker`, not sent by the peers
+    EpochChanged { old: u16, new: u16 },
+    /// the connection to the peer dropped and is reestablished, the events sent in between are missed. Derived by the
+    /// subscription, not sent by the peers
+    Disconnected
+}
+
+impl NetworkEvent {
+    /// epoch the event was issued in, if it carries one
+    pub fn epoch(&self) -> Option<u16> {
+        match self {
+            Self::BroadcastTick(tick) => Some(tick.epoch),
+            Self::BroadcastFutureTick(tick_data) => Some(tick_data.epoch),
+            Self::BroadcastComputors(computors) => Some(computors.epoch),
+            Self::EpochChanged { new, .. } => Some(*new),
+            _ => None
+        }
+    }
+}
+
+/// Derives `NetworkEvent::EpochChanged` from the epochs of the received events.
+/// Epochs only advance, events of older epochs come from lagging peers and are no change
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct EpochTracker {
+    epoch: Option<u16>
+}
+
+impl EpochTracker {
+    pub fn epoch(&self) -> Option<u16> {
+        self.epoch
+    }
+
+    /// `EpochChanged` if `event` is of a later epoch than the ones seen so far, the first epoch seen is no change
+    pub fn observe(&mut self, event: &NetworkEvent) -> Option<NetworkEvent> {
+        let new = event.epoch()?;
+
+        match self.epoch {
+            Some(old) if new > old => {
+                self.epoch = Some(new);
+                Some(NetworkEvent::EpochChanged { old, new })
+            },
+            Some(_) => None,
+            None => {
+                self.epoch = Some(new);
+                None
+            }
+        }
+    }
+}
+
+/// Network event with the time and peer it was received from
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct EventEnvelope {
+    /// milliseconds since the UNIX epoch
+    pub received_at: u64,
+    /// address of the peer which sent the event
+    pub source: String,
+    pub event: NetworkEvent
+}
+
+#[cfg(all(test, feature = "serde"))]
+fn round_trip(event: NetworkEvent) {
+    let envelope = EventEnvelope { received_at: 1_700_000_000_000, source: "127.0.0.1:21841".into(), event };
+    let json = serde_json::to_string(&envelope).unwrap();
+
+    assert_eq!(serde_json::from_str::<EventEnvelope>(&json).unwrap(), envelope);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_event_round_trip() {
+    use core::net::Ipv4Addr;
+    use qubic_types::{QubicId, QubicTxHash, Signature};
+    use crate::types::{time::QubicTime, transactions::{RawTransaction, TransactionData}};
+
+    let time = QubicTime { milliseconds: 500, second: 1, minute: 2, hour: 3, day: 4, month: 5, year: 24 };
+
+    round_trip(NetworkEvent::ExchangePublicPeers(ExchangePublicPeers { peers: [Ipv4Addr::new(1, 2, 3, 4), Ipv4Addr::new(5, 6, 7, 8), Ipv4Addr::LOCALHOST, Ipv4Addr::UNSPECIFIED] }));
+
+    round_trip(NetworkEvent::BroadcastMessage(Box::new(BroadcastMessage {
+        source_public_key: QubicId([1; 32]),
+        destination_public_key: QubicId([2; 32]),
+        gamming_nonce: Default::default(),
+        solution_mining_seed: Default::default(),
+        solution_nonce: Default::default(),
+        signature: Signature([3; 64])
+    })));
+
+    round_trip(NetworkEvent::BroadcastTransaction(Box::new(TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, tick: 12_000_000, input_type: 0, input_size: 0 },
+        data: TransactionData::default(),
+        signature: Signature([3; 64])
+    })));
+
+    round_trip(NetworkEvent::BroadcastTick(Box::new(Tick {
+        computor_index: 1,
+        epoch: 100,
+        tick: 12_000_000,
+        time,
+        prev_resource_testing_digest: 1,
+        salted_resource_testing_digest: 2,
+        prev_spectrum_digest: [1; 32].into(),
+        prev_universe_digest: [2; 32].into(),
+        prev_computor_digest: [3; 32].into(),
+        salted_spectrum_digest: [4; 32].into(),
+        salted_universe_digest: [5; 32].into(),
+        salted_computor_digest: [6; 32].into(),
+        transaction_digest: [7; 32].into(),
+        expected_next_tick_transaction_digest: [8; 32].into(),
+        signature: Signature([9; 64])
+    })));
+
+    let mut tick_data = TickData::new_boxed();
+    tick_data.computor_index = 1;
+    tick_data.epoch = 100;
+    tick_data.tick = 12_000_000;
+    tick_data.time = time;
+    tick_data.time_lock = [1; 32];
+    tick_data.signature = Signature([2; 64]);
+    tick_data.transaction_digest[3] = QubicTxHash([4; 32]);
+    tick_data.contract_fees[5] = 6;
+
+    round_trip(NetworkEvent::BroadcastFutureTick(tick_data));
+
+    let mut computors = Computors::new_boxed();
+    computors.epoch = 101;
+    computors.signature = Signature([1; 64]);
+    computors.public_key[675] = QubicId([2; 32]);
+
+    round_trip(NetworkEvent::BroadcastComputors(computors));
+    round_trip(NetworkEvent::EpochChanged { old: 100, new: 101 });
+    round_trip(NetworkEvent::Disconnected);
+}
+
+#[test]
+fn test_epoch_tracker() {
+    use crate::types::time::QubicTime;
+
+    let tick = |epoch| NetworkEvent::BroadcastTick(Box::new(Tick {
+        computor_index: 0,
+        epoch,
+        tick: 0,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 25 },
+        prev_resource_testing_digest: 0,
+        salted_resource_testing_digest: 0,
+        prev_spectrum_digest: Default::default(),
+        prev_universe_digest: Default::default(),
+        prev_computor_digest: Default::default(),
+        salted_spectrum_digest: Default::default(),
+        salted_universe_digest: Default::default(),
+        salted_computor_digest: Default::default(),
+        transaction_digest: Default::default(),
+        expected_next_tick_transaction_digest: Default::default(),
+        signature: Default::default()
+    }));
+    let computors = |epoch| {
+        let mut computors = Computors::new_boxed();
+        computors.epoch = epoch;
+
+        NetworkEvent::BroadcastComputors(computors)
+    };
+    let peers = NetworkEvent::ExchangePublicPeers(ExchangePublicPeers::default());
+
+    let mut tracker = EpochTracker::default();
+    let changes = [peers, tick(100), tick(100), computors(101), tick(100), tick(101), tick(102)].iter()
+        .filter_map(|event| tracker.observe(event))
+        .collect::<alloc::vec::Vec<_>>();
+
+    // the lagging tick of epoch 100 does not turn the epoch back
+    assert_eq!(changes, [NetworkEvent::EpochChanged { old: 100, new: 101 }, NetworkEvent::EpochChanged { old: 101, new: 102 }]);
+    assert_eq!(tracker.epoch(), Some(102));
+}
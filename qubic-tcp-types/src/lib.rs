@@ -1,65 +1,144 @@
 #![cfg_attr(not(feature = "std"), no_std)]
 
+// the wire structs are encoded through their `repr(C)` layout, see `types`
+#[cfg(target_endian = "big")]
+compile_error!("qubic-tcp-types only supports little-endian targets: wire structs are encoded through their in-memory layout and would produce wrong packets");
+
 #[macro_use]
 pub extern crate alloc;
 use rand::Rng;
+use qubic_types::U24;
 
 pub mod types;
 pub mod utils;
 pub mod prelude;
 pub mod consts;
 pub mod events;
+pub mod views;
+
+/// declares `MessageType` along with the list of its variants and their names, which can't get out of step this way
+macro_rules! message_types {
+    ($($variant: ident = $value: literal => $name: literal),* $(,)?) => {
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+        #[repr(u8)]
+        pub enum MessageType {
+            $($variant = $value),*
+        }
+
+        impl MessageType {
+            const ALL: &'static [MessageType] = &[$(MessageType::$variant),*];
+
+            /// stable snake_case name, e.g. `respond_entity`
+            pub const fn name(self) -> &'static str {
+                match self {
+                    $(MessageType::$variant => $name),*
+                }
+            }
+        }
+    };
+}
+
+message_types! {
+    BroadcastMessage = 1 => "broadcast_message",
+
+    ExchangePublicPeers = 0 => "exchange_public_peers",
+    BroadcastComputors = 2 => "broadcast_computors",
+    BroadcastTick = 3 => "broadcast_tick",
+    BroadcastFutureTickData = 8 => "broadcast_future_tick_data",
+    RequestComputors = 11 => "request_computors",
+    RequestQuorumTick = 14 => "request_quorum_tick",
+    RequestTickData = 16 => "request_tick_data",
+    BroadcastTransaction = 24 => "broadcast_transaction",
+
+    RequestCurrentTickInfo = 27 => "request_current_tick_info",
+    RespondCurrentTickInfo = 28 => "respond_current_tick_info",
+
+    RequestTickTransactions = 29 => "request_tick_transactions",
+
+    RequestEntity = 31 => "request_entity",
+    RespondEntity = 32 => "respond_entity",
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-#[repr(u8)]
-pub enum MessageType {
-    BroadcastMessage = 1,
-    
-    ExchangePublicPeers = 0,
-    BroadcastComputors = 2,
-    BroadcastTick = 3,
-    BroadcastFutureTickData = 8,
-    RequestComputors = 11,
-    RequestQuorumTick = 14,
-    RequestTickData = 16,
-    BroadcastTransaction = 24,
+    RequestContractIPO = 33 => "request_contract_ipo",
+    RespondContractIPO = 34 => "respond_contract_ipo",
 
-    RequestCurrentTickInfo = 27,
-    RespondCurrentTickInfo = 28,
+    EndResponse = 35 => "end_response",
 
-    RequestTickTransactions = 29,
+    RequestIssuedAsset = 36 => "request_issued_asset",
+    RespondIssuedAsset = 37 => "respond_issued_asset",
+    RequestOwnedAsset = 38 => "request_owned_asset",
+    RespondOwnedAsset = 39 => "respond_owned_asset",
+    RequestPossessedAsset = 40 => "request_possessed_asset",
+    RespondPossessedAsset = 41 => "respond_possessed_asset",
 
-    RequestEntity = 31,
-    RespondEntity = 32,
+    RequestContractFunction = 42 => "request_contract_function",
+    RespondContractFunction = 43 => "respond_contract_function",
 
-    RequestContractIPO = 33,
-    RespondContractIPO = 34,
+    RequestLog = 44 => "request_log",
+    RespondLog = 45 => "respond_log",
 
-    EndResponse = 35,
+    RequestSystemInfo = 46 => "request_system_info",
+    RespondSystemInfo = 47 => "respond_system_info",
 
-    RequestIssuedAsset = 36,
-    RespondIssuedAsset = 37,
-    RequestOwnedAsset = 38,
-    RespondOwnedAsset = 39,
-    RequestPossessedAsset = 40,
-    RespondPossessedAsset = 41,
+    RequestAssets = 52 => "request_assets",
+    RespondAssets = 53 => "respond_assets",
 
-    RequestContractFunction = 42,
-    RespondContractFunction = 43,
+    ProcessSpecialCommand = 255 => "process_special_command"
+}
 
-    RequestLog = 44,
-    RespondLog = 45,
+impl MessageType {
+    /// every known message type in declaration order
+    pub fn iter() -> impl Iterator<Item = Self> {
+        Self::ALL.iter().copied()
+    }
+}
 
-    RequestSystemInfo = 46,
-    RespondSystemInfo = 47,
+/// the name, with the alternate flag (`{:#}`) followed by the number, e.g. `respond_entity (32)`
+impl core::fmt::Display for MessageType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match f.alternate() {
+            true => write!(f, "{} ({})", self.name(), *self as u8),
+            false => f.write_str(self.name())
+        }
+    }
+}
 
-    ProcessSpecialCommand = 255
+/// A message type name or number which is not known
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct UnknownMessageType(pub alloc::string::String);
+
+impl core::fmt::Display for UnknownMessageType {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Unknown message type {}", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for UnknownMessageType {}
+
+impl TryFrom<u8> for MessageType {
+    type Error = UnknownMessageType;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::iter().find(|message_type| *message_type as u8 == value).ok_or_else(|| UnknownMessageType(format!("{value}")))
+    }
+}
+
+/// accepts the name (`respond_entity`) as well as the number (`32`)
+impl core::str::FromStr for MessageType {
+    type Err = UnknownMessageType;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s.parse::<u8>() {
+            Ok(value) => Self::try_from(value),
+            Err(_) => Self::iter().find(|message_type| message_type.name() == s).ok_or_else(|| UnknownMessageType(s.into()))
+        }
+    }
 }
 
 #[derive(Debug, Clone, Copy)]
 #[repr(C)]
 pub struct Header {
-    pub size: [u8; 3],
+    pub size: U24,
     pub message_type: MessageType,
     pub dejavu: u32,
 }
@@ -67,10 +146,9 @@ pub struct Header {
 
 impl Header {
     #[cfg(not(feature = "wasm"))]
-    pub fn new(size: usize, message_type: MessageType, randomize_dejavu: bool) -> Self {
+    pub fn new(size: U24, message_type: MessageType, randomize_dejavu: bool) -> Self {
         
-        let mut new = Self { size: [0; 3], message_type, dejavu: 0};
-        new.set_size(size);
+        let mut new = Self { size, message_type, dejavu: 0};
         if randomize_dejavu {
             new.randomize_dejavu();
         }
@@ -78,23 +156,12 @@ impl Header {
         new
     }
 
-    pub fn new_with_dejavu(size: usize, message_type: MessageType, dejavu: u32) -> Self {
-        
-        let mut new = Self { size: [0; 3], message_type, dejavu: 0};
-        new.set_size(size);
-        new.dejavu = dejavu;
-
-        new
+    pub fn new_with_dejavu(size: U24, message_type: MessageType, dejavu: u32) -> Self {
+        Self { size, message_type, dejavu }
     }
 
     pub fn get_size(&self) -> usize {
-        (self.size[0] as usize) | (self.size[1] as usize) << 8 | (self.size[2] as usize) << 16
-    }
-    
-    pub fn set_size(&mut self, size: usize) {
-        self.size[0] = size as u8;
-        self.size[1] = (size >> 8) as u8 ;
-        self.size[2] = (size >> 16) as u8 ;
+        self.size.get()
     }
 
     pub fn zero_dejavu(&mut self) {
@@ -110,3 +177,35 @@ impl Header {
         self.message_type = new_type;
     }
 }
+
+#[test]
+fn test_message_type_names() {
+    use alloc::{collections::BTreeSet, string::ToString};
+
+    let message_types: alloc::vec::Vec<_> = MessageType::iter().collect();
+
+    for message_type in message_types.iter().copied() {
+        let (name, number) = (message_type.name(), message_type as u8);
+
+        assert_eq!(message_type.to_string(), name);
+        assert_eq!(format!("{message_type:#}"), format!("{name} ({number})"));
+        assert_eq!(name.parse::<MessageType>(), Ok(message_type));
+        assert_eq!(number.to_string().parse::<MessageType>(), Ok(message_type));
+        assert_eq!(MessageType::try_from(number), Ok(message_type));
+        assert!(name.bytes().all(|c| c.is_ascii_lowercase() || c == b'_'), "{name}");
+    }
+
+    // names and numbers identify a message type each
+    assert_eq!(message_types.iter().map(|message_type| message_type.name()).collect::<BTreeSet<_>>().len(), message_types.len());
+    assert_eq!(message_types.iter().map(|message_type| *message_type as u8).collect::<BTreeSet<_>>().len(), message_types.len());
+
+    // every other number is unknown
+    for number in (0..=u8::MAX).filter(|number| !message_types.iter().any(|message_type| *message_type as u8 == *number)) {
+        assert_eq!(MessageType::try_from(number), Err(UnknownMessageType(number.to_string())));
+    }
+
+    assert_eq!("RespondEntity".parse::<MessageType>(), Err(UnknownMessageType("RespondEntity".into())));
+    assert!("".parse::<MessageType>().is_err());
+    assert!("256".parse::<MessageType>().is_err());
+    assert_eq!("respond_entity".parse(), Ok(MessageType::RespondEntity));
+}
@@ -0,0 +1,118 @@
+//! Categories of transfers for wallet statements, e.g. to label the payouts at the end of an epoch
+//!
+//! Computors pay out the revenue of an epoch as plain transfers. A transfer is a reward if it was sent by a computor of an
+//! epoch within the payout window of that epoch. The windows are given by the caller since the last tick of an epoch is
+//! only known once the next epoch started.
+
+use alloc::vec::Vec;
+use core::ops::RangeInclusive;
+
+use qubic_types::QubicId;
+
+use crate::consts::MAX_NUMBER_OF_CONTRACTS;
+
+use super::{transactions::RawTransaction, Computors};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "category", rename_all = "lowercase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum TransferCategory {
+    Transfer,
+    /// payout of the computors of `epoch`
+    Reward { epoch: u16 },
+    /// sent to or by a contract
+    Contract,
+    /// sent to the zero identity
+    Burn
+}
+
+/// Computors of an epoch with the ticks their payouts are expected in
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct EpochPayouts {
+    pub epoch: u16,
+    pub computors: Vec<QubicId>,
+    pub ticks: RangeInclusive<u32>
+}
+
+impl EpochPayouts {
+    pub fn new(computors: &Computors, ticks: RangeInclusive<u32>) -> Self {
+        Self { epoch: computors.epoch, computors: computors.public_key.to_vec(), ticks }
+    }
+
+    /// payouts within `window` ticks around `last_tick`, the last tick of the epoch of `computors`
+    pub fn at_epoch_end(computors: &Computors, last_tick: u32, window: u32) -> Self {
+        Self::new(computors, last_tick.saturating_sub(window)..=last_tick.saturating_add(window))
+    }
+}
+
+/// index of the contract `id` belongs to, contract ids carry the index in their first 8 bytes
+pub fn contract_index(id: &QubicId) -> Option<u32> {
+    let index = u64::from_le_bytes(id.0[..8].try_into().unwrap());
+
+    (index != 0 && index < MAX_NUMBER_OF_CONTRACTS as u64 && id.0[8..].iter().all(|b| *b == 0)).then_some(index as u32)
+}
+
+/// category of the transfer, `payouts` are the known computor sets of the epochs
+pub fn classify(tx: &RawTransaction, payouts: &[EpochPayouts]) -> TransferCategory {
+    if tx.to == QubicId::default() {
+        return TransferCategory::Burn
+    }
+
+    if contract_index(&tx.to).is_some() || contract_index(&tx.from).is_some() {
+        return TransferCategory::Contract
+    }
+
+    payouts.iter()
+        .find(|payouts| payouts.ticks.contains(&tx.tick) && payouts.computors.contains(&tx.from))
+        .map_or(TransferCategory::Transfer, |payouts| TransferCategory::Reward { epoch: payouts.epoch })
+}
+
+#[cfg(test)]
+fn computors(epoch: u16, first: u8) -> Computors {
+    use qubic_types::Signature;
+
+    let mut public_key = [QubicId::default(); crate::consts::NUMBER_OF_COMPUTORS];
+    for (i, id) in public_key.iter_mut().enumerate() {
+        *id = QubicId([[first, 0xcc].as_slice(), &(i as u32).to_le_bytes(), &[0xcc; 26]].concat().try_into().unwrap());
+    }
+
+    Computors { epoch, public_key, signature: Signature::default() }
+}
+
+#[test]
+fn test_classify_transfers() {
+    use super::assets::QXID;
+
+    let (epoch_100, epoch_101) = (computors(100, 1), computors(101, 2));
+    let payouts = [EpochPayouts::at_epoch_end(&epoch_100, 1_000, 5), EpochPayouts::at_epoch_end(&epoch_101, 2_000, 5)];
+    let (user, other) = (QubicId([9; 32]), QubicId([8; 32]));
+    let tx = |from, to, tick| RawTransaction { from, to, amount: 1_000, tick, ..Default::default() };
+
+    // payouts of a computor of the epoch within the window
+    assert_eq!(classify(&tx(epoch_100.public_key[0], user, 998), &payouts), TransferCategory::Reward { epoch: 100 });
+    assert_eq!(classify(&tx(epoch_100.public_key[675], user, 1_005), &payouts), TransferCategory::Reward { epoch: 100 });
+    assert_eq!(classify(&tx(epoch_101.public_key[3], user, 2_000), &payouts), TransferCategory::Reward { epoch: 101 });
+
+    // computors outside the window of their epoch and other identities within it send plain transfers
+    assert_eq!(classify(&tx(epoch_100.public_key[0], user, 1_500), &payouts), TransferCategory::Transfer);
+    assert_eq!(classify(&tx(epoch_100.public_key[0], user, 2_000), &payouts), TransferCategory::Transfer);
+    assert_eq!(classify(&tx(other, user, 1_000), &payouts), TransferCategory::Transfer);
+    assert_eq!(classify(&tx(epoch_100.public_key[0], user, 1_000), &[]), TransferCategory::Transfer);
+
+    assert_eq!(classify(&tx(user, QXID, 1_000), &payouts), TransferCategory::Contract);
+    assert_eq!(classify(&tx(QXID, user, 1_000), &payouts), TransferCategory::Contract);
+    assert_eq!(classify(&tx(user, QubicId::default(), 1_000), &payouts), TransferCategory::Burn);
+
+    assert_eq!(contract_index(&QubicId([[4, 0].as_slice(), &[0; 30]].concat().try_into().unwrap())), Some(4));
+    assert_eq!(contract_index(&QubicId([[0, 4].as_slice(), &[0; 30]].concat().try_into().unwrap())), None);
+    assert_eq!(contract_index(&user), None);
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_transfer_category_serde() {
+    assert_eq!(serde_json::to_value(TransferCategory::Reward { epoch: 100 }).unwrap(), serde_json::json!({ "category": "reward", "epoch": 100 }));
+    assert_eq!(serde_json::to_value(TransferCategory::Burn).unwrap(), serde_json::json!({ "category": "burn" }));
+    assert_eq!(serde_json::from_value::<TransferCategory>(serde_json::json!({ "category": "transfer" })).unwrap(), TransferCategory::Transfer);
+}
@@ -1,5 +1,5 @@
-use core::{fmt::Debug, str::FromStr};
-use qubic_types::QubicId;
+use core::{fmt::{Debug, Display, Write}, str::FromStr};
+use qubic_types::{QubicId, errors::IdKind};
 
 #[cfg(feature = "serde")]
 use serde::{de::Visitor, Serialize, Deserialize};
@@ -9,8 +9,12 @@ use crate::MessageType;
 use super::transactions::TransactionData;
 
 pub const QXID: QubicId = QubicId([1, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0, 0]);
-pub const TRANSFER_FEE: u64 = 1_000_000;
-pub const ISSUE_ASSET_FEE: u64 = 1_000_000_000;
+pub use super::fees::{ISSUE_ASSET_FEE, TRANSFER_FEE};
+
+/// QX input type of `IssueAssetInput`
+pub const QX_ISSUE_ASSET: u16 = 1;
+/// QX input type of `TransferAssetOwnershipAndPossessionInput`
+pub const QX_TRANSFER_OWNERSHIP_AND_POSSESSION: u16 = 2;
 
 macro_rules! generate_packed_integers {
     ($($name: ident $alias: ty)*) => {
@@ -52,9 +56,52 @@ macro_rules! generate_packed_integers {
 
 generate_packed_integers!(U16 u16 I16 i16 U32 u32 I32 i32 U64 u64 I64 i64);
 
+/// Characters of an asset name, the last byte of the 8 byte form is always zero
+pub const ASSET_NAME_MAX_LEN: usize = 7;
+
+/// ASCII asset name of at most 7 characters padded with zeros. QX inputs carry the 8 byte form (a little endian
+/// `u64`), issuance records and logs the 7 byte form. `LEN` is either 7 or 8
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
-pub struct AssetName<const LEN: usize>(pub [u8; LEN]);
+pub struct AssetName<const LEN: usize = 8>(pub [u8; LEN]);
+
+impl<const LEN: usize> AssetName<LEN> {
+    pub fn from_bytes7(bytes: [u8; 7]) -> Self {
+        let mut name = [0u8; LEN];
+        name[..ASSET_NAME_MAX_LEN].copy_from_slice(&bytes);
+
+        Self(name)
+    }
+
+    /// the 7 byte form, the padding byte of the 8 byte form is dropped
+    pub fn as_bytes7(&self) -> [u8; 7] {
+        self.0[..ASSET_NAME_MAX_LEN].try_into().expect("asset names have at least 7 bytes")
+    }
+
+    /// the name as QX inputs encode it
+    pub fn from_u64(value: u64) -> Self {
+        Self::from_bytes7(value.to_le_bytes()[..ASSET_NAME_MAX_LEN].try_into().expect("u64 has 8 bytes"))
+    }
+
+    pub fn as_u64(&self) -> u64 {
+        let mut bytes = [0u8; 8];
+        bytes[..ASSET_NAME_MAX_LEN].copy_from_slice(&self.as_bytes7());
+
+        u64::from_le_bytes(bytes)
+    }
+}
+
+impl From<AssetName<7>> for AssetName<8> {
+    fn from(value: AssetName<7>) -> Self {
+        Self::from_bytes7(value.0)
+    }
+}
+
+impl From<AssetName<8>> for AssetName<7> {
+    fn from(value: AssetName<8>) -> Self {
+        Self(value.as_bytes7())
+    }
+}
 
 #[cfg(feature = "serde")]
 pub struct AssetNameVisitor<const LEN: usize>;
@@ -64,34 +111,23 @@ impl<'de, const LEN: usize> Visitor<'de> for AssetNameVisitor<LEN> {
     type Value = AssetName<LEN>;
 
     fn expecting(&self, formatter: &mut core::fmt::Formatter) -> core::fmt::Result {
-        formatter.write_str(&format!("Expected {LEN} character string"))
+        write!(formatter, "an ASCII string of 1 to {ASSET_NAME_MAX_LEN} characters")
     }
 
     fn visit_str<E>(self, v: &str) -> Result<Self::Value, E>
         where
             E: serde::de::Error, {
-        match AssetName::<LEN>::from_str(v) {
-            Ok(r) => Ok(r),
-            Err(e) => Err(E::custom(e.to_string()))
-        }
-    }
-
-    fn visit_string<E>(self, v: String) -> Result<Self::Value, E>
-        where
-            E: serde::de::Error, {
-        match AssetName::<LEN>::from_str(&v) {
-            Ok(r) => Ok(r),
-            Err(e) => Err(E::custom(e.to_string()))
-        }
+        AssetName::<LEN>::from_str(v).map_err(|e| E::custom(e.to_string()))
     }
 }
 
+/// serialized as the name without its padding
 #[cfg(feature = "serde")]
 impl<const LEN: usize> Serialize for AssetName<LEN> {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error>
         where
             S: serde::Serializer {
-        serializer.collect_str(&self.to_string())
+        serializer.collect_str(self)
     }
 }
 
@@ -104,42 +140,52 @@ impl<'de, const LEN: usize> Deserialize<'de> for AssetName<LEN> {
     }
 }
 
+#[cfg(feature = "utoipa")]
+impl<const LEN: usize> utoipa::PartialSchema for AssetName<LEN> {
+    fn schema() -> utoipa::openapi::RefOr<utoipa::openapi::schema::Schema> {
+        utoipa::openapi::ObjectBuilder::new()
+            .schema_type(utoipa::openapi::Type::String)
+            .description(Some("ASCII asset name"))
+            .min_length(Some(1))
+            .max_length(Some(ASSET_NAME_MAX_LEN))
+            .into()
+    }
+}
+
+#[cfg(feature = "utoipa")]
+impl<const LEN: usize> utoipa::ToSchema for AssetName<LEN> {}
+
+/// accepts 1 to 7 ASCII characters except NUL, which pads the name
 impl<const LEN: usize> FromStr for AssetName<LEN> {
     type Err = qubic_types::errors::QubicError;
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        if s.len() > LEN || !s.is_ascii() {
-            return Err(qubic_types::errors::QubicError::InvalidIdLengthError { ident: "Name", expected: 7, found: s.len() })
-        }
+        let kind = IdKind::AssetName { max_len: ASSET_NAME_MAX_LEN };
 
-        let mut name = [0u8; LEN];
+        if !s.is_ascii() || s.contains('\0') {
+            return Err(qubic_types::errors::QubicError::InvalidIdFormatError { kind })
+        }
 
-        for (idx, c) in s.as_bytes().iter().enumerate() {
-            name[idx] = *c;
+        if s.is_empty() || s.len() > ASSET_NAME_MAX_LEN {
+            return Err(qubic_types::errors::QubicError::InvalidIdLengthError { kind, found: s.len() })
         }
 
-        Ok(AssetName(name))
+        let mut name = [0u8; 7];
+        name[..s.len()].copy_from_slice(s.as_bytes());
+
+        Ok(Self::from_bytes7(name))
     }
 }
 
-impl<const LEN: usize> ToString for AssetName<LEN> {
-    fn to_string(&self) -> String {
-        let mut name = String::with_capacity(LEN);
-
-        for byte in self.0 {
-            if byte != 0 {
-                name.push(char::from(byte));
-            }
-        }
-
-        name
+/// the name up to its padding
+impl<const LEN: usize> Display for AssetName<LEN> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        self.0.iter().take_while(|byte| **byte != 0).try_for_each(|byte| f.write_char(char::from(*byte)))
     }
 }
 
 impl<const LEN: usize> Debug for AssetName<LEN> {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_str(&self.to_string())?;
-
-        Ok(())
+        Display::fmt(self, f)
     }
 }
 
@@ -185,6 +231,35 @@ pub struct Asset {
     pub asset_type: AssetType
 }
 
+/// Condensed view of an asset issuance
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct AssetSummary {
+    pub issuer: QubicId,
+    pub name: AssetName<7>,
+    /// issuance records do not carry the issued amount, `None` unless it was looked up separately
+    pub total_units: Option<i64>,
+    pub decimals: u8,
+    pub uom: [u8; 7]
+}
+
+impl AssetSummary {
+    /// returns `None` if the asset is not an issuance record
+    pub fn from_issuance(asset: &Asset) -> Option<Self> {
+        match asset.asset_type {
+            AssetType::Issuance(issuance) => Some(Self {
+                issuer: asset.public_key,
+                name: issuance.name,
+                total_units: None,
+                decimals: issuance.number_of_decimal_places,
+                uom: issuance.unit_of_measurement
+            }),
+            _ => None
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct FeesInput;
@@ -199,6 +274,7 @@ pub struct FeesOutput {
 
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct TransferAssetInput {
     pub destination: QubicId
@@ -212,14 +288,22 @@ impl From<TransferAssetInput> for TransactionData {
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct IssueAssetInput {
-    pub name: AssetName<8>,
+    pub name: AssetName,
     pub number_of_units: i64,
     pub unit_of_measurement: u64,
     pub number_of_decimal_places: i8
 }
 
+impl IssueAssetInput {
+    /// `unit_of_measurement` is given in the 7 byte form of the issuance records
+    pub fn new(name: AssetName, number_of_units: i64, unit_of_measurement: [u8; 7], number_of_decimal_places: i8) -> Self {
+        Self { name, number_of_units, unit_of_measurement: AssetName::<8>::from_bytes7(unit_of_measurement).as_u64(), number_of_decimal_places }
+    }
+}
+
 impl From<IssueAssetInput> for TransactionData {
     fn from(value: IssueAssetInput) -> Self {
         Self::IssueAsset(value)
@@ -232,18 +316,27 @@ pub struct IssueAssetOutput {
     pub issued_number_of_units: i64
 }
 
+/// Moves ownership and possession of `number_of_units` to `new_owner`
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct TransferAssetOwnershipAndPossessionInput {
     pub issuer: QubicId,
     pub possessor: QubicId,
     pub new_owner: QubicId,
-    pub asset_name: AssetName<8>,
+    pub asset_name: AssetName,
     pub number_of_units: i64,
 }
 
 set_message_type!(TransferAssetOwnershipAndPossessionInput, MessageType::BroadcastTransaction);
 
+impl From<TransferAssetOwnershipAndPossessionInput> for TransactionData {
+    fn from(value: TransferAssetOwnershipAndPossessionInput) -> Self {
+        Self::TransferOwnershipAndPossession(value)
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct TranferAssetOwnershipAndPossessionOutput {
@@ -296,4 +389,141 @@ pub struct RespondPossessedAsset {
     pub ownership_asset: Asset,
     pub issuance_asset: Asset,
     pub tick: u32
-}
\ No newline at end of file
+}
+
+/// Asset records of the universe matching a filter, answered with a `RespondAssets` per record. Mirrors `RequestAssets`
+/// of core `src/network_messages/assets.h`, only the filter variant of its union is supported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct RequestAssets {
+    pub request_type: u16,
+    pub flags: u16,
+    pub ownership_managing_contract: u16,
+    pub possession_managing_contract: u16,
+    pub issuer: QubicId,
+    pub asset_name: u64,
+    pub owner: QubicId,
+    pub possessor: QubicId
+}
+
+set_message_type!(RequestAssets, MessageType::RequestAssets);
+
+impl RequestAssets {
+    pub const ISSUANCE_RECORDS: u16 = 0;
+    pub const OWNERSHIP_RECORDS: u16 = 1;
+    pub const POSSESSION_RECORDS: u16 = 2;
+
+    pub const ANY_ISSUER: u16 = 0b10;
+    pub const ANY_ASSET_NAME: u16 = 0b100;
+    pub const ANY_OWNER: u16 = 0b1000;
+    pub const ANY_OWNERSHIP_MANAGING_CONTRACT: u16 = 0b10000;
+    pub const ANY_POSSESSOR: u16 = 0b100000;
+    pub const ANY_POSSESSION_MANAGING_CONTRACT: u16 = 0b1000000;
+
+    /// issuance records of assets named `name` by any issuer
+    pub fn issuances_by_name(name: AssetName) -> Self {
+        Self {
+            request_type: Self::ISSUANCE_RECORDS,
+            flags: Self::ANY_ISSUER,
+            ownership_managing_contract: 0,
+            possession_managing_contract: 0,
+            issuer: QubicId::default(),
+            asset_name: name.as_u64(),
+            owner: QubicId::default(),
+            possessor: QubicId::default()
+        }
+    }
+
+    /// ownership records of the asset `name` issued by `issuer`, whoever owns it through any contract
+    pub fn ownerships_of(issuer: QubicId, name: AssetName) -> Self {
+        Self {
+            request_type: Self::OWNERSHIP_RECORDS,
+            flags: Self::ANY_OWNER | Self::ANY_OWNERSHIP_MANAGING_CONTRACT,
+            issuer,
+            ..Self::issuances_by_name(name)
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C, align(8))]
+pub struct RespondAssets {
+    pub asset: Asset,
+    pub tick: u32,
+    /// index of the record in the universe, ownership and possession records refer to their issuance by it
+    pub universe_index: u32
+}
+
+set_message_type!(RespondAssets, MessageType::RespondAssets);
+
+impl AssetSummary {
+    /// summary of the issuance `issuance` with the units of the ownership records among `records` which belong to it
+    pub fn with_ownerships(issuance: &RespondAssets, records: &[RespondAssets]) -> Option<Self> {
+        let total_units = records.iter().filter_map(|record| match record.asset.asset_type {
+            AssetType::Ownership(ownership) if u32::from(ownership.issuance_index) == issuance.universe_index => Some(i64::from(ownership.number_of_units)),
+            _ => None
+        }).sum();
+
+        Self::from_issuance(&issuance.asset).map(|summary| Self { total_units: Some(total_units), ..summary })
+    }
+}
+
+#[test]
+fn test_request_assets() {
+    use qubic_types::traits::ToBytes;
+
+    let name = AssetName::from_str("CFB").unwrap();
+    let request = RequestAssets::ownerships_of(QubicId([7; 32]), name).to_bytes();
+
+    assert_eq!(request.len(), 112);
+    assert_eq!(request[..4], [1, 0, 0b11000, 0]);
+    assert_eq!(request[8..40], [7; 32]);
+    assert_eq!(request[40..48], *b"CFB\0\0\0\0\0");
+    assert_eq!(core::mem::size_of::<RespondAssets>(), 56);
+
+    let issuance = RespondAssets {
+        asset: Asset { public_key: QubicId([7; 32]), asset_type: AssetType::Issuance(Issuance { name: name.into(), number_of_decimal_places: 0, unit_of_measurement: [0; 7] }) },
+        tick: 1,
+        universe_index: 4
+    };
+    let ownership = |issuance_index: u32, units: i64| RespondAssets {
+        asset: Asset {
+            public_key: QubicId([1; 32]),
+            asset_type: AssetType::Ownership(Ownership { padding: 0, managing_contract_index: 1.into(), issuance_index: issuance_index.into(), number_of_units: units.into() })
+        },
+        tick: 1,
+        universe_index: 9
+    };
+
+    let summary = AssetSummary::with_ownerships(&issuance, &[issuance, ownership(4, 600), ownership(5, 1), ownership(4, 400)]).unwrap();
+    assert_eq!((summary.issuer, summary.total_units), (QubicId([7; 32]), Some(1000)));
+}
+
+#[test]
+fn test_asset_name() {
+    let names = ["Q", "QX", "QXM", "TEST", "CFBTK", "QWALLE", "MLM1234"];
+
+    for name in names {
+        let long = AssetName::<8>::from_str(name).unwrap();
+        let short = AssetName::<7>::from_str(name).unwrap();
+
+        assert_eq!(long.to_string(), name);
+        assert_eq!(short.to_string(), name);
+        assert_eq!(long.0[7], 0);
+        assert_eq!(AssetName::<7>::from(long), short);
+        assert_eq!(AssetName::<8>::from(short), long);
+        assert_eq!(long.as_bytes7(), short.0);
+        assert_eq!(AssetName::<8>::from_u64(long.as_u64()), long);
+        assert_eq!(short.as_u64(), long.as_u64());
+    }
+
+    assert_eq!(AssetName::<8>::from_str("QX").unwrap().as_u64(), u64::from_le_bytes(*b"QX\0\0\0\0\0\0"));
+    assert_eq!(serde_json::to_value(AssetName::<7>::from_str("QX").unwrap()).unwrap(), serde_json::json!("QX"));
+    assert_eq!(serde_json::from_value::<AssetName>(serde_json::json!("QX")).unwrap(), AssetName::from_str("QX").unwrap());
+
+    for invalid in ["TOOLONG8", "", "Q\0X", "QÜ"] {
+        assert!(AssetName::<8>::from_str(invalid).is_err(), "{invalid:?} was accepted");
+        assert!(AssetName::<7>::from_str(invalid).is_err(), "{invalid:?} was accepted");
+    }
+    assert!(serde_json::from_value::<AssetName<7>>(serde_json::json!("TOOLONG8")).is_err());
+}
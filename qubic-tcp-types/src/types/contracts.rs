@@ -1,4 +1,4 @@
-use crate::MessageType;
+use crate::{utils::QubicRequest, MessageType};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
@@ -8,4 +8,28 @@ pub struct RequestContractFunction {
     pub input_size: u16
 }
 
-set_message_type!(RequestContractFunction, MessageType::RequestContractFunction);
\ No newline at end of file
+set_message_type!(RequestContractFunction, MessageType::RequestContractFunction);
+
+/// Request of a contract function followed by its input. Encoded through its layout like `RawCall`, the size of `T`
+/// has to be a multiple of 4 bytes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct ContractFunctionCall<T: Copy> {
+    pub function: RequestContractFunction,
+    pub input: T
+}
+
+impl<T: Copy> ContractFunctionCall<T> {
+    pub fn new(contract_index: u32, input_type: u16, input: T) -> Self {
+        Self {
+            function: RequestContractFunction { contract_index, input_type, input_size: core::mem::size_of::<T>() as u16 },
+            input
+        }
+    }
+}
+
+impl<T: Copy> QubicRequest for ContractFunctionCall<T> {
+    fn get_message_type() -> MessageType {
+        MessageType::RequestContractFunction
+    }
+}
@@ -0,0 +1,109 @@
+use core::convert::Infallible;
+
+use super::{send_to_many::SendToManyInput, transactions::TransactionData};
+
+/// Fee of every QX asset transfer
+pub const TRANSFER_FEE: u64 = 1_000_000;
+/// Fee of issuing an asset on QX
+pub const ISSUE_ASSET_FEE: u64 = 1_000_000_000;
+/// Amount burned by a work solution transaction
+pub const SUBMIT_WORK_BURN: u64 = 1_000_000;
+/// Fee of creating a poll on QUtil
+pub const QUTIL_POLL_CREATION_FEE: u64 = 10_000_000;
+/// Fee of every vote on a QUtil poll
+pub const QUTIL_VOTE_FEE: u64 = 100;
+
+/// Amounts a transaction has to carry for its operation, anything in `required_amount` beyond the fee and burns is forwarded by the contract
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct FeeBreakdown {
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
+    pub required_amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
+    pub contract_fee: u64,
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
+    pub burns: u64
+}
+
+impl FeeBreakdown {
+    pub fn fees(&self) -> u64 {
+        self.contract_fee + self.burns
+    }
+}
+
+pub trait FeeEstimator {
+    type Err;
+
+    fn estimate(&self, data: &TransactionData) -> Result<FeeBreakdown, Self::Err>;
+}
+
+/// Fees known up front, the SendToMany fee is set by the contract and has to be requested from a computor.
+/// Estimating never fails, the required amount of a SendToMany whose fee and amounts exceed `u64::MAX` saturates at
+/// `u64::MAX`, which no balance covers
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+pub struct FeeSchedule {
+    pub send_to_many_fee: u64
+}
+
+impl FeeEstimator for FeeSchedule {
+    type Err = Infallible;
+
+    fn estimate(&self, data: &TransactionData) -> Result<FeeBreakdown, Self::Err> {
+        let fee = |contract_fee| FeeBreakdown { required_amount: contract_fee, contract_fee, burns: 0 };
+
+        Ok(match data {
+            TransactionData::TransferAsset(_)
+            | TransactionData::TransferOwnershipAndPossession(_) => fee(TRANSFER_FEE),
+            TransactionData::IssueAsset(_) => fee(ISSUE_ASSET_FEE),
+            TransactionData::SubmitWork { .. } => FeeBreakdown { required_amount: SUBMIT_WORK_BURN, contract_fee: 0, burns: SUBMIT_WORK_BURN },
+            TransactionData::SendToMany(SendToManyInput { amounts, .. }) => FeeBreakdown {
+                required_amount: amounts.iter().fold(self.send_to_many_fee, |sum, amount| sum.saturating_add(*amount)),
+                contract_fee: self.send_to_many_fee,
+                burns: 0
+            },
+            TransactionData::IpoBid(_) | TransactionData::Contract(_) | TransactionData::Memo(_) | TransactionData::Unknown(_) | TransactionData::None => FeeBreakdown::default()
+        })
+    }
+}
+
+#[test]
+fn test_estimated_fees() {
+    use core::str::FromStr;
+    use qubic_types::{MiningSeed, Nonce, QubicId, QubicWallet};
+    use super::{assets::{AssetName, IssueAssetInput, TransferAssetInput, TransferAssetOwnershipAndPossessionInput}, transactions::TransactionBuilder, ContractIpoBid};
+
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let schedule = FeeSchedule { send_to_many_fee: 10 };
+
+    let mut send_to_many = SendToManyInput::default();
+    send_to_many.amounts[..3].copy_from_slice(&[100, 200, 300]);
+
+    // amounts of the transactions built by hand in the client
+    let cases = [
+        (TransactionData::TransferAsset(TransferAssetInput { destination: QubicId([2; 32]) }), TRANSFER_FEE),
+        (TransactionData::TransferOwnershipAndPossession(TransferAssetOwnershipAndPossessionInput { issuer: QubicId([2; 32]), possessor: QubicId([3; 32]), new_owner: QubicId([4; 32]), asset_name: AssetName::from_str("QX").unwrap(), number_of_units: 5 }), TRANSFER_FEE),
+        (TransactionData::IssueAsset(IssueAssetInput { name: AssetName::from_str("TEST").unwrap(), number_of_units: 1000, unit_of_measurement: 0, number_of_decimal_places: 0 }), ISSUE_ASSET_FEE),
+        (TransactionData::SubmitWork { seed: MiningSeed([1; 32]), nonce: Nonce([2; 32]) }, 1_000_000),
+        (TransactionData::SendToMany(send_to_many), 10 + 600),
+        (TransactionData::IpoBid(ContractIpoBid { price: 100, quantity: 2 }), 0),
+        (TransactionData::None, 0)
+    ];
+
+    for (data, amount) in cases {
+        assert_eq!(schedule.estimate(&data).unwrap().required_amount, amount);
+
+        let tx = TransactionBuilder::new().with_tx_data(data).unwrap().with_estimated_fees(&schedule).unwrap().with_signing_wallet(&wallet).build();
+
+        assert_eq!(tx.raw_transaction.amount, amount);
+    }
+
+    // plain transfers keep their amount
+    let tx = TransactionBuilder::new().with_amount(500).with_estimated_fees(&schedule).unwrap().build();
+    assert_eq!(tx.raw_transaction.amount, 500);
+
+    assert_eq!(schedule.estimate(&TransactionData::SendToMany(send_to_many)).unwrap(), FeeBreakdown { required_amount: 610, contract_fee: 10, burns: 0 });
+
+    // amounts beyond u64::MAX saturate instead of overflowing
+    send_to_many.amounts[..2].copy_from_slice(&[u64::MAX, 1]);
+    assert_eq!(schedule.estimate(&TransactionData::SendToMany(send_to_many)).unwrap().required_amount, u64::MAX);
+}
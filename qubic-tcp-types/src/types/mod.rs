@@ -1,3 +1,9 @@
+//! Wire structs of the computor protocol
+//!
+//! Every integer on the wire is little-endian. Most structs are `repr(C)` and encoded through their in-memory layout
+//! by the `ToBytes`/`FromBytes` impls of qubic-types, so the crate refuses to build for big-endian targets. Encodings
+//! written by hand, like the transaction header of `TransactionWithData`, use `to_le_bytes`/`from_le_bytes`.
+
 #[macro_use]
 mod macros;
 pub mod transactions;
@@ -8,13 +14,20 @@ pub mod assets;
 pub mod special_commands;
 pub mod qlogging;
 pub mod send_to_many;
+pub mod qutil;
 pub mod contracts;
+pub mod fees;
+pub mod simulation;
+pub mod activity;
 
 use core::net::Ipv4Addr;
-use qubic_types::{traits::ToBytes, MiningSeed, Nonce, QubicId, Signature};
+use alloc::boxed::Box;
+use qubic_types::{traits::{GetSigner, ToBytes}, MiningSeed, Nonce, QubicId, Signature};
+#[cfg(all(feature = "std", not(feature = "wasm")))]
+use qubic_types::{errors::U24OverflowError, U24};
 use time::QubicTime;
 
-use crate::{consts::SPECTRUM_DEPTH, utils::QubicRequest, Header, MessageType};
+use crate::{consts::{ARBITRATOR, NUMBER_OF_COMPUTORS, SPECTRUM_DEPTH}, utils::QubicRequest, Header, MessageType};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
@@ -71,12 +84,24 @@ pub struct RespondedEntity {
 
 set_message_type!(RespondedEntity, MessageType::RespondEntity);
 
+const _: () = assert!(core::mem::size_of::<RespondedEntity>() == core::mem::size_of::<Entity>() + 8 + SPECTRUM_DEPTH * core::mem::size_of::<QubicId>());
+
+impl RespondedEntity {
+    /// drops the tick and spectrum index the entity is valid for
+    pub fn entity_only(&self) -> Entity {
+        self.entity
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct Entity {
     pub public_key: QubicId,
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
     pub incoming_amount: u64,
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
     pub outgoing_amount: u64,
     pub number_of_incoming_transfers: u32,
     pub number_of_outgoing_transfers: u32,
@@ -97,15 +122,34 @@ pub struct RequestComputors;
 set_message_type!(RequestComputors, MessageType::RequestComputors);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Computors {
     pub epoch: u16,
-    pub public_key: [QubicId; 676],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_big_array"))]
+    pub public_key: [QubicId; NUMBER_OF_COMPUTORS],
     pub signature: Signature
 }
 
 set_message_type!(Computors, MessageType::BroadcastComputors);
 
+/// computor lists are signed by the arbitrator, `VerifySignature::verify` checks a list against it
+impl GetSigner for Computors {
+    fn get_signer(&self) -> &QubicId {
+        &ARBITRATOR
+    }
+}
+
+const _: () = assert!(core::mem::size_of::<Computors>() == 2 + NUMBER_OF_COMPUTORS * core::mem::size_of::<QubicId>() + core::mem::size_of::<Signature>());
+
+impl Computors {
+    /// zeroed computor list, allocated on the heap without passing the stack
+    pub fn new_boxed() -> Box<Self> {
+        // every field is an integer or a byte array, all zeroes is a valid value
+        unsafe { Box::<Self>::new_zeroed().assume_init() }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
@@ -120,14 +164,25 @@ set_message_type!(RequestContractIpo, MessageType::RequestContractIPO);
 pub struct ContractIpo {
     pub contract_index: u32,
     pub tick: u32,
-    pub public_keys: [QubicId; 676],
-    pub prices: [u64; 676]
+    pub public_keys: [QubicId; NUMBER_OF_COMPUTORS],
+    pub prices: [u64; NUMBER_OF_COMPUTORS]
 }
 
 set_message_type!(ContractIpo, MessageType::RespondContractIPO);
 
+const _: () = assert!(core::mem::size_of::<ContractIpo>() == 8 + NUMBER_OF_COMPUTORS * (core::mem::size_of::<QubicId>() + 8));
+
+impl ContractIpo {
+    /// zeroed IPO, allocated on the heap without passing the stack
+    pub fn new_boxed() -> Box<Self> {
+        // every field is an integer or a byte array, all zeroes is a valid value
+        unsafe { Box::<Self>::new_zeroed().assume_init() }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct ContractIpoBid {
     pub price: u64,
@@ -135,6 +190,7 @@ pub struct ContractIpoBid {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct ExchangePublicPeers {
     pub peers: [Ipv4Addr; 4]
@@ -160,24 +216,111 @@ pub struct Packet<T> {
 
 #[cfg(all(feature = "std", not(feature = "wasm")))]
 impl<T: ToBytes + QubicRequest> Packet<T> {
-    pub fn new(data: T, randomize_dejavu: bool) -> Packet<T> {
-        let data_size = data.to_bytes().len();
+    /// fails if the packet exceeds the maximum size of a header
+    pub fn new(data: T, randomize_dejavu: bool) -> Result<Packet<T>, U24OverflowError> {
+        let size = U24::try_from(core::mem::size_of::<Header>() + data.encoded_len())?;
 
-        Self {
-            header: Header::new(core::mem::size_of::<Header>() + data_size, T::get_message_type(), randomize_dejavu),
+        Ok(Self {
+            header: Header::new(size, T::get_message_type(), randomize_dejavu),
             data
-        }
+        })
+    }
+}
+
+#[cfg(all(feature = "std", not(feature = "wasm")))]
+impl<'a, T: ToBytes + QubicRequest> Packet<ByRef<'a, T>> {
+    /// packet encoding `data` without taking ownership, e.g. to broadcast a transaction to many peers
+    pub fn from_ref(data: &'a T, randomize_dejavu: bool) -> Result<Self, U24OverflowError> {
+        Packet::new(ByRef(data), randomize_dejavu)
+    }
+}
+
+/// Encodes the referenced value. References are `Copy` and would be encoded as the pointer itself by the
+/// blanket `ToBytes` impl, so the wrapper is not `Copy`
+#[derive(Debug)]
+pub struct ByRef<'a, T>(pub &'a T);
+
+impl<T> Clone for ByRef<'_, T> {
+    fn clone(&self) -> Self {
+        Self(self.0)
+    }
+}
+
+impl<T: ToBytes> ToBytes for ByRef<'_, T> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.0.to_bytes()
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.0.write_to(buf);
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.0.encoded_len()
+    }
+}
+
+impl<T: QubicRequest> QubicRequest for ByRef<'_, T> {
+    fn get_message_type() -> MessageType {
+        T::get_message_type()
+    }
+}
+
+/// Encoding of a `T` made once, sent as the `T` it was made of, e.g. to hash a transaction and send the same bytes
+#[derive(Debug, Clone)]
+pub struct Encoded<T> {
+    bytes: Vec<u8>,
+    encoded: core::marker::PhantomData<T>
+}
+
+impl<T: ToBytes> Encoded<T> {
+    pub fn new(value: &T) -> Self {
+        Self { bytes: value.to_bytes(), encoded: core::marker::PhantomData }
+    }
+}
+
+impl<T> Encoded<T> {
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.bytes
+    }
+}
+
+impl<T> ToBytes for Encoded<T> {
+    fn to_bytes(&self) -> Vec<u8> {
+        self.bytes.clone()
+    }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.bytes);
+    }
+
+    fn encoded_len(&self) -> usize {
+        self.bytes.len()
+    }
+}
+
+impl<T: QubicRequest> QubicRequest for Encoded<T> {
+    fn get_message_type() -> MessageType {
+        T::get_message_type()
     }
 }
 
 impl<T: ToBytes> ToBytes for Packet<T> {
     fn to_bytes(&self) -> Vec<u8> {
-        let mut buffer = self.header.to_bytes();
+        let mut buffer = Vec::with_capacity(self.encoded_len());
+        self.write_to(&mut buffer);
 
-        buffer.extend(self.data.to_bytes());
-        
         buffer
     }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.header.write_to(buf);
+        self.data.write_to(buf);
+    }
+
+    fn encoded_len(&self) -> usize {
+        core::mem::size_of::<Header>() + self.data.encoded_len()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -187,6 +330,8 @@ pub struct RequestSystemInfo;
 set_message_type!(RequestSystemInfo, MessageType::RequestSystemInfo);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C, packed)]
 pub struct SystemInfo {
     pub version: i16,
@@ -207,4 +352,132 @@ pub struct SystemInfo {
     pub current_entity_balance_dust_threshold: u64
 }
 
-set_message_type!(SystemInfo, MessageType::RespondSystemInfo);
\ No newline at end of file
+set_message_type!(SystemInfo, MessageType::RespondSystemInfo);
+
+#[cfg(all(feature = "std", not(feature = "wasm")))]
+#[test]
+fn test_packet_from_ref() {
+    use transactions::{RawTransaction, TransactionData, TransactionWithData};
+
+    let tx = TransactionWithData {
+        raw_transaction: RawTransaction { amount: 100, input_size: 3, ..Default::default() },
+        data: TransactionData::Unknown(vec![1, 2, 3]),
+        signature: Signature([4; 64])
+    };
+
+    let (borrowed, owned) = (Packet::from_ref(&tx, false).unwrap(), Packet::new(tx.clone(), false).unwrap());
+
+    assert_eq!(borrowed.header.get_size(), owned.header.get_size());
+    assert_eq!(borrowed.header.message_type, MessageType::BroadcastTransaction);
+    assert_eq!(borrowed.to_bytes()[8..], owned.to_bytes()[8..]);
+
+    // an encoding made up front is sent as is
+    let encoded = Encoded::new(&tx);
+    let packet = Packet::from_ref(&encoded, false).unwrap();
+
+    assert_eq!(encoded.as_bytes(), tx.to_bytes());
+    assert_eq!(packet.header.get_size(), owned.header.get_size());
+    assert_eq!(packet.header.message_type, MessageType::BroadcastTransaction);
+    assert_eq!(packet.to_bytes()[8..], owned.to_bytes()[8..]);
+}
+
+#[cfg(all(feature = "std", not(feature = "wasm")))]
+#[test]
+fn test_packet_size() {
+    use transactions::{RawTransaction, TransactionData, TransactionWithData};
+
+    let transaction = |packet_size: usize| TransactionWithData {
+        raw_transaction: RawTransaction::default(),
+        data: TransactionData::Unknown(vec![0; packet_size - core::mem::size_of::<Header>() - core::mem::size_of::<RawTransaction>() - core::mem::size_of::<Signature>()]),
+        signature: Signature::default()
+    };
+
+    let packet = Packet::new(transaction(0xFF_FFFF), false).unwrap();
+    assert_eq!(packet.header.get_size(), 0xFF_FFFF);
+    assert_eq!(packet.header.size.to_le_bytes(), [0xFF; 3]);
+
+    // oversized payloads are rejected instead of truncated
+    assert_eq!(Packet::new(transaction(0x100_0000), false).unwrap_err(), U24OverflowError { value: 0x100_0000 });
+}
+
+/// fixtures of the little-endian wire format, the fields have to decode the same on every target
+#[test]
+fn test_little_endian_fixtures() {
+    use qubic_types::traits::FromBytes;
+    use crate::consts::NUMBER_OF_TRANSACTION_PER_TICK;
+    use ticks::TickData;
+    use time::QubicTime;
+    use transactions::{RawTransaction, TransactionData, TransactionWithData};
+
+    let header = [0x28, 0x00, 0x01, 27, 0x78, 0x56, 0x34, 0x12];
+    let decoded = Header::from_bytes(&header).unwrap();
+    assert_eq!((decoded.get_size(), decoded.message_type, decoded.dejavu), (0x01_0028, MessageType::RequestCurrentTickInfo, 0x1234_5678));
+    assert_eq!(decoded.to_bytes(), header);
+
+    let transaction = [
+        [1; 32].as_slice(), &[2; 32],
+        &[8, 7, 6, 5, 4, 3, 2, 1], // amount
+        &[57, 48, 0, 0], // tick 12345
+        &[0, 0], &[0, 0], // input type and size
+        &[9; 64]
+    ].concat();
+    let raw_transaction = RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 0x0102_0304_0506_0708, tick: 12_345, input_type: 0, input_size: 0 };
+    assert_eq!(TransactionWithData::from_bytes(&transaction).unwrap(), TransactionWithData { raw_transaction, data: TransactionData::None, signature: Signature([9; 64]) });
+    assert_eq!(TransactionWithData::from_bytes(&transaction).unwrap().to_bytes(), transaction);
+    assert_eq!(RawTransaction::from_bytes(&transaction[..80]).unwrap(), raw_transaction);
+
+    let entity = [
+        [3; 32].as_slice(),
+        &[220, 5, 0, 0, 0, 0, 0, 0], // 1500 incoming
+        &[244, 1, 0, 0, 0, 0, 0, 0], // 500 outgoing
+        &[3, 0, 0, 0], &[1, 0, 0, 0],
+        &[246, 26, 183, 0], // tick 11999990
+        &[24, 23, 183, 0] // tick 11999000
+    ].concat();
+    let expected = Entity { public_key: QubicId([3; 32]), incoming_amount: 1_500, outgoing_amount: 500, number_of_incoming_transfers: 3, number_of_outgoing_transfers: 1, latest_incoming_transfer_tick: 11_999_990, latest_outgoing_transfer_tick: 11_999_000 };
+    assert_eq!(Entity::from_bytes(&entity).unwrap(), expected);
+    assert_eq!(expected.to_bytes(), entity);
+
+    let system_info = [
+        [0xfe, 0xff].as_slice(), // version -2
+        &[100, 0],
+        &[0, 27, 183, 0], // tick 12000000
+        &[240, 243, 182, 0], // initial tick 11990000
+        &[5, 27, 183, 0], // latest created tick 12000005
+        &[244, 1, 5, 4, 3, 2, 1, 25], // 2025-01-02 03:04:05.500
+        &[164, 2, 0, 0], &[104, 16, 0, 0], // 676 entities, 4200 transactions
+        &[7; 32],
+        &[57, 48, 0, 0],
+        &[8, 7, 6, 5, 4, 3, 2, 1],
+        &[64, 66, 15, 0, 0, 0, 0, 0] // dust threshold 1000000
+    ].concat();
+    let expected = SystemInfo {
+        version: -2,
+        epoch: 100,
+        tick: 12_000_000,
+        initial_tick: 11_990_000,
+        latest_created_tick: 12_000_005,
+        time: QubicTime { milliseconds: 500, second: 5, minute: 4, hour: 3, day: 2, month: 1, year: 25 },
+        number_of_entities: 676,
+        number_of_transactions: 4_200,
+        random_mining_seed: [7; 32],
+        solution_threshold: 12_345,
+        total_spectrum_amount: 0x0102_0304_0506_0708,
+        current_entity_balance_dust_threshold: 1_000_000
+    };
+    assert_eq!(SystemInfo::from_bytes(&system_info).unwrap(), expected);
+    assert_eq!(expected.to_bytes(), system_info);
+
+    // computor 5 of epoch 100 at tick 12345 with a single transaction and a fee of contract 1
+    let contract_fees = 48 + NUMBER_OF_TRANSACTION_PER_TICK * 32;
+    let mut tick_data = vec![0; core::mem::size_of::<TickData>()];
+    tick_data[..8].copy_from_slice(&[5, 0, 100, 0, 57, 48, 0, 0]);
+    tick_data[48..80].copy_from_slice(&[6; 32]);
+    tick_data[contract_fees + 8..contract_fees + 16].copy_from_slice(&[8, 7, 6, 5, 4, 3, 2, 1]);
+
+    let decoded = TickData::from_bytes(&tick_data).unwrap();
+    assert_eq!((decoded.computor_index, decoded.epoch, decoded.tick), (5, 100, 12_345));
+    assert_eq!((decoded.transaction_digest[0].0, decoded.transaction_digest[1].0), ([6; 32], [0; 32]));
+    assert_eq!((decoded.contract_fee(0), decoded.contract_fee(1)), (Some(0), Some(0x0102_0304_0506_0708)));
+    assert_eq!(decoded.to_bytes(), tick_data);
+}
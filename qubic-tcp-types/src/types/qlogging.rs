@@ -23,7 +23,6 @@ impl QubicLogType {
             Self::AssetIssuance => Some(55),
             Self::AssetOwnershipChange => Some(119),
             Self::AssetPossessionChange => Some(119),
-            Self::ContractErrorMessage => Some(4),
             _ => None
         }
     }
@@ -43,9 +42,9 @@ pub struct RespondLog {
 
 use core::fmt::{Debug, Display};
 
-use qubic_types::{errors::ByteEncodingError, traits::FromBytes, QubicId};
+use qubic_types::{errors::ByteEncodingError, traits::FromBytes, QubicId, U24};
 
-use crate::{types::assets::AssetName, MessageType};
+use crate::{types::{assets::AssetName, send_to_many::SEND_TO_MANY_CONTRACT_INDEX}, MessageType};
 
 
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
@@ -61,13 +60,13 @@ pub struct LogHeader {
 
     pub epoch: u8,
     pub tick: u32,
-    pub size: [u8; 3],
+    pub size: U24,
     pub log_type: QubicLogType
 }
 
 impl LogHeader {
     pub fn get_size(&self) -> usize {
-        self.size[0] as usize | (self.size[1] as usize) << 8 | (self.size[2] as usize) << 16
+        self.size.get()
     }
 }
 
@@ -83,7 +82,7 @@ impl Display for LogHeader {
     }
 }
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, PartialEq, Eq)]
 #[repr(C)]
 pub struct QuTransferLog {
     pub from: QubicId,
@@ -126,7 +125,7 @@ pub struct AssetIssuanceLog {
 
 impl Display for AssetIssuanceLog {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        f.write_fmt(format_args!("New Asset {} issued by {} | Shares: {}", self.from, self.name.to_string(), self.number_of_shares as f32 / self.number_of_decimal_places as f32))
+        f.write_fmt(format_args!("New Asset {} issued by {} | Shares: {}", self.from, self.name, self.number_of_shares as f32 / self.number_of_decimal_places as f32))
     }
 }
 
@@ -155,12 +154,118 @@ pub struct AssetPossessionChangeLog {
 }
 
 
+pub const QX_CONTRACT_INDEX: u32 = 1;
+pub const QUOTTERY_CONTRACT_INDEX: u32 = 2;
+pub const QUTIL_CONTRACT_INDEX: u32 = SEND_TO_MANY_CONTRACT_INDEX;
+
+/// Offset of `logtype` in QUtil's `QUtilLogger` (core `src/contracts/QUtil.h`): contract index, padding, source and
+/// destination ids and the amount precede it
+const QUTIL_LOG_TYPE_OFFSET: usize = 4 + 4 + 32 + 32 + 8;
+
+/// `QUtilLogInfo` codes of core `src/contracts/QUtil.h` as `(contract index, code, description)`. Core only fixes the contract
+/// index at the start of a contract message, so codes are only known for contracts whose logger layout is listed here
+const CONTRACT_MESSAGES: &[(u32, u32, &str)] = &[
+    (QUTIL_CONTRACT_INDEX, 0, "success"),
+    (QUTIL_CONTRACT_INDEX, 1, "invalid input"),
+    (QUTIL_CONTRACT_INDEX, 2, "triggered"),
+    (QUTIL_CONTRACT_INDEX, 3, "funds sent"),
+];
+
+/// human readable meaning of the message `code` of a contract, `None` for contracts and codes this crate doesn't know
+pub fn contract_message(contract_index: u32, code: u32) -> Option<&'static str> {
+    CONTRACT_MESSAGES.iter().find(|(index, c, _)| *index == contract_index && *c == code).map(|(_, _, description)| *description)
+}
+
+/// offset of the code in the messages of `contract_index`, `None` for contracts whose message layout is unknown
+fn contract_code_offset(contract_index: u32) -> Option<usize> {
+    match contract_index {
+        QUTIL_CONTRACT_INDEX => Some(QUTIL_LOG_TYPE_OFFSET),
+        _ => None
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ContractMessageKind {
+    Error,
+    Warning,
+    Information,
+    Debug
+}
+
+impl ContractMessageKind {
+    pub fn from_log_type(log_type: QubicLogType) -> Option<Self> {
+        match log_type {
+            QubicLogType::ContractErrorMessage => Some(Self::Error),
+            QubicLogType::ContractWarningMessage => Some(Self::Warning),
+            QubicLogType::ContractInformationMessage => Some(Self::Information),
+            QubicLogType::ContractDebugMessage => Some(Self::Debug),
+            _ => None
+        }
+    }
+}
+
+/// Message logged by a contract, `raw` is the contract specific data following the contract index. `code` is only read
+/// for contracts with a known message layout and messages long enough to carry it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractMessageLog {
+    pub contract_index: u32,
+    pub kind: ContractMessageKind,
+    pub code: Option<u32>,
+    pub raw: Vec<u8>
+}
+
+impl ContractMessageLog {
+    pub fn parse(kind: ContractMessageKind, data: &[u8]) -> Result<Self, ByteEncodingError> {
+        if data.len() < 4 {
+            return Err(ByteEncodingError::InvalidMinimumDataLength { expected_min: 4, found: data.len() })
+        }
+
+        let contract_index = u32::from_bytes(&data[..4])?;
+        let code = match contract_code_offset(contract_index).and_then(|offset| data.get(offset..offset + 4)) {
+            Some(code) => Some(u32::from_bytes(code)?),
+            None => None
+        };
+
+        Ok(Self {
+            contract_index,
+            kind,
+            code,
+            raw: data[4..].to_vec()
+        })
+    }
+
+    pub fn description(&self) -> Option<&'static str> {
+        contract_message(self.contract_index, self.code?)
+    }
+}
+
+impl Display for ContractMessageLog {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_fmt(format_args!("Contract {} {:?}", self.contract_index, self.kind))?;
+
+        if let Some(code) = self.code {
+            f.write_fmt(format_args!(" {code}"))?;
+        }
+
+        if let Some(description) = self.description() {
+            f.write_fmt(format_args!(" ({description})"))?;
+        }
+
+        if !self.raw.is_empty() {
+            f.write_fmt(format_args!(" | {} bytes of data", self.raw.len()))?;
+        }
+
+        Ok(())
+    }
+}
+
 #[derive(Debug, Clone, Default)]
 pub enum LogMessages {
     QuTransferLog(QuTransferLog),
     AssetIssuanceLog(AssetIssuanceLog),
     AssetOwnershipChangeLog(AssetOwnershipChangeLog),
     AssetPossessionChangeLog(AssetPossessionChangeLog),
+    ContractMessage(ContractMessageLog),
     String(String),
     #[default]
     None
@@ -173,6 +278,7 @@ impl Display for LogMessages {
             Self::AssetIssuanceLog(log) => f.write_fmt(format_args!("{log}")),
             Self::AssetOwnershipChangeLog(log) => f.write_fmt(format_args!("{log:?}")),
             Self::AssetPossessionChangeLog(log) => f.write_fmt(format_args!("{log:?}")),
+            Self::ContractMessage(log) => f.write_fmt(format_args!("{log}")),
             Self::String(log) => f.write_fmt(format_args!("{log}")),
             Self::None => f.write_str("")
         }
@@ -197,12 +303,10 @@ impl FromBytes for QubicLog {
             return Ok(Self::default())
         }
 
-        let header = LogHeader::from_bytes(&data[..core::mem::size_of::<LogHeader>()])?;
-
-        dbg!(header.get_size());
-        dbg!(header.log_type);
+        let header = LogHeader::from_bytes(data.get(..core::mem::size_of::<LogHeader>()).ok_or(ByteEncodingError::InvalidMinimumDataLength { expected_min: core::mem::size_of::<LogHeader>(), found: data.len() })?)?;
 
-        let cut_data = &data[core::mem::size_of::<LogHeader>()..header.get_size() + core::mem::size_of::<LogHeader>()];
+        let end = header.get_size() + core::mem::size_of::<LogHeader>();
+        let cut_data = data.get(core::mem::size_of::<LogHeader>()..end).ok_or(ByteEncodingError::InvalidMinimumDataLength { expected_min: end, found: data.len() })?;
         let message = match header.log_type {
             QubicLogType::QuTransfer => {
                 let log = QuTransferLog::from_bytes(&cut_data)?;
@@ -223,7 +327,12 @@ impl FromBytes for QubicLog {
                 let log = AssetPossessionChangeLog::from_bytes(&cut_data)?;
 
                 LogMessages::AssetPossessionChangeLog(log)
-            }
+            },
+            QubicLogType::ContractErrorMessage | QubicLogType::ContractWarningMessage | QubicLogType::ContractInformationMessage | QubicLogType::ContractDebugMessage => {
+                let kind = ContractMessageKind::from_log_type(header.log_type).expect("contract message log type");
+
+                LogMessages::ContractMessage(ContractMessageLog::parse(kind, cut_data)?)
+            },
             _ => LogMessages::String(String::new())
         };
 
@@ -232,4 +341,258 @@ impl FromBytes for QubicLog {
             message
         })
     }
-}
\ No newline at end of file
+}
+
+/// Every log of a `RespondLog` message, the node sends the logs emitted since the last request
+#[derive(Debug, Clone, Default)]
+pub struct QubicLogs(pub Vec<QubicLog>);
+
+impl QubicLogs {
+    /// QU transfers of `tick` in the order they were executed
+    pub fn transfers(&self, tick: u32) -> impl Iterator<Item = &QuTransferLog> {
+        self.0.iter().filter_map(move |log| match &log.message {
+            LogMessages::QuTransferLog(transfer) if log.header.tick == tick => Some(transfer),
+            _ => None
+        })
+    }
+}
+
+impl FromBytes for QubicLogs {
+    fn from_bytes(mut data: &[u8]) -> Result<Self, ByteEncodingError> {
+        let mut logs = Vec::new();
+
+        while !data.is_empty() {
+            let log = QubicLog::from_bytes(data)?;
+            data = &data[core::mem::size_of::<LogHeader>() + log.header.get_size()..];
+            logs.push(log);
+        }
+
+        Ok(Self(logs))
+    }
+}
+
+/// Failure of `LogParser` to read the next log
+#[cfg(feature = "std")]
+#[derive(Debug)]
+pub enum LogParseError {
+    Io(std::io::Error),
+    /// the source ended within the log starting at byte `offset`
+    Truncated { offset: u64, expected: usize, found: usize },
+    /// the log starting at byte `offset` is complete but its message does not decode
+    Decode { offset: u64, error: ByteEncodingError }
+}
+
+#[cfg(feature = "std")]
+impl Display for LogParseError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Io(e) => write!(f, "I/O error: {e}"),
+            Self::Truncated { offset, expected, found } => write!(f, "Log at byte {offset} is truncated ({found} of {expected} bytes)"),
+            Self::Decode { offset, error } => write!(f, "Log at byte {offset} does not decode: {error}")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for LogParseError {}
+
+/// Reads the logs of `RespondLog` messages one at a time from `reader`, e.g. a file written by
+/// `Qu::request_log_to_writer`, without loading all of them into memory.
+///
+/// A log whose message does not decode is returned as `LogParseError::Decode` and parsing continues with the next
+/// log. A source ending within a log yields `LogParseError::Truncated` and ends the iteration
+#[cfg(feature = "std")]
+pub struct LogParser<R> {
+    reader: R,
+    offset: u64,
+    done: bool
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> LogParser<R> {
+    pub fn new(reader: R) -> Self {
+        Self { reader, offset: 0, done: false }
+    }
+
+    /// bytes of the source consumed so far
+    pub fn offset(&self) -> u64 {
+        self.offset
+    }
+
+    pub fn into_inner(self) -> R {
+        self.reader
+    }
+
+    /// reads until `buf` is full or the source ends, returns the number of bytes read
+    fn fill(&mut self, buf: &mut [u8]) -> std::io::Result<usize> {
+        let mut read = 0;
+
+        while read < buf.len() {
+            match self.reader.read(&mut buf[read..]) {
+                Ok(0) => break,
+                Ok(n) => read += n,
+                Err(e) if e.kind() == std::io::ErrorKind::Interrupted => continue,
+                Err(e) => return Err(e)
+            }
+        }
+
+        Ok(read)
+    }
+
+    fn next_log(&mut self) -> Result<Option<QubicLog>, LogParseError> {
+        const HEADER_SIZE: usize = core::mem::size_of::<LogHeader>();
+
+        let offset = self.offset;
+        let mut data = vec![0; HEADER_SIZE];
+
+        match self.fill(&mut data).map_err(LogParseError::Io)? {
+            0 => return Ok(None),
+            HEADER_SIZE => (),
+            found => return Err(LogParseError::Truncated { offset, expected: HEADER_SIZE, found })
+        }
+
+        self.offset += HEADER_SIZE as u64;
+
+        let header = LogHeader::from_bytes(&data).map_err(|error| LogParseError::Decode { offset, error })?;
+        let expected = HEADER_SIZE + header.get_size();
+        data.resize(expected, 0);
+
+        let read = self.fill(&mut data[HEADER_SIZE..]).map_err(LogParseError::Io)?;
+        self.offset += read as u64;
+
+        let found = HEADER_SIZE + read;
+
+        if found < expected {
+            return Err(LogParseError::Truncated { offset, expected, found })
+        }
+
+        QubicLog::from_bytes(&data).map(Some).map_err(|error| LogParseError::Decode { offset, error })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<R: std::io::Read> Iterator for LogParser<R> {
+    type Item = Result<QubicLog, LogParseError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.done {
+            return None
+        }
+
+        let next = self.next_log();
+        self.done = !matches!(next, Ok(Some(_)) | Err(LogParseError::Decode { .. }));
+
+        next.transpose()
+    }
+}
+
+#[test]
+fn test_parse_logs() {
+    use qubic_types::traits::ToBytes;
+
+    let log = |tick: u32, log_type: QubicLogType, message: &[u8]| {
+        let header = LogHeader { tick, size: U24::try_from(message.len()).unwrap(), log_type, ..Default::default() };
+
+        [header.to_bytes(), message.to_vec()].concat()
+    };
+    let transfer = |from: u8, to: u8, amount: u64| [[from; 32].as_slice(), &[to; 32], &amount.to_le_bytes()].concat();
+
+    let data = [
+        log(10, QubicLogType::QuTransfer, &transfer(1, 2, 100)),
+        log(10, QubicLogType::ContractErrorMessage, &[0; 8]),
+        log(11, QubicLogType::QuTransfer, &transfer(2, 3, 50)),
+        log(10, QubicLogType::QuTransfer, &transfer(1, 2, 100))
+    ].concat();
+
+    let logs = QubicLogs::from_bytes(&data).unwrap();
+    assert_eq!(logs.0.len(), 4);
+
+    let expected = QuTransferLog { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, transfer_id: None };
+    assert_eq!(logs.transfers(10).collect::<Vec<_>>(), [&expected, &expected]);
+    assert_eq!(logs.transfers(11).map(|t| t.amount).collect::<Vec<_>>(), [50]);
+    assert_eq!(logs.transfers(12).count(), 0);
+
+    // a truncated message is rejected instead of read out of bounds
+    assert!(QubicLogs::from_bytes(&data[..data.len() - 1]).is_err());
+    assert_eq!(QubicLogs::from_bytes(&[]).unwrap().0.len(), 0);
+}
+
+#[test]
+fn test_parse_contract_messages() {
+    use qubic_types::traits::ToBytes;
+
+    let log = |log_type: QubicLogType, contract_index: u32, raw: &[u8]| {
+        let message = [contract_index.to_le_bytes().as_slice(), raw].concat();
+        let header = LogHeader { tick: 10, size: U24::try_from(message.len()).unwrap(), log_type, ..Default::default() };
+
+        QubicLog::from_bytes(&[header.to_bytes(), message].concat()).map(|log| log.message)
+    };
+    let message = |kind, contract_index, code, raw: &[u8]| ContractMessageLog { contract_index, kind, code, raw: raw.to_vec() };
+    // QUtilLogger without the contract index: padding, source, destination, amount and logtype
+    let qutil = |code: u32| [[0; 4].as_slice(), &[1; 32], &[2; 32], &100i64.to_le_bytes(), &code.to_le_bytes()].concat();
+
+    let LogMessages::ContractMessage(error) = log(QubicLogType::ContractErrorMessage, QUTIL_CONTRACT_INDEX, &qutil(1)).unwrap() else { panic!("not a contract message") };
+    assert_eq!(error, message(ContractMessageKind::Error, QUTIL_CONTRACT_INDEX, Some(1), &qutil(1)));
+    assert_eq!(error.description(), Some("invalid input"));
+    assert_eq!(error.to_string(), "Contract 4 Error 1 (invalid input) | 80 bytes of data");
+
+    let LogMessages::ContractMessage(information) = log(QubicLogType::ContractInformationMessage, QUTIL_CONTRACT_INDEX, &qutil(3)).unwrap() else { panic!("not a contract message") };
+    assert_eq!(information.description(), Some("funds sent"));
+
+    // the code of a QUtil message is read at the logtype of QUtilLogger, not after the contract index
+    let LogMessages::ContractMessage(short) = log(QubicLogType::ContractErrorMessage, QUTIL_CONTRACT_INDEX, &[1, 0, 0, 0]).unwrap() else { panic!("not a contract message") };
+    assert_eq!(short, message(ContractMessageKind::Error, QUTIL_CONTRACT_INDEX, None, &[1, 0, 0, 0]));
+
+    // messages of contracts with an unknown layout are kept without a code
+    let LogMessages::ContractMessage(warning) = log(QubicLogType::ContractWarningMessage, QX_CONTRACT_INDEX, &[7; 3]).unwrap() else { panic!("not a contract message") };
+    assert_eq!(warning, message(ContractMessageKind::Warning, QX_CONTRACT_INDEX, None, &[7; 3]));
+    assert_eq!(warning.description(), None);
+    assert_eq!(warning.to_string(), "Contract 1 Warning | 3 bytes of data");
+
+    let LogMessages::ContractMessage(debug) = log(QubicLogType::ContractDebugMessage, 99, &[]).unwrap() else { panic!("not a contract message") };
+    assert_eq!(debug, message(ContractMessageKind::Debug, 99, None, &[]));
+    assert_eq!(debug.to_string(), "Contract 99 Debug");
+
+    let header = LogHeader { size: U24::from(3u16), log_type: QubicLogType::ContractErrorMessage, ..Default::default() };
+    assert!(QubicLog::from_bytes(&[header.to_bytes(), vec![0; 3]].concat()).is_err());
+}
+
+/// `fixtures/logs.bin` holds five logs of different types followed by a transfer truncated after 20 bytes
+#[cfg(feature = "std")]
+#[test]
+fn test_log_parser() {
+    const FIXTURE: &[u8] = include_bytes!("../../fixtures/logs.bin");
+
+    let mut parser = LogParser::new(FIXTURE);
+    let logs = parser.by_ref().take(5).collect::<Result<Vec<_>, _>>().unwrap();
+
+    assert_eq!(logs.iter().map(|log| (log.header.tick, log.header.log_type)).collect::<Vec<_>>(), [
+        (14_000_000, QubicLogType::QuTransfer),
+        (14_000_000, QubicLogType::ContractErrorMessage),
+        (14_000_001, QubicLogType::ContractInformationMessage),
+        (14_000_001, QubicLogType::QuTransfer),
+        (14_000_002, QubicLogType::CustomMessage)
+    ]);
+    assert_eq!(logs[0].to_string(), format!("[2024/05/01 12:00:00 EP110@14000000 QuTransfer] Transfer {} -> {} | Amount: 1000 QUs | Transfer ID: None", QubicId([1; 32]), QubicId([2; 32])));
+    // the QUtil message is too short for QUtilLogger, the QX message has no known layout
+    assert!(matches!(&logs[1].message, LogMessages::ContractMessage(message) if message.contract_index == QUTIL_CONTRACT_INDEX && message.code.is_none() && message.raw == [2, 0, 0, 0]));
+    assert!(matches!(&logs[2].message, LogMessages::ContractMessage(message) if message.contract_index == QX_CONTRACT_INDEX && message.code.is_none() && message.raw == [9, 0, 0, 0, 7, 7, 7, 7]));
+    assert!(matches!(&logs[3].message, LogMessages::QuTransferLog(QuTransferLog { amount: 250, transfer_id: Some(7), .. })));
+    assert_eq!(parser.offset(), 260);
+
+    // the truncated transfer ends the iteration
+    assert!(matches!(parser.next(), Some(Err(LogParseError::Truncated { offset: 260, expected: 88, found: 36 }))));
+    assert!(parser.next().is_none());
+
+    // the same logs as parsing the complete logs at once
+    let complete = QubicLogs::from_bytes(&FIXTURE[..260]).unwrap();
+    assert_eq!(complete.0.len(), 5);
+    assert_eq!(complete.transfers(14_000_001).map(|t| t.amount).collect::<Vec<_>>(), [250]);
+    assert_eq!(LogParser::new(&FIXTURE[..260]).count(), 5);
+
+    // a message which does not decode is skipped over
+    let header = LogHeader { size: U24::from(3u16), log_type: QubicLogType::ContractErrorMessage, ..Default::default() };
+    let data = [qubic_types::traits::ToBytes::to_bytes(&header), vec![0; 3], FIXTURE[..88].to_vec()].concat();
+    let parsed = LogParser::new(data.as_slice()).collect::<Vec<_>>();
+    assert!(matches!(parsed[..], [Err(LogParseError::Decode { offset: 0, .. }), Ok(_)]));
+}
@@ -0,0 +1,253 @@
+//! Inputs and outputs of the QUtil procedures and functions besides SendToMany, laid out like the structs of
+//! `QUtil.h`. The burn and the polls are sent as transactions to `QubicId::from_contract_id(QUTIL_CONTRACT_INDEX)`, the
+//! poll results are read with a `ContractFunctionCall`
+
+use alloc::vec::Vec;
+use qubic_types::QubicId;
+
+use super::assets::AssetName;
+
+pub use super::{fees::{QUTIL_POLL_CREATION_FEE, QUTIL_VOTE_FEE}, qlogging::QUTIL_CONTRACT_INDEX};
+
+/// QUtil input type of `BurnQuInput`
+pub const QUTIL_BURN_QUBIC: u16 = 2;
+/// QUtil input type of `CreatePollInput`
+pub const QUTIL_CREATE_POLL: u16 = 4;
+/// QUtil input type of `VoteInput`
+pub const QUTIL_VOTE: u16 = 5;
+/// QUtil function returning the `GetPollResultsOutput` of a `GetPollResultsInput`
+pub const QUTIL_GET_CURRENT_RESULT: u16 = 3;
+
+/// Options of a poll, the votes of every option are counted whether it was named in the poll or not
+pub const QUTIL_MAX_OPTIONS: usize = 64;
+/// Assets whose holders may vote on an asset poll
+pub const QUTIL_MAX_ASSETS_PER_POLL: usize = 16;
+pub const QUTIL_POLL_NAME_MAX_LEN: usize = 32;
+pub const QUTIL_POLL_GITHUB_URL_MAX_SIZE: usize = 256;
+
+/// Poll weighting the votes by the QU balance of the voters
+pub const QUTIL_POLL_TYPE_QUBIC: u64 = 1;
+/// Poll weighting the votes by the shares of `CreatePollInput::allowed_assets` the voters hold
+pub const QUTIL_POLL_TYPE_ASSET: u64 = 2;
+
+/// Burns `amount` of the amount of the transaction, the rest is refunded
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct BurnQuInput {
+    pub amount: i64
+}
+
+/// Amount burned, negative if the amount of the transaction did not cover the burn
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct BurnQuOutput {
+    pub amount: i64
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct PollAsset {
+    pub issuer: QubicId,
+    pub asset_name: AssetName
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct CreatePollInput {
+    /// ASCII name padded with zeros
+    pub poll_name: [u8; QUTIL_POLL_NAME_MAX_LEN],
+    pub poll_type: u64,
+    /// balance or shares a voter has to hold at least
+    pub min_amount: u64,
+    /// ASCII link to the proposal padded with zeros
+    pub github_link: [u8; QUTIL_POLL_GITHUB_URL_MAX_SIZE],
+    pub allowed_assets: [PollAsset; QUTIL_MAX_ASSETS_PER_POLL],
+    /// assets of `allowed_assets` in use, zero for QU polls
+    pub num_assets: u64
+}
+
+impl CreatePollInput {
+    /// poll weighting the votes by the QU balance of the voters
+    pub fn qubic(name: &str, min_amount: u64, github_link: &str) -> Result<Self, PollInputError> {
+        Self::asset(name, min_amount, github_link, &[]).map(|input| Self { poll_type: QUTIL_POLL_TYPE_QUBIC, ..input })
+    }
+
+    /// poll weighting the votes by the shares of `allowed_assets` the voters hold
+    pub fn asset(name: &str, min_amount: u64, github_link: &str, allowed_assets: &[PollAsset]) -> Result<Self, PollInputError> {
+        if name.len() > QUTIL_POLL_NAME_MAX_LEN {
+            return Err(PollInputError::NameTooLong(name.len()))
+        }
+
+        if github_link.len() > QUTIL_POLL_GITHUB_URL_MAX_SIZE {
+            return Err(PollInputError::LinkTooLong(github_link.len()))
+        }
+
+        if allowed_assets.len() > QUTIL_MAX_ASSETS_PER_POLL {
+            return Err(PollInputError::TooManyAssets(allowed_assets.len()))
+        }
+
+        let mut input = Self {
+            poll_name: [0; QUTIL_POLL_NAME_MAX_LEN],
+            poll_type: QUTIL_POLL_TYPE_ASSET,
+            min_amount,
+            github_link: [0; QUTIL_POLL_GITHUB_URL_MAX_SIZE],
+            allowed_assets: [PollAsset { issuer: QubicId::default(), asset_name: AssetName([0; 8]) }; QUTIL_MAX_ASSETS_PER_POLL],
+            num_assets: allowed_assets.len() as u64
+        };
+
+        input.poll_name[..name.len()].copy_from_slice(name.as_bytes());
+        input.github_link[..github_link.len()].copy_from_slice(github_link.as_bytes());
+        input.allowed_assets[..allowed_assets.len()].copy_from_slice(allowed_assets);
+
+        Ok(input)
+    }
+}
+
+/// Field of a `CreatePollInput` not fitting into its layout
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PollInputError {
+    NameTooLong(usize),
+    LinkTooLong(usize),
+    TooManyAssets(usize)
+}
+
+impl core::fmt::Display for PollInputError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::NameTooLong(len) => write!(f, "Poll name of {len} bytes exceeds the maximum of {QUTIL_POLL_NAME_MAX_LEN} bytes"),
+            Self::LinkTooLong(len) => write!(f, "GitHub link of {len} bytes exceeds the maximum of {QUTIL_POLL_GITHUB_URL_MAX_SIZE} bytes"),
+            Self::TooManyAssets(len) => write!(f, "{len} assets exceed the maximum of {QUTIL_MAX_ASSETS_PER_POLL} assets per poll")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for PollInputError {}
+
+/// Vote of `address`, which has to be the source of the transaction, for `chosen_option` with `amount` of its balance
+/// or shares
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct VoteInput {
+    pub poll_id: u64,
+    pub address: QubicId,
+    pub amount: u64,
+    pub chosen_option: u64
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct GetPollResultsInput {
+    pub poll_id: u64
+}
+
+/// Votes of a poll by option as returned by the contract, see `PollResults` for the decoded form
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[repr(C)]
+pub struct GetPollResultsOutput {
+    /// amount voted for every option
+    pub result: [u64; QUTIL_MAX_OPTIONS],
+    pub voter_count: [u64; QUTIL_MAX_OPTIONS],
+    pub is_active: u64
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PollOptionResult {
+    pub option: u64,
+    pub amount: u64,
+    pub voters: u64
+}
+
+/// Votes of a poll, only the options voted for are listed in ascending order
+#[derive(Debug, Default, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+pub struct PollResults {
+    pub active: bool,
+    pub options: Vec<PollOptionResult>
+}
+
+impl PollResults {
+    /// option voted for with the largest amount, the lower option on a tie
+    pub fn leading(&self) -> Option<&PollOptionResult> {
+        self.options.iter().rev().max_by_key(|option| option.amount)
+    }
+}
+
+impl From<GetPollResultsOutput> for PollResults {
+    fn from(output: GetPollResultsOutput) -> Self {
+        let options = output.result.iter().zip(output.voter_count.iter()).enumerate()
+            .filter(|(_, (amount, voters))| **amount > 0 || **voters > 0)
+            .map(|(option, (amount, voters))| PollOptionResult { option: option as u64, amount: *amount, voters: *voters })
+            .collect();
+
+        Self { active: output.is_active != 0, options }
+    }
+}
+
+#[test]
+fn test_qutil_input_layouts() {
+    use core::str::FromStr;
+    use qubic_types::traits::ToBytes;
+
+    assert_eq!(BurnQuInput { amount: 1_000 }.to_bytes(), 1_000i64.to_le_bytes());
+
+    let vote = VoteInput { poll_id: 7, address: QubicId([3; 32]), amount: 500, chosen_option: 2 };
+    assert_eq!(vote.to_bytes(), [&7u64.to_le_bytes()[..], &[3; 32], &500u64.to_le_bytes(), &2u64.to_le_bytes()].concat());
+
+    assert_eq!(GetPollResultsInput { poll_id: 9 }.to_bytes(), 9u64.to_le_bytes());
+
+    let asset = PollAsset { issuer: QubicId([5; 32]), asset_name: AssetName::from_str("QX").unwrap() };
+    let poll = CreatePollInput::asset("Raise fees", 10, "https://github.com/qubic/proposal/1", &[asset]).unwrap();
+    let bytes = poll.to_bytes();
+    assert_eq!(bytes.len(), 952);
+    assert_eq!(&bytes[..32], &[b"Raise fees".as_slice(), &[0; 22]].concat());
+    assert_eq!(&bytes[32..48], &[2u64.to_le_bytes(), 10u64.to_le_bytes()].concat());
+    assert_eq!(&bytes[48..83], b"https://github.com/qubic/proposal/1");
+    assert!(bytes[83..304].iter().all(|byte| *byte == 0));
+    assert_eq!(&bytes[304..344], &[&[5; 32][..], b"QX\0\0\0\0\0\0"].concat());
+    assert!(bytes[344..944].iter().all(|byte| *byte == 0));
+    assert_eq!(&bytes[944..], 1u64.to_le_bytes());
+
+    let poll = CreatePollInput::qubic("QU", 1_000, "").unwrap();
+    assert_eq!((poll.poll_type, poll.num_assets), (QUTIL_POLL_TYPE_QUBIC, 0));
+
+    assert_eq!(CreatePollInput::qubic(&"a".repeat(33), 0, ""), Err(PollInputError::NameTooLong(33)));
+    assert_eq!(CreatePollInput::qubic("QU", 0, &"a".repeat(257)), Err(PollInputError::LinkTooLong(257)));
+    assert_eq!(CreatePollInput::asset("QU", 0, "", &[asset; 17]), Err(PollInputError::TooManyAssets(17)));
+}
+
+#[test]
+fn test_poll_results() {
+    use qubic_types::traits::FromBytes;
+
+    assert_eq!(BurnQuOutput::from_bytes(&(-5i64).to_le_bytes()).unwrap(), BurnQuOutput { amount: -5 });
+
+    let mut bytes = vec![0; 64 * 8 * 2 + 8];
+    bytes[8..16].copy_from_slice(&300u64.to_le_bytes());
+    bytes[3 * 8..4 * 8].copy_from_slice(&700u64.to_le_bytes());
+    bytes[(64 + 1) * 8..(64 + 2) * 8].copy_from_slice(&2u64.to_le_bytes());
+    bytes[(64 + 3) * 8..(64 + 4) * 8].copy_from_slice(&1u64.to_le_bytes());
+    bytes[128 * 8] = 1;
+
+    let results = PollResults::from(GetPollResultsOutput::from_bytes(&bytes).unwrap());
+    assert_eq!(results, PollResults {
+        active: true,
+        options: vec![
+            PollOptionResult { option: 1, amount: 300, voters: 2 },
+            PollOptionResult { option: 3, amount: 700, voters: 1 }
+        ]
+    });
+    assert_eq!(results.leading().map(|option| option.option), Some(3));
+
+    // inactive poll without votes
+    let results = PollResults::from(GetPollResultsOutput::from_bytes(&vec![0; 64 * 8 * 2 + 8]).unwrap());
+    assert_eq!(results, PollResults::default());
+    assert_eq!(results.leading(), None);
+}
@@ -5,6 +5,7 @@ pub const SEND_TO_MANY_CONTRACT_INDEX: u32 = 4;
 
 #[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct SendToManyInput {
     pub ids: [QubicId; 25],
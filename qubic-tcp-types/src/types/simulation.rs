@@ -0,0 +1,155 @@
+//! Estimates whether a transaction would execute before it is signed or broadcast, from the balance of its source,
+//! the current tick and the fees of its operation. Nothing is simulated on a computor, a passing check only means the
+//! transaction is not rejected for the checked reasons.
+
+use alloc::string::String;
+
+use qubic_types::QubicId;
+
+use super::{fees::{FeeBreakdown, FeeSchedule}, ticks::CurrentTickInfo, transactions::{TransactionData, TransactionWithData}};
+
+/// Ticks a transaction has to target beyond the current tick to reach the computors in time
+pub const MIN_TICK_MARGIN: u32 = 5;
+
+/// State of the network a transaction is checked against
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SimulationContext {
+    /// balance of the source
+    pub balance: u64,
+    pub tick_info: CurrentTickInfo,
+    /// balances below are dust, 0 if unknown
+    pub dust_threshold: u64,
+    /// fees of the operation of the transaction
+    pub fees: FeeBreakdown
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SimulationCheck {
+    pub passed: bool,
+    pub detail: String
+}
+
+impl SimulationCheck {
+    fn new(passed: bool, detail: String) -> Self {
+        Self { passed, detail }
+    }
+}
+
+/// Verdict of `simulate_transfer` with the result of every check
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct TransferSimulation {
+    /// every check passed
+    pub likely_to_execute: bool,
+    /// the source can pay the amount and the fees it has to carry
+    pub balance: SimulationCheck,
+    /// the target tick is far enough ahead of the current tick
+    pub tick: SimulationCheck,
+    /// the amount covers the fees of the operation, plain transfers are not dust
+    pub amount: SimulationCheck,
+    /// the input fits the destination, plain transfers are not sent to contracts
+    pub destination: SimulationCheck
+}
+
+/// checks `tx` against the state of the network, the signature is not checked
+pub fn simulate_transfer(tx: &TransactionWithData, context: &SimulationContext) -> TransferSimulation {
+    let raw = &tx.raw_transaction;
+    let info = &context.tick_info;
+    let plain = matches!(tx.data, TransactionData::None);
+    let is_contract = raw.to != QubicId::default() && raw.to.0[8..].iter().all(|b| *b == 0);
+
+    // the contract fees are paid out of the amount, a lower amount is still deducted but the operation fails
+    let cost = raw.amount.max(context.fees.required_amount);
+    let balance = SimulationCheck::new(
+        context.balance >= cost,
+        format!("Balance {} of the source, {cost} required", context.balance)
+    );
+
+    let margin = raw.tick.saturating_sub(info.tick);
+    let tick = match raw.tick >= info.tick.saturating_add(MIN_TICK_MARGIN) {
+        true => SimulationCheck::new(true, format!("Tick {} is {margin} ticks (~{}s) ahead of the current tick {}", raw.tick, u64::from(margin) * u64::from(info.tick_duration.max(1)), info.tick)),
+        false => SimulationCheck::new(false, format!("Tick {} is not at least {MIN_TICK_MARGIN} ticks ahead of the current tick {}", raw.tick, info.tick))
+    };
+
+    let amount = if raw.amount < context.fees.required_amount {
+        SimulationCheck::new(false, format!("Amount {} does not cover the required {}", raw.amount, context.fees.required_amount))
+    } else if plain && raw.amount == 0 {
+        SimulationCheck::new(false, "Transfer of 0 QU".into())
+    } else if plain && raw.amount < context.dust_threshold {
+        SimulationCheck::new(false, format!("Amount {} is below the dust threshold {}", raw.amount, context.dust_threshold))
+    } else {
+        SimulationCheck::new(true, format!("Amount {} covers fees of {}", raw.amount, context.fees.fees()))
+    };
+
+    // only the SendToMany fee is read from the schedule, which is the contract fee of a SendToMany
+    let report = tx.validate(&FeeSchedule { send_to_many_fee: context.fees.contract_fee });
+    let destination = if !report.type_consistent || !report.size_consistent {
+        SimulationCheck::new(false, format!("Input type {} of {} bytes does not fit destination {}", raw.input_type, raw.input_size, raw.to))
+    } else if plain && is_contract && raw.input_type != 0 {
+        SimulationCheck::new(false, format!("Contract {} expects input for input type {}", raw.to, raw.input_type))
+    } else {
+        SimulationCheck::new(true, format!("Destination {} accepts the input", raw.to))
+    };
+
+    TransferSimulation {
+        likely_to_execute: balance.passed && tick.passed && amount.passed && destination.passed,
+        balance,
+        tick,
+        amount,
+        destination
+    }
+}
+
+#[test]
+fn test_simulate_transfer() {
+    use super::{assets::{AssetName, QXID, QX_TRANSFER_OWNERSHIP_AND_POSSESSION, TransferAssetOwnershipAndPossessionInput}, fees::{FeeEstimator, FeeSchedule, TRANSFER_FEE}, transactions::RawTransaction};
+    use core::str::FromStr;
+    use qubic_types::traits::ToBytes;
+
+    let info = CurrentTickInfo { tick_duration: 2, epoch: 100, tick: 1_000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 900 };
+    let context = SimulationContext { balance: 10_000, tick_info: info, dust_threshold: 10, fees: FeeBreakdown::default() };
+    let transfer = |amount, tick| TransactionWithData::from(RawTransaction { to: QubicId([2; 32]), amount, tick, ..Default::default() });
+
+    let passing = simulate_transfer(&transfer(500, 1_010), &context);
+    assert!(passing.likely_to_execute);
+    assert_eq!(passing.tick.detail, "Tick 1010 is 10 ticks (~20s) ahead of the current tick 1000");
+
+    // the time to the last tick doesn't overflow
+    let far = simulate_transfer(&transfer(500, u32::MAX), &context);
+    assert!(far.tick.passed);
+    assert_eq!(far.tick.detail, "Tick 4294967295 is 4294966295 ticks (~8589932590s) ahead of the current tick 1000");
+
+    // every check fails on its own
+    let checks = |tx: &TransactionWithData, context: &SimulationContext| {
+        let simulation = simulate_transfer(tx, context);
+        (simulation.likely_to_execute, simulation.balance.passed, simulation.tick.passed, simulation.amount.passed, simulation.destination.passed)
+    };
+
+    assert_eq!(checks(&transfer(10_001, 1_010), &context), (false, false, true, true, true));
+    assert_eq!(checks(&transfer(500, 1_004), &context), (false, true, false, true, true));
+    assert_eq!(checks(&transfer(500, 999), &context), (false, true, false, true, true));
+    assert_eq!(checks(&transfer(0, 1_010), &context), (false, true, true, false, true));
+    assert_eq!(checks(&transfer(9, 1_010), &context), (false, true, true, false, true));
+
+    // plain transfers to a contract with an input type lack the input
+    let to_contract = TransactionWithData::from(RawTransaction { to: QXID, amount: 500, tick: 1_010, input_type: QX_TRANSFER_OWNERSHIP_AND_POSSESSION, ..Default::default() });
+    assert_eq!(checks(&to_contract, &context), (false, true, true, true, false));
+
+    // contract fees are part of the amount and have to be covered by the balance
+    let data = TransactionData::TransferOwnershipAndPossession(TransferAssetOwnershipAndPossessionInput { issuer: QubicId([2; 32]), possessor: QubicId([3; 32]), new_owner: QubicId([4; 32]), asset_name: AssetName::from_str("QX").unwrap(), number_of_units: 5 });
+    let fees = FeeSchedule::default().estimate(&data).unwrap();
+    let asset_transfer = |amount| {
+        let mut tx = TransactionWithData { raw_transaction: RawTransaction { to: QXID, amount, tick: 1_010, input_type: QX_TRANSFER_OWNERSHIP_AND_POSSESSION, ..Default::default() }, data: data.clone(), ..Default::default() };
+        tx.raw_transaction.input_size = tx.data.encoded_len() as u16;
+        tx
+    };
+
+    let rich = SimulationContext { balance: TRANSFER_FEE, fees, ..context };
+    assert_eq!(checks(&asset_transfer(TRANSFER_FEE), &rich), (true, true, true, true, true));
+    assert_eq!(checks(&asset_transfer(TRANSFER_FEE - 1), &rich), (false, true, true, false, true));
+    assert_eq!(checks(&asset_transfer(TRANSFER_FEE), &SimulationContext { fees, ..context }), (false, false, true, true, true));
+}
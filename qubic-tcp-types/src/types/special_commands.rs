@@ -1,15 +1,16 @@
-use core::fmt::Debug;
+use core::fmt::{Debug, Display};
 
 use qubic_types::{traits::{FromBytes, ToBytes, Sign}, errors::QubicError};
 use qubic_types::{QubicId, QubicWallet, Signature};
-use crate::consts::NUMBER_OF_COMPUTORS;
+use crate::consts::{BallotVotes, MAX_NUMBER_EPOCH, MAX_SOLUTION_THRESHOLD, NUMBER_OF_COMPUTORS};
 
 use crate::utils::QubicRequest;
 
 use super::time::QubicSetUtcTime;
 
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum CommandType {
     SpecialCommandShutDown                     = 0,
@@ -36,13 +37,24 @@ pub enum CommandType {
     SpecialCommandGetMiningScoreRanking        = 14,
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct CommandDescriptor {
     pub nonce: [u8; 7],
     pub command_type: CommandType
 }
 
+impl CommandDescriptor {
+    /// the node only accepts nonces exceeding the one of the previous command, only the lower 7 bytes are used
+    pub fn with_nonce(command_type: CommandType, nonce: u64) -> Self {
+        let mut descriptor = Self { nonce: [0; 7], command_type };
+        descriptor.nonce.copy_from_slice(&nonce.to_le_bytes()[..7]);
+
+        descriptor
+    }
+}
+
 #[cfg(feature = "std")]
 impl CommandDescriptor {
     pub fn new(command_type: CommandType) -> Self {
@@ -73,7 +85,8 @@ macro_rules! set_command_type {
     };
 }
 
-#[derive(Clone, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SpecialCommand<T: ToBytes + FromBytes> {
     pub descriptor: CommandDescriptor,
@@ -95,6 +108,101 @@ impl<T: GetCommandType + ToBytes + FromBytes> SpecialCommand<T> {
     }
 }
 
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommandError {
+    InvalidEpoch(u32),
+    InvalidThreshold(i32),
+    InvalidTime(QubicSetUtcTime)
+}
+
+impl Display for CommandError {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::InvalidEpoch(epoch) => write!(f, "Epoch {epoch} is not within 1..{MAX_NUMBER_EPOCH}"),
+            Self::InvalidThreshold(threshold) => write!(f, "Solution threshold {threshold} is not within 1..={MAX_SOLUTION_THRESHOLD}"),
+            Self::InvalidTime(time) => write!(f, "{time:?} is not a valid time")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for CommandError {}
+
+/// Prepares special commands with validated payloads, e.g. to review them before they are signed and sent
+#[cfg(feature = "std")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CommandBuilder<T> {
+    payload: T,
+    nonce: Option<u64>
+}
+
+#[cfg(feature = "std")]
+impl CommandBuilder<SetEpochParams> {
+    pub fn set_solution_threshold(epoch: u32, threshold: i32) -> Result<Self, CommandError> {
+        if epoch == 0 || epoch >= MAX_NUMBER_EPOCH {
+            return Err(CommandError::InvalidEpoch(epoch))
+        }
+
+        if !(1..=MAX_SOLUTION_THRESHOLD).contains(&threshold) {
+            return Err(CommandError::InvalidThreshold(threshold))
+        }
+
+        Ok(Self { payload: SetEpochParams { epoch, treshold: threshold }, nonce: None })
+    }
+}
+
+#[cfg(feature = "std")]
+impl CommandBuilder<ToggleMainMode> {
+    pub fn toggle_main_aux(mode: NodeMode) -> Self {
+        Self { payload: ToggleMainMode::new(mode), nonce: None }
+    }
+}
+
+#[cfg(feature = "std")]
+impl CommandBuilder<SetTime> {
+    pub fn set_time(time: QubicSetUtcTime) -> Result<Self, CommandError> {
+        if !time.is_valid() {
+            return Err(CommandError::InvalidTime(time))
+        }
+
+        Ok(Self { payload: SetTime { time }, nonce: None })
+    }
+}
+
+#[cfg(feature = "std")]
+impl<T: GetCommandType + ToBytes + FromBytes> CommandBuilder<T> {
+    /// nonce of the command, the current unix time in seconds by default like the qubic-cli
+    pub fn with_nonce(mut self, nonce: u64) -> Self {
+        self.nonce = Some(nonce);
+        self
+    }
+
+    /// the command with a zero signature, sign it with `Sign::sign` before sending it
+    pub fn unsigned(self) -> SpecialCommand<T> {
+        let descriptor = match self.nonce {
+            Some(nonce) => CommandDescriptor::with_nonce(T::get_command_type(), nonce),
+            None => CommandDescriptor::new(T::get_command_type())
+        };
+
+        SpecialCommand { descriptor, payload: self.payload, signature: Signature::default() }
+    }
+
+    pub fn build(self, operator: &QubicWallet) -> SpecialCommand<T> {
+        let mut command = self.unsigned();
+        command.sign(operator).unwrap();
+
+        command
+    }
+
+    /// hex of the packet which would be sent, unsigned and with a zero dejavu
+    pub fn dry_run(self) -> String {
+        let bytes = self.unsigned().to_bytes();
+        let header = crate::Header::new_with_dejavu(qubic_types::U24::try_from(core::mem::size_of::<crate::Header>() + bytes.len()).unwrap(), crate::MessageType::ProcessSpecialCommand, 0);
+
+        header.to_bytes().iter().chain(&bytes).map(|b| format!("{b:02x}")).collect()
+    }
+}
+
 impl<T: ToBytes + FromBytes> Sign for SpecialCommand<T> {
     fn sign(&mut self, wallet: &QubicWallet) -> Result<(), QubicError> {
 
@@ -119,11 +227,20 @@ impl<T: ToBytes + FromBytes> Sign for SpecialCommand<T> {
 
 impl<T: ToBytes + FromBytes> ToBytes for SpecialCommand<T> {
     fn to_bytes(&self) -> Vec<u8> {
-        let mut bytes = self.descriptor.to_bytes();
-        bytes.extend(self.payload.to_bytes());
-        bytes.extend(self.signature.to_bytes());
+        let mut bytes = Vec::with_capacity(self.encoded_len());
+        self.write_to(&mut bytes);
         bytes
     }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.descriptor.write_to(buf);
+        self.payload.write_to(buf);
+        self.signature.write_to(buf);
+    }
+
+    fn encoded_len(&self) -> usize {
+        core::mem::size_of::<CommandDescriptor>() + self.payload.encoded_len() + core::mem::size_of::<Signature>()
+    }
 }
 
 impl<T: ToBytes + FromBytes> FromBytes for SpecialCommand<T> {
@@ -147,9 +264,11 @@ impl<T: ToBytes + FromBytes> QubicRequest for SpecialCommand<T> {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Proposal {
     pub uri_size: u8,
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_big_array"))]
     pub uri: [u8; 255]
 }
 
@@ -171,20 +290,25 @@ impl Proposal {
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Ballot {
     pub zero: u8,
-    pub votes: [u8; (NUMBER_OF_COMPUTORS * 3 + 7)/8],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_big_array"))]
+    pub votes: BallotVotes,
     pub quasi_random_number: u8
 }
 
+const _: () = assert!(core::mem::size_of::<Ballot>() == 2 + (NUMBER_OF_COMPUTORS * 3).div_ceil(8));
+
 impl Default for Ballot {
     fn default() -> Self {
-        Self { zero: 0, votes: [0; (NUMBER_OF_COMPUTORS * 3 + 7)/8], quasi_random_number: 0 }
+        Self { zero: 0, votes: [0; core::mem::size_of::<BallotVotes>()], quasi_random_number: 0 }
     }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum VoteOption {
     NotVoted,
     Option(u8)
@@ -219,7 +343,8 @@ impl Ballot {
     }
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct GetProposalAndBallotRequest {
     pub computor_index: u16,
@@ -229,7 +354,8 @@ pub struct GetProposalAndBallotRequest {
 
 set_command_type!(GetProposalAndBallotRequest, CommandType::SpecialCommandGetProposalAndBallotRequest);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct GetProposalAndBallotResponse {
     pub computor_index: u16,
@@ -240,7 +366,8 @@ pub struct GetProposalAndBallotResponse {
 
 set_command_type!(GetProposalAndBallotResponse, CommandType::SpecialCommandGetProposalAndBallotResponse);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SetProposalAndBallotRequest {
     pub computor_index: u16,
@@ -252,7 +379,8 @@ pub struct SetProposalAndBallotRequest {
 
 set_command_type!(SetProposalAndBallotRequest, CommandType::SpecialCommandSetProposalAndBallotRequest);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SetProposalAndBallotResponse {
     pub computor_index: u16,
@@ -261,49 +389,95 @@ pub struct SetProposalAndBallotResponse {
 
 set_command_type!(SetProposalAndBallotResponse, CommandType::SpecialCommandSetProposalAndBallotResponse);
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(u8)]
 pub enum NodeMode {
     Aux = 0,
     Main = 1
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct ToggleMainMode {
-    pub mode: NodeMode
+    pub mode: NodeMode,
+    pub padding: [u8; 7]
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+impl ToggleMainMode {
+    pub fn new(mode: NodeMode) -> Self {
+        Self { mode, padding: [0; 7] }
+    }
+}
+
+set_command_type!(ToggleMainMode, CommandType::SpecialCommandToggleMainAuxRequest);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SetEpochParams {
     pub epoch: u32,
     pub treshold: i32
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+set_command_type!(SetEpochParams, CommandType::SpecialCommandSetSolutionThresholdRequest);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct SetTime {
     pub time: QubicSetUtcTime
 }
 
+set_command_type!(SetTime, CommandType::SpecialCommandSendTime);
+
+/// Answer of the node to `SetTime`, the echoed descriptor with the time the node runs on after setting it. The command
+/// type is kept as the byte received, the node answers commands of other keys with silence or other packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[repr(C)]
+pub struct SendTimeResponse {
+    pub nonce: [u8; 7],
+    pub command_type: u8,
+    pub time: QubicSetUtcTime
+}
+
+impl SendTimeResponse {
+    /// whether the node echoed a `SetTime` command, i.e. accepted it
+    pub fn is_accepted(&self) -> bool {
+        self.command_type == CommandType::SpecialCommandSendTime as u8
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct MiningScoreEntry {
     pub miner: QubicId,
     pub score: u32
 }
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct GetMiningScoreRanking;
 
 set_command_type!(GetMiningScoreRanking, CommandType::SpecialCommandGetMiningScoreRanking);
 
 #[derive(Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct MiningScoreRanking {
+    /// echoed descriptor of the request
+    pub descriptor: CommandDescriptor,
     pub rankings: Vec<MiningScoreEntry>
 }
 
+impl MiningScoreRanking {
+    /// the descriptor followed by the number of rankings, the rankings follow
+    pub const HEADER_SIZE: usize = core::mem::size_of::<CommandDescriptor>() + core::mem::size_of::<u32>();
+}
+
 impl Debug for MiningScoreRanking {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
 
@@ -319,20 +493,104 @@ impl Debug for MiningScoreRanking {
 
 impl FromBytes for MiningScoreRanking {
     fn from_bytes(data: &[u8]) -> Result<Self, qubic_types::errors::ByteEncodingError> {
-        let mut rankings = Vec::new();
-        let mut offset = 12;
+        use qubic_types::errors::ByteEncodingError;
 
-        if (data.len() - 12) % core::mem::size_of::<MiningScoreEntry>() != 0 {
-            return Err(qubic_types::errors::ByteEncodingError::InvalidDataLength { expected: (data.len() / core::mem::size_of::<MiningScoreEntry>()) * core::mem::size_of::<MiningScoreEntry>(), found: data.len() });
-        }
+        let Some(header) = data.get(..Self::HEADER_SIZE) else {
+            return Err(ByteEncodingError::InvalidMinimumDataLength { expected_min: Self::HEADER_SIZE, found: data.len() })
+        };
 
-        while offset < data.len() {
-            let entry = MiningScoreEntry::from_bytes(&data[offset..offset + core::mem::size_of::<MiningScoreEntry>()])?;
-            rankings.push(entry);
-            offset += core::mem::size_of::<MiningScoreEntry>();
+        let descriptor = CommandDescriptor::from_bytes(&header[..core::mem::size_of::<CommandDescriptor>()])?;
+        let number_of_rankings = u32::from_le_bytes(header[core::mem::size_of::<CommandDescriptor>()..].try_into().unwrap()) as usize;
+
+        // the node may send its whole ranking buffer, only the first `number_of_rankings` entries are set
+        let expected_min = Self::HEADER_SIZE + number_of_rankings * core::mem::size_of::<MiningScoreEntry>();
+        if data.len() < expected_min {
+            return Err(ByteEncodingError::InvalidMinimumDataLength { expected_min, found: data.len() })
         }
-        
 
-        Ok(Self { rankings })
+        let rankings = data[Self::HEADER_SIZE..expected_min].chunks_exact(core::mem::size_of::<MiningScoreEntry>())
+            .map(MiningScoreEntry::from_bytes)
+            .collect::<Result<_, _>>()?;
+
+        Ok(Self { descriptor, rankings })
     }
-}
\ No newline at end of file
+}
+
+/// packets as the qubic-cli lays them out for the nonce 1_700_000_000, unsigned and with a zero dejavu
+#[cfg(feature = "std")]
+#[test]
+fn test_command_dry_run() {
+    let header = "580000ff00000000";
+    let signature = "00".repeat(64);
+
+    let threshold = CommandBuilder::set_solution_threshold(150, 137).unwrap().with_nonce(1_700_000_000);
+    assert_eq!(threshold.dry_run(), format!("{header}00f15365000000059600000089000000{signature}"));
+
+    let toggle = CommandBuilder::toggle_main_aux(NodeMode::Main).with_nonce(1_700_000_000);
+    assert_eq!(toggle.dry_run(), format!("{header}00f15365000000070100000000000000{signature}"));
+
+    // the signed command carries the nonce and parameters of the dry run
+    let command = toggle.build(&QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap());
+    assert_ne!(command.signature, Signature::default());
+    assert_eq!(command.to_bytes()[..16], [0x00, 0xf1, 0x53, 0x65, 0x00, 0x00, 0x00, 0x07, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00]);
+}
+
+#[cfg(feature = "std")]
+#[test]
+fn test_command_builder_validation() {
+    assert_eq!(CommandBuilder::set_solution_threshold(0, 137), Err(CommandError::InvalidEpoch(0)));
+    assert_eq!(CommandBuilder::set_solution_threshold(MAX_NUMBER_EPOCH, 137), Err(CommandError::InvalidEpoch(MAX_NUMBER_EPOCH)));
+    assert_eq!(CommandBuilder::set_solution_threshold(150, 0), Err(CommandError::InvalidThreshold(0)));
+    assert_eq!(CommandBuilder::set_solution_threshold(150, MAX_SOLUTION_THRESHOLD + 1), Err(CommandError::InvalidThreshold(MAX_SOLUTION_THRESHOLD + 1)));
+
+    // 2024 is a leap year, 2100 is not
+    assert!(CommandBuilder::set_time(QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 999_999_999)).is_ok());
+    assert!(CommandBuilder::set_time(QubicSetUtcTime::new(100, 2, 29, 0, 0, 0, 0)).is_err());
+    assert!(CommandBuilder::set_time(QubicSetUtcTime::new(25, 13, 1, 0, 0, 0, 0)).is_err());
+    assert!(CommandBuilder::set_time(QubicSetUtcTime::new(25, 4, 31, 0, 0, 0, 0)).is_err());
+    assert!(CommandBuilder::set_time(QubicSetUtcTime::new(25, 4, 30, 24, 0, 0, 0)).is_err());
+}
+
+#[cfg(all(feature = "std", feature = "serde"))]
+#[test]
+fn test_command_serde() {
+    let command = CommandBuilder::set_solution_threshold(150, 137).unwrap().with_nonce(1_700_000_000).unsigned();
+    let json = serde_json::to_string(&command).unwrap();
+
+    let prepared: SpecialCommand<SetEpochParams> = serde_json::from_str(&json).unwrap();
+    assert_eq!(prepared, command);
+    assert_eq!(prepared.to_bytes(), command.to_bytes());
+}
+
+/// echo of a node which set its time to 2024/02/29 23:59:59.5 for the command with the nonce 5
+#[test]
+fn test_send_time_response_from_bytes() {
+    let echo = [[5, 0, 0, 0, 0, 0, 0, 13].as_slice(), &[24, 2, 29, 23, 59, 59, 0, 0], &500_000_000u32.to_le_bytes()].concat();
+    let response = SendTimeResponse::from_bytes(&echo).unwrap();
+
+    assert!(response.is_accepted());
+    assert_eq!(response.nonce, CommandDescriptor::with_nonce(CommandType::SpecialCommandSendTime, 5).nonce);
+    assert_eq!(response.time, QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 500_000_000));
+
+    // an unknown command type is not accepted rather than undecodable
+    assert!(!SendTimeResponse::from_bytes(&[&echo[..7], &[200], &echo[8..]].concat()).unwrap().is_accepted());
+    assert!(SendTimeResponse::from_bytes(&echo[..19]).is_err());
+}
+
+/// responses of a node with its descriptor `0500000000000e`, the number of rankings and the rankings
+#[test]
+fn test_mining_score_ranking_from_bytes() {
+    let header = |number_of_rankings: u32| [[5, 0, 0, 0, 0, 0, 0, 14].as_slice(), &number_of_rankings.to_le_bytes()].concat();
+    let entry = |miner: u8, score: u32| [[miner; 32].as_slice(), &score.to_le_bytes()].concat();
+
+    let empty = MiningScoreRanking::from_bytes(&header(0)).unwrap();
+    assert_eq!(empty.descriptor.command_type, CommandType::SpecialCommandGetMiningScoreRanking);
+    assert!(empty.rankings.is_empty());
+
+    // unset entries of the ranking buffer are ignored
+    let ranking = MiningScoreRanking::from_bytes(&[header(2), entry(1, 120), entry(2, 80), vec![0; 36 * 3]].concat()).unwrap();
+    assert_eq!(ranking.rankings, [MiningScoreEntry { miner: QubicId([1; 32]), score: 120 }, MiningScoreEntry { miner: QubicId([2; 32]), score: 80 }]);
+
+    assert!(MiningScoreRanking::from_bytes(&[header(2), entry(1, 120)].concat()).is_err());
+    assert!(MiningScoreRanking::from_bytes(&header(0)[..11]).is_err());
+}
@@ -1,18 +1,22 @@
 use core::fmt::Debug;
+use alloc::boxed::Box;
 
-use qubic_types::{Signature, H256, QubicTxHash};
+use qubic_types::{traits::ToBytes, Epoch, QubicId, QubicTxHash, QubicWallet, Signature, H256};
+use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
 
-use crate::{MessageType, consts::{NUMBER_OF_TRANSACTION_PER_TICK, NUMBER_OF_COMPUTORS, MAX_NUMBER_OF_CONTRACTS}};
+use crate::{MessageType, consts::{NUMBER_OF_TRANSACTION_PER_TICK, NUMBER_OF_COMPUTORS, MAX_NUMBER_OF_CONTRACTS, QUORUM, VoteFlags}};
 
-use super::time::QubicTime;
+use super::{time::QubicTime, transactions::TransactionWithData};
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct GetCurrentTickInfo;
 set_message_type!(GetCurrentTickInfo, MessageType::RequestCurrentTickInfo);
 
+/// Wire struct with raw integers, `tick()`, `epoch()` and `initial_tick()` are the typed numbers
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct CurrentTickInfo {
     pub tick_duration: u16,
@@ -33,6 +37,19 @@ pub enum TickPeriod {
 }
 
 impl CurrentTickInfo {
+    pub fn tick(&self) -> qubic_types::Tick {
+        qubic_types::Tick(self.tick)
+    }
+
+    pub fn epoch(&self) -> Epoch {
+        Epoch(self.epoch)
+    }
+
+    /// first tick of the epoch
+    pub fn initial_tick(&self) -> qubic_types::Tick {
+        qubic_types::Tick(self.initial_tick)
+    }
+
     pub fn tick_period(&self) -> TickPeriod {
         let ticks_in_epoch = self.tick - self.initial_tick;
 
@@ -58,7 +75,9 @@ pub struct RequestTickData {
 
 set_message_type!(RequestTickData, MessageType::RequestTickData);
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct TickData {
     pub computor_index: u16,
@@ -68,7 +87,9 @@ pub struct TickData {
     pub time: QubicTime,
 
     pub time_lock: [u8; 32],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_big_array"))]
     pub transaction_digest: [QubicTxHash; NUMBER_OF_TRANSACTION_PER_TICK],
+    #[cfg_attr(feature = "serde", serde(with = "crate::utils::serde_big_array"))]
     pub contract_fees: [u64; MAX_NUMBER_OF_CONTRACTS],
 
     pub signature: Signature
@@ -76,7 +97,55 @@ pub struct TickData {
 
 set_message_type!(TickData, MessageType::BroadcastFutureTickData);
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+const _: () = assert!(core::mem::size_of::<TickData>() == 8 + core::mem::size_of::<QubicTime>() + 32 + NUMBER_OF_TRANSACTION_PER_TICK * core::mem::size_of::<QubicTxHash>() + MAX_NUMBER_OF_CONTRACTS * 8 + core::mem::size_of::<Signature>());
+
+impl TickData {
+    /// zeroed tick data, allocated on the heap without passing the stack
+    pub fn new_boxed() -> Box<Self> {
+        // every field is an integer or a byte array, all zeroes is a valid value
+        unsafe { Box::<Self>::new_zeroed().assume_init() }
+    }
+
+    /// fee the contract with `contract_index` was charged for its execution in the tick, `None` if the index
+    /// exceeds the number of contracts
+    pub fn contract_fee(&self, contract_index: usize) -> Option<u64> {
+        self.contract_fees.get(contract_index).copied()
+    }
+
+    /// contracts charged in the tick with their fees in order of the contract index
+    pub fn charged_contract_fees(&self) -> impl Iterator<Item = (usize, u64)> + '_ {
+        self.contract_fees.iter().copied().enumerate().filter(|(_, fee)| *fee != 0)
+    }
+
+    pub fn total_contract_fees(&self) -> u64 {
+        self.contract_fees.iter().sum()
+    }
+
+    /// whether the digests of the tick list `tx`
+    pub fn verify_transaction(&self, tx: &TransactionWithData) -> bool {
+        self.transaction_digest.contains(&QubicTxHash::from(tx))
+    }
+
+    /// transaction digest computors vote for with this tick data (`Tick::transaction_digest`), the K12 hash of the
+    /// tick data as sent including its signature. It commits to the transaction digests in the order of their slots
+    pub fn digest(&self) -> H256 {
+        let mut digest = [0; 32];
+        let mut kg = KangarooTwelve::new(b"");
+        kg.update(&self.to_bytes());
+        kg.into_xof().squeeze(&mut digest);
+
+        H256(digest)
+    }
+
+    /// whether a quorum of computors voted for the digest of this tick data
+    pub fn verify_quorum(&self, quorum: &QuorumSummary) -> bool {
+        quorum.verifies(self.digest())
+    }
+}
+
+/// Vote of a computor for a tick, zeroed by `Default`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct Tick {
     pub computor_index: u16,
@@ -102,11 +171,322 @@ pub struct Tick {
 
 set_message_type!(Tick, MessageType::BroadcastTick);
 
+impl Tick {
+    /// digest the computor signs the vote with, the K12 hash of the vote without its signature. Like
+    /// `processBroadcastTick` of the core it is taken with the computor index xored with the message type
+    pub fn signing_digest(&self) -> [u8; 32] {
+        let mut bytes = self.to_bytes();
+        bytes[..2].copy_from_slice(&(self.computor_index ^ MessageType::BroadcastTick as u16).to_le_bytes());
+
+        let mut digest = [0; 32];
+        let mut kg = KangarooTwelve::new(b"");
+        kg.update(&bytes[..bytes.len() - core::mem::size_of::<Signature>()]);
+        kg.into_xof().squeeze(&mut digest);
+
+        digest
+    }
+
+    /// whether the vote was signed by `computor`, the identity at `computor_index` of the computors of the epoch
+    pub fn verify(&self, computor: &QubicId) -> bool {
+        computor.verify_raw(self.signing_digest(), self.signature)
+    }
+
+    pub fn sign(&mut self, wallet: &QubicWallet) {
+        self.signature = wallet.sign_raw(self.signing_digest());
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct QuorumTickData {
     pub tick: u32,
-    pub vote_flags: [u8; (NUMBER_OF_COMPUTORS + 7)/8]
+    pub vote_flags: VoteFlags
 }
 
 set_message_type!(QuorumTickData, MessageType::RequestQuorumTick);
+
+const _: () = assert!(core::mem::size_of::<QuorumTickData>() == (4 + NUMBER_OF_COMPUTORS.div_ceil(8)).next_multiple_of(4));
+
+/// Digests computors have to agree on for a tick to reach quorum
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct TickDigests {
+    pub prev_spectrum_digest: H256,
+    pub prev_universe_digest: H256,
+    pub transaction_digest: H256
+}
+
+impl From<&Tick> for TickDigests {
+    fn from(value: &Tick) -> Self {
+        Self {
+            prev_spectrum_digest: value.prev_spectrum_digest,
+            prev_universe_digest: value.prev_universe_digest,
+            transaction_digest: value.transaction_digest
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct QuorumSummary {
+    /// number of distinct computors which voted
+    pub total_votes: usize,
+    /// size of the largest group of votes sharing the same digests
+    pub agreeing_votes: usize,
+    pub quorum_reached: bool,
+    /// digests of the largest group, `None` if there are no votes
+    pub digests: Option<TickDigests>
+}
+
+impl QuorumSummary {
+    /// aggregates the votes of a tick, only the first vote of every computor is counted
+    pub fn from_votes(votes: &[Tick]) -> Self {
+        let mut seen = [false; NUMBER_OF_COMPUTORS];
+        let mut groups: Vec<(TickDigests, usize)> = Vec::new();
+        let mut total_votes = 0;
+
+        for vote in votes {
+            let index = vote.computor_index as usize;
+
+            if index >= NUMBER_OF_COMPUTORS || seen[index] {
+                continue;
+            }
+
+            seen[index] = true;
+            total_votes += 1;
+
+            let digests = TickDigests::from(vote);
+
+            match groups.iter_mut().find(|(d, _)| *d == digests) {
+                Some((_, count)) => *count += 1,
+                None => groups.push((digests, 1))
+            }
+        }
+
+        let largest = groups.into_iter().max_by_key(|(_, count)| *count);
+        let agreeing_votes = largest.map(|(_, count)| count).unwrap_or(0);
+
+        Self {
+            total_votes,
+            agreeing_votes,
+            quorum_reached: agreeing_votes >= QUORUM,
+            digests: largest.map(|(digests, _)| digests)
+        }
+    }
+
+    /// whether the quorum was reached on `transaction_digest`
+    pub fn verifies(&self, transaction_digest: H256) -> bool {
+        self.quorum_reached && self.digests.is_some_and(|digests| digests.transaction_digest == transaction_digest)
+    }
+}
+
+/// What a computor answered for the tick data of a tick
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TickDataStatus {
+    Present(Box<TickData>),
+    /// the computor answered without tick data, no transactions were proposed for the tick
+    EmptyTick,
+    /// the computor did not answer, it has no record of the tick
+    Missing
+}
+
+impl TickDataStatus {
+    /// transaction digest computors vote for: the digest of the tick data, zero for an empty tick and `None` if the
+    /// tick data is missing
+    pub fn digest(&self) -> Option<H256> {
+        match self {
+            Self::Present(tick_data) => Some(tick_data.digest()),
+            Self::EmptyTick => Some(H256::zero()),
+            Self::Missing => None
+        }
+    }
+
+    /// whether a quorum of computors voted for this tick data, see `TickData::verify_quorum`
+    pub fn verify_quorum(&self, quorum: &QuorumSummary) -> bool {
+        self.digest().is_some_and(|digest| quorum.verifies(digest))
+    }
+}
+
+/// Tick data of the ticks `start..=end`, see `Client::request_tick_data_range`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickDataRange {
+    pub start: u32,
+    pub end: u32,
+    /// status of every tick of the range, indexed by `tick - start`
+    pub ticks: Vec<TickDataStatus>
+}
+
+impl TickDataRange {
+    pub fn get(&self, tick: u32) -> Option<&TickDataStatus> {
+        self.ticks.get(tick.checked_sub(self.start)? as usize)
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (u32, &TickDataStatus)> {
+        (self.start..=self.end).zip(&self.ticks)
+    }
+
+    /// inclusive ranges of consecutive `Missing` ticks in ascending order
+    pub fn gaps(&self) -> Vec<core::ops::RangeInclusive<u32>> {
+        let mut gaps: Vec<core::ops::RangeInclusive<u32>> = Vec::new();
+
+        for (tick, status) in self.iter() {
+            if *status != TickDataStatus::Missing {
+                continue;
+            }
+
+            match gaps.last_mut() {
+                Some(gap) if *gap.end() + 1 == tick => *gap = *gap.start()..=tick,
+                _ => gaps.push(tick..=tick)
+            }
+        }
+
+        gaps
+    }
+}
+
+#[cfg(test)]
+fn quorum_votes(agreeing: usize, disagreeing: usize) -> Vec<Tick> {
+    let base = Tick {
+        computor_index: 0,
+        epoch: 100,
+        tick: 12_000_000,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 24 },
+        prev_resource_testing_digest: 0,
+        salted_resource_testing_digest: 0,
+        prev_spectrum_digest: H256::repeat_byte(1),
+        prev_universe_digest: H256::repeat_byte(2),
+        prev_computor_digest: H256::zero(),
+        salted_spectrum_digest: H256::zero(),
+        salted_universe_digest: H256::zero(),
+        salted_computor_digest: H256::zero(),
+        transaction_digest: H256::repeat_byte(3),
+        expected_next_tick_transaction_digest: H256::zero(),
+        signature: Signature::default()
+    };
+
+    (0..agreeing + disagreeing).map(|i| Tick {
+        computor_index: i as u16,
+        transaction_digest: if i < agreeing { base.transaction_digest } else { H256::repeat_byte(4) },
+        ..base
+    }).collect()
+}
+
+#[test]
+fn test_tick_signature() {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let mut vote = quorum_votes(8, 0)[7];
+    vote.sign(&wallet);
+
+    assert!(vote.verify(&wallet.public_key));
+    assert!(!vote.verify(&QubicWallet::from_seed("bbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbbb").unwrap().public_key));
+
+    // the signature covers the computor index, the vote of another index is not signed by the computor
+    assert!(!Tick { computor_index: 6, ..vote }.verify(&wallet.public_key));
+    assert!(!Tick { transaction_digest: H256::repeat_byte(9), ..vote }.verify(&wallet.public_key));
+}
+
+#[test]
+fn test_quorum_threshold() {
+    let summary = QuorumSummary::from_votes(&quorum_votes(451, 225));
+    assert_eq!(summary.total_votes, NUMBER_OF_COMPUTORS);
+    assert_eq!(summary.agreeing_votes, 451);
+    assert!(summary.quorum_reached);
+    assert_eq!(summary.digests.unwrap().transaction_digest, H256::repeat_byte(3));
+
+    let summary = QuorumSummary::from_votes(&quorum_votes(450, 226));
+    assert_eq!(summary.agreeing_votes, 450);
+    assert!(!summary.quorum_reached);
+}
+
+#[test]
+fn test_quorum_duplicate_votes() {
+    let mut ticks = quorum_votes(450, 0);
+    ticks.extend(quorum_votes(1, 0));
+
+    let summary = QuorumSummary::from_votes(&ticks);
+    assert_eq!(summary.total_votes, 450);
+    assert!(!summary.quorum_reached);
+
+    assert_eq!(QuorumSummary::from_votes(&[]), QuorumSummary { total_votes: 0, agreeing_votes: 0, quorum_reached: false, digests: None });
+}
+
+#[test]
+fn test_tick_data_range_gaps() {
+    use qubic_types::traits::FromBytes;
+
+    let present = || TickDataStatus::Present(Box::new(TickData::from_bytes(&vec![0; core::mem::size_of::<TickData>()]).unwrap()));
+    let range = TickDataRange {
+        start: 10,
+        end: 17,
+        ticks: vec![TickDataStatus::Missing, present(), TickDataStatus::Missing, TickDataStatus::Missing, TickDataStatus::EmptyTick, present(), TickDataStatus::Missing, TickDataStatus::Missing]
+    };
+
+    assert_eq!(range.gaps(), [10..=10, 12..=13, 16..=17]);
+    assert_eq!((range.get(14), range.get(9), range.get(18)), (Some(&TickDataStatus::EmptyTick), None, None));
+    assert_eq!(TickDataRange { start: 1, end: 2, ticks: vec![present(), TickDataStatus::EmptyTick] }.gaps(), []);
+}
+
+#[test]
+fn test_contract_fees() {
+    use core::mem::{offset_of, size_of};
+    use qubic_types::traits::FromBytes;
+
+    // fees are little endian u64s indexed by contract right after the transaction digests
+    let mut bytes = vec![0; size_of::<TickData>()];
+    for (contract_index, fee) in [(1, 1_000u64), (4, 250)] {
+        let offset = offset_of!(TickData, contract_fees) + contract_index * size_of::<u64>();
+        bytes[offset..offset + 8].copy_from_slice(&fee.to_le_bytes());
+    }
+
+    let tick_data = TickData::from_bytes(&bytes).unwrap();
+    assert_eq!((tick_data.contract_fee(1), tick_data.contract_fee(2), tick_data.contract_fee(MAX_NUMBER_OF_CONTRACTS)), (Some(1_000), Some(0), None));
+    assert_eq!(tick_data.charged_contract_fees().collect::<Vec<_>>(), [(1, 1_000), (4, 250)]);
+    assert_eq!(tick_data.total_contract_fees(), 1_250);
+}
+
+#[test]
+fn test_tick_data_digest() {
+    use qubic_types::traits::FromBytes;
+
+    // the tick data as the core lays it out, the digest has to follow its field order
+    let mut bytes = Vec::new();
+    bytes.extend_from_slice(&7u16.to_le_bytes());
+    bytes.extend_from_slice(&100u16.to_le_bytes());
+    bytes.extend_from_slice(&12_000_000u32.to_le_bytes());
+    bytes.extend_from_slice(&[0xe8, 0x03, 5, 4, 3, 2, 1, 24]);
+    bytes.extend_from_slice(&[1; 32]);
+    for slot in 0..NUMBER_OF_TRANSACTION_PER_TICK {
+        bytes.extend_from_slice(&[if slot < 2 { slot as u8 + 2 } else { 0 }; 32]);
+    }
+    for contract_index in 0..MAX_NUMBER_OF_CONTRACTS {
+        bytes.extend_from_slice(&(contract_index as u64).to_le_bytes());
+    }
+    bytes.extend_from_slice(&[9; 64]);
+
+    let tick_data = TickData::from_bytes(&bytes).unwrap();
+    assert_eq!((tick_data.computor_index, tick_data.time.milliseconds, tick_data.transaction_digest[1], tick_data.contract_fee(3)), (7, 1_000, QubicTxHash([3; 32]), Some(3)));
+
+    // K12 of the bytes above, computed with an implementation of RFC 9861 independent of this crate
+    assert_eq!(tick_data.digest(), H256([0x6d, 0xbe, 0xb2, 0xf1, 0xce, 0x95, 0x50, 0x43, 0x2c, 0x7b, 0x14, 0x14, 0x9b, 0x6d, 0x40, 0xdd, 0x9b, 0x9b, 0x3f, 0x3f, 0xf7, 0x9d, 0xf6, 0x3d, 0xe6, 0xbc, 0x05, 0x74, 0x30, 0x2b, 0x86, 0x72]));
+
+    // swapping two digests changes the digest
+    let mut swapped = tick_data;
+    swapped.transaction_digest.swap(0, 1);
+    assert_ne!(swapped.digest(), tick_data.digest());
+}
+
+#[test]
+fn test_verify_tick_quorum() {
+    use qubic_types::traits::FromBytes;
+
+    let tick_data = TickData::from_bytes(&vec![1; core::mem::size_of::<TickData>()]).unwrap();
+    let votes = |transaction_digest: H256, agreeing| quorum_votes(agreeing, 0).into_iter().map(|vote| Tick { transaction_digest, ..vote }).collect::<Vec<_>>();
+
+    assert!(tick_data.verify_quorum(&QuorumSummary::from_votes(&votes(tick_data.digest(), QUORUM))));
+    assert!(!tick_data.verify_quorum(&QuorumSummary::from_votes(&votes(tick_data.digest(), QUORUM - 1))));
+    assert!(!tick_data.verify_quorum(&QuorumSummary::from_votes(&votes(H256::repeat_byte(3), QUORUM))));
+
+    // computors vote for a zero digest without tick data
+    let empty = QuorumSummary::from_votes(&votes(H256::zero(), QUORUM));
+    assert!(TickDataStatus::EmptyTick.verify_quorum(&empty));
+    assert!(!TickDataStatus::Missing.verify_quorum(&empty));
+    assert!(!TickDataStatus::Present(Box::new(tick_data)).verify_quorum(&empty));
+}
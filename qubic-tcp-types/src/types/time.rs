@@ -1,7 +1,9 @@
 use core::fmt::{Debug, Display};
 
 
-#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct QubicTime {
     pub milliseconds: u16,
@@ -26,6 +28,7 @@ impl Display for QubicTime {
 }
 
 #[derive(Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 #[repr(C)]
 pub struct QubicSetUtcTime {
     pub year: u8,
@@ -34,41 +37,155 @@ pub struct QubicSetUtcTime {
     pub hour: u8,
     pub minute: u8,
     pub second: u8,
+    #[cfg_attr(feature = "serde", serde(skip))]
     _pad: u8,
     pub nanosecond: u32
 }
 
+impl QubicSetUtcTime {
+    /// `year` counts from 2000
+    pub fn new(year: u8, month: u8, day: u8, hour: u8, minute: u8, second: u8, nanosecond: u32) -> Self {
+        Self { year, month, day, hour, minute, second, _pad: 0, nanosecond }
+    }
+
+    /// the first field out of its range, leap years are taken into account for February 29
+    pub fn validate(&self) -> Result<(), InvalidTime> {
+        let year = 2000 + self.year as u32;
+        let is_leap = year.is_multiple_of(4) && (!year.is_multiple_of(100) || year.is_multiple_of(400));
+        let days_in_month = match self.month {
+            2 if is_leap => 29,
+            1..=12 => DAYS_IN_MONTHS[self.month as usize - 1] as u8,
+            _ => return Err(InvalidTime::Month(self.month))
+        };
+
+        match self {
+            Self { day, .. } if !(1..=days_in_month).contains(day) => Err(InvalidTime::Day(*day)),
+            Self { hour, .. } if *hour >= 24 => Err(InvalidTime::Hour(*hour)),
+            Self { minute, .. } if *minute >= 60 => Err(InvalidTime::Minute(*minute)),
+            Self { second, .. } if *second >= 60 => Err(InvalidTime::Second(*second)),
+            Self { nanosecond, .. } if *nanosecond >= 1_000_000_000 => Err(InvalidTime::Nanosecond(*nanosecond)),
+            _ => Ok(())
+        }
+    }
+
+    /// whether the fields form an existing date and time
+    pub fn is_valid(&self) -> bool {
+        self.validate().is_ok()
+    }
+
+    /// time `seconds` and `nanosecond` after the unix epoch, an error outside of the years 2000 to 2255 the year
+    /// counted from 2000 covers
+    pub fn from_unix(seconds: i64, nanosecond: u32) -> Result<Self, InvalidTime> {
+        if nanosecond >= 1_000_000_000 {
+            return Err(InvalidTime::Nanosecond(nanosecond))
+        }
+
+        let (year, month, day) = civil_from_days(seconds.div_euclid(SECONDS_PER_DAY));
+        let time_of_day = seconds.rem_euclid(SECONDS_PER_DAY);
+        let year = u8::try_from(year - 2000).map_err(|_| InvalidTime::Year(year))?;
+
+        Ok(Self::new(year, month, day, (time_of_day / 3600) as u8, (time_of_day % 3600 / 60) as u8, (time_of_day % 60) as u8, nanosecond))
+    }
+
+    /// seconds since the unix epoch, the nanoseconds are left out
+    pub fn unix_seconds(&self) -> Result<i64, InvalidTime> {
+        self.validate()?;
+
+        let days = days_from_civil(2000 + self.year as i64, self.month, self.day);
+
+        Ok(days * SECONDS_PER_DAY + self.hour as i64 * 3600 + self.minute as i64 * 60 + self.second as i64)
+    }
+
+    /// current time of the system clock, an error if the clock is set before 2000 or after 2255
+    #[cfg(feature = "std")]
+    pub fn now() -> Result<Self, InvalidTime> {
+        std::time::SystemTime::now().try_into()
+    }
+
+    /// the UTC time, a leap second of chrono is kept at the last nanosecond of its minute
+    #[cfg(feature = "chrono")]
+    pub fn from_datetime(time: chrono::DateTime<chrono::Utc>) -> Result<Self, InvalidTime> {
+        Self::from_unix(time.timestamp(), time.timestamp_subsec_nanos().min(999_999_999))
+    }
+
+    #[cfg(feature = "chrono")]
+    pub fn to_datetime(&self) -> Result<chrono::DateTime<chrono::Utc>, InvalidTime> {
+        chrono::DateTime::from_timestamp(self.unix_seconds()?, self.nanosecond).ok_or(InvalidTime::Year(2000 + self.year as i64))
+    }
+}
+
+/// Field of a `QubicSetUtcTime` out of its range
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum InvalidTime {
+    /// years before 2000 and after 2255 cannot be set
+    Year(i64),
+    Month(u8),
+    Day(u8),
+    Hour(u8),
+    Minute(u8),
+    Second(u8),
+    Nanosecond(u32)
+}
+
+impl Display for InvalidTime {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        match self {
+            Self::Year(year) => write!(f, "Year {year} is not within 2000..=2255"),
+            Self::Month(month) => write!(f, "Month {month} is not within 1..=12"),
+            Self::Day(day) => write!(f, "Day {day} does not exist in its month"),
+            Self::Hour(hour) => write!(f, "Hour {hour} is not within 0..24"),
+            Self::Minute(minute) => write!(f, "Minute {minute} is not within 0..60"),
+            Self::Second(second) => write!(f, "Second {second} is not within 0..60"),
+            Self::Nanosecond(nanosecond) => write!(f, "Nanosecond {nanosecond} is not within 0..1000000000")
+        }
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InvalidTime {}
+
 // non leap years
-const YEAR: u128 = 3600*24*365;
-const DAY: u128 = 3600*24;
 const DAYS_IN_MONTHS: [u128; 12] = [31, 28, 31, 30, 31, 30, 31, 31, 30, 31, 30, 31];
+const SECONDS_PER_DAY: i64 = 3600 * 24;
+
+/// days of the proleptic Gregorian date since the unix epoch, counted in eras of 400 years which all have 146097 days
+fn days_from_civil(year: i64, month: u8, day: u8) -> i64 {
+    // the years start in March, February and its leap day come last
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month as i64 + 9) % 12) + 2) / 5 + day as i64 - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+
+    era * 146_097 + day_of_era - 719_468
+}
+
+/// `(year, month, day)` of the days since the unix epoch, the inverse of `days_from_civil`
+fn civil_from_days(days: i64) -> (i64, u8, u8) {
+    let days = days + 719_468;
+    let era = days.div_euclid(146_097);
+    let day_of_era = days - era * 146_097;
+    let year_of_era = (day_of_era - day_of_era / 1460 + day_of_era / 36_524 - day_of_era / 146_096) / 365;
+    let day_of_year = day_of_era - (365 * year_of_era + year_of_era / 4 - year_of_era / 100);
+    let month_from_march = (5 * day_of_year + 2) / 153;
+    let day = (day_of_year - (153 * month_from_march + 2) / 5 + 1) as u8;
+    let month = if month_from_march < 10 { month_from_march + 3 } else { month_from_march - 9 } as u8;
+
+    (year_of_era + era * 400 + (month <= 2) as i64, month, day)
+}
+
 #[cfg(feature = "std")]
-impl From<std::time::SystemTime> for QubicSetUtcTime {
-    fn from(time: std::time::SystemTime) -> Self {
-        let unix_epoch = time.duration_since(std::time::UNIX_EPOCH).unwrap().as_nanos();
-
-        let qubic_epoch = unix_epoch/1_000_000_000 - 30*YEAR - (30/4)*3600*24;
-        let year = qubic_epoch/YEAR;
-        let is_leap = year%4 == 0;
-        let mut day = qubic_epoch/DAY - (365*year + (year/4 - is_leap as u8 as u128));
-
-        let mut month = 0;
-        for i in 0..12 {
-            let days_in_month = DAYS_IN_MONTHS[i] + (if i == 1 && is_leap { 1 } else { 0 });
-            if day < days_in_month {
-                month = i + 1;
-                break;
-            }
-
-            day -= days_in_month;
-        }
+impl TryFrom<std::time::SystemTime> for QubicSetUtcTime {
+    type Error = InvalidTime;
+
+    fn try_from(time: std::time::SystemTime) -> Result<Self, Self::Error> {
+        let (seconds, nanosecond) = match time.duration_since(std::time::UNIX_EPOCH) {
+            Ok(since) => (i64::try_from(since.as_secs()).unwrap_or(i64::MAX), since.subsec_nanos()),
+            // before the unix epoch and therefore out of range, the year is still reported
+            Err(e) => (-i64::try_from(e.duration().as_secs()).unwrap_or(i64::MAX) - (e.duration().subsec_nanos() > 0) as i64, 0)
+        };
 
-        let hour = (qubic_epoch%DAY)/3600;
-        let minute = (qubic_epoch%3600)/60;
-        let second = qubic_epoch%60;
-        let nanosecond = unix_epoch%1_000_000_000;
-        
-        Self { year: year as u8, month: month as u8, day: day as u8, hour: hour as u8, minute: minute as u8, second: second as u8, _pad: 0, nanosecond: nanosecond as u32 }
+        Self::from_unix(seconds, nanosecond)
     }
 }
 
@@ -86,7 +203,93 @@ impl Display for QubicSetUtcTime {
 
 #[test]
 fn test_time() {
-    let now = std::time::SystemTime::now();
+    let now = QubicSetUtcTime::now().unwrap();
+    assert!(now.is_valid() && now.year >= 24);
 
-    dbg!(QubicSetUtcTime::from(now));
-}
\ No newline at end of file
+    assert_eq!(QubicSetUtcTime::try_from(std::time::UNIX_EPOCH), Err(InvalidTime::Year(1970)));
+    assert_eq!(QubicSetUtcTime::try_from(std::time::UNIX_EPOCH - std::time::Duration::from_secs(1)), Err(InvalidTime::Year(1969)));
+}
+
+/// packed times of the unix timestamps around the leap days and the turns of the year
+#[test]
+fn test_unix_round_trip() {
+    let cases = [
+        (946_684_800, QubicSetUtcTime::new(0, 1, 1, 0, 0, 0, 0)),
+        // 2024 is a leap year, its leap day is followed by March 1
+        (1_709_164_800, QubicSetUtcTime::new(24, 2, 29, 0, 0, 0, 0)),
+        (1_709_251_199, QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 0)),
+        (1_709_251_200, QubicSetUtcTime::new(24, 3, 1, 0, 0, 0, 0)),
+        // 2000 is a leap year as a multiple of 400, 2100 is not
+        (951_782_400, QubicSetUtcTime::new(0, 2, 29, 0, 0, 0, 0)),
+        (4_107_542_400, QubicSetUtcTime::new(100, 3, 1, 0, 0, 0, 0)),
+        (1_735_689_599, QubicSetUtcTime::new(24, 12, 31, 23, 59, 59, 0)),
+        (1_735_689_600, QubicSetUtcTime::new(25, 1, 1, 0, 0, 0, 0)),
+        (9_025_257_599, QubicSetUtcTime::new(255, 12, 31, 23, 59, 59, 0))
+    ];
+
+    for (seconds, time) in cases {
+        assert_eq!(QubicSetUtcTime::from_unix(seconds, 0), Ok(time), "{seconds}");
+        assert_eq!(time.unix_seconds(), Ok(seconds), "{time}");
+    }
+
+    let time = QubicSetUtcTime::from_unix(1_709_251_199, 999_999_999).unwrap();
+    assert_eq!((time.second, time.nanosecond), (59, 999_999_999));
+
+    assert_eq!(QubicSetUtcTime::from_unix(946_684_799, 0), Err(InvalidTime::Year(1999)));
+    assert_eq!(QubicSetUtcTime::from_unix(9_025_257_600, 0), Err(InvalidTime::Year(2256)));
+    assert_eq!(QubicSetUtcTime::from_unix(946_684_800, 1_000_000_000), Err(InvalidTime::Nanosecond(1_000_000_000)));
+}
+
+#[test]
+fn test_validate() {
+    assert_eq!(QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 999_999_999).validate(), Ok(()));
+    assert_eq!(QubicSetUtcTime::new(25, 2, 29, 0, 0, 0, 0).validate(), Err(InvalidTime::Day(29)));
+    assert_eq!(QubicSetUtcTime::new(100, 2, 29, 0, 0, 0, 0).validate(), Err(InvalidTime::Day(29)));
+    assert_eq!(QubicSetUtcTime::new(25, 0, 1, 0, 0, 0, 0).validate(), Err(InvalidTime::Month(0)));
+    assert_eq!(QubicSetUtcTime::new(25, 13, 1, 0, 0, 0, 0).validate(), Err(InvalidTime::Month(13)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 0, 0, 0, 0, 0).validate(), Err(InvalidTime::Day(0)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 30, 24, 0, 0, 0).validate(), Err(InvalidTime::Hour(24)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 30, 0, 60, 0, 0).validate(), Err(InvalidTime::Minute(60)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 30, 0, 0, 60, 0).validate(), Err(InvalidTime::Second(60)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 30, 0, 0, 0, 1_000_000_000).validate(), Err(InvalidTime::Nanosecond(1_000_000_000)));
+    assert_eq!(QubicSetUtcTime::new(25, 4, 30, 24, 0, 0, 0).unix_seconds(), Err(InvalidTime::Hour(24)));
+}
+
+/// layout of the payload of `SetTime`: the year from 2000 to the second in a byte each, two zero bytes aligning the
+/// nanoseconds which follow
+#[test]
+fn test_packed_time() {
+    use qubic_types::traits::{FromBytes, ToBytes};
+
+    let time = QubicSetUtcTime::new(24, 2, 29, 23, 59, 58, 123_456_789);
+    let bytes = [[24, 2, 29, 23, 59, 58, 0, 0].as_slice(), &123_456_789u32.to_le_bytes()].concat();
+
+    assert_eq!(time.to_bytes(), bytes);
+    assert_eq!(QubicSetUtcTime::from_bytes(&bytes), Ok(time));
+}
+
+#[cfg(feature = "chrono")]
+#[test]
+fn test_datetime_round_trip() {
+    use chrono::{TimeZone, Utc};
+
+    for (time, packed) in [
+        (Utc.with_ymd_and_hms(2024, 2, 29, 23, 59, 59).unwrap(), QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 0)),
+        (Utc.with_ymd_and_hms(2024, 3, 1, 0, 0, 0).unwrap(), QubicSetUtcTime::new(24, 3, 1, 0, 0, 0, 0)),
+        (Utc.with_ymd_and_hms(2025, 12, 31, 23, 59, 59).unwrap(), QubicSetUtcTime::new(25, 12, 31, 23, 59, 59, 0)),
+        (Utc.with_ymd_and_hms(2026, 1, 1, 0, 0, 0).unwrap(), QubicSetUtcTime::new(26, 1, 1, 0, 0, 0, 0))
+    ] {
+        let time = time + chrono::Duration::nanoseconds(250_000_001);
+        let packed = QubicSetUtcTime { nanosecond: 250_000_001, ..packed };
+
+        assert_eq!(QubicSetUtcTime::from_datetime(time), Ok(packed), "{time}");
+        assert_eq!(packed.to_datetime(), Ok(time), "{packed}");
+    }
+
+    // a leap second stays within its minute
+    let leap = chrono::NaiveDate::from_ymd_opt(2016, 12, 31).unwrap().and_hms_nano_opt(23, 59, 59, 1_500_000_000).unwrap().and_utc();
+    assert_eq!(QubicSetUtcTime::from_datetime(leap), Ok(QubicSetUtcTime::new(16, 12, 31, 23, 59, 59, 999_999_999)));
+
+    assert_eq!(QubicSetUtcTime::from_datetime(Utc.with_ymd_and_hms(1999, 12, 31, 23, 59, 59).unwrap()), Err(InvalidTime::Year(1999)));
+    assert_eq!(QubicSetUtcTime::new(25, 2, 29, 0, 0, 0, 0).to_datetime(), Err(InvalidTime::Day(29)));
+}
@@ -1,23 +1,49 @@
 use core::{fmt::Debug, num::NonZeroUsize, ptr::read_unaligned};
 use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
-use qubic_types::{traits::{FromBytes, GetSigner, Sign, ToBytes}, MiningSeed, Nonce, QubicId, QubicTxHash, QubicWallet, Signature};
+use qubic_types::{traits::{FromBytes, GetSigner, Sign, ToBytes, VerifySignature}, uri::QubicUri, MiningSeed, Nonce, QubicId, QubicTxHash, QubicWallet, Signature, Tick};
 
-use crate::{consts::NUMBER_OF_TRANSACTION_PER_TICK, utils::QubicRequest, MessageType};
+use crate::{consts::{TransactionBitfield, MAX_INPUT_SIZE, NUMBER_OF_TRANSACTION_PER_TICK}, utils::QubicRequest, MessageType};
 
-use super::{assets::{IssueAssetInput, TransferAssetInput, ISSUE_ASSET_FEE, QXID, TRANSFER_FEE}, send_to_many::{SendToManyInput, SEND_TO_MANY_CONTRACT_INDEX}, ContractIpoBid};
+use super::{activity::contract_index, assets::{IssueAssetInput, TransferAssetInput, TransferAssetOwnershipAndPossessionInput, QXID, QX_ISSUE_ASSET, QX_TRANSFER_OWNERSHIP_AND_POSSESSION}, fees::{FeeEstimator, FeeSchedule, ISSUE_ASSET_FEE, SUBMIT_WORK_BURN, TRANSFER_FEE}, qlogging::{QUOTTERY_CONTRACT_INDEX, QX_CONTRACT_INDEX}, send_to_many::{SendToManyInput, SEND_TO_MANY_CONTRACT_INDEX}, ticks::{CurrentTickInfo, TickData}, ContractIpoBid};
 
+/// Unsigned fields of a transaction without its input, the form `Qu::send_raw_transaction` signs
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct RawTransaction {
     pub from: QubicId,
     pub to: QubicId,
+    #[cfg_attr(feature = "serde", serde(with = "qubic_types::amount"))]
     pub amount: u64,
     pub tick: u32,
     pub input_type: u16,
     pub input_size: u16,
 }
 
+impl RawTransaction {
+    /// appends the little-endian wire encoding field by field, independent of the byte order of the target
+    fn write_le(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(&self.from.0);
+        buf.extend_from_slice(&self.to.0);
+        buf.extend_from_slice(&self.amount.to_le_bytes());
+        buf.extend_from_slice(&self.tick.to_le_bytes());
+        buf.extend_from_slice(&self.input_type.to_le_bytes());
+        buf.extend_from_slice(&self.input_size.to_le_bytes());
+    }
+
+    fn read_le(data: &[u8; core::mem::size_of::<RawTransaction>()]) -> Self {
+        Self {
+            from: QubicId(data[..32].try_into().unwrap()),
+            to: QubicId(data[32..64].try_into().unwrap()),
+            amount: u64::from_le_bytes(data[64..72].try_into().unwrap()),
+            tick: u32::from_le_bytes(data[72..76].try_into().unwrap()),
+            input_type: u16::from_le_bytes(data[76..78].try_into().unwrap()),
+            input_size: u16::from_le_bytes(data[78..80].try_into().unwrap())
+        }
+    }
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
 pub struct RawCall<T: Copy> {
@@ -45,8 +71,11 @@ impl<T: Copy> GetSigner for Call<T> {
 }
 
 
+/// Signed transaction without input (e.g. a plain transfer), the legacy body of broadcasts. Transactions with input
+/// are `TransactionWithData`, both hash the same if the input is empty
 #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 #[repr(C)]
 pub struct Transaction {
     pub raw_transaction: RawTransaction,
@@ -63,15 +92,17 @@ set_message_type!(Transaction, MessageType::BroadcastTransaction);
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[repr(C)]
-pub struct TransactionFlags([u8; NUMBER_OF_TRANSACTION_PER_TICK/8]);
+pub struct TransactionFlags(TransactionBitfield);
+
+const _: () = assert!(core::mem::size_of::<TransactionFlags>() * 8 == NUMBER_OF_TRANSACTION_PER_TICK);
 
 impl TransactionFlags {
     pub fn all() -> Self {
-        Self([0; NUMBER_OF_TRANSACTION_PER_TICK/8])
+        Self([0; NUMBER_OF_TRANSACTION_PER_TICK / 8])
     }
 
     pub fn first(first: NonZeroUsize) -> Self {
-        let mut flags = [0u8; NUMBER_OF_TRANSACTION_PER_TICK/8];
+        let mut flags = [0u8; NUMBER_OF_TRANSACTION_PER_TICK / 8];
         let full = usize::from(first)/8;
 
         for flag in flags.iter_mut() {
@@ -86,6 +117,15 @@ impl TransactionFlags {
 
         Self(flags)
     }
+
+    /// slots with a cleared flag are requested
+    pub fn is_requested(&self, slot: usize) -> bool {
+        self.0[slot / 8] & (1 << (slot % 8)) == 0
+    }
+
+    pub fn requested_count(&self) -> usize {
+        NUMBER_OF_TRANSACTION_PER_TICK - self.0.iter().map(|flags| flags.count_ones() as usize).sum::<usize>()
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
@@ -97,6 +137,138 @@ pub struct RequestedTickTransactions {
 
 set_message_type!(RequestedTickTransactions, MessageType::RequestTickTransactions);
 
+/// Transactions of a tick together with whether the computor returned all requested ones
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct TickTransactionsReport {
+    pub transactions: Vec<TransactionWithData>,
+    /// number of requested slots
+    pub requested: usize,
+    pub received: usize,
+    /// number of transactions in the tick, `None` if the tick data is not available
+    pub tick_data_digest_count: Option<usize>,
+    /// every requested transaction listed in the tick data was received, always false without tick data
+    pub complete: bool
+}
+
+impl TickTransactionsReport {
+    pub fn new(transactions: Vec<TransactionWithData>, flags: &TransactionFlags, tick_data: Option<&TickData>) -> Self {
+        let digests = tick_data.map(|tick_data| tick_data.transaction_digest.iter().enumerate().filter(|(_, digest)| **digest != QubicTxHash::default()).collect::<Vec<_>>());
+
+        let complete = digests.as_ref().is_some_and(|digests| {
+            let received = transactions.iter().map(QubicTxHash::from).collect::<Vec<_>>();
+
+            digests.iter().filter(|(slot, _)| flags.is_requested(*slot)).all(|(_, digest)| received.contains(digest))
+        });
+
+        Self {
+            requested: flags.requested_count(),
+            received: transactions.len(),
+            tick_data_digest_count: digests.map(|digests| digests.len()),
+            complete,
+            transactions
+        }
+    }
+}
+
+/// drops transactions received more than once, the first occurrence is kept
+pub fn dedup_transactions(transactions: &mut Vec<TransactionWithData>) {
+    let mut seen = Vec::with_capacity(transactions.len());
+
+    transactions.retain(|tx| {
+        let hash = QubicTxHash::from(tx);
+        let first = !seen.contains(&hash);
+        seen.push(hash);
+
+        first
+    });
+}
+
+/// orders the transactions by their slot in the digests of `tick_data`, transactions which are not listed follow in
+/// arrival order
+pub fn order_transactions(transactions: &mut [TransactionWithData], tick_data: &TickData) {
+    transactions.sort_by_cached_key(|tx| {
+        let hash = QubicTxHash::from(tx);
+
+        tick_data.transaction_digest.iter().position(|digest| *digest == hash).unwrap_or(usize::MAX)
+    });
+}
+
+/// whether the source and signature pass the bit checks `QubicId::verify_raw` does before the curve math. A plausible
+/// signature may still be invalid, an implausible one never is
+pub fn signature_plausible(tx: &TransactionWithData) -> bool {
+    let (public_key, signature) = (&tx.raw_transaction.from.0, &tx.signature.0);
+
+    public_key[15] & 0x80 == 0 && signature[15] & 0x80 == 0 && signature[62] & 0xC0 == 0 && signature[63] == 0
+}
+
+/// `VerifySignature::verify` of every transaction, e.g. of the transactions of a tick an indexer ingests.
+/// Implausible signatures are rejected without hashing the transaction, the others are verified in parallel on the
+/// rayon thread pool with the `rayon` feature and one after the other without it
+pub fn verify_batch(txs: &[TransactionWithData]) -> Vec<bool> {
+    let verify = |tx: &TransactionWithData| signature_plausible(tx) && tx.verify();
+
+    #[cfg(feature = "rayon")]
+    {
+        use rayon::prelude::*;
+
+        txs.par_iter().map(verify).collect()
+    }
+
+    #[cfg(not(feature = "rayon"))]
+    txs.iter().map(verify).collect()
+}
+
+/// Status of a transaction targeting a tick, `NotIncluded` is only reported once the network passed the tick
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "status", rename_all = "camelCase", rename_all_fields = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum TransactionStatus {
+    /// the network has not passed `target_tick` yet
+    Pending { target_tick: u32, current_tick: u32 },
+    /// the tick passed without the transaction
+    NotIncluded,
+    /// the computor received the transaction but the tick data does not list it
+    Included,
+    /// listed in the tick data of the tick
+    Executed,
+    /// the tick cannot be checked, e.g. it belongs to a previous epoch
+    Unknown { reason: String }
+}
+
+impl TransactionStatus {
+    /// status known from the current tick alone: `Pending` until the network passed `tick`, `Unknown` for ticks
+    /// before the initial tick of the epoch since computors only keep the current epoch
+    pub fn from_current_tick(tick: u32, info: &CurrentTickInfo) -> Option<Self> {
+        if tick >= info.tick {
+            return Some(Self::Pending { target_tick: tick, current_tick: info.tick })
+        }
+
+        (tick < info.initial_tick).then(|| Self::Unknown { reason: format!("Tick {tick} precedes the initial tick {} of epoch {}", info.initial_tick, info.epoch) })
+    }
+
+    /// status of `tx_hash` in a passed tick from the hashes of the transactions received for it and its tick data,
+    /// `tick_data` is `None` for an empty tick
+    pub fn in_tick<I: IntoIterator<Item = QubicTxHash>>(tx_hash: &QubicTxHash, received: I, tick_data: Option<&TickData>) -> Self {
+        if tick_data.is_some_and(|tick_data| tick_data.transaction_digest.contains(tx_hash)) {
+            Self::Executed
+        } else if received.into_iter().any(|hash| hash == *tx_hash) {
+            Self::Included
+        } else {
+            Self::NotIncluded
+        }
+    }
+}
+
+/// Status of a transaction together with whether it was derived from the tick data a quorum of computors voted for
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ProvenTransactionStatus {
+    pub status: TransactionStatus,
+    /// the tick data (or its absence for an empty tick) hashes to the transaction digest of the quorum, never set for
+    /// `Pending` and `Unknown`
+    pub proven: bool
+}
+
 impl From<Transaction> for QubicTxHash {
     fn from(val: Transaction) -> Self {
         let mut hash = [0; 32];
@@ -121,14 +293,67 @@ impl<T: Copy> From<Call<T>> for QubicTxHash {
     }
 }
 
+/// Bytes of input a memo may have
+pub const MAX_MEMO_SIZE: usize = 32;
+
+/// Whether the input of `raw` is a memo. Qubic transactions have no memo field, exchanges tag deposits with up to
+/// `MAX_MEMO_SIZE` bytes of input of input type 0 sent along a transfer of a positive amount to an identity which is
+/// not a contract. This is a convention among wallets and exchanges, the network does not enforce it
+pub fn is_memo(raw: &RawTransaction) -> bool {
+    raw.input_type == 0 && raw.amount > 0 && (1..=MAX_MEMO_SIZE).contains(&(raw.input_size as usize)) && contract_index(&raw.to).is_none()
+}
+
+/// A memo exceeds `MAX_MEMO_SIZE`, the length is handed back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct MemoTooLong(pub usize);
+
+impl core::fmt::Display for MemoTooLong {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Memo of {} bytes exceeds the maximum of {MAX_MEMO_SIZE} bytes", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for MemoTooLong {}
+
+/// The input of a transaction exceeds `MAX_INPUT_SIZE`, the length is handed back
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct InputTooLong(pub usize);
+
+impl core::fmt::Display for InputTooLong {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Input of {} bytes exceeds the maximum of {MAX_INPUT_SIZE} bytes", self.0)
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for InputTooLong {}
+
+/// Call of a contract procedure this crate has no layout for, `data` is the raw input
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ContractCall {
+    pub contract_index: u32,
+    pub input_type: u16,
+    pub data: Vec<u8>
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub enum TransactionData {
     TransferAsset(TransferAssetInput),
+    TransferOwnershipAndPossession(TransferAssetOwnershipAndPossessionInput),
     IssueAsset(IssueAssetInput),
     IpoBid(ContractIpoBid),
     SubmitWork { seed: MiningSeed, nonce: Nonce },
     SendToMany(SendToManyInput),
+    /// input of a contract procedure which is not decoded
+    Contract(ContractCall),
+    /// tag of a plain transfer, e.g. the deposit account at an exchange, see `is_memo`
+    Memo(Vec<u8>),
+    /// input of a transaction to an identity which is not a contract
     Unknown(Vec<u8>),
 
     #[default]
@@ -139,32 +364,70 @@ impl ToBytes for TransactionData {
     fn to_bytes(&self) -> Vec<u8> {
         match self {
             TransactionData::TransferAsset(d) => d.to_bytes(),
+            TransactionData::TransferOwnershipAndPossession(d) => d.to_bytes(),
             TransactionData::IssueAsset(d) => d.to_bytes(),
             TransactionData::IpoBid(d) => d.to_bytes(),
             TransactionData::SubmitWork { seed, nonce } => [seed.to_bytes(), nonce.to_bytes()].concat(),
             TransactionData::SendToMany(d) => d.to_bytes(),
-            TransactionData::Unknown(d) => d.clone(),
+            TransactionData::Contract(call) => call.data.clone(),
+            TransactionData::Memo(d) | TransactionData::Unknown(d) => d.clone(),
             TransactionData::None => vec![]
         }
     }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        match self {
+            TransactionData::TransferAsset(d) => d.write_to(buf),
+            TransactionData::TransferOwnershipAndPossession(d) => d.write_to(buf),
+            TransactionData::IssueAsset(d) => d.write_to(buf),
+            TransactionData::IpoBid(d) => d.write_to(buf),
+            TransactionData::SubmitWork { seed, nonce } => {
+                seed.write_to(buf);
+                nonce.write_to(buf);
+            },
+            TransactionData::SendToMany(d) => d.write_to(buf),
+            TransactionData::Contract(call) => buf.extend_from_slice(&call.data),
+            TransactionData::Memo(d) | TransactionData::Unknown(d) => buf.extend_from_slice(d),
+            TransactionData::None => ()
+        }
+    }
+
+    fn encoded_len(&self) -> usize {
+        match self {
+            TransactionData::TransferAsset(d) => d.encoded_len(),
+            TransactionData::TransferOwnershipAndPossession(d) => d.encoded_len(),
+            TransactionData::IssueAsset(d) => d.encoded_len(),
+            TransactionData::IpoBid(d) => d.encoded_len(),
+            TransactionData::SubmitWork { seed, nonce } => seed.encoded_len() + nonce.encoded_len(),
+            TransactionData::SendToMany(d) => d.encoded_len(),
+            TransactionData::Contract(call) => call.data.len(),
+            TransactionData::Memo(d) | TransactionData::Unknown(d) => d.len(),
+            TransactionData::None => 0
+        }
+    }
 }
 
 impl TransactionData {
-    pub fn sanitize_transaction(&self, tx: &mut RawTransaction) {
+    /// sets the fields of `tx` the data determines, `tx` is left as is if the data does not fit a transaction
+    pub fn sanitize_transaction(&self, tx: &mut RawTransaction) -> Result<(), InputTooLong> {
+        if self.encoded_len() > MAX_INPUT_SIZE {
+            return Err(InputTooLong(self.encoded_len()))
+        }
+
         match self {
             Self::IpoBid(_) => {
                 tx.input_type = 0;
                 tx.input_size = core::mem::size_of::<ContractIpoBid>() as u16;
             },
             Self::IssueAsset(_) => {
-                tx.input_type = 1;
+                tx.input_type = QX_ISSUE_ASSET;
                 tx.input_size = core::mem::size_of::<IssueAssetInput>() as u16;
                 tx.to = QXID;
                 tx.amount = ISSUE_ASSET_FEE;
             },
             Self::SubmitWork { .. } => {
                 tx.to = QubicId::default();
-                tx.amount = 1_000_000;
+                tx.amount = SUBMIT_WORK_BURN;
                 tx.input_type = 2;
                 tx.input_size = (core::mem::size_of::<MiningSeed>() as u16) + (core::mem::size_of::<Nonce>() as u16);
             },
@@ -174,21 +437,73 @@ impl TransactionData {
                 tx.to = QXID;
                 tx.input_size = core::mem::size_of::<TransferAssetInput>() as u16;
             },
+            Self::TransferOwnershipAndPossession(_) => {
+                tx.input_type = QX_TRANSFER_OWNERSHIP_AND_POSSESSION;
+                tx.amount = TRANSFER_FEE;
+                tx.to = QXID;
+                tx.input_size = core::mem::size_of::<TransferAssetOwnershipAndPossessionInput>() as u16;
+            },
             Self::SendToMany(SendToManyInput { ids: _, amounts }) => {
                 tx.input_type = 1;
                 tx.input_size = core::mem::size_of::<SendToManyInput>() as u16;
                 tx.to = QubicId::from_contract_id(SEND_TO_MANY_CONTRACT_INDEX);
                 tx.amount += amounts.iter().sum::<u64>();
             },
+            Self::Contract(call) => {
+                tx.input_type = call.input_type;
+                tx.input_size = call.data.len() as u16;
+                tx.to = QubicId::from_contract_id(call.contract_index);
+            },
+            Self::Memo(memo) => {
+                tx.input_type = 0;
+                tx.input_size = memo.len() as u16;
+            },
             Self::Unknown(data) => {
                 tx.input_size = data.len() as u16;
             },
             Self::None => ()
         }
+
+        Ok(())
+    }
+
+    /// `Contract` if `raw` is sent to a contract, `Unknown` otherwise
+    fn undecoded(raw: &RawTransaction, data: Vec<u8>) -> Self {
+        match contract_index(&raw.to) {
+            Some(contract_index) => Self::Contract(ContractCall { contract_index, input_type: raw.input_type, data }),
+            None => Self::Unknown(data)
+        }
+    }
+
+    /// the memo of a plain transfer carrying one
+    pub fn memo(&self) -> Option<&[u8]> {
+        match self {
+            Self::Memo(memo) => Some(memo),
+            _ => None
+        }
+    }
+
+    /// name of the variant, `None` for plain transfers
+    pub fn name(&self) -> &'static str {
+        match self {
+            Self::TransferAsset(_) => "TransferAsset",
+            Self::TransferOwnershipAndPossession(_) => "TransferOwnershipAndPossession",
+            Self::IssueAsset(_) => "IssueAsset",
+            Self::IpoBid(_) => "IpoBid",
+            Self::SubmitWork { .. } => "SubmitWork",
+            Self::SendToMany(_) => "SendToMany",
+            Self::Contract(_) => "Contract",
+            Self::Memo(_) => "Memo",
+            Self::Unknown(_) => "Unknown",
+            Self::None => "None"
+        }
     }
 }
 
 /// ### Heap allocated Transaction with custom serializer/deserializer for the data field
+///
+/// The form every client API sends and receives, `RawTransaction`, `Transaction` and `Call` convert into it.
+/// A `TransactionWithData` without input converts back into a `Transaction` with `try_from`
 /// ```
 /// use qubic_types::QubicWallet;
 /// 
@@ -198,6 +513,7 @@ impl TransactionData {
 /// ```
 #[derive(Debug, Clone, PartialEq, Eq, Hash, Default)]
 #[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
 pub struct TransactionWithData {
     pub raw_transaction: RawTransaction,
     pub data: TransactionData,
@@ -208,13 +524,20 @@ set_message_type!(TransactionWithData, MessageType::BroadcastTransaction);
 
 impl ToBytes for TransactionWithData {
     fn to_bytes(&self) -> Vec<u8> {
-        let mut data = self.raw_transaction.to_bytes();
+        let mut data = Vec::with_capacity(self.encoded_len());
+        self.write_to(&mut data);
 
-        data.extend(self.data.to_bytes());
+        data
+    }
 
-        data.extend(self.signature.to_bytes());
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        self.raw_transaction.write_le(buf);
+        self.data.write_to(buf);
+        self.signature.write_to(buf);
+    }
 
-        data
+    fn encoded_len(&self) -> usize {
+        core::mem::size_of::<RawTransaction>() + self.data.encoded_len() + core::mem::size_of::<Signature>()
     }
 }
 
@@ -224,13 +547,16 @@ impl FromBytes for TransactionWithData {
             return Err(qubic_types::errors::ByteEncodingError::InvalidMinimumDataLength { expected_min: core::mem::size_of::<RawTransaction>() + core::mem::size_of::<Signature>(), found: data.len() })
         }
 
-        let raw_tx = unsafe {
-            read_unaligned(data.as_ptr() as *const RawTransaction)
-        };
+        let raw_tx = RawTransaction::read_le(data[..core::mem::size_of::<RawTransaction>()].try_into().unwrap());
 
-        let sig = unsafe {
-            read_unaligned(&data[data.len() - core::mem::size_of::<Signature>()] as *const u8 as *const Signature)
-        };
+        // the decoders below trust `input_size`, a peer may claim more input than it sent
+        let expected = core::mem::size_of::<RawTransaction>() + raw_tx.input_size as usize + core::mem::size_of::<Signature>();
+
+        if data.len() != expected {
+            return Err(qubic_types::errors::ByteEncodingError::InvalidDataLength { expected, found: data.len() })
+        }
+
+        let sig = Signature::from_bytes(&data[data.len() - core::mem::size_of::<Signature>()..])?;
 
         let tx_data = data[core::mem::size_of::<RawTransaction>()..data.len()-core::mem::size_of::<Signature>()].to_vec();
 
@@ -238,14 +564,16 @@ impl FromBytes for TransactionWithData {
 
         match raw_tx.input_type {
             0 => {
-    	        if raw_tx.input_size == 16 {
+                if is_memo(&raw_tx) && tx_data.len() == raw_tx.input_size as usize {
+                    data = TransactionData::Memo(tx_data);
+                } else if raw_tx.input_size == 16 {
                     let bid = ContractIpoBid::from_bytes(&tx_data)?;
 
                     data = TransactionData::IpoBid(bid);
                 } else if raw_tx.input_size == 0 {
                     data = TransactionData::None;
                 } else {
-                    data = TransactionData::Unknown(tx_data);
+                    data = TransactionData::undecoded(&raw_tx, tx_data);
                 }
             },
             1 => {
@@ -262,17 +590,24 @@ impl FromBytes for TransactionWithData {
 
                     data = TransactionData::SendToMany(input);
                 } else {
-                    data = TransactionData::Unknown(tx_data);
+                    data = TransactionData::undecoded(&raw_tx, tx_data);
                 }
             },
             2 => {
                 if raw_tx.input_size as usize == (core::mem::size_of::<MiningSeed>() + core::mem::size_of::<Nonce>())
                 && tx_data.len() == (core::mem::size_of::<MiningSeed>() + core::mem::size_of::<Nonce>())
-                && raw_tx.amount == 1_000_000 {
+                && raw_tx.amount == SUBMIT_WORK_BURN {
                     data = TransactionData::SubmitWork {
                         seed: MiningSeed::from_bytes(&tx_data[..core::mem::size_of::<MiningSeed>()])?,
                         nonce: Nonce::from_bytes(&tx_data[core::mem::size_of::<MiningSeed>()..])?
                     };
+                } else if raw_tx.to == QXID && raw_tx.input_size as usize == core::mem::size_of::<TransferAssetOwnershipAndPossessionInput>()
+                && tx_data.len() == core::mem::size_of::<TransferAssetOwnershipAndPossessionInput>() {
+                    let input = unsafe {
+                        read_unaligned(tx_data.as_ptr() as *const TransferAssetOwnershipAndPossessionInput)
+                    };
+
+                    data = TransactionData::TransferOwnershipAndPossession(input);
                 } else if raw_tx.input_size as usize == core::mem::size_of::<IssueAssetInput>() {
                     let input = unsafe {
                         read_unaligned(tx_data.as_ptr() as *const TransferAssetInput)
@@ -280,14 +615,14 @@ impl FromBytes for TransactionWithData {
 
                     data = TransactionData::TransferAsset(input);
                 } else {
-                    data = TransactionData::Unknown(tx_data);
+                    data = TransactionData::undecoded(&raw_tx, tx_data);
                 }
             }
             _ => {
                 if raw_tx.input_size == 0 {
                     data = TransactionData::None;
                 } else {
-                    data = TransactionData::Unknown(tx_data);
+                    data = TransactionData::undecoded(&raw_tx, tx_data);
                 }
             }
         }
@@ -298,21 +633,143 @@ impl FromBytes for TransactionWithData {
     }
 }
 
+/// Result of `TransactionWithData::validate`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct ValidationReport {
+    pub signature_valid: bool,
+    /// `input_size` equals the length of the data
+    pub size_consistent: bool,
+    /// the data variant matches `input_type` and the destination
+    pub type_consistent: bool,
+    /// the amount covers the fee or burn of known contract calls, and for SendToMany the amounts with the fee of the
+    /// schedule, always true for other transactions
+    pub amount_plausible: bool
+}
+
+impl ValidationReport {
+    /// size and type are consistent, the data can be trusted to describe the transaction
+    pub fn is_well_formed(&self) -> bool {
+        self.size_consistent && self.type_consistent
+    }
+
+    pub fn is_valid(&self) -> bool {
+        self.signature_valid && self.is_well_formed() && self.amount_plausible
+    }
+}
+
+impl TransactionWithData {
+    /// checks the signature and whether `raw_transaction` agrees with the data, `verify` only checks the signature.
+    /// The SendToMany fee is set by the contract, `fees` should come from `fee_schedule` of a client
+    pub fn validate(&self, fees: &FeeSchedule) -> ValidationReport {
+        self.validate_with(self.verify(), fees)
+    }
+
+    /// `validate` with the result of a signature check done beforehand, e.g. by `verify_batch`
+    pub fn validate_with(&self, signature_valid: bool, fees: &FeeSchedule) -> ValidationReport {
+        let tx = &self.raw_transaction;
+        let is_contract = |id: &QubicId| *id != QubicId::default() && id.0[8..].iter().all(|b| *b == 0);
+
+        let type_consistent = match &self.data {
+            TransactionData::IpoBid(_) => tx.input_type == 0 && is_contract(&tx.to),
+            TransactionData::IssueAsset(_) => tx.input_type == QX_ISSUE_ASSET && tx.to == QXID,
+            TransactionData::SubmitWork { .. } => tx.input_type == 2 && tx.to == QubicId::default(),
+            TransactionData::TransferAsset(_) => tx.input_type == 2 && tx.to == QXID,
+            TransactionData::TransferOwnershipAndPossession(_) => tx.input_type == QX_TRANSFER_OWNERSHIP_AND_POSSESSION && tx.to == QXID,
+            TransactionData::SendToMany(_) => tx.input_type == 1 && tx.to == QubicId::from_contract_id(SEND_TO_MANY_CONTRACT_INDEX),
+            TransactionData::Contract(call) => tx.input_type == call.input_type && tx.to == QubicId::from_contract_id(call.contract_index),
+            TransactionData::Memo(memo) => tx.input_type == 0 && contract_index(&tx.to).is_none() && memo.len() <= MAX_MEMO_SIZE,
+            TransactionData::Unknown(_) | TransactionData::None => true
+        };
+
+        let amount_plausible = match &self.data {
+            TransactionData::IpoBid(_) => tx.amount == 0,
+            TransactionData::IssueAsset(_) => tx.amount >= ISSUE_ASSET_FEE,
+            TransactionData::SubmitWork { .. } => tx.amount == SUBMIT_WORK_BURN,
+            TransactionData::TransferAsset(_)
+            | TransactionData::TransferOwnershipAndPossession(_) => tx.amount >= TRANSFER_FEE,
+            TransactionData::SendToMany(SendToManyInput { amounts, .. }) => amounts.iter().try_fold(fees.send_to_many_fee, |sum, amount| sum.checked_add(*amount)).is_some_and(|sum| tx.amount >= sum),
+            TransactionData::Memo(_) => tx.amount > 0,
+            TransactionData::Contract(_) | TransactionData::Unknown(_) | TransactionData::None => true
+        };
+
+        ValidationReport {
+            signature_valid,
+            size_consistent: tx.input_size as usize == self.data.to_bytes().len(),
+            type_consistent,
+            amount_plausible
+        }
+    }
+}
+
+/// What a transaction does, classified by its data and destination
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub enum TransactionKind {
+    /// transfer without input or with a memo to an identity which is not a contract
+    Transfer,
+    QxCall,
+    QuotteryCall,
+    SubmitWork,
+    SendToMany,
+    /// call of the contract with the index
+    OtherContract(u32),
+    /// input sent to an identity which is not a contract
+    Unknown
+}
+
+impl TransactionWithData {
+    /// decoded calls are classified by their data, everything else sent to a contract (including plain transfers)
+    /// by the contract
+    pub fn kind(&self) -> TransactionKind {
+        let tx = &self.raw_transaction;
+
+        match &self.data {
+            TransactionData::SubmitWork { .. } => TransactionKind::SubmitWork,
+            TransactionData::SendToMany(_) => TransactionKind::SendToMany,
+            TransactionData::TransferAsset(_)
+            | TransactionData::TransferOwnershipAndPossession(_)
+            | TransactionData::IssueAsset(_) => TransactionKind::QxCall,
+            TransactionData::Memo(_) => TransactionKind::Transfer,
+            TransactionData::Unknown(_) => TransactionKind::Unknown,
+            TransactionData::IpoBid(_) | TransactionData::Contract(_) | TransactionData::None => match contract_index(&tx.to) {
+                Some(QX_CONTRACT_INDEX) => TransactionKind::QxCall,
+                Some(QUOTTERY_CONTRACT_INDEX) => TransactionKind::QuotteryCall,
+                Some(index) => TransactionKind::OtherContract(index),
+                None if self.data == TransactionData::None => TransactionKind::Transfer,
+                None => TransactionKind::Unknown
+            }
+        }
+    }
+}
+
 impl GetSigner for TransactionWithData {
     fn get_signer(&self) -> &QubicId {
         &self.raw_transaction.from
     }
 }
 
+/// hash of an encoded transaction, e.g. the payload of a packet which is about to be sent
+pub fn transaction_hash(encoded: &[u8]) -> QubicTxHash {
+    let mut hash = [0; 32];
+    let mut kg = KangarooTwelve::new(b"");
+    kg.update(encoded);
+    kg.into_xof().squeeze(&mut hash);
+
+    QubicTxHash(hash)
+}
+
 impl From<TransactionWithData> for QubicTxHash {
     fn from(val: TransactionWithData) -> Self {
-        let mut hash = [0; 32];
-        let tx_bytes = val.to_bytes();
-        let mut kg = KangarooTwelve::new(b"");
-        kg.update(&tx_bytes);
-        kg.into_xof().squeeze(&mut hash);
+        QubicTxHash::from(&val)
+    }
+}
 
-        QubicTxHash(hash)
+impl From<&TransactionWithData> for QubicTxHash {
+    fn from(val: &TransactionWithData) -> Self {
+        transaction_hash(&val.to_bytes())
     }
 }
 
@@ -326,6 +783,36 @@ impl From<Transaction> for TransactionWithData {
     }
 }
 
+/// A `TransactionWithData` with input does not fit a `Transaction`, the transaction is handed back
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct DataNotEmpty(pub TransactionWithData);
+
+impl core::fmt::Display for DataNotEmpty {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "Transaction carries {} bytes of input, a Transaction without data cannot hold them", self.0.data.encoded_len())
+    }
+}
+
+#[cfg(feature = "std")]
+impl std::error::Error for DataNotEmpty {}
+
+impl TryFrom<TransactionWithData> for Transaction {
+    type Error = DataNotEmpty;
+
+    fn try_from(value: TransactionWithData) -> Result<Self, Self::Error> {
+        match value.data.encoded_len() {
+            0 => Ok(Self { raw_transaction: value.raw_transaction, signature: value.signature }),
+            _ => Err(DataNotEmpty(value))
+        }
+    }
+}
+
+impl From<&TransactionWithData> for RawTransaction {
+    fn from(value: &TransactionWithData) -> Self {
+        value.raw_transaction
+    }
+}
+
 impl<T> From<Call<T>> for TransactionWithData
     where T: Copy + Into<TransactionData>
 {
@@ -348,6 +835,13 @@ impl From<RawTransaction> for TransactionWithData {
     }
 }
 
+/// Checks the target tick of a transaction before it is built, e.g. whether the tick lies in the current epoch
+pub trait TickValidator {
+    type Err;
+
+    fn validate_tick(&self, tick: u32) -> Result<(), Self::Err>;
+}
+
 #[derive(Debug, Clone, Default)]
 pub struct TransactionBuilder<'a> {
     raw_tx: RawTransaction,
@@ -375,13 +869,41 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
-    pub fn with_tx_data(mut self, data: TransactionData) -> Self {
+    /// rejects data exceeding `MAX_INPUT_SIZE`, `build` would not be able to encode its size
+    pub fn with_tx_data(mut self, data: TransactionData) -> Result<Self, InputTooLong> {
+        if data.encoded_len() > MAX_INPUT_SIZE {
+            return Err(InputTooLong(data.encoded_len()))
+        }
+
         self.data = data;
+        Ok(self)
+    }
+
+    /// tags the transfer with `memo`, see `is_memo`. An empty memo sends no input
+    pub fn with_memo(mut self, memo: impl Into<Vec<u8>>) -> Result<Self, MemoTooLong> {
+        let memo = memo.into();
+
+        self.data = match memo.len() {
+            0 => TransactionData::None,
+            len if len > MAX_MEMO_SIZE => return Err(MemoTooLong(len)),
+            _ => TransactionData::Memo(memo)
+        };
+
+        Ok(self)
+    }
+
+    /// takes a `Tick` or a raw `u32` tick
+    pub fn with_tick(mut self, tick: impl Into<Tick>) -> Self {
+        self.raw_tx.tick = tick.into().get();
         self
     }
 
-    pub fn with_tick(mut self, tick: u32) -> Self {
-        self.raw_tx.tick = tick;
+    /// pays the identity of a scanned `qubic:` URI, amount and tick are only taken if the URI sets them
+    pub fn with_uri(mut self, uri: &QubicUri) -> Self {
+        self.raw_tx.to = uri.identity;
+        self.raw_tx.amount = uri.amount.unwrap_or(self.raw_tx.amount);
+        self.raw_tx.tick = uri.tick.unwrap_or(self.raw_tx.tick);
+
         self
     }
 
@@ -392,6 +914,18 @@ impl<'a> TransactionBuilder<'a> {
         self
     }
 
+    /// adds the fees of the operation set by `with_tx_data` to the amount, amounts forwarded by the contract (e.g. SendToMany) are added by `build`
+    pub fn with_estimated_fees<E: FeeEstimator>(mut self, estimator: &E) -> Result<Self, E::Err> {
+        self.raw_tx.amount += estimator.estimate(&self.data)?.fees();
+        Ok(self)
+    }
+
+    /// rejects the target tick set so far if `validator` considers it stale
+    pub fn preflight<V: TickValidator>(self, validator: &V) -> Result<Self, V::Err> {
+        validator.validate_tick(self.raw_tx.tick)?;
+        Ok(self)
+    }
+
     pub fn with_signing_wallet(mut self, wallet: &'a QubicWallet) -> Self {
         self.signer = Some(wallet);
         self
@@ -399,7 +933,7 @@ impl<'a> TransactionBuilder<'a> {
 
     pub fn build(mut self) -> TransactionWithData {
         if let Some(signer) = self.signer {
-            self.data.sanitize_transaction(&mut self.raw_tx);
+            self.data.sanitize_transaction(&mut self.raw_tx).expect("input size is checked by with_tx_data");
 
             self.raw_tx.from = signer.public_key;
 
@@ -413,7 +947,7 @@ impl<'a> TransactionBuilder<'a> {
 
             tx
         } else {
-            self.data.sanitize_transaction(&mut self.raw_tx);
+            self.data.sanitize_transaction(&mut self.raw_tx).expect("input size is checked by with_tx_data");
 
             TransactionWithData {
                 raw_transaction: self.raw_tx,
@@ -422,4 +956,440 @@ impl<'a> TransactionBuilder<'a> {
             }
         }
     }
-}
\ No newline at end of file
+}
+/// raw bytes of a QX transfer from `[1; 32]` with the ids `[2; 32]`, `[3; 32]`, `[4; 32]`, asset "QX" and 5 units
+#[cfg(test)]
+fn qx_transfer_fixture(input_type: u16) -> Vec<u8> {
+    let mut fixture = vec![1; 32];
+    fixture.extend_from_slice(&QXID.0);
+    fixture.extend_from_slice(&[0x40, 0x42, 0x0f, 0, 0, 0, 0, 0]); // 1_000_000 fee
+    fixture.extend_from_slice(&[0x39, 0x30, 0, 0]); // tick 12345
+    fixture.extend_from_slice(&input_type.to_le_bytes());
+    fixture.extend_from_slice(&[112, 0]);
+    fixture.extend([[2; 32], [3; 32], [4; 32]].concat());
+    fixture.extend_from_slice(b"QX\0\0\0\0\0\0");
+    fixture.extend_from_slice(&[5, 0, 0, 0, 0, 0, 0, 0]);
+    fixture.extend_from_slice(&[9; 64]);
+
+    fixture
+}
+
+#[test]
+fn test_qx_transfer_decoding() {
+    use core::str::FromStr;
+    use super::assets::AssetName;
+
+    let tx = |data: TransactionData| {
+        let mut raw_transaction = RawTransaction { from: QubicId([1; 32]), tick: 12345, ..Default::default() };
+        data.sanitize_transaction(&mut raw_transaction).unwrap();
+
+        TransactionWithData { raw_transaction, data, signature: Signature([9; 64]) }
+    };
+    let (a, b, c, asset_name) = (QubicId([2; 32]), QubicId([3; 32]), QubicId([4; 32]), AssetName::from_str("QX").unwrap());
+
+    let tx = tx(TransactionData::TransferOwnershipAndPossession(TransferAssetOwnershipAndPossessionInput { issuer: a, possessor: b, new_owner: c, asset_name, number_of_units: 5 }));
+    let mut fixture = qx_transfer_fixture(QX_TRANSFER_OWNERSHIP_AND_POSSESSION);
+
+    assert_eq!(tx.to_bytes(), fixture);
+    assert_eq!(TransactionWithData::from_bytes(&fixture).unwrap(), tx);
+
+    // the layout is only decoded for transactions sent to QX, other contracts keep the raw input
+    fixture[32] = 2;
+
+    let tx = TransactionWithData::from_bytes(&fixture).unwrap();
+    assert!(matches!(&tx.data, TransactionData::Contract(ContractCall { contract_index: 2, input_type: QX_TRANSFER_OWNERSHIP_AND_POSSESSION, data }) if data.len() == core::mem::size_of::<TransferAssetOwnershipAndPossessionInput>()));
+    assert_eq!(tx.kind(), TransactionKind::QuotteryCall);
+    assert_eq!(tx.to_bytes(), fixture);
+
+    fixture[40] = 1;
+    assert!(matches!(TransactionWithData::from_bytes(&fixture).unwrap().data, TransactionData::Unknown(_)));
+}
+
+#[test]
+fn test_undecoded_contract_calls() {
+    use qubic_types::QubicWallet;
+    use super::qlogging::QUTIL_CONTRACT_INDEX;
+
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let signed = |to: QubicId, input_type: u16, data: &[u8]| {
+        let mut tx = TransactionWithData {
+            raw_transaction: RawTransaction { from: wallet.public_key, to, amount: 10, tick: 100, input_type, input_size: data.len() as u16 },
+            data: TransactionData::Unknown(data.to_vec()),
+            ..Default::default()
+        };
+        tx.sign(&wallet).unwrap();
+
+        TransactionWithData::from_bytes(&tx.to_bytes()).unwrap()
+    };
+
+    // calls of unregistered contract indices keep the index and input type
+    let call = signed(QubicId::from_contract_id(77), 3, &[1, 2, 3]);
+    assert_eq!(call.data, TransactionData::Contract(ContractCall { contract_index: 77, input_type: 3, data: vec![1, 2, 3] }));
+    assert_eq!(call.kind(), TransactionKind::OtherContract(77));
+    assert!(call.validate(&FeeSchedule::default()).is_valid());
+    assert_eq!(call.data.name(), "Contract");
+
+    // built calls address the contract
+    let mut raw = RawTransaction::default();
+    call.data.sanitize_transaction(&mut raw).unwrap();
+    assert_eq!((raw.to, raw.input_type, raw.input_size), (QubicId::from_contract_id(77), 3, 3));
+
+    assert_eq!(signed(QubicId::from_contract_id(QUTIL_CONTRACT_INDEX), 5, &[0; 8]).kind(), TransactionKind::OtherContract(QUTIL_CONTRACT_INDEX));
+    assert_eq!(signed(QXID, 200, &[0; 8]).kind(), TransactionKind::QxCall);
+    assert_eq!(signed(QXID, 0, &[]).kind(), TransactionKind::QxCall);
+    assert_eq!(signed(QubicId([9; 32]), 0, &[]).kind(), TransactionKind::Transfer);
+    assert_eq!(signed(QubicId([9; 32]), 3, &[1]).kind(), TransactionKind::Unknown);
+}
+
+#[test]
+fn test_memos() {
+    use qubic_types::QubicWallet;
+
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let decoded = |to: QubicId, amount: u64, data: &[u8]| {
+        let mut tx = TransactionWithData {
+            raw_transaction: RawTransaction { from: wallet.public_key, to, amount, tick: 100, input_type: 0, input_size: data.len() as u16 },
+            data: TransactionData::Unknown(data.to_vec()),
+            ..Default::default()
+        };
+        tx.sign(&wallet).unwrap();
+
+        TransactionWithData::from_bytes(&tx.to_bytes()).unwrap().data
+    };
+
+    // memos have at least one and at most `MAX_MEMO_SIZE` bytes
+    assert_eq!(decoded(QubicId([9; 32]), 1_000, b"1"), TransactionData::Memo(b"1".to_vec()));
+    assert_eq!(decoded(QubicId([9; 32]), 1_000, &[7; MAX_MEMO_SIZE]), TransactionData::Memo(vec![7; MAX_MEMO_SIZE]));
+    assert_eq!(decoded(QubicId([9; 32]), 1_000, &[7; MAX_MEMO_SIZE + 1]), TransactionData::Unknown(vec![7; MAX_MEMO_SIZE + 1]));
+    assert_eq!(decoded(QubicId([9; 32]), 1_000, &[]), TransactionData::None);
+    // 16 bytes are a memo on a transfer and a bid on a contract
+    assert_eq!(decoded(QubicId([9; 32]), 1_000, &[7; 16]).name(), "Memo");
+    assert_eq!(decoded(QubicId::from_contract_id(5), 0, &[7; 16]).name(), "IpoBid");
+    // no memo without an amount or to a contract
+    assert_eq!(decoded(QubicId([9; 32]), 0, b"user-42"), TransactionData::Unknown(b"user-42".to_vec()));
+    assert_eq!(decoded(QubicId::from_contract_id(77), 1_000, b"user-42").name(), "Contract");
+
+    let tx = TransactionBuilder::new().with_to_id(QubicId([9; 32])).with_amount(1_000).with_tick(100).with_memo("user-42").unwrap().with_signing_wallet(&wallet).build();
+    assert_eq!((tx.raw_transaction.input_type, tx.raw_transaction.input_size), (0, 7));
+    assert_eq!(tx.data.memo(), Some(b"user-42".as_slice()));
+    assert_eq!(tx.kind(), TransactionKind::Transfer);
+    assert!(tx.validate(&FeeSchedule::default()).is_valid());
+    assert!(!TransactionWithData { raw_transaction: RawTransaction { amount: 0, ..tx.raw_transaction }, ..tx.clone() }.validate(&FeeSchedule::default()).amount_plausible);
+    assert_eq!(TransactionWithData::from_bytes(&tx.to_bytes()).unwrap(), tx);
+
+    assert!(TransactionBuilder::new().with_memo([1; MAX_MEMO_SIZE]).is_ok());
+    assert_eq!(TransactionBuilder::new().with_memo([1; MAX_MEMO_SIZE + 1]).unwrap_err(), MemoTooLong(MAX_MEMO_SIZE + 1));
+    assert_eq!(TransactionBuilder::new().with_memo("").unwrap().build().data, TransactionData::None);
+}
+
+#[test]
+fn test_tick_transactions_report() {
+    use crate::consts::MAX_NUMBER_OF_CONTRACTS;
+    use super::time::QubicTime;
+
+    let transactions = (1..=3).map(|amount| TransactionWithData::from(RawTransaction { amount, tick: 100, ..Default::default() })).collect::<Vec<_>>();
+
+    let mut tick_data = TickData {
+        computor_index: 0,
+        epoch: 100,
+        tick: 100,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 25 },
+        time_lock: [0; 32],
+        transaction_digest: [QubicTxHash::default(); NUMBER_OF_TRANSACTION_PER_TICK],
+        contract_fees: [0; MAX_NUMBER_OF_CONTRACTS],
+        signature: Signature::default()
+    };
+
+    for (slot, tx) in transactions.iter().enumerate() {
+        tick_data.transaction_digest[slot] = tx.clone().into();
+    }
+
+    let all = TransactionFlags::all();
+    let report = TickTransactionsReport::new(transactions.clone(), &all, Some(&tick_data));
+    assert_eq!((report.requested, report.received, report.tick_data_digest_count, report.complete), (NUMBER_OF_TRANSACTION_PER_TICK, 3, Some(3), true));
+    assert!(transactions.iter().all(|tx| tick_data.verify_transaction(tx)));
+    assert!(!tick_data.verify_transaction(&RawTransaction { amount: 4, tick: 100, ..Default::default() }.into()));
+
+    // the peer pruned the last transaction
+    let report = TickTransactionsReport::new(transactions[..2].to_vec(), &all, Some(&tick_data));
+    assert_eq!((report.received, report.complete), (2, false));
+
+    // slots which were not requested are not missing
+    let mut flags = TransactionFlags::all();
+    flags.0[0] = 1 << 2;
+    assert!(!flags.is_requested(2) && flags.is_requested(1));
+
+    let report = TickTransactionsReport::new(transactions[..2].to_vec(), &flags, Some(&tick_data));
+    assert_eq!((report.requested, report.complete), (NUMBER_OF_TRANSACTION_PER_TICK - 1, true));
+
+    // without tick data completeness is unknown
+    let report = TickTransactionsReport::new(transactions, &all, None);
+    assert_eq!((report.tick_data_digest_count, report.complete), (None, false));
+}
+
+#[test]
+fn test_dedup_and_order_transactions() {
+    use crate::consts::MAX_NUMBER_OF_CONTRACTS;
+    use super::time::QubicTime;
+
+    let [a, b, c, unlisted] = [1, 2, 3, 4].map(|amount| TransactionWithData::from(RawTransaction { amount, tick: 100, ..Default::default() }));
+
+    let mut tick_data = TickData {
+        computor_index: 0,
+        epoch: 100,
+        tick: 100,
+        time: QubicTime { milliseconds: 0, second: 0, minute: 0, hour: 0, day: 1, month: 1, year: 25 },
+        time_lock: [0; 32],
+        transaction_digest: [QubicTxHash::default(); NUMBER_OF_TRANSACTION_PER_TICK],
+        contract_fees: [0; MAX_NUMBER_OF_CONTRACTS],
+        signature: Signature::default()
+    };
+
+    // slots of the tick data do not have to be contiguous
+    for (slot, tx) in [(0, &a), (5, &b), (9, &c)] {
+        tick_data.transaction_digest[slot] = tx.clone().into();
+    }
+
+    let mut transactions = vec![c.clone(), unlisted.clone(), a.clone(), c.clone(), b.clone(), a.clone()];
+    dedup_transactions(&mut transactions);
+    assert_eq!(transactions, [c.clone(), unlisted.clone(), a.clone(), b.clone()]);
+
+    order_transactions(&mut transactions, &tick_data);
+    assert_eq!(transactions, [a, b, c, unlisted]);
+}
+
+#[test]
+fn test_verify_batch() {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+
+    let transactions: Vec<_> = (0..64u64).map(|i| {
+        let mut tx = TransactionWithData::from(RawTransaction { from: wallet.public_key, to: QubicId([2; 32]), amount: i, tick: 100, ..Default::default() });
+        tx.sign(&wallet).unwrap();
+
+        match i % 4 {
+            // tampered after signing
+            1 => tx.raw_transaction.amount += 1,
+            // rejected by the pre-filter
+            2 => tx.signature.0[63] = 1,
+            3 => tx.signature.0[15] |= 0x80,
+            _ => ()
+        }
+
+        tx
+    }).collect();
+
+    let individually: Vec<_> = transactions.iter().map(|tx| tx.verify()).collect();
+    assert_eq!(verify_batch(&transactions), individually);
+    assert_eq!(individually.iter().filter(|valid| **valid).count(), 16);
+
+    assert!(transactions.iter().step_by(4).all(signature_plausible));
+    assert!(!transactions.iter().skip(2).step_by(4).any(signature_plausible));
+    assert!(verify_batch(&[]).is_empty());
+}
+
+#[test]
+fn test_validate_transaction() {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let work = TransactionData::SubmitWork { seed: MiningSeed([1; 32]), nonce: Nonce([2; 32]) };
+    let fees = FeeSchedule { send_to_many_fee: 10 };
+    let valid = ValidationReport { signature_valid: true, size_consistent: true, type_consistent: true, amount_plausible: true };
+
+    let signed = |data: TransactionData, tamper: fn(&mut RawTransaction)| {
+        let mut tx = TransactionBuilder::new().with_tx_data(data).unwrap().build();
+        tx.raw_transaction.from = wallet.public_key;
+        tamper(&mut tx.raw_transaction);
+
+        // signed without `Sign`, which decodes the data again and would replace the inconsistent variant
+        let bytes = tx.to_bytes();
+        let mut digest = [0; 32];
+        let mut kg = KangarooTwelve::new(b"");
+        kg.update(&bytes[..bytes.len() - core::mem::size_of::<Signature>()]);
+        kg.into_xof().squeeze(&mut digest);
+        tx.signature = wallet.sign_raw(digest);
+
+        tx
+    };
+
+    let tx = signed(work.clone(), |_| ());
+    assert_eq!(tx.validate(&fees), valid);
+    assert!(tx.validate(&fees).is_valid());
+
+    // the signature no longer covers the transaction
+    let mut tampered = tx.clone();
+    tampered.raw_transaction.tick += 1;
+    assert_eq!(tampered.validate(&fees), ValidationReport { signature_valid: false, ..valid });
+
+    let tx = signed(work.clone(), |tx| tx.input_size = 32);
+    assert_eq!(tx.validate(&fees), ValidationReport { size_consistent: false, ..valid });
+    assert!(!tx.validate(&fees).is_well_formed());
+
+    let tx = signed(TransactionData::Unknown(vec![1, 2, 3]), |tx| tx.input_size = 4);
+    assert_eq!(tx.validate(&fees), ValidationReport { size_consistent: false, ..valid });
+
+    let tx = signed(work.clone(), |tx| tx.input_type = 1);
+    assert_eq!(tx.validate(&fees), ValidationReport { type_consistent: false, ..valid });
+
+    let tx = signed(work.clone(), |tx| tx.to = QXID);
+    assert_eq!(tx.validate(&fees), ValidationReport { type_consistent: false, ..valid });
+
+    let tx = signed(work, |tx| tx.amount = 0);
+    assert_eq!(tx.validate(&fees), ValidationReport { amount_plausible: false, ..valid });
+    assert!(tx.validate(&fees).is_well_formed() && !tx.validate(&fees).is_valid());
+
+    let send_to_many = TransactionData::SendToMany(SendToManyInput { ids: [QubicId::default(); 25], amounts: [10; 25] });
+    assert_eq!(signed(send_to_many.clone(), |tx| tx.amount = 260).validate(&fees), valid);
+    // the amounts alone don't pay the contract fee
+    assert_eq!(signed(send_to_many.clone(), |_| ()).validate(&fees), ValidationReport { amount_plausible: false, ..valid });
+    assert_eq!(signed(send_to_many, |tx| tx.amount = 259).validate(&fees), ValidationReport { amount_plausible: false, ..valid });
+
+    // plain transfers carry no data to contradict
+    assert_eq!(signed(TransactionData::None, |tx| tx.amount = 1).validate(&fees), valid);
+}
+
+#[test]
+fn test_transaction_conversions() {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let built = TransactionBuilder::new().with_to_id(QubicId([2; 32])).with_amount(1_000).with_tick(500).with_signing_wallet(&wallet).build();
+    assert!(built.verify());
+
+    // a transaction without input round-trips and keeps its hash, the signature is hashed by both
+    let tx = Transaction::try_from(built.clone()).unwrap();
+    assert_eq!((tx.raw_transaction, tx.signature), (RawTransaction::from(&built), built.signature));
+    assert_eq!(QubicTxHash::from(tx), QubicTxHash::from(&built));
+    assert_eq!(TransactionWithData::from(tx), built);
+    assert_ne!(QubicTxHash::from(Transaction { signature: Signature::default(), ..tx }), QubicTxHash::from(&built));
+
+    let with_input = TransactionBuilder::new().with_tx_data(TransactionData::Unknown(vec![1, 2, 3])).unwrap().with_signing_wallet(&wallet).build();
+    assert_eq!(Transaction::try_from(with_input.clone()), Err(DataNotEmpty(with_input.clone())));
+    assert_eq!(RawTransaction::from(&with_input).input_size, 3);
+    // empty unknown input is no input
+    assert!(Transaction::try_from(TransactionWithData { data: TransactionData::Unknown(vec![]), ..built }).is_ok());
+}
+
+#[test]
+fn test_builder_with_uri() {
+    use core::str::FromStr;
+
+    let uri = QubicUri::from_str("qubic:BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK?amount=1000&label=Coffee").unwrap();
+    let tx = TransactionBuilder::new().with_tick(500).with_uri(&uri).build();
+
+    assert_eq!((tx.raw_transaction.to, tx.raw_transaction.amount, tx.raw_transaction.tick), (uri.identity, 1000, 500));
+}
+
+#[test]
+fn test_builder_with_typed_tick() {
+    let info = CurrentTickInfo { tick_duration: 1, epoch: 101, tick: 12_000_000, number_of_aligned_votes: 0, number_of_misaligned_votes: 0, initial_tick: 11_990_000 };
+    assert_eq!((info.tick(), info.epoch(), info.initial_tick()), (Tick(12_000_000), qubic_types::Epoch(101), Tick(11_990_000)));
+
+    // typed and raw ticks build the same transaction
+    let tx = TransactionBuilder::new().with_tick(info.tick() + 5).build();
+    assert_eq!(tx.raw_transaction.tick, 12_000_005);
+    assert_eq!(TransactionBuilder::new().with_tick(12_000_005).build().raw_transaction, tx.raw_transaction);
+}
+
+#[cfg(all(feature = "std", not(feature = "wasm")))]
+#[test]
+fn test_write_to() {
+    use super::{assets::{AssetName, IssueAssetInput}, Packet};
+
+    let raw_transaction = RawTransaction { from: QubicId([1; 32]), amount: 100, tick: 12_000_000, ..Default::default() };
+    let data = [
+        TransactionData::None,
+        TransactionData::Unknown(vec![3; 17]),
+        TransactionData::SubmitWork { seed: MiningSeed([1; 32]), nonce: Nonce([2; 32]) },
+        TransactionData::IssueAsset(IssueAssetInput { name: AssetName([4; 8]), number_of_units: 10, unit_of_measurement: 0, number_of_decimal_places: 0 })
+    ];
+
+    for data in data {
+        let packet = Packet::new(TransactionWithData { raw_transaction, data, signature: Signature([5; 64]) }, false).unwrap();
+        let concatenated = [packet.header.to_bytes(), packet.data.raw_transaction.to_bytes(), packet.data.data.to_bytes(), packet.data.signature.to_bytes()].concat();
+
+        // appended after the existing content
+        let mut buf = vec![9];
+        packet.write_to(&mut buf);
+
+        assert_eq!(buf[1..], concatenated);
+        assert_eq!(packet.to_bytes(), concatenated);
+        assert_eq!((packet.encoded_len(), packet.header.get_size()), (concatenated.len(), concatenated.len()));
+    }
+}
+
+#[test]
+fn test_transaction_status() {
+    let info = CurrentTickInfo { tick_duration: 1, epoch: 100, tick: 12_000_000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 11_900_000 };
+
+    assert_eq!(TransactionStatus::from_current_tick(12_000_005, &info), Some(TransactionStatus::Pending { target_tick: 12_000_005, current_tick: 12_000_000 }));
+    assert_eq!(TransactionStatus::from_current_tick(12_000_000, &info), Some(TransactionStatus::Pending { target_tick: 12_000_000, current_tick: 12_000_000 }));
+    assert_eq!(TransactionStatus::from_current_tick(11_999_999, &info), None);
+    assert!(matches!(TransactionStatus::from_current_tick(11_899_999, &info), Some(TransactionStatus::Unknown { .. })));
+
+    let mut tick_data = TickData::from_bytes(&vec![0; core::mem::size_of::<TickData>()]).unwrap();
+    tick_data.transaction_digest[3] = QubicTxHash([1; 32]);
+
+    assert_eq!(TransactionStatus::in_tick(&QubicTxHash([1; 32]), [QubicTxHash([1; 32])], Some(&tick_data)), TransactionStatus::Executed);
+    assert_eq!(TransactionStatus::in_tick(&QubicTxHash([2; 32]), [QubicTxHash([1; 32]), QubicTxHash([2; 32])], Some(&tick_data)), TransactionStatus::Included);
+    assert_eq!(TransactionStatus::in_tick(&QubicTxHash([1; 32]), [], None), TransactionStatus::NotIncluded);
+
+    assert_eq!(serde_json::to_value(TransactionStatus::Pending { target_tick: 2, current_tick: 1 }).unwrap(), serde_json::json!({ "status": "pending", "targetTick": 2, "currentTick": 1 }));
+    assert_eq!(serde_json::to_value(TransactionStatus::NotIncluded).unwrap(), serde_json::json!({ "status": "notIncluded" }));
+    assert_eq!(serde_json::from_value::<TransactionStatus>(serde_json::json!({ "status": "unknown", "reason": "pruned" })).unwrap(), TransactionStatus::Unknown { reason: "pruned".to_owned() });
+}
+
+#[test]
+fn test_input_size_limit() {
+    let mut raw = RawTransaction::default();
+    assert_eq!(TransactionData::Unknown(vec![1; MAX_INPUT_SIZE]).sanitize_transaction(&mut raw), Ok(()));
+    assert_eq!(raw.input_size as usize, MAX_INPUT_SIZE);
+
+    let tx = TransactionBuilder::new().with_tx_data(TransactionData::Unknown(vec![1; MAX_INPUT_SIZE])).unwrap().build();
+    assert_eq!(tx.raw_transaction.input_size as usize, MAX_INPUT_SIZE);
+
+    // one past the limit and one past what `input_size` holds, which was truncated by the cast before
+    for len in [MAX_INPUT_SIZE + 1, u16::MAX as usize + 1] {
+        let mut raw = RawTransaction::default();
+        assert_eq!(TransactionData::Unknown(vec![1; len]).sanitize_transaction(&mut raw), Err(InputTooLong(len)));
+        assert_eq!(raw, RawTransaction::default());
+
+        assert_eq!(TransactionBuilder::new().with_tx_data(TransactionData::Unknown(vec![1; len])).unwrap_err(), InputTooLong(len));
+    }
+}
+
+#[test]
+fn test_input_size_mismatch() {
+    use qubic_types::errors::{ByteEncodingError, QubicError};
+
+    let min = core::mem::size_of::<RawTransaction>() + core::mem::size_of::<Signature>();
+    let encoded = |input_type: u16, input_size: u16, input: &[u8]| {
+        let raw = RawTransaction { from: QubicId([1; 32]), to: QXID, amount: TRANSFER_FEE, input_type, input_size, ..Default::default() };
+
+        [raw.to_bytes(), input.to_vec(), vec![9; 64]].concat()
+    };
+
+    // sizes of the decoded layouts claimed without carrying the input, they were read past the buffer before
+    for (input_type, input_size) in [
+        (0, core::mem::size_of::<ContractIpoBid>()),
+        (1, core::mem::size_of::<IssueAssetInput>()),
+        (1, core::mem::size_of::<SendToManyInput>()),
+        (2, core::mem::size_of::<TransferAssetOwnershipAndPossessionInput>()),
+        (2, core::mem::size_of::<IssueAssetInput>()),
+        (0, u16::MAX as usize)
+    ] {
+        let bytes = encoded(input_type, input_size as u16, &[]);
+        assert_eq!(TransactionWithData::from_bytes(&bytes), Err(ByteEncodingError::InvalidDataLength { expected: min + input_size, found: min }));
+
+        let bytes = encoded(input_type, input_size as u16, &vec![1; input_size - 1]);
+        assert_eq!(TransactionWithData::from_bytes(&bytes), Err(ByteEncodingError::InvalidDataLength { expected: min + input_size, found: min + input_size - 1 }));
+    }
+
+    // more input than claimed
+    assert_eq!(TransactionWithData::from_bytes(&encoded(0, 0, &[1])), Err(ByteEncodingError::InvalidDataLength { expected: min, found: min + 1 }));
+    assert_eq!(TransactionWithData::from_bytes(&encoded(9, 2, &[1, 2, 3])), Err(ByteEncodingError::InvalidDataLength { expected: min + 2, found: min + 3 }));
+
+    let tx = TransactionWithData::from_bytes(&encoded(9, 3, &[1, 2, 3])).unwrap();
+    assert_eq!(tx.data, TransactionData::Contract(ContractCall { contract_index: QX_CONTRACT_INDEX, input_type: 9, data: vec![1, 2, 3] }));
+
+    // a transaction claiming input it does not carry is not signed instead of panicking
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let mut tx = TransactionWithData::from(RawTransaction { from: wallet.public_key, input_size: 8, ..Default::default() });
+    assert_eq!(tx.sign(&wallet), Err(QubicError::UndecodableSigned(ByteEncodingError::InvalidDataLength { expected: min + 8, found: min })));
+}
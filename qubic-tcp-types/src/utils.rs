@@ -6,4 +6,22 @@ pub trait QubicRequest {
 
 pub trait QubicReturnType {
     type ReturnType;
-}
\ No newline at end of file
+}
+
+/// (de)serializes arrays exceeding the 32 elements serde supports out of the box
+#[cfg(feature = "serde")]
+pub(crate) mod serde_big_array {
+    use alloc::vec::Vec;
+    use serde::{de::Error, Deserialize, Deserializer, Serialize, Serializer};
+
+    pub fn serialize<S: Serializer, T: Serialize, const N: usize>(array: &[T; N], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_seq(array)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>, T: Deserialize<'de>, const N: usize>(deserializer: D) -> Result<[T; N], D::Error> {
+        let elements = Vec::<T>::deserialize(deserializer)?;
+        let len = elements.len();
+
+        elements.try_into().map_err(|_| D::Error::invalid_length(len, &"an array of fixed length"))
+    }
+}
@@ -0,0 +1,526 @@
+use core::{mem::{offset_of, size_of}, net::Ipv4Addr};
+
+use alloc::boxed::Box;
+use qubic_types::{errors::ByteEncodingError, traits::FromBytes, MiningSeed, Nonce, QubicId, QubicTxHash, Signature, H256};
+use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+
+use crate::{consts::{MAX_NUMBER_OF_CONTRACTS, NUMBER_OF_COMPUTORS, NUMBER_OF_TRANSACTION_PER_TICK}, events::NetworkEvent, types::{ticks::{Tick, TickData}, time::QubicTime, transactions::{RawTransaction, TransactionWithData}, BroadcastMessage, Computors, ExchangePublicPeers}, Header, MessageType};
+
+/// copies `N` bytes at `offset`, the views check the length of their buffer up front
+fn read<const N: usize>(data: &[u8], offset: usize) -> [u8; N] {
+    let mut bytes = [0; N];
+    bytes.copy_from_slice(&data[offset..offset + N]);
+
+    bytes
+}
+
+fn read_time(data: &[u8], offset: usize) -> QubicTime {
+    let [milliseconds @ .., second, minute, hour, day, month, year] = read::<8>(data, offset);
+
+    QubicTime { milliseconds: u16::from_le_bytes(milliseconds), second, minute, hour, day, month, year }
+}
+
+fn check_length(data: &[u8], expected: usize) -> Result<(), ByteEncodingError> {
+    match data.len() == expected {
+        true => Ok(()),
+        false => Err(ByteEncodingError::InvalidDataLength { expected, found: data.len() })
+    }
+}
+
+/// Transaction borrowed from a received buffer, the input is only parsed by `to_owned`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TransactionView<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> TransactionView<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ByteEncodingError> {
+        let min = size_of::<RawTransaction>() + size_of::<Signature>();
+
+        if data.len() < min {
+            return Err(ByteEncodingError::InvalidMinimumDataLength { expected_min: min, found: data.len() })
+        }
+
+        let view = Self { data };
+        check_length(data, min + view.input_size() as usize)?;
+
+        Ok(view)
+    }
+
+    pub fn from(&self) -> QubicId {
+        QubicId(read(self.data, offset_of!(RawTransaction, from)))
+    }
+
+    pub fn to(&self) -> QubicId {
+        QubicId(read(self.data, offset_of!(RawTransaction, to)))
+    }
+
+    pub fn amount(&self) -> u64 {
+        u64::from_le_bytes(read(self.data, offset_of!(RawTransaction, amount)))
+    }
+
+    pub fn tick(&self) -> u32 {
+        u32::from_le_bytes(read(self.data, offset_of!(RawTransaction, tick)))
+    }
+
+    pub fn input_type(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(RawTransaction, input_type)))
+    }
+
+    pub fn input_size(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(RawTransaction, input_size)))
+    }
+
+    pub fn input(&self) -> &'a [u8] {
+        &self.data[size_of::<RawTransaction>()..self.data.len() - size_of::<Signature>()]
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(read(self.data, self.data.len() - size_of::<Signature>()))
+    }
+
+    pub fn raw_transaction(&self) -> RawTransaction {
+        RawTransaction { from: self.from(), to: self.to(), amount: self.amount(), tick: self.tick(), input_type: self.input_type(), input_size: self.input_size() }
+    }
+
+    /// hash of the transaction bytes as received
+    pub fn hash(&self) -> QubicTxHash {
+        let mut hash = [0; 32];
+        let mut kg = KangarooTwelve::new(b"");
+        kg.update(self.data);
+        kg.into_xof().squeeze(&mut hash);
+
+        QubicTxHash(hash)
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> Result<TransactionWithData, ByteEncodingError> {
+        TransactionWithData::from_bytes(self.data)
+    }
+}
+
+/// Quorum tick vote borrowed from a received buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickView<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> TickView<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ByteEncodingError> {
+        check_length(data, size_of::<Tick>())?;
+
+        Ok(Self { data })
+    }
+
+    pub fn computor_index(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(Tick, computor_index)))
+    }
+
+    pub fn epoch(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(Tick, epoch)))
+    }
+
+    pub fn tick(&self) -> u32 {
+        u32::from_le_bytes(read(self.data, offset_of!(Tick, tick)))
+    }
+
+    pub fn time(&self) -> QubicTime {
+        read_time(self.data, offset_of!(Tick, time))
+    }
+
+    pub fn prev_spectrum_digest(&self) -> H256 {
+        H256(read(self.data, offset_of!(Tick, prev_spectrum_digest)))
+    }
+
+    pub fn prev_universe_digest(&self) -> H256 {
+        H256(read(self.data, offset_of!(Tick, prev_universe_digest)))
+    }
+
+    pub fn prev_computor_digest(&self) -> H256 {
+        H256(read(self.data, offset_of!(Tick, prev_computor_digest)))
+    }
+
+    pub fn transaction_digest(&self) -> H256 {
+        H256(read(self.data, offset_of!(Tick, transaction_digest)))
+    }
+
+    pub fn expected_next_tick_transaction_digest(&self) -> H256 {
+        H256(read(self.data, offset_of!(Tick, expected_next_tick_transaction_digest)))
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(read(self.data, offset_of!(Tick, signature)))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> Tick {
+        Tick {
+            computor_index: self.computor_index(),
+            epoch: self.epoch(),
+            tick: self.tick(),
+            time: self.time(),
+            prev_resource_testing_digest: u64::from_le_bytes(read(self.data, offset_of!(Tick, prev_resource_testing_digest))),
+            salted_resource_testing_digest: u64::from_le_bytes(read(self.data, offset_of!(Tick, salted_resource_testing_digest))),
+            prev_spectrum_digest: self.prev_spectrum_digest(),
+            prev_universe_digest: self.prev_universe_digest(),
+            prev_computor_digest: self.prev_computor_digest(),
+            salted_spectrum_digest: H256(read(self.data, offset_of!(Tick, salted_spectrum_digest))),
+            salted_universe_digest: H256(read(self.data, offset_of!(Tick, salted_universe_digest))),
+            salted_computor_digest: H256(read(self.data, offset_of!(Tick, salted_computor_digest))),
+            transaction_digest: self.transaction_digest(),
+            expected_next_tick_transaction_digest: self.expected_next_tick_transaction_digest(),
+            signature: self.signature()
+        }
+    }
+}
+
+/// Tick data borrowed from a received buffer, avoids copying the digests of all transaction slots
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TickDataView<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> TickDataView<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ByteEncodingError> {
+        check_length(data, size_of::<TickData>())?;
+
+        Ok(Self { data })
+    }
+
+    pub fn computor_index(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(TickData, computor_index)))
+    }
+
+    pub fn epoch(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(TickData, epoch)))
+    }
+
+    pub fn tick(&self) -> u32 {
+        u32::from_le_bytes(read(self.data, offset_of!(TickData, tick)))
+    }
+
+    pub fn time(&self) -> QubicTime {
+        read_time(self.data, offset_of!(TickData, time))
+    }
+
+    /// `None` if `slot` exceeds the transaction slots of a tick
+    pub fn transaction_digest(&self, slot: usize) -> Option<QubicTxHash> {
+        (slot < NUMBER_OF_TRANSACTION_PER_TICK).then(|| QubicTxHash(read(self.data, offset_of!(TickData, transaction_digest) + slot * size_of::<QubicTxHash>())))
+    }
+
+    /// digests of the occupied transaction slots
+    pub fn transaction_digests(&self) -> impl Iterator<Item = QubicTxHash> + 'a {
+        let data = self.data;
+
+        (0..NUMBER_OF_TRANSACTION_PER_TICK)
+            .map(move |slot| QubicTxHash(read(data, offset_of!(TickData, transaction_digest) + slot * size_of::<QubicTxHash>())))
+            .filter(|digest| *digest != QubicTxHash::default())
+    }
+
+    /// `None` if `contract_index` exceeds the number of contracts
+    pub fn contract_fee(&self, contract_index: usize) -> Option<u64> {
+        (contract_index < MAX_NUMBER_OF_CONTRACTS).then(|| u64::from_le_bytes(read(self.data, offset_of!(TickData, contract_fees) + contract_index * size_of::<u64>())))
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(read(self.data, offset_of!(TickData, signature)))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> Box<TickData> {
+        let mut tick_data = TickData::new_boxed();
+        tick_data.computor_index = self.computor_index();
+        tick_data.epoch = self.epoch();
+        tick_data.tick = self.tick();
+        tick_data.time = self.time();
+        tick_data.time_lock = read(self.data, offset_of!(TickData, time_lock));
+        tick_data.signature = self.signature();
+
+        for slot in 0..NUMBER_OF_TRANSACTION_PER_TICK {
+            tick_data.transaction_digest[slot] = QubicTxHash(read(self.data, offset_of!(TickData, transaction_digest) + slot * size_of::<QubicTxHash>()));
+        }
+
+        for contract_index in 0..MAX_NUMBER_OF_CONTRACTS {
+            tick_data.contract_fees[contract_index] = u64::from_le_bytes(read(self.data, offset_of!(TickData, contract_fees) + contract_index * size_of::<u64>()));
+        }
+
+        tick_data
+    }
+}
+
+/// Broadcasted message borrowed from a received buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct BroadcastMessageView<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> BroadcastMessageView<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ByteEncodingError> {
+        check_length(data, size_of::<BroadcastMessage>())?;
+
+        Ok(Self { data })
+    }
+
+    pub fn source_public_key(&self) -> QubicId {
+        QubicId(read(self.data, offset_of!(BroadcastMessage, source_public_key)))
+    }
+
+    pub fn destination_public_key(&self) -> QubicId {
+        QubicId(read(self.data, offset_of!(BroadcastMessage, destination_public_key)))
+    }
+
+    pub fn gamming_nonce(&self) -> Nonce {
+        Nonce(read(self.data, offset_of!(BroadcastMessage, gamming_nonce)))
+    }
+
+    pub fn solution_mining_seed(&self) -> MiningSeed {
+        MiningSeed(read(self.data, offset_of!(BroadcastMessage, solution_mining_seed)))
+    }
+
+    pub fn solution_nonce(&self) -> Nonce {
+        Nonce(read(self.data, offset_of!(BroadcastMessage, solution_nonce)))
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(read(self.data, offset_of!(BroadcastMessage, signature)))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> BroadcastMessage {
+        BroadcastMessage {
+            source_public_key: self.source_public_key(),
+            destination_public_key: self.destination_public_key(),
+            gamming_nonce: self.gamming_nonce(),
+            solution_mining_seed: self.solution_mining_seed(),
+            solution_nonce: self.solution_nonce(),
+            signature: self.signature()
+        }
+    }
+}
+
+/// Computor list of an epoch borrowed from a received buffer
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ComputorsView<'a> {
+    data: &'a [u8]
+}
+
+impl<'a> ComputorsView<'a> {
+    pub fn new(data: &'a [u8]) -> Result<Self, ByteEncodingError> {
+        check_length(data, size_of::<Computors>())?;
+
+        Ok(Self { data })
+    }
+
+    pub fn epoch(&self) -> u16 {
+        u16::from_le_bytes(read(self.data, offset_of!(Computors, epoch)))
+    }
+
+    /// `None` past the last computor
+    pub fn public_key(&self, index: usize) -> Option<QubicId> {
+        (index < NUMBER_OF_COMPUTORS).then(|| QubicId(read(self.data, offset_of!(Computors, public_key) + index * size_of::<QubicId>())))
+    }
+
+    pub fn signature(&self) -> Signature {
+        Signature(read(self.data, offset_of!(Computors, signature)))
+    }
+
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.data
+    }
+
+    pub fn to_owned(&self) -> Box<Computors> {
+        let mut computors = Computors::new_boxed();
+        computors.epoch = self.epoch();
+        computors.signature = self.signature();
+
+        for (index, public_key) in computors.public_key.iter_mut().enumerate() {
+            *public_key = QubicId(read(self.data, offset_of!(Computors, public_key) + index * size_of::<QubicId>()));
+        }
+
+        computors
+    }
+}
+
+/// Borrowed counterpart of `NetworkEvent`, the derived `EpochChanged` and `Disconnected` have none
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum NetworkEventView<'a> {
+    ExchangePublicPeers(ExchangePublicPeers),
+    BroadcastMessage(BroadcastMessageView<'a>),
+    BroadcastTransaction(TransactionView<'a>),
+    BroadcastTick(TickView<'a>),
+    BroadcastFutureTick(TickDataView<'a>),
+    BroadcastComputors(ComputorsView<'a>)
+}
+
+impl<'a> NetworkEventView<'a> {
+    /// `None` if messages of `message_type` are no network events
+    pub fn parse(message_type: MessageType, payload: &'a [u8]) -> Result<Option<Self>, ByteEncodingError> {
+        Ok(Some(match message_type {
+            MessageType::ExchangePublicPeers => {
+                check_length(payload, size_of::<ExchangePublicPeers>())?;
+
+                Self::ExchangePublicPeers(ExchangePublicPeers { peers: core::array::from_fn(|i| Ipv4Addr::from(read::<4>(payload, i * 4))) })
+            },
+            MessageType::BroadcastMessage => Self::BroadcastMessage(BroadcastMessageView::new(payload)?),
+            MessageType::BroadcastTransaction => Self::BroadcastTransaction(TransactionView::new(payload)?),
+            MessageType::BroadcastTick => Self::BroadcastTick(TickView::new(payload)?),
+            MessageType::BroadcastFutureTickData => Self::BroadcastFutureTick(TickDataView::new(payload)?),
+            MessageType::BroadcastComputors => Self::BroadcastComputors(ComputorsView::new(payload)?),
+            _ => return Ok(None)
+        }))
+    }
+
+    pub fn to_owned(&self) -> Result<NetworkEvent, ByteEncodingError> {
+        Ok(match self {
+            Self::ExchangePublicPeers(peers) => NetworkEvent::ExchangePublicPeers(*peers),
+            Self::BroadcastMessage(message) => NetworkEvent::BroadcastMessage(Box::new(message.to_owned())),
+            Self::BroadcastTransaction(tx) => NetworkEvent::BroadcastTransaction(Box::new(tx.to_owned()?)),
+            Self::BroadcastTick(tick) => NetworkEvent::BroadcastTick(Box::new(tick.to_owned())),
+            Self::BroadcastFutureTick(tick_data) => NetworkEvent::BroadcastFutureTick(tick_data.to_owned()),
+            Self::BroadcastComputors(computors) => NetworkEvent::BroadcastComputors(computors.to_owned())
+        })
+    }
+}
+
+/// Message as received from a peer, the view is only parsed on request
+#[derive(Debug, Clone, Copy)]
+pub struct RawEvent<'a> {
+    /// address of the peer which sent the message
+    pub source: &'a str,
+    pub header: &'a Header,
+    pub payload: &'a [u8]
+}
+
+impl<'a> RawEvent<'a> {
+    pub fn view(&self) -> Result<Option<NetworkEventView<'a>>, ByteEncodingError> {
+        NetworkEventView::parse(self.header.message_type, self.payload)
+    }
+}
+
+#[test]
+fn test_transaction_view() {
+    use qubic_types::traits::ToBytes;
+    use crate::types::transactions::TransactionData;
+
+    let tx = TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, tick: 12_000_000, input_type: 0, input_size: 3 },
+        data: TransactionData::Memo(vec![4, 5, 6]),
+        signature: Signature([3; 64])
+    };
+    let bytes = tx.to_bytes();
+    let view = TransactionView::new(&bytes).unwrap();
+
+    assert_eq!(view.raw_transaction(), tx.raw_transaction);
+    assert_eq!((view.from(), view.to(), view.amount(), view.tick()), (QubicId([1; 32]), QubicId([2; 32]), 100, 12_000_000));
+    assert_eq!(view.input(), [4, 5, 6]);
+    assert_eq!(view.signature(), tx.signature);
+    assert_eq!(view.hash(), QubicTxHash::from(tx.clone()));
+    assert_eq!(view.to_owned().unwrap(), tx);
+
+    // the input has to match its announced size
+    assert!(matches!(TransactionView::new(&bytes[..bytes.len() - 1]), Err(ByteEncodingError::InvalidDataLength { expected: 147, found: 146 })));
+    assert!(matches!(TransactionView::new(&[bytes.as_slice(), &[0]].concat()), Err(ByteEncodingError::InvalidDataLength { expected: 147, found: 148 })));
+    assert!(matches!(TransactionView::new(&bytes[..100]), Err(ByteEncodingError::InvalidMinimumDataLength { expected_min: 144, found: 100 })));
+}
+
+#[test]
+fn test_event_views() {
+    use qubic_types::{traits::ToBytes, U24};
+
+    let time = QubicTime { milliseconds: 500, second: 1, minute: 2, hour: 3, day: 4, month: 5, year: 24 };
+    let tick = Tick {
+        computor_index: 1,
+        epoch: 100,
+        tick: 12_000_000,
+        time,
+        prev_resource_testing_digest: 1,
+        salted_resource_testing_digest: 2,
+        prev_spectrum_digest: [1; 32].into(),
+        prev_universe_digest: [2; 32].into(),
+        prev_computor_digest: [3; 32].into(),
+        salted_spectrum_digest: [4; 32].into(),
+        salted_universe_digest: [5; 32].into(),
+        salted_computor_digest: [6; 32].into(),
+        transaction_digest: [7; 32].into(),
+        expected_next_tick_transaction_digest: [8; 32].into(),
+        signature: Signature([9; 64])
+    };
+
+    let mut tick_data = TickData::new_boxed();
+    tick_data.computor_index = 2;
+    tick_data.epoch = 100;
+    tick_data.tick = 12_000_001;
+    tick_data.time = time;
+    tick_data.time_lock = [1; 32];
+    tick_data.signature = Signature([2; 64]);
+    tick_data.transaction_digest[0] = QubicTxHash([3; 32]);
+    tick_data.transaction_digest[1023] = QubicTxHash([4; 32]);
+    tick_data.contract_fees[1] = 1_000;
+
+    let message = BroadcastMessage {
+        source_public_key: QubicId([1; 32]),
+        destination_public_key: QubicId([2; 32]),
+        gamming_nonce: Nonce([3; 32]),
+        solution_mining_seed: MiningSeed([4; 32]),
+        solution_nonce: Nonce([5; 32]),
+        signature: Signature([6; 64])
+    };
+    let peers = ExchangePublicPeers { peers: [Ipv4Addr::new(1, 2, 3, 4), Ipv4Addr::LOCALHOST, Ipv4Addr::UNSPECIFIED, Ipv4Addr::new(5, 6, 7, 8)] };
+
+    let tick_bytes = tick.to_bytes();
+    let view = TickView::new(&tick_bytes).unwrap();
+    assert_eq!((view.computor_index(), view.epoch(), view.tick(), view.time()), (1, 100, 12_000_000, time));
+    assert_eq!(view.transaction_digest(), tick.transaction_digest);
+
+    let tick_data_bytes = tick_data.to_bytes();
+    let view = TickDataView::new(&tick_data_bytes).unwrap();
+    assert_eq!(view.transaction_digests().collect::<Vec<_>>(), [QubicTxHash([3; 32]), QubicTxHash([4; 32])]);
+    assert_eq!((view.transaction_digest(1023), view.transaction_digest(1024)), (Some(QubicTxHash([4; 32])), None));
+    assert_eq!((view.contract_fee(1), view.contract_fee(MAX_NUMBER_OF_CONTRACTS)), (Some(1_000), None));
+
+    let mut computors = Computors::new_boxed();
+    computors.epoch = 100;
+    computors.signature = Signature([7; 64]);
+    computors.public_key[0] = QubicId([8; 32]);
+    computors.public_key[675] = QubicId([9; 32]);
+
+    let computors_bytes = computors.to_bytes();
+    let view = ComputorsView::new(&computors_bytes).unwrap();
+    assert_eq!((view.epoch(), view.public_key(0), view.public_key(NUMBER_OF_COMPUTORS - 1), view.public_key(NUMBER_OF_COMPUTORS)), (100, Some(QubicId([8; 32])), Some(QubicId([9; 32])), None));
+
+    let message_bytes = message.to_bytes();
+    let peers_bytes = peers.to_bytes();
+
+    let events = [
+        (MessageType::BroadcastTick, tick_bytes.as_slice(), NetworkEvent::BroadcastTick(Box::new(tick))),
+        (MessageType::BroadcastFutureTickData, &tick_data_bytes, NetworkEvent::BroadcastFutureTick(tick_data)),
+        (MessageType::BroadcastMessage, &message_bytes, NetworkEvent::BroadcastMessage(Box::new(message))),
+        (MessageType::ExchangePublicPeers, &peers_bytes, NetworkEvent::ExchangePublicPeers(peers)),
+        (MessageType::BroadcastComputors, &computors_bytes, NetworkEvent::BroadcastComputors(computors))
+    ];
+
+    for (message_type, payload, event) in events {
+        assert_eq!(NetworkEventView::parse(message_type, payload).unwrap().unwrap().to_owned().unwrap(), event);
+
+        // truncated payloads are rejected instead of read past their end
+        assert!(NetworkEventView::parse(message_type, &payload[..payload.len() - 1]).is_err());
+    }
+
+    assert_eq!(NetworkEventView::parse(MessageType::RequestEntity, &[]).unwrap(), None);
+
+    let header = Header::new_with_dejavu(U24::try_from(8 + tick_bytes.len()).unwrap(), MessageType::BroadcastTick, 0);
+    let raw = RawEvent { source: "127.0.0.1:21841", header: &header, payload: &tick_bytes };
+    assert!(matches!(raw.view(), Ok(Some(NetworkEventView::BroadcastTick(view))) if view.tick() == 12_000_000));
+}
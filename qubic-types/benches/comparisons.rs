@@ -0,0 +1,20 @@
+use criterion::{black_box, criterion_group, criterion_main, Criterion};
+use qubic_types::{ConstantTimeEq, QubicWallet};
+
+fn bench_comparisons(c: &mut Criterion) {
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let signature = wallet.sign(10u64);
+    let mut other = signature;
+    other.0[63] ^= 1;
+
+    c.bench_function("signature eq", |b| b.iter(|| black_box(signature) == black_box(other)));
+    c.bench_function("signature ct_eq", |b| b.iter(|| bool::from(black_box(signature).ct_eq(&black_box(other)))));
+
+    c.bench_function("id eq", |b| b.iter(|| black_box(wallet.public_key) == black_box(wallet.public_key)));
+    c.bench_function("id ct_eq", |b| b.iter(|| bool::from(black_box(wallet.public_key).ct_eq(&black_box(wallet.public_key)))));
+
+    c.bench_function("verify", |b| b.iter(|| wallet.public_key.verify(black_box(10u64), black_box(signature))));
+}
+
+criterion_group!(benches, bench_comparisons);
+criterion_main!(benches);
@@ -0,0 +1,22 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use qubic_types::{batch, QubicId};
+
+fn ids() -> Vec<QubicId> {
+    (0..10_000u64).map(|i| QubicId::from_le_u64([i, i.wrapping_mul(31), i.wrapping_mul(131), i.wrapping_mul(1031)])).collect()
+}
+
+fn bench_identities(c: &mut Criterion) {
+    let ids = ids();
+    let identities = ids.iter().map(|id| id.get_identity()).collect::<Vec<_>>();
+    let identities = identities.iter().map(String::as_str).collect::<Vec<_>>();
+
+    c.bench_function("get_identity loop", |b| b.iter(|| ids.iter().map(|id| id.get_identity()).collect::<Vec<_>>()));
+    c.bench_function("identities_from_ids", |b| b.iter(|| batch::identities_from_ids(&ids)));
+    c.bench_function("identities_from_ids_iter", |b| b.iter(|| batch::identities_from_ids_iter(ids.iter().copied()).count()));
+
+    c.bench_function("from_str loop", |b| b.iter(|| identities.iter().map(|id| id.parse::<QubicId>()).collect::<Vec<_>>()));
+    c.bench_function("ids_from_strs", |b| b.iter(|| batch::ids_from_strs(&identities)));
+}
+
+criterion_group!(benches, bench_identities);
+criterion_main!(benches);
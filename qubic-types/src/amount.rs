@@ -0,0 +1,143 @@
+//! JSON encoding of QU amounts
+//!
+//! Amounts exceed the 2^53 integers JavaScript numbers represent exactly, JS clients want them as strings while data
+//! pipelines want numbers. `QuAmount` serializes as the `NumberFormat` of the current thread (numbers unless set
+//! with `NumberFormat::scope`) and deserializes from both. Amount fields use it through the serde `with` attribute:
+//!
+//! ```
+//! #[derive(serde::Serialize, serde::Deserialize)]
+//! struct Transfer {
+//!     #[serde(with = "qubic_types::amount")]
+//!     amount: u64
+//! }
+//! ```
+//!
+//! Formats which are not human readable always encode amounts as integers.
+
+use core::fmt;
+
+use serde::{de::{self, Visitor}, Deserialize, Deserializer, Serialize, Serializer};
+
+/// Encoding of amounts in human readable formats
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+#[serde(rename_all = "lowercase")]
+pub enum NumberFormat {
+    #[default]
+    Number,
+    String
+}
+
+#[cfg(feature = "std")]
+std::thread_local! {
+    static FORMAT: core::cell::Cell<NumberFormat> = const { core::cell::Cell::new(NumberFormat::Number) };
+}
+
+impl NumberFormat {
+    /// format amounts are serialized with on this thread
+    #[cfg(feature = "std")]
+    pub fn current() -> Self {
+        FORMAT.with(|format| format.get())
+    }
+
+    /// without `std` amounts are always numbers
+    #[cfg(not(feature = "std"))]
+    pub fn current() -> Self {
+        Self::Number
+    }
+
+    /// serializes the amounts of `f` in this format, the previous format is restored afterwards (also on panics)
+    #[cfg(feature = "std")]
+    pub fn scope<R>(self, f: impl FnOnce() -> R) -> R {
+        struct Restore(NumberFormat);
+
+        impl Drop for Restore {
+            fn drop(&mut self) {
+                FORMAT.with(|format| format.set(self.0));
+            }
+        }
+
+        let _restore = Restore(FORMAT.with(|format| format.replace(self)));
+
+        f()
+    }
+}
+
+/// Amount of QU, see the module documentation
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct QuAmount(pub u64);
+
+impl From<u64> for QuAmount {
+    fn from(value: u64) -> Self {
+        Self(value)
+    }
+}
+
+impl From<QuAmount> for u64 {
+    fn from(value: QuAmount) -> Self {
+        value.0
+    }
+}
+
+impl Serialize for QuAmount {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match NumberFormat::current() {
+            NumberFormat::String if serializer.is_human_readable() => serializer.collect_str(&self.0),
+            _ => serializer.serialize_u64(self.0)
+        }
+    }
+}
+
+struct AmountVisitor;
+
+impl Visitor<'_> for AmountVisitor {
+    type Value = QuAmount;
+
+    fn expecting(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        formatter.write_str("an amount as an unsigned integer or a string of digits")
+    }
+
+    fn visit_u64<E: de::Error>(self, v: u64) -> Result<Self::Value, E> {
+        Ok(QuAmount(v))
+    }
+
+    fn visit_i64<E: de::Error>(self, v: i64) -> Result<Self::Value, E> {
+        u64::try_from(v).map(QuAmount).map_err(|_| E::invalid_value(de::Unexpected::Signed(v), &self))
+    }
+
+    fn visit_str<E: de::Error>(self, v: &str) -> Result<Self::Value, E> {
+        v.parse().map(QuAmount).map_err(|_| E::invalid_value(de::Unexpected::Str(v), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for QuAmount {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        match deserializer.is_human_readable() {
+            true => deserializer.deserialize_any(AmountVisitor),
+            false => deserializer.deserialize_u64(AmountVisitor)
+        }
+    }
+}
+
+pub fn serialize<S: Serializer>(amount: &u64, serializer: S) -> Result<S::Ok, S::Error> {
+    QuAmount(*amount).serialize(serializer)
+}
+
+pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    QuAmount::deserialize(deserializer).map(u64::from)
+}
+
+/// `with` module of optional amounts
+pub mod option {
+    use serde::{Deserialize, Deserializer, Serialize, Serializer};
+
+    use super::QuAmount;
+
+    pub fn serialize<S: Serializer>(amount: &Option<u64>, serializer: S) -> Result<S::Ok, S::Error> {
+        amount.map(QuAmount).serialize(serializer)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+        Option::<QuAmount>::deserialize(deserializer).map(|amount| amount.map(u64::from))
+    }
+}
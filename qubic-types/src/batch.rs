@@ -0,0 +1,45 @@
+//! Bulk conversions between identities and public keys
+
+use core::str::FromStr;
+
+#[cfg(feature = "rayon")]
+use rayon::prelude::*;
+
+use crate::{QubicId, errors::QubicError, impls::identity_hasher};
+
+fn to_identity(id: &QubicId, hasher: &tiny_keccak::KangarooTwelve<&'static [u8]>) -> String {
+    String::from_utf8(id.get_identity_bytes_with(hasher).to_vec()).unwrap()
+}
+
+/// Parses identities, runs in parallel with the `rayon` feature
+pub fn ids_from_strs(ids: &[&str]) -> Vec<Result<QubicId, QubicError>> {
+    #[cfg(feature = "rayon")]
+    return ids.par_iter().map(|id| QubicId::from_str(id)).collect();
+
+    #[cfg(not(feature = "rayon"))]
+    ids.iter().map(|id| QubicId::from_str(id)).collect()
+}
+
+/// Encodes public keys as identities, runs in parallel with the `rayon` feature
+pub fn identities_from_ids(ids: &[QubicId]) -> Vec<String> {
+    #[cfg(feature = "rayon")]
+    return ids.par_iter().map_init(identity_hasher, |hasher, id| to_identity(id, hasher)).collect();
+
+    #[cfg(not(feature = "rayon"))]
+    {
+        let hasher = identity_hasher();
+        ids.iter().map(|id| to_identity(id, &hasher)).collect()
+    }
+}
+
+/// Lazily parses identities without collecting them
+pub fn ids_from_strs_iter<'a>(ids: impl IntoIterator<Item = &'a str>) -> impl Iterator<Item = Result<QubicId, QubicError>> {
+    ids.into_iter().map(QubicId::from_str)
+}
+
+/// Lazily encodes public keys as identities without collecting them
+pub fn identities_from_ids_iter(ids: impl IntoIterator<Item = QubicId>) -> impl Iterator<Item = String> {
+    let hasher = identity_hasher();
+
+    ids.into_iter().map(move |id| to_identity(&id, &hasher))
+}
@@ -1,19 +1,83 @@
 
+use core::fmt::{self, Display};
+
 #[cfg(not(feature = "std"))]
 use thiserror_no_std::Error;
 
 #[cfg(feature = "std")]
 use thiserror::Error;
 
-use crate::QubicId;
+use alloc::string::String;
 
-#[derive(Debug, Error)]
+use crate::{QubicId, U24};
+
+/// Encoded key kinds, each kind determines the required length and character set
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IdKind {
+    /// 60 uppercase characters
+    Identity,
+    /// 55 lowercase characters
+    Seed,
+    /// 60 lowercase characters
+    TxHash,
+    /// 60 lowercase characters
+    MiningSeed,
+    /// 32 bytes
+    PublicKeyBytes,
+    /// 32 bytes
+    TxHashBytes,
+    /// up to `max_len` ASCII characters
+    AssetName { max_len: usize }
+}
+
+impl IdKind {
+    /// required length, the maximum length for asset names
+    pub const fn expected_len(&self) -> usize {
+        match self {
+            Self::Identity | Self::TxHash | Self::MiningSeed => 60,
+            Self::Seed => 55,
+            Self::PublicKeyBytes | Self::TxHashBytes => 32,
+            Self::AssetName { max_len } => *max_len
+        }
+    }
+
+    /// human readable constraint of the kind
+    pub const fn constraint(&self) -> &'static str {
+        match self {
+            Self::Identity => "uppercase characters A-Z",
+            Self::Seed | Self::TxHash | Self::MiningSeed => "lowercase characters a-z",
+            Self::PublicKeyBytes | Self::TxHashBytes => "bytes",
+            Self::AssetName { .. } => "ASCII characters at most"
+        }
+    }
+}
+
+impl Display for IdKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Self::Identity => "identity",
+            Self::Seed => "seed",
+            Self::TxHash => "transaction hash",
+            Self::MiningSeed => "mining seed",
+            Self::PublicKeyBytes => "public key",
+            Self::TxHashBytes => "transaction hash digest",
+            Self::AssetName { .. } => "asset name"
+        };
+
+        match self {
+            Self::AssetName { max_len } => write!(f, "{name} ({} {max_len})", self.constraint()),
+            _ => write!(f, "{name} ({} {})", self.expected_len(), self.constraint())
+        }
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
 pub enum QubicError {
-    #[error("Invalid {ident} length (expected {expected}, found {found})")]
-    InvalidIdLengthError { ident: &'static str, expected: usize, found: usize },
+    #[error("Invalid length of {kind}, found {found}")]
+    InvalidIdLengthError { kind: IdKind, found: usize },
 
-    #[error("Invalid format of {ident}. Make sure all charcters are upper/lower case")]
-    InvalidIdFormatError { ident: &'static str },
+    #[error("Invalid format of {kind}")]
+    InvalidIdFormatError { kind: IdKind },
 
     #[error("Elliptic curve error. Decoded point was not found found on the elliptic curve")]
     EllipticCurveError,
@@ -22,14 +86,101 @@ pub enum QubicError {
     FormattingError,
 
     #[error("Found non matching signer (expected: {expected}, found: {found})")]
-    WrongSignature { expected: QubicId, found: QubicId }
+    WrongSignature { expected: QubicId, found: QubicId },
+
+    #[error("Checksum of {kind} does not match")]
+    ChecksumMismatch { kind: IdKind },
+
+    /// the encoding of a signed value does not decode again, e.g. a transaction claiming more input than it carries
+    #[error("Signed value cannot be decoded: {0}")]
+    UndecodableSigned(ByteEncodingError)
 }
 
-#[derive(Debug, Error)]
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
 pub enum ByteEncodingError {
     #[error("Invalid data length (expected {expected}, found {found})")]
     InvalidDataLength { expected: usize, found: usize },
 
     #[error("Invalid minimum data length (expected {expected_min}, found {found})")]
     InvalidMinimumDataLength { expected_min: usize, found: usize }
-}
\ No newline at end of file
+}
+
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+#[error("{value} exceeds the maximum 24 bit value {max}", max = U24::MAX)]
+pub struct U24OverflowError {
+    pub value: usize
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ChallengeError {
+    #[error("Challenge was issued for {found} instead of {expected}")]
+    WrongAudience { expected: String, found: String },
+
+    #[error("Challenge expired {expired_for}s ago")]
+    Expired { expired_for: u64 },
+
+    #[error("Challenge was issued {ahead}s in the future")]
+    IssuedInFuture { ahead: u64 },
+
+    #[error("Challenge signature does not match {identity}")]
+    InvalidSignature { identity: QubicId }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum UriError {
+    #[error("URI does not start with qubic:")]
+    InvalidScheme,
+
+    #[error("Invalid identity of URI: {0}")]
+    InvalidIdentity(QubicError),
+
+    #[error("Checksum of the identity {identity} does not match")]
+    ChecksumMismatch { identity: String },
+
+    #[error("Invalid value {value} of parameter {param}")]
+    InvalidParam { param: String, value: String },
+
+    #[error("Parameter {param} is given more than once")]
+    DuplicateParam { param: String },
+
+    #[error("Invalid percent encoding in {0}")]
+    InvalidEncoding(String)
+}
+
+#[cfg(feature = "mnemonic")]
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum MnemonicError {
+    #[error("Invalid number of words (expected {expected}, found {found})")]
+    InvalidWordCount { expected: usize, found: usize },
+
+    #[error("Unknown word {word} at position {position}")]
+    UnknownWord { position: usize, word: String },
+
+    #[error("Words do not encode a seed")]
+    OutOfRange,
+
+    #[error("Checksum of the words does not match")]
+    ChecksumMismatch
+}
+
+#[cfg(feature = "keystore")]
+#[derive(Debug, Error)]
+pub enum KeystoreError {
+    #[error("Keystore file failed: {0}")]
+    Io(#[from] std::io::Error),
+
+    #[error("Wrong password or the keystore file was altered")]
+    WrongPassword,
+
+    #[error("Keystore file is malformed")]
+    Malformed,
+
+    #[error("Keystore is locked")]
+    Locked,
+
+    #[error("Keystore does not hold {0}")]
+    UnknownIdentity(QubicId),
+
+    #[error(transparent)]
+    Qubic(#[from] QubicError)
+}
@@ -1,14 +1,47 @@
 #[cfg(target_arch = "x86_64")]
 use core::arch::x86_64::{_subborrow_u64, _addcarry_u64};
 
-use alloc::format;
+use alloc::{format, string::String, vec::Vec};
 
 use core::{ptr::copy_nonoverlapping, fmt::{Debug, Display}, str::FromStr};
 
 use four_q::{types::PointAffine, ops::{ecc_mul_fixed, encode, decode, ecc_mul, montgomery_multiply_mod_order, ecc_mul_double}, consts::{MONTGOMERY_R_PRIME, ONE, CURVE_ORDER_0, CURVE_ORDER_1, CURVE_ORDER_3, CURVE_ORDER_2}};
+use rand_core::{CryptoRng, RngCore};
+use subtle::{Choice, ConstantTimeEq};
 use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
 
-use crate::{QubicId, errors::QubicError, Signature, QubicWallet, traits::ToBytes, MiningSeed, Nonce, QubicTxHash};
+use crate::{QubicId, errors::{IdKind, QubicError, U24OverflowError}, Signature, QubicWallet, traits::ToBytes, MiningSeed, Nonce, QubicTxHash, SeedString, U24, Tick, Epoch, LenientId};
+
+/// unkeyed K12 instance used for identity checksums
+#[inline]
+pub(crate) fn identity_hasher() -> KangarooTwelve<&'static [u8]> {
+    KangarooTwelve::new(&[])
+}
+
+/// 4 checksum characters of an identity: the low 18 bits of the K12 hash of the key in base 26, least significant first
+#[inline]
+pub fn checksum_chars(key: &[u8; 32], lowercase: bool) -> [u8; 4] {
+    checksum_chars_with(key, lowercase, &identity_hasher())
+}
+
+/// checksums with a clone of an already set up hasher, used for batch conversions
+#[inline]
+pub(crate) fn checksum_chars_with(key: &[u8; 32], lowercase: bool, hasher: &KangarooTwelve<&'static [u8]>) -> [u8; 4] {
+    let mut checksum = [0u8; 3];
+    let mut kg = hasher.clone();
+    kg.update(key);
+    kg.into_xof().squeeze(&mut checksum);
+
+    let mut checksum = U24::from_le_bytes(checksum).get() & 0x3FFFF;
+    let base = if lowercase { b'a' } else { b'A' };
+
+    core::array::from_fn(|_| {
+        let c = (checksum % 26) as u8 + base;
+        checksum /= 26;
+
+        c
+    })
+}
 
 fn addcarry_u64(c_in: u8, a: u64, b: u64, out: &mut u64) -> u8  {
     #[cfg(target_arch = "x86_64")]
@@ -44,6 +77,18 @@ fn subborrow_u64(b_in: u8, a: u64, b: u64, out: &mut u64) -> u8 {
     }
 }
 
+/// key of the 56 base 26 characters of an identity starting at `base`, `None` if a chunk of 14 exceeds 64 bits
+fn decode_identity(id: &[u8; 60], base: u8) -> Option<[u8; 32]> {
+    let mut key = [0u8; 32];
+
+    for (i, chunk) in id[..56].chunks_exact(14).enumerate() {
+        let value = chunk.iter().rev().try_fold(0u64, |value, c| value.checked_mul(26)?.checked_add((c - base) as u64))?;
+        key[i << 3..(i << 3) + 8].copy_from_slice(&value.to_le_bytes());
+    }
+
+    Some(key)
+}
+
 impl FromStr for QubicId {
     type Err = QubicError;
 
@@ -52,13 +97,13 @@ impl FromStr for QubicId {
         let mut buffer = [0u8; 32];
 
         if !id.chars().all(|c| c.is_uppercase() && c.is_ascii_alphabetic()) {
-            return Err(QubicError::InvalidIdFormatError { ident: "ID" })
+            return Err(QubicError::InvalidIdFormatError { kind: IdKind::Identity })
         }
 
         let id = id.as_bytes();
 
         if id.len() != 60 {
-            return Err(QubicError::InvalidIdLengthError { ident: "ID", expected: 60, found: id.len()})
+            return Err(QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: id.len() })
         }
 
         for i in 0..4 {
@@ -81,18 +126,61 @@ impl QubicId {
     #[inline]
     pub fn check_id(id: &str) -> Result<(), QubicError> {
         if !id.chars().all(|c| c.is_uppercase() && c.is_ascii_alphabetic()) {
-            return Err(QubicError::InvalidIdFormatError { ident: "ID" })
+            return Err(QubicError::InvalidIdFormatError { kind: IdKind::Identity })
         }
 
         let id = id.as_bytes();
 
         if id.len() != 60 {
-            return Err(QubicError::InvalidIdLengthError { ident: "ID", expected: 55, found: id.len()})
+            return Err(QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: id.len() })
         }
 
         Ok(())
     }
 
+    /// parses an identity as pasted by users, surrounding whitespace is trimmed and lowercase letters are accepted.
+    /// If the checksum does not match, every identity differing from the input in a single character is tried and the
+    /// ones with a matching checksum are suggested. None of them is picked, several suggestions are ambiguous
+    ///
+    /// ```
+    /// use std::str::FromStr;
+    ///
+    /// use qubic_types::{LenientId, QubicId};
+    ///
+    /// let id = QubicId::parse_lenient(" bzbqfllbncxemglobhuvftluplvcpquassilfaboffbcadqssupnwlzbqexk\n").unwrap();
+    /// assert_eq!(id, LenientId::Valid(QubicId::from_str("BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK").unwrap()));
+    /// ```
+    pub fn parse_lenient(input: &str) -> Result<LenientId, QubicError> {
+        let id = input.trim().to_ascii_uppercase();
+        Self::check_id(&id)?;
+
+        let id: [u8; 60] = id.as_bytes().try_into().unwrap();
+        let hasher = identity_hasher();
+        let valid = |id: &[u8; 60]| decode_identity(id, b'A').filter(|key| checksum_chars_with(key, false, &hasher) == id[56..]);
+
+        if let Some(key) = valid(&id) {
+            return Ok(LenientId::Valid(QubicId(key)))
+        }
+
+        let mut suggestions = Vec::new();
+
+        for position in 0..id.len() {
+            for c in (b'A'..=b'Z').filter(|c| *c != id[position]) {
+                let mut candidate = id;
+                candidate[position] = c;
+
+                if valid(&candidate).is_some() {
+                    suggestions.push(String::from_utf8(candidate.to_vec()).unwrap());
+                }
+            }
+        }
+
+        match suggestions.is_empty() {
+            true => Err(QubicError::ChecksumMismatch { kind: IdKind::Identity }),
+            false => Ok(LenientId::Suggestion(suggestions))
+        }
+    }
+
     #[inline]
     pub fn get_identity(&self) -> String {
         let mut identity = [0u8; 60];
@@ -104,22 +192,29 @@ impl QubicId {
             }
         }
 
-        let mut identity_bytes_checksum = [0u8; 3];
-        let mut kg = KangarooTwelve::new(b"");
-        kg.update(&self.0);
-        kg.into_xof().squeeze(&mut identity_bytes_checksum);
-        let mut identity_bytes_checksum = identity_bytes_checksum[0] as u64 | (identity_bytes_checksum[1] as u64) << 8 | (identity_bytes_checksum[2] as u64) << 16;
-        identity_bytes_checksum &= 0x3FFFF;
-        for i in 0..4 {
-            identity[56 + i] = (identity_bytes_checksum % 26) as u8 + b'A';
-            identity_bytes_checksum /= 26;
-        }
+        identity[56..].copy_from_slice(&checksum_chars(&self.0, false));
 
         String::from_utf8(identity.to_vec()).unwrap()
     }
 
     #[inline]
     pub fn get_identity_bytes(&self) -> [u8; 60] {
+        self.get_identity_bytes_with(&identity_hasher())
+    }
+
+    /// first and last 4 characters of the identity for logging
+    pub fn redact(&self) -> String {
+        redact_identity(&self.get_identity_bytes())
+    }
+
+    /// first and last 5 characters of the identity as shown by `Debug`, e.g. `BZBQF...BQEXK`
+    pub fn short(&self) -> String {
+        short_identity(&self.get_identity_bytes())
+    }
+
+    /// checksums with a clone of an already set up hasher, used for batch conversions
+    #[inline]
+    pub(crate) fn get_identity_bytes_with(&self, hasher: &KangarooTwelve<&'static [u8]>) -> [u8; 60] {
         let mut identity = [0u8; 60];
         for i in 0..4 {
             let mut public_key_fragment = u64::from_le_bytes(self.0[i << 3..(i << 3) + 8].try_into().unwrap());
@@ -129,16 +224,7 @@ impl QubicId {
             }
         }
 
-        let mut identity_bytes_checksum = [0u8; 3];
-        let mut kg = KangarooTwelve::new(b"");
-        kg.update(&self.0);
-        kg.into_xof().squeeze(&mut identity_bytes_checksum);
-        let mut identity_bytes_checksum = identity_bytes_checksum[0] as u64 | (identity_bytes_checksum[1] as u64) << 8 | (identity_bytes_checksum[2] as u64) << 16;
-        identity_bytes_checksum &= 0x3FFFF;
-        for i in 0..4 {
-            identity[56 + i] = (identity_bytes_checksum % 26) as u8 + b'A';
-            identity_bytes_checksum /= 26;
-        }
+        identity[56..].copy_from_slice(&checksum_chars_with(&self.0, false, hasher));
 
         identity
     }
@@ -148,7 +234,7 @@ impl QubicId {
         if let Ok(arr) = slice.try_into() {
             Ok(Self(arr))
         } else {
-            Err(QubicError::InvalidIdLengthError { ident: "PUBLIC_KEY", expected: 32, found: slice.len() })
+            Err(QubicError::InvalidIdLengthError { kind: IdKind::PublicKeyBytes, found: slice.len() })
         }
     }
 
@@ -266,14 +352,46 @@ impl QubicId {
 
         encode(&mut a, &mut a_bytes);
 
-        signature[0..32] == a_bytes[0..32]
+        signature[0..32].ct_eq(&a_bytes[0..32]).into()
+    }
+}
+
+macro_rules! impl_constant_time_eq {
+    ($($t: ty)*) => {
+        $(
+            impl ConstantTimeEq for $t {
+                fn ct_eq(&self, other: &Self) -> Choice {
+                    self.0.ct_eq(&other.0)
+                }
+            }
+        )*
+    };
+}
+
+impl_constant_time_eq!(Signature QubicId Nonce QubicTxHash);
+
+/// shortened identity, the full identity with `{:#?}`
+fn fmt_identity(identity: &[u8; 60], f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+    let identity = core::str::from_utf8(identity).map_err(|_| core::fmt::Error)?;
+
+    if f.alternate() {
+        f.write_str(identity)
+    } else {
+        f.write_fmt(format_args!("{}...{}", &identity[..5], &identity[55..]))
     }
 }
 
+fn short_identity(identity: &[u8; 60]) -> String {
+    format!("{}...{}", String::from_utf8_lossy(&identity[..5]), String::from_utf8_lossy(&identity[55..]))
+}
+
+fn redact_identity(identity: &[u8; 60]) -> String {
+    format!("{}...{}", String::from_utf8_lossy(&identity[..4]), String::from_utf8_lossy(&identity[56..]))
+}
+
 impl Debug for QubicId {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let id = self.get_identity();
-        f.write_str(&format!("{}...{}", &id[..5], &id[55..]))
+        fmt_identity(&self.get_identity_bytes(), f)
     }
 }
 
@@ -304,14 +422,23 @@ impl QubicWallet {
         )
     }
 
-    pub fn get_subseed(seed: &str) -> Result<[u8; 32], QubicError> {
-        if !seed.chars().all(|c| c.is_lowercase() && c.is_ascii_alphabetic()) {
-            return Err(QubicError::InvalidIdFormatError { ident: "SEED" })
-        }
+    /// Generates a random seed and its wallet
+    ///
+    /// ```
+    /// use qubic_types::{OsRng, QubicWallet};
+    /// let (seed, wallet) = QubicWallet::generate(&mut OsRng);
+    ///
+    /// assert_eq!(QubicWallet::from_seed(&seed).unwrap().public_key, wallet.public_key);
+    /// ```
+    pub fn generate<R: CryptoRng + RngCore>(rng: &mut R) -> (String, Self) {
+        let seed = SeedString::random(rng);
+        let wallet = seed.wallet();
 
-        if seed.len() != 55 {
-            return Err(QubicError::InvalidIdLengthError { ident: "SEED", expected: 55, found: seed.len()})
-        }
+        (seed.into_string(), wallet)
+    }
+
+    pub fn get_subseed(seed: &str) -> Result<[u8; 32], QubicError> {
+        SeedString::check_seed(seed)?;
 
         let seed = seed.as_bytes();
         let mut seed_bytes = [0u8; 55];
@@ -491,6 +618,247 @@ impl QubicWallet {
     }
 }
 
+impl SeedString {
+    #[inline]
+    pub fn check_seed(seed: &str) -> Result<(), QubicError> {
+        if !seed.chars().all(|c| c.is_lowercase() && c.is_ascii_alphabetic()) {
+            return Err(QubicError::InvalidIdFormatError { kind: IdKind::Seed })
+        }
+
+        if seed.len() != 55 {
+            return Err(QubicError::InvalidIdLengthError { kind: IdKind::Seed, found: seed.len() })
+        }
+
+        Ok(())
+    }
+
+    /// Draws every character uniformly from a-z, bytes which would bias the distribution are rejected
+    pub fn random<R: CryptoRng + RngCore>(rng: &mut R) -> Self {
+        // largest multiple of 26 fitting a byte
+        const LIMIT: u8 = 26 * 9;
+
+        let mut seed = String::with_capacity(55);
+        let mut buf = [0u8; 64];
+
+        while seed.len() < 55 {
+            rng.fill_bytes(&mut buf);
+
+            for b in buf.into_iter().filter(|b| *b < LIMIT).take(55 - seed.len()) {
+                seed.push((b'a' + b % 26) as char);
+            }
+        }
+
+        Self(seed)
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    pub fn into_string(self) -> String {
+        self.0
+    }
+
+    pub fn wallet(&self) -> QubicWallet {
+        QubicWallet::from_seed(&self.0).expect("seed is checked")
+    }
+}
+
+impl FromStr for SeedString {
+    type Err = QubicError;
+
+    fn from_str(seed: &str) -> Result<Self, Self::Err> {
+        Self::check_seed(seed)?;
+
+        Ok(Self(String::from(seed)))
+    }
+}
+
+impl TryFrom<String> for SeedString {
+    type Error = QubicError;
+
+    fn try_from(seed: String) -> Result<Self, Self::Error> {
+        Self::check_seed(&seed)?;
+
+        Ok(Self(seed))
+    }
+}
+
+impl AsRef<str> for SeedString {
+    fn as_ref(&self) -> &str {
+        &self.0
+    }
+}
+
+impl Debug for SeedString {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        f.write_str("SeedString(***)")
+    }
+}
+
+impl U24 {
+    pub const MAX: usize = 0xFF_FFFF;
+    pub const ZERO: Self = Self([0; 3]);
+
+    pub const fn from_le_bytes(bytes: [u8; 3]) -> Self {
+        Self(bytes)
+    }
+
+    pub const fn to_le_bytes(self) -> [u8; 3] {
+        self.0
+    }
+
+    pub const fn get(self) -> usize {
+        self.0[0] as usize | (self.0[1] as usize) << 8 | (self.0[2] as usize) << 16
+    }
+
+    pub fn checked_add(self, rhs: usize) -> Option<Self> {
+        self.get().checked_add(rhs).and_then(|sum| Self::try_from(sum).ok())
+    }
+
+    pub fn checked_sub(self, rhs: usize) -> Option<Self> {
+        self.get().checked_sub(rhs).and_then(|difference| Self::try_from(difference).ok())
+    }
+
+    /// difference as `usize`, 0 if `rhs` exceeds the value
+    pub const fn saturating_sub(self, rhs: usize) -> usize {
+        self.get().saturating_sub(rhs)
+    }
+}
+
+impl TryFrom<usize> for U24 {
+    type Error = U24OverflowError;
+
+    fn try_from(value: usize) -> Result<Self, Self::Error> {
+        if value > Self::MAX {
+            return Err(U24OverflowError { value })
+        }
+
+        let [b0, b1, b2, ..] = value.to_le_bytes();
+
+        Ok(Self([b0, b1, b2]))
+    }
+}
+
+impl TryFrom<u32> for U24 {
+    type Error = U24OverflowError;
+
+    fn try_from(value: u32) -> Result<Self, Self::Error> {
+        Self::try_from(value as usize)
+    }
+}
+
+impl From<u16> for U24 {
+    fn from(value: u16) -> Self {
+        let [b0, b1] = value.to_le_bytes();
+
+        Self([b0, b1, 0])
+    }
+}
+
+impl From<U24> for usize {
+    fn from(value: U24) -> Self {
+        value.get()
+    }
+}
+
+impl From<U24> for u32 {
+    fn from(value: U24) -> Self {
+        value.get() as u32
+    }
+}
+
+impl PartialOrd for U24 {
+    fn partial_cmp(&self, other: &Self) -> Option<core::cmp::Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for U24 {
+    fn cmp(&self, other: &Self) -> core::cmp::Ordering {
+        self.get().cmp(&other.get())
+    }
+}
+
+impl Debug for U24 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Debug::fmt(&self.get(), f)
+    }
+}
+
+impl Display for U24 {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        Display::fmt(&self.get(), f)
+    }
+}
+
+/// conversions, formatting and parsing of the integer newtypes
+macro_rules! impl_number {
+    ($($name: ident $int: ty)*) => {
+        $(
+            impl $name {
+                pub const fn get(self) -> $int {
+                    self.0
+                }
+            }
+
+            impl From<$int> for $name {
+                fn from(value: $int) -> Self {
+                    Self(value)
+                }
+            }
+
+            impl From<$name> for $int {
+                fn from(value: $name) -> Self {
+                    value.0
+                }
+            }
+
+            impl Display for $name {
+                fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    Display::fmt(&self.0, f)
+                }
+            }
+
+            impl FromStr for $name {
+                type Err = core::num::ParseIntError;
+
+                fn from_str(s: &str) -> Result<Self, Self::Err> {
+                    s.parse().map(Self)
+                }
+            }
+        )*
+    };
+}
+
+impl_number!(Tick u32 Epoch u16);
+
+impl Tick {
+    /// tick `rhs` ticks earlier, 0 if `rhs` exceeds the tick
+    pub const fn saturating_sub(self, rhs: u32) -> Self {
+        Self(self.0.saturating_sub(rhs))
+    }
+
+    /// number of ticks from `earlier` to this tick, 0 if `earlier` is later
+    pub const fn ticks_since(self, earlier: Tick) -> u32 {
+        self.0.saturating_sub(earlier.0)
+    }
+}
+
+impl core::ops::Add<u32> for Tick {
+    type Output = Self;
+
+    fn add(self, rhs: u32) -> Self {
+        Self(self.0 + rhs)
+    }
+}
+
+impl core::ops::AddAssign<u32> for Tick {
+    fn add_assign(&mut self, rhs: u32) {
+        self.0 += rhs;
+    }
+}
+
 impl Debug for Signature {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
         let mut hex_slice = [0; 128];
@@ -563,16 +931,7 @@ impl MiningSeed {
             }
         }
 
-        let mut identity_bytes_checksum = [0u8; 3];
-        let mut kg = KangarooTwelve::new(b"");
-        kg.update(&self.0);
-        kg.into_xof().squeeze(&mut identity_bytes_checksum);
-        let mut identity_bytes_checksum = identity_bytes_checksum[0] as u64 | (identity_bytes_checksum[1] as u64) << 8 | (identity_bytes_checksum[2] as u64) << 16;
-        identity_bytes_checksum &= 0x3FFFF;
-        for i in 0..4 {
-            identity[56 + i] = (identity_bytes_checksum % 26) as u8 + b'a';
-            identity_bytes_checksum /= 26;
-        }
+        identity[56..].copy_from_slice(&checksum_chars(&self.0, true));
 
         String::from_utf8(identity.to_vec()).unwrap()
     }
@@ -585,13 +944,13 @@ impl FromStr for MiningSeed {
         let mut buffer = [0u8; 32];
 
         if !s.chars().all(|c| c.is_lowercase() && c.is_ascii_alphabetic()) {
-            return Err(QubicError::InvalidIdFormatError { ident: "TxHash" })
+            return Err(QubicError::InvalidIdFormatError { kind: IdKind::MiningSeed })
         }
 
         let id = s.as_bytes();
 
         if id.len() != 60 {
-            return Err(QubicError::InvalidIdLengthError { ident: "TxHash", expected: 60, found: id.len()})
+            return Err(QubicError::InvalidIdLengthError { kind: IdKind::MiningSeed, found: id.len() })
         }
 
         for i in 0..4 {
@@ -663,6 +1022,11 @@ impl Nonce {
 impl QubicTxHash {
     #[inline]
     pub fn get_identity(&self) -> String {
+        String::from_utf8(self.get_identity_bytes().to_vec()).unwrap()
+    }
+
+    #[inline]
+    pub fn get_identity_bytes(&self) -> [u8; 60] {
         let mut identity = [0u8; 60];
         for i in 0..4 {
             let mut public_key_fragment = u64::from_le_bytes(self.0[i << 3..(i << 3) + 8].try_into().unwrap());
@@ -672,18 +1036,19 @@ impl QubicTxHash {
             }
         }
 
-        let mut identity_bytes_checksum = [0u8; 3];
-        let mut kg = KangarooTwelve::new(b"");
-        kg.update(&self.0);
-        kg.into_xof().squeeze(&mut identity_bytes_checksum);
-        let mut identity_bytes_checksum = identity_bytes_checksum[0] as u64 | (identity_bytes_checksum[1] as u64) << 8 | (identity_bytes_checksum[2] as u64) << 16;
-        identity_bytes_checksum &= 0x3FFFF;
-        for i in 0..4 {
-            identity[56 + i] = (identity_bytes_checksum % 26) as u8 + b'a';
-            identity_bytes_checksum /= 26;
-        }
+        identity[56..].copy_from_slice(&checksum_chars(&self.0, true));
 
-        String::from_utf8(identity.to_vec()).unwrap()
+        identity
+    }
+
+    /// first and last 4 characters of the hash for logging
+    pub fn redact(&self) -> String {
+        redact_identity(&self.get_identity_bytes())
+    }
+
+    /// first and last 5 characters of the hash as shown by `Debug`
+    pub fn short(&self) -> String {
+        short_identity(&self.get_identity_bytes())
     }
 }
 
@@ -694,13 +1059,13 @@ impl FromStr for QubicTxHash {
         let mut buffer = [0u8; 32];
 
         if !s.chars().all(|c| c.is_lowercase() && c.is_ascii_alphabetic()) {
-            return Err(QubicError::InvalidIdFormatError { ident: "TxHash" })
+            return Err(QubicError::InvalidIdFormatError { kind: IdKind::TxHash })
         }
 
         let id = s.as_bytes();
 
         if id.len() != 60 {
-            return Err(QubicError::InvalidIdLengthError { ident: "TxHash", expected: 60, found: id.len()})
+            return Err(QubicError::InvalidIdLengthError { kind: IdKind::TxHash, found: id.len() })
         }
 
         for i in 0..4 {
@@ -721,8 +1086,7 @@ impl FromStr for QubicTxHash {
 
 impl Debug for QubicTxHash {
     fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
-        let id = self.get_identity();
-        f.write_str(&format!("{}...{}", &id[..5], &id[55..]))
+        fmt_identity(&self.get_identity_bytes(), f)
     }
 }
 
@@ -0,0 +1,269 @@
+//! Encrypted store of the wallets of services signing on behalf of many identities
+//!
+//! The file is `QKS1 | salt (16) | nonce (12) | ciphertext`, the 55 characters of every seed encrypted with
+//! AES-256-GCM under a key derived from the master password with Argon2id. Every change rewrites the file to a
+//! temporary file which is renamed over the old one, a crash leaves either the old or the new keystore.
+//! The key, the decrypted seeds and the wallets derived from them are zeroized when they are dropped.
+//!
+//! ```no_run
+//! use qubic_types::{keystore::Keystore, traits::Signer, OsRng, SeedString};
+//!
+//! let keystore = Keystore::open("wallets.qks", "master password").unwrap();
+//! let id = keystore.add(SeedString::random(&mut OsRng)).unwrap();
+//!
+//! let signature = keystore.sign_message(&id, 1006u64).unwrap();
+//! assert!(id.verify(1006u64, signature));
+//! ```
+
+use std::{collections::HashMap, fs, io::Write, path::{Path, PathBuf}, str::FromStr, sync::RwLock, time::{Duration, Instant}};
+
+use aes_gcm::{aead::{Aead, Payload}, Aes256Gcm, Key, KeyInit, Nonce};
+use argon2::Argon2;
+use rand_core::{OsRng, RngCore};
+use zeroize::{Zeroize, Zeroizing};
+
+use crate::{errors::KeystoreError, traits::{GetSigner, Sign, Signer}, QubicId, QubicWallet, SeedString, Signature};
+
+const MAGIC: &[u8; 4] = b"QKS1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 12;
+const SEED_LEN: usize = 55;
+
+/// seed of a wallet of the keystore with the wallet derived from it
+type Entry = Zeroizing<(SeedString, QubicWallet)>;
+
+/// Wallets of an unlocked keystore with the key the file is encrypted with
+struct Unlocked {
+    key: Zeroizing<[u8; 32]>,
+    salt: [u8; SALT_LEN],
+    wallets: HashMap<QubicId, Entry>,
+    at: Instant
+}
+
+impl Zeroize for SeedString {
+    fn zeroize(&mut self) {
+        self.0.zeroize();
+    }
+}
+
+impl Zeroize for QubicWallet {
+    fn zeroize(&mut self) {
+        self.private_key.zeroize();
+        self.subseed.zeroize();
+    }
+}
+
+struct State {
+    /// in the order of the file, known while locked
+    identities: Vec<QubicId>,
+    unlocked: Option<Unlocked>
+}
+
+/// Wallets of many identities encrypted with a master password
+///
+/// The keystore is shared between threads, signing only takes a read lock. Locked keystores keep no keys in memory
+/// until `unlock`, with `with_auto_lock` they lock themselves the given time after they were unlocked.
+pub struct Keystore {
+    path: PathBuf,
+    auto_lock: Option<Duration>,
+    state: RwLock<State>
+}
+
+impl Keystore {
+    /// opens the keystore at `path` unlocked, an empty keystore is created if there is no file
+    pub fn open(path: impl AsRef<Path>, password: &str) -> Result<Self, KeystoreError> {
+        let path = path.as_ref().to_path_buf();
+
+        let (identities, unlocked) = match path.exists() {
+            true => read(&path, password)?,
+            false => {
+                let mut salt = [0; SALT_LEN];
+                OsRng.fill_bytes(&mut salt);
+
+                let unlocked = Unlocked { key: derive_key(password, &salt), salt, wallets: HashMap::new(), at: Instant::now() };
+                write(&path, &unlocked, &[])?;
+                (Vec::new(), unlocked)
+            }
+        };
+
+        Ok(Self { path, auto_lock: None, state: RwLock::new(State { identities, unlocked: Some(unlocked) }) })
+    }
+
+    /// locks the keystore `timeout` after every `unlock` (and after opening)
+    pub fn with_auto_lock(mut self, timeout: Duration) -> Self {
+        self.auto_lock = Some(timeout);
+        self
+    }
+
+    /// drops the keys from memory until `unlock`
+    pub fn lock(&self) {
+        self.state.write().unwrap().unlocked = None;
+    }
+
+    /// decrypts the keys again, the password is checked against the file
+    pub fn unlock(&self, password: &str) -> Result<(), KeystoreError> {
+        let mut state = self.state.write().unwrap();
+        let (identities, unlocked) = read(&self.path, password)?;
+
+        *state = State { identities, unlocked: Some(unlocked) };
+
+        Ok(())
+    }
+
+    pub fn is_locked(&self) -> bool {
+        self.state.read().unwrap().unlocked.as_ref().is_none_or(|unlocked| self.expired(unlocked))
+    }
+
+    /// identities of the keystore in the order they were added, also while locked
+    pub fn identities(&self) -> Vec<QubicId> {
+        self.state.read().unwrap().identities.clone()
+    }
+
+    pub fn contains(&self, id: &QubicId) -> bool {
+        self.state.read().unwrap().identities.contains(id)
+    }
+
+    /// adds the wallet of `seed` and persists the keystore, adding a wallet twice has no effect
+    pub fn add(&self, seed: SeedString) -> Result<QubicId, KeystoreError> {
+        self.update(|identities, wallets| {
+            let wallet = seed.wallet();
+
+            if wallets.insert(wallet.public_key, Zeroizing::new((seed, wallet))).is_none() {
+                identities.push(wallet.public_key);
+            }
+
+            wallet.public_key
+        })
+    }
+
+    /// removes the wallet of `id` and persists the keystore, returns whether it was held
+    pub fn remove(&self, id: &QubicId) -> Result<bool, KeystoreError> {
+        self.update(|identities, wallets| {
+            identities.retain(|identity| identity != id);
+            wallets.remove(id).is_some()
+        })
+    }
+
+    /// signs the packet with the wallet of its signer, e.g. a transaction of an identity of the keystore
+    pub fn sign<T: Sign + GetSigner>(&self, packet: &mut T) -> Result<(), KeystoreError> {
+        let signer = *packet.get_signer();
+
+        self.with_wallet(&signer, |wallet| packet.sign(wallet))?.map_err(KeystoreError::from)
+    }
+
+    fn with_wallet<R>(&self, id: &QubicId, f: impl FnOnce(&QubicWallet) -> R) -> Result<R, KeystoreError> {
+        {
+            let state = self.state.read().unwrap();
+
+            match &state.unlocked {
+                Some(unlocked) if !self.expired(unlocked) => {
+                    return unlocked.wallets.get(id).map(|entry| f(&entry.1)).ok_or(KeystoreError::UnknownIdentity(*id))
+                },
+                None => return Err(KeystoreError::Locked),
+                Some(_) => {}
+            }
+        }
+
+        self.lock();
+        Err(KeystoreError::Locked)
+    }
+
+    /// applies the change to the wallets and persists them, nothing is changed if the file cannot be written
+    fn update<R>(&self, f: impl FnOnce(&mut Vec<QubicId>, &mut HashMap<QubicId, Entry>) -> R) -> Result<R, KeystoreError> {
+        let mut state = self.state.write().unwrap();
+        let State { identities, unlocked } = &mut *state;
+
+        let unlocked = match unlocked {
+            Some(unlocked) if !self.expired(unlocked) => unlocked,
+            _ => {
+                state.unlocked = None;
+                return Err(KeystoreError::Locked)
+            }
+        };
+
+        let (mut changed_identities, mut changed_wallets) = (identities.clone(), unlocked.wallets.clone());
+        let result = f(&mut changed_identities, &mut changed_wallets);
+
+        let changed = Unlocked { key: unlocked.key.clone(), salt: unlocked.salt, wallets: changed_wallets, at: unlocked.at };
+        write(&self.path, &changed, &changed_identities)?;
+
+        *identities = changed_identities;
+        *unlocked = changed;
+
+        Ok(result)
+    }
+
+    fn expired(&self, unlocked: &Unlocked) -> bool {
+        self.auto_lock.is_some_and(|timeout| unlocked.at.elapsed() >= timeout)
+    }
+}
+
+impl Signer for Keystore {
+    type Error = KeystoreError;
+
+    fn sign_digest(&self, identity: &QubicId, digest: [u8; 32]) -> Result<Signature, KeystoreError> {
+        self.with_wallet(identity, |wallet| wallet.sign_raw(digest))
+    }
+}
+
+fn derive_key(password: &str, salt: &[u8; SALT_LEN]) -> Zeroizing<[u8; 32]> {
+    let mut key = Zeroizing::new([0; 32]);
+    Argon2::default().hash_password_into(password.as_bytes(), salt, key.as_mut()).expect("salt and key lengths are valid");
+
+    key
+}
+
+/// decrypts the wallets of the file, returns their identities in the order of the file
+fn read(path: &Path, password: &str) -> Result<(Vec<QubicId>, Unlocked), KeystoreError> {
+    let data = fs::read(path)?;
+
+    let Some((MAGIC, rest)) = data.split_first_chunk::<4>() else { return Err(KeystoreError::Malformed) };
+    let Some((salt, rest)) = rest.split_first_chunk::<SALT_LEN>() else { return Err(KeystoreError::Malformed) };
+    let Some((nonce, ciphertext)) = rest.split_first_chunk::<NONCE_LEN>() else { return Err(KeystoreError::Malformed) };
+
+    let key = derive_key(password, salt);
+    let plaintext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(key.as_ref()))
+        .decrypt(Nonce::from_slice(nonce), Payload { msg: ciphertext, aad: MAGIC })
+        .map(Zeroizing::new)
+        .map_err(|_| KeystoreError::WrongPassword)?;
+
+    if !plaintext.len().is_multiple_of(SEED_LEN) {
+        return Err(KeystoreError::Malformed)
+    }
+
+    let (mut identities, mut wallets) = (Vec::new(), HashMap::new());
+
+    for seed in plaintext.chunks(SEED_LEN) {
+        let seed = std::str::from_utf8(seed).ok().and_then(|seed| SeedString::from_str(seed).ok()).ok_or(KeystoreError::Malformed)?;
+        let wallet = seed.wallet();
+
+        identities.push(wallet.public_key);
+        wallets.insert(wallet.public_key, Zeroizing::new((seed, wallet)));
+    }
+
+    Ok((identities, Unlocked { key, salt: *salt, wallets, at: Instant::now() }))
+}
+
+/// encrypts the seeds of `identities` with a fresh nonce and replaces the file
+fn write(path: &Path, unlocked: &Unlocked, identities: &[QubicId]) -> Result<(), KeystoreError> {
+    // sized up front, growing the buffer would leave copies of the seeds behind
+    let mut plaintext = Zeroizing::new(Vec::with_capacity(identities.len() * SEED_LEN));
+    plaintext.extend(identities.iter().flat_map(|id| unlocked.wallets[id].0.as_str().bytes()));
+
+    let mut nonce = [0; NONCE_LEN];
+    OsRng.fill_bytes(&mut nonce);
+
+    let ciphertext = Aes256Gcm::new(Key::<Aes256Gcm>::from_slice(unlocked.key.as_ref()))
+        .encrypt(Nonce::from_slice(&nonce), Payload { msg: &plaintext, aad: MAGIC })
+        .expect("plaintext fits AES-GCM");
+
+    let mut tmp = path.as_os_str().to_owned();
+    tmp.push(".tmp");
+
+    let mut file = fs::File::create(&tmp)?;
+    file.write_all(&[MAGIC.as_slice(), &unlocked.salt, &nonce, &ciphertext].concat())?;
+    file.sync_all()?;
+    fs::rename(&tmp, path)?;
+
+    Ok(())
+}
@@ -1,16 +1,55 @@
+//! Identities, wallets and SchnorrQ signatures of Qubic
+//!
+//! ## `no_std`
+//!
+//! Without the default `std` feature the crate only needs `alloc`, e.g. for signing on embedded devices
+//! (`cargo build -p no-std-check --target thumbv7em-none-eabihf` builds that surface):
+//!
+//! - `QubicId`, `QubicTxHash`, `MiningSeed`, `Nonce`, `Signature` and `U24` with their identity encodings
+//! - the `Tick` and `Epoch` numbers
+//! - `QubicWallet::from_seed`, `QubicWallet::generate` with a caller provided rng, `sign`, `sign_raw` and `QubicId::verify`
+//! - the `traits`, `errors`, `message` and `uri` modules
+//! - serde support with the `serde` feature (`amount` included) and the word lists with `mnemonic`
+//!
+//! `batch`, `OsRng` and `std::error::Error` implementations of the errors require `std`, `rayon`, `utoipa` and
+//! `keystore` imply it.
+
 #![cfg_attr(not(feature = "std"), no_std)]
 
 #[cfg(test)]
 mod tests;
 mod impls;
 pub mod errors;
+#[cfg(feature = "std")]
+pub mod batch;
 pub extern crate alloc;
 
 #[cfg(feature = "serde")]
 mod serde_impl;
+#[cfg(feature = "serde")]
+pub mod amount;
+#[cfg(feature = "utoipa")]
+mod schema_impl;
 pub mod traits;
+pub mod message;
+pub mod uri;
+#[cfg(feature = "mnemonic")]
+pub mod mnemonic;
+#[cfg(feature = "keystore")]
+pub mod keystore;
+
+use alloc::{string::String, vec::Vec};
 
 pub use ethereum_types::{H256, H512, U256};
+/// checksum characters shared by the identity encodings of `QubicId`, `QubicTxHash` and `MiningSeed`
+pub use impls::checksum_chars;
+/// constant-time equality of `Signature`, `QubicId`, `Nonce` and `QubicTxHash` for security-sensitive comparisons
+pub use subtle::{Choice, ConstantTimeEq};
+/// rng traits of `QubicWallet::generate`
+pub use rand_core::{CryptoRng, RngCore};
+/// random number generator of the operating system
+#[cfg(feature = "std")]
+pub use rand_core::OsRng;
 
 
 /// 32 byte nonce type
@@ -55,6 +94,14 @@ impl Default for Signature {
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct QubicId(pub [u8; 32]);
 
+/// Identity as recognized by `QubicId::parse_lenient`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum LenientId {
+    Valid(QubicId),
+    /// identities differing from the input in a single character whose checksum matches, several are ambiguous
+    Suggestion(Vec<String>)
+}
+
 /// Represents a Qubic wallet containing private key, subseed and public key of the corresponding wallet
 /// 
 /// # Initialization
@@ -84,6 +131,50 @@ pub struct QubicWallet {
     pub public_key: QubicId
 }
 
+/// Wallet seed checked to consist of 55 lowercase characters a-z, its `Debug` output is redacted
+///
+/// ```
+/// use qubic_types::{OsRng, SeedString};
+///
+/// let seed: SeedString = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".parse().unwrap();
+/// assert_eq!(seed.wallet().get_identity(), "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK");
+///
+/// let random = SeedString::random(&mut OsRng);
+/// ```
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct SeedString(String);
+
+/// 24 bit little endian unsigned integer, the size encoding of packet and log headers
+///
+/// ```
+/// use qubic_types::U24;
+///
+/// let size = U24::try_from(0xFF_FFFFusize).unwrap();
+/// assert_eq!(size.to_le_bytes(), [0xFF; 3]);
+/// assert!(U24::try_from(0x100_0000usize).is_err());
+/// ```
+#[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
+#[repr(transparent)]
+pub struct U24([u8; 3]);
+
+/// Tick number, the typed form of the raw `u32` tick fields of the wire structs
+///
+/// Ticks and epochs are plain integers on the wire, the wrappers keep them from being mixed up in signatures:
+///
+/// ```
+/// use qubic_types::{Epoch, Tick};
+///
+/// let tick = Tick(12_000_000) + 5;
+/// assert_eq!(tick.saturating_sub(10), Tick(11_999_995));
+/// assert_eq!("12000005".parse::<Tick>().unwrap(), tick);
+/// assert_eq!(Epoch::from(101).to_string(), "101");
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Tick(pub u32);
+
+/// Epoch number, the typed form of the raw `u16` epoch fields of the wire structs, see `Tick`
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Epoch(pub u16);
 
 #[derive(Clone, Copy, Default, PartialEq, Eq, Hash)]
 pub struct QubicTxHash(pub [u8; 32]);
\ No newline at end of file
@@ -0,0 +1,115 @@
+//! Domain separated signatures of off-chain messages, e.g. authentication challenges
+//!
+//! Messages are hashed with a K12 customization string and prefixed with the length of their domain,
+//! a signed message therefore never verifies as a transaction or as a message of another domain.
+
+use alloc::{string::{String, ToString}, vec::Vec};
+use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+
+use crate::{errors::ChallengeError, Nonce, QubicId, QubicWallet, Signature};
+
+/// K12 customization string of message digests, transaction digests are hashed without one
+pub const MESSAGE_CUSTOMIZATION: &[u8] = b"qubic-signed-message";
+
+/// Domain of `SignedChallenge` signatures
+pub const CHALLENGE_DOMAIN: &str = "qubic-auth-challenge";
+
+/// Digest of `payload` signed in `domain`
+pub fn message_digest(domain: &str, payload: &[u8]) -> [u8; 32] {
+    let mut digest = [0; 32];
+    let mut kg = KangarooTwelve::new(MESSAGE_CUSTOMIZATION);
+    kg.update(&(domain.len() as u64).to_le_bytes());
+    kg.update(domain.as_bytes());
+    kg.update(payload);
+    kg.into_xof().squeeze(&mut digest);
+
+    digest
+}
+
+/// Signs `payload` in `domain`
+///
+/// ```
+/// use qubic_types::{message::{sign_message, verify_message}, QubicWallet};
+/// let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+///
+/// let signature = sign_message(&wallet, "example.org", b"qubic");
+///
+/// assert!(verify_message(&wallet.public_key, "example.org", b"qubic", signature));
+/// assert!(!verify_message(&wallet.public_key, "example.com", b"qubic", signature));
+/// ```
+pub fn sign_message(wallet: &QubicWallet, domain: &str, payload: &[u8]) -> Signature {
+    wallet.sign_raw(message_digest(domain, payload))
+}
+
+/// Verifies a signature of `payload` in `domain` created with `sign_message`
+pub fn verify_message(id: &QubicId, domain: &str, payload: &[u8], signature: Signature) -> bool {
+    id.verify_raw(message_digest(domain, payload), signature)
+}
+
+/// Challenge signed by `identity` to prove it controls the key, e.g. to sign in to `audience`
+#[derive(Debug, Clone, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "camelCase"))]
+#[cfg_attr(feature = "utoipa", derive(utoipa::ToSchema))]
+pub struct SignedChallenge {
+    pub identity: QubicId,
+    /// service the challenge was issued by
+    pub audience: String,
+    /// unix timestamp in seconds the challenge was issued at
+    pub timestamp: u64,
+    /// issued by the audience to prevent replays
+    pub nonce: Nonce,
+    pub signature: Signature
+}
+
+impl SignedChallenge {
+    /// Signs a challenge of `audience` issued at `timestamp`
+    pub fn sign(wallet: &QubicWallet, audience: &str, timestamp: u64, nonce: Nonce) -> Self {
+        let mut challenge = Self {
+            identity: wallet.public_key,
+            audience: audience.to_string(),
+            timestamp,
+            nonce,
+            signature: Signature::default()
+        };
+
+        challenge.signature = sign_message(wallet, CHALLENGE_DOMAIN, &challenge.challenge_bytes());
+
+        challenge
+    }
+
+    /// Canonical encoding of the signed fields: timestamp and nonce followed by the length prefixed audience
+    pub fn challenge_bytes(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(8 + 32 + 8 + self.audience.len());
+        bytes.extend(self.timestamp.to_le_bytes());
+        bytes.extend(self.nonce.0);
+        bytes.extend((self.audience.len() as u64).to_le_bytes());
+        bytes.extend(self.audience.as_bytes());
+
+        bytes
+    }
+
+    /// Checks the audience, that the challenge was issued at most `max_age` seconds before `now` and the signature.
+    /// Challenges issued up to `max_skew` seconds after `now` are accepted, the clock of the signer may be ahead
+    pub fn verify(&self, audience: &str, now: u64, max_age: u64, max_skew: u64) -> Result<(), ChallengeError> {
+        if self.audience != audience {
+            return Err(ChallengeError::WrongAudience { expected: audience.to_string(), found: self.audience.clone() })
+        }
+
+        if self.timestamp > now.saturating_add(max_skew) {
+            return Err(ChallengeError::IssuedInFuture { ahead: self.timestamp - now })
+        }
+
+        let age = now.saturating_sub(self.timestamp);
+
+        if age > max_age {
+            return Err(ChallengeError::Expired { expired_for: age - max_age })
+        }
+
+        if !verify_message(&self.identity, CHALLENGE_DOMAIN, &self.challenge_bytes(), self.signature) {
+            return Err(ChallengeError::InvalidSignature { identity: self.identity })
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,130 @@
+//! Word encoding of wallet seeds for easier backups
+//!
+//! This is a custom scheme of this crate, it is NOT BIP-39 and phrases are not compatible with other wallets.
+//! Words are 4 letters of two consonant-vowel syllables, each word encodes 12 bits. The 55 seed characters are
+//! read as a base 26 number encoded in 22 words, followed by 2 words of a K12 checksum of the seed.
+
+use alloc::{string::String, vec::Vec};
+use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+
+use crate::{errors::MnemonicError, SeedString};
+
+/// K12 customization string of the checksum
+pub const CHECKSUM_CUSTOMIZATION: &[u8] = b"qubic-seed-words";
+
+/// number of words of a phrase
+pub const WORDS: usize = DATA_WORDS + CHECKSUM_WORDS;
+
+const DATA_WORDS: usize = 22;
+const CHECKSUM_WORDS: usize = 2;
+const WORD_BITS: u32 = 12;
+
+const CONSONANTS: &[u8; 16] = b"bdfghjklmnprstvz";
+const VOWELS: &[u8; 4] = b"aiou";
+
+/// word of the given 12 bit index
+pub fn word(index: u16) -> String {
+    let syllable = |idx: u16| [CONSONANTS[(idx >> 2) as usize & 15], VOWELS[idx as usize & 3]];
+    let [a, b] = syllable(index >> 6);
+    let [c, d] = syllable(index & 63);
+
+    [a, b, c, d].into_iter().map(char::from).collect()
+}
+
+/// index of `word`, words are case insensitive
+pub fn word_index(word: &str) -> Option<u16> {
+    let word = word.as_bytes();
+    if word.len() != 4 {
+        return None
+    }
+
+    // consonants encode 4 bits, vowels 2 bits
+    word.iter().enumerate().try_fold(0u16, |index, (pos, c)| {
+        let c = c.to_ascii_lowercase();
+
+        Some(match pos % 2 {
+            0 => (index << 4) | CONSONANTS.iter().position(|&consonant| consonant == c)? as u16,
+            _ => (index << 2) | VOWELS.iter().position(|&vowel| vowel == c)? as u16
+        })
+    })
+}
+
+/// Encodes `seed` in `WORDS` words
+///
+/// ```
+/// use qubic_types::{mnemonic, SeedString};
+///
+/// let seed: SeedString = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa".parse().unwrap();
+/// let words = mnemonic::to_words(&seed);
+///
+/// assert_eq!(mnemonic::from_words(&words).unwrap(), seed);
+/// ```
+pub fn to_words(seed: &SeedString) -> Vec<String> {
+    let digits: Vec<u16> = seed.as_str().bytes().map(|c| (c - b'a') as u16).collect();
+    let data = convert_base(&digits, 26, 1 << WORD_BITS, DATA_WORDS).expect("26^55 fits 22 words");
+
+    data.into_iter().chain(checksum(seed)).map(word).collect()
+}
+
+/// Words of `seed` separated by spaces
+pub fn to_phrase(seed: &SeedString) -> String {
+    to_words(seed).join(" ")
+}
+
+/// Decodes the seed of `words`, the checksum is verified
+pub fn from_words<S: AsRef<str>>(words: &[S]) -> Result<SeedString, MnemonicError> {
+    if words.len() != WORDS {
+        return Err(MnemonicError::InvalidWordCount { expected: WORDS, found: words.len() })
+    }
+
+    let indices = words.iter().enumerate().map(|(position, word)| {
+        word_index(word.as_ref()).ok_or_else(|| MnemonicError::UnknownWord { position, word: String::from(word.as_ref()) })
+    }).collect::<Result<Vec<_>, _>>()?;
+
+    let digits = convert_base(&indices[..DATA_WORDS], 1 << WORD_BITS, 26, 55).ok_or(MnemonicError::OutOfRange)?;
+    let seed: String = digits.into_iter().map(|digit| (b'a' + digit as u8) as char).collect();
+    let seed = SeedString::try_from(seed).expect("digits are below 26");
+
+    if checksum(&seed) != indices[DATA_WORDS..] {
+        return Err(MnemonicError::ChecksumMismatch)
+    }
+
+    Ok(seed)
+}
+
+/// Decodes the seed of a phrase of whitespace separated words
+pub fn from_phrase(phrase: &str) -> Result<SeedString, MnemonicError> {
+    from_words(&phrase.split_whitespace().collect::<Vec<_>>())
+}
+
+fn checksum(seed: &SeedString) -> [u16; CHECKSUM_WORDS] {
+    let mut digest = [0u8; 3];
+    let mut kg = KangarooTwelve::new(CHECKSUM_CUSTOMIZATION);
+    kg.update(seed.as_str().as_bytes());
+    kg.into_xof().squeeze(&mut digest);
+
+    let bits = u32::from_be_bytes([0, digest[0], digest[1], digest[2]]);
+
+    [(bits >> WORD_BITS) as u16, (bits & 0xfff) as u16]
+}
+
+/// Converts big endian `digits` of base `from` to `len` big endian digits of base `to`, `None` if they don't fit
+fn convert_base(digits: &[u16], from: u32, to: u32, len: usize) -> Option<Vec<u16>> {
+    let mut converted = alloc::vec![0u32; len];
+
+    for digit in digits {
+        let mut carry = *digit as u32;
+
+        for out in converted.iter_mut().rev() {
+            let value = *out * from + carry;
+            *out = value % to;
+            carry = value / to;
+        }
+
+        if carry != 0 {
+            return None
+        }
+    }
+
+    Some(converted.into_iter().map(|digit| digit as u16).collect())
+}
@@ -0,0 +1,55 @@
+use utoipa::{openapi::{schema::{ObjectBuilder, Schema, SchemaFormat, KnownFormat, Type}, RefOr}, PartialSchema, ToSchema};
+
+use crate::{QubicId, Signature, MiningSeed, Nonce, QubicTxHash, Tick, Epoch};
+
+/// OpenAPI schemas of the string representations in `serde_impl`
+macro_rules! impl_string_schema {
+    ($($name: ident $description: literal $pattern: literal)*) => {
+        $(
+            impl PartialSchema for $name {
+                fn schema() -> RefOr<Schema> {
+                    ObjectBuilder::new()
+                        .schema_type(Type::String)
+                        .description(Some($description))
+                        .pattern(Some($pattern))
+                        .into()
+                }
+            }
+
+            impl ToSchema for $name {}
+        )*
+    };
+}
+
+impl_string_schema!(
+    QubicId "60 uppercase character identity" "^[A-Z]{60}$"
+    QubicTxHash "60 lowercase character transaction hash" "^[a-z]{60}$"
+    MiningSeed "60 lowercase character mining seed" "^[a-z]{60}$"
+    Signature "0x prefixed hexadecimal 64 byte signature" "^0x[0-9a-f]{128}$"
+    Nonce "0x prefixed hexadecimal 32 byte nonce" "^0x[0-9a-f]{64}$"
+);
+
+/// OpenAPI schemas of the integer newtypes, serialized as plain numbers
+macro_rules! impl_integer_schema {
+    ($($name: ident $description: literal $format: ident)*) => {
+        $(
+            impl PartialSchema for $name {
+                fn schema() -> RefOr<Schema> {
+                    ObjectBuilder::new()
+                        .schema_type(Type::Integer)
+                        .format(Some(SchemaFormat::KnownFormat(KnownFormat::$format)))
+                        .minimum(Some(0))
+                        .description(Some($description))
+                        .into()
+                }
+            }
+
+            impl ToSchema for $name {}
+        )*
+    };
+}
+
+impl_integer_schema!(
+    Tick "tick number" Int64
+    Epoch "epoch number" Int32
+);
@@ -1,9 +1,9 @@
 use core::str::FromStr;
-use alloc::format;
+use alloc::{format, string::{String, ToString}};
 
 use serde::{Serialize, Deserialize, de::Visitor};
 
-use crate::{QubicId, Signature, MiningSeed, Nonce, QubicTxHash};
+use crate::{QubicId, Signature, MiningSeed, Nonce, QubicTxHash, Tick, Epoch};
 
 
 struct QubicIdVisitor;
@@ -169,4 +169,28 @@ impl<'de> Deserialize<'de> for Nonce {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
         Ok(Nonce(deserializer.deserialize_str(HexVisitor)?))
     }
-}
\ No newline at end of file
+}
+
+impl Serialize for Tick {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        serializer.serialize_u32(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Tick {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        u32::deserialize(deserializer).map(Tick)
+    }
+}
+
+impl Serialize for Epoch {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: serde::Serializer {
+        serializer.serialize_u16(self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Epoch {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: serde::Deserializer<'de> {
+        u16::deserialize(deserializer).map(Epoch)
+    }
+}
@@ -1,5 +1,7 @@
 use core::str::FromStr;
 
+mod vectors;
+
 use crate::{QubicId, QubicWallet};
 
 const SEED: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
@@ -28,4 +30,497 @@ pub fn test_wallet() {
     let id = QubicId::from_str(ID).unwrap();
 
     assert!(id.verify(10u64, signature));
-}
\ No newline at end of file
+}
+#[test]
+pub fn test_invalid_id_errors() {
+    use crate::errors::{IdKind, QubicError};
+
+    assert_eq!(QubicId::from_str(&ID[..59]).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: 59 });
+    assert_eq!(QubicId::check_id(&ID[..59]).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: 59 });
+    assert_eq!(QubicId::from_str(&ID.to_lowercase()).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::Identity });
+    assert_eq!(QubicId::check_id(&format!("{}É", &ID[..59])).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::Identity });
+    assert_eq!(QubicId::from_slice(&[0; 31]).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::PublicKeyBytes, found: 31 });
+
+    assert_eq!(QubicWallet::from_seed(&SEED[..54]).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::Seed, found: 54 });
+    assert_eq!(QubicWallet::from_seed(&SEED.to_uppercase()).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::Seed });
+    assert_eq!(QubicWallet::from_seed(&format!("{}é", &SEED[..54])).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::Seed });
+
+    assert_eq!(crate::QubicTxHash::from_str(ID).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::TxHash });
+    assert_eq!(crate::QubicTxHash::from_str(&ID[..59].to_lowercase()).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::TxHash, found: 59 });
+
+    assert_eq!(IdKind::Identity.expected_len(), 60);
+    assert_eq!(IdKind::Seed.expected_len(), 55);
+    assert_eq!(IdKind::PublicKeyBytes.expected_len(), 32);
+    assert_eq!(IdKind::TxHashBytes.expected_len(), 32);
+    assert_eq!(
+        QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: 59 }.to_string(),
+        "Invalid length of identity (60 uppercase characters A-Z), found 59"
+    );
+}
+
+#[test]
+pub fn test_batch_conversion() {
+    use crate::batch;
+
+    let ids = (0..64u64).map(|i| QubicId::from_le_u64([i, i * 7, i * 13, i * 17])).collect::<Vec<_>>();
+    let identities = batch::identities_from_ids(&ids);
+
+    assert_eq!(identities, ids.iter().map(|id| id.get_identity()).collect::<Vec<_>>());
+    assert_eq!(batch::identities_from_ids_iter(ids.iter().copied()).collect::<Vec<_>>(), identities);
+
+    let mut strs = identities.iter().map(String::as_str).collect::<Vec<_>>();
+    strs.push("INVALID");
+
+    let parsed = batch::ids_from_strs(&strs);
+
+    assert_eq!(parsed[..64].iter().map(|id| *id.as_ref().unwrap()).collect::<Vec<_>>(), ids);
+    assert!(parsed[64].is_err());
+    assert_eq!(batch::ids_from_strs_iter(strs.iter().copied()).collect::<Vec<_>>(), parsed);
+}
+
+#[test]
+pub fn test_id_formatting() {
+    use crate::QubicTxHash;
+
+    let id = QubicId::from_str(ID).unwrap();
+
+    assert_eq!(format!("{id:?}"), "BZBQF...BQEXK");
+    assert_eq!(format!("{id:#?}"), ID);
+    assert_eq!(format!("{id}"), ID);
+    assert_eq!(id.redact(), "BZBQ...QEXK");
+
+    for id in [QubicId::default(), QubicId([0xFF; 32])] {
+        let identity = id.get_identity();
+
+        assert_eq!(format!("{id:?}"), format!("{}...{}", &identity[..5], &identity[55..]));
+        assert_eq!(format!("{id:#?}"), identity);
+        assert_eq!(id.redact(), format!("{}...{}", &identity[..4], &identity[56..]));
+    }
+
+    for hash in [QubicTxHash::default(), QubicTxHash([0xFF; 32])] {
+        let identity = hash.get_identity();
+
+        assert_eq!(identity.len(), 60);
+        assert_eq!(format!("{hash:?}"), format!("{}...{}", &identity[..5], &identity[55..]));
+        assert_eq!(format!("{hash:#?}"), identity);
+        assert_eq!(hash.redact(), format!("{}...{}", &identity[..4], &identity[56..]));
+    }
+}
+
+#[test]
+fn test_constant_time_eq() {
+    use crate::{ConstantTimeEq, Nonce, QubicTxHash, Signature};
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let signature = wallet.sign(10u64);
+
+    let mut signatures = vec![signature, Signature::default()];
+    // differences in the first, a middle and the last byte
+    for i in [0, 31, 63] {
+        let mut other = signature;
+        other.0[i] ^= 1;
+        signatures.push(other);
+    }
+
+    for a in signatures.iter() {
+        for b in signatures.iter() {
+            assert_eq!(bool::from(a.ct_eq(b)), a == b);
+        }
+    }
+
+    let ids = [wallet.public_key, QubicId::default(), QubicId([1; 32])];
+    for a in ids.iter() {
+        for b in ids.iter() {
+            assert_eq!(bool::from(a.ct_eq(b)), a == b);
+            assert_eq!(bool::from(Nonce(a.0).ct_eq(&Nonce(b.0))), a == b);
+            assert_eq!(bool::from(QubicTxHash(a.0).ct_eq(&QubicTxHash(b.0))), a == b);
+        }
+    }
+
+    // verification still accepts the signature and rejects tampered ones
+    assert!(wallet.public_key.verify(10u64, signature));
+    assert!(signatures[2..].iter().all(|tampered| !wallet.public_key.verify(10u64, *tampered)));
+}
+
+#[test]
+fn test_message_domain_separation() {
+    use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
+    use crate::message::{message_digest, sign_message, verify_message};
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let id = wallet.public_key;
+    // bytes of a transaction without its signature, signed the way `Sign` signs transactions
+    let tx = [[1u8; 32].as_slice(), &[2; 32], &100u64.to_le_bytes(), &12_000_000u32.to_le_bytes(), &[0; 4]].concat();
+    let mut tx_digest = [0; 32];
+    let mut kg = KangarooTwelve::new(b"");
+    kg.update(&tx);
+    kg.into_xof().squeeze(&mut tx_digest);
+
+    let tx_signature = wallet.sign_raw(tx_digest);
+    assert!(id.verify_raw(tx_digest, tx_signature));
+    // a transaction signature does not validate as a message of any domain
+    assert!(!verify_message(&id, "", &tx, tx_signature));
+    assert!(!verify_message(&id, "example.org", &tx, tx_signature));
+
+    // a message signature neither validates as a transaction nor in another domain
+    let signature = sign_message(&wallet, "example.org", &tx);
+    assert!(verify_message(&id, "example.org", &tx, signature));
+    assert!(!id.verify_raw(tx_digest, signature));
+    assert!(!verify_message(&id, "example.com", &tx, signature));
+    assert!(!verify_message(&QubicId([1; 32]), "example.org", &tx, signature));
+
+    // the domain length prefix keeps the split between domain and payload unambiguous
+    assert_ne!(message_digest("ab", b"c"), message_digest("a", b"bc"));
+}
+
+#[test]
+fn test_signed_challenge() {
+    use crate::{errors::ChallengeError, message::SignedChallenge, Nonce};
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let challenge = SignedChallenge::sign(&wallet, "example.org", 1_700_000_000, Nonce([7; 32]));
+
+    assert_eq!(challenge.verify("example.org", 1_700_000_000, 300, 0), Ok(()));
+    assert_eq!(challenge.verify("example.org", 1_700_000_300, 300, 0), Ok(()));
+    assert_eq!(challenge.verify("example.org", 1_700_000_301, 300, 0), Err(ChallengeError::Expired { expired_for: 1 }));
+    assert_eq!(challenge.verify("example.org", 1_699_999_990, 300, 0), Err(ChallengeError::IssuedInFuture { ahead: 10 }));
+    assert_eq!(challenge.verify("example.com", 1_700_000_000, 300, 0), Err(ChallengeError::WrongAudience { expected: "example.com".to_owned(), found: "example.org".to_owned() }));
+
+    // a clock of the signer running ahead is tolerated up to the skew
+    assert_eq!(challenge.verify("example.org", 1_699_999_990, 300, 10), Ok(()));
+    assert_eq!(challenge.verify("example.org", 1_699_999_989, 300, 10), Err(ChallengeError::IssuedInFuture { ahead: 11 }));
+
+    // every signed field is covered by the signature
+    let tampered = [
+        SignedChallenge { timestamp: challenge.timestamp + 1, ..challenge.clone() },
+        SignedChallenge { nonce: Nonce([8; 32]), ..challenge.clone() },
+        SignedChallenge { identity: QubicId([1; 32]), ..challenge.clone() }
+    ];
+
+    for tampered in tampered {
+        assert!(matches!(tampered.verify("example.org", 1_700_000_001, 300, 0), Err(ChallengeError::InvalidSignature { .. })));
+    }
+
+    let json = serde_json::to_value(&challenge).unwrap();
+    assert_eq!(json["audience"], "example.org");
+    assert_eq!(serde_json::from_value::<SignedChallenge>(json).unwrap(), challenge);
+}
+
+#[test]
+fn test_seed_string() {
+    use crate::{errors::{IdKind, QubicError}, SeedString};
+
+    let seed = SeedString::from_str(SEED).unwrap();
+    assert_eq!(seed.as_str(), SEED);
+    assert_eq!(seed.wallet().get_identity(), ID);
+    assert_eq!(format!("{seed:?}"), "SeedString(***)");
+
+    assert_eq!(SeedString::from_str(&SEED[..54]).unwrap_err(), QubicError::InvalidIdLengthError { kind: IdKind::Seed, found: 54 });
+    assert_eq!(SeedString::try_from(SEED.to_uppercase()).unwrap_err(), QubicError::InvalidIdFormatError { kind: IdKind::Seed });
+}
+
+#[test]
+fn test_generated_seed_distribution() {
+    use rand::{rngs::StdRng, SeedableRng};
+
+    let mut rng = StdRng::seed_from_u64(1006);
+    let mut counts = [0u32; 26];
+
+    for _ in 0..2000 {
+        let (seed, wallet) = QubicWallet::generate(&mut rng);
+
+        assert_eq!(QubicWallet::from_seed(&seed).unwrap().public_key, wallet.public_key);
+
+        for c in seed.bytes() {
+            counts[(c - b'a') as usize] += 1;
+        }
+    }
+
+    // chi-squared with 25 degrees of freedom, 52.6 is exceeded with a probability of 0.1%
+    let expected = (2000 * 55) as f64 / 26.0;
+    let chi_squared: f64 = counts.iter().map(|count| (*count as f64 - expected).powi(2) / expected).sum();
+
+    assert!(chi_squared < 52.6, "chi-squared {chi_squared} of {counts:?}");
+}
+
+#[cfg(feature = "mnemonic")]
+#[test]
+fn test_mnemonic() {
+    use rand::{rngs::StdRng, SeedableRng};
+    use crate::{errors::MnemonicError, mnemonic, SeedString};
+
+    for index in 0..4096 {
+        assert_eq!(mnemonic::word_index(&mnemonic::word(index)), Some(index));
+    }
+
+    let mut rng = StdRng::seed_from_u64(1006);
+    let seeds = [SeedString::from_str(SEED).unwrap(), SeedString::from_str(&"z".repeat(55)).unwrap()].into_iter()
+        .chain((0..100).map(|_| SeedString::random(&mut rng)));
+
+    for seed in seeds {
+        let words = mnemonic::to_words(&seed);
+        assert_eq!(words.len(), mnemonic::WORDS);
+
+        let decoded = mnemonic::from_phrase(&mnemonic::to_phrase(&seed).to_uppercase()).unwrap();
+        assert_eq!(decoded, seed);
+        assert_eq!(decoded.wallet().get_identity(), seed.wallet().get_identity());
+    }
+
+    let mut words = mnemonic::to_words(&SeedString::from_str(SEED).unwrap());
+    assert_eq!(mnemonic::from_words(&words[1..]), Err(MnemonicError::InvalidWordCount { expected: mnemonic::WORDS, found: mnemonic::WORDS - 1 }));
+
+    words[3] = mnemonic::word(mnemonic::word_index(&words[3]).unwrap() ^ 1);
+    assert_eq!(mnemonic::from_words(&words), Err(MnemonicError::ChecksumMismatch));
+
+    words[3] = "qubic".to_owned();
+    assert_eq!(mnemonic::from_words(&words), Err(MnemonicError::UnknownWord { position: 3, word: "qubic".to_owned() }));
+
+    // the largest value of 22 words exceeds 26^55
+    let overflow = vec![mnemonic::word(4095); mnemonic::WORDS];
+    assert_eq!(mnemonic::from_words(&overflow), Err(MnemonicError::OutOfRange));
+}
+
+#[test]
+fn test_u24() {
+    use crate::{errors::U24OverflowError, traits::{FromBytes, ToBytes}, U24};
+
+    let max = U24::try_from(0xFF_FFFFusize).unwrap();
+    assert_eq!(usize::from(max), U24::MAX);
+    assert_eq!(max.to_bytes(), [0xFF; 3]);
+    assert_eq!(U24::try_from(0x100_0000usize), Err(U24OverflowError { value: 0x100_0000 }));
+    assert_eq!(U24::try_from(u32::MAX), Err(U24OverflowError { value: u32::MAX as usize }));
+
+    let size = U24::from_bytes(&[0x56, 0x34, 0x12]).unwrap();
+    assert_eq!(size.get(), 0x12_3456);
+    assert_eq!(U24::try_from(0x12_3456usize).unwrap(), size);
+    assert_eq!(U24::from(0xABCDu16).get(), 0xABCD);
+    assert!(U24::from_bytes(&[0; 4]).is_err());
+    assert_eq!(*U24::from_bytes_boxed(&[0x56, 0x34, 0x12]).unwrap(), size);
+    assert!(U24::from_bytes_boxed(&[0; 2]).is_err());
+
+    assert!(U24::from(0x100u16) > U24::from(0xFFu16));
+    assert_eq!(max.checked_add(0), Some(max));
+    assert_eq!(max.checked_add(1), None);
+    assert_eq!(U24::ZERO.checked_sub(1), None);
+    assert_eq!(max.checked_sub(U24::MAX), Some(U24::ZERO));
+    assert_eq!(U24::from(8u16).saturating_sub(16), 0);
+    assert_eq!(format!("{max} {max:?}"), "16777215 16777215");
+}
+
+#[test]
+fn test_tick_and_epoch() {
+    use crate::{Epoch, Tick};
+
+    let mut tick = Tick::from(12_000_000);
+    assert_eq!(tick + 5, Tick(12_000_005));
+    tick += 1;
+    assert_eq!(tick.get(), 12_000_001);
+    assert_eq!(tick.saturating_sub(2), Tick(11_999_999));
+    assert_eq!(Tick(3).saturating_sub(5), Tick(0));
+    assert_eq!(tick.ticks_since(Tick(12_000_000)), 1);
+    assert_eq!(Tick(12_000_000).ticks_since(tick), 0);
+    assert!(Tick(2) > Tick(1));
+
+    assert_eq!(format!("{tick} {}", Epoch(101)), "12000001 101");
+    assert_eq!("101".parse::<Epoch>(), Ok(Epoch(101)));
+    assert!("70000".parse::<Epoch>().is_err());
+    assert!("-1".parse::<Tick>().is_err());
+    assert_eq!((u32::from(tick), u16::from(Epoch(7))), (12_000_001, 7));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_tick_and_epoch_serde() {
+    use crate::{Epoch, Tick};
+
+    assert_eq!(serde_json::to_value(Tick(12_000_000)).unwrap(), serde_json::json!(12_000_000));
+    assert_eq!(serde_json::to_value(Epoch(101)).unwrap(), serde_json::json!(101));
+    assert_eq!(serde_json::from_str::<Tick>("12000000").unwrap(), Tick(12_000_000));
+    assert_eq!(serde_json::from_str::<Epoch>("101").unwrap(), Epoch(101));
+    assert!(serde_json::from_str::<Epoch>("70000").is_err());
+}
+
+#[test]
+fn test_short_identity() {
+    let id = QubicId::from_str(ID).unwrap();
+    assert_eq!(id.short(), "BZBQF...BQEXK");
+    assert_eq!(id.short(), format!("{id:?}"));
+
+    let hash = crate::QubicTxHash([7; 32]);
+    assert_eq!(hash.short(), format!("{hash:?}"));
+    assert_eq!(hash.short(), format!("{}...{}", &hash.get_identity()[..5], &hash.get_identity()[55..]));
+}
+
+#[test]
+fn test_qubic_uri() {
+    use crate::{errors::UriError, uri::QubicUri};
+
+    let id = QubicId::from_str(ID).unwrap();
+    let uri = QubicUri::new(id).with_amount(1000).with_tick(12_000_000).with_label("Coffee & cake");
+    let encoded = format!("qubic:{ID}?amount=1000&tick=12000000&label=Coffee%20%26%20cake");
+
+    assert_eq!(uri.to_string(), encoded);
+    assert_eq!(QubicUri::from_str(&encoded).unwrap(), uri);
+    assert_eq!(QubicUri::from_str(&format!("QUBIC:{ID}")).unwrap(), QubicUri::new(id));
+
+    // unknown parameters survive a round trip
+    let uri = QubicUri::from_str(&format!("qubic:{ID}?memo=order%2F42&amount=5&flag")).unwrap();
+    assert_eq!((uri.amount, uri.params.clone()), (Some(5), vec![("memo".to_owned(), "order/42".to_owned()), ("flag".to_owned(), String::new())]));
+    assert_eq!(uri.to_string(), format!("qubic:{ID}?amount=5&memo=order%2F42&flag="));
+    assert_eq!(QubicUri::from_str(&uri.to_string()).unwrap(), uri);
+
+    let mut bad_checksum = ID.to_owned();
+    bad_checksum.replace_range(59.., "A");
+    assert_eq!(QubicUri::from_str(&format!("qubic:{bad_checksum}")), Err(UriError::ChecksumMismatch { identity: bad_checksum }));
+
+    assert_eq!(QubicUri::from_str(&format!("qubic:{ID}?amount=-5")), Err(UriError::InvalidParam { param: "amount".to_owned(), value: "-5".to_owned() }));
+    assert_eq!(QubicUri::from_str(&format!("qubic:{ID}?tick=5&tick=6")), Err(UriError::DuplicateParam { param: "tick".to_owned() }));
+    assert_eq!(QubicUri::from_str(&format!("qubic:{ID}?label=%2")), Err(UriError::InvalidEncoding("%2".to_owned())));
+    assert_eq!(QubicUri::from_str(&format!("bitcoin:{ID}")), Err(UriError::InvalidScheme));
+    assert!(matches!(QubicUri::from_str(&format!("qubic:{}", &ID[..59])), Err(UriError::InvalidIdentity(_))));
+}
+
+#[test]
+fn test_wallet_signer() {
+    use crate::{errors::QubicError, traits::Signer};
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let signature = wallet.sign_message(&wallet.public_key, 1006u64).unwrap();
+
+    assert_eq!(signature, wallet.sign(1006u64));
+    assert_eq!(wallet.sign_digest(&QubicId::default(), [0; 32]).unwrap_err(), QubicError::WrongSignature { expected: wallet.public_key, found: QubicId::default() });
+}
+
+#[cfg(feature = "keystore")]
+#[test]
+fn test_keystore() {
+    use std::{sync::Arc, time::Duration};
+    use crate::{errors::KeystoreError, keystore::Keystore, traits::Signer, OsRng, SeedString};
+
+    let path = std::env::temp_dir().join(format!("qubic-types-keystore-{}.qks", std::process::id()));
+    let _ = std::fs::remove_file(&path);
+
+    let keystore = Keystore::open(&path, "password").unwrap();
+    let first = keystore.add(SeedString::from_str(SEED).unwrap()).unwrap();
+    let ids: Vec<QubicId> = (0..3).map(|_| keystore.add(SeedString::random(&mut OsRng)).unwrap()).collect();
+
+    assert_eq!(first, QubicId::from_str(ID).unwrap());
+    assert_eq!(keystore.add(SeedString::from_str(SEED).unwrap()).unwrap(), first);
+    assert!(keystore.remove(&ids[1]).unwrap());
+    assert!(!keystore.remove(&ids[1]).unwrap());
+    assert_eq!(keystore.identities(), [first, ids[0], ids[2]]);
+
+    // the file holds every change, a wrong password does not decrypt it
+    assert!(matches!(Keystore::open(&path, "wrong"), Err(KeystoreError::WrongPassword)));
+    let keystore = Arc::new(Keystore::open(&path, "password").unwrap());
+    assert_eq!(keystore.identities(), [first, ids[0], ids[2]]);
+
+    // threads sign concurrently on behalf of every identity
+    let threads: Vec<_> = (0..8).map(|thread| {
+        let (keystore, identities) = (keystore.clone(), keystore.identities());
+
+        std::thread::spawn(move || for message in 0..20u64 {
+            let id = identities[(thread + message as usize) % identities.len()];
+            assert!(id.verify(message, keystore.sign_message(&id, message).unwrap()));
+        })
+    }).collect();
+
+    threads.into_iter().for_each(|thread| thread.join().unwrap());
+    assert!(matches!(keystore.sign_digest(&ids[1], [0; 32]), Err(KeystoreError::UnknownIdentity(id)) if id == ids[1]));
+
+    // locked keystores only enumerate their identities
+    keystore.lock();
+    assert!(keystore.is_locked());
+    assert_eq!(keystore.identities().len(), 3);
+    assert!(matches!(keystore.sign_digest(&first, [0; 32]), Err(KeystoreError::Locked)));
+    assert!(matches!(keystore.add(SeedString::random(&mut OsRng)), Err(KeystoreError::Locked)));
+    assert!(matches!(keystore.unlock("wrong"), Err(KeystoreError::WrongPassword)));
+    keystore.unlock("password").unwrap();
+    assert!(keystore.sign_digest(&first, [0; 32]).is_ok());
+
+    let keystore = Keystore::open(&path, "password").unwrap().with_auto_lock(Duration::from_millis(50));
+    assert!(!keystore.is_locked());
+    std::thread::sleep(Duration::from_millis(60));
+    assert!(matches!(keystore.sign_digest(&first, [0; 32]), Err(KeystoreError::Locked)));
+    assert!(keystore.is_locked());
+
+    // a truncated file is rejected
+    let data = std::fs::read(&path).unwrap();
+    std::fs::write(&path, &data[..20]).unwrap();
+    assert!(matches!(Keystore::open(&path, "password"), Err(KeystoreError::Malformed)));
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(feature = "keystore")]
+#[test]
+fn test_keystore_zeroize() {
+    use zeroize::Zeroize;
+    use crate::SeedString;
+
+    let mut seed = SeedString::from_str(SEED).unwrap();
+    let mut wallet = seed.wallet();
+
+    seed.zeroize();
+    wallet.zeroize();
+
+    assert_eq!(seed.as_str(), "");
+    assert_eq!((wallet.private_key, wallet.subseed), ([0; 32], [0; 32]));
+}
+
+#[cfg(feature = "serde")]
+#[test]
+fn test_amount_format() {
+    use crate::amount::{NumberFormat, QuAmount};
+
+    #[derive(Debug, PartialEq, serde::Serialize, serde::Deserialize)]
+    struct Transfer {
+        #[serde(with = "crate::amount")]
+        amount: u64,
+        #[serde(default, with = "crate::amount::option")]
+        fee: Option<u64>
+    }
+
+    let transfer = Transfer { amount: u64::MAX, fee: Some(1) };
+
+    assert_eq!(serde_json::to_value(&transfer).unwrap(), serde_json::json!({ "amount": u64::MAX, "fee": 1 }));
+    assert_eq!(NumberFormat::String.scope(|| serde_json::to_value(&transfer)).unwrap(), serde_json::json!({ "amount": "18446744073709551615", "fee": "1" }));
+    // the format is restored after the scope
+    assert_eq!(NumberFormat::current(), NumberFormat::Number);
+
+    // both forms are read in either format
+    for json in [serde_json::json!({ "amount": u64::MAX, "fee": 1 }), serde_json::json!({ "amount": "18446744073709551615", "fee": "1" })] {
+        assert_eq!(serde_json::from_value::<Transfer>(json.clone()).unwrap(), transfer);
+        assert_eq!(NumberFormat::String.scope(|| serde_json::from_value::<Transfer>(json)).unwrap(), transfer);
+    }
+
+    assert_eq!(serde_json::from_value::<Transfer>(serde_json::json!({ "amount": 5 })).unwrap(), Transfer { amount: 5, fee: None });
+    assert!(serde_json::from_value::<QuAmount>(serde_json::json!(-1)).is_err());
+    assert!(serde_json::from_value::<QuAmount>(serde_json::json!("1.5")).is_err());
+    assert_eq!(serde_json::from_str::<NumberFormat>("\"string\"").unwrap(), NumberFormat::String);
+}
+
+#[test]
+fn test_parse_lenient() {
+    use crate::{errors::{IdKind, QubicError}, LenientId};
+
+    let valid = Ok(LenientId::Valid(QubicId::from_str(ID).unwrap()));
+    assert_eq!(QubicId::parse_lenient(ID), valid);
+    assert_eq!(QubicId::parse_lenient(&format!("  {}\t\n", ID.to_lowercase())), valid);
+
+    // mistyped in the key and in the checksum
+    for typo in ["BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXA", "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNXLZBQEXK"] {
+        assert_eq!(QubicId::parse_lenient(typo), Ok(LenientId::Suggestion(vec![ID.to_string()])));
+    }
+
+    // two corrections match, neither is picked
+    assert_eq!(QubicId::parse_lenient("BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBDEXK"), Ok(LenientId::Suggestion(vec![
+        "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQURSSILFABOFFBCADQSSUPNWLZBDEXK".to_string(),
+        ID.to_string()
+    ])));
+
+    // more than a single character is off
+    assert_eq!(QubicId::parse_lenient("BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEAA"), Err(QubicError::ChecksumMismatch { kind: IdKind::Identity }));
+    assert_eq!(QubicId::parse_lenient("BZBQ"), Err(QubicError::InvalidIdLengthError { kind: IdKind::Identity, found: 4 }));
+    assert_eq!(QubicId::parse_lenient(&ID.replace('B', "1")), Err(QubicError::InvalidIdFormatError { kind: IdKind::Identity }));
+}
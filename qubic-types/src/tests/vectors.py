@@ -0,0 +1,118 @@
+#!/usr/bin/env python3
+"""Generates the identity vectors of vectors.rs independently of this crate.
+
+The identity encoding is a port of getIdentity of the Qubic core (src/four_q.h): every 8-byte little-endian fragment
+of the public key gives 14 base-26 letters, the checksum is the first 3 bytes of KangarooTwelve over the key masked to
+18 bits and gives 4 more letters. KangarooTwelve is implemented from RFC 9861 and checked against its test vectors
+before anything is printed.
+
+Usage: python3 vectors.py
+"""
+
+import random
+
+ROUND_CONSTANTS = [
+    0x0000000000000001, 0x0000000000008082, 0x800000000000808A, 0x8000000080008000, 0x000000000000808B, 0x0000000080000001,
+    0x8000000080008081, 0x8000000000008009, 0x000000000000008A, 0x0000000000000088, 0x0000000080008009, 0x000000008000000A,
+    0x000000008000808B, 0x800000000000008B, 0x8000000000008089, 0x8000000000008003, 0x8000000000008002, 0x8000000000000080,
+    0x000000000000800A, 0x800000008000000A, 0x8000000080008081, 0x8000000000008080, 0x0000000080000001, 0x8000000080008008,
+]
+ROTATIONS = [[0, 36, 3, 41, 18], [1, 44, 10, 45, 2], [62, 6, 43, 15, 61], [28, 55, 25, 21, 56], [27, 20, 39, 8, 14]]
+MASK = (1 << 64) - 1
+
+
+def rol(value, shift):
+    return ((value << shift) | (value >> (64 - shift))) & MASK if shift else value
+
+
+def keccak_p12(lanes):
+    """Keccak-p[1600, 12], the last 12 rounds of Keccak-f[1600], on lanes indexed [x][y]"""
+    for rc in ROUND_CONSTANTS[12:]:
+        c = [lanes[x][0] ^ lanes[x][1] ^ lanes[x][2] ^ lanes[x][3] ^ lanes[x][4] for x in range(5)]
+        d = [c[(x - 1) % 5] ^ rol(c[(x + 1) % 5], 1) for x in range(5)]
+        lanes = [[lanes[x][y] ^ d[x] for y in range(5)] for x in range(5)]
+
+        b = [[0] * 5 for _ in range(5)]
+        for x in range(5):
+            for y in range(5):
+                b[y][(2 * x + 3 * y) % 5] = rol(lanes[x][y], ROTATIONS[x][y])
+
+        lanes = [[b[x][y] ^ (~b[(x + 1) % 5][y] & b[(x + 2) % 5][y]) for y in range(5)] for x in range(5)]
+        lanes[0][0] ^= rc
+
+    return lanes
+
+
+def turbo_shake128(message, domain, length):
+    rate = 168
+    padded = bytearray(message) + bytes([domain])
+    padded += bytes(-len(padded) % rate)
+    padded[-1] ^= 0x80
+
+    lanes = [[0] * 5 for _ in range(5)]
+    for offset in range(0, len(padded), rate):
+        block = padded[offset:offset + rate]
+        for i in range(rate // 8):
+            lanes[i % 5][i // 5] ^= int.from_bytes(block[8 * i:8 * i + 8], "little")
+        lanes = keccak_p12(lanes)
+
+    output = bytearray()
+    while True:
+        output += b"".join(lanes[i % 5][i // 5].to_bytes(8, "little") for i in range(rate // 8))
+        if len(output) >= length:
+            return bytes(output[:length])
+        lanes = keccak_p12(lanes)
+
+
+def kangaroo_twelve(message, length):
+    """KT128 without customization for messages up to one chunk, which covers 32-byte keys"""
+    s = bytes(message) + b"\x00"
+    assert len(s) <= 8192
+    return turbo_shake128(s, 0x07, length)
+
+
+def identity(key):
+    letters = []
+    for i in range(4):
+        fragment = int.from_bytes(key[8 * i:8 * i + 8], "little")
+        for _ in range(14):
+            letters.append(chr(ord("A") + fragment % 26))
+            fragment //= 26
+
+    checksum = int.from_bytes(kangaroo_twelve(key, 3), "little") & 0x3FFFF
+    for _ in range(4):
+        letters.append(chr(ord("A") + checksum % 26))
+        checksum //= 26
+
+    return "".join(letters)
+
+
+def ptn(length):
+    return bytes(i % 251 for i in range(length))
+
+
+# RFC 9861 section 5, KT128 with an empty customization string
+assert kangaroo_twelve(b"", 32).hex() == "1ac2d450fc3b4205d19da7bfca1b37513c0803577ac7167f06fe2ce1f0ef39e5"
+assert kangaroo_twelve(ptn(1), 32).hex() == "2bda92450e8b147f8a7cb629e784a058efca7cf7d8218e02d345dfaa65244a1f"
+assert kangaroo_twelve(ptn(17), 32).hex() == "6bf75fa2239198db4772e36478f8e19b0f371205f6a9a93a273f51df37122888"
+
+KEYS = [
+    bytes.fromhex("1f590d03e613bdded38b4c0820ac44615f91af12435980b3ede3c08c315a2544"),
+    bytes(32),
+    bytes([0xff] * 32),
+    bytes([0x80] * 32),
+    bytes([0x7f] * 32),
+    bytes([0x01] * 32),
+    bytes(range(32)),
+    bytes(range(255, 223, -1)),
+    bytes([0] * 7 + [0xff]) * 4,
+    bytes([0xff, 0]) * 16,
+]
+
+# keys with random bytes, then keys with the high bit of every byte set
+rng = random.Random(657)
+KEYS += [bytes(rng.randrange(256) for _ in range(32)) for _ in range(2)]
+KEYS += [bytes(0x80 | rng.randrange(128) for _ in range(32)) for _ in range(13)]
+
+for key in KEYS:
+    print(f'    ("{key.hex()}", "{identity(key)}"),')
@@ -0,0 +1,74 @@
+//! Identities of public keys from the reference implementation, catches any drift of the encoding or the checksum.
+//!
+//! The vectors are printed by `vectors.py` next to this file, a port of `getIdentity` of the Qubic core
+//! (`src/four_q.h`) with a KangarooTwelve implemented from RFC 9861 and checked against its test vectors. It shares no
+//! code with this crate, so the vectors don't just repeat what the encoding computes
+
+use core::str::FromStr;
+
+use crate::{checksum_chars, MiningSeed, QubicId, QubicTxHash};
+
+/// (hex public key, identity): fixed patterns, then keys drawn with seed 657, the last 13 with the high bit of every byte set
+const VECTORS: [(&str, &str); 25] = [
+    ("1f590d03e613bdded38b4c0820ac44615f91af12435980b3ede3c08c315a2544", "BZBQFLLBNCXEMGLOBHUVFTLUPLVCPQUASSILFABOFFBCADQSSUPNWLZBQEXK"),
+    ("0000000000000000000000000000000000000000000000000000000000000000", "AAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAAFXIB"),
+    ("ffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffffff", "PQMUYSXMZCXHLHPQMUYSXMZCXHLHPQMUYSXMZCXHLHPQMUYSXMZCXHLHTGWM"),
+    ("8080808080808080808080808080808080808080808080808080808080808080", "GTVHKDSRGUUATDGTVHKDSRGUUATDGTVHKDSRGUUATDGTVHKDSRGUUATDRQMB"),
+    ("7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f7f", "JXQMOPFVSICHSDJXQMOPFVSICHSDJXQMOPFVSICHSDJXQMOPFVSICHSDLCYA"),
+    ("0101010101010101010101010101010101010101010101010101010101010101", "XVEVVNMWNLSTAAXVEVVNMWNLSTAAXVEVVNMWNLSTAAXVEVVNMWNLSTAADFIB"),
+    ("000102030405060708090a0b0c0d0e0f101112131415161718191a1b1c1d1e1f", "ICTNHRYOMCXHFAKVFBAYUMTQOJLAMOSOSERKAFGLRAOHFCLLNIHTXMXAWAPO"),
+    ("fffefdfcfbfaf9f8f7f6f5f4f3f2f1f0efeeedecebeae9e8e7e6e5e4e3e2e1e0", "HOTGRBZXMAAAGHFVGTYUCAGMIYZGDCUFGOGCZXQWTGBJHSNHKESJZUNGHYAL"),
+    ("00000000000000ff00000000000000ff00000000000000ff00000000000000ff", "UEZESAXUMRGOKHUEZESAXUMRGOKHUEZESAXUMRGOKHUEZESAXUMRGOKHETXO"),
+    ("ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00ff00", "VDRPANJBWLOTAAVDRPANJBWLOTAAVDRPANJBWLOTAAVDRPANJBWLOTAAYCIO"),
+    ("a181a18191de01f7f9f6fa191e02823d1e17e6a290d31d9c7ebcd34c9b955a13", "TJLBFVCANWINEHHZMBHLPOQEOLUBSWGTUCAUKKYWNECKQZFKZOIAZPOAKFZN"),
+    ("06a9d3906a6d2ff1e2706f932cd180ed2fe0da5e1441e6a0416e326bb151f8a1", "QFBETYCVJBBDAHGOAUVWFAVOTIXGHMOTMGUGXUVMREJWXMKQNJXGWHSEWFAJ"),
+    ("bea4f391c394debbcb88bcd08fa4c3dec6f092e4f4b4a8d6ce8886ffdae98886", "OCTMZTGNHLIWLFJZENSKFBWEKFMGCCEWLTSXNYGCGGGOZJVTJQGFGPXDIFXB"),
+    ("948fc6b79dadb6bc8da5e7e6e8cbd999fed181c4eab39ee1d59fbbe0c3fac1b2", "MODARFMQDIXMMFPJBKWEKWQWLEMEEVQPEIMFHWLJOGVMQBMNTQDLLZEFFRFJ"),
+    ("c980fdf291ab839acf95c2aff5f8c3a0dad6c0af8bffbe97e7a7f4e788edce87", "VMLMPZGDFPMRMEZJPSKCELRLFKREMLIGGUJLKODPKEVVYOYZSRKGGOYDRQXJ"),
+    ("edafd4c4d999bdb4a5a4d29098aa92b8ecbbc3b48a908981c6bdf2cab8ef80ce", "BGQKKPQFDPJMGFLLNZYYBCHTPJJFOXYVUJSUOHDVTDGYGHOKFJWCEYZFUDFH"),
+    ("9ab69a87aa9cd2d0b7efde888f99eacea68194f8b4a6eebd948ce39cfce1e0cc", "IDVARCIBBWRRBGPAENLHSOQUGGAGSUJHNRZNJJVKNFUWXDHBERXNGSYFSTAL"),
+    ("ee8985c091acd191829cfa96f0c9eff8f6a8c383ca83f1fdfc9fb2869fe3f893", "CIDWEYQFRDUCGEYFKSPSUVDSFZFHOUQMMWZSBJNTJHCRSTQAFAQFBTHECQVG"),
+    ("aadda4a7d981aadbd3f0f8e5e3b5cce0acca84acc7cad0dfaa828eb1da9ec6d7", "GOKOINSBITOWJGZDNDKUQQXDJTNGUKZKCPKMAWAANGMNXYPVTUUAFYGGENCG"),
+    ("87b2f78bdcafdef8d1a78ecfe1ddb8c2a7d6a4bfc9a5a3b0cad18493cb8b90fd", "XASFWWKMOPXXFHVQPAMWASNIWARFPCDBRGCHSBWJDFYLLZOILZAACMJHAKEC"),
+    ("95b5c1f88cea91bac69882b8b3958297bae68d95e8a991db88a5cf8addafbeb5", "DPLOLFEIJDVWKFGGDZBCOAMCNKKEMNVPVEXVJFRUJGSVDDLCKSXECGHFXNGJ"),
+    ("b29cd9c88599f6dfb1a6d9f4aac3eb9eeb8cc7b7ba99b2d5a8ffbff48997d2fe", "YRUIFAGUAGYCNGHLLUYJRWOXZZPEDSZKEIRSYFKJFGAOBZNFRYLDUKKHTEUF"),
+    ("85aba4f3ccb688f6c8d48d87c0c7b0e59ac4beafcdb5f99afbfc8b85d2e686f5", "PLAVPVGMPHBEEHASRELCIJKRJLRGEVJINJYMPYNANETKNSSVCWDGHKDHBNGK"),
+    ("a6ed8399c4fe94bce6efa5a2cbc2faf2bde69cfc99f6efc0c093a78fd8cc80d7", "WIUAVBYUYDIKMFKIFDGQWUAYGMBHPDSPYAGEWHVRPFSBXVXHDZEVVSGGZFKB"),
+    ("e8e4fb8697c8ccaab78687dfa094a09e9f9aa099e5a7e682fe80a2c3a9b0c197", "AKROBAJRILFZYEXWXTJBMUXZFUPERTQMUSCVBJXVUDURAUFGWTYXIPKEXBHN")
+];
+
+fn vectors() -> impl Iterator<Item = ([u8; 32], &'static str)> {
+    VECTORS.iter().map(|(key, identity)| (hex::decode(key).unwrap().try_into().unwrap(), *identity))
+}
+
+#[test]
+fn test_identity_vectors() {
+    for (key, identity) in vectors() {
+        let id = QubicId(key);
+
+        assert_eq!(id.get_identity(), identity);
+        assert_eq!(id.get_identity_bytes(), identity.as_bytes());
+        assert_eq!(crate::batch::identities_from_ids(&[id]), [identity]);
+        assert_eq!(QubicId::from_str(identity).unwrap(), id);
+    }
+}
+
+#[test]
+fn test_lowercase_vectors() {
+    for (key, identity) in vectors() {
+        let lowercase = identity.to_ascii_lowercase();
+
+        assert_eq!(QubicTxHash(key).get_identity(), lowercase);
+        assert_eq!(MiningSeed(key).get_identity(), lowercase);
+        assert_eq!(QubicTxHash::from_str(&lowercase).unwrap(), QubicTxHash(key));
+        assert_eq!(MiningSeed::from_str(&lowercase).unwrap(), MiningSeed(key));
+    }
+}
+
+#[test]
+fn test_checksum_chars() {
+    for (key, identity) in vectors() {
+        assert_eq!(&checksum_chars(&key, false), &identity.as_bytes()[56..]);
+        assert_eq!(&checksum_chars(&key, true), &identity.to_ascii_lowercase().as_bytes()[56..]);
+    }
+}
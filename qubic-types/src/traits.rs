@@ -1,4 +1,5 @@
 use core::ptr::read_unaligned;
+use alloc::{boxed::Box, vec::Vec};
 use tiny_keccak::{Hasher, IntoXof, KangarooTwelve, Xof};
 use crate::errors::QubicError;
 use crate::{QubicWallet, Signature};
@@ -7,20 +8,48 @@ use crate::{errors::ByteEncodingError, QubicId};
 
 pub trait ToBytes {
     fn to_bytes(&self) -> Vec<u8>;
+
+    /// appends the encoding to `buf`, types composed of other encodings override it to skip the intermediate buffers
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend(self.to_bytes());
+    }
+
+    /// length of the encoding, overridden where it is known without encoding
+    fn encoded_len(&self) -> usize {
+        self.to_bytes().len()
+    }
 }
 
 pub trait FromBytes where Self: Sized {
     fn from_bytes(data: &[u8]) -> Result<Self, ByteEncodingError>;
+
+    /// decodes straight into a heap allocation where the type allows it, large types like a computor list would
+    /// otherwise pass the stack on their way into the box
+    fn from_bytes_boxed(data: &[u8]) -> Result<Box<Self>, ByteEncodingError> {
+        Self::from_bytes(data).map(Box::new)
+    }
 }
 
+/// encodes the in-memory layout, which matches the little-endian wire format only on little-endian targets
 impl<T: Copy> ToBytes for T {
     fn to_bytes(&self) -> Vec<u8> {
         unsafe {
             core::slice::from_raw_parts(self as *const T as *const u8, core::mem::size_of::<T>()).to_vec()
         }
     }
+
+    fn write_to(&self, buf: &mut Vec<u8>) {
+        buf.extend_from_slice(unsafe {
+            core::slice::from_raw_parts(self as *const T as *const u8, core::mem::size_of::<T>())
+        });
+    }
+
+    fn encoded_len(&self) -> usize {
+        core::mem::size_of::<T>()
+    }
 }
 
+/// decodes the in-memory layout, see the `ToBytes` impl
 impl<T: Copy> FromBytes for T {
     fn from_bytes(data: &[u8]) -> Result<Self, ByteEncodingError> {
         if data.len() != core::mem::size_of::<Self>() {
@@ -33,6 +62,21 @@ impl<T: Copy> FromBytes for T {
             }
         )
     }
+
+    fn from_bytes_boxed(data: &[u8]) -> Result<Box<Self>, ByteEncodingError> {
+        if data.len() != core::mem::size_of::<Self>() {
+            return Err(ByteEncodingError::InvalidDataLength { expected: core::mem::size_of::<Self>(), found: data.len() })
+        }
+
+        let mut boxed = Box::<T>::new_uninit();
+
+        Ok(
+            unsafe {
+                core::ptr::copy_nonoverlapping(data.as_ptr(), boxed.as_mut_ptr() as *mut u8, data.len());
+                boxed.assume_init()
+            }
+        )
+    }
 }
 
 pub trait GetSigner {
@@ -47,6 +91,36 @@ pub trait SetSignature {
     fn set_signature(&mut self, signature: Signature);
 }
 
+/// Signs message digests on behalf of identities, a `QubicWallet` signs for its own identity and a
+/// `keystore::Keystore` for every identity it holds
+pub trait Signer {
+    type Error;
+
+    fn sign_digest(&self, identity: &QubicId, digest: [u8; 32]) -> Result<Signature, Self::Error>;
+
+    /// signs the KangarooTwelve digest of the message like `QubicWallet::sign`
+    fn sign_message<T: ToBytes>(&self, identity: &QubicId, message: T) -> Result<Signature, Self::Error> {
+        let mut digest = [0; 32];
+        let mut kg = KangarooTwelve::new(b"");
+        kg.update(&message.to_bytes());
+        kg.into_xof().squeeze(&mut digest);
+
+        self.sign_digest(identity, digest)
+    }
+}
+
+impl Signer for QubicWallet {
+    type Error = QubicError;
+
+    fn sign_digest(&self, identity: &QubicId, digest: [u8; 32]) -> Result<Signature, QubicError> {
+        if identity != &self.public_key {
+            return Err(QubicError::WrongSignature { expected: self.public_key, found: *identity })
+        }
+
+        Ok(self.sign_raw(digest))
+    }
+}
+
 pub trait Sign where Self: ToBytes + FromBytes {
     fn sign(&mut self, wallet: &QubicWallet) -> Result<(), QubicError>;
 }
@@ -73,7 +147,7 @@ impl<T> Sign for T
         let len = bytes.len();
         bytes[len - core::mem::size_of::<Signature>()..len].copy_from_slice(&sig.to_bytes());
 
-        *self = T::from_bytes(&bytes).unwrap();
+        *self = T::from_bytes(&bytes).map_err(QubicError::UndecodableSigned)?;
 
         Ok(())
     }
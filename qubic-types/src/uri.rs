@@ -0,0 +1,138 @@
+//! `qubic:` payment URIs for QR codes and links, e.g. `qubic:<IDENTITY>?amount=1000&tick=12000000&label=Coffee`
+//!
+//! `amount` is given in qus and `label` is percent encoded. Parameters this crate does not know are kept in order
+//! and written back, so a URI survives a round trip through wallets of different versions.
+
+use alloc::{format, string::{String, ToString}, vec::Vec};
+use core::{fmt::{self, Display}, str::FromStr};
+
+use crate::{errors::UriError, QubicId};
+
+pub const SCHEME: &str = "qubic";
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct QubicUri {
+    pub identity: QubicId,
+    /// amount in qus
+    pub amount: Option<u64>,
+    pub tick: Option<u32>,
+    pub label: Option<String>,
+    /// unknown parameters as decoded key/value pairs
+    pub params: Vec<(String, String)>
+}
+
+impl QubicUri {
+    pub fn new(identity: QubicId) -> Self {
+        Self { identity, amount: None, tick: None, label: None, params: Vec::new() }
+    }
+
+    pub fn with_amount(mut self, amount: u64) -> Self {
+        self.amount = Some(amount);
+        self
+    }
+
+    pub fn with_tick(mut self, tick: u32) -> Self {
+        self.tick = Some(tick);
+        self
+    }
+
+    pub fn with_label(mut self, label: impl Into<String>) -> Self {
+        self.label = Some(label.into());
+        self
+    }
+
+    /// appends a parameter which is not part of the format (yet)
+    pub fn with_param(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.params.push((key.into(), value.into()));
+        self
+    }
+}
+
+impl Display for QubicUri {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{SCHEME}:{}", self.identity)?;
+
+        let known = [
+            ("amount", self.amount.map(|amount| amount.to_string())),
+            ("tick", self.tick.map(|tick| tick.to_string())),
+            ("label", self.label.clone())
+        ];
+        let params = known.into_iter().filter_map(|(key, value)| Some((String::from(key), value?))).chain(self.params.iter().cloned());
+
+        for (i, (key, value)) in params.enumerate() {
+            write!(f, "{}{}={}", if i == 0 { '?' } else { '&' }, encode(&key), encode(&value))?;
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for QubicUri {
+    type Err = UriError;
+
+    /// the identity has to carry a valid checksum, the scheme is case insensitive
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let rest = s.split_once(':').filter(|(scheme, _)| scheme.eq_ignore_ascii_case(SCHEME)).ok_or(UriError::InvalidScheme)?.1;
+        let (identity, query) = rest.split_once('?').unwrap_or((rest, ""));
+
+        let id = QubicId::from_str(identity).map_err(UriError::InvalidIdentity)?;
+
+        if id.get_identity() != identity {
+            return Err(UriError::ChecksumMismatch { identity: identity.to_string() });
+        }
+
+        let mut uri = Self::new(id);
+
+        for param in query.split('&').filter(|param| !param.is_empty()) {
+            let (key, value) = param.split_once('=').unwrap_or((param, ""));
+            let (key, value) = (decode(key)?, decode(value)?);
+            let invalid = || UriError::InvalidParam { param: key.clone(), value: value.clone() };
+
+            let duplicate = match key.as_str() {
+                "amount" => uri.amount.replace(value.parse().map_err(|_| invalid())?).is_some(),
+                "tick" => uri.tick.replace(value.parse().map_err(|_| invalid())?).is_some(),
+                "label" => uri.label.replace(value.clone()).is_some(),
+                _ => {
+                    uri.params.push((key, value));
+                    continue;
+                }
+            };
+
+            if duplicate {
+                return Err(UriError::DuplicateParam { param: key });
+            }
+        }
+
+        Ok(uri)
+    }
+}
+
+/// percent encodes everything but the unreserved characters of RFC 3986
+fn encode(s: &str) -> String {
+    s.bytes().map(|b| match b {
+        b'A'..=b'Z' | b'a'..=b'z' | b'0'..=b'9' | b'-' | b'.' | b'_' | b'~' => (b as char).to_string(),
+        _ => format!("%{b:02X}")
+    }).collect()
+}
+
+fn decode(s: &str) -> Result<String, UriError> {
+    let invalid = || UriError::InvalidEncoding(s.to_string());
+    let mut bytes = Vec::with_capacity(s.len());
+    let mut iter = s.bytes();
+
+    while let Some(b) = iter.next() {
+        if b == b'%' {
+            let hex = [iter.next().ok_or_else(invalid)?, iter.next().ok_or_else(invalid)?];
+
+            if !hex.iter().all(u8::is_ascii_hexdigit) {
+                return Err(invalid());
+            }
+
+            bytes.push(u8::from_str_radix(core::str::from_utf8(&hex).unwrap(), 16).unwrap());
+        } else {
+            bytes.push(b);
+        }
+    }
+
+    String::from_utf8(bytes).map_err(|_| invalid())
+}
@@ -0,0 +1,67 @@
+//! Burns 10 QU with QUtil and prints the current results of a poll
+//!
+//! cargo run --example qutil -- <computor ip:port> <seed> <poll id>
+
+use qubic_web3_rs::{client::Client, qubic_tcp_types::types::qutil::PollResults, qubic_types::QubicWallet, transport::Tcp};
+
+const BURN: i64 = 10;
+/// ticks ahead of the current tick the burn is scheduled for
+const TICK_OFFSET: u32 = 10;
+
+struct Args {
+    computor: String,
+    seed: String,
+    poll_id: u64
+}
+
+fn args() -> anyhow::Result<Args> {
+    let mut args = std::env::args().skip(1);
+    let computor = args.next().unwrap_or("146.0.74.233:21841".to_owned());
+    let seed = args.next().ok_or_else(|| anyhow::anyhow!("missing seed"))?;
+    let poll_id = args.next().map(|id| id.parse()).transpose()?.unwrap_or(0);
+
+    Ok(Args { computor, seed, poll_id })
+}
+
+fn print_results(poll_id: u64, results: &PollResults) {
+    println!("poll {poll_id} is {}", if results.active { "active" } else { "inactive" });
+
+    for option in &results.options {
+        println!("  option {}: {} by {} voters", option.option, option.amount, option.voters);
+    }
+
+    if let Some(leading) = results.leading() {
+        println!("option {} leads", leading.option);
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+fn main() -> anyhow::Result<()> {
+    let args = args()?;
+    let client = Client::<Tcp>::new(args.computor)?;
+    let wallet = QubicWallet::from_seed(&args.seed)?;
+
+    let tick = client.qu().get_current_tick_info()?.tick + TICK_OFFSET;
+    let hash = client.qutil().burn_qu(&wallet, BURN, tick)?;
+    println!("burning {BURN} QU in tick {tick}: {hash}");
+
+    print_results(args.poll_id, &client.qutil().get_poll_results(args.poll_id)?);
+
+    Ok(())
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let args = args()?;
+    let client = Client::<Tcp>::new(args.computor).await?;
+    let wallet = QubicWallet::from_seed(&args.seed)?;
+
+    let tick = client.qu().get_current_tick_info().await?.tick + TICK_OFFSET;
+    let hash = client.qutil().burn_qu(&wallet, BURN, tick).await?;
+    println!("burning {BURN} QU in tick {tick}: {hash}");
+
+    print_results(args.poll_id, &client.qutil().get_poll_results(args.poll_id).await?);
+
+    Ok(())
+}
@@ -0,0 +1,96 @@
+//! Records 1000 network events of a computor to `events.jsonl` and replays them through the same handler
+//!
+//! cargo run --example record_events -- <computor ip:port>
+
+use std::{fs::File, io::BufReader, sync::{atomic::{AtomicUsize, Ordering}, Mutex}};
+
+use qubic_web3_rs::{client::Client, event_log::{EventLogReader, EventLogWriter}, qubic_tcp_types::{events::{EventEnvelope, NetworkEvent}, types::ExchangePublicPeers}, transport::Tcp};
+
+const EVENTS: usize = 1000;
+const LOG: &str = "events.jsonl";
+
+fn handle(event: EventEnvelope) -> anyhow::Result<()> {
+    match event.event {
+        NetworkEvent::BroadcastTick(tick) => println!("[{}] {} tick {} by computor {}", event.received_at, event.source, tick.tick, tick.computor_index),
+        NetworkEvent::BroadcastTransaction(tx) => println!("[{}] {} transaction from {}", event.received_at, event.source, tx.raw_transaction.from),
+        NetworkEvent::EpochChanged { old, new } => println!("[{}] {} epoch {old} -> {new}", event.received_at, event.source),
+        other => println!("[{}] {} {}", event.received_at, event.source, event_name(&other))
+    }
+
+    Ok(())
+}
+
+fn event_name(event: &NetworkEvent) -> &'static str {
+    match event {
+        NetworkEvent::ExchangePublicPeers(_) => "ExchangePublicPeers",
+        NetworkEvent::BroadcastMessage(_) => "BroadcastMessage",
+        NetworkEvent::BroadcastTransaction(_) => "BroadcastTransaction",
+        NetworkEvent::BroadcastTick(_) => "BroadcastTick",
+        NetworkEvent::BroadcastFutureTick(_) => "BroadcastFutureTick",
+        NetworkEvent::BroadcastComputors(_) => "BroadcastComputors",
+        NetworkEvent::EpochChanged { .. } => "EpochChanged",
+        NetworkEvent::Disconnected => "Disconnected"
+    }
+}
+
+fn recorder() -> (&'static Mutex<EventLogWriter<File>>, &'static AtomicUsize) {
+    let writer = Box::leak(Box::new(Mutex::new(EventLogWriter::new(File::create(LOG).unwrap()))));
+    let recorded = Box::leak(Box::new(AtomicUsize::new(0)));
+
+    (writer, recorded)
+}
+
+fn replay() -> anyhow::Result<()> {
+    let replayed = EventLogReader::new(BufReader::new(File::open(LOG)?)).replay(handle)?;
+
+    println!("replayed {replayed} events from {LOG}");
+
+    Ok(())
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+fn main() -> anyhow::Result<()> {
+    let computor = std::env::args().nth(1).unwrap_or("146.0.74.233:21841".to_owned());
+    let client = Client::<Tcp>::new(computor)?;
+    let (writer, recorded) = recorder();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
+        if recorded.fetch_add(1, Ordering::Relaxed) < EVENTS {
+            writer.lock().unwrap().write(&event)?;
+        }
+
+        Ok(())
+    })?;
+
+    while recorded.load(Ordering::Relaxed) < EVENTS {
+        std::thread::sleep(std::time::Duration::from_millis(500));
+    }
+
+    writer.lock().unwrap().flush()?;
+
+    replay()
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::main]
+async fn main() -> anyhow::Result<()> {
+    let computor = std::env::args().nth(1).unwrap_or("146.0.74.233:21841".to_owned());
+    let client = Client::<Tcp>::new(computor).await?;
+    let (writer, recorded) = recorder();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
+        if recorded.fetch_add(1, Ordering::Relaxed) < EVENTS {
+            writer.lock().unwrap().write(&event)?;
+        }
+
+        Ok(())
+    }).await?;
+
+    while recorded.load(Ordering::Relaxed) < EVENTS {
+        tokio::time::sleep(std::time::Duration::from_millis(500)).await;
+    }
+
+    writer.lock().unwrap().flush()?;
+
+    replay()
+}
@@ -0,0 +1,301 @@
+//! Client-side cache of responses which rarely or never change, e.g. for services requesting the same computors and
+//! tick data from several code paths
+//!
+//! `Client::cached` wraps a client like the `EpochGuard`. The current tick info is answered from the cache for
+//! `tick_info_ttl`, the computors until a tick info of a later epoch is observed and the tick data of a tick once the
+//! current tick passed it. Tick data of ticks which did not pass yet may still change and is requested every time.
+//! Entries are kept in a `CacheStore`, a `MemoryStore` unless set with `CachedClient::with_store`.
+
+use std::{collections::HashMap, sync::{atomic::{AtomicU64, Ordering}, Mutex}, time::{Duration, Instant}};
+
+use qubic_tcp_types::types::{ticks::{CurrentTickInfo, TickData}, Computors};
+use qubic_types::Tick;
+
+use crate::{client::Client, errors::Result, transport::Transport};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum CacheKey {
+    TickInfo,
+    Computors,
+    TickData(u32)
+}
+
+/// Cached response, the large ones are boxed
+#[derive(Debug, Clone)]
+pub enum CachedResponse {
+    TickInfo(CurrentTickInfo),
+    Computors(Box<Computors>),
+    TickData(Box<TickData>)
+}
+
+/// Cached response, answered until `expires` if set
+#[derive(Debug, Clone)]
+pub struct CacheEntry {
+    pub response: CachedResponse,
+    pub expires: Option<Instant>
+}
+
+/// Storage of the cached responses, shared by concurrent requests of the client
+pub trait CacheStore: Send + Sync {
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry>;
+
+    fn insert(&self, key: CacheKey, entry: CacheEntry);
+
+    fn remove(&self, key: &CacheKey);
+}
+
+/// Store keeping the entries in memory until they are replaced or removed
+#[derive(Debug, Default)]
+pub struct MemoryStore(Mutex<HashMap<CacheKey, CacheEntry>>);
+
+impl CacheStore for MemoryStore {
+    fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+        self.0.lock().unwrap().get(key).cloned()
+    }
+
+    fn insert(&self, key: CacheKey, entry: CacheEntry) {
+        self.0.lock().unwrap().insert(key, entry);
+    }
+
+    fn remove(&self, key: &CacheKey) {
+        self.0.lock().unwrap().remove(key);
+    }
+}
+
+/// Lifetimes of the cached responses
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct CacheConfig {
+    /// the current tick info is answered from the cache for this long (default 500ms), zero disables it
+    pub tick_info_ttl: Duration,
+    /// computors are dropped after this long even if no later epoch was observed, kept for the epoch if `None` (default)
+    pub computors_ttl: Option<Duration>,
+    /// tick data of passed ticks is dropped after this long, kept for good if `None` (default)
+    pub tick_data_ttl: Option<Duration>
+}
+
+impl Default for CacheConfig {
+    fn default() -> Self {
+        Self { tick_info_ttl: Duration::from_millis(500), computors_ttl: None, tick_data_ttl: None }
+    }
+}
+
+impl CacheConfig {
+    pub fn with_tick_info_ttl(mut self, ttl: Duration) -> Self {
+        self.tick_info_ttl = ttl;
+        self
+    }
+
+    pub fn with_computors_ttl(mut self, ttl: Duration) -> Self {
+        self.computors_ttl = Some(ttl);
+        self
+    }
+
+    pub fn with_tick_data_ttl(mut self, ttl: Duration) -> Self {
+        self.tick_data_ttl = Some(ttl);
+        self
+    }
+}
+
+/// Requests answered from the cache and from the peer
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64
+}
+
+/// `CacheStats` per category of responses
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct CacheReport {
+    pub tick_info: CacheStats,
+    pub computors: CacheStats,
+    pub tick_data: CacheStats
+}
+
+#[derive(Debug, Default)]
+struct Counters {
+    hits: AtomicU64,
+    misses: AtomicU64
+}
+
+impl Counters {
+    fn stats(&self) -> CacheStats {
+        CacheStats { hits: self.hits.load(Ordering::Relaxed), misses: self.misses.load(Ordering::Relaxed) }
+    }
+}
+
+/// Client answering repeated requests from its cache, see the module documentation
+pub struct CachedClient<T: Transport> {
+    client: Client<T>,
+    config: CacheConfig,
+    store: Box<dyn CacheStore>,
+    /// epoch and tick of the latest tick info, kept after the cached tick info expired
+    latest: Mutex<Option<(u16, u32)>>,
+    counters: [Counters; 3]
+}
+
+impl<T: Transport> CachedClient<T> {
+    pub fn new(client: Client<T>, config: CacheConfig) -> Self {
+        Self { client, config, store: Box::new(MemoryStore::default()), latest: Mutex::default(), counters: Default::default() }
+    }
+
+    /// keeps the entries in `store` instead of memory
+    pub fn with_store(mut self, store: impl CacheStore + 'static) -> Self {
+        self.store = Box::new(store);
+        self
+    }
+
+    pub fn client(&self) -> &Client<T> {
+        &self.client
+    }
+
+    pub fn stats(&self) -> CacheReport {
+        CacheReport { tick_info: self.counters[0].stats(), computors: self.counters[1].stats(), tick_data: self.counters[2].stats() }
+    }
+
+    /// unexpired entry of `key`, counted as a hit or a miss
+    fn lookup(&self, key: CacheKey) -> Option<CachedResponse> {
+        let counters = &self.counters[match key { CacheKey::TickInfo => 0, CacheKey::Computors => 1, CacheKey::TickData(_) => 2 }];
+        let entry = self.store.get(&key).filter(|entry| entry.expires.is_none_or(|expires| expires > Instant::now()));
+
+        match entry {
+            Some(entry) => {
+                counters.hits.fetch_add(1, Ordering::Relaxed);
+                Some(entry.response)
+            },
+            None => {
+                counters.misses.fetch_add(1, Ordering::Relaxed);
+                None
+            }
+        }
+    }
+
+    fn insert(&self, key: CacheKey, response: CachedResponse, ttl: Option<Duration>) {
+        self.store.insert(key, CacheEntry { response, expires: ttl.map(|ttl| Instant::now() + ttl) });
+    }
+
+    /// caches the tick info and drops the computors of earlier epochs
+    fn cache_tick_info(&self, tick_info: CurrentTickInfo) -> CurrentTickInfo {
+        {
+            let mut latest = self.latest.lock().unwrap();
+
+            // responses of lagging peers do not move the latest tick back
+            if latest.is_some_and(|(epoch, tick)| (tick_info.epoch, tick_info.tick) < (epoch, tick)) {
+                return tick_info
+            }
+
+            *latest = Some((tick_info.epoch, tick_info.tick));
+        }
+
+        if !self.config.tick_info_ttl.is_zero() {
+            self.insert(CacheKey::TickInfo, CachedResponse::TickInfo(tick_info), Some(self.config.tick_info_ttl));
+        }
+
+        if let Some(CacheEntry { response: CachedResponse::Computors(computors), .. }) = self.store.get(&CacheKey::Computors) {
+            if computors.epoch < tick_info.epoch {
+                self.store.remove(&CacheKey::Computors);
+            }
+        }
+
+        tick_info
+    }
+
+    /// caches the computors unless a later epoch was observed
+    fn cache_computors(&self, computors: Computors) -> Computors {
+        if self.latest.lock().unwrap().is_none_or(|(epoch, _)| epoch <= computors.epoch) {
+            self.insert(CacheKey::Computors, CachedResponse::Computors(Box::new(computors)), self.config.computors_ttl);
+        }
+
+        computors
+    }
+
+    /// whether the latest observed tick passed `tick`
+    fn passed(&self, tick: u32) -> bool {
+        self.latest.lock().unwrap().is_some_and(|(_, latest)| latest > tick)
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl<T: Transport> CachedClient<T> {
+    pub fn get_current_tick_info(&self) -> Result<CurrentTickInfo> {
+        if let Some(CachedResponse::TickInfo(tick_info)) = self.lookup(CacheKey::TickInfo) {
+            return Ok(tick_info)
+        }
+
+        Ok(self.cache_tick_info(self.client.qu().get_current_tick_info()?))
+    }
+
+    pub fn request_computors(&self) -> Result<Computors> {
+        if let Some(CachedResponse::Computors(computors)) = self.lookup(CacheKey::Computors) {
+            return Ok(*computors)
+        }
+
+        Ok(self.cache_computors(*self.client.qu().request_computors()?))
+    }
+
+    pub fn request_tick_data(&self, tick: impl Into<Tick>) -> Result<TickData> {
+        let tick = tick.into().get();
+
+        if let Some(CachedResponse::TickData(tick_data)) = self.lookup(CacheKey::TickData(tick)) {
+            return Ok(*tick_data)
+        }
+
+        // tick data requested after the tick passed is final
+        if !self.passed(tick) {
+            self.get_current_tick_info()?;
+        }
+
+        let passed = self.passed(tick);
+        let tick_data = self.client.qu().request_tick_data(tick)?;
+
+        if passed {
+            self.insert(CacheKey::TickData(tick), CachedResponse::TickData(tick_data.clone()), self.config.tick_data_ttl);
+        }
+
+        Ok(*tick_data)
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl<T: Transport> CachedClient<T> {
+    pub async fn get_current_tick_info(&self) -> Result<CurrentTickInfo> {
+        if let Some(CachedResponse::TickInfo(tick_info)) = self.lookup(CacheKey::TickInfo) {
+            return Ok(tick_info)
+        }
+
+        let tick_info = self.client.qu().get_current_tick_info().await?;
+
+        Ok(self.cache_tick_info(tick_info))
+    }
+
+    pub async fn request_computors(&self) -> Result<Computors> {
+        if let Some(CachedResponse::Computors(computors)) = self.lookup(CacheKey::Computors) {
+            return Ok(*computors)
+        }
+
+        let computors = *self.client.qu().request_computors().await?;
+
+        Ok(self.cache_computors(computors))
+    }
+
+    pub async fn request_tick_data(&self, tick: impl Into<Tick>) -> Result<TickData> {
+        let tick = tick.into().get();
+
+        if let Some(CachedResponse::TickData(tick_data)) = self.lookup(CacheKey::TickData(tick)) {
+            return Ok(*tick_data)
+        }
+
+        // tick data requested after the tick passed is final
+        if !self.passed(tick) {
+            self.get_current_tick_info().await?;
+        }
+
+        let passed = self.passed(tick);
+        let tick_data = self.client.qu().request_tick_data(tick).await?;
+
+        if passed {
+            self.insert(CacheKey::TickData(tick), CachedResponse::TickData(tick_data.clone()), self.config.tick_data_ttl);
+        }
+
+        Ok(*tick_data)
+    }
+}
@@ -1,24 +1,339 @@
-use std::{hash::Hash, marker::PhantomData, ptr::{copy_nonoverlapping, read_unaligned}, str::FromStr, time::Duration};
+use std::{collections::HashMap, marker::PhantomData, panic::{catch_unwind, AssertUnwindSafe}, ptr::copy_nonoverlapping, str::FromStr, time::{SystemTime, UNIX_EPOCH}};
 
 #[cfg(not(any(feature = "async", feature = "http")))]
-use std::{thread::JoinHandle, io::{Write, Read}};
+use std::{thread::JoinHandle, io::{Write, Read}, time::Duration};
 
-use crate::transport::Transport;
-use qubic_tcp_types::{events::NetworkEvent, types::{assets::{AssetName, IssueAssetInput, RequestIssuedAsset, RequestOwnedAsset, RequestPossessedAsset, RespondIssuedAsset, RespondOwnedAsset, RespondPossessedAsset, TransferAssetOwnershipAndPossessionInput, ISSUE_ASSET_FEE, QXID, TRANSFER_FEE}, contracts::RequestContractFunction, qlogging::{QubicLog, RequestLog}, send_to_many::{SendToManyFeeOutput, SendToManyInput, SendToManyTransaction, SEND_TO_MANY_CONTRACT_INDEX}, special_commands::{GetMiningScoreRanking, MiningScoreRanking, SpecialCommand}, BroadcastMessage, Computors, ContractIpo, ContractIpoBid, ExchangePublicPeers, Packet, RequestComputors, RequestContractIpo, RequestEntity, RequestSystemInfo, RespondedEntity, SystemInfo}, Header, MessageType};
+use crate::{cache::{CacheConfig, CachedClient}, epoch_guard::EpochGuard, interceptor::{Interceptor, Interceptors}, proxy::ProxyConfig, subscription::{self, SubscriptionConfig, SubscriptionHandle}, transport::{connect_stream, RequestOptions, Transport}, wire_dump::WireDump};
+use qubic_tcp_types::{events::{EpochTracker, EventEnvelope, NetworkEvent}, views::{NetworkEventView, RawEvent}, types::{assets::{AssetName, AssetSummary, IssueAssetInput, RequestAssets, RequestIssuedAsset, RequestOwnedAsset, RequestPossessedAsset, RespondAssets, RespondIssuedAsset, RespondOwnedAsset, RespondPossessedAsset, TransferAssetOwnershipAndPossessionInput, ISSUE_ASSET_FEE, QXID, QX_TRANSFER_OWNERSHIP_AND_POSSESSION, TRANSFER_FEE}, contracts::{ContractFunctionCall, RequestContractFunction}, fees::{FeeBreakdown, FeeEstimator, FeeSchedule}, simulation::{simulate_transfer, SimulationContext, TransferSimulation}, qlogging::{QubicLog, QubicLogs, RequestLog}, qutil::{BurnQuInput, CreatePollInput, GetPollResultsInput, GetPollResultsOutput, PollResults, VoteInput, QUTIL_BURN_QUBIC, QUTIL_CONTRACT_INDEX, QUTIL_CREATE_POLL, QUTIL_GET_CURRENT_RESULT, QUTIL_POLL_CREATION_FEE, QUTIL_VOTE, QUTIL_VOTE_FEE}, send_to_many::{SendToManyFeeOutput, SendToManyInput, SendToManyTransaction, SEND_TO_MANY_CONTRACT_INDEX}, special_commands::{CommandBuilder, CommandType, GetMiningScoreRanking, MiningScoreRanking, SendTimeResponse, SpecialCommand}, time::QubicSetUtcTime, BroadcastMessage, Computors, ContractIpo, ContractIpoBid, ExchangePublicPeers, Packet, RequestComputors, RequestContractIpo, RequestEntity, RequestSystemInfo, RespondedEntity, SystemInfo}, Header, MessageType};
 use qubic_tcp_types::prelude::*;
-use anyhow::Result;
+use qubic_tcp_types::consts::VoteFlags;
+use crate::errors::{ClientError, Result};
 use kangarootwelve::KangarooTwelve;
-use qubic_types::{traits::{FromBytes, Sign, ToBytes}, QubicId, QubicTxHash, QubicWallet, Signature};
+use qubic_types::{errors::ByteEncodingError, traits::{FromBytes, Sign, ToBytes}, QubicId, QubicTxHash, QubicWallet, Signature, Tick as TickNumber};
 use rand::Rng;
 
+#[cfg(not(any(feature = "async", feature = "http")))]
+use qubic_tcp_types::types::{transactions::transaction_hash, Encoded};
+
+#[cfg(any(feature = "async", feature = "http"))]
+use futures::io::{AsyncWrite, AsyncWriteExt, AsyncReadExt};
+#[cfg(any(feature = "async", feature = "http"))]
+use futures::{channel::mpsc, SinkExt, Stream, StreamExt};
+#[cfg(any(feature = "async", feature = "http"))]
+use std::collections::HashSet;
+#[cfg(any(feature = "async", feature = "http"))]
+use crate::{runtime::{self, TcpStream}, transport::{timed, Timeouts}};
+
+/// transfers need a positive number of units and non-zero receiving ids
+fn validate_transfer(units: i64, ids: &[(&str, QubicId)]) -> Result<()> {
+    if units <= 0 {
+        return Err(ClientError::InvalidInput(format!("Number of units has to be positive (found {units})")))
+    }
+
+    if let Some((ident, _)) = ids.iter().find(|(_, id)| *id == QubicId::default()) {
+        return Err(ClientError::InvalidInput(format!("The {ident} must not be the zero ID")))
+    }
+
+    Ok(())
+}
+
+/// the node stays silent on commands of other keys and echoes the descriptor of the ones it accepted
+fn mining_score_ranking(response: Result<MiningScoreRanking>) -> Result<MiningScoreRanking> {
+    const COMMAND: CommandType = CommandType::SpecialCommandGetMiningScoreRanking;
+
+    match response {
+        Ok(ranking) if ranking.descriptor.command_type == COMMAND => Ok(ranking),
+        Ok(_) | Err(ClientError::Timeout) => Err(ClientError::CommandRejected(COMMAND)),
+        Err(e) => Err(e)
+    }
+}
+
+/// time the node runs on after accepting a `SetTime` command, like other commands it is not answered if rejected
+fn set_time_echo(response: Result<SendTimeResponse>) -> Result<QubicSetUtcTime> {
+    const COMMAND: CommandType = CommandType::SpecialCommandSendTime;
+
+    match response {
+        Ok(echo) if echo.is_accepted() => Ok(echo.time),
+        Ok(_) | Err(ClientError::Timeout) => Err(ClientError::CommandRejected(COMMAND)),
+        Err(e) => Err(e)
+    }
+}
+
+/// a panic of the handler is logged and the message skipped, a subscription must not die silently with its handler
+fn handle_message(handler: &mut impl FnMut(&Header, &[u8]) -> anyhow::Result<()>, header: &Header, payload: &[u8]) -> anyhow::Result<()> {
+    catch_unwind(AssertUnwindSafe(|| handler(header, payload))).unwrap_or_else(|panic| {
+        let message = panic.downcast_ref::<&str>().copied().or_else(|| panic.downcast_ref::<String>().map(String::as_str)).unwrap_or("non-string payload");
+        log::error!("Subscription handler panicked on {}, the message is skipped: {message}", header.message_type);
+
+        Ok(())
+    })
+}
+
+/// Messages of a peer the subscription is sent to, connects on the first read and reconnects after the connection dropped
+#[cfg(not(any(feature = "async", feature = "http")))]
+struct MessageReader<'a, T: Transport> {
+    transport: &'a T,
+    public_peers: ExchangePublicPeers,
+    stream: Option<std::net::TcpStream>,
+    header_buffer: Vec<u8>,
+    data_buffer: Vec<u8>
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl<'a, T: Transport> MessageReader<'a, T> {
+    fn new(transport: &'a T, public_peers: ExchangePublicPeers) -> Self {
+        Self { transport, public_peers, stream: None, header_buffer: vec![0u8; std::mem::size_of::<Header>()], data_buffer: vec![0u8; 10_000_000] }
+    }
+
+    /// next message of the peer, `None` if the connection dropped. The next call reconnects, failing to do so is an error
+    fn next(&mut self) -> anyhow::Result<Option<(Header, &[u8])>> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => {
+                let mut stream = self.transport.connect()?;
+                stream.set_read_timeout(Some(Duration::from_secs(5)))?;
+                stream.write_all(&Packet::new(self.public_peers, true)?.to_bytes())?;
+
+                self.stream.insert(stream)
+            }
+        };
+
+        if stream.read_exact(&mut self.header_buffer).is_err() {
+            self.stream = None;
+            return Ok(None)
+        }
+
+        let header = Header::from_bytes(&self.header_buffer)?;
+
+        let Some(payload) = self.data_buffer.get_mut(..header.get_size().saturating_sub(std::mem::size_of::<Header>())) else {
+            self.stream = None;
+            return Ok(None)
+        };
+
+        if stream.read_exact(payload).is_err() {
+            self.stream = None;
+            return Ok(None)
+        }
+
+        Ok(Some((header, payload)))
+    }
+}
+
+/// hands every message of the peer to `handler` until it fails, reconnects whenever the connection drops
+#[cfg(not(any(feature = "async", feature = "http")))]
+fn read_messages<T: Transport>(transport: &T, public_peers: ExchangePublicPeers, mut handler: impl FnMut(&Header, &[u8]) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    let mut reader = MessageReader::new(transport, public_peers);
+
+    loop {
+        if let Some((header, payload)) = reader.next()? {
+            handle_message(&mut handler, &header, payload)?;
+        }
+    }
+}
+
+/// Messages of a peer the subscription is sent to, connects on the first read and reconnects after the connection dropped
 #[cfg(any(feature = "async", feature = "http"))]
-use tokio::io::{AsyncWriteExt, AsyncReadExt};
+struct MessageReader<'a> {
+    url: &'a str,
+    proxy: Option<&'a ProxyConfig>,
+    public_peers: ExchangePublicPeers,
+    timeouts: Timeouts,
+    stream: Option<TcpStream>,
+    header_buffer: Vec<u8>,
+    data_buffer: Vec<u8>
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl<'a> MessageReader<'a> {
+    fn new(url: &'a str, proxy: Option<&'a ProxyConfig>, public_peers: ExchangePublicPeers) -> Self {
+        Self {
+            url,
+            proxy,
+            public_peers,
+            timeouts: Timeouts::default(),
+            stream: None,
+            header_buffer: vec![0u8; std::mem::size_of::<Header>()],
+            data_buffer: vec![0u8; 10_000_000]
+        }
+    }
+
+    /// next message of the peer, `None` if the connection dropped. The next call reconnects, failing to do so is an error
+    async fn next(&mut self) -> anyhow::Result<Option<(Header, &[u8])>> {
+        let stream = match &mut self.stream {
+            Some(stream) => stream,
+            None => {
+                let mut stream = connect_stream(self.url, &self.timeouts, self.proxy).await?;
+                timed(self.timeouts.write, stream.write_all(&Packet::new(self.public_peers, true)?.to_bytes())).await?;
+
+                self.stream.insert(stream)
+            }
+        };
+
+        if timed(self.timeouts.read, stream.read_exact(&mut self.header_buffer)).await.is_err() {
+            self.stream = None;
+            return Ok(None)
+        }
+
+        let header = Header::from_bytes(&self.header_buffer)?;
+
+        let Some(payload) = self.data_buffer.get_mut(..header.get_size().saturating_sub(std::mem::size_of::<Header>())) else {
+            self.stream = None;
+            return Ok(None)
+        };
+
+        if timed(self.timeouts.read, stream.read_exact(payload)).await.is_err() {
+            self.stream = None;
+            return Ok(None)
+        }
+
+        Ok(Some((header, payload)))
+    }
+}
+
+/// hands every message of the peer to `handler` until it fails, reconnects whenever the connection drops
+#[cfg(any(feature = "async", feature = "http"))]
+async fn read_messages(url: &str, proxy: Option<&ProxyConfig>, public_peers: ExchangePublicPeers, mut handler: impl FnMut(&Header, &[u8]) -> anyhow::Result<()>) -> anyhow::Result<()> {
+    let mut reader = MessageReader::new(url, proxy, public_peers);
+
+    loop {
+        if let Some((header, payload)) = reader.next().await? {
+            handle_message(&mut handler, &header, payload)?;
+        }
+    }
+}
+
+/// owned events of a message received from `source`, an event of a later epoch than the ones seen before is followed
+/// by `EpochChanged`. Malformed messages have none
+fn network_events(source: &str, epochs: &mut EpochTracker, header: &Header, payload: &[u8]) -> Vec<EventEnvelope> {
+    let Ok(Some(view)) = NetworkEventView::parse(header.message_type, payload) else { return Vec::new() };
+    let Ok(event) = view.to_owned() else { return Vec::new() };
+    let epoch_change = epochs.observe(&event);
+
+    std::iter::once(event).chain(epoch_change).map(|event| envelope(source, event)).collect()
+}
+
+/// stamps an event received from `source` with the current time
+fn envelope(source: &str, event: NetworkEvent) -> EventEnvelope {
+    EventEnvelope {
+        received_at: SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0),
+        source: source.to_owned(),
+        event
+    }
+}
+
+/// response decoded straight into a heap allocation, large responses like tick data would otherwise pass the stack
+struct Boxed<T>(Box<T>);
+
+impl<T: FromBytes> FromBytes for Boxed<T> {
+    fn from_bytes(data: &[u8]) -> std::result::Result<Self, ByteEncodingError> {
+        T::from_bytes_boxed(data).map(Self)
+    }
+}
+
+/// Requests in flight on the connection of `Qu::request_tick_data_range`
+const TICK_DATA_PIPELINE: usize = 32;
+/// Requests of a tick sent by `Qu::request_tick_data_range` before it is given up as `Missing`
+const TICK_DATA_ATTEMPTS: u32 = 3;
+
+/// requests and results of `Qu::request_tick_data_range`, responses are matched to their request by dejavu
+struct TickDataPipeline {
+    start: u32,
+    end: u32,
+    next: Option<u32>,
+    /// tick and number of requests sent of every request in flight by dejavu
+    in_flight: HashMap<u32, (u32, u32)>,
+    /// dejavus of the requests in flight to send again
+    retries: Vec<u32>,
+    ticks: Vec<TickDataStatus>
+}
+
+impl TickDataPipeline {
+    fn new(start: u32, end: u32) -> Result<Self> {
+        if start > end {
+            return Err(ClientError::InvalidInput(format!("Start tick {start} exceeds end tick {end}")))
+        }
+
+        Ok(Self { start, end, next: Some(start), in_flight: HashMap::new(), retries: Vec::new(), ticks: vec![TickDataStatus::Missing; (end - start) as usize + 1] })
+    }
+
+    fn request(tick: u32, dejavu: u32) -> Result<Vec<u8>> {
+        let mut packet = Packet::new(RequestTickData { tick }, false)?;
+        packet.header.dejavu = dejavu;
+
+        Ok(packet.to_bytes())
+    }
+
+    /// requests of the ticks to retry and of the next ticks until `TICK_DATA_PIPELINE` are in flight
+    fn requests(&mut self) -> Result<Vec<u8>> {
+        let mut bytes = Vec::new();
+
+        for dejavu in std::mem::take(&mut self.retries) {
+            if let Some(&(tick, _)) = self.in_flight.get(&dejavu) {
+                bytes.extend(Self::request(tick, dejavu)?);
+            }
+        }
+
+        while self.in_flight.len() < TICK_DATA_PIPELINE {
+            let Some(tick) = self.next else { break };
+            self.next = tick.checked_add(1).filter(|next| *next <= self.end);
+
+            // a zero dejavu would have the request relayed to the peers of the computor
+            let dejavu = (tick - self.start).wrapping_add(1).max(1);
+
+            self.in_flight.insert(dejavu, (tick, 1));
+            bytes.extend(Self::request(tick, dejavu)?);
+        }
+
+        Ok(bytes)
+    }
+
+    fn is_done(&self) -> bool {
+        self.next.is_none() && self.in_flight.is_empty()
+    }
+
+    /// `EndResponse` answers ticks without tick data, packets of no request in flight (e.g. the greeting) are skipped
+    fn receive(&mut self, header: &Header, payload: &[u8]) {
+        let Some(&(tick, _)) = self.in_flight.get(&header.dejavu) else { return };
+
+        let status = match header.message_type {
+            MessageType::EndResponse => TickDataStatus::EmptyTick,
+            MessageType::BroadcastFutureTickData => match TickData::from_bytes_boxed(payload) {
+                Ok(tick_data) if tick_data.epoch == 0 => TickDataStatus::EmptyTick,
+                Ok(tick_data) if tick_data.tick == tick => TickDataStatus::Present(tick_data),
+                _ => TickDataStatus::Missing
+            },
+            _ => return
+        };
+
+        self.in_flight.remove(&header.dejavu);
+        self.ticks[(tick - self.start) as usize] = status;
+    }
+
+    /// nothing arrived within the read timeout, the requests in flight are sent again with their dejavu, so a late
+    /// response to an earlier request still counts. The computor has no record of the ticks it did not answer to
+    /// `TICK_DATA_ATTEMPTS` requests
+    fn time_out(&mut self) {
+        self.in_flight.retain(|_, (_, attempts)| *attempts < TICK_DATA_ATTEMPTS);
+
+        for (_, attempts) in self.in_flight.values_mut() {
+            *attempts += 1;
+        }
+
+        self.retries = self.in_flight.keys().copied().collect();
+    }
+
+    fn finish(self) -> TickDataRange {
+        TickDataRange { start: self.start, end: self.end, ticks: self.ticks }
+    }
+}
 
 #[derive(Debug, Clone)]
 pub struct ClientBuilder<T: Transport> {
     pd: PhantomData<T>,
     url: String,
-    timeout: Option<std::time::Duration>
+    options: RequestOptions,
+    interceptors: Interceptors,
+    wire_dump: WireDump
 }
 
 impl<T: Transport> ClientBuilder<T> {
@@ -26,32 +341,85 @@ impl<T: Transport> ClientBuilder<T> {
         Self {
             pd: PhantomData,
             url: url.to_string(),
-            timeout: None
+            options: RequestOptions::default(),
+            interceptors: Interceptors::default(),
+            wire_dump: WireDump::default()
         }
     }
 
     /// specifies the read and write timeout for a connection, if not set the Transport will set the timeout to it's default value (5 seconds)
     pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
-        self.timeout = Some(timeout);
+        self.options = self.options.with_timeout(timeout);
+
+        self
+    }
+
+    /// specifies the timeout for establishing a connection (default 5 seconds)
+    pub fn with_connect_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.options = self.options.with_connect_timeout(timeout);
+
+        self
+    }
+
+    /// specifies the timeout for reading responses (default 5 seconds)
+    pub fn with_read_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.options = self.options.with_read_timeout(timeout);
+
+        self
+    }
+
+    /// specifies the timeout for writing requests (default 5 seconds)
+    pub fn with_write_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.options = self.options.with_write_timeout(timeout);
+
+        self
+    }
+
+    /// invokes `interceptor` around every request of the client, interceptors run in the order they were added
+    pub fn with_interceptor(mut self, interceptor: impl Interceptor + Send + Sync + 'static) -> Self {
+        self.interceptors.push(interceptor);
+
+        self
+    }
+
+    /// tunnels every connection of the client through `proxy`, including broadcasts and subscriptions
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.options = self.options.with_proxy(proxy);
+
+        self
+    }
+
+    /// follows computors which are too busy to answer to one of the peers they suggest at most `retries` times
+    /// (`DEFAULT_BUSY_RETRIES` unless set), 0 returns `ClientError::PeerBusy` right away
+    pub fn with_busy_retries(mut self, retries: usize) -> Self {
+        self.options = self.options.with_busy_retries(retries);
+
+        self
+    }
+
+    /// writes every frame the client sends and receives to `dump`, e.g. `WireDump::to_file`
+    pub fn with_wire_dump(mut self, dump: WireDump) -> Self {
+        self.wire_dump = dump;
 
         self
     }
+
     #[cfg(not(any(feature = "async", feature = "http")))]
     pub fn build(self) -> Result<Client<T>, T::Err> {
-        Ok(
-            Client {
-                transport: T::new(self.url, self.timeout)?
-            }
-        )
+        let mut transport = T::new(self.url, self.options)?;
+        transport.set_interceptors(self.interceptors);
+        transport.set_wire_dump(self.wire_dump);
+
+        Ok(Client { transport })
     }
 
     #[cfg(any(feature = "async", feature = "http"))]
     pub async fn build(self) -> Result<Client<T>, T::Err> {
-        Ok(
-            Client {
-                transport: T::new(self.url, self.timeout).await?
-            }
-        )
+        let mut transport = T::new(self.url, self.options).await?;
+        transport.set_interceptors(self.interceptors);
+        transport.set_wire_dump(self.wire_dump);
+
+        Ok(Client { transport })
     }
 }
 
@@ -61,23 +429,64 @@ pub struct Client<T: Transport> {
     transport: Box<T>
 }
 
+impl<T> Client<T> where T: Transport {
+    /// underlying transport, e.g. to start a `ConnectedTcp` heartbeat
+    pub fn transport(&self) -> &T {
+        &self.transport
+    }
+
+    /// client watching the epoch of the peer, e.g. for long-running services
+    pub fn epoch_guard(self) -> EpochGuard<T> {
+        EpochGuard::new(self)
+    }
+
+    /// client answering repeated requests of the tick info, computors and tick data from a cache
+    pub fn cached(self, config: CacheConfig) -> CachedClient<T> {
+        CachedClient::new(self, config)
+    }
+}
+
 #[cfg(not(any(feature = "async", feature = "http")))]
 impl<T> Client<T> where T: Transport {
     pub fn new(url: impl ToString) -> Result<Self, T::Err> {
         Ok(Self {
-            transport: T::new(url.to_string(), None)?
+            transport: T::new(url.to_string(), RequestOptions::default())?
         })
     }
 
     pub fn qu(&self) -> Qu<T> {
+        self.qu_with(RequestOptions::default())
+    }
+
+    /// `Qu` whose requests override the client's timeouts with `options`
+    pub fn qu_with(&self, options: RequestOptions) -> Qu<'_, T> {
         Qu {
-            transport: &self.transport
+            transport: &self.transport,
+            options
         }
     }
 
     pub fn qx(&self) -> Qx<T> {
+        self.qx_with(RequestOptions::default())
+    }
+
+    /// `Qx` whose requests override the client's timeouts with `options`
+    pub fn qx_with(&self, options: RequestOptions) -> Qx<'_, T> {
         Qx {
-            transport: &self.transport
+            transport: &self.transport,
+            options
+        }
+    }
+
+    pub fn qutil(&self) -> QUtil<'_, T> {
+        self.qutil_with(RequestOptions::default())
+    }
+
+    /// `QUtil` whose requests override the client's timeouts with `options`
+    pub fn qutil_with(&self, options: RequestOptions) -> QUtil<'_, T> {
+        QUtil {
+            transport: &self.transport,
+            options
         }
     }
 }
@@ -86,55 +495,136 @@ impl<T> Client<T> where T: Transport {
 impl<T> Client<T> where T: Transport {
     pub async fn new(url: impl ToString) -> Result<Self, T::Err> {
         Ok(Self {
-            transport: T::new(url.to_string(), None).await?
+            transport: T::new(url.to_string(), RequestOptions::default()).await?
         })
     }
 
     pub fn qu(&self) -> Qu<T> {
+        self.qu_with(RequestOptions::default())
+    }
+
+    /// `Qu` whose requests override the client's timeouts with `options`
+    pub fn qu_with(&self, options: RequestOptions) -> Qu<'_, T> {
         Qu {
-            transport: &self.transport
+            transport: &self.transport,
+            options
         }
     }
 
     pub fn qx(&self) -> Qx<T> {
+        self.qx_with(RequestOptions::default())
+    }
+
+    /// `Qx` whose requests override the client's timeouts with `options`
+    pub fn qx_with(&self, options: RequestOptions) -> Qx<'_, T> {
         Qx {
-            transport: &self.transport
+            transport: &self.transport,
+            options
+        }
+    }
+
+    pub fn qutil(&self) -> QUtil<'_, T> {
+        self.qutil_with(RequestOptions::default())
+    }
+
+    /// `QUtil` whose requests override the client's timeouts with `options`
+    pub fn qutil_with(&self, options: RequestOptions) -> QUtil<'_, T> {
+        QUtil {
+            transport: &self.transport,
+            options
         }
     }
 }
 
 pub struct Qu<'a, T: Transport> {
-    transport: &'a T
+    transport: &'a T,
+    options: RequestOptions
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
-#[repr(u8)]
-pub enum TransactionStatus {
-    Failed,
-    Included,
-    Executed
-}
+pub use qubic_tcp_types::types::transactions::{ProvenTransactionStatus, TransactionStatus};
 
 pub const NUMBER_OF_EXCHANGES_PEERS: usize = 4;
 
+/// bytes `Qu::request_log_to_writer` reads from the peer before handing them to the writer
+pub const LOG_CHUNK_SIZE: usize = 64 * 1024;
+
+/// Outcome of broadcasting a transaction to several peers at once
+#[derive(Debug, Default)]
+pub struct BroadcastReport {
+    pub succeeded: Vec<String>,
+    pub failed: Vec<(String, ClientError)>
+}
+
+impl BroadcastReport {
+    fn check(self, min_success: usize) -> Result<Self> {
+        if self.succeeded.len() < min_success {
+            return Err(ClientError::BroadcastFailed { succeeded: self.succeeded.len(), required: min_success, failed: self.failed });
+        }
+
+        Ok(self)
+    }
+}
+
+impl<T: Transport> Qu<'_, T> {
+    /// options of the transports to the broadcast peers, they connect through the proxy of the client
+    fn broadcast_options(&self) -> RequestOptions {
+        RequestOptions { proxy: self.options.proxy_or(self.transport.proxy()).cloned(), ..self.options.clone() }
+    }
+}
+
 #[cfg(not(any(feature = "async", feature = "http")))]
 impl<'a, T> Qu<'a, T> where T: Transport {
+    /// signs and sends the transaction, a `RawTransaction`, `Call` or `TransactionWithData` (signed again with `wallet`)
     pub fn send_raw_transaction<Tx: Into<TransactionWithData>>(&self, wallet: &QubicWallet, raw_transaction: Tx) -> Result<QubicTxHash> {
         let mut txwd: TransactionWithData = raw_transaction.into();
         txwd.sign(wallet)?;
-        let hash = txwd.clone().into();
+        let encoded = Encoded::new(&txwd);
+        let hash = transaction_hash(encoded.as_bytes());
 
-        self.transport.send_without_response(Packet::new(txwd, false))?;
+        self.transport.send_without_response(Packet::from_ref(&encoded, false)?, &self.options)?;
         Ok(hash)
     }
 
+    /// sends an already signed `Transaction`, `Call` or `TransactionWithData` as is
     pub fn send_signed_transaction<Tx: Into<TransactionWithData>>(&self, transaction: Tx) -> Result<QubicTxHash> {
-        let txwd: TransactionWithData = transaction.into();
-        let hash: QubicTxHash = txwd.clone().into();
-        self.transport.send_without_response(Packet::new(txwd, false))?;
+        let encoded = Encoded::new(&transaction.into());
+        let hash = transaction_hash(encoded.as_bytes());
+        self.transport.send_without_response(Packet::from_ref(&encoded, false)?, &self.options)?;
         Ok(hash)
     }
 
+    /// broadcasts the transaction to all `peers` concurrently, fails if less than `min_success` peers accepted it
+    pub fn send_signed_transaction_multi<Tx: Into<TransactionWithData>>(&self, transaction: Tx, peers: &[String], min_success: usize) -> Result<BroadcastReport>
+        where ClientError: From<T::Err>
+    {
+        let txwd: TransactionWithData = transaction.into();
+        let packet = Packet::from_ref(&txwd, false)?;
+        let options = self.broadcast_options();
+        let mut report = BroadcastReport::default();
+
+        std::thread::scope(|s| {
+            let handles = peers.iter().map(|peer| {
+                let (packet, options) = (packet.clone(), &options);
+                let handle = s.spawn(move || -> Result<()> {
+                    let transport = T::new(peer.clone(), options.clone())?;
+                    transport.send_without_response(packet, options)
+                });
+
+                (peer, handle)
+            }).collect::<Vec<_>>();
+
+            for (peer, handle) in handles {
+                match handle.join() {
+                    Ok(Ok(())) => report.succeeded.push(peer.clone()),
+                    Ok(Err(e)) => report.failed.push((peer.clone(), e)),
+                    Err(_) => report.failed.push((peer.clone(), ClientError::Io(std::io::Error::other("broadcast thread panicked"))))
+                }
+            }
+        });
+
+        report.check(min_success)
+    }
+
     pub fn submit_work(&self, wallet: &QubicWallet, solution: WorkSolution) -> Result<()> {
         let mut rng = rand::thread_rng();
         let mut message: BroadcastMessage = solution.into();
@@ -191,143 +681,203 @@ impl<'a, T> Qu<'a, T> where T: Transport {
 
         message.signature = wallet.sign_raw(digest);
 
-        self.transport.send_without_response(Packet::new(message, false))?;
+        self.transport.send_without_response(Packet::new(message, false)?, &self.options)?;
         Ok(())
     }
 
     pub fn get_current_tick_info(&self) -> Result<CurrentTickInfo> {
-        let packet = Packet::new(GetCurrentTickInfo, true);
+        let packet = Packet::new(GetCurrentTickInfo, true)?;
 
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(self.transport.send_with_response(packet, &self.options)?)
     }
 
-    pub fn request_computors(&self) -> Result<Computors> {
-        let packet = Packet::new(RequestComputors, true);
-        
-        Ok(self.transport.send_with_response(packet)?)
+    /// decoded on the heap, see `Computors::new_boxed`
+    pub fn request_computors(&self) -> Result<Box<Computors>> {
+        let packet = Packet::new(RequestComputors, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<Computors>, _>(packet, &self.options)?.0)
     }
 
+    /// the entity along with the tick and spectrum index it is valid for, see `RespondedEntity::entity_only`
     pub fn request_entity(&self, public_key: QubicId) -> Result<RespondedEntity> {
-        let packet = Packet::new(RequestEntity { public_key }, true);
+        let packet = Packet::new(RequestEntity { public_key }, true)?;
         
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(self.transport.send_with_response(packet, &self.options)?)
     }
 
-    pub fn request_contract_ipo(&self, contract_index: u32) -> Result<ContractIpo> {
-        let packet = Packet::new(RequestContractIpo { contract_index }, true);
-        
-        Ok(self.transport.send_with_response(packet)?)
+    /// decoded on the heap, see `ContractIpo::new_boxed`
+    pub fn request_contract_ipo(&self, contract_index: u32) -> Result<Box<ContractIpo>> {
+        let packet = Packet::new(RequestContractIpo { contract_index }, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<ContractIpo>, _>(packet, &self.options)?.0)
     }
 
-    pub fn request_tick_data(&self, tick: u32) -> Result<TickData> {
-        let packet = Packet::new(RequestTickData { tick }, true);
-    
-        Ok(self.transport.send_with_response(packet)?)
+    /// decoded on the heap, see `TickData::new_boxed`
+    pub fn request_tick_data(&self, tick: impl Into<TickNumber>) -> Result<Box<TickData>> {
+        let tick = tick.into().get();
+        let packet = Packet::new(RequestTickData { tick }, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<TickData>, _>(packet, &self.options)?.0)
+    }
+
+    /// tick data of the ticks `start..=end`, requested over one connection with up to 32 requests in flight.
+    /// Ticks the computor answers without tick data are `EmptyTick`. Ticks still unanswered at a read timeout are requested
+    /// again, ticks it does not answer to 3 requests are `Missing`, see `TickDataRange::gaps`
+    pub fn request_tick_data_range(&self, start: impl Into<TickNumber>, end: impl Into<TickNumber>) -> Result<TickDataRange> {
+        let (start, end) = (start.into().get(), end.into().get());
+        let mut pipeline = TickDataPipeline::new(start, end)?;
+        let timeouts = self.transport.timeouts().with_overrides(&self.options);
+        let mut stream = connect_stream(&self.transport.get_url(), &timeouts, self.options.proxy_or(self.transport.proxy()))?;
+        let mut header_buffer = [0; std::mem::size_of::<Header>()];
+
+        loop {
+            stream.write_all(&pipeline.requests()?)?;
+
+            if pipeline.is_done() {
+                break
+            }
+
+            match stream.read_exact(&mut header_buffer).map_err(ClientError::from) {
+                Ok(()) => (),
+                Err(ClientError::Timeout) => {
+                    pipeline.time_out();
+                    continue
+                },
+                Err(e) => return Err(e)
+            }
+
+            let header = Header::from_bytes(&header_buffer)?;
+            let mut payload = vec![0; header.get_size().saturating_sub(std::mem::size_of::<Header>())];
+            stream.read_exact(&mut payload)?;
+
+            pipeline.receive(&header, &payload);
+        }
+
+        Ok(pipeline.finish())
     }
 
-    pub fn request_quorum_tick(&self, tick: u32, vote_flags: [u8; (676 + 7) / 8]) -> Result<Tick> {
-        let packet = Packet::new(QuorumTickData { tick, vote_flags }, true);
+    pub fn request_quorum_tick(&self, tick: impl Into<TickNumber>, vote_flags: VoteFlags) -> Result<Tick> {
+        let tick = tick.into().get();
+        let packet = Packet::new(QuorumTickData { tick, vote_flags }, true)?;
         
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(self.transport.send_with_response(packet, &self.options)?)
+    }
+
+    /// collects the votes of all computors for `tick` and checks them against the quorum
+    pub fn request_quorum_votes(&self, tick: impl Into<TickNumber>) -> Result<QuorumSummary> {
+        let tick = tick.into().get();
+        let packet = Packet::new(QuorumTickData { tick, vote_flags: [0; std::mem::size_of::<VoteFlags>()] }, true)?;
+        let votes: Vec<Tick> = self.transport.send_with_multiple_responses(packet, &self.options)?;
+
+        Ok(QuorumSummary::from_votes(&votes))
+    }
+
+    /// whether `tick` is final, a quorum of computors voted for the same digests of the tick
+    pub fn is_tick_finalized(&self, tick: impl Into<TickNumber>) -> Result<bool> {
+        let tick = tick.into().get();
+        Ok(self.request_quorum_votes(tick)?.quorum_reached)
     }
 
     pub fn request_system_info(&self) -> Result<SystemInfo> {
-        let packet = Packet::new(RequestSystemInfo, true);
+        let packet = Packet::new(RequestSystemInfo, true)?;
 
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(self.transport.send_with_response(packet, &self.options)?)
     }
 
     pub fn exchange_public_peers(&self, peers: ExchangePublicPeers) -> Result<ExchangePublicPeers> {
-        let packet = Packet::new(peers, true);
+        let packet = Packet::new(peers, true)?;
 
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(self.transport.send_with_response(packet, &self.options)?)
     }
 
-    pub fn request_tick_transactions(&self, tick: u32, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
-        let packet = Packet::new(RequestedTickTransactions { tick, flags }, true);
+    /// transactions of the tick in arrival order, transactions the peer sent more than once are dropped
+    pub fn request_tick_transactions(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
+        let tick = tick.into().get();
+        let packet = Packet::new(RequestedTickTransactions { tick, flags }, true)?;
+        let mut transactions = self.transport.send_with_multiple_responses(packet, &self.options)?;
+        dedup_transactions(&mut transactions);
 
-        Ok(self.transport.send_with_multiple_responses(packet)?)
+        Ok(transactions)
     }
 
-    pub fn check_transaction_status(&self, tx_hash: QubicTxHash, tick: u32) -> Result<TransactionStatus> {
-        let mut status = TransactionStatus::Failed;
+    /// transactions of the tick in the order of the tick data, arrival order if the tick data is not available
+    pub fn request_tick_transactions_ordered(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
+        let tick = tick.into().get();
+        let mut transactions = self.request_tick_transactions(tick, flags)?;
 
-        let tt_packet = Packet::new(RequestedTickTransactions { tick, flags: TransactionFlags::all() }, true);
-        let tt: Vec<TransactionWithData> = self.transport.send_with_multiple_responses(tt_packet)?;
+        if let Some(tick_data) = self.request_tick_data(tick).ok().filter(|tick_data| tick_data.tick == tick) {
+            order_transactions(&mut transactions, &tick_data);
+        }
 
-        for tx in tt {
-            let digest: QubicTxHash = tx.into();
-            if digest == tx_hash {
-                status = TransactionStatus::Included;
-                break;
-            }
+        Ok(transactions)
+    }
+
+    /// cross-references the received transactions with the digests of the tick data, see `TickTransactionsReport::complete`
+    pub fn request_tick_transactions_detailed(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<TickTransactionsReport> {
+        let tick = tick.into().get();
+        let mut transactions = self.request_tick_transactions(tick, flags)?;
+        let tick_data = self.request_tick_data(tick).ok().filter(|tick_data| tick_data.tick == tick);
+
+        if let Some(tick_data) = &tick_data {
+            order_transactions(&mut transactions, tick_data);
         }
 
-        let td = self.request_tick_data(tick)?;
+        Ok(TickTransactionsReport::new(transactions, &flags, tick_data.as_deref()))
+    }
 
-        for executed_tx_hash in td.transaction_digest {
-            if executed_tx_hash == tx_hash {
-                status = TransactionStatus::Executed;
-                break;
-            }
+    /// status of the transaction in `tick`, `Pending` until the computor passed the tick. A tick without tick data is
+    /// `Unknown` unless the computor answers it as empty
+    pub fn check_transaction_status(&self, tx_hash: QubicTxHash, tick: impl Into<TickNumber>) -> Result<TransactionStatus> {
+        let tick = tick.into().get();
+        Ok(self.transaction_status(tx_hash, tick)?.0)
+    }
+
+    /// like `check_transaction_status`, the status is proven if the tick data it was derived from hashes to the
+    /// transaction digest a quorum of computors voted for
+    pub fn check_transaction_status_proven(&self, tx_hash: QubicTxHash, tick: impl Into<TickNumber>) -> Result<ProvenTransactionStatus> {
+        let tick = tick.into().get();
+        let (status, tick_data) = self.transaction_status(tx_hash, tick)?;
+
+        let proven = match tick_data {
+            Some(tick_data) if !matches!(status, TransactionStatus::Unknown { .. }) => tick_data.verify_quorum(&self.request_quorum_votes(tick)?),
+            _ => false
+        };
+
+        Ok(ProvenTransactionStatus { status, proven })
+    }
+
+    /// status of the transaction with the tick data it was derived from, `None` if the tick did not pass yet
+    fn transaction_status(&self, tx_hash: QubicTxHash, tick: u32) -> Result<(TransactionStatus, Option<TickDataStatus>)> {
+        if let Some(status) = TransactionStatus::from_current_tick(tick, &self.get_current_tick_info()?) {
+            return Ok((status, None))
         }
-        
-        Ok(status)
+
+        let received = self.request_tick_transactions(tick, TransactionFlags::all())?;
+        let tick_data = self.request_tick_data_range(tick, tick)?.ticks.pop();
+
+        let status = match &tick_data {
+            Some(TickDataStatus::Present(tick_data)) => TransactionStatus::in_tick(&tx_hash, received.iter().map(QubicTxHash::from), Some(tick_data)),
+            Some(TickDataStatus::EmptyTick) => TransactionStatus::in_tick(&tx_hash, received.iter().map(QubicTxHash::from), None),
+            Some(TickDataStatus::Missing) | None => TransactionStatus::Unknown { reason: format!("Computor did not answer the tick data of tick {tick}") }
+        };
+
+        Ok((status, tick_data))
     }
 
+    /// hands every network event to the handler, an event of a later epoch than the ones seen before is followed by `EpochChanged`
+    /// A panic of the handler is logged and its event skipped, the subscription goes on with the next message
     pub fn subscribe<F>(&self, public_peers: ExchangePublicPeers, event_handler: F) -> Result<()> 
-        where F: Fn(NetworkEvent) -> Result<()> + Send + Sync + 'static
+        where F: Fn(EventEnvelope) -> anyhow::Result<()> + Send + Sync + 'static
     {
         let url = self.transport.get_url();
-        let _: JoinHandle<Result<()>> = std::thread::Builder::new().name("qubic-event-handler".to_string()).stack_size(10_000_000).spawn(move || {
-            let event_handler = event_handler;
-            let url = url;
-            if let Ok(transport) = T::new(url.clone(), None) {
-                let mut header_buffer = vec![0u8; std::mem::size_of::<Header>()];
-                let mut data_buffer = vec![0u8; 10_000_000];
-
-                'connection: loop {
-                    let mut stream = transport.connect()?;
-                    stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-                    stream.write_all(&Packet::new(public_peers, true).to_bytes())?;
-                    loop {
-                        match stream.read_exact(&mut header_buffer) {
-                            Err(_) => continue 'connection,
-                            _ => ()
-                        };
-
-                        let header = unsafe {
-                            read_unaligned(header_buffer.as_ptr() as *const Header)
-                        };
-
-                        match stream.read_exact(&mut data_buffer[0..(header.get_size() - std::mem::size_of::<Header>())]) {
-                            Err(_) => continue 'connection,
-                            _ => ()
-                        };
-
-                        match header.message_type {
-                            MessageType::ExchangePublicPeers => {
-                                event_handler(NetworkEvent::ExchangePublicPeers(unsafe { read_unaligned(data_buffer.as_ptr() as *const ExchangePublicPeers) }))?;
-                            },
-                            MessageType::BroadcastMessage => {
-                                
-                                event_handler(NetworkEvent::BroadcastMessage(unsafe { read_unaligned(data_buffer.as_ptr() as *const BroadcastMessage) }))?;
-                            },
-                            MessageType::BroadcastTransaction => {
-                                let tx = TransactionWithData::from_bytes(&data_buffer[..header.get_size() - std::mem::size_of::<Header>()])?;
-
-                                event_handler(NetworkEvent::BroadcastTransaction(tx))?;
-                            },
-                            MessageType::BroadcastTick => {
-                                event_handler(NetworkEvent::BroadcastTick(unsafe { read_unaligned(data_buffer.as_ptr() as *const Tick) }))?;
-                            },
-                            MessageType::BroadcastFutureTickData => {
-                                event_handler(NetworkEvent::BroadcastFutureTick(Box::new(unsafe { read_unaligned(data_buffer.as_ptr() as *const TickData) })))?;
-                            }
-                            _ => ()
-                        }
-                    }
-                }
+        let options = RequestOptions { proxy: self.transport.proxy().cloned(), ..Default::default() };
+        let _: JoinHandle<anyhow::Result<()>> = std::thread::Builder::new().name("qubic-event-handler".to_string()).spawn(move || {
+            if let Ok(transport) = T::new(url.clone(), options) {
+                let mut epochs = EpochTracker::default();
+
+                read_messages(&*transport, public_peers, |header, payload| {
+                    network_events(&url, &mut epochs, header, payload).into_iter().try_for_each(&event_handler)
+                })?;
             }
             
             Ok(())
@@ -336,7 +886,57 @@ impl<'a, T> Qu<'a, T> where T: Transport {
         Ok(())
     }
 
-    pub fn make_ipo_bid(&self, wallet: &QubicWallet, contract_index: u32, price_per_share: u64, number_of_shares: u16, tick: u32) -> Result<QubicTxHash> {
+    /// like `subscribe` but every message is handed over as received, borrowed from the receive buffer.
+    /// Parsing is left to the handler, e.g. with `RawEvent::view`. Panics of the handler are logged and skipped as well
+    pub fn subscribe_raw<F>(&self, public_peers: ExchangePublicPeers, event_handler: F) -> Result<()>
+        where F: Fn(RawEvent<'_>) -> anyhow::Result<()> + Send + Sync + 'static
+    {
+        let url = self.transport.get_url();
+        let options = RequestOptions { proxy: self.transport.proxy().cloned(), ..Default::default() };
+        let _: JoinHandle<anyhow::Result<()>> = std::thread::Builder::new().name("qubic-raw-event-handler".to_string()).spawn(move || {
+            if let Ok(transport) = T::new(url.clone(), options) {
+                read_messages(&*transport, public_peers, |header, payload| event_handler(RawEvent { source: &url, header, payload }))?;
+            }
+
+            Ok(())
+        })?;
+
+        Ok(())
+    }
+
+    /// like `subscribe` but the events are buffered for the returned receiver, a slow consumer is handled according to
+    /// the overflow policy of `config`. A `Disconnected` event marks every reconnect, see the `subscription` module
+    pub fn subscribe_channel(&self, config: SubscriptionConfig) -> Result<(SubscriptionHandle, subscription::SubscriptionReceiver)> {
+        let url = self.transport.get_url();
+        let options = RequestOptions { proxy: self.transport.proxy().cloned(), ..Default::default() };
+        let (handle, sender, receiver) = subscription::channel(&config);
+
+        let _: JoinHandle<()> = std::thread::Builder::new().name("qubic-event-channel".to_string()).spawn(move || {
+            let transport = match T::new(url.clone(), options) {
+                Ok(transport) => transport,
+                Err(_) => return log::error!("Subscription to {url} failed to set up its transport")
+            };
+            let mut reader = MessageReader::new(&*transport, config.public_peers);
+            let mut epochs = EpochTracker::default();
+
+            while !sender.is_closed() {
+                let events = match reader.next() {
+                    Ok(Some((header, payload))) => network_events(&url, &mut epochs, &header, payload),
+                    Ok(None) => vec![envelope(&url, NetworkEvent::Disconnected)],
+                    Err(e) => return log::error!("Subscription to {url} ended: {e}")
+                };
+
+                if !events.into_iter().all(|event| sender.send(event)) {
+                    return
+                }
+            }
+        })?;
+
+        Ok((handle, receiver))
+    }
+
+    pub fn make_ipo_bid(&self, wallet: &QubicWallet, contract_index: u32, price_per_share: u64, number_of_shares: u16, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let mut dst = QubicId::default();
 
         dst.0[0..4].copy_from_slice(&contract_index.to_le_bytes());
@@ -361,85 +961,226 @@ impl<'a, T> Qu<'a, T> where T: Transport {
             signature: wallet.sign(raw_call)
         };
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
 
-        self.transport.send_without_response(packet)?;
+        self.transport.send_without_response(packet, &self.options)?;
         Ok(call.into())
     }
 
+    #[deprecated(note = "only the first log is parsed, use `request_logs` or `request_log_to_writer`")]
     pub fn request_log(&self, passcode: [u64; 4]) -> Result<QubicLog> {
-        let packet = Packet::new(RequestLog { passcode }, true);
+        let packet = Packet::new(RequestLog { passcode }, true)?;
+
+        Ok(self.transport.send_with_response(packet, &self.options)?)
+    }
+
+    /// every log the node emitted since the last request with the passcode
+    pub fn request_logs(&self, passcode: [u64; 4]) -> Result<QubicLogs> {
+        let packet = Packet::new(RequestLog { passcode }, true)?;
+
+        self.transport.send_with_response(packet, &self.options)
+    }
+
+    /// streams the raw logs the node emitted since the last request with the passcode to `writer` in chunks, read them
+    /// back with `LogParser`. Logs larger than `RequestOptions::with_max_log_size` are rejected before anything is
+    /// written. Returns the number of bytes written
+    pub fn request_log_to_writer(&self, passcode: [u64; 4], mut writer: impl Write) -> Result<usize> {
+        let timeouts = self.transport.timeouts().with_overrides(&self.options);
+        let mut stream = connect_stream(&self.transport.get_url(), &timeouts, self.options.proxy_or(self.transport.proxy()))?;
+        let mut header_buffer = [0; std::mem::size_of::<Header>()];
+        stream.write_all(&Packet::new(RequestLog { passcode }, true)?.to_bytes())?;
+
+        let size = loop {
+            stream.read_exact(&mut header_buffer)?;
+            let header = Header::from_bytes(&header_buffer)?;
+            let size = header.get_size().saturating_sub(std::mem::size_of::<Header>());
+
+            match header.message_type {
+                MessageType::RespondLog => break size,
+                // the greeting of the peer
+                MessageType::ExchangePublicPeers => stream.read_exact(&mut vec![0; size])?,
+                got => return Err(ClientError::UnexpectedMessageType { expected: MessageType::RespondLog, got })
+            }
+        };
+
+        if let Some(max) = self.options.max_log_size.filter(|max| size > *max) {
+            return Err(ClientError::ResponseTooLarge { size, max })
+        }
+
+        let mut chunk = vec![0; LOG_CHUNK_SIZE.min(size)];
+        let mut written = 0;
+
+        while written < size {
+            let chunk = &mut chunk[..LOG_CHUNK_SIZE.min(size - written)];
+            stream.read_exact(chunk)?;
+            writer.write_all(chunk)?;
+            written += chunk.len();
+        }
+
+        writer.flush()?;
+
+        Ok(written)
+    }
+
+    pub fn get_send_to_many_fees(&self) -> Result<SendToManyFeeOutput> {
+        let packet = Packet::new(RequestContractFunction {
+            contract_index: SEND_TO_MANY_CONTRACT_INDEX,
+            input_type: 1,
+            input_size: 0
+        }, true)?;
+
+        Ok(self.transport.send_with_response(packet, &self.options)?)
+    }
+
+    /// calls the function `input_type` of the contract with `input`, the output is decoded as `O`
+    pub fn request_contract_function<I: Copy, O: FromBytes>(&self, contract_index: u32, input_type: u16, input: I) -> Result<O> {
+        let packet = Packet::new(ContractFunctionCall::new(contract_index, input_type, input), true)?;
+
+        Ok(self.transport.send_with_response(packet, &self.options)?)
+    }
+
+    /// static fees together with the current SendToMany fee of the contract
+    pub fn fee_schedule(&self) -> Result<FeeSchedule> {
+        Ok(FeeSchedule { send_to_many_fee: self.get_send_to_many_fees()?.fee as u64 })
+    }
+
+    /// requests the SendToMany fee from the contract, all other fees are static
+    pub fn estimate_fees(&self, data: &TransactionData) -> Result<FeeBreakdown> {
+        let schedule = match data {
+            TransactionData::SendToMany(_) => self.fee_schedule()?,
+            _ => FeeSchedule::default()
+        };
 
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(schedule.estimate(data).unwrap_or_else(|e| match e {}))
     }
 
-    pub fn get_send_to_many_fees(&self) -> Result<SendToManyFeeOutput> {
-        let packet = Packet::new(RequestContractFunction {
-            contract_index: SEND_TO_MANY_CONTRACT_INDEX,
-            input_type: 1,
-            input_size: 0
-        }, true);
+    /// checks whether the transaction would likely execute against the balance of its source, the current tick and
+    /// the dust threshold, see `simulate_transfer`. The transaction does not have to be signed
+    pub fn simulate_transfer(&self, tx: &TransactionWithData) -> Result<TransferSimulation> {
+        let context = SimulationContext {
+            balance: self.request_entity(tx.raw_transaction.from)?.entity.balance(),
+            tick_info: self.get_current_tick_info()?,
+            dust_threshold: self.request_system_info()?.current_entity_balance_dust_threshold,
+            fees: self.estimate_fees(&tx.data)?
+        };
 
-        Ok(self.transport.send_with_response(packet)?)
+        Ok(simulate_transfer(tx, &context))
     }
 
     /// panics if txns.len() > 25
-    pub fn send_to_many(&self, wallet: &QubicWallet, txns: &[SendToManyTransaction], tick: u32) -> Result<QubicTxHash> {
+    pub fn send_to_many(&self, wallet: &QubicWallet, txns: &[SendToManyTransaction], tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let mut input = SendToManyInput::default();
         for (idx, tx) in txns.into_iter().enumerate() {
             input.ids[idx] = tx.id;
             input.amounts[idx] = tx.amount;
         }
 
-        let fee = self.get_send_to_many_fees()?;
-
         let tx = TransactionBuilder::new()
-                                        .with_amount(fee.fee as u64)
+                                        .with_tx_data(TransactionData::SendToMany(input))?
+                                        .with_estimated_fees(self)?
                                         .with_signing_wallet(wallet)
                                         .with_tick(tick)
-                                        .with_tx_data(TransactionData::SendToMany(input))
                                         .build();
         
-        let hash = QubicTxHash::from(tx.clone());
+        let encoded = Encoded::new(&tx);
+        let hash = transaction_hash(encoded.as_bytes());
 
-        let packet = Packet::new(tx, false);
+        let packet = Packet::from_ref(&encoded, false)?;
 
-        self.transport.send_without_response(packet)?;
+        self.transport.send_without_response(packet, &self.options)?;
         Ok(hash)
     }
 
+    /// scores of the miners, the node does not answer commands which are not signed by its operator
+    pub fn get_mining_score_ranking(&self, operator: &QubicWallet) -> Result<MiningScoreRanking> {
+        let packet = Packet::new(SpecialCommand::new(GetMiningScoreRanking, operator), true)?;
+
+        mining_score_ranking(self.transport.send_with_response(packet, &self.options))
+    }
+
     pub fn special_command_get_mining_ranking(&self, operator: &QubicWallet) -> Result<MiningScoreRanking> {
-        let packet = Packet::new(SpecialCommand::new(GetMiningScoreRanking, operator), true);
-        
-        Ok(self.transport.send_with_response(packet)?)
+        self.get_mining_score_ranking(operator)
+    }
+
+    /// sends a command prepared with `CommandBuilder`, it has to be signed by the operator of the node
+    pub fn send_special_command<C: ToBytes + FromBytes>(&self, command: SpecialCommand<C>) -> Result<()> {
+        self.transport.send_without_response(Packet::new(command, true)?, &self.options)
+    }
+
+    /// sets the clock of the node to `time`, answered with the time the node runs on then. The node does not answer
+    /// commands which are not signed by its operator
+    pub fn set_time(&self, operator: &QubicWallet, time: QubicSetUtcTime) -> Result<QubicSetUtcTime> {
+        let packet = Packet::new(CommandBuilder::set_time(time)?.build(operator), true)?;
+
+        set_time_echo(self.transport.send_with_response(packet, &self.options))
+    }
+}
+
+/// requests the SendToMany fee from the contract, all other fees are static
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl<T: Transport> FeeEstimator for Qu<'_, T> {
+    type Err = ClientError;
+
+    fn estimate(&self, data: &TransactionData) -> Result<FeeBreakdown> {
+        let schedule = match data {
+            TransactionData::SendToMany(_) => self.fee_schedule()?,
+            _ => FeeSchedule::default()
+        };
+
+        Ok(schedule.estimate(data).unwrap_or_else(|e| match e {}))
     }
 }
 
 pub struct Qx<'a, T: Transport> {
-    transport: &'a T
+    transport: &'a T,
+    options: RequestOptions
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 impl<'a, T: Transport> Qx<'a, T> {
     pub fn request_owned_assets(&self, id: QubicId) -> Result<Vec<RespondOwnedAsset>> {
-        let packet = Packet::new(RequestOwnedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestOwnedAsset { public_key: id }, true)?;
 
-        Ok(self.transport.send_with_multiple_responses(packet)?)
+        Ok(self.transport.send_with_multiple_responses(packet, &self.options)?)
     }
 
     pub fn request_issued_assets(&self, id: QubicId) -> Result<Vec<RespondIssuedAsset>> {
-        let packet = Packet::new(RequestIssuedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestIssuedAsset { public_key: id }, true)?;
 
-        Ok(self.transport.send_with_multiple_responses(packet)?)
+        Ok(self.transport.send_with_multiple_responses(packet, &self.options)?)
     }
 
     pub fn request_possessed_assets(&self, id: QubicId) -> Result<Vec<RespondPossessedAsset>> {
-        let packet = Packet::new(RequestPossessedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestPossessedAsset { public_key: id }, true)?;
+
+        Ok(self.transport.send_with_multiple_responses(packet, &self.options)?)
+    }
+
+    /// asset records of the universe matching the filter of `request`
+    pub fn request_assets(&self, request: RequestAssets) -> Result<Vec<RespondAssets>> {
+        let packet = Packet::new(request, true)?;
+
+        Ok(self.transport.send_with_multiple_responses(packet, &self.options)?)
+    }
+
+    /// looks up the issuance of the asset `name` in the universe, its total units are the sum of its ownership records.
+    /// The first issuance is taken if several issuers issued an asset of that name
+    pub fn find_asset(&self, name: &str) -> Result<Option<AssetSummary>> {
+        let name = AssetName::from_str(name)?;
+        let issuances = self.request_assets(RequestAssets::issuances_by_name(name))?;
+
+        let Some(issuance) = issuances.iter().find(|res| AssetSummary::from_issuance(&res.asset).is_some_and(|asset| asset.name == name.into())) else {
+            return Ok(None)
+        };
 
-        Ok(self.transport.send_with_multiple_responses(packet)?)
+        let ownerships = self.request_assets(RequestAssets::ownerships_of(issuance.asset.public_key, name))?;
+
+        Ok(AssetSummary::with_ownerships(issuance, &ownerships))
     }
 
-    pub fn transfer_qx_share(&self, wallet: &QubicWallet, possessor: QubicId, to: QubicId, units: i64, tick: u32) -> Result<QubicTxHash> {
+    pub fn transfer_qx_share(&self, wallet: &QubicWallet, possessor: QubicId, to: QubicId, units: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
@@ -465,14 +1206,21 @@ impl<'a, T: Transport> Qx<'a, T> {
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
 
-        self.transport.send_without_response(packet)?;
+        self.transport.send_without_response(packet, &self.options)?;
 
         Ok(call.into())
     }
 
-    pub fn issue_asset(&self, wallet: &QubicWallet, name: &str, unit_of_measurement: [u8; 7], number_of_units: i64, number_of_decimal_places: i8, tick: u32) -> Result<QubicTxHash> {
+    #[allow(clippy::too_many_arguments)]
+    pub fn issue_asset(&self, wallet: &QubicWallet, name: &str, unit_of_measurement: [u8; 7], number_of_units: i64, number_of_decimal_places: i8, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        self.issue_asset_with_input(wallet, IssueAssetInput::new(AssetName::from_str(name)?, number_of_units, unit_of_measurement, number_of_decimal_places), tick)
+    }
+
+    /// issues the asset of `input` for `ISSUE_ASSET_FEE`, see `IssueAssetInput::new`
+    pub fn issue_asset_with_input(&self, wallet: &QubicWallet, input: IssueAssetInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
@@ -482,66 +1230,141 @@ impl<'a, T: Transport> Qx<'a, T> {
             input_size: std::mem::size_of::<IssueAssetInput>() as u16
         };
 
-        let mut padded_uom = [0; 8];
-
-        padded_uom[..7].copy_from_slice(&unit_of_measurement);
-
         let mut call = Call {
             raw_call: RawCall {
                 tx,
-                input: IssueAssetInput {
-                    name: AssetName::from_str(name)?,
-                    number_of_units,
-                    unit_of_measurement: u64::from_le_bytes(padded_uom),
-                    number_of_decimal_places
-                }
+                input
             },
             signature: Signature::default()
         };
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
-        self.transport.send_without_response(packet)?;
+        let packet = Packet::new(call, false)?;
+        self.transport.send_without_response(packet, &self.options)?;
 
         Ok(call.into())
     }
 
-    pub fn transfer_asset(&self, wallet: &QubicWallet, possessor: QubicId, issuer: QubicId, to: QubicId, name: &str, units: i64, tick: u32) -> Result<QubicTxHash> {
+    /// transfers ownership and possession of `units` to `to`
+    #[allow(clippy::too_many_arguments)]
+    pub fn transfer_asset(&self, wallet: &QubicWallet, possessor: QubicId, issuer: QubicId, to: QubicId, name: &str, units: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let input = TransferAssetOwnershipAndPossessionInput {
+            possessor,
+            issuer,
+            new_owner: to,
+            asset_name: AssetName::from_str(name)?,
+            number_of_units: units
+        };
+
+        self.transfer_asset_with_input(wallet, input, tick)
+    }
+
+    /// transfers ownership and possession of the units of `input` to its new owner
+    pub fn transfer_asset_with_input(&self, wallet: &QubicWallet, input: TransferAssetOwnershipAndPossessionInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
+        validate_transfer(input.number_of_units, &[("possessor", input.possessor), ("new owner", input.new_owner)])?;
+
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
-            amount: 1_000_000,
+            amount: TRANSFER_FEE,
             tick,
-            input_type: 2,
+            input_type: QX_TRANSFER_OWNERSHIP_AND_POSSESSION,
             input_size: std::mem::size_of::<TransferAssetOwnershipAndPossessionInput>() as u16
         };
 
         let mut call = Call {
             raw_call: RawCall {
                 tx,
-                input: TransferAssetOwnershipAndPossessionInput {
-                    possessor,
-                    issuer,
-                    new_owner: to,
-                    asset_name: AssetName::from_str(name)?,
-                    number_of_units: units
-                }
+                input
             },
             signature: Signature::default()
         };
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
+
+        self.transport.send_without_response(packet, &self.options)?;
+        Ok(call.into())
+    }
+}
+
+/// Burns and polls of the QUtil contract besides SendToMany, see `qubic_tcp_types::types::qutil`
+pub struct QUtil<'a, T: Transport> {
+    transport: &'a T,
+    options: RequestOptions
+}
+
+/// procedure call of QUtil signed by `wallet`
+fn qutil_call<I: Copy>(wallet: &QubicWallet, input_type: u16, amount: u64, input: I, tick: impl Into<TickNumber>) -> Call<I> {
+    let tx = RawTransaction {
+        from: wallet.public_key,
+        to: QubicId::from_contract_id(QUTIL_CONTRACT_INDEX),
+        amount,
+        tick: tick.into().get(),
+        input_type,
+        input_size: std::mem::size_of::<I>() as u16
+    };
+
+    let mut call = Call {
+        raw_call: RawCall { tx, input },
+        signature: Signature::default()
+    };
+
+    call.signature = wallet.sign(call.raw_call);
+
+    call
+}
+
+/// the amount of a burn is sent along with it
+fn validate_burn(amount: i64) -> Result<u64> {
+    u64::try_from(amount).ok().filter(|amount| *amount > 0)
+        .ok_or_else(|| ClientError::InvalidInput(format!("Burned amount has to be positive (found {amount})")))
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl<'a, T: Transport> QUtil<'a, T> {
+    /// burns `amount` QU of `wallet`
+    pub fn burn_qu(&self, wallet: &QubicWallet, amount: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let call = qutil_call(wallet, QUTIL_BURN_QUBIC, validate_burn(amount)?, BurnQuInput { amount }, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options)?;
+
+        Ok(call.into())
+    }
+
+    /// creates the poll of `input` for `QUTIL_POLL_CREATION_FEE`
+    pub fn create_poll(&self, wallet: &QubicWallet, input: CreatePollInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let call = qutil_call(wallet, QUTIL_CREATE_POLL, QUTIL_POLL_CREATION_FEE, input, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options)?;
+
+        Ok(call.into())
+    }
+
+    /// votes for `chosen_option` of the poll with `amount` of the balance or shares of `wallet`, for `QUTIL_VOTE_FEE`
+    pub fn vote(&self, wallet: &QubicWallet, poll_id: u64, amount: u64, chosen_option: u64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let input = VoteInput { poll_id, address: wallet.public_key, amount, chosen_option };
+        let call = qutil_call(wallet, QUTIL_VOTE, QUTIL_VOTE_FEE, input, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options)?;
 
-        self.transport.send_without_response(packet)?;
         Ok(call.into())
     }
+
+    pub fn get_poll_results(&self, poll_id: u64) -> Result<PollResults> {
+        let packet = Packet::new(ContractFunctionCall::new(QUTIL_CONTRACT_INDEX, QUTIL_GET_CURRENT_RESULT, GetPollResultsInput { poll_id }), true)?;
+        let output: GetPollResultsOutput = self.transport.send_with_response(packet, &self.options)?;
+
+        Ok(output.into())
+    }
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 impl<'a, T> Qu<'a, T> where T: Transport {
+    /// signs and sends a transaction without input, transactions with input are built and sent with `send_signed_transaction`
     pub async fn send_raw_transaction(&self, wallet: &QubicWallet, raw_transaction: RawTransaction) -> Result<()> {
         
         let transaction = Transaction {
@@ -549,17 +1372,46 @@ impl<'a, T> Qu<'a, T> where T: Transport {
             signature: wallet.sign(raw_transaction)
         };
 
-        self.transport.send_without_response(Packet::new(transaction, false)).await?;
+        self.transport.send_without_response(Packet::new(transaction, false)?, &self.options).await?;
         Ok(())
     }
 
-    pub async fn send_signed_transaction(&self, transaction: Transaction) -> Result<()> {
-        self.transport.send_without_response(Packet::new(transaction, false)).await?;
+    /// sends an already signed `Transaction`, `Call` or `TransactionWithData` as is
+    pub async fn send_signed_transaction<Tx: Into<TransactionWithData>>(&self, transaction: Tx) -> Result<()> {
+        let txwd: TransactionWithData = transaction.into();
+        self.transport.send_without_response(Packet::from_ref(&txwd, false)?, &self.options).await?;
         Ok(())
     }
 
+    /// broadcasts the transaction to all `peers` concurrently, fails if less than `min_success` peers accepted it
+    pub async fn send_signed_transaction_multi<Tx: Into<TransactionWithData>>(&self, transaction: Tx, peers: &[String], min_success: usize) -> Result<BroadcastReport>
+        where ClientError: From<T::Err>
+    {
+        let txwd: TransactionWithData = transaction.into();
+        let packet = Packet::from_ref(&txwd, false)?;
+        let options = self.broadcast_options();
+
+        let sends = peers.iter().map(|peer| {
+            let (packet, options) = (packet.clone(), &options);
+            async move {
+                let transport = T::new(peer.clone(), options.clone()).await?;
+                transport.send_without_response(packet, options).await
+            }
+        });
+
+        let mut report = BroadcastReport::default();
+
+        for (peer, res) in peers.iter().zip(futures::future::join_all(sends).await) {
+            match res {
+                Ok(()) => report.succeeded.push(peer.clone()),
+                Err(e) => report.failed.push((peer.clone(), e))
+            }
+        }
+
+        report.check(min_success)
+    }
+
     pub async fn submit_work(&self, wallet: &QubicWallet, solution: WorkSolution) -> Result<()> {
-        let mut rng = rand::thread_rng();
         let mut message: BroadcastMessage = solution.into();
         let mut shared_key_and_gamming_nonce = [0u64; 8];
         let mut gamming_key: [u64; 4];
@@ -579,7 +1431,8 @@ impl<'a, T> Qu<'a, T> where T: Transport {
 
         loop {
             unsafe {
-                message.gamming_nonce.0 = rng.gen();
+                // a thread_rng held across the await below would make the future !Send
+                message.gamming_nonce.0 = rand::thread_rng().gen();
                 copy_nonoverlapping(message.gamming_nonce.0.as_ptr(), shared_key_and_gamming_nonce.as_mut_ptr().add(4) as *mut u8, 32);
                 let mut kg = KangarooTwelve::hash(&shared_key_and_gamming_nonce.iter().map(|i| i.to_le_bytes()).collect::<Vec<_>>().into_iter().flatten().collect::<Vec<_>>(), &[]);
                 let mut gk = [0; 32];
@@ -614,118 +1467,379 @@ impl<'a, T> Qu<'a, T> where T: Transport {
 
         message.signature = wallet.sign_raw(digest);
 
-        self.transport.send_without_response(Packet::new(message, false)).await?;
+        self.transport.send_without_response(Packet::new(message, false)?, &self.options).await?;
         Ok(())
     }
 
     pub async fn get_current_tick_info(&self) -> Result<CurrentTickInfo> {
-        let packet = Packet::new(GetCurrentTickInfo, true);
+        let packet = Packet::new(GetCurrentTickInfo, true)?;
 
-        self.transport.send_with_response(packet).await
+        self.transport.send_with_response(packet, &self.options).await
     }
 
-    pub async fn request_computors(&self) -> Result<Computors> {
-        let packet = Packet::new(RequestComputors, true);
-        
-        
-        self.transport.send_with_response(packet).await
+    pub async fn request_system_info(&self) -> Result<SystemInfo> {
+        let packet = Packet::new(RequestSystemInfo, true)?;
+
+        self.transport.send_with_response(packet, &self.options).await
+    }
+
+    pub async fn get_send_to_many_fees(&self) -> Result<SendToManyFeeOutput> {
+        let packet = Packet::new(RequestContractFunction {
+            contract_index: SEND_TO_MANY_CONTRACT_INDEX,
+            input_type: 1,
+            input_size: 0
+        }, true)?;
+
+        self.transport.send_with_response(packet, &self.options).await
+    }
+
+    /// calls the function `input_type` of the contract with `input`, the output is decoded as `O`
+    pub async fn request_contract_function<I: Copy, O: FromBytes>(&self, contract_index: u32, input_type: u16, input: I) -> Result<O> {
+        let packet = Packet::new(ContractFunctionCall::new(contract_index, input_type, input), true)?;
+
+        self.transport.send_with_response(packet, &self.options).await
+    }
+
+    /// static fees together with the current SendToMany fee of the contract, the schedule estimates fees without further requests
+    pub async fn fee_schedule(&self) -> Result<FeeSchedule> {
+        Ok(FeeSchedule { send_to_many_fee: self.get_send_to_many_fees().await?.fee as u64 })
+    }
+
+    /// requests the SendToMany fee from the contract, all other fees are static
+    pub async fn estimate_fees(&self, data: &TransactionData) -> Result<FeeBreakdown> {
+        let schedule = match data {
+            TransactionData::SendToMany(_) => self.fee_schedule().await?,
+            _ => FeeSchedule::default()
+        };
+
+        Ok(schedule.estimate(data).unwrap_or_else(|e| match e {}))
+    }
+
+    /// checks whether the transaction would likely execute against the balance of its source, the current tick and
+    /// the dust threshold, see `simulate_transfer`. The transaction does not have to be signed
+    pub async fn simulate_transfer(&self, tx: &TransactionWithData) -> Result<TransferSimulation> {
+        let context = SimulationContext {
+            balance: self.request_entity(tx.raw_transaction.from).await?.entity.balance(),
+            tick_info: self.get_current_tick_info().await?,
+            dust_threshold: self.request_system_info().await?.current_entity_balance_dust_threshold,
+            fees: self.estimate_fees(&tx.data).await?
+        };
+
+        Ok(simulate_transfer(tx, &context))
+    }
+
+    /// decoded on the heap, see `Computors::new_boxed`
+    pub async fn request_computors(&self) -> Result<Box<Computors>> {
+        let packet = Packet::new(RequestComputors, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<Computors>, _>(packet, &self.options).await?.0)
     }
 
+    /// the entity along with the tick and spectrum index it is valid for, see `RespondedEntity::entity_only`
     pub async fn request_entity(&self, public_key: QubicId) -> Result<RespondedEntity> {
-        let packet = Packet::new(RequestEntity { public_key }, true);
+        let packet = Packet::new(RequestEntity { public_key }, true)?;
         
-        self.transport.send_with_response(packet).await
+        self.transport.send_with_response(packet, &self.options).await
     }
 
-    pub async fn request_contract_ipo(&self, contract_index: u32) -> Result<ContractIpo> {
-        let packet = Packet::new(RequestContractIpo { contract_index }, true);
-        
-        self.transport.send_with_response(packet).await
+    /// decoded on the heap, see `ContractIpo::new_boxed`
+    pub async fn request_contract_ipo(&self, contract_index: u32) -> Result<Box<ContractIpo>> {
+        let packet = Packet::new(RequestContractIpo { contract_index }, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<ContractIpo>, _>(packet, &self.options).await?.0)
+    }
+
+    /// decoded on the heap, see `TickData::new_boxed`
+    pub async fn request_tick_data(&self, tick: impl Into<TickNumber>) -> Result<Box<TickData>> {
+        let tick = tick.into().get();
+        let packet = Packet::new(RequestTickData { tick }, true)?;
+
+        Ok(self.transport.send_with_response::<Boxed<TickData>, _>(packet, &self.options).await?.0)
+    }
+
+    /// tick data of the ticks `start..=end`, requested over one connection with up to 32 requests in flight.
+    /// Ticks the computor answers without tick data are `EmptyTick`. Ticks still unanswered at a read timeout are requested
+    /// again, ticks it does not answer to 3 requests are `Missing`, see `TickDataRange::gaps`
+    pub async fn request_tick_data_range(&self, start: impl Into<TickNumber>, end: impl Into<TickNumber>) -> Result<TickDataRange> {
+        let (start, end) = (start.into().get(), end.into().get());
+        let mut pipeline = TickDataPipeline::new(start, end)?;
+        let timeouts = self.transport.timeouts().with_overrides(&self.options);
+        let mut stream = connect_stream(&self.transport.get_url().await, &timeouts, self.options.proxy_or(self.transport.proxy())).await?;
+        let mut header_buffer = [0; std::mem::size_of::<Header>()];
+
+        loop {
+            timed(timeouts.write, stream.write_all(&pipeline.requests()?)).await?;
+
+            if pipeline.is_done() {
+                break
+            }
+
+            match timed(timeouts.read, stream.read_exact(&mut header_buffer)).await {
+                Ok(_) => (),
+                Err(ClientError::Timeout) => {
+                    pipeline.time_out();
+                    continue
+                },
+                Err(e) => return Err(e)
+            }
+
+            let header = Header::from_bytes(&header_buffer)?;
+            let mut payload = vec![0; header.get_size().saturating_sub(std::mem::size_of::<Header>())];
+            timed(timeouts.read, stream.read_exact(&mut payload)).await?;
+
+            pipeline.receive(&header, &payload);
+        }
+
+        Ok(pipeline.finish())
+    }
+
+    /// every log the node emitted since the last request with the passcode
+    pub async fn request_logs(&self, passcode: [u64; 4]) -> Result<QubicLogs> {
+        let packet = Packet::new(RequestLog { passcode }, true)?;
+
+        self.transport.send_with_response(packet, &self.options).await
     }
 
-    pub async fn request_tick_data(&self, tick: u32) -> Result<Tick> {
-        let packet = Packet::new(RequestTickData { tick }, true);
-    
-        self.transport.send_with_response(packet).await
+    /// streams the raw logs the node emitted since the last request with the passcode to `writer` in chunks, read them
+    /// back with `LogParser`. Logs larger than `RequestOptions::with_max_log_size` are rejected before anything is
+    /// written. Returns the number of bytes written
+    pub async fn request_log_to_writer(&self, passcode: [u64; 4], mut writer: impl AsyncWrite + Unpin) -> Result<usize> {
+        let timeouts = self.transport.timeouts().with_overrides(&self.options);
+        let mut stream = connect_stream(&self.transport.get_url().await, &timeouts, self.options.proxy_or(self.transport.proxy())).await?;
+        let mut header_buffer = [0; std::mem::size_of::<Header>()];
+        timed(timeouts.write, stream.write_all(&Packet::new(RequestLog { passcode }, true)?.to_bytes())).await?;
+
+        let size = loop {
+            timed(timeouts.read, stream.read_exact(&mut header_buffer)).await?;
+            let header = Header::from_bytes(&header_buffer)?;
+            let size = header.get_size().saturating_sub(std::mem::size_of::<Header>());
+
+            match header.message_type {
+                MessageType::RespondLog => break size,
+                // the greeting of the peer
+                MessageType::ExchangePublicPeers => { timed(timeouts.read, stream.read_exact(&mut vec![0; size])).await?; },
+                got => return Err(ClientError::UnexpectedMessageType { expected: MessageType::RespondLog, got })
+            }
+        };
+
+        if let Some(max) = self.options.max_log_size.filter(|max| size > *max) {
+            return Err(ClientError::ResponseTooLarge { size, max })
+        }
+
+        let mut chunk = vec![0; LOG_CHUNK_SIZE.min(size)];
+        let mut written = 0;
+
+        while written < size {
+            let chunk = &mut chunk[..LOG_CHUNK_SIZE.min(size - written)];
+            timed(timeouts.read, stream.read_exact(chunk)).await?;
+            timed(timeouts.write, writer.write_all(chunk)).await?;
+            written += chunk.len();
+        }
+
+        timed(timeouts.write, writer.flush()).await?;
+
+        Ok(written)
     }
 
-    pub async fn request_quorum_tick(&self, tick: u32, vote_flags: [u8; (676 + 7) / 8]) -> Result<Tick> {
-        let packet = Packet::new(QuorumTickData { tick, vote_flags }, true);
+    pub async fn request_quorum_tick(&self, tick: impl Into<TickNumber>, vote_flags: VoteFlags) -> Result<Tick> {
+        let tick = tick.into().get();
+        let packet = Packet::new(QuorumTickData { tick, vote_flags }, true)?;
         
-        self.transport.send_with_response(packet).await
+        self.transport.send_with_response(packet, &self.options).await
+    }
+
+    /// collects the votes of all computors for `tick` and checks them against the quorum
+    pub async fn request_quorum_votes(&self, tick: impl Into<TickNumber>) -> Result<QuorumSummary> {
+        let tick = tick.into().get();
+        let packet = Packet::new(QuorumTickData { tick, vote_flags: [0; std::mem::size_of::<VoteFlags>()] }, true)?;
+        let votes: Vec<Tick> = self.transport.send_with_multiple_responses(packet, &self.options).await?;
+
+        Ok(QuorumSummary::from_votes(&votes))
+    }
+
+    /// whether `tick` is final, a quorum of computors voted for the same digests of the tick
+    pub async fn is_tick_finalized(&self, tick: impl Into<TickNumber>) -> Result<bool> {
+        let tick = tick.into().get();
+        Ok(self.request_quorum_votes(tick).await?.quorum_reached)
     }
 
     pub async fn exchange_public_peers(&self, peers: ExchangePublicPeers) -> Result<ExchangePublicPeers> {
-        let packet = Packet::new(peers, true);
+        let packet = Packet::new(peers, true)?;
+
+        self.transport.send_with_response(packet, &self.options).await
+    }
+
+    /// transactions of the tick in arrival order, transactions the peer sent more than once are dropped
+    pub async fn request_tick_transactions(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
+        let tick = tick.into().get();
+        let packet = Packet::new(RequestedTickTransactions { tick, flags }, true)?;
+        let mut transactions = self.transport.send_with_multiple_responses(packet, &self.options).await?;
+        dedup_transactions(&mut transactions);
+
+        Ok(transactions)
+    }
+
+    /// transactions of the tick sent to `transactions` in arrival order as soon as they are received, transactions the
+    /// peer sent more than once are dropped. The number of sent transactions is returned, the request is dropped once
+    /// `transactions` is closed
+    pub async fn stream_tick_transactions(&self, tick: impl Into<TickNumber>, flags: TransactionFlags, mut transactions: mpsc::Sender<TransactionWithData>) -> Result<usize> {
+        let tick = tick.into().get();
+        let packet = Packet::new(RequestedTickTransactions { tick, flags }, true)?;
+        let (received, mut responses) = mpsc::channel(0);
+
+        let forward = async move {
+            let mut seen = HashSet::new();
+            let mut sent = 0;
+
+            while let Some(tx) = responses.next().await {
+                if !seen.insert(QubicTxHash::from(&tx)) {
+                    continue;
+                }
+
+                // dropping `responses` closes the channel of the transport as well
+                if transactions.send(tx).await.is_err() {
+                    break;
+                }
+
+                sent += 1;
+            }
+
+            sent
+        };
+
+        let (res, sent) = futures::join!(self.transport.send_with_streamed_responses(packet, &self.options, received), forward);
+        res.map(|_| sent)
+    }
+
+    /// transactions of the tick in the order of the tick data, arrival order if the tick data is not available
+    pub async fn request_tick_transactions_ordered(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
+        let tick = tick.into().get();
+        let mut transactions = self.request_tick_transactions(tick, flags).await?;
+
+        if let Some(tick_data) = self.request_tick_data(tick).await.ok().filter(|tick_data| tick_data.tick == tick) {
+            order_transactions(&mut transactions, &tick_data);
+        }
+
+        Ok(transactions)
+    }
+
+    /// cross-references the received transactions with the digests of the tick data, see `TickTransactionsReport::complete`
+    pub async fn request_tick_transactions_detailed(&self, tick: impl Into<TickNumber>, flags: TransactionFlags) -> Result<TickTransactionsReport> {
+        let tick = tick.into().get();
+        let mut transactions = self.request_tick_transactions(tick, flags).await?;
+        let tick_data = self.request_tick_data(tick).await.ok().filter(|tick_data| tick_data.tick == tick);
+
+        if let Some(tick_data) = &tick_data {
+            order_transactions(&mut transactions, tick_data);
+        }
+
+        Ok(TickTransactionsReport::new(transactions, &flags, tick_data.as_deref()))
+    }
+
+    /// status of the transaction in `tick`, `Pending` until the computor passed the tick. A tick without tick data is
+    /// `Unknown` unless the computor answers it as empty
+    pub async fn check_transaction_status(&self, tx_hash: QubicTxHash, tick: impl Into<TickNumber>) -> Result<TransactionStatus> {
+        let tick = tick.into().get();
+        Ok(self.transaction_status(tx_hash, tick).await?.0)
+    }
+
+    /// like `check_transaction_status`, the status is proven if the tick data it was derived from hashes to the
+    /// transaction digest a quorum of computors voted for
+    pub async fn check_transaction_status_proven(&self, tx_hash: QubicTxHash, tick: impl Into<TickNumber>) -> Result<ProvenTransactionStatus> {
+        let tick = tick.into().get();
+        let (status, tick_data) = self.transaction_status(tx_hash, tick).await?;
+
+        let proven = match tick_data {
+            Some(tick_data) if !matches!(status, TransactionStatus::Unknown { .. }) => tick_data.verify_quorum(&self.request_quorum_votes(tick).await?),
+            _ => false
+        };
 
-        self.transport.send_with_response(packet).await
+        Ok(ProvenTransactionStatus { status, proven })
     }
 
-    pub async fn request_tick_transactions(&self, tick: u32, flags: TransactionFlags) -> Result<Vec<TransactionWithData>> {
-        let packet = Packet::new(RequestedTickTransactions { tick, flags }, true);
+    /// status of the transaction with the tick data it was derived from, `None` if the tick did not pass yet
+    async fn transaction_status(&self, tx_hash: QubicTxHash, tick: u32) -> Result<(TransactionStatus, Option<TickDataStatus>)> {
+        if let Some(status) = TransactionStatus::from_current_tick(tick, &self.get_current_tick_info().await?) {
+            return Ok((status, None))
+        }
+
+        let received = self.request_tick_transactions(tick, TransactionFlags::all()).await?;
+        let tick_data = self.request_tick_data_range(tick, tick).await?.ticks.pop();
+
+        let status = match &tick_data {
+            Some(TickDataStatus::Present(tick_data)) => TransactionStatus::in_tick(&tx_hash, received.iter().map(QubicTxHash::from), Some(tick_data)),
+            Some(TickDataStatus::EmptyTick) => TransactionStatus::in_tick(&tx_hash, received.iter().map(QubicTxHash::from), None),
+            Some(TickDataStatus::Missing) | None => TransactionStatus::Unknown { reason: format!("Computor did not answer the tick data of tick {tick}") }
+        };
 
-        self.transport.send_with_multiple_responses(packet).await
+        Ok((status, tick_data))
     }
 
+    /// hands every network event to the handler, an event of a later epoch than the ones seen before is followed by `EpochChanged`
+    /// A panic of the handler is logged and its event skipped, the subscription goes on with the next message
     pub async fn subscribe<F>(&self, public_peers: ExchangePublicPeers, event_handler: F) -> Result<()> 
-        where F: Fn(NetworkEvent) -> Result<()> + Send + Sync + 'static
+        where F: Fn(EventEnvelope) -> anyhow::Result<()> + Send + Sync + 'static
     {
         let url = self.transport.get_url().await;
+        let proxy = self.transport.proxy().cloned();
 
-        let _: tokio::task::JoinHandle<Result<()>> = tokio::spawn(async move {
-            let event_handler = event_handler;
-            let mut header_buffer = vec![0u8; std::mem::size_of::<Header>()];
-            let mut data_buffer = vec![0u8; 10_000_000];
+        runtime::spawn(async move {
+            let mut epochs = EpochTracker::default();
 
-            'connection: loop {
-                let std_stream = std::net::TcpStream::connect(&url)?;
+            read_messages(&url, proxy.as_ref(), public_peers, |header, payload| {
+                network_events(&url, &mut epochs, header, payload).into_iter().try_for_each(&event_handler)
+            }).await
+        });
+        
+        Ok(())
+    }
 
-                std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-                std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+    /// like `subscribe` but every message is handed over as received, borrowed from the receive buffer.
+    /// Parsing is left to the handler, e.g. with `RawEvent::view`. Panics of the handler are logged and skipped as well
+    pub async fn subscribe_raw<F>(&self, public_peers: ExchangePublicPeers, event_handler: F) -> Result<()>
+        where F: Fn(RawEvent<'_>) -> anyhow::Result<()> + Send + Sync + 'static
+    {
+        let url = self.transport.get_url().await;
+        let proxy = self.transport.proxy().cloned();
 
-                let mut stream = tokio::net::TcpStream::from_std(std_stream)?;
+        runtime::spawn(async move {
+            read_messages(&url, proxy.as_ref(), public_peers, |header, payload| event_handler(RawEvent { source: &url, header, payload })).await
+        });
 
-                stream.write_all(&Packet::new(public_peers, true).to_bytes()).await?;
-                loop {
-                    if stream.read_exact(&mut header_buffer).await.is_err() {
-                        continue 'connection
-                    }
+        Ok(())
+    }
 
-                    let header = Header::from_bytes(&header_buffer).unwrap();
-
-                    if stream.read_exact(&mut data_buffer[0..(header.get_size() - std::mem::size_of::<Header>())]).await.is_err() {
-                        continue 'connection
-                    };
-
-                    match header.message_type {
-                        MessageType::ExchangePublicPeers => {
-                            event_handler(NetworkEvent::ExchangePublicPeers(unsafe { read_unaligned(data_buffer.as_ptr() as *const ExchangePublicPeers) }))?;
-                        },
-                        MessageType::BroadcastMessage => {
-                            
-                            event_handler(NetworkEvent::BroadcastMessage(unsafe { read_unaligned(data_buffer.as_ptr() as *const BroadcastMessage) }))?;
-                        },
-                        MessageType::BroadcastTransaction => {
-                            let tx = TransactionWithData::from_bytes(&data_buffer[..header.get_size() - std::mem::size_of::<Header>()]).unwrap();
-
-                            event_handler(NetworkEvent::BroadcastTransaction(tx))?;
-                        },
-                        MessageType::BroadcastTick => {
-                            event_handler(NetworkEvent::BroadcastTick(unsafe { read_unaligned(data_buffer.as_ptr() as *const Tick) }))?;
-                        },
-                        MessageType::BroadcastFutureTickData => {
-                            event_handler(NetworkEvent::BroadcastFutureTick(Box::new(unsafe { read_unaligned(data_buffer.as_ptr() as *const TickData) })))?;
-                        }
-                        _ => ()
+    /// like `subscribe` but the events are buffered for the returned stream, a slow consumer is handled according to
+    /// the overflow policy of `config`. A `Disconnected` event marks every reconnect, see the `subscription` module
+    pub async fn subscribe_channel(&self, config: SubscriptionConfig) -> Result<(SubscriptionHandle, impl Stream<Item = EventEnvelope> + Send + 'static)> {
+        let url = self.transport.get_url().await;
+        let proxy = self.transport.proxy().cloned();
+        let (handle, sender, stream) = subscription::channel(&config);
+
+        runtime::spawn(async move {
+            let mut reader = MessageReader::new(&url, proxy.as_ref(), config.public_peers);
+            let mut epochs = EpochTracker::default();
+
+            while !sender.is_closed() {
+                let events = match reader.next().await {
+                    Ok(Some((header, payload))) => network_events(&url, &mut epochs, &header, payload),
+                    Ok(None) => vec![envelope(&url, NetworkEvent::Disconnected)],
+                    Err(e) => return log::error!("Subscription to {url} ended: {e}")
+                };
+
+                for event in events {
+                    if !sender.send(event).await {
+                        return
                     }
                 }
             }
         });
-        
-        Ok(())
+
+        Ok((handle, stream))
     }
 
-    pub async fn make_ipo_bid(&self, wallet: &QubicWallet, contract_index: u32, price_per_share: u64, number_of_shares: u16, tick: u32) -> Result<QubicTxHash> {
+    pub async fn make_ipo_bid(&self, wallet: &QubicWallet, contract_index: u32, price_per_share: u64, number_of_shares: u16, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let mut dst = QubicId::default();
 
         dst.0[0..4].copy_from_slice(&contract_index.to_le_bytes());
@@ -750,34 +1864,81 @@ impl<'a, T> Qu<'a, T> where T: Transport {
             signature: wallet.sign(raw_call)
         };
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
 
-        self.transport.send_without_response(packet).await?;
+        self.transport.send_without_response(packet, &self.options).await?;
         Ok(call.into())
     }
+
+    /// scores of the miners, the node does not answer commands which are not signed by its operator
+    pub async fn get_mining_score_ranking(&self, operator: &QubicWallet) -> Result<MiningScoreRanking> {
+        let packet = Packet::new(SpecialCommand::new(GetMiningScoreRanking, operator), true)?;
+
+        mining_score_ranking(self.transport.send_with_response(packet, &self.options).await)
+    }
+
+    pub async fn special_command_get_mining_ranking(&self, operator: &QubicWallet) -> Result<MiningScoreRanking> {
+        self.get_mining_score_ranking(operator).await
+    }
+
+    /// sends a command prepared with `CommandBuilder`, it has to be signed by the operator of the node
+    pub async fn send_special_command<C: ToBytes + FromBytes>(&self, command: SpecialCommand<C>) -> Result<()> {
+        self.transport.send_without_response(Packet::new(command, true)?, &self.options).await
+    }
+
+    /// sets the clock of the node to `time`, answered with the time the node runs on then. The node does not answer
+    /// commands which are not signed by its operator
+    pub async fn set_time(&self, operator: &QubicWallet, time: QubicSetUtcTime) -> Result<QubicSetUtcTime> {
+        let packet = Packet::new(CommandBuilder::set_time(time)?.build(operator), true)?;
+
+        set_time_echo(self.transport.send_with_response(packet, &self.options).await)
+    }
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 impl<'a, T: Transport> Qx<'a, T> {
     pub async fn request_owned_assets(&self, id: QubicId) -> Result<Vec<RespondOwnedAsset>> {
-        let packet = Packet::new(RequestOwnedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestOwnedAsset { public_key: id }, true)?;
 
-        self.transport.send_with_multiple_responses(packet).await
+        self.transport.send_with_multiple_responses(packet, &self.options).await
     }
 
     pub async fn request_issued_assets(&self, id: QubicId) -> Result<Vec<RespondIssuedAsset>> {
-        let packet = Packet::new(RequestIssuedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestIssuedAsset { public_key: id }, true)?;
 
-        self.transport.send_with_multiple_responses(packet).await
+        self.transport.send_with_multiple_responses(packet, &self.options).await
     }
 
     pub async fn request_possessed_assets(&self, id: QubicId) -> Result<Vec<RespondPossessedAsset>> {
-        let packet = Packet::new(RequestPossessedAsset { public_key: id }, true);
+        let packet = Packet::new(RequestPossessedAsset { public_key: id }, true)?;
 
-        self.transport.send_with_multiple_responses(packet).await
+        self.transport.send_with_multiple_responses(packet, &self.options).await
     }
 
-    pub async fn transfer_qx_share(&self, wallet: &QubicWallet, possessor: QubicId, to: QubicId, units: i64, tick: u32) -> Result<QubicTxHash> {
+    /// asset records of the universe matching the filter of `request`
+    pub async fn request_assets(&self, request: RequestAssets) -> Result<Vec<RespondAssets>> {
+        let packet = Packet::new(request, true)?;
+
+        self.transport.send_with_multiple_responses(packet, &self.options).await
+    }
+
+    /// looks up the issuance of the asset `name` in the universe, its total units are the sum of its ownership records.
+    /// The first issuance is taken if several issuers issued an asset of that name
+    pub async fn find_asset(&self, name: &str) -> Result<Option<AssetSummary>> {
+        let name = AssetName::from_str(name)?;
+        let issuances = self.request_assets(RequestAssets::issuances_by_name(name)).await?;
+
+        let Some(issuance) = issuances.iter().find(|res| AssetSummary::from_issuance(&res.asset).is_some_and(|asset| asset.name == name.into())) else {
+            return Ok(None)
+        };
+
+        let ownerships = self.request_assets(RequestAssets::ownerships_of(issuance.asset.public_key, name)).await?;
+
+        Ok(AssetSummary::with_ownerships(issuance, &ownerships))
+    }
+
+    pub async fn transfer_qx_share(&self, wallet: &QubicWallet, possessor: QubicId, to: QubicId, units: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
@@ -794,7 +1955,7 @@ impl<'a, T: Transport> Qx<'a, T> {
                     possessor,
                     issuer: QubicId::default(),
                     new_owner: to,
-                    asset_name: AssetName::from_str("QX").unwrap(),
+                    asset_name: AssetName::from_str("QX")?,
                     number_of_units: units
                 }
             },
@@ -803,14 +1964,21 @@ impl<'a, T: Transport> Qx<'a, T> {
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
 
-        self.transport.send_without_response(packet).await?;
+        self.transport.send_without_response(packet, &self.options).await?;
 
         Ok(call.into())
     }
 
-    pub async fn issue_asset(&self, wallet: &QubicWallet, name: &str, unit_of_measurement: [u8; 7], number_of_units: i64, number_of_decimal_places: i8, tick: u32) -> Result<QubicTxHash> {
+    #[allow(clippy::too_many_arguments)]
+    pub async fn issue_asset(&self, wallet: &QubicWallet, name: &str, unit_of_measurement: [u8; 7], number_of_units: i64, number_of_decimal_places: i8, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        self.issue_asset_with_input(wallet, IssueAssetInput::new(AssetName::from_str(name)?, number_of_units, unit_of_measurement, number_of_decimal_places), tick).await
+    }
+
+    /// issues the asset of `input` for `ISSUE_ASSET_FEE`, see `IssueAssetInput::new`
+    pub async fn issue_asset_with_input(&self, wallet: &QubicWallet, input: IssueAssetInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
@@ -820,60 +1988,101 @@ impl<'a, T: Transport> Qx<'a, T> {
             input_size: std::mem::size_of::<IssueAssetInput>() as u16
         };
 
-        let mut padded_uom = [0; 8];
-
-        padded_uom[..7].copy_from_slice(&unit_of_measurement);
-
         let mut call = Call {
             raw_call: RawCall {
                 tx,
-                input: IssueAssetInput {
-                    name: AssetName::from_str(name).unwrap(),
-                    number_of_units,
-                    unit_of_measurement: u64::from_le_bytes(padded_uom),
-                    number_of_decimal_places
-                }
+                input
             },
             signature: Signature::default()
         };
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
-        self.transport.send_without_response(packet).await?;
+        let packet = Packet::new(call, false)?;
+        self.transport.send_without_response(packet, &self.options).await?;
 
         Ok(call.into())
     }
 
-    pub async fn transfer_asset(&self, wallet: &QubicWallet, possessor: QubicId, issuer: QubicId, to: QubicId, name: &str, units: i64, tick: u32) -> Result<QubicTxHash> {
+    /// transfers ownership and possession of `units` to `to`
+    #[allow(clippy::too_many_arguments)]
+    pub async fn transfer_asset(&self, wallet: &QubicWallet, possessor: QubicId, issuer: QubicId, to: QubicId, name: &str, units: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let input = TransferAssetOwnershipAndPossessionInput {
+            possessor,
+            issuer,
+            new_owner: to,
+            asset_name: AssetName::from_str(name)?,
+            number_of_units: units
+        };
+
+        self.transfer_asset_with_input(wallet, input, tick).await
+    }
+
+    /// transfers ownership and possession of the units of `input` to its new owner
+    pub async fn transfer_asset_with_input(&self, wallet: &QubicWallet, input: TransferAssetOwnershipAndPossessionInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let tick = tick.into().get();
+        validate_transfer(input.number_of_units, &[("possessor", input.possessor), ("new owner", input.new_owner)])?;
+
         let tx = RawTransaction {
             from: wallet.public_key,
             to: QXID,
-            amount: 1_000_000,
+            amount: TRANSFER_FEE,
             tick,
-            input_type: 2,
+            input_type: QX_TRANSFER_OWNERSHIP_AND_POSSESSION,
             input_size: std::mem::size_of::<TransferAssetOwnershipAndPossessionInput>() as u16
         };
 
         let mut call = Call {
             raw_call: RawCall {
                 tx,
-                input: TransferAssetOwnershipAndPossessionInput {
-                    possessor,
-                    issuer,
-                    new_owner: to,
-                    asset_name: AssetName::from_str(name).unwrap(),
-                    number_of_units: units
-                }
+                input
             },
             signature: Signature::default()
         };
 
         call.signature = wallet.sign(call.raw_call);
 
-        let packet = Packet::new(call, false);
+        let packet = Packet::new(call, false)?;
+
+        self.transport.send_without_response(packet, &self.options).await?;
+        Ok(call.into())
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl<'a, T: Transport> QUtil<'a, T> {
+    /// burns `amount` QU of `wallet`
+    pub async fn burn_qu(&self, wallet: &QubicWallet, amount: i64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let call = qutil_call(wallet, QUTIL_BURN_QUBIC, validate_burn(amount)?, BurnQuInput { amount }, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options).await?;
+
+        Ok(call.into())
+    }
+
+    /// creates the poll of `input` for `QUTIL_POLL_CREATION_FEE`
+    pub async fn create_poll(&self, wallet: &QubicWallet, input: CreatePollInput, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let call = qutil_call(wallet, QUTIL_CREATE_POLL, QUTIL_POLL_CREATION_FEE, input, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options).await?;
+
+        Ok(call.into())
+    }
+
+    /// votes for `chosen_option` of the poll with `amount` of the balance or shares of `wallet`, for `QUTIL_VOTE_FEE`
+    pub async fn vote(&self, wallet: &QubicWallet, poll_id: u64, amount: u64, chosen_option: u64, tick: impl Into<TickNumber>) -> Result<QubicTxHash> {
+        let input = VoteInput { poll_id, address: wallet.public_key, amount, chosen_option };
+        let call = qutil_call(wallet, QUTIL_VOTE, QUTIL_VOTE_FEE, input, tick);
+
+        self.transport.send_without_response(Packet::new(call, false)?, &self.options).await?;
 
-        self.transport.send_without_response(packet).await?;
         Ok(call.into())
     }
-}
\ No newline at end of file
+
+    pub async fn get_poll_results(&self, poll_id: u64) -> Result<PollResults> {
+        let packet = Packet::new(ContractFunctionCall::new(QUTIL_CONTRACT_INDEX, QUTIL_GET_CURRENT_RESULT, GetPollResultsInput { poll_id }), true)?;
+        let output: GetPollResultsOutput = self.transport.send_with_response(packet, &self.options).await?;
+
+        Ok(output.into())
+    }
+}
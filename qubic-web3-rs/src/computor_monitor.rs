@@ -0,0 +1,352 @@
+//! Tracks the votes of every computor over a sliding window of ticks, e.g. to flag computors which keep missing
+//! ticks or signing digests diverging from the majority
+//!
+//! Only the votes relayed by the subscribed peer are seen, missed ticks are therefore relative to the observed votes.
+//! Votes are only counted if they are signed by the computor of their index, the computors of their epoch have to be
+//! registered with `set_computors`.
+
+use std::collections::{BTreeMap, HashMap, VecDeque};
+
+use qubic_tcp_types::{consts::NUMBER_OF_COMPUTORS, events::NetworkEvent, types::ticks::{QuorumSummary, Tick, TickDigests}};
+use qubic_types::{Epoch, QubicId, Tick as TickNumber};
+
+/// Votes of a tick are evaluated once a vote for a tick this many ticks later is received
+pub const SETTLE_TICKS: u32 = 2;
+
+/// Votes for ticks further than this from the current tick are dropped, a vote far ahead would settle every pending
+/// tick at once
+pub const MAX_TICK_DISTANCE: u32 = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Vote {
+    /// the computor was not part of the set when the tick was voted on
+    Untracked,
+    Missed,
+    Agreed,
+    Diverged
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct ComputorStats {
+    pub computor_index: u16,
+    /// known if the computors of the epoch were registered with `set_computors`
+    pub identity: Option<QubicId>,
+    pub signed: u32,
+    pub missed: u32,
+    /// signed ticks whose digests differ from the majority
+    pub divergent: u32
+}
+
+impl ComputorStats {
+    /// number of ticks the computor was expected to vote for
+    pub fn observed(&self) -> u32 {
+        self.signed + self.missed
+    }
+
+    pub fn miss_rate(&self) -> f64 {
+        match self.observed() {
+            0 => 0.0,
+            observed => self.missed as f64 / observed as f64
+        }
+    }
+
+    pub fn divergence_rate(&self) -> f64 {
+        match self.signed {
+            0 => 0.0,
+            signed => self.divergent as f64 / signed as f64
+        }
+    }
+
+    fn add(&mut self, vote: Vote) {
+        match vote {
+            Vote::Untracked => (),
+            Vote::Missed => self.missed += 1,
+            Vote::Agreed => self.signed += 1,
+            Vote::Diverged => {
+                self.signed += 1;
+                self.divergent += 1;
+            }
+        }
+    }
+
+    fn remove(&mut self, vote: Vote) {
+        match vote {
+            Vote::Untracked => (),
+            Vote::Missed => self.missed -= 1,
+            Vote::Agreed => self.signed -= 1,
+            Vote::Diverged => {
+                self.signed -= 1;
+                self.divergent -= 1;
+            }
+        }
+    }
+}
+
+type AlertCallback = Box<dyn Fn(&ComputorAlert) + Send + Sync>;
+
+/// Rates above which a computor is alerted, computors observed for less than `min_observed` ticks are not alerted
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AlertThresholds {
+    pub max_miss_rate: f64,
+    pub max_divergence_rate: f64,
+    pub min_observed: u32
+}
+
+impl Default for AlertThresholds {
+    fn default() -> Self {
+        Self { max_miss_rate: 0.5, max_divergence_rate: 0.1, min_observed: 10 }
+    }
+}
+
+/// Raised once when a computor exceeds a threshold, it is raised again after the computor fell below it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComputorAlert {
+    MissingTicks(ComputorStats),
+    DivergentDigests(ComputorStats)
+}
+
+/// Computor statistics of the last `window` evaluated ticks, fed with the `BroadcastTick` events of a subscription
+///
+/// Computor indices change with the computor set of an epoch. If the computors of both epochs were registered with
+/// `set_computors` the window is re-keyed to the new indices by identity, otherwise it is reset.
+pub struct ComputorMonitor {
+    window: usize,
+    thresholds: AlertThresholds,
+    on_alert: Option<AlertCallback>,
+    computors: BTreeMap<u16, Vec<QubicId>>,
+    epoch: Option<u16>,
+    /// current tick of the network, see `set_tick`
+    current_tick: Option<u32>,
+    pending: BTreeMap<u32, Vec<Tick>>,
+    last_evaluated: Option<u32>,
+    history: VecDeque<Vec<Vote>>,
+    stats: Vec<ComputorStats>,
+    /// whether a missing ticks and a divergent digests alert was raised for the computor
+    alerting: Vec<(bool, bool)>
+}
+
+impl ComputorMonitor {
+    pub fn new(window: usize) -> Self {
+        Self {
+            window: window.max(1),
+            thresholds: AlertThresholds::default(),
+            on_alert: None,
+            computors: BTreeMap::new(),
+            epoch: None,
+            current_tick: None,
+            pending: BTreeMap::new(),
+            last_evaluated: None,
+            history: VecDeque::new(),
+            stats: Self::empty_stats(),
+            alerting: vec![(false, false); NUMBER_OF_COMPUTORS]
+        }
+    }
+
+    pub fn with_thresholds(mut self, thresholds: AlertThresholds) -> Self {
+        self.thresholds = thresholds;
+        self
+    }
+
+    /// calls `on_alert` whenever a computor exceeds a threshold
+    pub fn on_alert<F>(mut self, on_alert: F) -> Self
+        where F: Fn(&ComputorAlert) + Send + Sync + 'static
+    {
+        self.on_alert = Some(Box::new(on_alert));
+        self
+    }
+
+    fn empty_stats() -> Vec<ComputorStats> {
+        (0..NUMBER_OF_COMPUTORS).map(|idx| ComputorStats { computor_index: idx as u16, ..Default::default() }).collect()
+    }
+
+    /// registers the computor set of `epoch`, the sets of the current and the following epochs are kept
+    pub fn set_computors(&mut self, epoch: impl Into<Epoch>, ids: Vec<QubicId>) {
+        self.computors.insert(epoch.into().get(), ids);
+
+        if let Some(current) = self.epoch {
+            self.computors.retain(|&known, _| known >= current);
+        }
+    }
+
+    /// current tick of the network, votes further than `MAX_TICK_DISTANCE` from it or from the last evaluated tick
+    /// are dropped. Until it is set the first votes are accepted regardless of their tick
+    pub fn set_tick(&mut self, tick: impl Into<TickNumber>) {
+        self.current_tick = Some(tick.into().get());
+    }
+
+    /// epoch of the evaluated ticks
+    pub fn epoch(&self) -> Option<u16> {
+        self.epoch
+    }
+
+    /// number of ticks in the window
+    pub fn ticks(&self) -> usize {
+        self.history.len()
+    }
+
+    /// statistics of every computor index over the window
+    pub fn report(&self) -> Vec<ComputorStats> {
+        let ids = self.epoch.and_then(|epoch| self.computors.get(&epoch));
+
+        self.stats.iter().map(|stats| ComputorStats {
+            identity: ids.and_then(|ids| ids.get(stats.computor_index as usize).copied()),
+            ..*stats
+        }).collect()
+    }
+
+    /// feeds the votes of a subscription, other events are ignored
+    pub fn handle_event(&mut self, event: &NetworkEvent) {
+        if let NetworkEvent::BroadcastTick(tick) = event {
+            self.add_vote(**tick);
+        }
+    }
+
+    /// buffers the vote until its tick settles. Votes of evaluated ticks, votes out of `MAX_TICK_DISTANCE` and votes
+    /// not signed by the computor of their index are dropped
+    pub fn add_vote(&mut self, vote: Tick) {
+        if self.last_evaluated.is_some_and(|last| vote.tick <= last) || !self.in_window(vote.tick) || !self.signed(&vote) {
+            return
+        }
+
+        self.pending.entry(vote.tick).or_default().push(vote);
+
+        // pending votes fall out of the window once the current tick moved on
+        let anchor = self.anchor();
+        self.pending.retain(|&tick, _| Self::within(anchor, tick));
+
+        let Some(&newest) = self.pending.keys().next_back() else { return };
+
+        while let Some(entry) = self.pending.first_entry() {
+            if entry.key().saturating_add(SETTLE_TICKS) > newest {
+                break
+            }
+
+            let (tick, votes) = entry.remove_entry();
+            self.evaluate(tick, &votes);
+        }
+    }
+
+    /// newest trusted tick, the current tick or the last evaluated one
+    fn anchor(&self) -> Option<u32> {
+        self.current_tick.max(self.last_evaluated)
+    }
+
+    fn within(anchor: Option<u32>, tick: u32) -> bool {
+        anchor.is_none_or(|anchor| tick.abs_diff(anchor) <= MAX_TICK_DISTANCE)
+    }
+
+    fn in_window(&self, tick: u32) -> bool {
+        Self::within(self.anchor(), tick)
+    }
+
+    /// whether the computor of the vote's index in the computors of its epoch signed it
+    fn signed(&self, vote: &Tick) -> bool {
+        self.computors.get(&vote.epoch)
+            .and_then(|ids| ids.get(vote.computor_index as usize))
+            .is_some_and(|id| vote.verify(id))
+    }
+
+    fn evaluate(&mut self, tick: u32, votes: &[Tick]) {
+        self.last_evaluated = Some(tick);
+
+        let epoch = votes[0].epoch;
+        if self.epoch != Some(epoch) {
+            self.change_epoch(epoch);
+        }
+
+        let majority = QuorumSummary::from_votes(votes).digests;
+        let mut outcome = vec![Vote::Missed; NUMBER_OF_COMPUTORS];
+
+        // only the first vote of every computor is counted, like `QuorumSummary` does
+        for vote in votes.iter().rev() {
+            if let Some(slot) = outcome.get_mut(vote.computor_index as usize) {
+                *slot = if Some(TickDigests::from(vote)) == majority { Vote::Agreed } else { Vote::Diverged };
+            }
+        }
+
+        for (stats, vote) in self.stats.iter_mut().zip(&outcome) {
+            stats.add(*vote);
+        }
+
+        self.history.push_back(outcome);
+
+        if self.history.len() > self.window {
+            let expired = self.history.pop_front().unwrap();
+
+            for (stats, vote) in self.stats.iter_mut().zip(expired) {
+                stats.remove(vote);
+            }
+        }
+
+        self.raise_alerts();
+    }
+
+    /// re-keys the window to the computor indices of `epoch` or resets it if a computor set is unknown
+    fn change_epoch(&mut self, epoch: u16) {
+        let previous = self.epoch.and_then(|previous| self.computors.get(&previous));
+
+        match (previous, self.computors.get(&epoch)) {
+            (Some(previous), Some(next)) => {
+                let indices: HashMap<QubicId, usize> = next.iter().enumerate().map(|(idx, id)| (*id, idx)).collect();
+                let remap: Vec<Option<usize>> = previous.iter().map(|id| indices.get(id).copied()).collect();
+
+                for outcome in self.history.iter_mut() {
+                    let mut remapped = vec![Vote::Untracked; NUMBER_OF_COMPUTORS];
+
+                    for (vote, idx) in outcome.iter().zip(&remap) {
+                        if let Some(slot) = idx.and_then(|idx| remapped.get_mut(idx)) {
+                            *slot = *vote;
+                        }
+                    }
+
+                    *outcome = remapped;
+                }
+
+                let mut alerting = vec![(false, false); NUMBER_OF_COMPUTORS];
+                for (alert, idx) in self.alerting.iter().zip(&remap) {
+                    if let Some(slot) = idx.and_then(|idx| alerting.get_mut(idx)) {
+                        *slot = *alert;
+                    }
+                }
+                self.alerting = alerting;
+            },
+            _ => {
+                self.history.clear();
+                self.alerting = vec![(false, false); NUMBER_OF_COMPUTORS];
+            }
+        }
+
+        self.stats = Self::empty_stats();
+        for outcome in self.history.iter() {
+            for (stats, vote) in self.stats.iter_mut().zip(outcome) {
+                stats.add(*vote);
+            }
+        }
+
+        self.epoch = Some(epoch);
+        self.computors.retain(|&known, _| known >= epoch);
+    }
+
+    fn raise_alerts(&mut self) {
+        let report = self.report();
+        let thresholds = self.thresholds;
+
+        for (stats, (missing, divergent)) in report.into_iter().zip(self.alerting.iter_mut()) {
+            let observed = stats.observed() >= thresholds.min_observed;
+            let is_missing = observed && stats.miss_rate() > thresholds.max_miss_rate;
+            let is_divergent = observed && stats.divergence_rate() > thresholds.max_divergence_rate;
+
+            if let Some(on_alert) = &self.on_alert {
+                if is_missing && !*missing {
+                    on_alert(&ComputorAlert::MissingTicks(stats));
+                }
+
+                if is_divergent && !*divergent {
+                    on_alert(&ComputorAlert::DivergentDigests(stats));
+                }
+            }
+
+            (*missing, *divergent) = (is_missing, is_divergent);
+        }
+    }
+}
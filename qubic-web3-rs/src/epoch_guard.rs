@@ -0,0 +1,194 @@
+//! Keeps long-lived clients consistent across epoch transitions
+//!
+//! The computor set, the initial tick and the random mining seed change with every epoch. The guard caches the
+//! computors and the system info of the observed epoch, drops them once a later epoch is observed and rejects
+//! target ticks of earlier epochs. Epochs are only observed when the guard requests the peer, long-running
+//! services are expected to `refresh` it periodically, e.g. with every tick they poll.
+
+use std::sync::Mutex;
+
+use qubic_tcp_types::types::{ticks::CurrentTickInfo, transactions::TickValidator, Computors, SystemInfo};
+use qubic_types::Tick;
+
+use crate::{client::Client, errors::{ClientError, Result}, transport::Transport};
+
+type EpochCallback = Box<dyn Fn(u16, u16) + Send + Sync>;
+
+/// epoch and initial tick of the observed epoch with the responses cached for it
+#[derive(Default)]
+struct EpochState {
+    epoch: Option<(u16, u32)>,
+    computors: Option<Computors>,
+    system_info: Option<SystemInfo>
+}
+
+/// Client watching the epoch of its peer, see the module documentation
+pub struct EpochGuard<T: Transport> {
+    client: Client<T>,
+    state: Mutex<EpochState>,
+    on_epoch_change: Vec<EpochCallback>
+}
+
+impl<T: Transport> EpochGuard<T> {
+    pub fn new(client: Client<T>) -> Self {
+        Self { client, state: Mutex::default(), on_epoch_change: Vec::new() }
+    }
+
+    /// calls `callback` with the old and the new epoch after the caches of the old epoch were dropped
+    pub fn on_epoch_change(mut self, callback: impl Fn(u16, u16) + Send + Sync + 'static) -> Self {
+        self.on_epoch_change.push(Box::new(callback));
+        self
+    }
+
+    pub fn client(&self) -> &Client<T> {
+        &self.client
+    }
+
+    /// `None` until the first response of the peer
+    pub fn current_epoch(&self) -> Option<u16> {
+        self.state.lock().unwrap().epoch.map(|(epoch, _)| epoch)
+    }
+
+    pub fn initial_tick(&self) -> Option<u32> {
+        self.state.lock().unwrap().epoch.map(|(_, initial_tick)| initial_tick)
+    }
+
+    /// whether `tick` is not before the initial tick of the observed epoch, `false` while no epoch was observed
+    pub fn is_tick_in_current_epoch(&self, tick: impl Into<Tick>) -> bool {
+        let tick = tick.into().get();
+        self.initial_tick().is_some_and(|initial_tick| tick >= initial_tick)
+    }
+
+    /// records the epoch of a response, a later epoch drops the caches and runs the callbacks.
+    /// Responses of earlier epochs come from lagging peers and are ignored
+    fn observe(&self, epoch: u16, initial_tick: u32) {
+        let old = {
+            let mut state = self.state.lock().unwrap();
+
+            match state.epoch {
+                Some((current, _)) if epoch < current => return,
+                Some((current, _)) if epoch == current => None,
+                current => {
+                    if current.is_some() {
+                        state.computors = None;
+                        state.system_info = None;
+                    }
+
+                    state.epoch = Some((epoch, initial_tick));
+                    current.map(|(old, _)| old)
+                }
+            }
+        };
+
+        if let Some(old) = old {
+            for callback in &self.on_epoch_change {
+                callback(old, epoch);
+            }
+        }
+    }
+
+    /// caches the computors if they are of the observed epoch
+    fn cache_computors(&self, computors: Computors) -> Computors {
+        let mut state = self.state.lock().unwrap();
+
+        if state.epoch.is_some_and(|(epoch, _)| epoch == computors.epoch) {
+            state.computors = Some(computors);
+        }
+
+        computors
+    }
+
+    /// caches the system info if it is of the observed epoch
+    fn cache_system_info(&self, system_info: SystemInfo) -> SystemInfo {
+        let mut state = self.state.lock().unwrap();
+
+        if state.epoch.is_some_and(|(epoch, _)| epoch == system_info.epoch) {
+            state.system_info = Some(system_info);
+        }
+
+        system_info
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl<T: Transport> EpochGuard<T> {
+    /// requests the current tick info and observes its epoch
+    pub fn refresh(&self) -> Result<CurrentTickInfo> {
+        let tick_info = self.client.qu().get_current_tick_info()?;
+        self.observe(tick_info.epoch, tick_info.initial_tick);
+
+        Ok(tick_info)
+    }
+
+    /// computors of the observed epoch, requested once per epoch
+    pub fn computors(&self) -> Result<Computors> {
+        if let Some(computors) = self.state.lock().unwrap().computors {
+            return Ok(computors)
+        }
+
+        Ok(self.cache_computors(*self.client.qu().request_computors()?))
+    }
+
+    /// system info of the observed epoch, requested once per epoch. Its tick is the one of the request
+    pub fn system_info(&self) -> Result<SystemInfo> {
+        if let Some(system_info) = self.state.lock().unwrap().system_info {
+            return Ok(system_info)
+        }
+
+        let system_info = self.client.qu().request_system_info()?;
+        self.observe(system_info.epoch, system_info.initial_tick);
+
+        Ok(self.cache_system_info(system_info))
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl<T: Transport> EpochGuard<T> {
+    /// requests the current tick info and observes its epoch
+    pub async fn refresh(&self) -> Result<CurrentTickInfo> {
+        let tick_info = self.client.qu().get_current_tick_info().await?;
+        self.observe(tick_info.epoch, tick_info.initial_tick);
+
+        Ok(tick_info)
+    }
+
+    /// computors of the observed epoch, requested once per epoch
+    pub async fn computors(&self) -> Result<Computors> {
+        if let Some(computors) = self.state.lock().unwrap().computors {
+            return Ok(computors)
+        }
+
+        let computors = *self.client.qu().request_computors().await?;
+
+        Ok(self.cache_computors(computors))
+    }
+
+    /// system info of the observed epoch, requested once per epoch. Its tick is the one of the request
+    pub async fn system_info(&self) -> Result<SystemInfo> {
+        if let Some(system_info) = self.state.lock().unwrap().system_info {
+            return Ok(system_info)
+        }
+
+        let system_info = self.client.qu().request_system_info().await?;
+        self.observe(system_info.epoch, system_info.initial_tick);
+
+        Ok(self.cache_system_info(system_info))
+    }
+}
+
+/// rejects ticks before the initial tick of the observed epoch, `refresh` the guard before building transactions
+impl<T: Transport> TickValidator for EpochGuard<T> {
+    type Err = ClientError;
+
+    fn validate_tick(&self, tick: u32) -> Result<()> {
+        let Some((epoch, initial_tick)) = self.state.lock().unwrap().epoch else {
+            return Err(ClientError::InvalidInput("No epoch observed yet, refresh the epoch guard first".to_owned()))
+        };
+
+        if tick < initial_tick {
+            return Err(ClientError::StaleTick { tick, epoch, initial_tick })
+        }
+
+        Ok(())
+    }
+}
@@ -0,0 +1,87 @@
+use std::{convert::Infallible, net::Ipv4Addr};
+
+use qubic_tcp_types::{types::{special_commands::{CommandError, CommandType}, transactions::InputTooLong}, MessageType};
+use qubic_types::errors::{ByteEncodingError, QubicError, U24OverflowError};
+use thiserror::Error;
+
+pub type Result<T, E = ClientError> = core::result::Result<T, E>;
+
+#[derive(Debug, Error)]
+pub enum ClientError {
+    #[error("I/O error: {0}")]
+    Io(std::io::Error),
+
+    #[error("Request timed out")]
+    Timeout,
+
+    #[error("Failed to decode response: {0}")]
+    Decode(#[from] ByteEncodingError),
+
+    #[error("Unexpected message type (expected {expected:#}, got {got:#})")]
+    UnexpectedMessageType { expected: MessageType, got: MessageType },
+
+    #[error("Peer closed the connection")]
+    PeerClosed,
+
+    /// the peer greeted with its public peers and closed the connection instead of answering
+    #[error("Peer is busy, it suggested {suggested_peers:?}")]
+    PeerBusy { suggested_peers: Vec<Ipv4Addr> },
+
+    #[error("Response of {size} bytes exceeds the maximum of {max} bytes")]
+    ResponseTooLarge { size: usize, max: usize },
+
+    #[error("Invalid input: {0}")]
+    InvalidInput(String),
+
+    /// `failed` holds the error of every peer which did not accept the transaction
+    #[error("Transaction reached {succeeded} peers but {required} were required ({})", failed.iter().map(|(peer, e)| format!("{peer}: {e}")).collect::<Vec<_>>().join(", "))]
+    BroadcastFailed { succeeded: usize, required: usize, failed: Vec<(String, ClientError)> },
+
+    #[error("Tick {tick} precedes the initial tick {initial_tick} of epoch {epoch}")]
+    StaleTick { tick: u32, epoch: u16, initial_tick: u32 },
+
+    #[error("Special command {0:?} was rejected, it has to be signed by the operator of the node")]
+    CommandRejected(CommandType)
+}
+
+impl From<std::io::Error> for ClientError {
+    fn from(value: std::io::Error) -> Self {
+        use std::io::ErrorKind;
+
+        match value.kind() {
+            ErrorKind::TimedOut | ErrorKind::WouldBlock => Self::Timeout,
+            ErrorKind::UnexpectedEof | ErrorKind::ConnectionReset | ErrorKind::ConnectionAborted | ErrorKind::BrokenPipe => Self::PeerClosed,
+            _ => Self::Io(value)
+        }
+    }
+}
+
+impl From<QubicError> for ClientError {
+    fn from(value: QubicError) -> Self {
+        Self::InvalidInput(value.to_string())
+    }
+}
+
+impl From<U24OverflowError> for ClientError {
+    fn from(value: U24OverflowError) -> Self {
+        Self::InvalidInput(value.to_string())
+    }
+}
+
+impl From<InputTooLong> for ClientError {
+    fn from(value: InputTooLong) -> Self {
+        Self::InvalidInput(value.to_string())
+    }
+}
+
+impl From<CommandError> for ClientError {
+    fn from(value: CommandError) -> Self {
+        Self::InvalidInput(value.to_string())
+    }
+}
+
+impl From<Infallible> for ClientError {
+    fn from(value: Infallible) -> Self {
+        match value {}
+    }
+}
@@ -0,0 +1,68 @@
+//! Records network events as JSON lines and replays them later, e.g. for backtesting
+
+use std::io::{BufRead, Lines, Write};
+
+use qubic_tcp_types::events::EventEnvelope;
+
+/// Appends events to a JSONL log
+pub struct EventLogWriter<W: Write> {
+    writer: W
+}
+
+impl<W: Write> EventLogWriter<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    pub fn write(&mut self, event: &EventEnvelope) -> std::io::Result<()> {
+        serde_json::to_writer(&mut self.writer, event)?;
+        self.writer.write_all(b"\n")
+    }
+
+    pub fn flush(&mut self) -> std::io::Result<()> {
+        self.writer.flush()
+    }
+
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+/// Reads events from a JSONL log in the order they were recorded
+pub struct EventLogReader<R: BufRead> {
+    lines: Lines<R>
+}
+
+impl<R: BufRead> EventLogReader<R> {
+    pub fn new(reader: R) -> Self {
+        Self { lines: reader.lines() }
+    }
+
+    /// feeds all recorded events to a subscribe handler, returns the number of replayed events
+    pub fn replay<F>(self, event_handler: F) -> anyhow::Result<usize>
+        where F: Fn(EventEnvelope) -> anyhow::Result<()>
+    {
+        let mut replayed = 0;
+
+        for event in self {
+            event_handler(event?)?;
+            replayed += 1;
+        }
+
+        Ok(replayed)
+    }
+}
+
+impl<R: BufRead> Iterator for EventLogReader<R> {
+    type Item = std::io::Result<EventEnvelope>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        loop {
+            match self.lines.next()? {
+                Ok(line) if line.trim().is_empty() => continue,
+                Ok(line) => return Some(serde_json::from_str(&line).map_err(Into::into)),
+                Err(e) => return Some(Err(e))
+            }
+        }
+    }
+}
@@ -0,0 +1,193 @@
+//! Scripted computor on a local port, speaking the real packet framing so tests cover the transports end to end
+
+use std::{collections::BTreeMap, io::{Read, Write}, net::{Ipv4Addr, TcpListener, TcpStream}, sync::{atomic::{AtomicUsize, Ordering}, Arc, Mutex}, time::{Duration, Instant}};
+
+use qubic_tcp_types::{types::{ExchangePublicPeers, Packet}, Header, MessageType};
+use qubic_types::{traits::{FromBytes, ToBytes}, U24};
+
+type Handler = Box<dyn Fn(&[u8]) -> Reply + Send + Sync>;
+type Requests = Arc<Mutex<Vec<(MessageType, Vec<u8>)>>>;
+
+/// what the computor does with a request
+pub enum Reply {
+    /// writes the packets and keeps serving the connection
+    Packets(Vec<Vec<u8>>),
+    /// writes the packets after sleeping for the delay
+    Delayed(Duration, Vec<Vec<u8>>),
+    /// never answers, the client runs into its read timeout
+    Silence,
+    /// writes the packets and drops the connection
+    Close(Vec<Vec<u8>>)
+}
+
+/// frames `payload` as a packet of `message_type`
+pub fn packet(message_type: MessageType, payload: &[u8]) -> Vec<u8> {
+    let size = U24::try_from(std::mem::size_of::<Header>() + payload.len()).expect("payload fits a header");
+
+    [Header::new_with_dejavu(size, message_type, 0).to_bytes(), payload.to_vec()].concat()
+}
+
+/// the packet closing a stream of responses
+pub fn end_response() -> Vec<u8> {
+    packet(MessageType::EndResponse, &[])
+}
+
+/// like a real computor, responses carry the dejavu of their request
+fn answer(mut packets: Vec<Vec<u8>>, dejavu: u32) -> Vec<u8> {
+    const DEJAVU: std::ops::Range<usize> = 4..8;
+
+    for packet in packets.iter_mut().filter(|packet| packet.len() >= DEJAVU.end) {
+        packet[DEJAVU].copy_from_slice(&dejavu.to_le_bytes());
+    }
+
+    packets.concat()
+}
+
+/// computor answering requests with the handler registered for their message type, requests without a handler are ignored.
+/// Like a real computor it greets every connection with its public peers unless built `without_greeting`
+pub struct FakeComputor {
+    greeting: bool,
+    peers: ExchangePublicPeers,
+    handlers: BTreeMap<MessageType, Handler>
+}
+
+impl Default for FakeComputor {
+    fn default() -> Self {
+        Self { greeting: true, peers: ExchangePublicPeers::default(), handlers: BTreeMap::new() }
+    }
+}
+
+impl FakeComputor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn without_greeting(mut self) -> Self {
+        self.greeting = false;
+        self
+    }
+
+    /// public peers of the greeting, unspecified addresses by default
+    pub fn with_peers(mut self, peers: [Ipv4Addr; 4]) -> Self {
+        self.peers = ExchangePublicPeers { peers };
+        self
+    }
+
+    /// answers requests of `request` with the reply of `handler`, which is given the request payload
+    pub fn on(mut self, request: MessageType, handler: impl Fn(&[u8]) -> Reply + Send + Sync + 'static) -> Self {
+        self.handlers.insert(request, Box::new(handler));
+        self
+    }
+
+    /// answers requests of `request` with a single packet
+    pub fn respond(self, request: MessageType, response: MessageType, payload: Vec<u8>) -> Self {
+        let response = packet(response, &payload);
+
+        self.on(request, move |_| Reply::Packets(vec![response.clone()]))
+    }
+
+    /// answers requests of `request` with a packet per payload followed by `EndResponse`
+    pub fn stream(self, request: MessageType, response: MessageType, payloads: Vec<Vec<u8>>) -> Self {
+        let mut responses: Vec<_> = payloads.iter().map(|payload| packet(response, payload)).collect();
+        responses.push(end_response());
+
+        self.on(request, move |_| Reply::Packets(responses.clone()))
+    }
+
+    /// binds a local port and serves every connection on its own thread
+    pub fn start(self) -> RunningComputor {
+        self.start_on("127.0.0.1:0")
+    }
+
+    /// like `start` but binds `addr`, e.g. another loopback address on the port of a computor suggesting it as a peer
+    pub fn start_on(self, addr: &str) -> RunningComputor {
+        let listener = TcpListener::bind(addr).unwrap();
+        let url = listener.local_addr().unwrap().to_string();
+        let requests: Requests = Arc::default();
+        let connections = Arc::new(AtomicUsize::new(0));
+        let computor = Arc::new(self);
+
+        let received = requests.clone();
+        let accepted = connections.clone();
+        std::thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                accepted.fetch_add(1, Ordering::Relaxed);
+                let computor = computor.clone();
+                let received = received.clone();
+                std::thread::spawn(move || computor.serve(stream, &received));
+            }
+        });
+
+        RunningComputor { url, requests, connections }
+    }
+
+    fn serve(&self, mut stream: TcpStream, received: &Requests) -> std::io::Result<()> {
+        if self.greeting {
+            stream.write_all(&Packet::new(self.peers, false).unwrap().to_bytes())?;
+        }
+
+        let mut header_buffer = [0; std::mem::size_of::<Header>()];
+
+        loop {
+            stream.read_exact(&mut header_buffer)?;
+
+            let header = Header::from_bytes(&header_buffer).unwrap();
+            let mut payload = vec![0; header.get_size().saturating_sub(std::mem::size_of::<Header>())];
+            stream.read_exact(&mut payload)?;
+
+            received.lock().unwrap().push((header.message_type, payload.clone()));
+
+            let Some(handler) = self.handlers.get(&header.message_type) else { continue };
+
+            match handler(&payload) {
+                Reply::Packets(packets) => stream.write_all(&answer(packets, header.dejavu))?,
+                Reply::Delayed(delay, packets) => {
+                    std::thread::sleep(delay);
+                    stream.write_all(&answer(packets, header.dejavu))?;
+                },
+                Reply::Silence => (),
+                Reply::Close(packets) => return stream.write_all(&answer(packets, header.dejavu))
+            }
+        }
+    }
+}
+
+/// a started `FakeComputor`, recording every request it reads
+pub struct RunningComputor {
+    url: String,
+    requests: Requests,
+    connections: Arc<AtomicUsize>
+}
+
+impl RunningComputor {
+    pub fn url(&self) -> &str {
+        &self.url
+    }
+
+    /// number of connections accepted so far
+    pub fn connections(&self) -> usize {
+        self.connections.load(Ordering::Relaxed)
+    }
+
+    /// number of requests of `message_type` read so far
+    pub fn count(&self, message_type: MessageType) -> usize {
+        self.requests.lock().unwrap().iter().filter(|(ty, _)| *ty == message_type).count()
+    }
+
+    /// payload of the first request of `message_type`, waits up to 5s since fire-and-forget requests race the assertion
+    pub fn received(&self, message_type: MessageType) -> Option<Vec<u8>> {
+        let deadline = Instant::now() + Duration::from_secs(5);
+
+        loop {
+            if let Some((_, payload)) = self.requests.lock().unwrap().iter().find(|(ty, _)| *ty == message_type) {
+                return Some(payload.clone());
+            }
+
+            if Instant::now() > deadline {
+                return None;
+            }
+
+            std::thread::sleep(Duration::from_millis(10));
+        }
+    }
+}
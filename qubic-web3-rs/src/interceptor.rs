@@ -0,0 +1,237 @@
+//! Hooks around every request a transport sends, e.g. for logging or metrics
+//!
+//! Interceptors are registered with `ClientBuilder::with_interceptor` and invoked by the transport: `before` in the
+//! order of registration right before the request is written, `after` in reverse order once the request settled.
+//! `redirected` is invoked whenever a busy peer turned the request away and it is sent again to a peer it suggested.
+//! A panicking interceptor is skipped, it never fails the request or the interceptors after it.
+
+use std::{collections::BTreeMap, fmt::Debug, panic::{catch_unwind, AssertUnwindSafe}, sync::{Arc, Mutex}, time::{Duration, Instant}};
+
+use qubic_tcp_types::{Header, MessageType};
+use qubic_types::traits::FromBytes;
+
+use crate::errors::{ClientError, Result};
+
+/// Request written by a transport
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RequestInfo {
+    pub message_type: MessageType,
+    /// url of the transport
+    pub peer: String,
+    /// size of the packet including its header
+    pub payload_size: usize,
+    pub started: Instant
+}
+
+impl RequestInfo {
+    /// `None` if `packet` is shorter than a header
+    pub fn new(peer: &str, packet: &[u8]) -> Option<Self> {
+        let header = Header::from_bytes(packet.get(..std::mem::size_of::<Header>())?).ok()?;
+
+        Some(Self { message_type: header.message_type, peer: peer.to_owned(), payload_size: packet.len(), started: Instant::now() })
+    }
+}
+
+/// Outcome of a request which succeeded
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ResponseInfo {
+    /// number of packets decoded, 0 for requests without a response
+    pub responses: usize,
+    pub elapsed: Duration
+}
+
+pub trait Interceptor {
+    fn before(&self, req: &RequestInfo);
+
+    fn after(&self, req: &RequestInfo, result: &Result<ResponseInfo>);
+
+    /// the busy peer of `req` suggested the peer at `to`, which the request is sent to next
+    fn redirected(&self, _req: &RequestInfo, _to: &str) {}
+}
+
+impl<I: Interceptor + ?Sized> Interceptor for Arc<I> {
+    fn before(&self, req: &RequestInfo) {
+        (**self).before(req)
+    }
+
+    fn after(&self, req: &RequestInfo, result: &Result<ResponseInfo>) {
+        (**self).after(req, result)
+    }
+
+    fn redirected(&self, req: &RequestInfo, to: &str) {
+        (**self).redirected(req, to)
+    }
+}
+
+/// Chain of interceptors a transport invokes around every send
+#[derive(Clone, Default)]
+pub struct Interceptors(Vec<Arc<dyn Interceptor + Send + Sync>>);
+
+impl Debug for Interceptors {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_tuple("Interceptors").field(&self.0.len()).finish()
+    }
+}
+
+impl Interceptors {
+    pub fn push(&mut self, interceptor: impl Interceptor + Send + Sync + 'static) {
+        self.0.push(Arc::new(interceptor));
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.len()
+    }
+
+    /// runs `send` between the interceptors, `responses` counts the packets of a successful result
+    #[cfg(not(any(feature = "async", feature = "http")))]
+    pub fn intercept<R>(&self, peer: &str, packet: &[u8], responses: impl Fn(&R) -> usize, send: impl FnOnce() -> Result<R>) -> Result<R> {
+        let Some(req) = self.before(peer, packet) else { return send() };
+
+        self.after(&req, send(), responses)
+    }
+
+    /// awaits `send` between the interceptors, `responses` counts the packets of a successful result
+    #[cfg(any(feature = "async", feature = "http"))]
+    pub async fn intercept<R>(&self, peer: &str, packet: &[u8], responses: impl Fn(&R) -> usize, send: impl std::future::Future<Output = Result<R>>) -> Result<R> {
+        let Some(req) = self.before(peer, packet) else { return send.await };
+
+        self.after(&req, send.await, responses)
+    }
+
+    /// `packet` sent to `peer` is sent again to `to`
+    pub fn redirected(&self, peer: &str, packet: &[u8], to: &str) {
+        if self.0.is_empty() {
+            return
+        }
+
+        let Some(req) = RequestInfo::new(peer, packet) else { return };
+
+        for interceptor in &self.0 {
+            isolate("redirected", || interceptor.redirected(&req, to));
+        }
+    }
+
+    fn before(&self, peer: &str, packet: &[u8]) -> Option<RequestInfo> {
+        if self.0.is_empty() {
+            return None
+        }
+
+        let req = RequestInfo::new(peer, packet)?;
+
+        for interceptor in &self.0 {
+            isolate("before", || interceptor.before(&req));
+        }
+
+        Some(req)
+    }
+
+    fn after<R>(&self, req: &RequestInfo, res: Result<R>, responses: impl Fn(&R) -> usize) -> Result<R> {
+        // the error is moved into the result handed to the interceptors and moved back out afterwards
+        let (value, result) = match res {
+            Ok(value) => {
+                let info = ResponseInfo { responses: responses(&value), elapsed: req.started.elapsed() };
+                (Some(value), Ok(info))
+            },
+            Err(e) => (None, Err(e))
+        };
+
+        for interceptor in self.0.iter().rev() {
+            isolate("after", || interceptor.after(req, &result));
+        }
+
+        match (value, result) {
+            (Some(value), _) => Ok(value),
+            (None, Err(e)) => Err(e),
+            (None, Ok(_)) => unreachable!("a response info is only built for a value")
+        }
+    }
+}
+
+fn isolate(hook: &str, f: impl FnOnce()) {
+    if catch_unwind(AssertUnwindSafe(f)).is_err() {
+        log::warn!("Interceptor panicked in {hook}, the request is not affected");
+    }
+}
+
+/// Logs every request at debug and every failed request at warn level
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LoggingInterceptor;
+
+impl Interceptor for LoggingInterceptor {
+    fn before(&self, req: &RequestInfo) {
+        log::debug!("{} ({} bytes) to {}", req.message_type, req.payload_size, req.peer);
+    }
+
+    fn after(&self, req: &RequestInfo, result: &Result<ResponseInfo>) {
+        match result {
+            Ok(info) => log::debug!("{} to {} answered with {} packets in {:?}", req.message_type, req.peer, info.responses, info.elapsed),
+            Err(e) => log::warn!("{} to {} failed after {:?}: {e}", req.message_type, req.peer, req.started.elapsed())
+        }
+    }
+
+    fn redirected(&self, req: &RequestInfo, to: &str) {
+        log::info!("{} to busy peer {} is redirected to {to}", req.message_type, req.peer);
+    }
+}
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RequestMetrics {
+    pub requests: u64,
+    pub failures: u64,
+    pub timeouts: u64,
+    pub bytes_sent: u64,
+    /// times a busy peer redirected the request to another peer
+    pub redirects: u64,
+    /// summed over every settled request, including failed ones
+    pub total_latency: Duration
+}
+
+/// Counts requests per message type, register it as `Arc` to read the metrics while the client is in use
+#[derive(Debug, Default)]
+pub struct MetricsInterceptor {
+    metrics: Mutex<BTreeMap<MessageType, RequestMetrics>>
+}
+
+impl MetricsInterceptor {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn metrics(&self) -> BTreeMap<MessageType, RequestMetrics> {
+        self.metrics.lock().unwrap().clone()
+    }
+
+    pub fn get(&self, message_type: MessageType) -> RequestMetrics {
+        self.metrics.lock().unwrap().get(&message_type).copied().unwrap_or_default()
+    }
+}
+
+impl Interceptor for MetricsInterceptor {
+    fn before(&self, _req: &RequestInfo) {}
+
+    fn after(&self, req: &RequestInfo, result: &Result<ResponseInfo>) {
+        let mut metrics = self.metrics.lock().unwrap();
+        let metrics = metrics.entry(req.message_type).or_default();
+
+        metrics.requests += 1;
+        metrics.bytes_sent += req.payload_size as u64;
+        metrics.total_latency += req.started.elapsed();
+
+        match result {
+            Ok(_) => (),
+            Err(ClientError::Timeout) => {
+                metrics.failures += 1;
+                metrics.timeouts += 1;
+            },
+            Err(_) => metrics.failures += 1
+        }
+    }
+
+    fn redirected(&self, req: &RequestInfo, _to: &str) {
+        self.metrics.lock().unwrap().entry(req.message_type).or_default().redirects += 1;
+    }
+}
@@ -5,9 +5,22 @@
 
 pub mod transport;
 pub mod client;
+pub mod errors;
+pub mod event_log;
+pub mod computor_monitor;
+pub mod epoch_guard;
+pub mod cache;
+pub mod interceptor;
+pub mod proxy;
+pub mod wire_dump;
+pub mod subscription;
+#[cfg(any(feature = "async", feature = "http"))]
+pub mod runtime;
 
 pub extern crate qubic_tcp_types;
 pub extern crate qubic_types;
 
+#[cfg(any(test, feature = "fake-computor"))]
+pub mod fake_computor;
 #[cfg(test)]
 mod tests;
\ No newline at end of file
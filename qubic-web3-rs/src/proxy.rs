@@ -0,0 +1,322 @@
+//! SOCKS5 and HTTP CONNECT proxies for the connections to computors
+//!
+//! The handshake runs on a fresh connection to the proxy, afterwards the stream is tunneled to the computor and used
+//! like a direct connection. Host names of computors are resolved by the proxy.
+
+use std::{fmt::Debug, io::{Error, ErrorKind}, net::{Ipv4Addr, Ipv6Addr}, str::FromStr};
+
+use base64::{engine::general_purpose::STANDARD, Engine};
+
+use crate::errors::ClientError;
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+use std::io::{Read, Write};
+
+#[cfg(any(feature = "async", feature = "http"))]
+use futures::io::{AsyncRead, AsyncReadExt, AsyncWrite, AsyncWriteExt};
+
+/// Credentials of a proxy, the password is not printed by `Debug`
+#[derive(Clone, PartialEq, Eq, Hash)]
+pub struct ProxyAuth {
+    pub username: String,
+    pub password: String
+}
+
+impl ProxyAuth {
+    pub fn new(username: impl ToString, password: impl ToString) -> Self {
+        Self { username: username.to_string(), password: password.to_string() }
+    }
+}
+
+impl Debug for ProxyAuth {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("ProxyAuth").field("username", &self.username).field("password", &"***").finish()
+    }
+}
+
+/// Proxy outbound connections are tunneled through, see `ClientBuilder::with_proxy`
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum ProxyConfig {
+    Socks5 { addr: String, auth: Option<ProxyAuth> },
+    HttpConnect { addr: String, auth: Option<ProxyAuth> }
+}
+
+impl ProxyConfig {
+    /// address of the proxy itself
+    pub fn addr(&self) -> &str {
+        match self {
+            Self::Socks5 { addr, .. } | Self::HttpConnect { addr, .. } => addr
+        }
+    }
+
+    pub fn auth(&self) -> Option<&ProxyAuth> {
+        match self {
+            Self::Socks5 { auth, .. } | Self::HttpConnect { auth, .. } => auth.as_ref()
+        }
+    }
+}
+
+/// parses `socks5://[user:password@]host:port` and `http://[user:password@]host:port`
+impl FromStr for ProxyConfig {
+    type Err = ClientError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let invalid = || ClientError::InvalidInput(format!("{s:?} is not a socks5:// or http:// proxy URL"));
+
+        let (scheme, rest) = s.split_once("://").ok_or_else(invalid)?;
+        let rest = rest.trim_end_matches('/');
+
+        let (auth, addr) = match rest.rsplit_once('@') {
+            Some((auth, addr)) => {
+                let (username, password) = auth.split_once(':').ok_or_else(invalid)?;
+                (Some(ProxyAuth::new(username, password)), addr)
+            },
+            None => (None, rest)
+        };
+
+        if addr.rsplit_once(':').is_none_or(|(host, port)| host.is_empty() || port.parse::<u16>().is_err()) {
+            return Err(invalid())
+        }
+
+        let addr = addr.to_owned();
+
+        match scheme {
+            "socks5" | "socks5h" => Ok(Self::Socks5 { addr, auth }),
+            "http" => Ok(Self::HttpConnect { addr, auth }),
+            _ => Err(invalid())
+        }
+    }
+}
+
+fn proxy_error(message: String) -> Error {
+    Error::other(message)
+}
+
+/// splits `host:port` of the computor, IPv6 hosts are given in brackets
+fn target(url: &str) -> std::io::Result<(&str, u16)> {
+    let invalid = || Error::new(ErrorKind::InvalidInput, format!("{url:?} is not a host:port address"));
+
+    let (host, port) = url.rsplit_once(':').ok_or_else(invalid)?;
+    let port = port.parse().map_err(|_| invalid())?;
+
+    Ok((host.trim_start_matches('[').trim_end_matches(']'), port))
+}
+
+fn socks5_greeting(auth: Option<&ProxyAuth>) -> [u8; 3] {
+    [5, 1, if auth.is_some() { 2 } else { 0 }]
+}
+
+/// returns whether the proxy asks for the username and password
+fn socks5_method(reply: [u8; 2]) -> std::io::Result<bool> {
+    match reply {
+        [5, 0] => Ok(false),
+        [5, 2] => Ok(true),
+        [5, 0xFF] => Err(proxy_error("SOCKS5 proxy accepts none of the offered authentication methods".to_owned())),
+        _ => Err(proxy_error(format!("SOCKS5 proxy answered the greeting with {reply:?}")))
+    }
+}
+
+fn socks5_auth_request(auth: Option<&ProxyAuth>) -> std::io::Result<Vec<u8>> {
+    let auth = auth.ok_or_else(|| proxy_error("SOCKS5 proxy requires a username and password".to_owned()))?;
+    let (username, password) = (auth.username.as_bytes(), auth.password.as_bytes());
+
+    if username.len() > 255 || password.len() > 255 {
+        return Err(Error::new(ErrorKind::InvalidInput, "SOCKS5 username and password are limited to 255 bytes"))
+    }
+
+    Ok([&[1, username.len() as u8], username, &[password.len() as u8], password].concat())
+}
+
+fn socks5_auth_reply(reply: [u8; 2]) -> std::io::Result<()> {
+    match reply {
+        [1, 0] => Ok(()),
+        _ => Err(proxy_error("SOCKS5 proxy rejected the username and password".to_owned()))
+    }
+}
+
+fn socks5_connect_request(url: &str) -> std::io::Result<Vec<u8>> {
+    let (host, port) = target(url)?;
+    let mut request = vec![5, 1, 0];
+
+    if let Ok(ip) = Ipv4Addr::from_str(host) {
+        request.push(1);
+        request.extend(ip.octets());
+    } else if let Ok(ip) = Ipv6Addr::from_str(host) {
+        request.push(4);
+        request.extend(ip.octets());
+    } else {
+        let len = u8::try_from(host.len()).map_err(|_| Error::new(ErrorKind::InvalidInput, format!("host of {url:?} is too long for SOCKS5")))?;
+
+        request.extend([3, len]);
+        request.extend(host.as_bytes());
+    }
+
+    request.extend(port.to_be_bytes());
+
+    Ok(request)
+}
+
+/// checks the head of the connect reply, returns the length of the bound address and port following it.
+/// Domain addresses are prefixed with their length, which is read separately
+fn socks5_connect_reply(head: [u8; 4]) -> std::io::Result<Option<usize>> {
+    let reason = match head[1] {
+        0 => None,
+        1 => Some("general failure"),
+        2 => Some("connection not allowed by ruleset"),
+        3 => Some("network unreachable"),
+        4 => Some("host unreachable"),
+        5 => Some("connection refused"),
+        6 => Some("TTL expired"),
+        7 => Some("command not supported"),
+        8 => Some("address type not supported"),
+        _ => Some("unknown error")
+    };
+
+    if head[0] != 5 {
+        return Err(proxy_error(format!("SOCKS5 proxy answered the connect request with {head:?}")))
+    }
+
+    if let Some(reason) = reason {
+        return Err(proxy_error(format!("SOCKS5 proxy failed to connect: {reason}")))
+    }
+
+    match head[3] {
+        1 => Ok(Some(4 + 2)),
+        4 => Ok(Some(16 + 2)),
+        3 => Ok(None),
+        atyp => Err(proxy_error(format!("SOCKS5 proxy bound an address of unknown type {atyp}")))
+    }
+}
+
+fn http_connect_request(url: &str, auth: Option<&ProxyAuth>) -> Vec<u8> {
+    let mut request = format!("CONNECT {url} HTTP/1.1\r\nHost: {url}\r\n");
+
+    if let Some(auth) = auth {
+        request.push_str(&format!("Proxy-Authorization: Basic {}\r\n", STANDARD.encode(format!("{}:{}", auth.username, auth.password))));
+    }
+
+    request.push_str("\r\n");
+    request.into_bytes()
+}
+
+/// Longest response head of an HTTP proxy that is read
+const MAX_HTTP_RESPONSE_HEAD: usize = 8192;
+
+fn http_connect_response(head: &[u8]) -> std::io::Result<()> {
+    let head = String::from_utf8_lossy(head);
+    let status_line = head.lines().next().unwrap_or_default();
+
+    match status_line.split_whitespace().nth(1) {
+        Some(status) if status.starts_with('2') => Ok(()),
+        _ => Err(proxy_error(format!("HTTP proxy refused to connect: {status_line}")))
+    }
+}
+
+/// tunnels `stream`, a fresh connection to the proxy, to `url`
+#[cfg(not(any(feature = "async", feature = "http")))]
+pub(crate) fn handshake<S: Read + Write>(stream: &mut S, proxy: &ProxyConfig, url: &str) -> std::io::Result<()> {
+    match proxy {
+        ProxyConfig::Socks5 { auth, .. } => {
+            let mut reply = [0; 2];
+
+            stream.write_all(&socks5_greeting(auth.as_ref()))?;
+            stream.read_exact(&mut reply)?;
+
+            if socks5_method(reply)? {
+                stream.write_all(&socks5_auth_request(auth.as_ref())?)?;
+                stream.read_exact(&mut reply)?;
+                socks5_auth_reply(reply)?;
+            }
+
+            let mut head = [0; 4];
+
+            stream.write_all(&socks5_connect_request(url)?)?;
+            stream.read_exact(&mut head)?;
+
+            let bound = match socks5_connect_reply(head)? {
+                Some(bound) => bound,
+                None => {
+                    let mut len = [0; 1];
+                    stream.read_exact(&mut len)?;
+
+                    len[0] as usize + 2
+                }
+            };
+
+            stream.read_exact(&mut vec![0; bound])
+        },
+        ProxyConfig::HttpConnect { auth, .. } => {
+            stream.write_all(&http_connect_request(url, auth.as_ref()))?;
+
+            // read byte by byte, the computor greets right after the head
+            let mut head = Vec::new();
+            let mut byte = [0; 1];
+
+            while !head.ends_with(b"\r\n\r\n") {
+                if head.len() == MAX_HTTP_RESPONSE_HEAD {
+                    return Err(proxy_error("HTTP proxy response head is too long".to_owned()))
+                }
+
+                stream.read_exact(&mut byte)?;
+                head.push(byte[0]);
+            }
+
+            http_connect_response(&head)
+        }
+    }
+}
+
+/// tunnels `stream`, a fresh connection to the proxy, to `url`
+#[cfg(any(feature = "async", feature = "http"))]
+pub(crate) async fn handshake<S: AsyncRead + AsyncWrite + Unpin>(stream: &mut S, proxy: &ProxyConfig, url: &str) -> std::io::Result<()> {
+    match proxy {
+        ProxyConfig::Socks5 { auth, .. } => {
+            let mut reply = [0; 2];
+
+            stream.write_all(&socks5_greeting(auth.as_ref())).await?;
+            stream.read_exact(&mut reply).await?;
+
+            if socks5_method(reply)? {
+                stream.write_all(&socks5_auth_request(auth.as_ref())?).await?;
+                stream.read_exact(&mut reply).await?;
+                socks5_auth_reply(reply)?;
+            }
+
+            let mut head = [0; 4];
+
+            stream.write_all(&socks5_connect_request(url)?).await?;
+            stream.read_exact(&mut head).await?;
+
+            let bound = match socks5_connect_reply(head)? {
+                Some(bound) => bound,
+                None => {
+                    let mut len = [0; 1];
+                    stream.read_exact(&mut len).await?;
+                    len[0] as usize + 2
+                }
+            };
+
+            stream.read_exact(&mut vec![0; bound]).await?;
+
+            Ok(())
+        },
+        ProxyConfig::HttpConnect { auth, .. } => {
+            stream.write_all(&http_connect_request(url, auth.as_ref())).await?;
+
+            // read byte by byte, the computor greets right after the head
+            let mut head = Vec::new();
+            let mut byte = [0; 1];
+
+            while !head.ends_with(b"\r\n\r\n") {
+                if head.len() == MAX_HTTP_RESPONSE_HEAD {
+                    return Err(proxy_error("HTTP proxy response head is too long".to_owned()))
+                }
+
+                stream.read_exact(&mut byte).await?;
+                head.push(byte[0]);
+            }
+
+            http_connect_response(&head)
+        }
+    }
+}
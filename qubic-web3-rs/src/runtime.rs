@@ -0,0 +1,110 @@
+//! Runtime the async client runs on, tokio with the `rt-tokio` feature (default) or async-std with `rt-async-std`
+//!
+//! The protocol code of the async client (framing, headers, pairing requests with responses) reads and writes
+//! `futures::io` streams. This module is the only one touching the runtime: it connects TCP streams, times out
+//! futures, sleeps and spawns the subscriptions and heartbeats. Tokio is used if both features are enabled, a tokio
+//! client has to be used from within a tokio runtime.
+
+use std::{future::Future, io, net::SocketAddr, pin::Pin, task::{Context, Poll}, time::Duration};
+
+use futures::{io::{AsyncRead, AsyncWrite}, FutureExt};
+
+#[cfg(not(any(feature = "rt-tokio", feature = "rt-async-std")))]
+compile_error!("the async client needs a runtime, enable the `rt-tokio` or the `rt-async-std` feature");
+
+#[cfg(feature = "rt-tokio")]
+type Inner = tokio::net::TcpStream;
+#[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+type Inner = async_std::net::TcpStream;
+
+/// TCP connection of the async client on the runtime of the enabled feature
+#[derive(Debug)]
+pub struct TcpStream {
+    inner: Inner
+}
+
+impl TcpStream {
+    pub fn connect(addr: &str) -> impl Future<Output = io::Result<Self>> + '_ {
+        Inner::connect(addr).map(|inner| inner.map(|inner| Self { inner }))
+    }
+
+    pub fn peer_addr(&self) -> io::Result<SocketAddr> {
+        self.inner.peer_addr()
+    }
+}
+
+#[cfg(feature = "rt-tokio")]
+impl AsyncRead for TcpStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        let mut buf = tokio::io::ReadBuf::new(buf);
+
+        match tokio::io::AsyncRead::poll_read(Pin::new(&mut self.inner), cx, &mut buf) {
+            Poll::Ready(res) => Poll::Ready(res.map(|_| buf.filled().len())),
+            Poll::Pending => Poll::Pending
+        }
+    }
+}
+
+#[cfg(feature = "rt-tokio")]
+impl AsyncWrite for TcpStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        tokio::io::AsyncWrite::poll_write(Pin::new(&mut self.inner), cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tokio::io::AsyncWrite::poll_flush(Pin::new(&mut self.inner), cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        tokio::io::AsyncWrite::poll_shutdown(Pin::new(&mut self.inner), cx)
+    }
+}
+
+#[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+impl AsyncRead for TcpStream {
+    fn poll_read(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &mut [u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_read(cx, buf)
+    }
+}
+
+#[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+impl AsyncWrite for TcpStream {
+    fn poll_write(mut self: Pin<&mut Self>, cx: &mut Context<'_>, buf: &[u8]) -> Poll<io::Result<usize>> {
+        Pin::new(&mut self.inner).poll_write(cx, buf)
+    }
+
+    fn poll_flush(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_flush(cx)
+    }
+
+    fn poll_close(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<io::Result<()>> {
+        Pin::new(&mut self.inner).poll_close(cx)
+    }
+}
+
+/// output of `future`, `None` if it did not complete within `duration`. Not an `async fn`, the client nests timeouts in
+/// large futures already
+pub(crate) fn timeout<F: Future>(duration: Duration, future: F) -> impl Future<Output = Option<F::Output>> {
+    #[cfg(feature = "rt-tokio")]
+    return tokio::time::timeout(duration, future).map(Result::ok);
+
+    #[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+    return async_std::future::timeout(duration, future).map(Result::ok);
+}
+
+pub(crate) async fn sleep(duration: Duration) {
+    #[cfg(feature = "rt-tokio")]
+    tokio::time::sleep(duration).await;
+
+    #[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+    async_std::task::sleep(duration).await;
+}
+
+/// runs `future` in the background, its output is dropped
+pub(crate) fn spawn<F: Future + Send + 'static>(future: F) where F::Output: Send + 'static {
+    #[cfg(feature = "rt-tokio")]
+    drop(tokio::spawn(future));
+
+    #[cfg(all(feature = "rt-async-std", not(feature = "rt-tokio")))]
+    drop(async_std::task::spawn(future));
+}
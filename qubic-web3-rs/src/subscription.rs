@@ -0,0 +1,354 @@
+//! Subscriptions buffering the network events for a channel instead of handing them to a handler, see
+//! `Qu::subscribe_channel`
+//!
+//! The events are buffered until the consumer receives them, at most `SubscriptionConfig::capacity` of them. The
+//! `OverflowPolicy` decides what happens with the events of a consumer falling behind: `Block` stops reading from the
+//! peer until there is room again (the peer drops connections which are not read for too long), `DropOldest` and
+//! `DropNewest` keep reading and drop buffered or received events, counted by `SubscriptionHandle::dropped`.
+//!
+//! Like `Qu::subscribe` the subscription reconnects whenever the connection drops. The events the peer sent in between
+//! are missed, a `NetworkEvent::Disconnected` marks the gap. It is buffered like the events and may be dropped as well.
+//! The subscription ends with `SubscriptionHandle::cancel`, once the receiving end is dropped or if the peer cannot be
+//! reconnected. The receiving end ends after the buffered events then. The reading end notices a cancelled
+//! subscription or a dropped receiving end with the next message of the peer, `SubscriptionHandle::is_finished` tells
+//! when it is done.
+
+use std::sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc};
+#[cfg(not(any(feature = "async", feature = "http")))]
+use std::ops::Deref;
+
+use qubic_tcp_types::{events::EventEnvelope, types::ExchangePublicPeers};
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+use std::time::Duration;
+#[cfg(not(any(feature = "async", feature = "http")))]
+use crossbeam_channel::{Receiver, SendTimeoutError, Sender, TrySendError};
+
+#[cfg(any(feature = "async", feature = "http"))]
+use std::{collections::VecDeque, sync::Mutex, task::Poll};
+#[cfg(any(feature = "async", feature = "http"))]
+use futures::{task::AtomicWaker, Stream};
+
+/// Interval a blocked sync subscription checks whether it was cancelled in
+#[cfg(not(any(feature = "async", feature = "http")))]
+const CANCEL_POLL: Duration = Duration::from_millis(100);
+
+/// What happens with a received event while the buffer is full
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum OverflowPolicy {
+    /// the oldest buffered event is dropped for it
+    DropOldest,
+    /// the received event is dropped
+    DropNewest,
+    /// the peer is not read until the consumer made room
+    #[default]
+    Block
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SubscriptionConfig {
+    pub public_peers: ExchangePublicPeers,
+    /// events buffered for the consumer (default 1024), at least one
+    pub capacity: usize,
+    pub overflow: OverflowPolicy
+}
+
+impl Default for SubscriptionConfig {
+    fn default() -> Self {
+        Self { public_peers: ExchangePublicPeers::default(), capacity: 1024, overflow: OverflowPolicy::default() }
+    }
+}
+
+impl SubscriptionConfig {
+    pub fn with_public_peers(mut self, public_peers: ExchangePublicPeers) -> Self {
+        self.public_peers = public_peers;
+        self
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity;
+        self
+    }
+
+    pub fn with_overflow(mut self, overflow: OverflowPolicy) -> Self {
+        self.overflow = overflow;
+        self
+    }
+}
+
+/// Wakes the one task waiting for it, a notification while no task waits is kept for the next wait. Either end of a
+/// subscription waits on its own `Notify`, the runtime of the client does not matter
+#[cfg(any(feature = "async", feature = "http"))]
+#[derive(Default)]
+struct Notify {
+    notified: AtomicBool,
+    waker: AtomicWaker
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Notify {
+    fn notify_one(&self) {
+        self.notified.store(true, Ordering::Release);
+        self.waker.wake();
+    }
+
+    async fn notified(&self) {
+        futures::future::poll_fn(|cx| {
+            // registered before checking again, a notification in between wakes the task
+            if !self.notified.swap(false, Ordering::AcqRel) {
+                self.waker.register(cx.waker());
+
+                if !self.notified.swap(false, Ordering::AcqRel) {
+                    return Poll::Pending
+                }
+            }
+
+            Poll::Ready(())
+        }).await
+    }
+}
+
+/// State shared by the handle, the reading end and, with the async client, the buffer
+#[derive(Default)]
+struct Shared {
+    cancelled: AtomicBool,
+    dropped: AtomicU64,
+    #[cfg(any(feature = "async", feature = "http"))]
+    events: Mutex<VecDeque<EventEnvelope>>,
+    /// an event was buffered or the reading end is done
+    #[cfg(any(feature = "async", feature = "http"))]
+    readable: Notify,
+    /// an event was received, the subscription was cancelled or the stream dropped
+    #[cfg(any(feature = "async", feature = "http"))]
+    writable: Notify,
+    sender_done: AtomicBool,
+    #[cfg(any(feature = "async", feature = "http"))]
+    receiver_done: AtomicBool
+}
+
+/// Controls a subscription of `Qu::subscribe_channel`
+#[derive(Clone)]
+pub struct SubscriptionHandle {
+    shared: Arc<Shared>
+}
+
+impl SubscriptionHandle {
+    /// ends the subscription before the next event, the buffered events are still received
+    pub fn cancel(&self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+
+        #[cfg(any(feature = "async", feature = "http"))]
+        self.shared.writable.notify_one();
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// events dropped by the overflow policy so far
+    pub fn dropped(&self) -> u64 {
+        self.shared.dropped.load(Ordering::Relaxed)
+    }
+
+    /// whether the reading end is done, no further events are buffered
+    pub fn is_finished(&self) -> bool {
+        self.shared.sender_done.load(Ordering::Acquire)
+    }
+}
+
+/// Buffers the events of the reader for the receiver
+#[cfg(not(any(feature = "async", feature = "http")))]
+pub(crate) struct EventSender {
+    sender: Sender<EventEnvelope>,
+    /// receiver dropping the oldest events for `DropOldest`
+    oldest: Option<Receiver<EventEnvelope>>,
+    overflow: OverflowPolicy,
+    shared: Arc<Shared>
+}
+
+/// Receiving end of a sync subscription, dereferences to the channel receiver. Dropping it ends the subscription,
+/// clones of the channel receiver do not keep it alive
+#[cfg(not(any(feature = "async", feature = "http")))]
+pub struct SubscriptionReceiver {
+    receiver: Receiver<EventEnvelope>,
+    shared: Arc<Shared>
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl Deref for SubscriptionReceiver {
+    type Target = Receiver<EventEnvelope>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.receiver
+    }
+}
+
+/// the sender of a `DropOldest` subscription keeps a receiver of its own, the channel is not disconnected by this drop
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl Drop for SubscriptionReceiver {
+    fn drop(&mut self) {
+        self.shared.cancelled.store(true, Ordering::Relaxed);
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+pub(crate) fn channel(config: &SubscriptionConfig) -> (SubscriptionHandle, EventSender, SubscriptionReceiver) {
+    let (sender, receiver) = crossbeam_channel::bounded(config.capacity.max(1));
+    let shared = Arc::new(Shared::default());
+    let oldest = (config.overflow == OverflowPolicy::DropOldest).then(|| receiver.clone());
+
+    (
+        SubscriptionHandle { shared: shared.clone() },
+        EventSender { sender, oldest, overflow: config.overflow, shared: shared.clone() },
+        SubscriptionReceiver { receiver, shared }
+    )
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl EventSender {
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed)
+    }
+
+    /// buffers the event according to the overflow policy, false once the subscription ended
+    pub(crate) fn send(&self, mut event: EventEnvelope) -> bool {
+        loop {
+            if self.is_closed() {
+                return false
+            }
+
+            let full = match self.overflow {
+                OverflowPolicy::Block => match self.sender.send_timeout(event, CANCEL_POLL) {
+                    Ok(()) => return true,
+                    Err(SendTimeoutError::Timeout(rejected)) => {
+                        event = rejected;
+                        continue
+                    },
+                    Err(SendTimeoutError::Disconnected(_)) => return false
+                },
+                _ => match self.sender.try_send(event) {
+                    Ok(()) => return true,
+                    Err(TrySendError::Full(rejected)) => rejected,
+                    Err(TrySendError::Disconnected(_)) => return false
+                }
+            };
+
+            match &self.oldest {
+                // the consumer may have made room in the meantime, nothing is dropped then
+                Some(oldest) => if oldest.try_recv().is_ok() {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                },
+                None => {
+                    self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+                    return true
+                }
+            }
+
+            event = full;
+        }
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        self.shared.sender_done.store(true, Ordering::Release);
+    }
+}
+
+/// Buffers the events of the reader for the stream, the stream ends once it is dropped
+#[cfg(any(feature = "async", feature = "http"))]
+pub(crate) struct EventSender {
+    capacity: usize,
+    overflow: OverflowPolicy,
+    shared: Arc<Shared>
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+struct EventReceiver {
+    shared: Arc<Shared>
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+pub(crate) fn channel(config: &SubscriptionConfig) -> (SubscriptionHandle, EventSender, impl Stream<Item = EventEnvelope> + Send + 'static) {
+    let shared = Arc::new(Shared::default());
+    let receiver = EventReceiver { shared: shared.clone() };
+    let stream = futures::stream::unfold(receiver, |receiver| async move {
+        receiver.recv().await.map(|event| (event, receiver))
+    });
+
+    (SubscriptionHandle { shared: shared.clone() }, EventSender { capacity: config.capacity.max(1), overflow: config.overflow, shared }, stream)
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl EventSender {
+    pub(crate) fn is_closed(&self) -> bool {
+        self.shared.cancelled.load(Ordering::Relaxed) || self.shared.receiver_done.load(Ordering::Relaxed)
+    }
+
+    /// buffers the event according to the overflow policy, false once the subscription ended
+    pub(crate) async fn send(&self, event: EventEnvelope) -> bool {
+        loop {
+            if self.is_closed() {
+                return false
+            }
+
+            {
+                let mut events = self.shared.events.lock().unwrap();
+
+                if events.len() < self.capacity || self.overflow != OverflowPolicy::Block {
+                    if events.len() >= self.capacity {
+                        self.shared.dropped.fetch_add(1, Ordering::Relaxed);
+
+                        match self.overflow {
+                            OverflowPolicy::DropNewest => return true,
+                            _ => events.pop_front()
+                        };
+                    }
+
+                    events.push_back(event);
+                    self.shared.readable.notify_one();
+
+                    return true
+                }
+            }
+
+            self.shared.writable.notified().await;
+        }
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Drop for EventSender {
+    fn drop(&mut self) {
+        self.shared.sender_done.store(true, Ordering::Release);
+        self.shared.readable.notify_one();
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl EventReceiver {
+    async fn recv(&self) -> Option<EventEnvelope> {
+        loop {
+            if let Some(event) = self.shared.events.lock().unwrap().pop_front() {
+                self.shared.writable.notify_one();
+                return Some(event)
+            }
+
+            // events buffered before the sender was done are still received
+            if self.shared.sender_done.load(Ordering::Acquire) {
+                return self.shared.events.lock().unwrap().pop_front()
+            }
+
+            self.shared.readable.notified().await;
+        }
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Drop for EventReceiver {
+    fn drop(&mut self) {
+        self.shared.receiver_done.store(true, Ordering::Relaxed);
+        self.shared.writable.notify_one();
+    }
+}
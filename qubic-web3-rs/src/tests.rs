@@ -1,313 +1,2909 @@
 use std::str::FromStr;
 
-use qubic_tcp_types::{prelude::TransactionFlags, types::{ExchangePublicPeers, ticks::TickData}, events::NetworkEvent};
-use qubic_types::{QubicId, QubicTxHash};
+use qubic_tcp_types::{consts::NUMBER_OF_COMPUTORS, prelude::TransactionFlags, types::{ExchangePublicPeers, ticks::{CurrentTickInfo, TickData}, transactions::TransactionWithData}, events::NetworkEvent, MessageType};
+use qubic_types::{QubicId, QubicTxHash, QubicWallet};
 use crate::qubic_types::traits::VerifySignature;
 
-use crate::{*, transport::Tcp, client::Client};
+use crate::{*, transport::Tcp, client::Client, fake_computor::{end_response, FakeComputor, Reply, RunningComputor, packet}};
 
-const COMPUTOR: &str = "146.0.74.233:21841"; // check https://app.qubic.li/network/live for current peers
-const _TESTNET: &str = "57.129.19.155:31841";
+const SEED: &str = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
+
+/// transaction of `amount` to `to` signed by the wallet of `SEED`
+fn signed_transaction(to: QubicId, amount: u64, tick: u32) -> TransactionWithData {
+    use qubic_tcp_types::types::transactions::RawTransaction;
+    use qubic_types::traits::Sign;
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let mut tx: TransactionWithData = RawTransaction { from: wallet.public_key, to, amount, tick, ..Default::default() }.into();
+    tx.sign(&wallet).unwrap();
+
+    tx
+}
+
+fn tick_data(tick: u32, digests: &[QubicTxHash]) -> TickData {
+    use qubic_types::traits::FromBytes;
+
+    let mut data = TickData::from_bytes(&vec![0; std::mem::size_of::<TickData>()]).unwrap();
+    data.epoch = 100;
+    data.tick = tick;
+    data.transaction_digest[..digests.len()].copy_from_slice(digests);
+
+    data
+}
+
+fn mining_ranking() -> Vec<qubic_tcp_types::types::special_commands::MiningScoreEntry> {
+    use qubic_tcp_types::types::special_commands::MiningScoreEntry;
+
+    vec![MiningScoreEntry { miner: QubicId([1; 32]), score: 120 }, MiningScoreEntry { miner: QubicId([2; 32]), score: 80 }]
+}
+
+/// accepted command echoed with the number of rankings
+fn mining_ranking_response() -> Vec<u8> {
+    use qubic_tcp_types::types::special_commands::{CommandDescriptor, CommandType};
+    use qubic_types::traits::ToBytes;
+
+    let ranking = mining_ranking();
+
+    [
+        CommandDescriptor::with_nonce(CommandType::SpecialCommandGetMiningScoreRanking, 0).to_bytes(),
+        (ranking.len() as u32).to_le_bytes().to_vec(),
+        ranking.iter().flat_map(|entry| entry.to_bytes()).collect()
+    ].concat()
+}
+
+/// computor repeating and reordering the transactions of tick 12_000_000, its tick data lists them as `txs`
+fn shuffling_computor() -> (Vec<TransactionWithData>, RunningComputor) {
+    use qubic_types::traits::ToBytes;
+
+    let txs = (1..=3).map(|amount| signed_transaction(QubicId([1; 32]), amount, 12_000_000)).collect::<Vec<_>>();
+    let digests: Vec<QubicTxHash> = txs.iter().cloned().map(Into::into).collect();
+    let stream = [2, 0, 2, 1, 0].map(|i| txs[i].to_bytes()).to_vec();
+
+    let computor = FakeComputor::new()
+        .stream(MessageType::RequestTickTransactions, MessageType::BroadcastTransaction, stream)
+        .respond(MessageType::RequestTickData, MessageType::BroadcastFutureTickData, tick_data(12_000_000, &digests).to_bytes())
+        .start();
+
+    (txs, computor)
+}
+
+/// computor at tick 12_000_000 answering every request of the client with canned data,
+/// the transactions are the ones of every requested tick
+fn fake_network() -> (CurrentTickInfo, Vec<TransactionWithData>, RunningComputor) {
+    use qubic_tcp_types::types::{Entity, RequestEntity, RespondedEntity, RequestContractIpo, ContractIpo, SystemInfo};
+    use qubic_types::traits::{FromBytes, ToBytes};
+
+    let (info, _) = current_tick_response();
+    let txs = vec![
+        signed_transaction(QubicId([1; 32]), 10, info.tick - 5),
+        signed_transaction(QubicId([2; 32]), 20, info.tick - 5),
+        signed_transaction(QubicId([1; 32]), 30, info.tick - 5)
+    ];
+    let digests: Vec<QubicTxHash> = txs.iter().cloned().map(Into::into).collect();
+    let mut system_info = SystemInfo::from_bytes(&vec![0; std::mem::size_of::<SystemInfo>()]).unwrap();
+    system_info.current_entity_balance_dust_threshold = 100;
+
+    let computor = FakeComputor::new()
+        .respond(MessageType::RequestCurrentTickInfo, MessageType::RespondCurrentTickInfo, info.to_bytes())
+        .respond(MessageType::RequestSystemInfo, MessageType::RespondSystemInfo, system_info.to_bytes())
+        .on(MessageType::RequestEntity, |payload| {
+            let public_key = RequestEntity::from_bytes(payload).unwrap().public_key;
+            let entity = Entity {
+                public_key,
+                incoming_amount: 1_500,
+                outgoing_amount: 500,
+                number_of_incoming_transfers: 3,
+                number_of_outgoing_transfers: 1,
+                latest_incoming_transfer_tick: 11_999_990,
+                latest_outgoing_transfer_tick: 11_999_000
+            };
+
+            Reply::Packets(vec![packet(MessageType::RespondEntity, &RespondedEntity { entity, tick: 12_000_000, spectrum_index: 42, siblings: Default::default() }.to_bytes())])
+        })
+        .stream(MessageType::RequestTickTransactions, MessageType::BroadcastTransaction, txs.iter().map(|tx| tx.to_bytes()).collect())
+        .on(MessageType::RequestTickData, move |payload| {
+            let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+
+            Reply::Packets(vec![packet(MessageType::BroadcastFutureTickData, &tick_data(tick, &digests).to_bytes())])
+        })
+        .respond(MessageType::RequestQuorumTick, MessageType::BroadcastTick, broadcast_tick().to_bytes())
+        .respond(MessageType::ProcessSpecialCommand, MessageType::ProcessSpecialCommand, mining_ranking_response())
+        .on(MessageType::RequestContractIPO, |payload| {
+            let mut ipo = ContractIpo::from_bytes(&vec![0; std::mem::size_of::<ContractIpo>()]).unwrap();
+            ipo.contract_index = RequestContractIpo::from_bytes(payload).unwrap().contract_index;
+            ipo.tick = 12_000_000;
+            ipo.public_keys[0] = QubicWallet::from_seed(SEED).unwrap().public_key;
+            ipo.prices[0] = 4;
+
+            Reply::Packets(vec![packet(MessageType::RespondContractIPO, &ipo.to_bytes())])
+        })
+        .stream(MessageType::RequestOwnedAsset, MessageType::RespondOwnedAsset, Vec::new())
+        .stream(MessageType::RequestIssuedAsset, MessageType::RespondIssuedAsset, issued_asset_responses())
+        .start();
+
+    (info, txs, computor)
+}
+
+/// computor interleaving a tick vote, its public peers and a transaction for every subscriber
+fn interleaving_computor() -> (qubic_tcp_types::types::ticks::Tick, TransactionWithData, RunningComputor) {
+    use qubic_tcp_types::types::Packet;
+    use qubic_types::traits::ToBytes;
+
+    let tick = broadcast_tick();
+    let tx = signed_transaction(QubicId([2; 32]), 100, tick.tick + 1);
+    let broadcasts = vec![
+        Packet::new(tick, false).unwrap().to_bytes(),
+        Packet::new(ExchangePublicPeers::default(), false).unwrap().to_bytes(),
+        Packet::new(tx.clone(), false).unwrap().to_bytes()
+    ];
+
+    let computor = FakeComputor::new().on(MessageType::ExchangePublicPeers, move |_| Reply::Packets(broadcasts.clone())).start();
+
+    (tick, tx, computor)
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_request_entity() {
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    let responded = client.qu().request_entity(QubicId([7; 32])).unwrap();
+
+    assert_eq!((responded.tick, responded.spectrum_index), (12_000_000, 42));
+    assert_eq!(responded.entity_only(), responded.entity);
+    assert_eq!(responded.entity_only().balance(), 1_000);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_simulate_transfer() {
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    // the source holds 1_000 QU, amounts below 100 QU are dust
+    let likely = client.qu().simulate_transfer(&signed_transaction(QubicId([2; 32]), 1_000, info.tick + 10)).unwrap();
+    assert!(likely.likely_to_execute);
+
+    let cases = [(1_001, info.tick + 10), (50, info.tick + 10), (500, info.tick + 1)];
+    let failed = cases.map(|(amount, tick)| {
+        let simulation = client.qu().simulate_transfer(&signed_transaction(QubicId([2; 32]), amount, tick)).unwrap();
+        (simulation.likely_to_execute, simulation.balance.passed, simulation.amount.passed, simulation.tick.passed)
+    });
+    assert_eq!(failed, [(false, false, true, true), (false, true, false, true), (false, true, true, false)]);
+}
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test() {
-    use qubic_tcp_types::types::transactions::RawTransaction;
-    let seed = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
-    use client::Client;
-    use qubic_types::{QubicId, QubicWallet};
-    use transport::Tcp;
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    let current_tick = dbg!(client.qu().get_current_tick_info().unwrap());
     let to = QubicId::from_str("BGKBSSHTGNLYOBUNOBYZNPEYDNABWKCHIWGOOUJRTGJOXTYPPWSXMGUAXHKI").unwrap();
-    let wallet = QubicWallet::from_seed(seed).unwrap();
-    let entity = dbg!(client.qu().request_entity(to).unwrap().entity);
-    println!("{}", entity.public_key);
-    println!("Balance: {}", entity.balance());
-
-    let tx = RawTransaction {
-        from: wallet.public_key,
-        to,
-        amount: 10,
-        tick: current_tick.tick + 30,
-        ..Default::default()
-    };
+    let entity = client.qu().request_entity(to).unwrap().entity;
 
-    dbg!(tx);
+    assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+    assert_eq!(entity.public_key, to);
+    assert_eq!(entity.balance(), 1_000);
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_tick_transactions() {
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    let current_tick = client.qu().get_current_tick_info().unwrap();
-
-    dbg!(current_tick);
-
-    let tick_txns = client.qu().request_tick_transactions(current_tick.tick - 5, TransactionFlags::all()).unwrap();
+    let tick_txns = client.qu().request_tick_transactions(info.tick - 5, TransactionFlags::all()).unwrap();
 
     for tx in &tick_txns {
         assert!(tx.verify());
     }
 
-    dbg!(tick_txns);
+    assert_eq!(tick_txns, txs);
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_tick_data() {
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    let current_tick = client.qu().get_current_tick_info().unwrap();
+    let tick_data = client.qu().request_tick_data(info.tick().saturating_sub(10)).unwrap();
+
+    assert_eq!(tick_data.tick, info.tick - 10);
+    assert_eq!(tick_data.transaction_digest[0], txs[0].clone().into());
+}
+
+/// tick data is decoded on the heap, a thread with a stack smaller than `TickData` can request it
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_tick_data_small_stack() {
+    use qubic_types::traits::ToBytes;
+
+    let response = tick_data(12_000_000, &[QubicTxHash([2; 32])]).to_bytes();
+
+    let tick = std::thread::Builder::new().stack_size(64 * 1024).spawn(move || {
+        mock_responses(vec![response]);
+        let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+
+        client.qu().request_tick_data(12_000_000).unwrap().tick
+    }).unwrap().join().unwrap();
 
-    dbg!(current_tick);
-    dbg!(std::mem::size_of::<TickData>());
+    assert_eq!(tick, 12_000_000);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_is_tick_finalized() {
+    use qubic_tcp_types::consts::QUORUM;
 
-    let tick_data = client.qu().request_tick_data(current_tick.tick - 10).unwrap();
+    let computor = voting_computor(&[0, QUORUM - 1, QUORUM]);
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    dbg!(tick_data);
+    // the tick only becomes final with the vote completing the quorum
+    assert!(!client.qu().is_tick_finalized(12_000_000).unwrap());
+    assert!(!client.qu().is_tick_finalized(12_000_000).unwrap());
+    assert!(client.qu().is_tick_finalized(12_000_000).unwrap());
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_mining_score() {
-    use qubic_types::QubicWallet;
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    let mining_score = client.qu().get_mining_score_ranking(&QubicWallet::from_seed(SEED).unwrap()).unwrap();
+
+    assert_eq!(mining_score.rankings, mining_ranking());
+}
+
+/// computor answering tick data of ticks ending in 3 or 4 with silence and of ticks ending in 7 without tick data.
+/// The first request of a tick ending in 5 is dropped
+fn tick_range_computor() -> RunningComputor {
+    use std::{collections::HashSet, sync::Mutex};
+    use qubic_types::traits::ToBytes;
+
+    let requested = Mutex::new(HashSet::new());
+
+    FakeComputor::new()
+        .on(MessageType::RequestTickData, move |payload| {
+            let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+
+            match tick % 10 {
+                3 | 4 => Reply::Silence,
+                5 if requested.lock().unwrap().insert(tick) => Reply::Silence,
+                7 => Reply::Packets(vec![end_response()]),
+                _ => Reply::Packets(vec![packet(MessageType::BroadcastFutureTickData, &tick_data(tick, &[]).to_bytes())])
+            }
+        })
+        .start()
+}
+
+fn assert_tick_range(range: &qubic_tcp_types::types::ticks::TickDataRange) {
+    use qubic_tcp_types::types::ticks::TickDataStatus;
+
+    assert_eq!((range.start, range.end, range.ticks.len()), (100, 199, 100));
+    assert_eq!(range.gaps(), (10..20).map(|i| i * 10 + 3..=i * 10 + 4).collect::<Vec<_>>());
+
+    for (tick, status) in range.iter() {
+        match (tick % 10, status) {
+            (3 | 4, TickDataStatus::Missing) | (7, TickDataStatus::EmptyTick) => (),
+            (_, TickDataStatus::Present(tick_data)) => assert_eq!(tick_data.tick, tick),
+            _ => panic!("unexpected status of tick {tick}: {status:?}")
+        }
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_request_tick_data_range() {
+    use crate::{client::ClientBuilder, transport::ConnectedTcp};
+
+    let computor = tick_range_computor();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(100)).build().unwrap();
+
+    assert_tick_range(&client.qu().request_tick_data_range(100, 199).unwrap());
+    assert_eq!(computor.connections(), 1);
+    // the dropped requests are retried once, the unanswered ones until they are given up
+    assert_eq!(computor.count(MessageType::RequestTickData), 100 + 10 + 20 * 2);
+    assert!(matches!(client.qu().request_tick_data_range(2, 1), Err(errors::ClientError::InvalidInput(_))));
+
+    // the pooled connection is left alone, the range is requested on a connection of its own
+    let client = ClientBuilder::<ConnectedTcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(100)).build().unwrap();
+    assert_tick_range(&client.qu().request_tick_data_range(100, 199).unwrap());
+    assert_eq!(computor.connections(), 3);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_request_tick_data_range() {
+    use crate::{client::ClientBuilder, transport::ConnectedTcp};
+
+    let computor = tick_range_computor();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(100)).build().await.unwrap();
 
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    assert_tick_range(&client.qu().request_tick_data_range(100, 199).await.unwrap());
+    assert_eq!(computor.connections(), 1);
+    assert!(matches!(client.qu().request_tick_data_range(2, 1).await, Err(errors::ClientError::InvalidInput(_))));
 
+    // the pooled connection is left alone, the range is requested on a connection of its own
+    let client = ClientBuilder::<ConnectedTcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(100)).build().await.unwrap();
+    assert_tick_range(&client.qu().request_tick_data_range(100, 199).await.unwrap());
+    assert_eq!(computor.connections(), 3);
+}
+
+/// computor echoing the descriptor and the time of `SetTime` commands
+fn set_time_computor() -> RunningComputor {
+    use qubic_tcp_types::types::special_commands::{SetTime, SpecialCommand};
+    use qubic_types::traits::FromBytes;
 
-    let mining_score = client.qu().special_command_get_mining_ranking(&QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap()).unwrap();
+    FakeComputor::new()
+        .on(MessageType::ProcessSpecialCommand, |payload| {
+            let command = SpecialCommand::<SetTime>::from_bytes(payload).unwrap();
 
-    println!("{:?}", mining_score);
+            Reply::Packets(vec![packet(MessageType::ProcessSpecialCommand, &payload[..8 + std::mem::size_of_val(&command.payload)])])
+        })
+        .start()
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
-fn test_period_detection() {
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+fn test_mining_score_rejected() {
+    use crate::client::ClientBuilder;
+    use qubic_tcp_types::types::special_commands::CommandType;
 
+    let computor = FakeComputor::new().on(MessageType::ProcessSpecialCommand, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().unwrap();
 
-    let current_tick = client.qu().get_current_tick_info().unwrap();
+    let res = client.qu().get_mining_score_ranking(&QubicWallet::from_seed(SEED).unwrap());
+
+    assert!(matches!(res, Err(errors::ClientError::CommandRejected(CommandType::SpecialCommandGetMiningScoreRanking))));
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_set_time() {
+    use crate::client::ClientBuilder;
+    use qubic_tcp_types::types::{special_commands::CommandType, time::QubicSetUtcTime};
+
+    let operator = QubicWallet::from_seed(SEED).unwrap();
+    let time = QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 999_999_999);
 
+    let computor = set_time_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    assert_eq!(client.qu().set_time(&operator, time).unwrap(), time);
+    assert!(matches!(client.qu().set_time(&operator, QubicSetUtcTime::new(23, 2, 29, 0, 0, 0, 0)), Err(errors::ClientError::InvalidInput(_))));
 
-    println!("{:?}", current_tick.tick_period());
+    let computor = FakeComputor::new().on(MessageType::ProcessSpecialCommand, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().unwrap();
+
+    assert!(matches!(client.qu().set_time(&operator, time), Err(errors::ClientError::CommandRejected(CommandType::SpecialCommandSendTime))));
 }
 
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_period_detection() {
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    assert_eq!(client.qu().get_current_tick_info().unwrap().tick_period(), info.tick_period());
+}
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_check() {
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    use crate::client::TransactionStatus;
 
-    let tick = 11885253;
-    let hash = QubicTxHash::from_str("fazkeookoirgnemyesoqdkfwhhbbrvhbgnqkwvstidaocuhouprgkwacevsm").unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    dbg!(client.qu().check_transaction_status(hash, tick).unwrap());
+    assert_eq!(client.qu().check_transaction_status(txs[1].clone().into(), info.tick - 5).unwrap(), TransactionStatus::Executed);
+    assert_eq!(client.qu().check_transaction_status(QubicTxHash([9; 32]), info.tick - 5).unwrap(), TransactionStatus::NotIncluded);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_check_proven() {
+    use crate::client::{ProvenTransactionStatus, TransactionStatus};
+    use qubic_tcp_types::consts::QUORUM;
+
+    let (info, txs, computor) = proving_computor(QUORUM);
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    assert_eq!(client.qu().check_transaction_status_proven(txs[1].clone().into(), info.tick - 5).unwrap(), ProvenTransactionStatus { status: TransactionStatus::Executed, proven: true });
+    assert_eq!(client.qu().check_transaction_status_proven(QubicTxHash([9; 32]), info.tick - 5).unwrap(), ProvenTransactionStatus { status: TransactionStatus::NotIncluded, proven: true });
+    assert!(!client.qu().check_transaction_status_proven(txs[1].clone().into(), info.tick).unwrap().proven);
+
+    // without a quorum on the digest of the tick data the status stands unproven
+    let (info, txs, computor) = proving_computor(QUORUM - 1);
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    assert_eq!(client.qu().check_transaction_status_proven(txs[1].clone().into(), info.tick - 5).unwrap(), ProvenTransactionStatus { status: TransactionStatus::Executed, proven: false });
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_check_pending() {
+    use crate::client::TransactionStatus;
+
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    // neither the current tick nor later ones have passed
+    for tick in [info.tick, info.tick + 5] {
+        assert_eq!(client.qu().check_transaction_status(txs[1].clone().into(), tick).unwrap(), TransactionStatus::Pending { target_tick: tick, current_tick: info.tick });
+    }
+
+    assert!(matches!(client.qu().check_transaction_status(txs[1].clone().into(), info.initial_tick - 1).unwrap(), TransactionStatus::Unknown { .. }));
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_check_proven() {
+    use crate::client::{ProvenTransactionStatus, TransactionStatus};
+    use qubic_tcp_types::consts::QUORUM;
+
+    let (info, txs, computor) = proving_computor(QUORUM);
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    assert_eq!(client.qu().check_transaction_status_proven(txs[1].clone().into(), info.tick - 5).await.unwrap(), ProvenTransactionStatus { status: TransactionStatus::Executed, proven: true });
+
+    let (info, txs, computor) = proving_computor(QUORUM - 1);
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    assert_eq!(client.qu().check_transaction_status_proven(txs[1].clone().into(), info.tick - 5).await.unwrap(), ProvenTransactionStatus { status: TransactionStatus::Executed, proven: false });
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_check_pending() {
+    use crate::client::TransactionStatus;
+
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    assert_eq!(client.qu().check_transaction_status(txs[1].clone().into(), info.tick + 5).await.unwrap(), TransactionStatus::Pending { target_tick: info.tick + 5, current_tick: info.tick });
+    assert_eq!(client.qu().check_transaction_status(QubicTxHash([9; 32]), info.tick - 5).await.unwrap(), TransactionStatus::NotIncluded);
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_subscription() {
-    use qubic_tcp_types::types::transactions::TransactionData;
+    let (tick, tx, computor) = interleaving_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    let (sender, receiver) = crossbeam_channel::unbounded::<NetworkEvent>();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
+        sender.send(event.event)?;
+        Ok(())
+    }).unwrap();
+
+    // the greeting and the peers between the broadcasts don't disturb the stream
+    let timeout = std::time::Duration::from_secs(5);
+    let events: Vec<_> = std::iter::from_fn(|| receiver.recv_timeout(timeout).ok())
+        .filter(|event| !matches!(event, NetworkEvent::ExchangePublicPeers(_)))
+        .take(2)
+        .collect();
+
+    assert_eq!(events, vec![NetworkEvent::BroadcastTick(Box::new(tick)), NetworkEvent::BroadcastTransaction(Box::new(tx))]);
+}
 
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_subscription_survives_panic() {
+    let (_, tx, computor) = interleaving_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    let (tx, rx) = crossbeam_channel::unbounded::<NetworkEvent>();
+    let (sender, receiver) = crossbeam_channel::unbounded::<NetworkEvent>();
 
     client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
-        let tx = tx.clone();
-        tx.send(event)?;
+        assert!(!matches!(event.event, NetworkEvent::BroadcastTick(_)), "handler failed on a tick");
+        sender.send(event.event)?;
         Ok(())
     }).unwrap();
 
-    let mut solutions = 0;
-    let mut transactions = 0;
-    let mut current_tick = 0;
-    let mut ep = 0;
-
-    loop {
-
-        std::thread::sleep(std::time::Duration::from_millis(2_000));
-        while !rx.is_empty() {
-
-            match rx.recv().unwrap() {
-                NetworkEvent::BroadcastMessage(_) => {
-                    solutions += 1;
-                },
-                NetworkEvent::BroadcastTransaction(tx) => {
-                    if tx.verify() {
-                        match tx.data {
-                            TransactionData::SubmitWork { seed: _, nonce: _ } => solutions += 1,
-                            _ => {
-                                transactions += 1
-                            }
-                        }
-                    }
-                },
-                NetworkEvent::BroadcastTick(t) => {
-                    current_tick = t.tick;
-                    ep = t.epoch;
-                },
-                _ => ()
-            }
-        }
+    // the tick is lost with the panicking call, the events after it still arrive
+    let timeout = std::time::Duration::from_secs(5);
+    let event = std::iter::from_fn(|| receiver.recv_timeout(timeout).ok()).find(|event| !matches!(event, NetworkEvent::ExchangePublicPeers(_)));
 
-        println!("Tick: {} | EP: {} | Solutions: {} | Transactions: {}", current_tick, ep, solutions, transactions);
-    }
+    assert_eq!(event, Some(NetworkEvent::BroadcastTransaction(Box::new(tx))));
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_ipo() {
-    use qubic_types::QubicWallet;
+    use qubic_types::traits::FromBytes;
 
-    let client = Client::<Tcp>::new("57.129.19.155:31841").unwrap();
-    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
 
-    dbg!(client.qu().request_contract_ipo(3).unwrap().public_keys);
+    assert_eq!(client.qu().request_contract_ipo(3).unwrap().public_keys[0], wallet.public_key);
 
     let current_tick = client.qu().get_current_tick_info().unwrap();
-
     client.qu().make_ipo_bid(&wallet, 3, 4, 2, current_tick.tick + 10).unwrap();
+
+    let bid = TransactionWithData::from_bytes(&computor.received(MessageType::BroadcastTransaction).unwrap()).unwrap();
+
+    assert_eq!(bid.raw_transaction.from, wallet.public_key);
+    assert_eq!(bid.raw_transaction.to.0[..4], 3u32.to_le_bytes());
+    assert_eq!(bid.raw_transaction.tick, info.tick + 10);
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
-fn test_asset() {
-    let client = Client::<Tcp>::new("57.129.19.155:31841").unwrap();
+fn test_send_special_command() {
+    use qubic_tcp_types::types::special_commands::{CommandBuilder, NodeMode, SpecialCommand, ToggleMainMode};
+    use qubic_types::traits::{Sign, ToBytes};
+
+    let computor = FakeComputor::new().start();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    // prepared as JSON, reviewed and signed later on
+    let prepared = serde_json::to_string(&CommandBuilder::toggle_main_aux(NodeMode::Aux).unsigned()).unwrap();
+    let mut command: SpecialCommand<ToggleMainMode> = serde_json::from_str(&prepared).unwrap();
+    command.sign(&QubicWallet::from_seed(SEED).unwrap()).unwrap();
 
-    dbg!(client.qu().request_entity(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).unwrap());
-    dbg!(client.qx().request_owned_assets(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).unwrap());
-    dbg!(client.qx().request_owned_assets(QubicId::from_str("MINECOMVVJWRHCKXPROBYQFUGKHAFMRIIYKPMDXGVAJLGHCMIRVVVDIBJIKB").unwrap()).unwrap());
+    client.qu().send_special_command(command.clone()).unwrap();
 
-    dbg!(client.qx().request_issued_assets(QubicId::default()).unwrap());
+    assert_eq!(computor.received(MessageType::ProcessSpecialCommand).unwrap(), command.to_bytes());
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_asset() {
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let id = QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap();
 
-    dbg!(client.qx().request_issued_assets(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).unwrap());
+    assert_eq!(client.qu().request_entity(id).unwrap().entity.public_key, id);
+    assert!(client.qx().request_owned_assets(id).unwrap().is_empty());
+    assert_eq!(client.qx().request_issued_assets(QubicId::default()).unwrap().len(), 3);
 }
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 #[test]
 fn test_tick() {
-    let client = Client::<Tcp>::new(COMPUTOR).unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
 
-    let tick = 13237172;
-    let id = QubicId::from_str("DTWINQHFOSIBVDHKQWCYLAMNSCJDWARQRNAYCHDRIBBFYTSVUWHREYEEUBXF").unwrap();
+    let txns = client.qu().request_tick_transactions(info.tick - 5, TransactionFlags::all()).unwrap();
+    let to_id: Vec<_> = txns.into_iter().filter(|tx| tx.raw_transaction.to == QubicId([1; 32])).collect();
 
-    let txns = client.qu().request_tick_transactions(tick, TransactionFlags::all()).unwrap();
+    assert_eq!(to_id, vec![txs[0].clone(), txs[2].clone()]);
+}
 
-    dbg!(txns.len());
-    for tx in txns {
-        if tx.raw_transaction.to == id {
-            dbg!(tx);
-        }
-    }
-    
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_tick_transactions_cleaned() {
+    let (txs, computor) = shuffling_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    let arrived = client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).unwrap();
+    assert_eq!(arrived, [txs[2].clone(), txs[0].clone(), txs[1].clone()]);
+
+    assert_eq!(client.qu().request_tick_transactions_ordered(12_000_000, TransactionFlags::all()).unwrap(), txs);
+
+    let report = client.qu().request_tick_transactions_detailed(12_000_000, TransactionFlags::all()).unwrap();
+    assert_eq!((report.received, report.complete), (3, true));
+    assert_eq!(report.transactions, txs);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_fake_computor_timeout() {
+    use crate::client::ClientBuilder;
+
+    let computor = FakeComputor::new().on(MessageType::RequestCurrentTickInfo, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info(), Err(errors::ClientError::Timeout)));
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_fake_computor_abrupt_close() {
+    use qubic_types::traits::ToBytes;
+
+    let tx = signed_transaction(QubicId([1; 32]), 10, 12_000_000);
+    let computor = FakeComputor::new()
+        .on(MessageType::RequestEntity, |_| Reply::Close(Vec::new()))
+        .on(MessageType::RequestTickTransactions, move |_| Reply::Close(vec![packet(MessageType::BroadcastTransaction, &tx.to_bytes())]))
+        .start();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    assert!(matches!(client.qu().request_entity(QubicId::default()), Err(errors::ClientError::PeerClosed)));
+    // closed in the middle of the stream, before `EndResponse`
+    assert!(matches!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()), Err(errors::ClientError::PeerClosed)));
+}
+
+/// computor greeting with 127.0.0.2 as its only peer and closing every connection, and the computor on 127.0.0.2
+/// under the same port answering the requests. Peers are suggested by address only, the port is the one of the busy
+/// computor, so the second computor needs another loopback address. Only Linux routes all of 127.0.0.0/8 by default
+#[cfg(target_os = "linux")]
+fn busy_network() -> (RunningComputor, RunningComputor) {
+    use std::net::Ipv4Addr;
+    use qubic_tcp_types::types::RespondedEntity;
+    use qubic_types::traits::{FromBytes, ToBytes};
+
+    let busy = FakeComputor::new()
+        .with_peers([Ipv4Addr::new(127, 0, 0, 2), Ipv4Addr::UNSPECIFIED, Ipv4Addr::UNSPECIFIED, Ipv4Addr::UNSPECIFIED])
+        .on(MessageType::RequestEntity, |_| Reply::Close(Vec::new()))
+        .on(MessageType::RequestTickTransactions, |_| Reply::Close(Vec::new()))
+        .start();
+
+    let mut entity = RespondedEntity::from_bytes(&vec![0; std::mem::size_of::<RespondedEntity>()]).unwrap();
+    entity.tick = 12_000_000;
+    let tx = signed_transaction(QubicId([1; 32]), 10, 12_000_000);
+    let port = busy.url().rsplit_once(':').unwrap().1;
+
+    let suggested = FakeComputor::new()
+        .respond(MessageType::RequestEntity, MessageType::RespondEntity, entity.to_bytes())
+        .stream(MessageType::RequestTickTransactions, MessageType::BroadcastTransaction, vec![tx.to_bytes()])
+        .start_on(&format!("127.0.0.2:{port}"));
+
+    (busy, suggested)
+}
+
+#[cfg(all(not(any(feature = "async", feature = "http")), target_os = "linux"))]
+#[test]
+fn test_busy_peer_redirect() {
+    use std::{net::Ipv4Addr, sync::Arc};
+    use crate::{client::ClientBuilder, interceptor::MetricsInterceptor};
+
+    let (busy, suggested) = busy_network();
+    let metrics = Arc::new(MetricsInterceptor::new());
+    let client = ClientBuilder::<Tcp>::new(busy.url()).with_interceptor(metrics.clone()).build().unwrap();
+
+    // the retry lands on the suggested peer
+    assert_eq!(client.qu().request_entity(QubicId::default()).unwrap().tick, 12_000_000);
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).unwrap().len(), 1);
+    assert!(busy.received(MessageType::RequestEntity).is_some() && suggested.received(MessageType::RequestEntity).is_some());
+    assert_eq!((metrics.get(MessageType::RequestEntity).redirects, metrics.get(MessageType::RequestEntity).failures), (1, 0));
+
+    let client = ClientBuilder::<Tcp>::new(busy.url()).with_busy_retries(0).build().unwrap();
+    assert!(matches!(client.qu().request_entity(QubicId::default()), Err(errors::ClientError::PeerBusy { suggested_peers }) if suggested_peers == vec![Ipv4Addr::new(127, 0, 0, 2)]));
+    assert_eq!(suggested.connections(), 2);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_request_entity() {
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let responded = client.qu().request_entity(QubicId([7; 32])).await.unwrap();
+
+    assert_eq!((responded.tick, responded.spectrum_index), (12_000_000, 42));
+    assert_eq!(responded.entity_only(), responded.entity);
+    assert_eq!(responded.entity_only().balance(), 1_000);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_simulate_transfer() {
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let likely = client.qu().simulate_transfer(&signed_transaction(QubicId([2; 32]), 1_000, info.tick + 10)).await.unwrap();
+    assert!(likely.likely_to_execute);
+
+    let unlikely = client.qu().simulate_transfer(&signed_transaction(QubicId([2; 32]), 1_001, info.tick + 1)).await.unwrap();
+    assert_eq!((unlikely.likely_to_execute, unlikely.balance.passed, unlikely.amount.passed, unlikely.tick.passed), (false, false, true, false));
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 #[tokio::test]
 async fn test() {
-    use qubic_tcp_types::prelude::*;
-    let seed = "aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa";
-    use client::Client;
-    use qubic_types::{QubicId, QubicWallet};
-    use transport::Tcp;
-    let client = Client::<Tcp>::new(COMPUTOR).await.unwrap();
-
-    let current_tick = dbg!(client.qu().get_current_tick_info().await.unwrap());
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
     let to = QubicId::from_str("BGKBSSHTGNLYOBUNOBYZNPEYDNABWKCHIWGOOUJRTGJOXTYPPWSXMGUAXHKI").unwrap();
-    let wallet = QubicWallet::from_seed(seed).unwrap();
-    let entity = dbg!(client.qu().request_entity(to).await.unwrap().entity);
-    println!("{}", entity.public_key);
-    let balance = entity.incoming_amount - entity.outgoing_amount;
-    println!("Balance: {}", balance);
-    let tx = RawTransaction {
-        from: wallet.public_key,
-        to,
-        amount: 10,
-        tick: current_tick.tick + 30,
-        ..Default::default()
-    };
+    let entity = client.qu().request_entity(to).await.unwrap().entity;
+
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+    assert_eq!(entity.public_key, to);
+    assert_eq!(entity.incoming_amount - entity.outgoing_amount, 1_000);
+}
 
-    dbg!(tx);
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_is_tick_finalized() {
+    use qubic_tcp_types::consts::QUORUM;
+
+    let computor = voting_computor(&[0, QUORUM - 1, QUORUM]);
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    // the tick only becomes final with the vote completing the quorum
+    assert!(!client.qu().is_tick_finalized(12_000_000).await.unwrap());
+    assert!(!client.qu().is_tick_finalized(12_000_000).await.unwrap());
+    assert!(client.qu().is_tick_finalized(12_000_000).await.unwrap());
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 #[tokio::test]
 async fn test_tick_transactions() {
-    let client = Client::<Tcp>::new(COMPUTOR).await.unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let tick_txns = client.qu().request_tick_transactions(info.tick - 5, TransactionFlags::all()).await.unwrap();
+
+    assert_eq!(tick_txns, txs);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_tick_transactions_cleaned() {
+    let (txs, computor) = shuffling_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let arrived = client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).await.unwrap();
+    assert_eq!(arrived, [txs[2].clone(), txs[0].clone(), txs[1].clone()]);
+
+    let (sender, receiver) = futures::channel::mpsc::channel(0);
+    let qu = client.qu();
+    let (sent, streamed) = futures::join!(qu.stream_tick_transactions(12_000_000, TransactionFlags::all(), sender), futures::StreamExt::collect::<Vec<_>>(receiver));
+    assert_eq!((sent.unwrap(), streamed), (3, arrived));
 
-    let tick_txns = client.qu().request_tick_transactions(12380150, TransactionFlags::all()).await.unwrap();
+    assert_eq!(client.qu().request_tick_transactions_ordered(12_000_000, TransactionFlags::all()).await.unwrap(), txs);
 
-    dbg!(tick_txns);
+    let report = client.qu().request_tick_transactions_detailed(12_000_000, TransactionFlags::all()).await.unwrap();
+    assert_eq!((report.received, report.complete), (3, true));
+    assert_eq!(report.transactions, txs);
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 #[tokio::test(flavor = "multi_thread")]
 async fn test_subscription() {
-    let client = Client::<Tcp>::new(COMPUTOR).await.unwrap();
+    let (tick, tx, computor) = interleaving_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
 
-    let (tx, rx) = crossbeam_channel::unbounded::<NetworkEvent>();
+    let (sender, receiver) = crossbeam_channel::unbounded::<NetworkEvent>();
 
     client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
-        let tx = tx.clone();
-        tx.send(event)?;
+        sender.send(event.event)?;
         Ok(())
     }).await.unwrap();
 
-    let mut solutions = 0;
-    let mut transactions = 0;
-    let mut current_tick = 0;
-    let mut ep = 0;
-
-    loop {
-
-        std::thread::sleep(std::time::Duration::from_millis(2_000));
-        while !rx.is_empty() {
-
-            match rx.recv().unwrap() {
-                NetworkEvent::BroadcastMessage(_) => {
-                    solutions += 1;
-                },
-                NetworkEvent::BroadcastTransaction(_) => {
-                    transactions += 1;
-                },
-                NetworkEvent::BroadcastTick(t) => {
-                    current_tick = t.tick;
-                    ep = t.epoch;
-                },
-                _ => ()
-            }
-        }
+    let events = tokio::task::spawn_blocking(move || {
+        let timeout = std::time::Duration::from_secs(5);
 
-        println!("Tick: {} | EP: {} | Solutions: {} | Transactions: {}", current_tick, ep, solutions, transactions);
-    }
+        std::iter::from_fn(|| receiver.recv_timeout(timeout).ok())
+            .filter(|event| !matches!(event, NetworkEvent::ExchangePublicPeers(_)))
+            .take(2)
+            .collect::<Vec<_>>()
+    }).await.unwrap();
+
+    assert_eq!(events, vec![NetworkEvent::BroadcastTick(Box::new(tick)), NetworkEvent::BroadcastTransaction(Box::new(tx))]);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_subscription_survives_panic() {
+    let (_, tx, computor) = interleaving_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let (sender, receiver) = crossbeam_channel::unbounded::<NetworkEvent>();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| {
+        assert!(!matches!(event.event, NetworkEvent::BroadcastTick(_)), "handler failed on a tick");
+        sender.send(event.event)?;
+        Ok(())
+    }).await.unwrap();
+
+    let event = tokio::task::spawn_blocking(move || {
+        let timeout = std::time::Duration::from_secs(5);
+
+        std::iter::from_fn(|| receiver.recv_timeout(timeout).ok()).find(|event| !matches!(event, NetworkEvent::ExchangePublicPeers(_)))
+    }).await.unwrap();
+
+    assert_eq!(event, Some(NetworkEvent::BroadcastTransaction(Box::new(tx))));
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 #[tokio::test]
 async fn test_read_only_qu() {
-    let client = Client::<Tcp>::new(COMPUTOR).await.unwrap();
+    let (info, txs, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let id = QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap();
 
-    dbg!(client.qu().request_entity(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).await.unwrap());
-    dbg!(client.qu().exchange_public_peers(ExchangePublicPeers::default()).await.unwrap());
-    let current_tick = dbg!(client.qu().get_current_tick_info().await.unwrap());
-    dbg!(client.qu().request_quorum_tick(current_tick.tick - 10, [0u8; (676 + 7) / 8]).await.unwrap());
-    dbg!(client.qu().request_tick_data(current_tick.tick - 10).await.unwrap());
+    assert_eq!(client.qu().request_entity(id).await.unwrap().entity.public_key, id);
+    assert_eq!(client.qu().exchange_public_peers(ExchangePublicPeers::default()).await.unwrap(), ExchangePublicPeers::default());
+
+    let current_tick = client.qu().get_current_tick_info().await.unwrap();
+    assert_eq!(current_tick, info);
+    assert_eq!(client.qu().request_quorum_tick(current_tick.tick - 10, [0u8; std::mem::size_of::<qubic_tcp_types::consts::VoteFlags>()]).await.unwrap(), broadcast_tick());
+    assert_eq!(client.qu().request_tick_data(current_tick.tick - 10).await.unwrap().transaction_digest[0], txs[0].clone().into());
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 #[tokio::test]
-async fn test_asset() {
-    let client = Client::<Tcp>::new(_TESTNET).await.unwrap();
+async fn test_mining_score() {
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
 
-    dbg!(client.qu().request_entity(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).await.unwrap());
-    dbg!(client.qx().request_owned_assets(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).await.unwrap());
-    dbg!(client.qx().request_owned_assets(QubicId::from_str("MINECOMVVJWRHCKXPROBYQFUGKHAFMRIIYKPMDXGVAJLGHCMIRVVVDIBJIKB").unwrap()).await.unwrap());
+    let mining_score = client.qu().get_mining_score_ranking(&QubicWallet::from_seed(SEED).unwrap()).await.unwrap();
 
-    dbg!(client.qx().request_issued_assets(QubicId::default()).await.unwrap());
+    assert_eq!(mining_score.rankings, mining_ranking());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_mining_score_rejected() {
+    use crate::client::ClientBuilder;
+    use qubic_tcp_types::types::special_commands::CommandType;
+
+    let computor = FakeComputor::new().on(MessageType::ProcessSpecialCommand, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().await.unwrap();
+
+    let res = client.qu().get_mining_score_ranking(&QubicWallet::from_seed(SEED).unwrap()).await;
+
+    assert!(matches!(res, Err(errors::ClientError::CommandRejected(CommandType::SpecialCommandGetMiningScoreRanking))));
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_set_time() {
+    use crate::client::ClientBuilder;
+    use qubic_tcp_types::types::{special_commands::CommandType, time::QubicSetUtcTime};
+
+    let operator = QubicWallet::from_seed(SEED).unwrap();
+    let time = QubicSetUtcTime::new(24, 2, 29, 23, 59, 59, 999_999_999);
+
+    let computor = set_time_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    assert_eq!(client.qu().set_time(&operator, time).await.unwrap(), time);
+    assert!(matches!(client.qu().set_time(&operator, QubicSetUtcTime::new(23, 2, 29, 0, 0, 0, 0)).await, Err(errors::ClientError::InvalidInput(_))));
+
+    let computor = FakeComputor::new().on(MessageType::ProcessSpecialCommand, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().await.unwrap();
+
+    assert!(matches!(client.qu().set_time(&operator, time).await, Err(errors::ClientError::CommandRejected(CommandType::SpecialCommandSendTime))));
+}
 
-    dbg!(client.qx().request_issued_assets(QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap()).await.unwrap());
-}
\ No newline at end of file
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_ipo() {
+    use qubic_types::traits::FromBytes;
+
+    let (info, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+
+    assert_eq!(client.qu().request_contract_ipo(3).await.unwrap().public_keys[0], wallet.public_key);
+
+    client.qu().make_ipo_bid(&wallet, 3, 4, 2, info.tick + 10).await.unwrap();
+
+    let bid = TransactionWithData::from_bytes(&computor.received(MessageType::BroadcastTransaction).unwrap()).unwrap();
+
+    assert_eq!(bid.raw_transaction.from, wallet.public_key);
+    assert_eq!(bid.raw_transaction.to.0[..4], 3u32.to_le_bytes());
+    assert_eq!(bid.raw_transaction.tick, info.tick + 10);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_send_special_command() {
+    use qubic_tcp_types::types::special_commands::{CommandBuilder, NodeMode, SpecialCommand, ToggleMainMode};
+    use qubic_types::traits::{Sign, ToBytes};
+
+    let computor = FakeComputor::new().start();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    // prepared as JSON, reviewed and signed later on
+    let prepared = serde_json::to_string(&CommandBuilder::toggle_main_aux(NodeMode::Aux).unsigned()).unwrap();
+    let mut command: SpecialCommand<ToggleMainMode> = serde_json::from_str(&prepared).unwrap();
+    command.sign(&QubicWallet::from_seed(SEED).unwrap()).unwrap();
+
+    client.qu().send_special_command(command.clone()).await.unwrap();
+
+    assert_eq!(computor.received(MessageType::ProcessSpecialCommand).unwrap(), command.to_bytes());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_asset() {
+    let (_, _, computor) = fake_network();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let id = QubicId::from_str("XOHYYIZLBNOAWDRWRMSGFTOBSEPATZLQYNTRBPHFXDAIOYQTGTNFTDABLLFA").unwrap();
+
+    assert_eq!(client.qu().request_entity(id).await.unwrap().entity.public_key, id);
+    assert!(client.qx().request_owned_assets(id).await.unwrap().is_empty());
+    assert_eq!(client.qx().request_issued_assets(QubicId::default()).await.unwrap().len(), 3);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_fake_computor_timeout() {
+    use crate::client::ClientBuilder;
+
+    let computor = FakeComputor::new().on(MessageType::RequestCurrentTickInfo, |_| Reply::Silence).start();
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(std::time::Duration::from_millis(50)).build().await.unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info().await, Err(errors::ClientError::Timeout)));
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_fake_computor_abrupt_close() {
+    use qubic_types::traits::ToBytes;
+
+    let tx = signed_transaction(QubicId([1; 32]), 10, 12_000_000);
+    let computor = FakeComputor::new()
+        .on(MessageType::RequestEntity, |_| Reply::Close(Vec::new()))
+        .on(MessageType::RequestTickTransactions, move |_| Reply::Close(vec![packet(MessageType::BroadcastTransaction, &tx.to_bytes())]))
+        .start();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    assert!(matches!(client.qu().request_entity(QubicId::default()).await, Err(errors::ClientError::PeerClosed)));
+    assert!(matches!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).await, Err(errors::ClientError::PeerClosed)));
+}
+
+#[cfg(all(any(feature = "async", feature = "http"), target_os = "linux"))]
+#[tokio::test]
+async fn test_busy_peer_redirect() {
+    use std::{net::Ipv4Addr, sync::Arc};
+    use crate::{client::ClientBuilder, interceptor::MetricsInterceptor};
+
+    let (busy, suggested) = busy_network();
+    let metrics = Arc::new(MetricsInterceptor::new());
+    let client = ClientBuilder::<Tcp>::new(busy.url()).with_interceptor(metrics.clone()).build().await.unwrap();
+
+    // the retry lands on the suggested peer
+    assert_eq!(client.qu().request_entity(QubicId::default()).await.unwrap().tick, 12_000_000);
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).await.unwrap().len(), 1);
+    assert!(busy.received(MessageType::RequestEntity).is_some() && suggested.received(MessageType::RequestEntity).is_some());
+    assert_eq!((metrics.get(MessageType::RequestEntity).redirects, metrics.get(MessageType::RequestEntity).failures), (1, 0));
+
+    let client = ClientBuilder::<Tcp>::new(busy.url()).with_busy_retries(0).build().await.unwrap();
+    assert!(matches!(client.qu().request_entity(QubicId::default()).await, Err(errors::ClientError::PeerBusy { suggested_peers }) if suggested_peers == vec![Ipv4Addr::new(127, 0, 0, 2)]));
+    assert_eq!(suggested.connections(), 2);
+}
+
+/// in-memory transport, urls starting with `unreachable` fail to connect and urls starting with `rejecting` fail to send.
+/// Responses queued with `mock_responses` before creating the client are returned by the request methods
+struct MockTransport {
+    url: String,
+    responses: Vec<Vec<u8>>
+}
+
+thread_local! {
+    static MOCK_RESPONSES: std::cell::RefCell<Vec<Vec<u8>>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+fn mock_responses(responses: Vec<Vec<u8>>) {
+    MOCK_RESPONSES.with(|r| *r.borrow_mut() = responses);
+}
+
+impl MockTransport {
+    fn create(url: String) -> Result<Box<Self>, std::io::Error> {
+        if url.starts_with("unreachable") {
+            return Err(std::io::ErrorKind::ConnectionRefused.into());
+        }
+
+        Ok(Box::new(Self { url, responses: MOCK_RESPONSES.with(|r| r.take()) }))
+    }
+
+    fn respond<T: qubic_types::traits::FromBytes>(&self) -> errors::Result<T> {
+        match self.responses.first() {
+            Some(res) => Ok(T::from_bytes(res)?),
+            None => Err(errors::ClientError::PeerClosed)
+        }
+    }
+
+    fn respond_all<T: qubic_types::traits::FromBytes>(&self) -> errors::Result<Vec<T>> {
+        Ok(self.responses.iter().map(|res| T::from_bytes(res)).collect::<Result<_, _>>()?)
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl transport::Transport for MockTransport {
+    type Err = std::io::Error;
+
+    fn new(url: String, _options: transport::RequestOptions) -> Result<Box<Self>, Self::Err> {
+        Self::create(url)
+    }
+
+    fn send_without_response<D: qubic_tcp_types::utils::QubicRequest + qubic_types::traits::ToBytes>(&self, _data: qubic_tcp_types::types::Packet<D>, _options: &transport::RequestOptions) -> errors::Result<()> {
+        if self.url.starts_with("rejecting") {
+            return Err(errors::ClientError::PeerClosed);
+        }
+
+        Ok(())
+    }
+
+    fn send_with_response<T: qubic_types::traits::FromBytes, D: qubic_tcp_types::utils::QubicRequest + qubic_types::traits::ToBytes>(&self, _data: qubic_tcp_types::types::Packet<D>, _options: &transport::RequestOptions) -> errors::Result<T> {
+        self.respond()
+    }
+
+    fn send_with_multiple_responses<T: qubic_types::traits::FromBytes, D: qubic_tcp_types::utils::QubicRequest + qubic_types::traits::ToBytes>(&self, _data: qubic_tcp_types::types::Packet<D>, _options: &transport::RequestOptions) -> errors::Result<Vec<T>> {
+        self.respond_all()
+    }
+
+    fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    fn connect(&self) -> errors::Result<std::net::TcpStream> {
+        Err(errors::ClientError::InvalidInput("not supported by mock".to_owned()))
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl transport::Transport for MockTransport {
+    type Err = std::io::Error;
+
+    async fn new(url: String, _options: transport::RequestOptions) -> Result<Box<Self>, Self::Err> {
+        Self::create(url)
+    }
+
+    async fn send_without_response(&self, _data: impl qubic_types::traits::ToBytes, _options: &transport::RequestOptions) -> errors::Result<()> {
+        if self.url.starts_with("rejecting") {
+            return Err(errors::ClientError::PeerClosed);
+        }
+
+        Ok(())
+    }
+
+    async fn send_with_response<T: qubic_types::traits::FromBytes, D: qubic_tcp_types::utils::QubicRequest + qubic_types::traits::ToBytes>(&self, _data: qubic_tcp_types::types::Packet<D>, _options: &transport::RequestOptions) -> errors::Result<T> {
+        self.respond()
+    }
+
+    async fn send_with_multiple_responses<T: qubic_types::traits::FromBytes, D: qubic_tcp_types::utils::QubicRequest + qubic_types::traits::ToBytes>(&self, _data: qubic_tcp_types::types::Packet<D>, _options: &transport::RequestOptions) -> errors::Result<Vec<T>> {
+        self.respond_all()
+    }
+
+    async fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn connect(&self) -> errors::Result<crate::runtime::TcpStream> {
+        Err(errors::ClientError::InvalidInput("not supported by mock".to_owned()))
+    }
+}
+
+fn broadcast_peers() -> Vec<String> {
+    vec!["peer-a:21841".to_owned(), "unreachable:21841".to_owned(), "peer-b:21841".to_owned(), "rejecting:21841".to_owned()]
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_broadcast_partial_failure() {
+    use qubic_tcp_types::prelude::Transaction;
+
+    let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+    let peers = broadcast_peers();
+
+    let report = client.qu().send_signed_transaction_multi(Transaction::default(), &peers, 2).unwrap();
+
+    assert_eq!(report.succeeded, vec!["peer-a:21841".to_owned(), "peer-b:21841".to_owned()]);
+    assert_eq!(report.failed.iter().map(|(peer, _)| peer.as_str()).collect::<Vec<_>>(), vec!["unreachable:21841", "rejecting:21841"]);
+
+    assert!(matches!(report.failed[0].1, errors::ClientError::Io(_)));
+    assert!(matches!(report.failed[1].1, errors::ClientError::PeerClosed));
+
+    match client.qu().send_signed_transaction_multi(Transaction::default(), &peers, 3) {
+        Err(errors::ClientError::BroadcastFailed { succeeded: 2, required: 3, failed }) => {
+            assert!(matches!(failed.as_slice(), [(a, errors::ClientError::Io(_)), (b, errors::ClientError::PeerClosed)] if a == "unreachable:21841" && b == "rejecting:21841"));
+        },
+        res => panic!("unexpected result {res:?}")
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_broadcast_partial_failure() {
+    use qubic_tcp_types::prelude::Transaction;
+
+    let client = Client::<MockTransport>::new("peer-a:21841").await.unwrap();
+    let peers = broadcast_peers();
+
+    let report = client.qu().send_signed_transaction_multi(Transaction::default(), &peers, 2).await.unwrap();
+
+    assert_eq!(report.succeeded, vec!["peer-a:21841".to_owned(), "peer-b:21841".to_owned()]);
+    assert_eq!(report.failed.iter().map(|(peer, _)| peer.as_str()).collect::<Vec<_>>(), vec!["unreachable:21841", "rejecting:21841"]);
+
+    assert!(matches!(report.failed[0].1, errors::ClientError::Io(_)));
+    assert!(matches!(report.failed[1].1, errors::ClientError::PeerClosed));
+
+    match client.qu().send_signed_transaction_multi(Transaction::default(), &peers, 3).await {
+        Err(errors::ClientError::BroadcastFailed { succeeded: 2, required: 3, failed }) => {
+            assert!(matches!(failed.as_slice(), [(a, errors::ClientError::Io(_)), (b, errors::ClientError::PeerClosed)] if a == "unreachable:21841" && b == "rejecting:21841"));
+        },
+        res => panic!("unexpected result {res:?}")
+    }
+}
+
+fn issued_asset_responses() -> Vec<Vec<u8>> {
+    use qubic_tcp_types::types::assets::{Asset, AssetName, AssetType, Issuance, RespondIssuedAsset};
+    use qubic_types::traits::ToBytes;
+
+    [("QX", QubicId::default()), ("CFB", QubicId([7; 32])), ("QFT", QubicId([9; 32]))].into_iter().map(|(name, issuer)| {
+        RespondIssuedAsset {
+            asset: Asset {
+                public_key: issuer,
+                asset_type: AssetType::Issuance(Issuance { name: AssetName::from_str(name).unwrap(), number_of_decimal_places: 2, unit_of_measurement: [0; 7] })
+            },
+            tick: 1
+        }.to_bytes()
+    }).collect()
+}
+
+/// issuances of three assets, each with two ownership records, as the universe answers `RequestAssets`
+fn universe_asset_responses() -> Vec<Vec<u8>> {
+    use qubic_tcp_types::types::assets::{Asset, AssetName, AssetType, Issuance, Ownership, RespondAssets};
+    use qubic_types::traits::ToBytes;
+
+    [("QX", QubicId::default()), ("CFB", QubicId([7; 32])), ("QFT", QubicId([9; 32]))].into_iter().zip(0u32..).flat_map(|((name, issuer), index)| {
+        let issuance = RespondAssets {
+            asset: Asset {
+                public_key: issuer,
+                asset_type: AssetType::Issuance(Issuance { name: AssetName::from_str(name).unwrap(), number_of_decimal_places: 2, unit_of_measurement: [0; 7] })
+            },
+            tick: 1,
+            universe_index: index
+        };
+        let ownership = |owner: u8, units: i64| RespondAssets {
+            asset: Asset {
+                public_key: QubicId([owner; 32]),
+                asset_type: AssetType::Ownership(Ownership { padding: 0, managing_contract_index: 1.into(), issuance_index: index.into(), number_of_units: units.into() })
+            },
+            tick: 1,
+            universe_index: 100 + index * 2 + u32::from(owner)
+        };
+
+        [issuance, ownership(0, 1000 * (i64::from(index) + 1)), ownership(1, 5)].map(|record| record.to_bytes())
+    }).collect()
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_find_asset() {
+    mock_responses(universe_asset_responses());
+    let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+
+    let asset = client.qx().find_asset("CFB").unwrap().unwrap();
+    assert_eq!(asset.issuer, QubicId([7; 32]));
+    assert_eq!(asset.name.to_string(), "CFB");
+    assert_eq!(asset.decimals, 2);
+    assert_eq!(asset.total_units, Some(2005));
+
+    assert!(client.qx().find_asset("MISSING").unwrap().is_none());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_find_asset() {
+    mock_responses(universe_asset_responses());
+    let client = Client::<MockTransport>::new("peer-a:21841").await.unwrap();
+
+    let asset = client.qx().find_asset("CFB").await.unwrap().unwrap();
+    assert_eq!(asset.issuer, QubicId([7; 32]));
+    assert_eq!(asset.name.to_string(), "CFB");
+    assert_eq!(asset.decimals, 2);
+    assert_eq!(asset.total_units, Some(2005));
+
+    assert!(client.qx().find_asset("MISSING").await.unwrap().is_none());
+}
+
+fn current_tick_response() -> (qubic_tcp_types::types::ticks::CurrentTickInfo, Vec<u8>) {
+    use qubic_types::traits::ToBytes;
+
+    let info = qubic_tcp_types::types::ticks::CurrentTickInfo { tick_duration: 1, epoch: 100, tick: 12_000_000, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: 11_900_000 };
+
+    (info, qubic_tcp_types::types::Packet::new(info, false).unwrap().to_bytes())
+}
+
+/// computor answering the current tick info after sleeping for `delay`
+fn slow_computor(delay: std::time::Duration) -> (qubic_tcp_types::types::ticks::CurrentTickInfo, RunningComputor) {
+    let (info, response) = current_tick_response();
+    let computor = FakeComputor::new().on(MessageType::RequestCurrentTickInfo, move |_| Reply::Delayed(delay, vec![response.clone()])).start();
+
+    (info, computor)
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_request_timeout_override() {
+    use crate::{client::ClientBuilder, transport::RequestOptions};
+    use std::time::Duration;
+
+    let (info, computor) = slow_computor(Duration::from_millis(300));
+
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(Duration::from_millis(50)).build().unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info(), Err(errors::ClientError::Timeout)));
+    assert_eq!(client.qu_with(RequestOptions::new().with_read_timeout(Duration::from_secs(5))).get_current_tick_info().unwrap(), info);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_request_timeout_override() {
+    use crate::{client::ClientBuilder, transport::RequestOptions};
+    use std::time::Duration;
+
+    let (info, computor) = slow_computor(Duration::from_millis(300));
+
+    let client = ClientBuilder::<Tcp>::new(computor.url()).with_read_timeout(Duration::from_millis(50)).build().await.unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info().await, Err(errors::ClientError::Timeout)));
+    assert_eq!(client.qu_with(RequestOptions::new().with_read_timeout(Duration::from_secs(5))).get_current_tick_info().await.unwrap(), info);
+}
+
+fn broadcast_tick() -> qubic_tcp_types::types::ticks::Tick {
+    use qubic_tcp_types::types::{ticks::Tick, time::QubicTime};
+    use qubic_types::Signature;
+
+    Tick {
+        computor_index: 1,
+        epoch: 100,
+        tick: 12_000_000,
+        time: QubicTime { milliseconds: 0, second: 1, minute: 2, hour: 3, day: 4, month: 5, year: 24 },
+        prev_resource_testing_digest: 0,
+        salted_resource_testing_digest: 0,
+        prev_spectrum_digest: [1; 32].into(),
+        prev_universe_digest: [2; 32].into(),
+        prev_computor_digest: [3; 32].into(),
+        salted_spectrum_digest: [4; 32].into(),
+        salted_universe_digest: [5; 32].into(),
+        salted_computor_digest: [6; 32].into(),
+        transaction_digest: [7; 32].into(),
+        expected_next_tick_transaction_digest: [8; 32].into(),
+        signature: Signature([9; 64])
+    }
+}
+
+/// computor answering the `n`th quorum tick request with `agreeing[n]` matching votes (the last count once exhausted) and
+/// one divergent vote
+fn voting_computor(agreeing: &'static [usize]) -> RunningComputor {
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use qubic_tcp_types::types::ticks::Tick;
+    use qubic_types::traits::ToBytes;
+
+    let requests = AtomicUsize::new(0);
+
+    FakeComputor::new().on(MessageType::RequestQuorumTick, move |_| {
+        let votes = agreeing[requests.fetch_add(1, Ordering::SeqCst).min(agreeing.len() - 1)];
+        let divergent = Tick { computor_index: (NUMBER_OF_COMPUTORS - 1) as u16, transaction_digest: [0; 32].into(), ..broadcast_tick() };
+        let mut packets: Vec<_> = (0..votes)
+            .map(|index| Tick { computor_index: index as u16, ..broadcast_tick() })
+            .chain([divergent])
+            .map(|vote| packet(MessageType::BroadcastTick, &vote.to_bytes()))
+            .collect();
+        packets.push(end_response());
+
+        Reply::Packets(packets)
+    }).start()
+}
+
+/// computor at tick 12_000_000 listing the transactions in the tick data of every tick, `agreeing` computors vote for
+/// the digest of the tick data and the others for another digest
+fn proving_computor(agreeing: usize) -> (CurrentTickInfo, Vec<TransactionWithData>, RunningComputor) {
+    use qubic_tcp_types::types::ticks::Tick;
+    use qubic_types::traits::ToBytes;
+
+    let (info, _) = current_tick_response();
+    let txs = vec![signed_transaction(QubicId([1; 32]), 10, info.tick - 5), signed_transaction(QubicId([2; 32]), 20, info.tick - 5)];
+    let digests: Vec<QubicTxHash> = txs.iter().map(QubicTxHash::from).collect();
+    let (tick_digests, vote_digests) = (digests.clone(), digests);
+
+    let computor = FakeComputor::new()
+        .respond(MessageType::RequestCurrentTickInfo, MessageType::RespondCurrentTickInfo, info.to_bytes())
+        .stream(MessageType::RequestTickTransactions, MessageType::BroadcastTransaction, txs.iter().map(|tx| tx.to_bytes()).collect())
+        .on(MessageType::RequestTickData, move |payload| {
+            let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+
+            Reply::Packets(vec![packet(MessageType::BroadcastFutureTickData, &tick_data(tick, &tick_digests).to_bytes())])
+        })
+        .on(MessageType::RequestQuorumTick, move |payload| {
+            let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+            let digest = tick_data(tick, &vote_digests).digest();
+
+            let mut packets: Vec<_> = (0..NUMBER_OF_COMPUTORS)
+                .map(|index| Tick { computor_index: index as u16, tick, transaction_digest: if index < agreeing { digest } else { [0; 32].into() }, ..broadcast_tick() })
+                .map(|vote| packet(MessageType::BroadcastTick, &vote.to_bytes()))
+                .collect();
+            packets.push(end_response());
+
+            Reply::Packets(packets)
+        })
+        .start();
+
+    (info, txs, computor)
+}
+
+/// computor broadcasting a tick vote and a transaction to every subscriber, without greeting it first
+fn broadcasting_computor() -> (qubic_tcp_types::types::ticks::Tick, TransactionWithData, RunningComputor) {
+    use qubic_tcp_types::types::{transactions::{RawTransaction, TransactionData}, Packet};
+    use qubic_types::{traits::ToBytes, Signature};
+
+    let tick = broadcast_tick();
+    let tx = TransactionWithData {
+        raw_transaction: RawTransaction { from: QubicId([1; 32]), to: QubicId([2; 32]), amount: 100, tick: 12_000_001, input_type: 0, input_size: 2 },
+        data: TransactionData::Memo(vec![1, 2]),
+        signature: Signature([3; 64])
+    };
+
+    let broadcasts = vec![Packet::new(tick, false).unwrap().to_bytes(), Packet::new(tx.clone(), false).unwrap().to_bytes()];
+    let computor = FakeComputor::new().without_greeting().on(MessageType::ExchangePublicPeers, move |_| Reply::Packets(broadcasts.clone())).start();
+
+    (tick, tx, computor)
+}
+
+/// computor broadcasting a tick of epoch 100, the computors of epoch 101, a lagging tick of epoch 100 and a tick of epoch 101
+fn epoch_change_computor() -> (Vec<NetworkEvent>, RunningComputor) {
+    use qubic_tcp_types::types::{Computors, Packet};
+    use qubic_types::traits::ToBytes;
+
+    let tick = |epoch, tick| qubic_tcp_types::types::ticks::Tick { epoch, tick, ..broadcast_tick() };
+    let computors = Computors { epoch: 101, public_key: [QubicId([1; 32]); NUMBER_OF_COMPUTORS], signature: Default::default() };
+    let (old, lagging, new) = (tick(100, 12_000_000), tick(100, 12_000_001), tick(101, 12_100_000));
+
+    let broadcasts = vec![
+        Packet::new(old, false).unwrap().to_bytes(),
+        Packet::new(computors, false).unwrap().to_bytes(),
+        Packet::new(lagging, false).unwrap().to_bytes(),
+        Packet::new(new, false).unwrap().to_bytes()
+    ];
+    let computor = FakeComputor::new().without_greeting().on(MessageType::ExchangePublicPeers, move |_| Reply::Packets(broadcasts.clone())).start();
+
+    let events = vec![
+        NetworkEvent::BroadcastTick(Box::new(old)),
+        NetworkEvent::BroadcastComputors(Box::new(computors)),
+        NetworkEvent::EpochChanged { old: 100, new: 101 },
+        NetworkEvent::BroadcastTick(Box::new(lagging)),
+        NetworkEvent::BroadcastTick(Box::new(new))
+    ];
+
+    (events, computor)
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_epoch_change_subscription() {
+    let (expected, computor) = epoch_change_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| Ok(sender.send(event.event)?)).unwrap();
+
+    let events: Vec<_> = std::iter::from_fn(|| receiver.recv_timeout(std::time::Duration::from_secs(5)).ok()).take(expected.len()).collect();
+
+    assert_eq!(events, expected);
+}
+
+/// the subscription thread runs with the default stack size, the computor lists and tick data are decoded on the heap
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_subscription_default_stack() {
+    use qubic_tcp_types::types::Computors;
+    use qubic_types::traits::ToBytes;
+
+    let mut computors = Computors::new_boxed();
+    computors.epoch = 100;
+    computors.public_key[NUMBER_OF_COMPUTORS - 1] = QubicId([1; 32]);
+    let tick_data = Box::new(tick_data(12_000_000, &[QubicTxHash([2; 32])]));
+
+    let broadcasts = vec![packet(MessageType::BroadcastComputors, &computors.to_bytes()), packet(MessageType::BroadcastFutureTickData, &tick_data.to_bytes())];
+    let computor = FakeComputor::new().without_greeting().on(MessageType::ExchangePublicPeers, move |_| Reply::Packets(broadcasts.clone())).start();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| Ok(sender.send(event.event)?)).unwrap();
+
+    let events: Vec<_> = std::iter::from_fn(|| receiver.recv_timeout(std::time::Duration::from_secs(5)).ok()).take(2).collect();
+
+    assert_eq!(events, vec![NetworkEvent::BroadcastComputors(computors), NetworkEvent::BroadcastFutureTick(tick_data)]);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_epoch_change_subscription() {
+    let (expected, computor) = epoch_change_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    client.qu().subscribe(ExchangePublicPeers::default(), move |event| Ok(sender.send(event.event)?)).await.unwrap();
+
+    let mut events = Vec::new();
+
+    while events.len() < expected.len() {
+        events.push(tokio::time::timeout(std::time::Duration::from_secs(5), receiver.recv()).await.unwrap().unwrap());
+    }
+
+    assert_eq!(events, expected);
+}
+
+/// the message type and the owned event of a raw subscription event
+fn owned_event(event: qubic_tcp_types::views::RawEvent<'_>) -> anyhow::Result<(qubic_tcp_types::MessageType, NetworkEvent)> {
+    let view = event.view()?.ok_or_else(|| anyhow::anyhow!("no network event"))?;
+
+    Ok((event.header.message_type, view.to_owned()?))
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_raw_subscription() {
+    use qubic_tcp_types::MessageType;
+
+    let (tick, tx, computor) = broadcasting_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let (sender, receiver) = std::sync::mpsc::channel();
+
+    client.qu().subscribe_raw(ExchangePublicPeers::default(), move |event| Ok(sender.send(owned_event(event)?)?)).unwrap();
+
+    let timeout = std::time::Duration::from_secs(5);
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), (MessageType::BroadcastTick, NetworkEvent::BroadcastTick(Box::new(tick))));
+    assert_eq!(receiver.recv_timeout(timeout).unwrap(), (MessageType::BroadcastTransaction, NetworkEvent::BroadcastTransaction(Box::new(tx))));
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_raw_subscription() {
+    use qubic_tcp_types::MessageType;
+
+    let (tick, tx, computor) = broadcasting_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let (sender, mut receiver) = tokio::sync::mpsc::unbounded_channel();
+
+    client.qu().subscribe_raw(ExchangePublicPeers::default(), move |event| Ok(sender.send(owned_event(event)?)?)).await.unwrap();
+
+    let timeout = std::time::Duration::from_secs(5);
+    assert_eq!(tokio::time::timeout(timeout, receiver.recv()).await.unwrap().unwrap(), (MessageType::BroadcastTick, NetworkEvent::BroadcastTick(Box::new(tick))));
+    assert_eq!(tokio::time::timeout(timeout, receiver.recv()).await.unwrap().unwrap(), (MessageType::BroadcastTransaction, NetworkEvent::BroadcastTransaction(Box::new(tx))));
+}
+
+/// computor broadcasting eight ticks at once to every subscriber, every broadcast is followed by closing the
+/// connection if `close`
+fn burst_computor(close: bool) -> (Vec<NetworkEvent>, RunningComputor) {
+    use qubic_tcp_types::types::Packet;
+    use qubic_types::traits::ToBytes;
+
+    let ticks: Vec<_> = (0..8).map(|i| qubic_tcp_types::types::ticks::Tick { tick: 12_000_000 + i, ..broadcast_tick() }).collect();
+    let broadcasts: Vec<_> = ticks.iter().map(|tick| Packet::new(*tick, false).unwrap().to_bytes()).collect();
+    let computor = FakeComputor::new().without_greeting().on(MessageType::ExchangePublicPeers, move |_| match close {
+        true => Reply::Close(broadcasts.clone()),
+        false => Reply::Packets(broadcasts.clone())
+    }).start();
+
+    (ticks.into_iter().map(|tick| NetworkEvent::BroadcastTick(Box::new(tick))).collect(), computor)
+}
+
+/// events of the burst received by a slow consumer with a buffer of two events, and the events dropped
+fn overflow_cases() -> [(crate::subscription::OverflowPolicy, std::ops::Range<usize>, u64); 3] {
+    use crate::subscription::OverflowPolicy;
+
+    [(OverflowPolicy::Block, 0..8, 0), (OverflowPolicy::DropNewest, 0..2, 6), (OverflowPolicy::DropOldest, 6..8, 6)]
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_subscription_overflow() {
+    use std::time::Duration;
+    use crate::subscription::SubscriptionConfig;
+
+    for (overflow, expected, dropped) in overflow_cases() {
+        let (ticks, computor) = burst_computor(false);
+        let client = Client::<Tcp>::new(computor.url()).unwrap();
+        let (handle, receiver) = client.qu().subscribe_channel(SubscriptionConfig::default().with_capacity(2).with_overflow(overflow)).unwrap();
+
+        // the burst arrives while the consumer is busy
+        std::thread::sleep(Duration::from_millis(500));
+        let events: Vec<_> = std::iter::from_fn(|| receiver.recv_timeout(Duration::from_millis(500)).ok()).map(|event| event.event).collect();
+        handle.cancel();
+
+        assert_eq!(events, ticks[expected], "{overflow:?}");
+        assert_eq!(handle.dropped(), dropped, "{overflow:?}");
+    }
+}
+
+/// the sender of a `DropOldest` subscription keeps a receiver of its own, dropping the consumer's still ends it
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_subscription_receiver_dropped() {
+    use std::time::{Duration, Instant};
+    use crate::subscription::{OverflowPolicy, SubscriptionConfig};
+
+    let (_, computor) = burst_computor(true);
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let (handle, receiver) = client.qu().subscribe_channel(SubscriptionConfig::default().with_capacity(2).with_overflow(OverflowPolicy::DropOldest)).unwrap();
+
+    drop(receiver);
+
+    let deadline = Instant::now() + Duration::from_secs(5);
+    while !handle.is_finished() && Instant::now() < deadline {
+        std::thread::sleep(Duration::from_millis(10));
+    }
+
+    assert!(handle.is_finished());
+    assert!(handle.is_cancelled());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_subscription_overflow() {
+    use std::time::Duration;
+    use futures::StreamExt;
+    use crate::subscription::SubscriptionConfig;
+
+    for (overflow, expected, dropped) in overflow_cases() {
+        let (ticks, computor) = burst_computor(false);
+        let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+        let (handle, stream) = client.qu().subscribe_channel(SubscriptionConfig::default().with_capacity(2).with_overflow(overflow)).await.unwrap();
+        let mut stream = std::pin::pin!(stream);
+
+        // the burst arrives while the consumer is busy
+        tokio::time::sleep(Duration::from_millis(500)).await;
+        let mut events = Vec::new();
+
+        while let Ok(Some(event)) = tokio::time::timeout(Duration::from_millis(500), stream.next()).await {
+            events.push(event.event);
+        }
+
+        handle.cancel();
+
+        assert_eq!(events, ticks[expected], "{overflow:?}");
+        assert_eq!(handle.dropped(), dropped, "{overflow:?}");
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_subscription_reconnects() {
+    use std::time::Duration;
+    use crate::subscription::SubscriptionConfig;
+
+    let (ticks, computor) = burst_computor(true);
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let (handle, receiver) = client.qu().subscribe_channel(SubscriptionConfig::default()).unwrap();
+
+    let events: Vec<_> = std::iter::from_fn(|| receiver.recv_timeout(Duration::from_secs(5)).ok()).take(18).map(|event| event.event).collect();
+    handle.cancel();
+
+    let connection = ticks.into_iter().chain([NetworkEvent::Disconnected]);
+    assert_eq!(events, connection.clone().chain(connection).collect::<Vec<_>>());
+    assert!(computor.connections() >= 2);
+
+    // the subscription ends after the buffered events once cancelled
+    while receiver.recv_timeout(Duration::from_secs(5)).is_ok() {}
+    assert!(receiver.recv().is_err());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_subscription_reconnects() {
+    use std::time::Duration;
+    use futures::StreamExt;
+    use crate::subscription::SubscriptionConfig;
+
+    let (ticks, computor) = burst_computor(true);
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let (handle, stream) = client.qu().subscribe_channel(SubscriptionConfig::default()).await.unwrap();
+    let mut stream = std::pin::pin!(stream);
+    let mut events = Vec::new();
+
+    while events.len() < 18 {
+        events.push(tokio::time::timeout(Duration::from_secs(5), stream.next()).await.unwrap().unwrap().event);
+    }
+
+    handle.cancel();
+
+    let connection = ticks.into_iter().chain([NetworkEvent::Disconnected]);
+    assert_eq!(events, connection.clone().chain(connection).collect::<Vec<_>>());
+    assert!(computor.connections() >= 2);
+
+    // the stream ends after the buffered events once cancelled
+    while tokio::time::timeout(Duration::from_secs(5), stream.next()).await.unwrap().is_some() {}
+}
+
+/// the async client on the runtime of the enabled feature: requests on both transports, a heartbeat and a subscription.
+/// Waits with the `runtime` module only, the async-std test runs without any tokio runtime
+#[cfg(any(feature = "async", feature = "http"))]
+async fn runtime_roundtrip() {
+    use std::time::Duration;
+    use futures::StreamExt;
+    use crate::{runtime, subscription::SubscriptionConfig, transport::ConnectedTcp};
+
+    let (info, response) = current_tick_response();
+    let computor = FakeComputor::new().on(MessageType::RequestCurrentTickInfo, move |_| Reply::Packets(vec![response.clone()])).start();
+
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+
+    let client = Client::<ConnectedTcp>::new(computor.url()).await.unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(50)).unwrap();
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+
+    // the heartbeat probes the idle computor on the pooled connection
+    runtime::sleep(Duration::from_millis(250)).await;
+    assert!(client.transport().is_healthy());
+    assert_eq!(computor.connections(), 2);
+
+    let (ticks, computor) = burst_computor(false);
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let (handle, stream) = client.qu().subscribe_channel(SubscriptionConfig::default()).await.unwrap();
+    let mut stream = std::pin::pin!(stream);
+
+    for tick in ticks {
+        assert_eq!(runtime::timeout(Duration::from_secs(5), stream.next()).await.unwrap().unwrap().event, tick);
+    }
+
+    handle.cancel();
+}
+
+#[cfg(all(any(feature = "async", feature = "http"), feature = "rt-tokio"))]
+#[tokio::test]
+async fn test_runtime_tokio() {
+    runtime_roundtrip().await;
+}
+
+#[cfg(all(any(feature = "async", feature = "http"), feature = "rt-async-std", not(feature = "rt-tokio")))]
+#[test]
+fn test_runtime_async_std() {
+    async_std::task::block_on(runtime_roundtrip());
+}
+
+enum PeerScript {
+    /// answers every request on the connection with the response
+    Respond(Vec<u8>),
+    /// reads the request header and drops the connection
+    Close,
+    /// answers every request with the `stale` frames of an earlier request first, then with the `response` packets
+    /// carrying the dejavu of the request
+    Interleave { stale: Vec<u8>, response: Vec<Vec<u8>> }
+}
+
+/// every accepted connection plays the next script, the listener is closed once all scripts are used
+fn scripted_peer(scripts: Vec<PeerScript>) -> String {
+    use std::io::{Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = listener.local_addr().unwrap().to_string();
+
+    std::thread::spawn(move || {
+        for script in scripts {
+            let Ok((mut stream, _)) = listener.accept() else { break };
+
+            std::thread::spawn(move || {
+                let mut header = [0u8; std::mem::size_of::<qubic_tcp_types::Header>()];
+
+                match script {
+                    PeerScript::Close => {
+                        let _ = stream.read_exact(&mut header);
+                    },
+                    PeerScript::Respond(response) => {
+                        while stream.read_exact(&mut header).is_ok() {
+                            let header: qubic_tcp_types::Header = qubic_types::traits::FromBytes::from_bytes(&header).unwrap();
+                            let mut payload = vec![0; header.get_size() - std::mem::size_of::<qubic_tcp_types::Header>()];
+
+                            if stream.read_exact(&mut payload).is_err() || stream.write_all(&response).is_err() {
+                                break;
+                            }
+                        }
+                    },
+                    PeerScript::Interleave { stale, response } => {
+                        while stream.read_exact(&mut header).is_ok() {
+                            let header: qubic_tcp_types::Header = qubic_types::traits::FromBytes::from_bytes(&header).unwrap();
+                            let mut payload = vec![0; header.get_size() - std::mem::size_of::<qubic_tcp_types::Header>()];
+                            let mut response = response.clone();
+
+                            for packet in response.iter_mut() {
+                                packet[4..8].copy_from_slice(&header.dejavu.to_le_bytes());
+                            }
+
+                            if stream.read_exact(&mut payload).is_err() || stream.write_all(&[stale.clone(), response.concat()].concat()).is_err() {
+                                break;
+                            }
+                        }
+                    }
+                }
+            });
+        }
+    });
+
+    url
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_connected_tcp_retries_stale_connection() {
+    use crate::transport::ConnectedTcp;
+
+    let (info, response) = current_tick_response();
+    let url = scripted_peer(vec![PeerScript::Close, PeerScript::Respond(response)]);
+
+    let client = Client::<ConnectedTcp>::new(url).unwrap();
+
+    assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+    assert!(client.transport().is_healthy());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_connected_tcp_retries_stale_connection() {
+    use crate::transport::ConnectedTcp;
+
+    let (info, response) = current_tick_response();
+    let url = scripted_peer(vec![PeerScript::Close, PeerScript::Respond(response)]);
+
+    let client = Client::<ConnectedTcp>::new(url).await.unwrap();
+
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+    assert!(client.transport().is_healthy());
+}
+
+/// peer answering the transactions of tick 12_000_000 after replaying a transaction and the `EndResponse` of an earlier
+/// request, on every connection
+fn cross_talking_peer() -> (Vec<TransactionWithData>, String) {
+    use qubic_types::traits::ToBytes;
+
+    let txs = (1..=2).map(|amount| signed_transaction(QubicId([1; 32]), amount, 12_000_000)).collect::<Vec<_>>();
+    let earlier = signed_transaction(QubicId([2; 32]), 99, 11_999_999);
+    let mut stale = [packet(MessageType::BroadcastTransaction, &earlier.to_bytes()), end_response()].concat();
+
+    for frame in [0, stale.len() - std::mem::size_of::<qubic_tcp_types::Header>()] {
+        stale[frame + 4..frame + 8].copy_from_slice(&0x0BAD_F00Du32.to_le_bytes());
+    }
+
+    let mut response: Vec<Vec<u8>> = txs.iter().map(|tx| packet(MessageType::BroadcastTransaction, &tx.to_bytes())).collect();
+    response.push(end_response());
+
+    let url = scripted_peer((0..3).map(|_| PeerScript::Interleave { stale: stale.clone(), response: response.clone() }).collect());
+
+    (txs, url)
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_connected_tcp_discards_cross_talk() {
+    use crate::transport::ConnectedTcp;
+
+    let (txs, url) = cross_talking_peer();
+    let client = Client::<ConnectedTcp>::new(url).unwrap();
+
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).unwrap(), txs);
+    assert_eq!(client.transport().cross_talk(), 2);
+
+    // the poisoned connection is replaced before the next request
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).unwrap(), txs);
+    assert_eq!(client.transport().cross_talk(), 4);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_connected_tcp_discards_cross_talk() {
+    use crate::transport::ConnectedTcp;
+
+    let (txs, url) = cross_talking_peer();
+    let client = Client::<ConnectedTcp>::new(url).await.unwrap();
+
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).await.unwrap(), txs);
+    assert_eq!(client.transport().cross_talk(), 2);
+
+    // the poisoned connection is replaced before the next request
+    assert_eq!(client.qu().request_tick_transactions(12_000_000, TransactionFlags::all()).await.unwrap(), txs);
+    assert_eq!(client.transport().cross_talk(), 4);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_connected_tcp_heartbeat() {
+    use crate::transport::ConnectedTcp;
+    use std::time::Duration;
+
+    let (info, response) = current_tick_response();
+
+    // the probes are answered on the pooled connection, the peer does not accept a second one
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Respond(response.clone())])).unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(client.transport().is_healthy());
+
+    // the pooled connection dies, the heartbeat replaces it before the next request
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close, PeerScript::Respond(response)])).unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(client.transport().is_healthy());
+    assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+
+    // neither the pooled connection nor a new one answers
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close])).unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    std::thread::sleep(Duration::from_millis(200));
+    assert!(!client.transport().is_healthy());
+}
+
+/// the error of the request is returned, not the one of reconnecting to the peer which closed the listener
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_connected_tcp_keeps_request_error() {
+    use crate::transport::ConnectedTcp;
+
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close])).unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info(), Err(errors::ClientError::PeerClosed)));
+    assert!(!client.transport().is_healthy());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_connected_tcp_heartbeat() {
+    use crate::transport::ConnectedTcp;
+    use std::time::Duration;
+
+    let (info, response) = current_tick_response();
+
+    // the probes are answered on the pooled connection, the peer does not accept a second one
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Respond(response.clone())])).await.unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(client.transport().is_healthy());
+
+    // the pooled connection dies, the heartbeat replaces it before the next request
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close, PeerScript::Respond(response)])).await.unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(client.transport().is_healthy());
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+
+    // neither the pooled connection nor a new one answers
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close])).await.unwrap();
+    client.transport().start_heartbeat(Duration::from_millis(20)).unwrap();
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!client.transport().is_healthy());
+}
+
+/// the error of the request is returned, not the one of reconnecting to the peer which closed the listener
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_connected_tcp_keeps_request_error() {
+    use crate::transport::ConnectedTcp;
+
+    let client = Client::<ConnectedTcp>::new(scripted_peer(vec![PeerScript::Close])).await.unwrap();
+
+    assert!(matches!(client.qu().get_current_tick_info().await, Err(errors::ClientError::PeerClosed)));
+    assert!(!client.transport().is_healthy());
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_connected_tcp_concurrent_requests() {
+    use crate::transport::ConnectedTcp;
+
+    let (info, computor) = slow_computor(std::time::Duration::ZERO);
+    let client = Client::<ConnectedTcp>::new(computor.url()).unwrap();
+
+    std::thread::scope(|scope| {
+        let requests: Vec<_> = (0..50).map(|_| scope.spawn(|| client.qu().get_current_tick_info())).collect();
+
+        for request in requests {
+            assert_eq!(request.join().unwrap().unwrap(), info);
+        }
+    });
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test(flavor = "multi_thread")]
+async fn test_connected_tcp_concurrent_requests() {
+    use crate::transport::ConnectedTcp;
+    use std::sync::Arc;
+
+    let (info, computor) = slow_computor(std::time::Duration::ZERO);
+    let client = Arc::new(Client::<ConnectedTcp>::new(computor.url()).await.unwrap());
+
+    let requests: Vec<_> = (0..50).map(|_| {
+        let client = client.clone();
+        tokio::spawn(async move { client.qu().get_current_tick_info().await })
+    }).collect();
+
+    for request in requests {
+        assert_eq!(request.await.unwrap().unwrap(), info);
+    }
+}
+
+#[test]
+fn test_event_log_round_trip() {
+    use crate::event_log::{EventLogReader, EventLogWriter};
+    use qubic_tcp_types::{events::EventEnvelope, types::ticks::Tick};
+    use std::sync::Mutex;
+
+    let events = vec![
+        EventEnvelope { received_at: 1, source: "127.0.0.1:21841".into(), event: NetworkEvent::ExchangePublicPeers(ExchangePublicPeers::default()) },
+        EventEnvelope { received_at: 2, source: "127.0.0.1:21841".into(), event: NetworkEvent::BroadcastTick(Box::new(Tick { tick: 12_000_000, ..Default::default() })) }
+    ];
+
+    let mut writer = EventLogWriter::new(Vec::new());
+
+    for event in events.iter() {
+        writer.write(event).unwrap();
+    }
+
+    let log = writer.into_inner();
+
+    assert_eq!(EventLogReader::new(log.as_slice()).collect::<std::io::Result<Vec<_>>>().unwrap(), events);
+
+    let replayed = Mutex::new(Vec::new());
+
+    assert_eq!(EventLogReader::new(log.as_slice()).replay(|event| {
+        replayed.lock().unwrap().push(event);
+        Ok(())
+    }).unwrap(), 2);
+    assert_eq!(replayed.into_inner().unwrap(), events);
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_asset_transfer_validation() {
+    use qubic_types::QubicWallet;
+    use qubic_tcp_types::types::assets::{AssetName, TransferAssetOwnershipAndPossessionInput};
+
+    let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let (custodian, owner) = (QubicId([1; 32]), QubicId([2; 32]));
+
+    assert!(matches!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), owner, "QX", 0, 1), Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), QubicId::default(), "QX", 1, 1), Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, QubicId::default(), QubicId::default(), owner, "QX", 1, 1), Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, owner, QubicId::default(), custodian, "QX", -5, 1), Err(errors::ClientError::InvalidInput(_))));
+
+    assert!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), owner, "QX", 10, 1).is_ok());
+
+    // the contract input is validated the same way
+    let input = TransferAssetOwnershipAndPossessionInput { possessor: custodian, issuer: QubicId::default(), new_owner: owner, asset_name: AssetName::from_str("QX").unwrap(), number_of_units: 10 };
+    assert!(matches!(client.qx().transfer_asset_with_input(&wallet, TransferAssetOwnershipAndPossessionInput { number_of_units: 0, ..input }, 1), Err(errors::ClientError::InvalidInput(_))));
+    assert!(client.qx().transfer_asset_with_input(&wallet, input, 1).is_ok());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_asset_transfer_validation() {
+    use qubic_types::QubicWallet;
+    use qubic_tcp_types::types::assets::{AssetName, TransferAssetOwnershipAndPossessionInput};
+
+    let client = Client::<MockTransport>::new("peer-a:21841").await.unwrap();
+    let wallet = QubicWallet::from_seed("aaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaaa").unwrap();
+    let (custodian, owner) = (QubicId([1; 32]), QubicId([2; 32]));
+
+    assert!(matches!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), owner, "QX", 0, 1).await, Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), QubicId::default(), "QX", 1, 1).await, Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, QubicId::default(), QubicId::default(), owner, "QX", 1, 1).await, Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qx().transfer_asset(&wallet, owner, QubicId::default(), custodian, "QX", -5, 1).await, Err(errors::ClientError::InvalidInput(_))));
+
+    assert!(client.qx().transfer_asset(&wallet, custodian, QubicId::default(), owner, "QX", 10, 1).await.is_ok());
+
+    // the contract input is validated the same way
+    let input = TransferAssetOwnershipAndPossessionInput { possessor: custodian, issuer: QubicId::default(), new_owner: owner, asset_name: AssetName::from_str("QX").unwrap(), number_of_units: 10 };
+    assert!(matches!(client.qx().transfer_asset_with_input(&wallet, TransferAssetOwnershipAndPossessionInput { number_of_units: 0, ..input }, 1).await, Err(errors::ClientError::InvalidInput(_))));
+    assert!(client.qx().transfer_asset_with_input(&wallet, input, 1).await.is_ok());
+}
+
+/// computor answering the poll results of poll 7, votes for option 1 and 3
+fn qutil_computor() -> RunningComputor {
+    let mut output = vec![0; 64 * 8 * 2 + 8];
+    output[8..16].copy_from_slice(&300u64.to_le_bytes());
+    output[3 * 8..4 * 8].copy_from_slice(&700u64.to_le_bytes());
+    output[65 * 8..66 * 8].copy_from_slice(&2u64.to_le_bytes());
+    output[67 * 8..68 * 8].copy_from_slice(&1u64.to_le_bytes());
+    output[128 * 8] = 1;
+
+    FakeComputor::new().respond(MessageType::RequestContractFunction, MessageType::RespondContractFunction, output).start()
+}
+
+/// checks the burn of 1000 QU and the request of the results of poll 7 `computor` received
+fn assert_qutil_requests(computor: &RunningComputor, results: qubic_tcp_types::types::qutil::PollResults) {
+    use qubic_tcp_types::types::{qutil::{PollOptionResult, PollResults}, transactions::RawTransaction};
+    use qubic_types::traits::FromBytes;
+
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+    let burn = computor.received(MessageType::BroadcastTransaction).unwrap();
+    let tx = RawTransaction::from_bytes(&burn[..80]).unwrap();
+
+    assert_eq!((tx.from, tx.to, tx.amount, tx.tick, tx.input_type, tx.input_size), (wallet.public_key, QubicId::from_contract_id(4), 1_000, 100, 2, 8));
+    assert_eq!(burn[80..88], 1_000i64.to_le_bytes());
+    assert!(TransactionWithData::from_bytes(&burn).unwrap().verify());
+
+    let request = computor.received(MessageType::RequestContractFunction).unwrap();
+    assert_eq!(request, [&4u32.to_le_bytes()[..], &3u16.to_le_bytes(), &8u16.to_le_bytes(), &7u64.to_le_bytes()].concat());
+
+    assert_eq!(results, PollResults {
+        active: true,
+        options: vec![PollOptionResult { option: 1, amount: 300, voters: 2 }, PollOptionResult { option: 3, amount: 700, voters: 1 }]
+    });
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_qutil() {
+    let computor = qutil_computor();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+
+    assert!(matches!(client.qutil().burn_qu(&wallet, 0, 100), Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qutil().burn_qu(&wallet, -5, 100), Err(errors::ClientError::InvalidInput(_))));
+
+    client.qutil().burn_qu(&wallet, 1_000, 100).unwrap();
+    let results = client.qutil().get_poll_results(7).unwrap();
+
+    assert_qutil_requests(&computor, results);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_qutil() {
+    let computor = qutil_computor();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+    let wallet = QubicWallet::from_seed(SEED).unwrap();
+
+    assert!(matches!(client.qutil().burn_qu(&wallet, 0, 100).await, Err(errors::ClientError::InvalidInput(_))));
+    assert!(matches!(client.qutil().burn_qu(&wallet, -5, 100).await, Err(errors::ClientError::InvalidInput(_))));
+
+    client.qutil().burn_qu(&wallet, 1_000, 100).await.unwrap();
+    let results = client.qutil().get_poll_results(7).await.unwrap();
+
+    assert_qutil_requests(&computor, results);
+}
+
+fn send_to_many_data() -> qubic_tcp_types::prelude::TransactionData {
+    use qubic_tcp_types::types::send_to_many::SendToManyInput;
+
+    let mut input = SendToManyInput::default();
+    input.amounts[..2].copy_from_slice(&[100, 200]);
+
+    qubic_tcp_types::prelude::TransactionData::SendToMany(input)
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_fee_estimation() {
+    use qubic_tcp_types::{prelude::{TransactionBuilder, TransactionData}, types::fees::{FeeBreakdown, FeeEstimator, TRANSFER_FEE}};
+
+    mock_responses(vec![10u32.to_le_bytes().to_vec()]);
+    let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+
+    assert_eq!(client.qu().estimate(&send_to_many_data()).unwrap(), FeeBreakdown { required_amount: 310, contract_fee: 10, burns: 0 });
+    assert_eq!(client.qu().estimate(&TransactionData::None).unwrap(), FeeBreakdown::default());
+    assert_eq!(client.qu().estimate(&TransactionData::TransferAsset(Default::default())).unwrap().required_amount, TRANSFER_FEE);
+
+    let tx = TransactionBuilder::new().with_tx_data(send_to_many_data()).unwrap().with_estimated_fees(&client.qu()).unwrap().build();
+    assert_eq!(tx.raw_transaction.amount, 310);
+
+    // without a response from the contract the fee is unknown
+    let client = Client::<MockTransport>::new("peer-a:21841").unwrap();
+    assert!(matches!(client.qu().estimate(&send_to_many_data()), Err(errors::ClientError::PeerClosed)));
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_fee_estimation() {
+    use qubic_tcp_types::{prelude::{TransactionBuilder, TransactionData}, types::fees::{FeeBreakdown, TRANSFER_FEE}};
+
+    mock_responses(vec![10u32.to_le_bytes().to_vec()]);
+    let client = Client::<MockTransport>::new("peer-a:21841").await.unwrap();
+
+    assert_eq!(client.qu().estimate_fees(&send_to_many_data()).await.unwrap(), FeeBreakdown { required_amount: 310, contract_fee: 10, burns: 0 });
+    assert_eq!(client.qu().estimate_fees(&TransactionData::None).await.unwrap(), FeeBreakdown::default());
+    assert_eq!(client.qu().estimate_fees(&TransactionData::TransferAsset(Default::default())).await.unwrap().required_amount, TRANSFER_FEE);
+
+    let schedule = client.qu().fee_schedule().await.unwrap();
+    let tx = TransactionBuilder::new().with_tx_data(send_to_many_data()).unwrap().with_estimated_fees(&schedule).unwrap().build();
+    assert_eq!(tx.raw_transaction.amount, 310);
+
+    let client = Client::<MockTransport>::new("peer-a:21841").await.unwrap();
+    assert!(matches!(client.qu().estimate_fees(&send_to_many_data()).await, Err(errors::ClientError::PeerClosed)));
+}
+
+/// wallets of the computors which vote in the monitor tests
+fn monitor_wallets() -> Vec<QubicWallet> {
+    (b'a'..=b'e').map(|seed| QubicWallet::from_seed(&(seed as char).to_string().repeat(55)).unwrap()).collect()
+}
+
+/// vote of `wallet` signed as the computor at `computor_index`
+fn monitor_vote(wallet: &QubicWallet, tick: u32, epoch: u16, computor_index: u16, digest: u8) -> qubic_tcp_types::types::ticks::Tick {
+    use qubic_types::H256;
+
+    let mut vote = qubic_tcp_types::types::ticks::Tick { tick, epoch, computor_index, transaction_digest: H256::repeat_byte(digest), ..Default::default() };
+    vote.sign(wallet);
+
+    vote
+}
+
+/// identities of a computor set led by the voting wallets, `shift` rotates the indices
+fn monitor_computors(wallets: &[QubicWallet], shift: usize) -> Vec<QubicId> {
+    (0..NUMBER_OF_COMPUTORS).map(|idx| {
+        let base = (idx + NUMBER_OF_COMPUTORS - shift) % NUMBER_OF_COMPUTORS;
+
+        wallets.get(base).map(|wallet| wallet.public_key).unwrap_or_else(|| {
+            let mut id = [0; 32];
+            id[..2].copy_from_slice(&(base as u16).to_le_bytes());
+            QubicId(id)
+        })
+    }).collect()
+}
+
+#[test]
+fn test_computor_monitor_window() {
+    use crate::computor_monitor::{AlertThresholds, ComputorAlert, ComputorMonitor, MAX_TICK_DISTANCE};
+    use std::sync::{Arc, Mutex};
+
+    let wallets = monitor_wallets();
+    let alerts = Arc::new(Mutex::new(Vec::new()));
+    let collected = alerts.clone();
+    let mut monitor = ComputorMonitor::new(10)
+        .with_thresholds(AlertThresholds { max_miss_rate: 0.4, max_divergence_rate: 0.5, min_observed: 5 })
+        .on_alert(move |alert| collected.lock().unwrap().push(*alert));
+    monitor.set_computors(100, monitor_computors(&wallets, 0));
+
+    // computors 0..3 agree, 3 signs divergent digests, 4 votes every second tick, everyone else is silent
+    for tick in 1..=14 {
+        for index in 0..5 {
+            if index == 4 && tick % 2 == 1 {
+                continue
+            }
+
+            monitor.handle_event(&NetworkEvent::BroadcastTick(Box::new(monitor_vote(&wallets[index as usize], tick, 100, index, if index == 3 { 2 } else { 1 }))));
+        }
+    }
+
+    // ticks 13 and 14 did not settle yet, the window holds ticks 3..=12
+    assert_eq!((monitor.epoch(), monitor.ticks()), (Some(100), 10));
+
+    let report = monitor.report();
+    assert_eq!(report.len(), NUMBER_OF_COMPUTORS);
+    assert_eq!((report[0].signed, report[0].missed, report[0].divergent), (10, 0, 0));
+    assert_eq!((report[3].signed, report[3].missed, report[3].divergent), (10, 0, 10));
+    assert_eq!((report[4].signed, report[4].missed, report[4].divergent), (5, 5, 0));
+    assert_eq!((report[5].signed, report[5].missed, report[5].divergent), (0, 10, 0));
+    assert_eq!(report[0].identity, Some(wallets[0].public_key));
+
+    // votes of evaluated ticks are dropped
+    monitor.add_vote(monitor_vote(&wallets[0], 5, 100, 5, 1));
+    assert_eq!(monitor.report()[5].missed, 10);
+
+    // every alert is raised once
+    {
+        let alerts = alerts.lock().unwrap();
+        let alerted = |index: u16| alerts.iter().filter(|alert| matches!(alert, ComputorAlert::MissingTicks(stats) | ComputorAlert::DivergentDigests(stats) if stats.computor_index == index)).collect::<Vec<_>>();
+
+        assert!(alerted(0).is_empty());
+        assert!(matches!(alerted(3)[..], [ComputorAlert::DivergentDigests(_)]));
+        assert!(matches!(alerted(4)[..], [ComputorAlert::MissingTicks(stats)] if stats.observed() >= 5));
+        assert!(matches!(alerted(NUMBER_OF_COMPUTORS as u16 - 1)[..], [ComputorAlert::MissingTicks(_)]));
+    }
+
+    // unsigned votes, votes signed by another computor and votes far from the current tick do not settle ticks
+    let mut monitor = ComputorMonitor::new(10);
+    monitor.set_computors(100, monitor_computors(&wallets, 0));
+    monitor.set_tick(2);
+
+    monitor.add_vote(monitor_vote(&wallets[0], 1, 100, 0, 1));
+    monitor.add_vote(monitor_vote(&wallets[0], 2, 100, 0, 1));
+
+    let unsigned = qubic_tcp_types::types::ticks::Tick { tick: 20, epoch: 100, computor_index: 0, ..Default::default() };
+    for vote in [unsigned, monitor_vote(&wallets[1], 20, 100, 0, 1), monitor_vote(&wallets[0], 2 + MAX_TICK_DISTANCE + 1, 100, 0, 1), monitor_vote(&wallets[0], u32::MAX, 100, 0, 1)] {
+        monitor.add_vote(vote);
+        assert_eq!(monitor.ticks(), 0);
+    }
+
+    monitor.add_vote(monitor_vote(&wallets[0], 2 + MAX_TICK_DISTANCE, 100, 0, 1));
+    assert_eq!(monitor.ticks(), 2);
+}
+
+#[test]
+fn test_computor_monitor_epoch_transition() {
+    use crate::computor_monitor::ComputorMonitor;
+
+    let wallets = monitor_wallets();
+
+    // every wallet votes at its index in the computors rotated by `shift`
+    let feed = |monitor: &mut ComputorMonitor, ticks: std::ops::RangeInclusive<u32>, epoch: u16, shift: usize, diverging: u16| {
+        for tick in ticks {
+            for (idx, wallet) in wallets.iter().enumerate() {
+                let index = ((idx + shift) % NUMBER_OF_COMPUTORS) as u16;
+                monitor.add_vote(monitor_vote(wallet, tick, epoch, index, if index == diverging { 2 } else { 1 }));
+            }
+        }
+    };
+
+    // identities of both epochs are known, the window follows the computor to its new index
+    let mut monitor = ComputorMonitor::new(100);
+    monitor.set_computors(100, monitor_computors(&wallets, 0));
+    monitor.set_computors(101, monitor_computors(&wallets, 1));
+
+    feed(&mut monitor, 1..=10, 100, 0, 0);
+    feed(&mut monitor, 11..=22, 101, 1, 1);
+
+    let report = monitor.report();
+    assert_eq!((monitor.epoch(), monitor.ticks()), (Some(101), 20));
+    assert_eq!(report[1].identity, Some(wallets[0].public_key));
+    assert_eq!((report[1].signed, report[1].divergent), (20, 20));
+    assert_eq!((report[2].signed, report[2].divergent), (20, 0));
+    assert_eq!((report[0].signed, report[0].missed), (0, 20));
+
+    // without the identities of the new epoch its votes can't be verified and are dropped
+    let mut monitor = ComputorMonitor::new(100);
+    monitor.set_computors(100, monitor_computors(&wallets, 0));
+
+    feed(&mut monitor, 1..=10, 100, 0, 0);
+    feed(&mut monitor, 11..=22, 101, 1, 1);
+
+    let report = monitor.report();
+    assert_eq!((monitor.epoch(), monitor.ticks()), (Some(100), 8));
+    assert_eq!((report[0].signed, report[0].divergent), (8, 8));
+}
+
+/// computor answering the tick info, computors and system info of the epoch set in `epoch`, counting the computors requests
+fn rollover_computor(epoch: std::sync::Arc<std::sync::atomic::AtomicU16>, computors_requests: std::sync::Arc<std::sync::atomic::AtomicUsize>) -> RunningComputor {
+    use std::sync::atomic::Ordering;
+    use qubic_tcp_types::types::{Computors, SystemInfo};
+    use qubic_types::traits::{FromBytes, ToBytes};
+
+    let tick_info_epoch = epoch.clone();
+    let system_info_epoch = epoch.clone();
+
+    FakeComputor::new()
+        .on(MessageType::RequestCurrentTickInfo, move |_| {
+            let epoch = tick_info_epoch.load(Ordering::SeqCst);
+            let info = CurrentTickInfo { tick_duration: 1, epoch, tick: epoch as u32 * 100_000 + 10, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: epoch as u32 * 100_000 };
+
+            Reply::Packets(vec![packet(MessageType::RespondCurrentTickInfo, &info.to_bytes())])
+        })
+        .on(MessageType::RequestComputors, move |_| {
+            computors_requests.fetch_add(1, Ordering::SeqCst);
+
+            let mut computors = Computors::from_bytes(&vec![0; std::mem::size_of::<Computors>()]).unwrap();
+            computors.epoch = epoch.load(Ordering::SeqCst);
+
+            Reply::Packets(vec![packet(MessageType::BroadcastComputors, &computors.to_bytes())])
+        })
+        .on(MessageType::RequestSystemInfo, move |_| {
+            let mut system_info = SystemInfo::from_bytes(&vec![0; std::mem::size_of::<SystemInfo>()]).unwrap();
+            system_info.epoch = system_info_epoch.load(Ordering::SeqCst);
+            system_info.initial_tick = system_info.epoch as u32 * 100_000;
+
+            Reply::Packets(vec![packet(MessageType::RespondSystemInfo, &system_info.to_bytes())])
+        })
+        .start()
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_epoch_guard_rollover() {
+    use std::sync::{atomic::{AtomicU16, AtomicUsize, Ordering}, Arc, Mutex};
+    use qubic_tcp_types::prelude::TransactionBuilder;
+
+    let (epoch, computors_requests, changes) = (Arc::new(AtomicU16::new(100)), Arc::new(AtomicUsize::new(0)), Arc::new(Mutex::new(Vec::new())));
+    let computor = rollover_computor(epoch.clone(), computors_requests.clone());
+    let recorded = changes.clone();
+    let guard = Client::<Tcp>::new(computor.url()).unwrap().epoch_guard().on_epoch_change(move |old, new| recorded.lock().unwrap().push((old, new)));
+
+    assert!(matches!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard), Err(errors::ClientError::InvalidInput(_))));
+
+    guard.refresh().unwrap();
+    assert_eq!((guard.current_epoch(), guard.initial_tick()), (Some(100), Some(10_000_000)));
+    assert_eq!(guard.computors().unwrap().epoch, 100);
+    assert_eq!(guard.computors().unwrap().epoch, 100);
+    assert_eq!(computors_requests.load(Ordering::SeqCst), 1);
+    assert!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard).is_ok());
+
+    // the first epoch observed is no change, the rollover is reported once however often it is observed
+    epoch.store(101, Ordering::SeqCst);
+    guard.refresh().unwrap();
+    guard.refresh().unwrap();
+    assert_eq!({ guard.system_info().unwrap().epoch }, 101);
+    assert_eq!(*changes.lock().unwrap(), [(100, 101)]);
+
+    // the computors of the old epoch were dropped
+    assert_eq!(guard.computors().unwrap().epoch, 101);
+    assert_eq!(guard.computors().unwrap().epoch, 101);
+    assert_eq!(computors_requests.load(Ordering::SeqCst), 2);
+
+    assert!(!guard.is_tick_in_current_epoch(10_000_005));
+    assert!(matches!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard), Err(errors::ClientError::StaleTick { tick: 10_000_005, epoch: 101, initial_tick: 10_100_000 })));
+
+    // a lagging peer does not roll the epoch back
+    epoch.store(100, Ordering::SeqCst);
+    guard.refresh().unwrap();
+    assert_eq!(guard.current_epoch(), Some(101));
+    assert_eq!(changes.lock().unwrap().len(), 1);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_epoch_guard_rollover() {
+    use std::sync::{atomic::{AtomicU16, AtomicUsize, Ordering}, Arc, Mutex};
+    use qubic_tcp_types::prelude::TransactionBuilder;
+
+    let (epoch, computors_requests, changes) = (Arc::new(AtomicU16::new(100)), Arc::new(AtomicUsize::new(0)), Arc::new(Mutex::new(Vec::new())));
+    let computor = rollover_computor(epoch.clone(), computors_requests.clone());
+    let recorded = changes.clone();
+    let guard = Client::<Tcp>::new(computor.url()).await.unwrap().epoch_guard().on_epoch_change(move |old, new| recorded.lock().unwrap().push((old, new)));
+
+    assert!(matches!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard), Err(errors::ClientError::InvalidInput(_))));
+
+    guard.refresh().await.unwrap();
+    assert_eq!((guard.current_epoch(), guard.initial_tick()), (Some(100), Some(10_000_000)));
+    assert_eq!(guard.computors().await.unwrap().epoch, 100);
+    assert_eq!(guard.computors().await.unwrap().epoch, 100);
+    assert_eq!(computors_requests.load(Ordering::SeqCst), 1);
+    assert!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard).is_ok());
+
+    // the first epoch observed is no change, the rollover is reported once however often it is observed
+    epoch.store(101, Ordering::SeqCst);
+    guard.refresh().await.unwrap();
+    guard.refresh().await.unwrap();
+    assert_eq!({ guard.system_info().await.unwrap().epoch }, 101);
+    assert_eq!(*changes.lock().unwrap(), [(100, 101)]);
+
+    // the computors of the old epoch were dropped
+    assert_eq!(guard.computors().await.unwrap().epoch, 101);
+    assert_eq!(guard.computors().await.unwrap().epoch, 101);
+    assert_eq!(computors_requests.load(Ordering::SeqCst), 2);
+
+    assert!(!guard.is_tick_in_current_epoch(qubic_types::Tick(10_000_005)));
+    assert!(matches!(TransactionBuilder::new().with_tick(10_000_005).preflight(&guard), Err(errors::ClientError::StaleTick { tick: 10_000_005, epoch: 101, initial_tick: 10_100_000 })));
+
+    // a lagging peer does not roll the epoch back
+    epoch.store(100, Ordering::SeqCst);
+    guard.refresh().await.unwrap();
+    assert_eq!(guard.current_epoch(), Some(101));
+    assert_eq!(changes.lock().unwrap().len(), 1);
+}
+
+/// computor answering the tick info and computors of the epoch and tick set in `state` and the tick data of every tick
+fn caching_computor(state: std::sync::Arc<std::sync::Mutex<(u16, u32)>>) -> RunningComputor {
+    use qubic_tcp_types::types::Computors;
+    use qubic_types::traits::{FromBytes, ToBytes};
+
+    let tick_info_state = state.clone();
+
+    FakeComputor::new()
+        .on(MessageType::RequestCurrentTickInfo, move |_| {
+            let (epoch, tick) = *tick_info_state.lock().unwrap();
+            let info = CurrentTickInfo { tick_duration: 1, epoch, tick, number_of_aligned_votes: 451, number_of_misaligned_votes: 0, initial_tick: epoch as u32 * 100_000 };
+
+            Reply::Packets(vec![packet(MessageType::RespondCurrentTickInfo, &info.to_bytes())])
+        })
+        .on(MessageType::RequestComputors, move |_| {
+            let mut computors = Computors::from_bytes(&vec![0; std::mem::size_of::<Computors>()]).unwrap();
+            computors.epoch = state.lock().unwrap().0;
+
+            Reply::Packets(vec![packet(MessageType::BroadcastComputors, &computors.to_bytes())])
+        })
+        .on(MessageType::RequestTickData, |payload| {
+            let tick = u32::from_le_bytes(payload[..4].try_into().unwrap());
+
+            Reply::Packets(vec![packet(MessageType::BroadcastFutureTickData, &tick_data(tick, &[]).to_bytes())])
+        })
+        .start()
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_cached_client() {
+    use std::{sync::{Arc, Mutex}, time::Duration};
+    use cache::{CacheConfig, CacheEntry, CacheKey, CacheStats, CacheStore, MemoryStore};
+
+    /// store recording the keys inserted into it
+    struct RecordingStore(MemoryStore, Arc<Mutex<Vec<CacheKey>>>);
+
+    impl CacheStore for RecordingStore {
+        fn get(&self, key: &CacheKey) -> Option<CacheEntry> {
+            self.0.get(key)
+        }
+
+        fn insert(&self, key: CacheKey, entry: CacheEntry) {
+            self.1.lock().unwrap().push(key);
+            self.0.insert(key, entry);
+        }
+
+        fn remove(&self, key: &CacheKey) {
+            self.0.remove(key);
+        }
+    }
+
+    let state = Arc::new(Mutex::new((100, 10_000_010)));
+    let computor = caching_computor(state.clone());
+    let inserted = Arc::new(Mutex::new(Vec::new()));
+    let cached = Client::<Tcp>::new(computor.url()).unwrap()
+        .cached(CacheConfig::default().with_tick_info_ttl(Duration::from_millis(200)))
+        .with_store(RecordingStore(Default::default(), inserted.clone()));
+
+    // the tick info is requested again once it expired
+    assert_eq!(cached.get_current_tick_info().unwrap().tick, 10_000_010);
+    assert_eq!(cached.get_current_tick_info().unwrap().tick, 10_000_010);
+    assert_eq!(computor.count(MessageType::RequestCurrentTickInfo), 1);
+    std::thread::sleep(Duration::from_millis(250));
+    cached.get_current_tick_info().unwrap();
+    assert_eq!(computor.count(MessageType::RequestCurrentTickInfo), 2);
+
+    // tick data of passed ticks is kept, the one of later ticks may still change
+    for _ in 0..2 {
+        assert_eq!(cached.request_tick_data(10_000_005).unwrap().tick, 10_000_005);
+        assert_eq!(cached.request_tick_data(10_000_020).unwrap().tick, 10_000_020);
+    }
+    assert_eq!(computor.count(MessageType::RequestTickData), 3);
+
+    // concurrent requests share the cache
+    std::thread::scope(|s| {
+        for _ in 0..4 {
+            s.spawn(|| cached.request_tick_data(10_000_005).unwrap());
+        }
+    });
+    assert_eq!(computor.count(MessageType::RequestTickData), 3);
+
+    assert_eq!(cached.request_computors().unwrap().epoch, 100);
+    assert_eq!(cached.request_computors().unwrap().epoch, 100);
+    assert_eq!(computor.count(MessageType::RequestComputors), 1);
+
+    // the computors are dropped once a later epoch is observed
+    *state.lock().unwrap() = (101, 10_100_000);
+    std::thread::sleep(Duration::from_millis(250));
+    assert_eq!(cached.get_current_tick_info().unwrap().epoch, 101);
+    assert_eq!(cached.request_computors().unwrap().epoch, 101);
+    assert_eq!(computor.count(MessageType::RequestComputors), 2);
+
+    let stats = cached.stats();
+    assert_eq!((stats.computors, stats.tick_data), (CacheStats { hits: 1, misses: 2 }, CacheStats { hits: 5, misses: 3 }));
+    assert_eq!(inserted.lock().unwrap().iter().filter(|key| **key == CacheKey::TickData(10_000_005)).count(), 1);
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_cached_client() {
+    use std::{sync::{Arc, Mutex}, time::Duration};
+    use cache::{CacheConfig, CacheStats};
+
+    let state = Arc::new(Mutex::new((100, 10_000_010)));
+    let computor = caching_computor(state.clone());
+    let cached = Client::<Tcp>::new(computor.url()).await.unwrap().cached(CacheConfig::default().with_tick_info_ttl(Duration::from_millis(200)));
+
+    // the tick info is requested again once it expired
+    assert_eq!(cached.get_current_tick_info().await.unwrap().tick, 10_000_010);
+    assert_eq!(cached.get_current_tick_info().await.unwrap().tick, 10_000_010);
+    assert_eq!(computor.count(MessageType::RequestCurrentTickInfo), 1);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    cached.get_current_tick_info().await.unwrap();
+    assert_eq!(computor.count(MessageType::RequestCurrentTickInfo), 2);
+
+    // tick data of passed ticks is kept, the one of later ticks may still change
+    for _ in 0..2 {
+        assert_eq!(cached.request_tick_data(10_000_005).await.unwrap().tick, 10_000_005);
+        assert_eq!(cached.request_tick_data(10_000_020).await.unwrap().tick, 10_000_020);
+    }
+    assert_eq!(computor.count(MessageType::RequestTickData), 3);
+
+    assert_eq!(cached.request_computors().await.unwrap().epoch, 100);
+    assert_eq!(cached.request_computors().await.unwrap().epoch, 100);
+    assert_eq!(computor.count(MessageType::RequestComputors), 1);
+
+    // the computors are dropped once a later epoch is observed
+    *state.lock().unwrap() = (101, 10_100_000);
+    tokio::time::sleep(Duration::from_millis(250)).await;
+    assert_eq!(cached.get_current_tick_info().await.unwrap().epoch, 101);
+    assert_eq!(cached.request_computors().await.unwrap().epoch, 101);
+    assert_eq!(computor.count(MessageType::RequestComputors), 2);
+
+    let stats = cached.stats();
+    assert_eq!((stats.computors, stats.tick_data), (CacheStats { hits: 1, misses: 2 }, CacheStats { hits: 1, misses: 3 }));
+}
+
+/// records its invocations as `<name> before <message type>` and `<name> after <responses or error>`
+struct RecordingInterceptor {
+    name: &'static str,
+    events: std::sync::Arc<std::sync::Mutex<Vec<String>>>
+}
+
+impl interceptor::Interceptor for RecordingInterceptor {
+    fn before(&self, req: &interceptor::RequestInfo) {
+        self.events.lock().unwrap().push(format!("{} before {:?}", self.name, req.message_type));
+    }
+
+    fn after(&self, _req: &interceptor::RequestInfo, result: &errors::Result<interceptor::ResponseInfo>) {
+        let outcome = result.as_ref().map(|info| info.responses.to_string()).unwrap_or_else(|e| e.to_string());
+        self.events.lock().unwrap().push(format!("{} after {outcome}", self.name));
+    }
+}
+
+struct PanickingInterceptor;
+
+impl interceptor::Interceptor for PanickingInterceptor {
+    fn before(&self, _req: &interceptor::RequestInfo) {
+        panic!("interceptor failed before the request");
+    }
+
+    fn after(&self, _req: &interceptor::RequestInfo, _result: &errors::Result<interceptor::ResponseInfo>) {
+        panic!("interceptor failed after the request");
+    }
+}
+
+/// computor answering the current tick info and never the computors
+fn intercepted_computor() -> (CurrentTickInfo, RunningComputor) {
+    let (info, response) = current_tick_response();
+    let computor = FakeComputor::new()
+        .on(MessageType::RequestCurrentTickInfo, move |_| Reply::Packets(vec![response.clone()]))
+        .on(MessageType::RequestComputors, |_| Reply::Silence)
+        .start();
+
+    (info, computor)
+}
+
+fn expected_interceptor_events() -> Vec<String> {
+    ["outer before RequestCurrentTickInfo", "inner before RequestCurrentTickInfo", "inner after 1", "outer after 1"].map(String::from).to_vec()
+}
+
+/// logs of `fixtures/logs.bin`, 1500 complete ones spanning several chunks followed by the truncated one
+fn log_fixture() -> Vec<u8> {
+    const FIXTURE: &[u8] = include_bytes!("../../qubic-tcp-types/fixtures/logs.bin");
+
+    [FIXTURE[..260].repeat(299), FIXTURE.to_vec()].concat()
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_request_log_to_writer() {
+    use std::{fs::File, io::BufReader};
+    use qubic_tcp_types::types::qlogging::{LogParseError, LogParser};
+    use transport::RequestOptions;
+
+    let logs = log_fixture();
+    let computor = FakeComputor::new().respond(MessageType::RequestLog, MessageType::RespondLog, logs.clone()).start();
+    let client = Client::<Tcp>::new(computor.url()).unwrap();
+
+    let path = std::env::temp_dir().join(format!("qubic-logs-{}.bin", std::process::id()));
+    assert_eq!(client.qu().request_log_to_writer([1, 2, 3, 4], File::create(&path).unwrap()).unwrap(), logs.len());
+    assert_eq!(std::fs::read(&path).unwrap(), logs);
+
+    let mut parser = LogParser::new(BufReader::new(File::open(&path).unwrap()));
+    assert_eq!(parser.by_ref().take(1500).filter(Result::is_ok).count(), 1500);
+    assert!(matches!(parser.next(), Some(Err(LogParseError::Truncated { .. }))));
+    assert!(parser.next().is_none());
+    std::fs::remove_file(&path).unwrap();
+
+    // logs above the maximum are rejected before anything is written
+    let mut written = Vec::new();
+    let res = client.qu_with(RequestOptions::new().with_max_log_size(1_000)).request_log_to_writer([1, 2, 3, 4], &mut written);
+    assert!(matches!(res, Err(errors::ClientError::ResponseTooLarge { size, max: 1_000 }) if size == logs.len()));
+    assert!(written.is_empty());
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_request_log_to_writer() {
+    use qubic_tcp_types::types::qlogging::{LogParseError, LogParser};
+    use transport::RequestOptions;
+
+    let logs = log_fixture();
+    let computor = FakeComputor::new().respond(MessageType::RequestLog, MessageType::RespondLog, logs.clone()).start();
+    let client = Client::<Tcp>::new(computor.url()).await.unwrap();
+
+    let mut written = Vec::new();
+    assert_eq!(client.qu().request_log_to_writer([1, 2, 3, 4], &mut written).await.unwrap(), logs.len());
+    assert_eq!(written, logs);
+
+    let mut parser = LogParser::new(written.as_slice());
+    assert_eq!(parser.by_ref().take(1500).filter(Result::is_ok).count(), 1500);
+    assert!(matches!(parser.next(), Some(Err(LogParseError::Truncated { .. }))));
+
+    let mut written = Vec::new();
+    let res = client.qu_with(RequestOptions::new().with_max_log_size(1_000)).request_log_to_writer([1, 2, 3, 4], &mut written).await;
+    assert!(matches!(res, Err(errors::ClientError::ResponseTooLarge { size, max: 1_000 }) if size == logs.len()));
+    assert!(written.is_empty());
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_interceptors() {
+    use std::{sync::{Arc, Mutex}, time::Duration};
+    use crate::{client::ClientBuilder, interceptor::{MetricsInterceptor, RequestMetrics}};
+
+    let (info, computor) = intercepted_computor();
+    let (events, metrics) = (Arc::new(Mutex::new(Vec::new())), Arc::new(MetricsInterceptor::new()));
+    let client = ClientBuilder::<Tcp>::new(computor.url())
+        .with_read_timeout(Duration::from_millis(50))
+        .with_interceptor(RecordingInterceptor { name: "outer", events: events.clone() })
+        .with_interceptor(PanickingInterceptor)
+        .with_interceptor(RecordingInterceptor { name: "inner", events: events.clone() })
+        .with_interceptor(metrics.clone())
+        .build()
+        .unwrap();
+
+    // before runs in the order of registration and after in reverse, the panicking interceptor is skipped
+    assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+    assert_eq!(*events.lock().unwrap(), expected_interceptor_events());
+
+    assert!(matches!(client.qu().request_computors(), Err(errors::ClientError::Timeout)));
+    assert_eq!(events.lock().unwrap().last().unwrap(), "outer after Request timed out");
+
+    let tick_info = metrics.get(MessageType::RequestCurrentTickInfo);
+    assert_eq!((tick_info.requests, tick_info.failures, tick_info.bytes_sent), (1, 0, std::mem::size_of::<qubic_tcp_types::Header>() as u64));
+    assert_eq!(RequestMetrics { total_latency: Duration::ZERO, ..metrics.get(MessageType::RequestComputors) }, RequestMetrics { requests: 1, failures: 1, timeouts: 1, bytes_sent: 8, redirects: 0, total_latency: Duration::ZERO });
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_interceptors() {
+    use std::{sync::{Arc, Mutex}, time::Duration};
+    use crate::{client::ClientBuilder, interceptor::{MetricsInterceptor, RequestMetrics}};
+
+    let (info, computor) = intercepted_computor();
+    let (events, metrics) = (Arc::new(Mutex::new(Vec::new())), Arc::new(MetricsInterceptor::new()));
+    let client = ClientBuilder::<Tcp>::new(computor.url())
+        .with_read_timeout(Duration::from_millis(50))
+        .with_interceptor(RecordingInterceptor { name: "outer", events: events.clone() })
+        .with_interceptor(PanickingInterceptor)
+        .with_interceptor(RecordingInterceptor { name: "inner", events: events.clone() })
+        .with_interceptor(metrics.clone())
+        .build()
+        .await
+        .unwrap();
+
+    // before runs in the order of registration and after in reverse, the panicking interceptor is skipped
+    assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+    assert_eq!(*events.lock().unwrap(), expected_interceptor_events());
+
+    assert!(matches!(client.qu().request_computors().await, Err(errors::ClientError::Timeout)));
+    assert_eq!(events.lock().unwrap().last().unwrap(), "outer after Request timed out");
+
+    let tick_info = metrics.get(MessageType::RequestCurrentTickInfo);
+    assert_eq!((tick_info.requests, tick_info.failures, tick_info.bytes_sent), (1, 0, std::mem::size_of::<qubic_tcp_types::Header>() as u64));
+    assert_eq!(RequestMetrics { total_latency: Duration::ZERO, ..metrics.get(MessageType::RequestComputors) }, RequestMetrics { requests: 1, failures: 1, timeouts: 1, bytes_sent: 8, redirects: 0, total_latency: Duration::ZERO });
+}
+
+/// checks the frames of a current tick info request dumped with payloads cut at 4 bytes
+fn check_wire_frames(frames: &[wire_dump::WireFrame], info: CurrentTickInfo) {
+    use qubic_types::traits::ToBytes;
+    use wire_dump::Direction;
+
+    // request, greeting of the computor and response
+    let summary: Vec<_> = frames.iter().map(|frame| (frame.direction, frame.message_type, frame.size, frame.truncated)).collect();
+    assert_eq!(summary, [
+        (Direction::Sent, MessageType::RequestCurrentTickInfo as u8, 8, false),
+        (Direction::Received, MessageType::ExchangePublicPeers as u8, std::mem::size_of::<qubic_tcp_types::types::Packet<ExchangePublicPeers>>(), true),
+        (Direction::Received, MessageType::RespondCurrentTickInfo as u8, 8 + std::mem::size_of::<CurrentTickInfo>(), true)
+    ]);
+
+    assert_eq!(frames[2].dejavu, frames[0].dejavu);
+    assert_eq!(frames[2].payload, info.to_bytes()[..4]);
+    assert!(frames.iter().all(|frame| frame.peer.starts_with("127.0.0.1:")));
+}
+
+/// parses the dump at `path` back into its frames, every frame is written exactly as its line
+fn parse_wire_dump(path: &std::path::Path) -> Vec<wire_dump::WireFrame> {
+    let dump = std::fs::read_to_string(path).unwrap();
+    let frames: Vec<wire_dump::WireFrame> = dump.lines().map(|line| line.parse().unwrap()).collect();
+
+    assert!(dump.lines().zip(&frames).all(|(line, frame)| frame.to_string() == line));
+    assert!("1 sent peer type=x size=8 dejavu=0 ".parse::<wire_dump::WireFrame>().is_err());
+
+    frames
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_wire_dump() {
+    use std::sync::{Arc, Mutex};
+    use crate::{client::ClientBuilder, wire_dump::WireDump};
+
+    let path = std::env::temp_dir().join(format!("qubic-web3-wire-dump-{}-sync.log", std::process::id()));
+    let (info, computor) = intercepted_computor();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = frames.clone();
+    let callback = ClientBuilder::<Tcp>::new(computor.url()).with_wire_dump(WireDump::to_callback(move |frame| recorded.lock().unwrap().push(frame.clone())).with_max_payload(4)).build().unwrap();
+    assert_eq!(callback.qu().get_current_tick_info().unwrap(), info);
+    check_wire_frames(&frames.lock().unwrap(), info);
+
+    let file = ClientBuilder::<Tcp>::new(computor.url()).with_wire_dump(WireDump::to_file(&path).unwrap().with_max_payload(4)).build().unwrap();
+    assert_eq!(file.qu().get_current_tick_info().unwrap(), info);
+    check_wire_frames(&parse_wire_dump(&path), info);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_wire_dump() {
+    use std::sync::{Arc, Mutex};
+    use crate::{client::ClientBuilder, wire_dump::WireDump};
+
+    let path = std::env::temp_dir().join(format!("qubic-web3-wire-dump-{}-async.log", std::process::id()));
+    let (info, computor) = intercepted_computor();
+    let frames = Arc::new(Mutex::new(Vec::new()));
+
+    let recorded = frames.clone();
+    let callback = ClientBuilder::<Tcp>::new(computor.url()).with_wire_dump(WireDump::to_callback(move |frame| recorded.lock().unwrap().push(frame.clone())).with_max_payload(4)).build().await.unwrap();
+    assert_eq!(callback.qu().get_current_tick_info().await.unwrap(), info);
+    check_wire_frames(&frames.lock().unwrap(), info);
+
+    let file = ClientBuilder::<Tcp>::new(computor.url()).with_wire_dump(WireDump::to_file(&path).unwrap().with_max_payload(4)).build().await.unwrap();
+    assert_eq!(file.qu().get_current_tick_info().await.unwrap(), info);
+    check_wire_frames(&parse_wire_dump(&path), info);
+
+    std::fs::remove_file(path).unwrap();
+}
+
+/// local SOCKS5 proxy, or HTTP CONNECT proxy if `http`, requiring `auth` if given. Records the targets it tunneled to
+fn fake_proxy(http: bool, auth: Option<proxy::ProxyAuth>) -> (String, std::sync::Arc<std::sync::Mutex<Vec<String>>>) {
+    use std::{io::{Read, Write}, net::{Ipv4Addr, TcpListener, TcpStream}, sync::Arc};
+    use base64::{engine::general_purpose::STANDARD, Engine};
+
+    fn read_vec(stream: &mut TcpStream, len: usize) -> std::io::Result<Vec<u8>> {
+        let mut buffer = vec![0; len];
+        stream.read_exact(&mut buffer)?;
+
+        Ok(buffer)
+    }
+
+    fn socks5(client: &mut TcpStream, auth: Option<&proxy::ProxyAuth>) -> std::io::Result<Option<String>> {
+        let greeting = read_vec(client, 2)?;
+        let method = if auth.is_some() { 2 } else { 0 };
+
+        if !read_vec(client, greeting[1] as usize)?.contains(&method) {
+            return client.write_all(&[5, 0xFF]).map(|_| None)
+        }
+
+        client.write_all(&[5, method])?;
+
+        if let Some(auth) = auth {
+            let username_len = read_vec(client, 2)?[1] as usize;
+            let username = read_vec(client, username_len)?;
+            let password_len = read_vec(client, 1)?[0] as usize;
+            let password = read_vec(client, password_len)?;
+
+            let accepted = username == auth.username.as_bytes() && password == auth.password.as_bytes();
+            client.write_all(&[1, !accepted as u8])?;
+
+            if !accepted {
+                return Ok(None)
+            }
+        }
+
+        let host = match read_vec(client, 4)?[3] {
+            1 => Ipv4Addr::from(<[u8; 4]>::try_from(read_vec(client, 4)?).unwrap()).to_string(),
+            3 => {
+                let len = read_vec(client, 1)?[0] as usize;
+                String::from_utf8(read_vec(client, len)?).unwrap()
+            },
+            atyp => panic!("unexpected address type {atyp}")
+        };
+        let port = u16::from_be_bytes(read_vec(client, 2)?.try_into().unwrap());
+
+        client.write_all(&[5, 0, 0, 1, 0, 0, 0, 0, 0, 0])?;
+
+        Ok(Some(format!("{host}:{port}")))
+    }
+
+    fn http_connect(client: &mut TcpStream, auth: Option<&proxy::ProxyAuth>) -> std::io::Result<Option<String>> {
+        let mut head = Vec::new();
+
+        while !head.ends_with(b"\r\n\r\n") {
+            head.extend(read_vec(client, 1)?);
+        }
+
+        let head = String::from_utf8(head).unwrap();
+        let target = head.split_whitespace().nth(1).unwrap().to_owned();
+        let authorized = auth.is_none_or(|auth| head.contains(&format!("Proxy-Authorization: Basic {}\r\n", STANDARD.encode(format!("{}:{}", auth.username, auth.password)))));
+
+        match authorized {
+            true => client.write_all(b"HTTP/1.1 200 Connection established\r\n\r\n").map(|_| Some(target)),
+            false => client.write_all(b"HTTP/1.1 407 Proxy Authentication Required\r\n\r\n").map(|_| None)
+        }
+    }
+
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap().to_string();
+    let targets = Arc::new(std::sync::Mutex::new(Vec::new()));
+    let recorded = targets.clone();
+
+    std::thread::spawn(move || {
+        for mut client in listener.incoming().flatten() {
+            let (auth, recorded) = (auth.clone(), recorded.clone());
+
+            std::thread::spawn(move || -> std::io::Result<()> {
+                let target = match http {
+                    true => http_connect(&mut client, auth.as_ref())?,
+                    false => socks5(&mut client, auth.as_ref())?
+                };
+                let Some(target) = target else { return Ok(()) };
+
+                recorded.lock().unwrap().push(target.clone());
+
+                let mut upstream = TcpStream::connect(target)?;
+                let (mut upstream_read, mut client_write) = (upstream.try_clone()?, client.try_clone()?);
+                std::thread::spawn(move || std::io::copy(&mut upstream_read, &mut client_write));
+
+                std::io::copy(&mut client, &mut upstream).map(|_| ())
+            });
+        }
+    });
+
+    (addr, targets)
+}
+
+fn proxy_configs(auth: proxy::ProxyAuth) -> Vec<(proxy::ProxyConfig, std::sync::Arc<std::sync::Mutex<Vec<String>>>)> {
+    use proxy::ProxyConfig;
+
+    let (socks5, socks5_targets) = fake_proxy(false, Some(auth.clone()));
+    let (http, http_targets) = fake_proxy(true, Some(auth.clone()));
+
+    vec![
+        (ProxyConfig::Socks5 { addr: socks5, auth: Some(auth.clone()) }, socks5_targets),
+        (ProxyConfig::HttpConnect { addr: http, auth: Some(auth) }, http_targets)
+    ]
+}
+
+#[test]
+fn test_proxy_config() {
+    use proxy::{ProxyAuth, ProxyConfig};
+
+    assert_eq!("socks5://127.0.0.1:1080".parse::<ProxyConfig>().unwrap(), ProxyConfig::Socks5 { addr: "127.0.0.1:1080".to_owned(), auth: None });
+    assert_eq!("http://user:p@ss@proxy.example.org:3128/".parse::<ProxyConfig>().unwrap(), ProxyConfig::HttpConnect { addr: "proxy.example.org:3128".to_owned(), auth: Some(ProxyAuth::new("user", "p@ss")) });
+
+    for invalid in ["127.0.0.1:1080", "ftp://127.0.0.1:21", "socks5://127.0.0.1", "socks5://user@127.0.0.1:1080", "http://:3128"] {
+        assert!(invalid.parse::<ProxyConfig>().is_err(), "{invalid}");
+    }
+
+    assert!(!format!("{:?}", ProxyAuth::new("user", "secret")).contains("secret"));
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+#[test]
+fn test_proxied_connections() {
+    use crate::{client::ClientBuilder, proxy::{ProxyAuth, ProxyConfig}, transport::ConnectedTcp};
+
+    let (info, _, computor) = fake_network();
+
+    for (proxy, targets) in proxy_configs(ProxyAuth::new("user", "secret")) {
+        let client = ClientBuilder::<Tcp>::new(computor.url()).with_proxy(proxy.clone()).build().unwrap();
+        assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+
+        let client = ClientBuilder::<ConnectedTcp>::new(computor.url()).with_proxy(proxy.clone()).build().unwrap();
+        assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+        assert_eq!(client.qu().get_current_tick_info().unwrap(), info);
+
+        // the pooled connection is opened once
+        assert_eq!(*targets.lock().unwrap(), [computor.url(), computor.url()]);
+
+        let rejected = match proxy {
+            ProxyConfig::Socks5 { addr, .. } => ProxyConfig::Socks5 { addr, auth: Some(ProxyAuth::new("user", "wrong")) },
+            ProxyConfig::HttpConnect { addr, .. } => ProxyConfig::HttpConnect { addr, auth: None }
+        };
+
+        let client = ClientBuilder::<Tcp>::new(computor.url()).with_proxy(rejected).build().unwrap();
+        assert!(matches!(client.qu().get_current_tick_info(), Err(errors::ClientError::Io(_))));
+        assert_eq!(targets.lock().unwrap().len(), 2);
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+#[tokio::test]
+async fn test_proxied_connections() {
+    use crate::{client::ClientBuilder, proxy::{ProxyAuth, ProxyConfig}, transport::{ConnectedTcp, Transport}};
+
+    let (info, _, computor) = fake_network();
+    // host names are resolved by the proxy
+    let url = computor.url().replace("127.0.0.1", "localhost");
+
+    for (proxy, targets) in proxy_configs(ProxyAuth::new("user", "secret")) {
+        let client = ClientBuilder::<Tcp>::new(&url).with_proxy(proxy.clone()).build().await.unwrap();
+        assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+
+        let client = ClientBuilder::<ConnectedTcp>::new(&url).with_proxy(proxy.clone()).build().await.unwrap();
+        assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+        assert_eq!(client.qu().get_current_tick_info().await.unwrap(), info);
+        assert_eq!(client.transport().get_url().await, url);
+
+        // the pooled connection is opened once
+        assert_eq!(*targets.lock().unwrap(), [url.clone(), url.clone()]);
+
+        let rejected = match proxy {
+            ProxyConfig::Socks5 { addr, .. } => ProxyConfig::Socks5 { addr, auth: Some(ProxyAuth::new("user", "wrong")) },
+            ProxyConfig::HttpConnect { addr, .. } => ProxyConfig::HttpConnect { addr, auth: None }
+        };
+
+        let client = ClientBuilder::<Tcp>::new(&url).with_proxy(rejected).build().await.unwrap();
+        assert!(matches!(client.qu().get_current_tick_info().await, Err(errors::ClientError::Io(_))));
+        assert_eq!(targets.lock().unwrap().len(), 2);
+    }
+}
@@ -1,163 +1,553 @@
 
-use std::{cell::RefCell, convert::Infallible, time::Duration};
+use std::{convert::Infallible, net::Ipv4Addr, sync::{atomic::{AtomicBool, AtomicU64, Ordering}, Arc, Weak}, time::{Duration, SystemTime, UNIX_EPOCH}};
 #[cfg(not(any(feature = "async", feature = "http")))]
-use std::{net::TcpStream, io::{Write, Read}};
+use std::{net::{TcpStream, ToSocketAddrs}, io::{Write, Read}, sync::{Mutex, MutexGuard, PoisonError}};
 
 #[cfg(any(feature = "async", feature = "http"))]
-use tokio::net::TcpStream;
+use futures::{channel::mpsc, lock::Mutex, SinkExt};
+#[cfg(any(feature = "async", feature = "http"))]
+use crate::runtime::{self, TcpStream};
 
-use anyhow::Result;
+use crate::{errors::{ClientError, Result}, interceptor::Interceptors, proxy::{self, ProxyConfig}, wire_dump::WireDump};
 
-use qubic_tcp_types::{Header, types::{Packet, ExchangePublicPeers}, MessageType, utils::QubicRequest};
+use qubic_tcp_types::{Header, types::{Packet, ExchangePublicPeers, ticks::{CurrentTickInfo, GetCurrentTickInfo}}, MessageType, utils::QubicRequest};
 use qubic_types::traits::{ToBytes, FromBytes};
 
 #[cfg(any(feature = "async", feature = "http"))]
-use tokio::io::{AsyncWriteExt, AsyncReadExt};
+use futures::io::{AsyncWriteExt, AsyncReadExt};
+
+pub const DEFAULT_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Times `Tcp` follows a busy peer to one of the peers it suggested unless set with `RequestOptions::with_busy_retries`
+pub const DEFAULT_BUSY_RETRIES: usize = 2;
+
+/// Connect, read and write timeouts and the proxy, unset values fall back to the transport's defaults
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct RequestOptions {
+    pub connect_timeout: Option<Duration>,
+    pub read_timeout: Option<Duration>,
+    pub write_timeout: Option<Duration>,
+    pub proxy: Option<ProxyConfig>,
+    pub busy_retries: Option<usize>,
+    /// logs streamed by `Qu::request_log_to_writer` larger than this are rejected, unlimited if `None`
+    pub max_log_size: Option<usize>
+}
+
+impl RequestOptions {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_connect_timeout(mut self, timeout: Duration) -> Self {
+        self.connect_timeout = Some(timeout);
+
+        self
+    }
+
+    pub fn with_read_timeout(mut self, timeout: Duration) -> Self {
+        self.read_timeout = Some(timeout);
+
+        self
+    }
+
+    pub fn with_write_timeout(mut self, timeout: Duration) -> Self {
+        self.write_timeout = Some(timeout);
+
+        self
+    }
+
+    /// sets the read and write timeout
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_read_timeout(timeout).with_write_timeout(timeout)
+    }
+
+    /// connects through `proxy` instead of directly
+    pub fn with_proxy(mut self, proxy: ProxyConfig) -> Self {
+        self.proxy = Some(proxy);
+
+        self
+    }
+
+    /// times a request turned away by a busy peer is sent again to one of the peers it suggested, 0 disables redirects
+    pub fn with_busy_retries(mut self, retries: usize) -> Self {
+        self.busy_retries = Some(retries);
+
+        self
+    }
+
+    /// rejects logs of more than `max` bytes before `Qu::request_log_to_writer` writes any of them
+    pub fn with_max_log_size(mut self, max: usize) -> Self {
+        self.max_log_size = Some(max);
+
+        self
+    }
+
+    /// proxy of the options, falling back to `proxy` of the transport
+    pub(crate) fn proxy_or<'a>(&'a self, proxy: Option<&'a ProxyConfig>) -> Option<&'a ProxyConfig> {
+        self.proxy.as_ref().or(proxy)
+    }
+}
+
+/// Resolved timeouts of a transport
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub struct Timeouts {
+    pub connect: Duration,
+    pub read: Duration,
+    pub write: Duration
+}
+
+impl Default for Timeouts {
+    fn default() -> Self {
+        Self { connect: DEFAULT_TIMEOUT, read: DEFAULT_TIMEOUT, write: DEFAULT_TIMEOUT }
+    }
+}
+
+impl Timeouts {
+    /// returns a copy with every timeout set in `options` replaced
+    pub fn with_overrides(self, options: &RequestOptions) -> Self {
+        Self {
+            connect: options.connect_timeout.unwrap_or(self.connect),
+            read: options.read_timeout.unwrap_or(self.read),
+            write: options.write_timeout.unwrap_or(self.write)
+        }
+    }
+}
+
+impl From<RequestOptions> for Timeouts {
+    fn from(value: RequestOptions) -> Self {
+        Self::default().with_overrides(&value)
+    }
+}
+
+/// peers greet new connections with their public peers, anything else means the response stream is out of sync
+fn expect_public_peers(packet: &[u8]) -> Result<()> {
+    let header = Header::from_bytes(&packet[..std::mem::size_of::<Header>()])?;
+
+    if header.message_type != MessageType::ExchangePublicPeers {
+        return Err(ClientError::UnexpectedMessageType { expected: MessageType::ExchangePublicPeers, got: header.message_type });
+    }
+
+    Ok(())
+}
+
+/// a busy computor greets with its public peers and closes the connection without answering. `PeerClosed` after the
+/// `greeting` payload becomes `PeerBusy`, unless the peer suggested no other peer
+fn busy_or_closed(e: ClientError, greeting: &[u8]) -> ClientError {
+    if !matches!(e, ClientError::PeerClosed) {
+        return e
+    }
+
+    let suggested_peers: Vec<Ipv4Addr> = ExchangePublicPeers::from_bytes(greeting)
+        .map(|greeting| greeting.peers.into_iter().filter(|peer| !peer.is_unspecified()).collect())
+        .unwrap_or_default();
+
+    match suggested_peers.is_empty() {
+        true => e,
+        false => ClientError::PeerBusy { suggested_peers }
+    }
+}
+
+/// dejavu of the request framed in `bytes`
+fn request_dejavu(bytes: &[u8]) -> u32 {
+    u32::from_le_bytes(bytes[4..8].try_into().expect("request starts with a header"))
+}
+
+/// Whether a frame received for the request with `dejavu` answers it. Computors answer with the dejavu of the request,
+/// including the `EndResponse` terminating a stream of responses, another non-zero dejavu marks a frame of an earlier
+/// request. Frames without a dejavu, and any frame for a request without one, cannot be told apart and count as answers
+fn answers(header: &Header, dejavu: u32) -> bool {
+    dejavu == 0 || header.dejavu == 0 || header.dejavu == dejavu
+}
+
+/// url of the first suggested peer not `tried` yet, peers only advertise their address so the port of `url` is kept
+fn redirect_url(url: &str, suggested_peers: &[Ipv4Addr], tried: &[String]) -> Option<String> {
+    suggested_peers.iter()
+        .map(|peer| match url.rsplit_once(':') {
+            Some((_, port)) => format!("{peer}:{port}"),
+            None => peer.to_string()
+        })
+        .find(|next| !tried.contains(next))
+}
+
+/// connects to `url`, through the proxy if one is given
+#[cfg(not(any(feature = "async", feature = "http")))]
+pub(crate) fn connect_stream(url: &str, timeouts: &Timeouts, proxy: Option<&ProxyConfig>) -> std::io::Result<TcpStream> {
+    let mut last_err = None;
+    let addr = proxy.map_or(url, ProxyConfig::addr);
+
+    for addr in addr.to_socket_addrs()? {
+        match TcpStream::connect_timeout(&addr, timeouts.connect) {
+            Ok(mut stream) => {
+                stream.set_read_timeout(Some(timeouts.read))?;
+                stream.set_write_timeout(Some(timeouts.write))?;
+
+                if let Some(proxy) = proxy {
+                    proxy::handshake(&mut stream, proxy, url)?;
+                }
+
+                return Ok(stream)
+            },
+            Err(e) => last_err = Some(e)
+        }
+    }
+
+    Err(last_err.unwrap_or_else(|| std::io::Error::new(std::io::ErrorKind::InvalidInput, format!("could not resolve {addr}"))))
+}
+
+/// connects to `url`, through the proxy if one is given. The connect timeout includes the proxy handshake
+#[cfg(any(feature = "async", feature = "http"))]
+async fn open_stream(url: &str, timeouts: &Timeouts, proxy: Option<&ProxyConfig>) -> std::io::Result<TcpStream> {
+    let connect = async {
+        let Some(proxy) = proxy else { return TcpStream::connect(url).await };
+
+        let mut stream = TcpStream::connect(proxy.addr()).await?;
+        proxy::handshake(&mut stream, proxy, url).await?;
+
+        Ok(stream)
+    };
+
+    match runtime::timeout(timeouts.connect, connect).await {
+        Some(stream) => stream,
+        None => Err(std::io::ErrorKind::TimedOut.into())
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+pub(crate) async fn connect_stream(url: &str, timeouts: &Timeouts, proxy: Option<&ProxyConfig>) -> Result<TcpStream> {
+    Ok(open_stream(url, timeouts, proxy).await?)
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+pub(crate) async fn timed<R>(timeout: Duration, f: impl std::future::Future<Output = std::io::Result<R>>) -> Result<R> {
+    Ok(runtime::timeout(timeout, f).await.ok_or(ClientError::Timeout)??)
+}
 
 #[cfg(not(any(feature = "async", feature = "http")))]
 pub trait Transport {
     type Err;
 
-    fn new(url: String, timeout: Option<std::time::Duration>) -> Result<Box<Self>, Self::Err>;
+    fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err>;
 
-    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<()>;
+    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<()>;
 
-    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T>;
+    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T>;
 
-    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>>;
+    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>>;
 
     fn get_url(&self) -> String;
- 
+
     fn connect(&self) -> Result<TcpStream>;
+
+    /// interceptors to invoke around every send, see `ClientBuilder::with_interceptor`. Ignored by default, for
+    /// transports which do not support them
+    fn set_interceptors(&mut self, _interceptors: Interceptors) {}
+
+    /// dump of the frames sent and received, see `ClientBuilder::with_wire_dump`. Ignored by default, for transports
+    /// which do not support it
+    fn set_wire_dump(&mut self, _dump: WireDump) {}
+
+    /// proxy the transport connects through, see `ClientBuilder::with_proxy`
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        None
+    }
+
+    /// timeouts of the connections of the transport
+    fn timeouts(&self) -> Timeouts {
+        Timeouts::default()
+    }
 }
 
 #[cfg(any(feature = "async", feature = "http"))]
 pub trait Transport {
     type Err;
 
-    async fn new(url: String, timeout: Option<std::time::Duration>) -> Result<Box<Self>, Self::Err>;
+    async fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err>;
+
+    async fn send_without_response(&self, data: impl ToBytes, options: &RequestOptions) -> Result<()>;
+
+    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T>;
+
+    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>>;
 
-    async fn send_without_response(&self, data: impl ToBytes) -> Result<()>;
+    /// like `send_with_multiple_responses`, every response is sent to `responses` once it is received instead of
+    /// collected, the number of received responses is returned. The request is dropped once `responses` is closed.
+    /// Transports which cannot stream the responses send them after the last one was received
+    async fn send_with_streamed_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions, mut responses: mpsc::Sender<T>) -> Result<usize> {
+        let received = self.send_with_multiple_responses::<T, D>(data, options).await?;
+        let count = received.len();
 
-    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T>;
+        for res in received {
+            if responses.send(res).await.is_err() {
+                break;
+            }
+        }
 
-    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>>;
+        Ok(count)
+    }
 
     async fn get_url(&self) -> String;
- 
+
     async fn connect(&self) -> Result<TcpStream>;
+
+    /// interceptors to invoke around every send, see `ClientBuilder::with_interceptor`. Ignored by default, for
+    /// transports which do not support them
+    fn set_interceptors(&mut self, _interceptors: Interceptors) {}
+
+    /// dump of the frames sent and received, see `ClientBuilder::with_wire_dump`. Ignored by default, for transports
+    /// which do not support it
+    fn set_wire_dump(&mut self, _dump: WireDump) {}
+
+    /// proxy the transport connects through, see `ClientBuilder::with_proxy`
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        None
+    }
+
+    /// timeouts of the connections of the transport
+    fn timeouts(&self) -> Timeouts {
+        Timeouts::default()
+    }
 }
 
 pub struct Tcp {
     pub(crate) url: String,
-    pub(crate) timeout: Duration
+    pub(crate) timeouts: Timeouts,
+    pub(crate) interceptors: Interceptors,
+    pub(crate) proxy: Option<ProxyConfig>,
+    pub(crate) wire_dump: WireDump,
+    /// see `RequestOptions::with_busy_retries`
+    pub(crate) busy_retries: usize
 }
 
-/// Default timeout: 5s
+/// Default timeouts: 5s. Requests turned away by a busy computor are sent again to one of the peers it suggested
 #[cfg(any(feature = "async", feature = "http"))]
 impl Transport for Tcp {
     type Err = Infallible;
 
-    async fn new(url: String, timeout: Option<Duration>) -> Result<Box<Self>, Self::Err> {
+    async fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err> {
         Ok(Box::new(Self {
             url,
-            timeout: if let Some(timeout) = timeout { timeout } else { std::time::Duration::from_secs(5) }
+            timeouts: Timeouts::default().with_overrides(&options),
+            interceptors: Interceptors::default(),
+            busy_retries: options.busy_retries.unwrap_or(DEFAULT_BUSY_RETRIES),
+            proxy: options.proxy,
+            wire_dump: WireDump::default()
         }))
     }
 
-    async fn send_without_response(&self, data: impl ToBytes) -> Result<()> {
-        let std_stream = std::net::TcpStream::connect(&self.url)?;
+    async fn send_without_response(&self, data: impl ToBytes, options: &RequestOptions) -> Result<()> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 0, async {
+            let timeouts = self.timeouts.with_overrides(options);
+            let mut stream = connect_stream(&self.url, &timeouts, options.proxy_or(self.proxy.as_ref())).await?;
 
-        std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-        std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+            self.wire_dump.sent(&bytes);
+            timed(timeouts.write, stream.write_all(&bytes)).await?;
 
-        let mut stream = TcpStream::from_std(std_stream)?;
+            Ok(())
+        }).await
+    }
 
-        stream.write_all(&data.to_bytes()).await?;
+    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T> {
+        let bytes = data.to_bytes();
 
-        Ok(())
+        let bytes = &bytes;
+
+        self.interceptors.intercept(&self.url, bytes, |_| 1, self.follow_busy(bytes, options, |url, dump| async move {
+            self.request::<T, D>(&url, &dump, bytes, options).await
+        })).await
     }
 
-    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T> {
-        let std_stream = std::net::TcpStream::connect(&self.url)?;
+    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>> {
+        let bytes = data.to_bytes();
+
+        let bytes = &bytes;
 
-        std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-        std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+        self.interceptors.intercept(&self.url, bytes, Vec::len, self.follow_busy(bytes, options, |url, dump| async move {
+            let mut ret = Vec::new();
+            self.request_multiple(&url, &dump, bytes, options, Responses::Collect(&mut ret)).await?;
 
-        let mut stream = TcpStream::from_std(std_stream)?;
+            Ok(ret)
+        })).await
+    }
+
+    async fn send_with_streamed_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions, responses: mpsc::Sender<T>) -> Result<usize> {
+        let bytes = data.to_bytes();
+
+        let bytes = &bytes;
+
+        // a busy peer turns the request away before any response, so a redirected request never repeats a response
+        self.interceptors.intercept(&self.url, bytes, |received| *received, self.follow_busy(bytes, options, |url, dump| {
+            let mut responses = responses.clone();
+
+            async move { self.request_multiple(&url, &dump, bytes, options, Responses::Stream(&mut responses)).await }
+        })).await
+    }
+
+    async fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    async fn connect(&self) -> Result<TcpStream> {
+        connect_stream(&self.url, &self.timeouts, self.proxy.as_ref()).await
+    }
+
+    fn set_interceptors(&mut self, interceptors: Interceptors) {
+        self.interceptors = interceptors;
+    }
+
+    fn set_wire_dump(&mut self, dump: WireDump) {
+        self.wire_dump = dump.for_peer(&self.url);
+    }
+
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+}
+
+/// destination of the responses of `Tcp::request_multiple`
+#[cfg(any(feature = "async", feature = "http"))]
+enum Responses<'a, T> {
+    Collect(&'a mut Vec<T>),
+    Stream(&'a mut mpsc::Sender<T>)
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl<T> Responses<'_, T> {
+    /// waits until the next response can be pushed, false once the receiver of the streamed responses is gone
+    async fn ready(&mut self) -> bool {
+        match self {
+            Self::Collect(_) => true,
+            Self::Stream(sender) => std::future::poll_fn(|cx| sender.poll_ready(cx)).await.is_ok()
+        }
+    }
+
+    fn push(&mut self, res: T) {
+        match self {
+            Self::Collect(ret) => ret.push(res),
+            // a receiver dropped since `ready` is noticed by the next `ready`
+            Self::Stream(sender) => { let _ = sender.start_send(res); }
+        }
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Tcp {
+    /// sends with `send` to the peer of the transport and follows busy peers to the peers they suggest, at most
+    /// `busy_retries` times
+    async fn follow_busy<R, F: std::future::Future<Output = Result<R>>>(&self, bytes: &[u8], options: &RequestOptions, send: impl Fn(String, WireDump) -> F) -> Result<R> {
+        let mut tried = vec![self.url.clone()];
+        let mut dump = self.wire_dump.clone();
+        let mut retries = options.busy_retries.unwrap_or(self.busy_retries);
+
+        loop {
+            let url = tried.last().unwrap().clone();
+
+            match send(url.clone(), dump.clone()).await {
+                Err(ClientError::PeerBusy { suggested_peers }) if retries > 0 => {
+                    let Some(next) = redirect_url(&self.url, &suggested_peers, &tried) else {
+                        return Err(ClientError::PeerBusy { suggested_peers })
+                    };
+
+                    self.interceptors.redirected(&url, bytes, &next);
+                    dump = self.wire_dump.clone().for_peer(&next);
+                    tried.push(next);
+                    retries -= 1;
+                },
+                res => return res
+            }
+        }
+    }
+
+    async fn request<T: FromBytes, D: QubicRequest>(&self, url: &str, dump: &WireDump, bytes: &[u8], options: &RequestOptions) -> Result<T> {
+        let timeouts = self.timeouts.with_overrides(options);
+        let mut stream = connect_stream(url, &timeouts, options.proxy_or(self.proxy.as_ref())).await?;
 
         let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
-        stream.write_all(&data.to_bytes()).await?;
+        dump.sent(bytes);
+        timed(timeouts.write, stream.write_all(bytes)).await?;
 
-        stream.read_exact(&mut header_buffer).await?;
+        timed(timeouts.read, stream.read_exact(&mut header_buffer)).await?;
 
-        let mut header = Header::from_bytes(&header_buffer).unwrap();
+        let mut header = Header::from_bytes(&header_buffer)?;
 
         let offset = header.message_type == MessageType::ExchangePublicPeers && D::get_message_type() != MessageType::ExchangePublicPeers;
 
         if offset {
             let mut flush_buf = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-            stream.read_exact(&mut flush_buf).await?;
-            drop(flush_buf);
+            timed(timeouts.read, stream.read_exact(&mut flush_buf)).await?;
+            dump.received(&header_buffer, &flush_buf);
 
-            stream.read_exact(&mut header_buffer).await?;
+            timed(timeouts.read, stream.read_exact(&mut header_buffer)).await.map_err(|e| busy_or_closed(e, &flush_buf))?;
 
-            header = Header::from_bytes(&header_buffer).unwrap();
+            header = Header::from_bytes(&header_buffer)?;
         }
 
         let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-        stream.read_exact(&mut data_buffer).await?;
+        timed(timeouts.read, stream.read_exact(&mut data_buffer)).await?;
 
-        let res = T::from_bytes(&data_buffer).unwrap();
+        dump.received(&header_buffer, &data_buffer);
+
+        let res = T::from_bytes(&data_buffer)?;
 
         Ok(res)
     }
 
-    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>> {
-        let mut ret: Vec<T> = Vec::new();
+    /// pushes the responses to `responses` as they are received and returns their number, stops early once a streamed
+    /// receiver is gone
+    async fn request_multiple<T: FromBytes>(&self, url: &str, dump: &WireDump, bytes: &[u8], options: &RequestOptions, mut responses: Responses<'_, T>) -> Result<usize> {
+        let mut received = 0;
+
+        let timeouts = self.timeouts.with_overrides(options);
+        let mut stream = connect_stream(url, &timeouts, options.proxy_or(self.proxy.as_ref())).await?;
+
+        let mut greeting = vec![0; std::mem::size_of::<Packet<ExchangePublicPeers>>()];
+        dump.sent(bytes);
+        timed(timeouts.write, stream.write_all(bytes)).await?;
+        timed(timeouts.read, stream.read_exact(&mut greeting)).await?;
+        let (greeting_header, greeting) = greeting.split_at(std::mem::size_of::<Header>());
+        dump.received(greeting_header, greeting);
+        expect_public_peers(greeting_header)?;
+        let dejavu = request_dejavu(bytes);
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
 
-        let std_stream = std::net::TcpStream::connect(&self.url)?;
+        loop {
+            timed(timeouts.read, stream.read_exact(&mut header_buffer)).await.map_err(|e| match received {
+                0 => busy_or_closed(e, greeting),
+                _ => e
+            })?;
 
-        std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-        std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
+            let header = Header::from_bytes(&header_buffer)?;
 
-        let mut stream = TcpStream::from_std(std_stream)?;
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-        let mut header_buffer = vec![0; std::mem::size_of::<Packet<ExchangePublicPeers>>()];
-        stream.write_all(&data.to_bytes()).await?;
-        stream.read_exact(&mut header_buffer).await?;
-        header_buffer = vec![0; std::mem::size_of::<Header>()];
+            timed(timeouts.read, stream.read_exact(&mut data_buffer)).await?;
 
-        loop {
-            stream.read_exact(&mut header_buffer).await?;
+            dump.received(&header_buffer, &data_buffer);
 
-            let header = Header::from_bytes(&header_buffer).unwrap();
+            if !answers(&header, dejavu) {
+                continue;
+            }
 
             if header.message_type == MessageType::EndResponse {
                 break;
             }
 
-            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
-
-            
-            stream.read_exact(&mut data_buffer).await?;
-
-            let res = T::from_bytes(&data_buffer).unwrap();
+            if !responses.ready().await {
+                break;
+            }
 
-            ret.push(res);
+            responses.push(T::from_bytes(&data_buffer)?);
+            received += 1;
         }
-        
-        Ok(ret)
-    }
-
-    async fn get_url(&self) -> String {
-        self.url.clone()
-    }
 
-    async fn connect(&self) -> Result<TcpStream> {
-        Ok(TcpStream::connect(&self.url).await?)
+        Ok(received)
     }
 }
 
@@ -165,30 +555,105 @@ impl Transport for Tcp {
 impl Transport for Tcp {
     type Err = Infallible;
 
-    /// defaults timeout to 5 seconds if parameter is None
-    fn new(url: String, timeout: Option<Duration>) -> Result<Box<Self>, Self::Err> {
+    /// defaults unset timeouts to 5 seconds
+    fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err> {
         Ok(Box::new(Self {
             url,
-            timeout: if let Some(timeout) = timeout { timeout } else { std::time::Duration::from_secs(5) }
+            timeouts: Timeouts::default().with_overrides(&options),
+            interceptors: Interceptors::default(),
+            busy_retries: options.busy_retries.unwrap_or(DEFAULT_BUSY_RETRIES),
+            proxy: options.proxy,
+            wire_dump: WireDump::default()
         }))
     }
 
-    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<()> {
-        let mut stream = TcpStream::connect(&self.url)?;
-        stream.set_write_timeout(Some(self.timeout))?;
+    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<()> {
+        let bytes = data.to_bytes();
 
-        stream.write_all(&data.to_bytes())?;
-        Ok(())
+        self.interceptors.intercept(&self.url, &bytes, |_| 0, || {
+            let mut stream = connect_stream(&self.url, &self.timeouts.with_overrides(options), options.proxy_or(self.proxy.as_ref()))?;
+
+            self.wire_dump.sent(&bytes);
+            stream.write_all(&bytes)?;
+            Ok(())
+        })
+    }
+
+    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 1, || {
+            self.follow_busy(&bytes, options, |url, dump| self.request::<T, D>(url, dump, &bytes, options))
+        })
+    }
+
+    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, Vec::len, || {
+            self.follow_busy(&bytes, options, |url, dump| self.request_multiple(url, dump, &bytes, options))
+        })
+    }
+
+    fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        Ok(connect_stream(&self.url, &self.timeouts, self.proxy.as_ref())?)
+    }
+
+    fn set_interceptors(&mut self, interceptors: Interceptors) {
+        self.interceptors = interceptors;
+    }
+
+    fn set_wire_dump(&mut self, dump: WireDump) {
+        self.wire_dump = dump.for_peer(&self.url);
     }
 
-    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T> {
-        let mut stream = TcpStream::connect(&self.url)?;
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
 
-        stream.set_read_timeout(Some(self.timeout))?;
-        stream.set_write_timeout(Some(self.timeout))?;
+    fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl Tcp {
+    /// sends with `send` to the peer of the transport and follows busy peers to the peers they suggest, at most
+    /// `busy_retries` times
+    fn follow_busy<R>(&self, bytes: &[u8], options: &RequestOptions, send: impl Fn(&str, &WireDump) -> Result<R>) -> Result<R> {
+        let mut tried = vec![self.url.clone()];
+        let mut dump = self.wire_dump.clone();
+        let mut retries = options.busy_retries.unwrap_or(self.busy_retries);
+
+        loop {
+            let url = tried.last().unwrap();
+
+            match send(url, &dump) {
+                Err(ClientError::PeerBusy { suggested_peers }) if retries > 0 => {
+                    let Some(next) = redirect_url(&self.url, &suggested_peers, &tried) else {
+                        return Err(ClientError::PeerBusy { suggested_peers })
+                    };
+
+                    self.interceptors.redirected(url, bytes, &next);
+                    dump = self.wire_dump.clone().for_peer(&next);
+                    tried.push(next);
+                    retries -= 1;
+                },
+                res => return res
+            }
+        }
+    }
+
+    fn request<T: FromBytes, D: QubicRequest>(&self, url: &str, dump: &WireDump, bytes: &[u8], options: &RequestOptions) -> Result<T> {
+        let mut stream = connect_stream(url, &self.timeouts.with_overrides(options), options.proxy_or(self.proxy.as_ref()))?;
 
         let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
-        stream.write_all(&data.to_bytes())?;
+        dump.sent(bytes);
+        stream.write_all(bytes)?;
 
         stream.read_exact(&mut header_buffer)?;
 
@@ -200,9 +665,9 @@ impl Transport for Tcp {
             let mut flush_buf = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
             stream.read_exact(&mut flush_buf)?;
-            drop(flush_buf);
+            dump.received(&header_buffer, &flush_buf);
 
-            stream.read_exact(&mut header_buffer)?;
+            stream.read_exact(&mut header_buffer).map_err(|e| busy_or_closed(e.into(), &flush_buf))?;
 
             header = Header::from_bytes(&header_buffer)?;
         }
@@ -211,334 +676,702 @@ impl Transport for Tcp {
 
         stream.read_exact(&mut data_buffer)?;
 
+        dump.received(&header_buffer, &data_buffer);
+
         let res = T::from_bytes(&data_buffer)?;
 
         Ok(res)
     }
 
-    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>> {
+    fn request_multiple<T: FromBytes>(&self, url: &str, dump: &WireDump, bytes: &[u8], options: &RequestOptions) -> Result<Vec<T>> {
         let mut ret: Vec<T> = Vec::new();
 
-        let mut stream = TcpStream::connect(&self.url)?;
+        let mut stream = connect_stream(url, &self.timeouts.with_overrides(options), options.proxy_or(self.proxy.as_ref()))?;
 
-        stream.set_read_timeout(Some(self.timeout))?;
-        stream.set_write_timeout(Some(self.timeout))?;
-
-        let mut header_buffer = vec![0; std::mem::size_of::<Packet<ExchangePublicPeers>>()];
-        stream.write_all(&data.to_bytes())?;
-        stream.read_exact(&mut header_buffer)?;
-        header_buffer = vec![0; std::mem::size_of::<Header>()];
+        let mut greeting = vec![0; std::mem::size_of::<Packet<ExchangePublicPeers>>()];
+        dump.sent(bytes);
+        stream.write_all(bytes)?;
+        stream.read_exact(&mut greeting)?;
+        let (greeting_header, greeting) = greeting.split_at(std::mem::size_of::<Header>());
+        dump.received(greeting_header, greeting);
+        expect_public_peers(greeting_header)?;
+        let dejavu = request_dejavu(bytes);
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
 
         loop {
-            stream.read_exact(&mut header_buffer)?;
+            stream.read_exact(&mut header_buffer).map_err(|e| match ret.is_empty() {
+                true => busy_or_closed(e.into(), greeting),
+                false => e.into()
+            })?;
 
-            let header = unsafe { *(header_buffer.as_ptr() as *const Header) };
+            let header = Header::from_bytes(&header_buffer)?;
 
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+
+            stream.read_exact(&mut data_buffer)?;
+
+            dump.received(&header_buffer, &data_buffer);
+
+            if !answers(&header, dejavu) {
+                continue;
+            }
 
             if header.message_type == MessageType::EndResponse {
                 break;
             }
 
-            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
-
-            
-            stream.read_exact(&mut data_buffer)?;
-
             let res = T::from_bytes(&data_buffer)?;
 
             ret.push(res);
         }
-        
+
         Ok(ret)
     }
+}
 
-    fn get_url(&self) -> String {
-        self.url.clone()
+/// Health of a `ConnectedTcp`, shared with its heartbeat
+#[derive(Debug)]
+pub struct ConnectionHealth {
+    healthy: AtomicBool,
+    reconnect: AtomicBool,
+    /// milliseconds since the UNIX epoch
+    last_activity: AtomicU64,
+    /// frames of earlier requests received, see `ConnectedTcp::cross_talk`
+    cross_talk: AtomicU64
+}
+
+impl ConnectionHealth {
+    fn new() -> Self {
+        let health = Self {
+            healthy: AtomicBool::new(true),
+            reconnect: AtomicBool::new(false),
+            last_activity: AtomicU64::new(0),
+            cross_talk: AtomicU64::new(0)
+        };
+
+        health.touch();
+        health
     }
 
-    fn connect(&self) -> Result<TcpStream> {
-        Ok(TcpStream::connect(&self.url)?)
+    fn now() -> u64 {
+        SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+    }
+
+    pub fn is_healthy(&self) -> bool {
+        self.healthy.load(Ordering::Relaxed)
+    }
+
+    fn touch(&self) {
+        self.last_activity.store(Self::now(), Ordering::Relaxed);
+    }
+
+    fn idle_for(&self) -> Duration {
+        Duration::from_millis(Self::now().saturating_sub(self.last_activity.load(Ordering::Relaxed)))
+    }
+
+    fn set_healthy(&self, healthy: bool) {
+        self.healthy.store(healthy, Ordering::Relaxed);
+    }
+
+    /// the pooled socket could not be replaced after a failure, it is replaced before the next request
+    fn mark_dead(&self) {
+        self.set_healthy(false);
+        self.reconnect.store(true, Ordering::Relaxed);
+    }
+
+    fn take_reconnect(&self) -> bool {
+        self.reconnect.swap(false, Ordering::Relaxed)
+    }
+
+    /// whether the frame answers the request with `dejavu`. A frame of an earlier request poisons the connection, the
+    /// responses to come may be out of sync as well: the socket is replaced before the next request instead of reused
+    fn check_answer(&self, header: &Header, dejavu: u32) -> bool {
+        if answers(header, dejavu) {
+            return true
+        }
+
+        // public peers are pushed unsolicited, with a dejavu of their own
+        if header.message_type != MessageType::ExchangePublicPeers {
+            self.cross_talk.fetch_add(1, Ordering::Relaxed);
+            self.reconnect.store(true, Ordering::Relaxed);
+        }
+
+        false
     }
 }
 
+/// the pooled socket most likely went stale, worth retrying on a fresh connection
+fn is_stale(e: &ClientError) -> bool {
+    matches!(e, ClientError::PeerClosed | ClientError::Io(_))
+}
+
+/// What the heartbeat of a `ConnectedTcp` holds of it, the transport is only referenced weakly
+struct Heartbeat {
+    stream: Weak<Mutex<TcpStream>>,
+    health: Weak<ConnectionHealth>,
+    url: String,
+    timeouts: Timeouts,
+    proxy: Option<ProxyConfig>,
+    wire_dump: WireDump
+}
+
+/// Transport keeping a single connection open. Requests are serialized on the connection,
+/// so one `ConnectedTcp` can be shared between threads or tasks.
+///
+/// A frame carrying the dejavu of an earlier request (e.g. an answer arriving after its request timed out) is discarded
+/// and poisons the connection, it is replaced before the next request. Frames with a dejavu of 0 count as answers and
+/// unsolicited `ExchangePublicPeers` never poison it, see `cross_talk`
 pub struct ConnectedTcp {
-    pub stream: RefCell<TcpStream>,
+    /// shared with the heartbeat, which probes the pooled socket
+    pub stream: Arc<Mutex<TcpStream>>,
     pub url: String,
-    timeout: Duration
+    timeouts: Timeouts,
+    health: Arc<ConnectionHealth>,
+    interceptors: Interceptors,
+    proxy: Option<ProxyConfig>,
+    wire_dump: WireDump
 }
 
-#[cfg(not(any(feature = "async", feature = "http")))]
-impl Transport for ConnectedTcp {
-    type Err = std::io::Error;
+impl ConnectedTcp {
+    /// false if the last request or heartbeat failed
+    pub fn is_healthy(&self) -> bool {
+        self.health.is_healthy()
+    }
 
-    fn new(url: String, timeout: Option<std::time::Duration>) -> Result<Box<Self>, Self::Err> {
-        let stream = TcpStream::connect(&url)?;
-        let timeout = if let Some(timeout) = timeout { timeout } else { Duration::from_secs(5) };
- 
-        stream.set_read_timeout(Some(timeout))?;
-        stream.set_write_timeout(Some(timeout))?;
-        Ok(
-            Box::new(Self {
-                stream: RefCell::new(stream),
-                url,
-                timeout
-            })
-        )
+    /// frames of earlier requests (e.g. answers arriving after their request timed out) discarded so far. Each of them
+    /// poisoned the connection, which was replaced before the next request
+    pub fn cross_talk(&self) -> u64 {
+        self.health.cross_talk.load(Ordering::Relaxed)
+    }
+
+    fn heartbeat(&self) -> Heartbeat {
+        Heartbeat {
+            stream: Arc::downgrade(&self.stream),
+            health: Arc::downgrade(&self.health),
+            url: self.url.clone(),
+            timeouts: self.timeouts,
+            proxy: self.proxy.clone(),
+            wire_dump: self.wire_dump.clone()
+        }
     }
+}
 
-    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<()> {
-        let mut self_stream = self.stream.borrow_mut();
-        match self_stream.write_all(&data.to_bytes()) {
+const _: fn() = || {
+    fn assert_send_sync<T: Send + Sync>() {}
 
-            // auto reconnection
-            Err(e) => {
-                let addr = self.get_url();
-                let stream = TcpStream::connect(addr)?;
-                stream.set_read_timeout(Some(self.timeout))?;
-                stream.set_write_timeout(Some(self.timeout))?;
-                *self_stream = stream;
-
-                return Err(e.into())
-            },
-            _ => ()
-        };
+    assert_send_sync::<crate::client::Client<ConnectedTcp>>();
+};
 
-        Ok(())
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl ConnectedTcp {
+    /// a request that panicked mid-flight leaves a connection out of sync, which the next failing request replaces
+    fn lock(&self) -> MutexGuard<'_, TcpStream> {
+        self.stream.lock().unwrap_or_else(PoisonError::into_inner)
     }
 
-    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T> {
+    /// applies the per-call timeouts to the open stream and reconnects if the heartbeat found the computor dead
+    fn prepare(&self, stream: &mut TcpStream, options: &RequestOptions) -> Result<()> {
+        if self.health.take_reconnect() {
+            self.reconnect(stream, options)?;
+        }
 
-        let res: Result<T> = {
-            self.stream.borrow_mut().flush()?;
+        let timeouts = self.timeouts.with_overrides(options);
 
-            let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
-            self.stream.borrow_mut().write_all(&data.to_bytes())?;
+        stream.set_read_timeout(Some(timeouts.read))?;
+        stream.set_write_timeout(Some(timeouts.write))?;
 
-            self.stream.borrow_mut().read_exact(&mut header_buffer)?;
+        Ok(())
+    }
 
-            let mut header = Header::from_bytes(&header_buffer)?;
+    fn reconnect(&self, stream: &mut TcpStream, options: &RequestOptions) -> Result<()> {
+        *stream = connect_stream(&self.url, &self.timeouts.with_overrides(options), options.proxy_or(self.proxy.as_ref()))?;
 
-            let offset = header.message_type == MessageType::ExchangePublicPeers && D::get_message_type() != MessageType::ExchangePublicPeers;
+        Ok(())
+    }
 
-            if offset {
-                let mut flush_buf = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+    /// records the outcome of a request, failed requests leave a fresh connection behind. The error of the request is
+    /// returned even if reconnecting fails as well, the next request tries to reconnect again
+    fn settle<T>(&self, stream: &mut TcpStream, res: Result<T>, options: &RequestOptions) -> Result<T> {
+        self.health.set_healthy(res.is_ok());
+        self.health.touch();
 
-                self.stream.borrow_mut().read_exact(&mut flush_buf)?;
-                drop(flush_buf);
+        if res.is_err() && self.reconnect(stream, options).is_err() {
+            self.health.mark_dead();
+        }
 
-                self.stream.borrow_mut().read_exact(&mut header_buffer)?;
+        res
+    }
 
-                header = Header::from_bytes(&header_buffer)?;
-            }
+    /// skips the public peers and frames of earlier requests
+    fn request<T: FromBytes>(stream: &mut TcpStream, bytes: &[u8], skip_public_peers: bool, dump: &WireDump, health: &ConnectionHealth) -> Result<T> {
+        stream.flush()?;
 
-            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+        let dejavu = request_dejavu(bytes);
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
+        dump.sent(bytes);
+        stream.write_all(bytes)?;
+
+        loop {
+            stream.read_exact(&mut header_buffer)?;
 
-            self.stream.borrow_mut().read_exact(&mut data_buffer)?;
+            let header = Header::from_bytes(&header_buffer)?;
 
-            let res = T::from_bytes(&data_buffer)?;
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-            Ok(res)
-        };
-        
+            stream.read_exact(&mut data_buffer)?;
 
-        match res {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                let stream = TcpStream::connect(self.get_url())?;
-                stream.set_read_timeout(Some(self.timeout))?;
-                stream.set_write_timeout(Some(self.timeout))?;
-                *self.stream.borrow_mut() = stream;
+            dump.received(&header_buffer, &data_buffer);
 
-                Err(e.into())
+            if (skip_public_peers && header.message_type == MessageType::ExchangePublicPeers) || !health.check_answer(&header, dejavu) {
+                continue;
             }
+
+            return Ok(T::from_bytes(&data_buffer)?)
         }
     }
 
-    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>> {
+    /// responses up to the `EndResponse` of the request, frames of earlier requests are skipped
+    fn request_multiple<T: FromBytes>(stream: &mut TcpStream, bytes: &[u8], dump: &WireDump, health: &ConnectionHealth) -> Result<Vec<T>> {
+        let mut ret: Vec<T> = Vec::new();
+        let dejavu = request_dejavu(bytes);
+        stream.flush()?;
+        dump.sent(bytes);
+        stream.write_all(bytes)?;
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
 
-        let res: Result<Vec<T>> = {
-            let mut ret: Vec<T> = Vec::new();
-            self.stream.borrow_mut().flush()?;
-            self.stream.borrow_mut().write_all(&data.to_bytes())?;
-            let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
+        loop {
+            stream.read_exact(&mut header_buffer)?;
 
-            loop {
-                self.stream.borrow_mut().read_exact(&mut header_buffer)?;
+            let header = Header::from_bytes(&header_buffer)?;
 
-                let header = unsafe { *(header_buffer.as_ptr() as *const Header) };
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
+            stream.read_exact(&mut data_buffer)?;
 
-                if header.message_type == MessageType::EndResponse {
-                    break;
-                }
+            dump.received(&header_buffer, &data_buffer);
+
+            if !health.check_answer(&header, dejavu) {
+                continue;
+            }
+
+            if header.message_type == MessageType::EndResponse {
+                break;
+            }
 
-                let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+            ret.push(T::from_bytes(&data_buffer)?);
+        }
 
-                
-                self.stream.borrow_mut().read_exact(&mut data_buffer)?;
+        Ok(ret)
+    }
 
-                let res = T::from_bytes(&data_buffer)?;
+    /// probes the pooled connection with `RequestCurrentTickInfo` whenever the transport was idle for `interval`, a
+    /// connection failing the probe is replaced right away. The heartbeat stops once the transport is dropped
+    pub fn start_heartbeat(&self, interval: Duration) -> Result<()> {
+        let heartbeat = self.heartbeat();
 
-                ret.push(res);
+        std::thread::Builder::new().name("qubic-heartbeat".to_string()).spawn(move || {
+            loop {
+                std::thread::sleep(interval);
+
+                if !heartbeat.beat(interval) {
+                    break;
+                }
             }
-            
-            Ok(ret)
+        })?;
+
+        Ok(())
+    }
+}
+
+#[cfg(not(any(feature = "async", feature = "http")))]
+impl Heartbeat {
+    /// probes the pooled socket unless a request used it within `interval` or holds it right now, false once the
+    /// transport was dropped
+    fn beat(&self, interval: Duration) -> bool {
+        let (Some(stream), Some(health)) = (self.stream.upgrade(), self.health.upgrade()) else { return false };
+
+        if health.idle_for() < interval {
+            return true
+        }
+
+        let mut stream = match stream.try_lock() {
+            Ok(stream) => stream,
+            Err(std::sync::TryLockError::Poisoned(e)) => e.into_inner(),
+            Err(std::sync::TryLockError::WouldBlock) => return true
         };
-        
-        match res {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                let stream = TcpStream::connect(self.get_url())?;
-                stream.set_read_timeout(Some(self.timeout))?;
-                stream.set_write_timeout(Some(self.timeout))?;
-                *self.stream.borrow_mut() = stream;
-
-                Err(e.into())
+
+        let res = self.probe(&mut stream, &health);
+        health.set_healthy(res.is_ok());
+        health.touch();
+
+        if res.is_err() {
+            match connect_stream(&self.url, &self.timeouts, self.proxy.as_ref()) {
+                Ok(fresh) => *stream = fresh,
+                Err(_) => health.mark_dead()
             }
         }
-    }
 
-    fn get_url(&self) -> String {
-        self.url.clone()
+        true
     }
 
-    fn connect(&self) -> Result<TcpStream> {
-        Ok(self.stream.borrow().try_clone()?)
+    fn probe(&self, stream: &mut TcpStream, health: &ConnectionHealth) -> Result<CurrentTickInfo> {
+        if health.take_reconnect() {
+            *stream = connect_stream(&self.url, &self.timeouts, self.proxy.as_ref())?;
+        }
+
+        stream.set_read_timeout(Some(self.timeouts.read))?;
+        stream.set_write_timeout(Some(self.timeouts.write))?;
+
+        let bytes = Packet::new(GetCurrentTickInfo, true)?.to_bytes();
+
+        ConnectedTcp::request(stream, &bytes, true, &self.wire_dump, health)
     }
 }
 
-#[cfg(any(feature = "async", feature = "http"))]
+#[cfg(not(any(feature = "async", feature = "http")))]
 impl Transport for ConnectedTcp {
     type Err = std::io::Error;
 
-    async fn new(url: String, timeout: Option<std::time::Duration>) -> Result<Box<Self>, Self::Err> {
-        let std_stream = std::net::TcpStream::connect(url.clone())?;
-        let timeout = if let Some(timeout) = timeout { timeout } else { Duration::from_secs(5) };
+    fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err> {
+        let timeouts = Timeouts::default().with_overrides(&options);
+        let stream = connect_stream(&url, &timeouts, options.proxy.as_ref())?;
 
-        std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-        std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
-        let stream = TcpStream::from_std(std_stream)?;
         Ok(
             Box::new(Self {
-                stream: RefCell::new(stream),
+                stream: Arc::new(Mutex::new(stream)),
                 url,
-                timeout
+                timeouts,
+                health: Arc::new(ConnectionHealth::new()),
+                interceptors: Interceptors::default(),
+                proxy: options.proxy,
+                wire_dump: WireDump::default()
             })
         )
     }
 
-    async fn send_without_response(&self, data: impl ToBytes) -> Result<()> {
-        if let Err(e) = self.stream.borrow_mut().write_all(&data.to_bytes()).await {
-            let std_stream = std::net::TcpStream::connect(self.get_url().await)?;
-            std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-            std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
-            *self.stream.borrow_mut() = TcpStream::from_std(std_stream)?;
+    fn send_without_response<D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<()> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 0, || {
+            let mut stream = self.lock();
+            self.prepare(&mut stream, options)?;
+
+            self.wire_dump.sent(&bytes);
+            let res = stream.write_all(&bytes).map_err(ClientError::from);
+
+            // auto reconnection
+            self.settle(&mut stream, res, options)
+        })
+    }
+
+    /// retries once on a fresh connection if the pooled socket went stale
+    fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T> {
+        let bytes = data.to_bytes();
+        let skip_public_peers = D::get_message_type() != MessageType::ExchangePublicPeers;
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 1, || {
+            let mut stream = self.lock();
+            self.prepare(&mut stream, options)?;
+
+            let res = match Self::request(&mut stream, &bytes, skip_public_peers, &self.wire_dump, &self.health) {
+                Err(e) if is_stale(&e) => match self.reconnect(&mut stream, options) {
+                    Ok(()) => Self::request(&mut stream, &bytes, skip_public_peers, &self.wire_dump, &self.health),
+                    Err(_) => Err(e)
+                },
+                res => res
+            };
+
+            self.settle(&mut stream, res, options)
+        })
+    }
+
+    fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, Vec::len, || {
+            let mut stream = self.lock();
+            self.prepare(&mut stream, options)?;
+
+            let res = Self::request_multiple(&mut stream, &bytes, &self.wire_dump, &self.health);
+
+            self.settle(&mut stream, res, options)
+        })
+    }
+
+    fn get_url(&self) -> String {
+        self.url.clone()
+    }
+
+    fn connect(&self) -> Result<TcpStream> {
+        Ok(self.lock().try_clone()?)
+    }
+
+    fn set_interceptors(&mut self, interceptors: Interceptors) {
+        self.interceptors = interceptors;
+    }
+
+    fn set_wire_dump(&mut self, dump: WireDump) {
+        self.wire_dump = dump.for_peer(&self.url);
+    }
+
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+}
 
-            return Err(e.into())
+#[cfg(any(feature = "async", feature = "http"))]
+impl ConnectedTcp {
+    /// reconnects if the heartbeat found the computor dead
+    async fn prepare(&self, stream: &mut TcpStream, options: &RequestOptions) -> Result<()> {
+        if self.health.take_reconnect() {
+            self.reconnect(stream, options).await?;
         }
 
         Ok(())
     }
 
-    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<T> {
+    async fn reconnect(&self, stream: &mut TcpStream, options: &RequestOptions) -> Result<()> {
+        *stream = connect_stream(&self.url, &self.timeouts.with_overrides(options), options.proxy_or(self.proxy.as_ref())).await?;
 
-        let res: Result<T> = {
+        Ok(())
+    }
 
-            self.stream.borrow_mut().flush().await?;
+    /// records the outcome of a request, failed requests leave a fresh connection behind. The error of the request is
+    /// returned even if reconnecting fails as well, the next request tries to reconnect again
+    async fn settle<T>(&self, stream: &mut TcpStream, res: Result<T>, options: &RequestOptions) -> Result<T> {
+        self.health.set_healthy(res.is_ok());
+        self.health.touch();
 
-            let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
-            self.stream.borrow_mut().write_all(&data.to_bytes()).await?;
+        if res.is_err() && self.reconnect(stream, options).await.is_err() {
+            self.health.mark_dead();
+        }
 
-            self.stream.borrow_mut().read_exact(&mut header_buffer).await?;
+        res
+    }
 
-            let mut header = Header::from_bytes(&header_buffer).unwrap();
+    /// skips the public peers and frames of earlier requests
+    async fn request<T: FromBytes>(stream: &mut TcpStream, bytes: &[u8], skip_public_peers: bool, timeouts: &Timeouts, dump: &WireDump, health: &ConnectionHealth) -> Result<T> {
+        timed(timeouts.write, stream.flush()).await?;
 
-            let offset = header.message_type == MessageType::ExchangePublicPeers && D::get_message_type() != MessageType::ExchangePublicPeers;
+        let dejavu = request_dejavu(bytes);
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
+        dump.sent(bytes);
+        timed(timeouts.write, stream.write_all(bytes)).await?;
 
-            if offset {
-                let mut flush_buf = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+        loop {
+            timed(timeouts.read, stream.read_exact(&mut header_buffer)).await?;
+
+            let header = Header::from_bytes(&header_buffer)?;
+
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-                self.stream.borrow_mut().read_exact(&mut flush_buf).await?;
-                drop(flush_buf);
+            timed(timeouts.read, stream.read_exact(&mut data_buffer)).await?;
 
-                self.stream.borrow_mut().read_exact(&mut header_buffer).await?;
+            dump.received(&header_buffer, &data_buffer);
 
-                header = Header::from_bytes(&header_buffer).unwrap();
+            if (skip_public_peers && header.message_type == MessageType::ExchangePublicPeers) || !health.check_answer(&header, dejavu) {
+                continue;
             }
 
-            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
+            return Ok(T::from_bytes(&data_buffer)?)
+        }
+    }
 
-            self.stream.borrow_mut().read_exact(&mut data_buffer).await?;
+    /// responses up to the `EndResponse` of the request, frames of earlier requests are skipped
+    async fn request_multiple<T: FromBytes>(stream: &mut TcpStream, bytes: &[u8], timeouts: &Timeouts, dump: &WireDump, health: &ConnectionHealth) -> Result<Vec<T>> {
+        let mut ret: Vec<T> = Vec::new();
+        let dejavu = request_dejavu(bytes);
 
-            let res = T::from_bytes(&data_buffer).unwrap();
+        timed(timeouts.write, stream.flush()).await?;
+        dump.sent(bytes);
+        timed(timeouts.write, stream.write_all(bytes)).await?;
+        let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
 
-            Ok(res)
-        };
-        
+        loop {
+            timed(timeouts.read, stream.read_exact(&mut header_buffer)).await?;
+
+            let header = Header::from_bytes(&header_buffer)?;
+
+            let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
 
-        match res {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                let std_stream = std::net::TcpStream::connect(self.get_url().await)?;
-                std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-                std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
-                *self.stream.borrow_mut() = TcpStream::from_std(std_stream)?;
+            timed(timeouts.read, stream.read_exact(&mut data_buffer)).await?;
 
-                Err(e.into())
+            dump.received(&header_buffer, &data_buffer);
+
+            if !health.check_answer(&header, dejavu) {
+                continue;
             }
-        }
-    }
 
-    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>) -> Result<Vec<T>> {
+            if header.message_type == MessageType::EndResponse {
+                break;
+            }
 
-        let res: Result<Vec<T>> = {
-            let mut ret: Vec<T> = Vec::new();
-            let mut stream = self.stream.borrow_mut();
+            ret.push(T::from_bytes(&data_buffer)?);
+        }
 
-            stream.flush().await?;
-            stream.write_all(&data.to_bytes()).await?;
-            let mut header_buffer = vec![0; std::mem::size_of::<Header>()];
+        Ok(ret)
+    }
+
+    /// probes the pooled connection with `RequestCurrentTickInfo` whenever the transport was idle for `interval`, a
+    /// connection failing the probe is replaced right away. Must be called from within the runtime of the client (see
+    /// the `runtime` module), the heartbeat stops once the transport is dropped
+    pub fn start_heartbeat(&self, interval: Duration) -> Result<()> {
+        let heartbeat = self.heartbeat();
 
+        runtime::spawn(async move {
             loop {
-                stream.read_exact(&mut header_buffer).await?;
-    
-                let header = Header::from_bytes(&header_buffer).unwrap();
-    
-                if header.message_type == MessageType::EndResponse {
+                runtime::sleep(interval).await;
+
+                if !heartbeat.beat(interval).await {
                     break;
                 }
-    
-                let mut data_buffer = vec![0; header.get_size() - std::mem::size_of::<Header>()];
-    
-                
-                stream.read_exact(&mut data_buffer).await?;
-    
-                let res = T::from_bytes(&data_buffer).unwrap();
-    
-                ret.push(res);
             }
-            
-            Ok(ret)
-        };
-        
-        match res {
-            Ok(r) => Ok(r),
-            Err(e) => {
-                let std_stream = std::net::TcpStream::connect(self.get_url().await)?;
-                std_stream.set_read_timeout(Some(Duration::from_secs(5)))?;
-                std_stream.set_write_timeout(Some(Duration::from_secs(5)))?;
-                *self.stream.borrow_mut() = TcpStream::from_std(std_stream)?;
-
-                Err(e)
+        });
+
+        Ok(())
+    }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Heartbeat {
+    /// probes the pooled socket unless a request used it within `interval` or holds it right now, false once the
+    /// transport was dropped
+    async fn beat(&self, interval: Duration) -> bool {
+        let (Some(stream), Some(health)) = (self.stream.upgrade(), self.health.upgrade()) else { return false };
+
+        if health.idle_for() < interval {
+            return true
+        }
+
+        let Some(mut stream) = stream.try_lock() else { return true };
+
+        let res = self.probe(&mut stream, &health).await;
+        health.set_healthy(res.is_ok());
+        health.touch();
+
+        if res.is_err() {
+            match connect_stream(&self.url, &self.timeouts, self.proxy.as_ref()).await {
+                Ok(fresh) => *stream = fresh,
+                Err(_) => health.mark_dead()
             }
         }
+
+        true
+    }
+
+    async fn probe(&self, stream: &mut TcpStream, health: &ConnectionHealth) -> Result<CurrentTickInfo> {
+        if health.take_reconnect() {
+            *stream = connect_stream(&self.url, &self.timeouts, self.proxy.as_ref()).await?;
+        }
+
+        let bytes = Packet::new(GetCurrentTickInfo, true)?.to_bytes();
+
+        ConnectedTcp::request(stream, &bytes, true, &self.timeouts, &self.wire_dump, health).await
     }
+}
+
+#[cfg(any(feature = "async", feature = "http"))]
+impl Transport for ConnectedTcp {
+    type Err = std::io::Error;
 
+    async fn new(url: String, options: RequestOptions) -> Result<Box<Self>, Self::Err> {
+        let timeouts = Timeouts::default().with_overrides(&options);
+        let stream = open_stream(&url, &timeouts, options.proxy.as_ref()).await?;
+
+        Ok(
+            Box::new(Self {
+                stream: Arc::new(Mutex::new(stream)),
+                url,
+                timeouts,
+                health: Arc::new(ConnectionHealth::new()),
+                interceptors: Interceptors::default(),
+                proxy: options.proxy,
+                wire_dump: WireDump::default()
+            })
+        )
+    }
+
+    async fn send_without_response(&self, data: impl ToBytes, options: &RequestOptions) -> Result<()> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 0, async {
+            let mut stream = self.stream.lock().await;
+            self.prepare(&mut stream, options).await?;
+
+            let timeouts = self.timeouts.with_overrides(options);
+            self.wire_dump.sent(&bytes);
+            let res = timed(timeouts.write, stream.write_all(&bytes)).await;
+
+            self.settle(&mut stream, res, options).await
+        }).await
+    }
+
+    /// retries once on a fresh connection if the pooled socket went stale
+    async fn send_with_response<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<T> {
+        let bytes = data.to_bytes();
+        let skip_public_peers = D::get_message_type() != MessageType::ExchangePublicPeers;
+
+        self.interceptors.intercept(&self.url, &bytes, |_| 1, async {
+            let mut stream = self.stream.lock().await;
+            self.prepare(&mut stream, options).await?;
+
+            let timeouts = self.timeouts.with_overrides(options);
+
+            let res = match Self::request(&mut stream, &bytes, skip_public_peers, &timeouts, &self.wire_dump, &self.health).await {
+                Err(e) if is_stale(&e) => match self.reconnect(&mut stream, options).await {
+                    Ok(()) => Self::request(&mut stream, &bytes, skip_public_peers, &timeouts, &self.wire_dump, &self.health).await,
+                    Err(_) => Err(e)
+                },
+                res => res
+            };
+
+            self.settle(&mut stream, res, options).await
+        }).await
+    }
+
+    async fn send_with_multiple_responses<T: FromBytes, D: QubicRequest + ToBytes>(&self, data: Packet<D>, options: &RequestOptions) -> Result<Vec<T>> {
+        let bytes = data.to_bytes();
+
+        self.interceptors.intercept(&self.url, &bytes, Vec::len, async {
+            let mut stream = self.stream.lock().await;
+            self.prepare(&mut stream, options).await?;
+
+            let timeouts = self.timeouts.with_overrides(options);
+            let res = Self::request_multiple(&mut stream, &bytes, &timeouts, &self.wire_dump, &self.health).await;
+
+            self.settle(&mut stream, res, options).await
+        }).await
+    }
+
+    /// address of the connected peer, the configured url if the connection is proxied
     async fn get_url(&self) -> String {
-        self.stream.borrow().peer_addr().unwrap().to_string()
+        match self.proxy {
+            Some(_) => self.url.clone(),
+            None => self.stream.lock().await.peer_addr().unwrap().to_string()
+        }
     }
 
     async fn connect(&self) -> Result<TcpStream> {
-        Ok(TcpStream::connect(self.get_url().await).await?)
+        connect_stream(&self.get_url().await, &self.timeouts, self.proxy.as_ref()).await
     }
-}
\ No newline at end of file
+
+    fn set_interceptors(&mut self, interceptors: Interceptors) {
+        self.interceptors = interceptors;
+    }
+
+    fn set_wire_dump(&mut self, dump: WireDump) {
+        self.wire_dump = dump.for_peer(&self.url);
+    }
+
+    fn proxy(&self) -> Option<&ProxyConfig> {
+        self.proxy.as_ref()
+    }
+
+    fn timeouts(&self) -> Timeouts {
+        self.timeouts
+    }
+}
@@ -0,0 +1,202 @@
+//! Dumps of the frames a transport sends and receives, e.g. to compare the bytes on the wire with the C client
+//!
+//! A dump is registered with `ClientBuilder::with_wire_dump` and written by the transport, one line per frame:
+//!
+//! `<unix ms> <sent|received> <peer> type=<message type> size=<size> dejavu=<dejavu> <payload hex>`
+//!
+//! The header fields are taken from the raw bytes, so malformed frames are dumped as they are. Payloads longer than
+//! `max_payload` bytes are cut and marked with a trailing `...`. A disabled dump is checked before anything is
+//! formatted or copied, transports without a dump pay for a branch per frame.
+
+use std::{fmt::{self, Debug, Display}, fs::{File, OpenOptions}, io::Write, path::Path, str::FromStr, sync::{Arc, Mutex}, time::{SystemTime, UNIX_EPOCH}};
+
+use qubic_tcp_types::Header;
+
+/// Payload bytes dumped per frame unless set with `WireDump::with_max_payload`
+pub const DEFAULT_MAX_PAYLOAD: usize = 1024;
+
+const HEADER_SIZE: usize = std::mem::size_of::<Header>();
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    Sent,
+    Received
+}
+
+/// Frame of a dump, `Display` writes the line of the dump and `FromStr` parses it back
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WireFrame {
+    /// unix milliseconds
+    pub timestamp: u64,
+    pub direction: Direction,
+    pub peer: String,
+    /// raw message type, unknown types are dumped too
+    pub message_type: u8,
+    /// size of the header field, including the header
+    pub size: usize,
+    pub dejavu: u32,
+    /// payload after the header, at most `max_payload` bytes
+    pub payload: Vec<u8>,
+    pub truncated: bool
+}
+
+impl WireFrame {
+    /// `None` if `header` is shorter than a header
+    fn new(direction: Direction, peer: &str, header: &[u8], payload: &[u8], max_payload: usize) -> Option<Self> {
+        let header = header.get(..HEADER_SIZE)?;
+
+        Some(Self {
+            timestamp: SystemTime::now().duration_since(UNIX_EPOCH).unwrap_or_default().as_millis() as u64,
+            direction,
+            peer: peer.to_owned(),
+            message_type: header[3],
+            size: u32::from_le_bytes([header[0], header[1], header[2], 0]) as usize,
+            dejavu: u32::from_le_bytes(header[4..8].try_into().unwrap()),
+            payload: payload[..payload.len().min(max_payload)].to_vec(),
+            truncated: payload.len() > max_payload
+        })
+    }
+}
+
+impl Display for WireFrame {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let direction = match self.direction {
+            Direction::Sent => "sent",
+            Direction::Received => "received"
+        };
+
+        write!(f, "{} {direction} {} type={} size={} dejavu={} ", self.timestamp, self.peer, self.message_type, self.size, self.dejavu)?;
+
+        for byte in &self.payload {
+            write!(f, "{byte:02x}")?;
+        }
+
+        if self.truncated {
+            write!(f, "...")?;
+        }
+
+        Ok(())
+    }
+}
+
+/// value of a `name=value` field
+fn field<'a>(field: &'a str, name: &str) -> Result<&'a str, String> {
+    field.strip_prefix(name).and_then(|value| value.strip_prefix('=')).ok_or_else(|| format!("expected {name}=, found {field}"))
+}
+
+impl FromStr for WireFrame {
+    type Err = String;
+
+    fn from_str(line: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<&str> = line.split(' ').collect();
+        let [timestamp, direction, peer, message_type, size, dejavu, payload] = fields[..] else {
+            return Err(format!("expected 7 fields, found {}", fields.len()))
+        };
+
+        let (payload, truncated) = match payload.strip_suffix("...") {
+            Some(payload) => (payload, true),
+            None => (payload, false)
+        };
+
+        Ok(Self {
+            timestamp: timestamp.parse().map_err(|e| format!("invalid timestamp: {e}"))?,
+            direction: match direction {
+                "sent" => Direction::Sent,
+                "received" => Direction::Received,
+                _ => return Err(format!("invalid direction {direction}"))
+            },
+            peer: peer.to_owned(),
+            message_type: field(message_type, "type")?.parse().map_err(|e| format!("invalid type: {e}"))?,
+            size: field(size, "size")?.parse().map_err(|e| format!("invalid size: {e}"))?,
+            dejavu: field(dejavu, "dejavu")?.parse().map_err(|e| format!("invalid dejavu: {e}"))?,
+            payload: hex::decode(payload).map_err(|e| format!("invalid payload: {e}"))?,
+            truncated
+        })
+    }
+}
+
+enum Sink {
+    File(Mutex<File>),
+    Callback(Box<dyn Fn(&WireFrame) + Send + Sync>)
+}
+
+/// Destination of the frames of a transport, disabled by default
+#[derive(Clone)]
+pub struct WireDump {
+    sink: Option<Arc<Sink>>,
+    max_payload: usize,
+    /// url of the transport, set by the transport
+    peer: Arc<str>
+}
+
+impl Default for WireDump {
+    fn default() -> Self {
+        Self { sink: None, max_payload: DEFAULT_MAX_PAYLOAD, peer: Arc::from("") }
+    }
+}
+
+impl Debug for WireDump {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("WireDump").field("enabled", &self.is_enabled()).field("max_payload", &self.max_payload).finish()
+    }
+}
+
+impl WireDump {
+    /// appends the frames to the file at `path`, the file is created if missing. Write errors are ignored, the dump
+    /// never fails a request
+    pub fn to_file(path: impl AsRef<Path>) -> std::io::Result<Self> {
+        let file = OpenOptions::new().create(true).append(true).open(path)?;
+
+        Ok(Self { sink: Some(Arc::new(Sink::File(Mutex::new(file)))), ..Default::default() })
+    }
+
+    /// invokes `callback` with every frame
+    pub fn to_callback(callback: impl Fn(&WireFrame) + Send + Sync + 'static) -> Self {
+        Self { sink: Some(Arc::new(Sink::Callback(Box::new(callback)))), ..Default::default() }
+    }
+
+    /// payload bytes dumped per frame, longer payloads are cut
+    pub fn with_max_payload(mut self, max_payload: usize) -> Self {
+        self.max_payload = max_payload;
+        self
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.sink.is_some()
+    }
+
+    /// the dump of a transport connected to `peer`
+    pub(crate) fn for_peer(self, peer: &str) -> Self {
+        Self { peer: Arc::from(peer), ..self }
+    }
+
+    /// dumps every packet of `bytes`, requests of several packets (e.g. tick data ranges) are split
+    pub(crate) fn sent(&self, mut bytes: &[u8]) {
+        let Some(sink) = &self.sink else { return };
+
+        while bytes.len() >= HEADER_SIZE {
+            let size = (u32::from_le_bytes([bytes[0], bytes[1], bytes[2], 0]) as usize).clamp(HEADER_SIZE, bytes.len());
+            let (packet, rest) = bytes.split_at(size);
+
+            self.write(sink, Direction::Sent, &packet[..HEADER_SIZE], &packet[HEADER_SIZE..]);
+            bytes = rest;
+        }
+    }
+
+    pub(crate) fn received(&self, header: &[u8], payload: &[u8]) {
+        let Some(sink) = &self.sink else { return };
+
+        self.write(sink, Direction::Received, header, payload);
+    }
+
+    fn write(&self, sink: &Sink, direction: Direction, header: &[u8], payload: &[u8]) {
+        let Some(frame) = WireFrame::new(direction, &self.peer, header, payload, self.max_payload) else { return };
+
+        match sink {
+            Sink::File(file) => {
+                let _ = writeln!(file.lock().unwrap_or_else(std::sync::PoisonError::into_inner), "{frame}");
+            },
+            Sink::Callback(callback) => callback(&frame)
+        }
+    }
+}
@@ -1,8 +1,7 @@
 use std::{time::Instant, thread::JoinHandle};
 
 use crossbeam_channel::{unbounded, Sender};
-use qubic_types::QubicWallet;
-use rand::Rng;
+use qubic_types::{keystore::Keystore, QubicWallet, SeedString};
 use clap::Parser;
 
 #[macro_use]
@@ -15,12 +14,21 @@ struct Args {
     threads: usize,
 
     #[arg(short, long)]
-    prefix: String
+    prefix: String,
+
+    /// Keystore the matching wallet is added to, created if missing. Its password is read from the
+    /// QUBIC_KEYSTORE_PASSWORD environment variable
+    #[arg(short, long)]
+    keystore: Option<String>
 }
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     env_logger::Builder::new().filter_level(log::LevelFilter::Info).init();
     let opt = Args::parse();
+    let keystore = match &opt.keystore {
+        Some(path) => Some(Keystore::open(path, &std::env::var("QUBIC_KEYSTORE_PASSWORD")?)?),
+        None => None
+    };
     let (tx, rx) = unbounded::<Update>();
 
     info!("Looking for an ID matching {}", opt.prefix);
@@ -44,6 +52,11 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
                     Update::Match(a) => {
                         info!("Found matching seed: {a}");
 
+                        if let Some(keystore) = &keystore {
+                            let id = keystore.add(a.parse::<SeedString>()?)?;
+                            info!("Added {id} to the keystore");
+                        }
+
                         break 'outer;
                     },
                     Update::Checks(sims) => {
@@ -79,9 +92,9 @@ fn start_threads(threads: usize, match_value: String, tx: Sender<Update>) -> Vec
             let tx = tx;
             let mut i = 0;
             let mut now = Instant::now();
+            let mut rng = rand::thread_rng();
             loop {
-                let seed = get_random_seed();
-                let id = QubicWallet::from_seed(&seed).unwrap();
+                let (seed, id) = QubicWallet::generate(&mut rng);
 
                 if id.get_identity().starts_with(&mv) {
                     println!("Match Found for seed {seed}");
@@ -104,15 +117,3 @@ fn start_threads(threads: usize, match_value: String, tx: Sender<Update>) -> Vec
 
     handles
 }
-
-pub fn get_random_seed() -> String {
-    let mut seed: [u8; 55] = [0; 55];
-
-    let mut rng = rand::thread_rng();
-
-    for s in seed.iter_mut() {
-        *s = b'a' + rng.gen::<u8>() % 26;
-    }
-
-    String::from_utf8(seed.to_vec()).unwrap()
-}
\ No newline at end of file